@@ -0,0 +1,389 @@
+#![cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite")),
+    allow(dead_code, unused_imports)
+)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock, PoisonError, Weak};
+
+use datafusion::arrow::array::{ArrayRef, ListBuilder, StringArray, StringBuilder};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::plan_err;
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::Result;
+use datafusion::logical_expr::Expr;
+use datafusion::prelude::SessionContext;
+
+use crate::registry::{Dialect, RegisterOptions, Registrar};
+
+/// What a function does and how to call it, as shown by [`catalog`] and `df_extras_functions()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Documentation {
+    /// How the function is called, e.g. `sqrt(X)` or `mod(y, x) → numeric`.
+    pub syntax: &'static str,
+    pub description: &'static str,
+    /// Calls and their results, written as `sqrt(16) → 4.0`.
+    pub examples: &'static [&'static str],
+    /// The upstream documentation, or `None` for functions that are not part of the database.
+    pub link: Option<&'static str>,
+}
+
+/// Implemented by every function in `df_extras` to describe itself.
+pub trait Documented {
+    fn documentation(&self) -> Documentation;
+}
+
+/// A function provided by `df_extras`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionInfo {
+    pub dialect: Dialect,
+    /// The section of the upstream documentation the function belongs to, e.g. "Maths".
+    pub category: &'static str,
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub documentation: Documentation,
+}
+
+impl FunctionInfo {
+    pub(crate) fn new(
+        dialect: Dialect,
+        category: &'static str,
+        name: String,
+        aliases: Vec<String>,
+        documentation: Documentation,
+    ) -> Self {
+        Self {
+            dialect,
+            category,
+            name,
+            aliases,
+            documentation,
+        }
+    }
+}
+
+/// Returns every function of the dialects enabled by this crate's features, in the order they
+/// are registered, as registered with the default [`RegisterOptions`]. `df_extras_functions()`
+/// lists what a session actually registered instead.
+pub fn catalog() -> Vec<FunctionInfo> {
+    let functions = RefCell::new(vec![]);
+    #[cfg(feature = "postgres")]
+    crate::postgres::register_all(&Registrar::for_catalog(
+        &functions,
+        Dialect::Postgres,
+        &RegisterOptions::default(),
+    ))
+    .expect("registering into the catalog does not fail");
+    #[cfg(feature = "sqlite")]
+    crate::sqlite::register_all(&Registrar::for_catalog(
+        &functions,
+        Dialect::Sqlite,
+        &RegisterOptions::default(),
+    ))
+    .expect("registering into the catalog does not fail");
+    functions.into_inner()
+}
+
+/// Registers the `df_extras_functions()` table function, which lists the functions registered
+/// into `ctx` under the names they were registered by. `functions` are those a dialect has just
+/// registered, and are listed along with those of any dialect registered into `ctx` before.
+pub(crate) fn register_functions_table(ctx: &SessionContext, functions: Vec<FunctionInfo>) {
+    let registered = session_functions(ctx);
+    {
+        let mut registered = registered.lock().unwrap_or_else(PoisonError::into_inner);
+        // Registering a function again replaces it, so it is listed once.
+        registered.retain(|old| {
+            !functions
+                .iter()
+                .any(|new| new.dialect == old.dialect && new.name == old.name)
+        });
+        registered.extend(functions);
+    }
+    ctx.register_udtf(
+        "df_extras_functions",
+        Arc::new(FunctionsTable {
+            functions: registered,
+        }),
+    );
+}
+
+/// Returns the list of the functions registered into `ctx`, which its `df_extras_functions()`
+/// holds. Sessions are told apart by their id, and a list goes away with the session's table
+/// function.
+fn session_functions(ctx: &SessionContext) -> Arc<Mutex<Vec<FunctionInfo>>> {
+    type Sessions = HashMap<String, Weak<Mutex<Vec<FunctionInfo>>>>;
+    static SESSIONS: OnceLock<Mutex<Sessions>> = OnceLock::new();
+
+    let mut sessions = SESSIONS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    sessions.retain(|_, functions| functions.strong_count() > 0);
+    let session_id = ctx.session_id();
+    let functions = sessions
+        .get(&session_id)
+        .and_then(Weak::upgrade)
+        .unwrap_or_default();
+    sessions.insert(session_id, Arc::downgrade(&functions));
+    functions
+}
+
+struct FunctionsTable {
+    functions: Arc<Mutex<Vec<FunctionInfo>>>,
+}
+
+impl TableFunctionImpl for FunctionsTable {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if !args.is_empty() {
+            return plan_err!("df_extras_functions() takes no arguments");
+        }
+
+        let functions = self
+            .functions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        let text_column = |value: fn(&FunctionInfo) -> Option<&str>| {
+            Arc::new(functions.iter().map(value).collect::<StringArray>()) as ArrayRef
+        };
+        let list_column = |values: fn(&FunctionInfo) -> Vec<&str>| {
+            let mut builder = ListBuilder::new(StringBuilder::new());
+            functions.iter().for_each(|function| {
+                builder.append_value(values(function).into_iter().map(Some));
+            });
+            Arc::new(builder.finish()) as ArrayRef
+        };
+
+        let list_type = DataType::List(Arc::new(Field::new("item", DataType::Utf8, true)));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("dialect", DataType::Utf8, false),
+            Field::new("category", DataType::Utf8, false),
+            Field::new("name", DataType::Utf8, false),
+            Field::new("aliases", list_type.clone(), false),
+            Field::new("syntax", DataType::Utf8, false),
+            Field::new("description", DataType::Utf8, false),
+            Field::new("examples", list_type, false),
+            Field::new("link", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                text_column(|function| Some(function.dialect.name())),
+                text_column(|function| Some(function.category)),
+                text_column(|function| Some(&function.name)),
+                list_column(|function| function.aliases.iter().map(String::as_str).collect()),
+                text_column(|function| Some(function.documentation.syntax)),
+                text_column(|function| Some(function.documentation.description)),
+                list_column(|function| function.documentation.examples.to_vec()),
+                text_column(|function| function.documentation.link),
+            ],
+        )?;
+
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
+
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use datafusion::assert_batches_sorted_eq;
+
+    use crate::postgres::register_postgres_udfs_with_options;
+    use crate::sqlite::{register_sqlite_udfs, register_sqlite_udfs_with_options};
+
+    use super::*;
+
+    #[test]
+    fn test_catalog() {
+        let functions = catalog();
+
+        let mut names = HashSet::new();
+        for function in &functions {
+            assert!(
+                names.insert((function.dialect, function.name.clone())),
+                "{} is registered twice",
+                function.name
+            );
+            assert!(!function.category.is_empty());
+            assert!(!function.documentation.description.is_empty());
+            assert!(!function.documentation.examples.is_empty());
+        }
+
+        let pow = functions
+            .iter()
+            .find(|function| function.dialect == Dialect::Sqlite && function.name == "pow")
+            .unwrap();
+        assert_eq!(pow.category, "Maths");
+        assert_eq!(pow.aliases, vec!["power".to_string()]);
+        assert_eq!(
+            pow.documentation.link,
+            Some("https://www.sqlite.org/lang_mathfunc.html")
+        );
+    }
+
+    /// The tables of supports/*.md are generated from the catalog, so that they list what is
+    /// registered. When this fails, replace the table of the category with the one printed.
+    #[test]
+    fn test_support_matrix() {
+        for (dialect, path) in [
+            (Dialect::Postgres, "supports/postgres.md"),
+            (Dialect::Sqlite, "supports/sqlite.md"),
+        ] {
+            let markdown =
+                std::fs::read_to_string(format!("{}/{path}", env!("CARGO_MANIFEST_DIR"))).unwrap();
+            let functions = catalog()
+                .into_iter()
+                .filter(|function| function.dialect == dialect)
+                .collect::<Vec<_>>();
+
+            let mut categories = functions
+                .iter()
+                .map(|function| function.category)
+                .collect::<Vec<_>>();
+            categories.dedup();
+            for category in categories {
+                let heading = format!("### {category}\n");
+                let section = match markdown.split_once(&heading) {
+                    Some((_, section)) => section.split("\n### ").next().unwrap_or_default(),
+                    None => panic!("{path} has no '{heading}'"),
+                };
+                let section_table = section
+                    .lines()
+                    .filter(|line| line.starts_with('|'))
+                    .map(|line| format!("{line}\n"))
+                    .collect::<String>();
+
+                let table = support_matrix(
+                    functions
+                        .iter()
+                        .filter(|function| function.category == category),
+                );
+                assert_eq!(
+                    section_table, table,
+                    "the {category} table of {path} is out of date, expected:\n{table}"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_functions_table() -> Result<()> {
+        let ctx = SessionContext::new();
+        register_sqlite_udfs(&ctx)?;
+
+        let df = ctx
+            .sql("select dialect, name, aliases, syntax, examples[1] as example, link FROM df_extras_functions() WHERE category = 'Maths' AND name LIKE 'p%'")
+            .await?;
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------+------+---------+-----------+--------------------------+-------------------------------------------+
+| dialect | name | aliases | syntax    | example                  | link                                      |
++---------+------+---------+-----------+--------------------------+-------------------------------------------+
+| sqlite  | pi   | []      | pi()      | pi() → 3.141592653589793 | https://www.sqlite.org/lang_mathfunc.html |
+| sqlite  | pow  | [power] | pow(X, Y) | pow(2, 10) → 1024.0      | https://www.sqlite.org/lang_mathfunc.html |
++---------+------+---------+-----------+--------------------------+-------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert!(ctx
+            .sql("select * from df_extras_functions(1)")
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_functions_table_lists_registered_functions() -> Result<()> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs_with_options(
+            &ctx,
+            &RegisterOptions::default()
+                .with_prefix("pg")
+                .with_allow_list(["mod", "unnest"]),
+        )?;
+        register_sqlite_udfs_with_options(
+            &ctx,
+            &RegisterOptions::default()
+                .with_shadow_builtins(false)
+                .with_allow_list(["sqrt", "soundex", "pow"]),
+        )?;
+        // Registering again does not list a function twice.
+        register_sqlite_udfs_with_options(
+            &ctx,
+            &RegisterOptions::default().with_allow_list(["soundex"]),
+        )?;
+
+        let df = ctx
+            .sql("select dialect, name, aliases from df_extras_functions()")
+            .await?;
+        let batches = df.clone().collect().await?;
+
+        let expected = [
+            "+----------+---------+---------+",
+            "| dialect  | name    | aliases |",
+            "+----------+---------+---------+",
+            "| postgres | pg.mod  | []      |",
+            "| sqlite   | soundex | []      |",
+            "+----------+---------+---------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let other_ctx = SessionContext::new();
+        register_sqlite_udfs_with_options(
+            &other_ctx,
+            &RegisterOptions::default().with_allow_list(["pow"]),
+        )?;
+        let df = other_ctx
+            .sql("select dialect, name, aliases from df_extras_functions()")
+            .await?;
+        let batches = df.clone().collect().await?;
+
+        let expected = [
+            "+---------+------+---------+",
+            "| dialect | name | aliases |",
+            "+---------+------+---------+",
+            "| sqlite  | pow  | [power] |",
+            "+---------+------+---------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    fn support_matrix<'a>(functions: impl Iterator<Item = &'a FunctionInfo>) -> String {
+        let mut functions = functions.collect::<Vec<_>>();
+        functions.sort_by(|left, right| left.name.cmp(&right.name));
+
+        let mut table = String::from("| Function | Description | Example |\n");
+        table.push_str("|----------|-------------|---------|\n");
+        for function in functions {
+            let mut description = function.documentation.description.to_string();
+            if !function.aliases.is_empty() {
+                let aliases = function
+                    .aliases
+                    .iter()
+                    .map(|alias| format!("`{alias}`"))
+                    .collect::<Vec<_>>();
+                description.push_str(&format!(". Also called {}.", aliases.join(", ")));
+            }
+            table.push_str(&format!(
+                "| `{}` | {} | `{}` |\n",
+                function.documentation.syntax, description, function.documentation.examples[0]
+            ));
+        }
+        table
+    }
+}
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, LargeStringBuilder, StringBuilder, StringViewArray, StringViewBuilder,
+};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::DataType::{LargeUtf8, Utf8, Utf8View};
+use datafusion::common::DataFusionError;
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature;
+
+/// The string types accepted by the functions that read text.
+pub(crate) const STRING_TYPES: [DataType; 3] = [Utf8, LargeUtf8, Utf8View];
+
+/// Returns the values of a Utf8, LargeUtf8 or Utf8View array, without copying them.
+pub(crate) fn string_values(array: &dyn Array) -> Result<Vec<Option<&str>>> {
+    match array.data_type() {
+        Utf8 => Ok(array.as_string::<i32>().iter().collect()),
+        LargeUtf8 => Ok(array.as_string::<i64>().iter().collect()),
+        Utf8View => Ok(array
+            .as_any()
+            .downcast_ref::<StringViewArray>()
+            .ok_or_else(|| DataFusionError::Internal("Could not read Utf8View array".to_string()))?
+            .iter()
+            .collect()),
+        t => Err(DataFusionError::Internal(format!(
+            "Unsupported type {t} for string argument"
+        ))),
+    }
+}
+
+/// Returns the type of the strings a function returns for an argument of type `arg_type`: a
+/// LargeUtf8 argument gives LargeUtf8, a Utf8View argument gives Utf8View, and anything else Utf8.
+pub(crate) fn string_return_type(arg_type: &DataType) -> DataType {
+    match arg_type {
+        LargeUtf8 => LargeUtf8,
+        Utf8View => Utf8View,
+        _ => Utf8,
+    }
+}
+
+/// Returns the signatures made of one type of each of `arguments`, e.g. every pair of a string
+/// type and a path type.
+pub(crate) fn exact_signatures(arguments: &[&[DataType]]) -> Vec<TypeSignature> {
+    arguments
+        .iter()
+        .fold(vec![vec![]], |signatures: Vec<Vec<DataType>>, types| {
+            signatures
+                .iter()
+                .flat_map(|signature| {
+                    types.iter().map(move |data_type| {
+                        let mut signature = signature.clone();
+                        signature.push(data_type.clone());
+                        signature
+                    })
+                })
+                .collect()
+        })
+        .into_iter()
+        .map(TypeSignature::Exact)
+        .collect()
+}
+
+/// Builds a Utf8, LargeUtf8 or Utf8View array, as chosen by [`string_return_type`].
+pub(crate) enum StringOutput {
+    Utf8(StringBuilder),
+    LargeUtf8(LargeStringBuilder),
+    Utf8View(StringViewBuilder),
+}
+
+impl StringOutput {
+    pub(crate) fn new(data_type: &DataType, capacity: usize) -> Self {
+        match data_type {
+            LargeUtf8 => Self::LargeUtf8(LargeStringBuilder::with_capacity(
+                capacity,
+                u8::MAX as usize,
+            )),
+            Utf8View => Self::Utf8View(StringViewBuilder::with_capacity(capacity)),
+            _ => Self::Utf8(StringBuilder::with_capacity(capacity, u8::MAX as usize)),
+        }
+    }
+
+    pub(crate) fn append_value(&mut self, value: impl AsRef<str>) {
+        match self {
+            Self::Utf8(builder) => builder.append_value(value),
+            Self::LargeUtf8(builder) => builder.append_value(value),
+            Self::Utf8View(builder) => builder.append_value(value),
+        }
+    }
+
+    pub(crate) fn append_null(&mut self) {
+        match self {
+            Self::Utf8(builder) => builder.append_null(),
+            Self::LargeUtf8(builder) => builder.append_null(),
+            Self::Utf8View(builder) => builder.append_null(),
+        }
+    }
+
+    pub(crate) fn finish(&mut self) -> ArrayRef {
+        match self {
+            Self::Utf8(builder) => Arc::new(builder.finish()),
+            Self::LargeUtf8(builder) => Arc::new(builder.finish()),
+            Self::Utf8View(builder) => Arc::new(builder.finish()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use datafusion::arrow::array::{Int64Array, LargeStringArray, StringArray};
+
+    use super::*;
+
+    #[test]
+    fn test_string_values() {
+        let values = vec![Some("a"), None, Some("a string longer than twelve bytes")];
+        let arrays: [ArrayRef; 3] = [
+            Arc::new(StringArray::from(values.clone())),
+            Arc::new(LargeStringArray::from(values.clone())),
+            Arc::new(StringViewArray::from(values.clone())),
+        ];
+        for array in arrays {
+            assert_eq!(string_values(&array).unwrap(), values);
+        }
+        assert!(string_values(&Int64Array::from(vec![1])).is_err());
+    }
+
+    #[test]
+    fn test_string_output() {
+        for data_type in STRING_TYPES {
+            let mut output = StringOutput::new(&string_return_type(&data_type), 2);
+            output.append_value("a");
+            output.append_null();
+            let array = output.finish();
+            assert_eq!(array.data_type(), &data_type);
+            assert_eq!(string_values(&array).unwrap(), vec![Some("a"), None]);
+        }
+        assert_eq!(string_return_type(&DataType::Binary), Utf8);
+    }
+
+    #[test]
+    fn test_exact_signatures() {
+        assert_eq!(
+            exact_signatures(&[&[Utf8, LargeUtf8], &[DataType::Int64]]),
+            vec![
+                TypeSignature::Exact(vec![Utf8, DataType::Int64]),
+                TypeSignature::Exact(vec![LargeUtf8, DataType::Int64]),
+            ]
+        );
+    }
+}
@@ -1,4 +1,6 @@
-use datafusion::arrow::array::{Float64Array, Int64Array, StringArray, UInt64Array, UInt8Array};
+use datafusion::arrow::array::{
+    BooleanArray, Float64Array, Int64Array, StringArray, UInt64Array, UInt8Array,
+};
 use datafusion::arrow::datatypes::{DataType, Field, Schema};
 use datafusion::arrow::record_batch::RecordBatch;
 use datafusion::error::Result;
@@ -121,3 +123,52 @@ pub fn set_up_json_data_test() -> Result<SessionContext> {
     ctx.register_batch("json_path_table", batch)?;
     Ok(ctx)
 }
+
+pub fn set_up_strings_data_test() -> Result<SessionContext> {
+    // define a schema.
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("index", DataType::UInt8, false),
+        Field::new("name", DataType::Utf8, true),
+        Field::new("flag", DataType::Boolean, true),
+        Field::new("accented", DataType::Utf8, true),
+        Field::new("escaped", DataType::Utf8, true),
+    ]));
+
+    // define data.
+    let batch = RecordBatch::try_new(
+        schema,
+        vec![
+            Arc::new(UInt8Array::from_iter_values([1, 2, 3, 4])),
+            Arc::new(StringArray::from(vec![
+                Some("hi THOMAS"),
+                Some("o'neil"),
+                Some("select"),
+                Some("a.b"),
+            ])),
+            Arc::new(BooleanArray::from(vec![
+                Some(true),
+                Some(false),
+                Some(true),
+                None,
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some("Karél"),
+                Some("François"),
+                Some("Zürich"),
+                None,
+            ])),
+            Arc::new(StringArray::from(vec![
+                Some(r"d\0061t\+000061"),
+                Some(r"\00e9"),
+                Some(r"\\"),
+                None,
+            ])),
+        ],
+    )?;
+
+    // declare a new context
+    let ctx = SessionContext::new();
+    ctx.register_batch("strings_table", batch)?;
+    // declare a table in memory.
+    Ok(ctx)
+}
@@ -1,3 +1,7 @@
+pub mod catalog;
 pub mod common;
 pub mod postgres;
+pub mod registry;
 pub mod sqlite;
+
+pub use catalog::catalog;
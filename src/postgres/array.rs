@@ -0,0 +1,534 @@
+//! Postgres' array text: reading it as `array_in` does, and writing it as `array_out` does.
+//!
+//! An array is written between braces, with its elements separated by commas and each of its
+//! dimensions in braces of their own, e.g. `{{1,2},{3,4}}`. Elements are double-quoted, with
+//! `"` and `\` escaped by a backslash, when they would not be read back as they are, and an
+//! unquoted `NULL` is a NULL element. The bounds of the dimensions may come first, as in
+//! `[1:2][1:2]={{1,2},{3,4}}`.
+
+use anyhow::{anyhow, bail, Result};
+
+/// The most dimensions Postgres allows in an array.
+const MAX_DIMENSIONS: usize = 6;
+
+/// The text of an array: the lengths and lower bounds of its dimensions, none for an empty
+/// array, and the text of its elements in row-major order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ArrayText {
+    pub(crate) dimensions: Vec<usize>,
+    pub(crate) lower_bounds: Vec<i64>,
+    pub(crate) elements: Vec<Option<String>>,
+}
+
+/// What the reader of an array last read, as in Postgres' `ArrayParseState`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    NoLevel,
+    LevelStarted,
+    ElementStarted,
+    QuotedElementStarted,
+    QuotedElementCompleted,
+    ElementDelimited,
+    LevelCompleted,
+    LevelDelimited,
+}
+
+/// Reads an array as `array_in` does, returning the text of its elements.
+pub(crate) fn parse_array(text: &str) -> Result<ArrayText> {
+    let malformed = |detail: &str| anyhow!("malformed array literal: \"{text}\": {detail}");
+    let mut rest = text.trim_start_matches(is_space);
+    let mut bounds = vec![];
+
+    while let Some(dimension) = rest.strip_prefix('[') {
+        let (first, after) = parse_bound(dimension).ok_or_else(|| {
+            malformed("\"[\" must introduce explicitly-specified array dimensions.")
+        })?;
+        let (lower_bound, upper_bound, after) = match after.strip_prefix(':') {
+            Some(after) => {
+                let (upper_bound, after) = parse_bound(after).ok_or_else(|| {
+                    malformed("\"[\" must introduce explicitly-specified array dimensions.")
+                })?;
+                (first, upper_bound, after)
+            }
+            None => (1, first, after),
+        };
+        rest = after
+            .strip_prefix(']')
+            .ok_or_else(|| malformed("Missing \"]\" after array dimensions."))?
+            .trim_start_matches(is_space);
+        if upper_bound < lower_bound {
+            bail!("upper bound cannot be less than lower bound");
+        }
+        bounds.push((lower_bound, upper_bound));
+    }
+    if bounds.len() > MAX_DIMENSIONS {
+        bail!(
+            "number of array dimensions ({}) exceeds the maximum allowed ({MAX_DIMENSIONS})",
+            bounds.len()
+        );
+    }
+
+    if bounds.is_empty() {
+        if !rest.starts_with('{') {
+            return Err(malformed(
+                "Array value must start with \"{\" or dimension information.",
+            ));
+        }
+        let (dimensions, elements) = read_array(rest)?;
+        let lower_bounds = vec![1; dimensions.len()];
+        return Ok(ArrayText {
+            dimensions,
+            lower_bounds,
+            elements,
+        });
+    }
+
+    rest = rest
+        .strip_prefix('=')
+        .ok_or_else(|| malformed("Missing \"=\" after array dimensions."))?
+        .trim_start_matches(is_space);
+    if !rest.starts_with('{') {
+        return Err(malformed("Array contents must start with \"{\"."));
+    }
+    let (dimensions, elements) = read_array(rest)?;
+    let specified = bounds
+        .iter()
+        .map(|(lower_bound, upper_bound)| (upper_bound - lower_bound + 1) as usize);
+    if !specified.eq(dimensions.iter().copied()) {
+        return Err(malformed(
+            "Specified array dimensions do not match array contents.",
+        ));
+    }
+    Ok(ArrayText {
+        dimensions,
+        lower_bounds: bounds.iter().map(|(lower_bound, _)| *lower_bound).collect(),
+        elements,
+    })
+}
+
+/// Writes an array as `array_out` does, given the lengths of its dimensions and the text of its
+/// elements in row-major order.
+pub(crate) fn format_array(dimensions: &[usize], elements: &[Option<String>]) -> String {
+    let mut text = String::new();
+    if dimensions.is_empty() || dimensions.contains(&0) {
+        text.push_str("{}");
+    } else {
+        write_level(&mut text, dimensions, elements);
+    }
+    text
+}
+
+/// Reads the braces of an array, whose text starts with `{`, as Postgres' `ArrayCount` and
+/// `ReadArrayStr` do. Returns the lengths of its dimensions and its elements.
+fn read_array(text: &str) -> Result<(Vec<usize>, Vec<Option<String>>)> {
+    let malformed = |detail: &str| anyhow!("malformed array literal: \"{text}\": {detail}");
+    let unexpected = |character: char| malformed(&format!("Unexpected \"{character}\" character."));
+    let mismatched =
+        || malformed("Multidimensional arrays must have sub-arrays with matching dimensions.");
+
+    let mut state = State::NoLevel;
+    let mut in_quotes = false;
+    // The number of items read in each open level, and in the last level of each depth.
+    let mut counts: Vec<usize> = vec![];
+    let mut lengths: Vec<Option<usize>> = vec![];
+    let mut elements = vec![];
+    let mut element_depth = None;
+    let mut element = String::new();
+    let mut is_literal = false;
+    let mut trailing_spaces = 0;
+    let mut chars = text.char_indices();
+
+    let end = loop {
+        let Some((index, character)) = chars.next() else {
+            return Err(malformed("Unexpected end of input."));
+        };
+        match character {
+            '\\' => {
+                if !matches!(
+                    state,
+                    State::LevelStarted
+                        | State::ElementStarted
+                        | State::QuotedElementStarted
+                        | State::ElementDelimited
+                ) {
+                    return Err(unexpected('\\'));
+                }
+                if state != State::QuotedElementStarted {
+                    state = State::ElementStarted;
+                }
+                let Some((_, escaped)) = chars.next() else {
+                    return Err(malformed("Unexpected end of input."));
+                };
+                element.push(escaped);
+                is_literal = true;
+                trailing_spaces = 0;
+            }
+            '"' => {
+                if !in_quotes
+                    && !matches!(
+                        state,
+                        State::LevelStarted
+                            | State::QuotedElementCompleted
+                            | State::ElementDelimited
+                    )
+                {
+                    return Err(malformed("Unexpected array element."));
+                }
+                in_quotes = !in_quotes;
+                state = if in_quotes {
+                    State::QuotedElementStarted
+                } else {
+                    State::QuotedElementCompleted
+                };
+                is_literal = true;
+            }
+            _ if in_quotes => element.push(character),
+            '{' => {
+                if !matches!(
+                    state,
+                    State::NoLevel | State::LevelStarted | State::LevelDelimited
+                ) {
+                    return Err(unexpected('{'));
+                }
+                if counts.len() == MAX_DIMENSIONS {
+                    bail!(
+                        "number of array dimensions ({}) exceeds the maximum allowed ({MAX_DIMENSIONS})",
+                        counts.len() + 1
+                    );
+                }
+                if element_depth.is_some_and(|depth| depth <= counts.len()) {
+                    return Err(mismatched());
+                }
+                state = State::LevelStarted;
+                counts.push(0);
+                if lengths.len() < counts.len() {
+                    lengths.push(None);
+                }
+            }
+            '}' => {
+                if !matches!(
+                    state,
+                    State::ElementStarted | State::QuotedElementCompleted | State::LevelCompleted
+                ) && !(counts.len() == 1 && state == State::LevelStarted)
+                {
+                    return Err(unexpected('}'));
+                }
+                if matches!(state, State::ElementStarted | State::QuotedElementCompleted) {
+                    end_element(
+                        &mut elements,
+                        &mut element,
+                        is_literal,
+                        trailing_spaces,
+                        counts.len(),
+                        &mut element_depth,
+                    )
+                    .map_err(|_| mismatched())?;
+                    *counts.last_mut().unwrap() += 1;
+                }
+                state = State::LevelCompleted;
+                let count = counts.pop().unwrap();
+                let length = &mut lengths[counts.len()];
+                if length.is_some_and(|length| length != count) {
+                    return Err(mismatched());
+                }
+                *length = Some(count);
+                match counts.last_mut() {
+                    Some(parent_count) => *parent_count += 1,
+                    None => break index + 1,
+                }
+            }
+            ',' => {
+                if !matches!(
+                    state,
+                    State::ElementStarted | State::QuotedElementCompleted | State::LevelCompleted
+                ) {
+                    return Err(unexpected(','));
+                }
+                if state == State::LevelCompleted {
+                    state = State::LevelDelimited;
+                } else {
+                    end_element(
+                        &mut elements,
+                        &mut element,
+                        is_literal,
+                        trailing_spaces,
+                        counts.len(),
+                        &mut element_depth,
+                    )
+                    .map_err(|_| mismatched())?;
+                    *counts.last_mut().unwrap() += 1;
+                    state = State::ElementDelimited;
+                }
+            }
+            _ if is_space(character) => {
+                if state == State::ElementStarted {
+                    element.push(character);
+                    trailing_spaces += character.len_utf8();
+                }
+            }
+            _ => {
+                if !matches!(
+                    state,
+                    State::LevelStarted | State::ElementStarted | State::ElementDelimited
+                ) {
+                    return Err(malformed("Unexpected array element."));
+                }
+                state = State::ElementStarted;
+                element.push(character);
+                trailing_spaces = 0;
+            }
+        }
+        if matches!(state, State::ElementDelimited | State::LevelCompleted) {
+            is_literal = false;
+            trailing_spaces = 0;
+        }
+    };
+
+    if !text[end..].chars().all(is_space) {
+        return Err(malformed("Junk after closing right brace."));
+    }
+    if elements.is_empty() {
+        return Ok((vec![], vec![]));
+    }
+    let dimensions = lengths.into_iter().flatten().collect();
+    Ok((dimensions, elements))
+}
+
+/// Adds the element read so far to `elements`, without its trailing unescaped whitespace. An
+/// element must be as deep in the array as the others are.
+fn end_element(
+    elements: &mut Vec<Option<String>>,
+    element: &mut String,
+    is_literal: bool,
+    trailing_spaces: usize,
+    depth: usize,
+    element_depth: &mut Option<usize>,
+) -> std::result::Result<(), ()> {
+    if element_depth.is_some_and(|element_depth| element_depth != depth) {
+        return Err(());
+    }
+    *element_depth = Some(depth);
+    element.truncate(element.len() - trailing_spaces);
+    let text = std::mem::take(element);
+    if !is_literal && text.eq_ignore_ascii_case("NULL") {
+        elements.push(None);
+    } else {
+        elements.push(Some(text));
+    }
+    Ok(())
+}
+
+/// Reads a bound of a dimension, an integer after optional whitespace.
+fn parse_bound(text: &str) -> Option<(i64, &str)> {
+    let text = text.trim_start_matches(is_space);
+    let digits_start = usize::from(text.starts_with(['+', '-']));
+    let digits = text[digits_start..]
+        .bytes()
+        .take_while(u8::is_ascii_digit)
+        .count();
+    if digits == 0 {
+        return None;
+    }
+    let (bound, rest) = text.split_at(digits_start + digits);
+    Some((bound.parse::<i32>().ok()?.into(), rest))
+}
+
+/// Writes the arrays of a dimension, whose elements are `elements`, in braces.
+fn write_level(text: &mut String, dimensions: &[usize], elements: &[Option<String>]) {
+    text.push('{');
+    let size = dimensions[1..].iter().product::<usize>();
+    for (index, elements) in elements.chunks(size).enumerate() {
+        if index > 0 {
+            text.push(',');
+        }
+        if dimensions.len() > 1 {
+            write_level(text, &dimensions[1..], elements);
+        } else {
+            write_element(text, elements[0].as_deref());
+        }
+    }
+    text.push('}');
+}
+
+/// Writes an element, quoted if it is empty, could be taken for NULL, or has characters that
+/// would be read as part of the array.
+fn write_element(text: &mut String, element: Option<&str>) {
+    let Some(element) = element else {
+        text.push_str("NULL");
+        return;
+    };
+    let needs_quotes = element.is_empty()
+        || element.eq_ignore_ascii_case("NULL")
+        || element.chars().any(|character| {
+            matches!(character, '"' | '\\' | '{' | '}' | ',') || is_space(character)
+        });
+    if !needs_quotes {
+        text.push_str(element);
+        return;
+    }
+    text.push('"');
+    for character in element.chars() {
+        if matches!(character, '"' | '\\') {
+            text.push('\\');
+        }
+        text.push(character);
+    }
+    text.push('"');
+}
+
+/// Whether a character is whitespace to Postgres' array reader.
+fn is_space(character: char) -> bool {
+    matches!(character, ' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn elements(text: &str) -> Vec<Option<String>> {
+        parse_array(text).unwrap().elements
+    }
+
+    fn roundtrip(text: &str) -> String {
+        let array = parse_array(text).unwrap();
+        format_array(&array.dimensions, &array.elements)
+    }
+
+    fn detail(text: &str) -> String {
+        let message = parse_array(text).unwrap_err().to_string();
+        match message.strip_prefix(&format!("malformed array literal: \"{text}\": ")) {
+            Some(detail) => detail.to_string(),
+            None => message,
+        }
+    }
+
+    #[test]
+    fn test_parse_array() {
+        assert_eq!(
+            parse_array("{1,2,\"a,b\",NULL}").unwrap(),
+            ArrayText {
+                dimensions: vec![4],
+                lower_bounds: vec![1],
+                elements: vec![
+                    Some("1".to_string()),
+                    Some("2".to_string()),
+                    Some("a,b".to_string()),
+                    None
+                ],
+            }
+        );
+        assert_eq!(
+            parse_array("{{1,2},{3,4}}").unwrap(),
+            ArrayText {
+                dimensions: vec![2, 2],
+                lower_bounds: vec![1, 1],
+                elements: ["1", "2", "3", "4"]
+                    .map(|element| Some(element.to_string()))
+                    .to_vec(),
+            }
+        );
+        assert_eq!(
+            parse_array(" {} ").unwrap(),
+            ArrayText {
+                dimensions: vec![],
+                lower_bounds: vec![],
+                elements: vec![],
+            }
+        );
+        assert_eq!(
+            parse_array("[0:1][2]={{a,b},{c,d}}").unwrap().lower_bounds,
+            vec![0, 1]
+        );
+        assert_eq!(
+            elements("{a b , c\\ ,  d\\\\e,\\ f}"),
+            ["a b", "c ", "d\\e", " f"].map(|element| Some(element.to_string()))
+        );
+        assert_eq!(
+            elements("{null,\"null\",N\\ULL,\"\",\" \"}"),
+            [None, Some("null"), Some("NULL"), Some(""), Some(" ")]
+                .map(|element| element.map(String::from))
+        );
+        assert_eq!(
+            elements("{ \"a\\\"b\" , \"{}\" }"),
+            [Some("a\"b".to_string()), Some("{}".to_string())]
+        );
+
+        assert_eq!(
+            detail("x"),
+            "Array value must start with \"{\" or dimension information."
+        );
+        assert_eq!(detail("{1,,2}"), "Unexpected \",\" character.");
+        assert_eq!(detail("{a,}"), "Unexpected \"}\" character.");
+        assert_eq!(detail("{{}}"), "Unexpected \"}\" character.");
+        assert_eq!(detail("{1,{2}}"), "Unexpected \"{\" character.");
+        assert_eq!(detail("{{1},2}"), "Unexpected array element.");
+        assert_eq!(detail("{\"a\"b}"), "Unexpected array element.");
+        assert_eq!(detail("{a \"b\"}"), "Unexpected array element.");
+        assert_eq!(
+            detail("{{1},{2,3}}"),
+            "Multidimensional arrays must have sub-arrays with matching dimensions."
+        );
+        assert_eq!(
+            detail("{{1},{{2}}}"),
+            "Multidimensional arrays must have sub-arrays with matching dimensions."
+        );
+        assert_eq!(
+            detail("{{{1}},{2}}"),
+            "Multidimensional arrays must have sub-arrays with matching dimensions."
+        );
+        assert_eq!(detail("{a"), "Unexpected end of input.");
+        assert_eq!(detail("{a\\"), "Unexpected end of input.");
+        assert_eq!(detail("{1}x"), "Junk after closing right brace.");
+        assert_eq!(detail("{a}}"), "Junk after closing right brace.");
+        assert_eq!(
+            detail("[1:3]={1,2}"),
+            "Specified array dimensions do not match array contents."
+        );
+        assert_eq!(
+            detail("[1:2]={}"),
+            "Specified array dimensions do not match array contents."
+        );
+        assert_eq!(
+            detail("[1:2]{1,2}"),
+            "Missing \"=\" after array dimensions."
+        );
+        assert_eq!(
+            detail("[1:2={1,2}"),
+            "Missing \"]\" after array dimensions."
+        );
+        assert_eq!(
+            detail("[a]={a}"),
+            "\"[\" must introduce explicitly-specified array dimensions."
+        );
+        assert_eq!(
+            detail("[2:1]={}"),
+            "upper bound cannot be less than lower bound"
+        );
+        assert_eq!(
+            detail("{{{{{{{1}}}}}}}"),
+            "number of array dimensions (7) exceeds the maximum allowed (6)"
+        );
+    }
+
+    #[test]
+    fn test_format_array() {
+        assert_eq!(roundtrip("{1,2,\"a,b\",NULL}"), "{1,2,\"a,b\",NULL}");
+        assert_eq!(roundtrip("  { a , b }  "), "{a,b}");
+        assert_eq!(roundtrip("[1:2][1:1]={{a},{b}}"), "{{a},{b}}");
+        assert_eq!(roundtrip("{{{1,2}},{{3,4}}}"), "{{{1,2}},{{3,4}}}");
+        assert_eq!(roundtrip("{}"), "{}");
+        assert_eq!(
+            roundtrip("{\"\\\\\\\"\",\\{,x\\}}"),
+            "{\"\\\\\\\"\",\"{\",\"x}\"}"
+        );
+        assert_eq!(
+            format_array(
+                &[10],
+                &["a\"b", "c\\d", "e,f", "g{h", "i}j", "k\tl", "NULL", "null", "", " "]
+                    .map(|element| Some(element.to_string()))
+            ),
+            "{\"a\\\"b\",\"c\\\\d\",\"e,f\",\"g{h\",\"i}j\",\"k\tl\",\"NULL\",\"null\",\"\",\" \"}"
+        );
+        assert_eq!(format_array(&[2, 0], &[]), "{}");
+    }
+}
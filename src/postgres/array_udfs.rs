@@ -0,0 +1,1930 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+
+use chrono::DateTime;
+use datafusion::arrow::array::{
+    new_empty_array, new_null_array, Array, ArrayRef, AsArray, Int64Builder,
+    IntervalMonthDayNanoArray, ListArray, ListBuilder, Scalar, StringArray, StringBuilder,
+    UInt32Array,
+};
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::compute::kernels::cmp::not_distinct;
+use datafusion::arrow::compute::{cast, concat, take};
+use datafusion::arrow::datatypes::DataType::{
+    Boolean, Date32, Decimal128, FixedSizeList, Float32, Float64, Int16, Int32, Int64, Int8,
+    Interval, LargeList, List, Time32, Time64, Timestamp, UInt16, UInt32, UInt64, UInt8, Utf8,
+};
+use datafusion::arrow::datatypes::{
+    DataType, Field, FieldRef, Int64Type, IntervalUnit, Schema, Time64MicrosecondType, TimeUnit,
+    TimestampMicrosecondType, DECIMAL128_MAX_PRECISION,
+};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::{exec_err, plan_err, DFSchema, DataFusionError, ExprSchema, ScalarValue};
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::Any;
+use datafusion::logical_expr::{ColumnarValue, Expr, ScalarUDFImpl, Signature, Volatility};
+use datafusion::optimizer::simplify_expressions::{ExprSimplifier, SimplifyContext};
+use datafusion::physical_expr::execution_props::ExecutionProps;
+use ipnet::IpNet;
+use rand::rngs::StdRng;
+use rand::seq::index::sample;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{exact_signatures, string_values, STRING_TYPES};
+use crate::postgres::array::{format_array, parse_array};
+use crate::postgres::datetime_udfs::{interval_value, intervals};
+use crate::postgres::interval::{format_interval, parse_interval, IntervalStyle};
+use crate::postgres::string_udfs::{execution_error, text_values};
+
+const ARRAY_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/16/functions-array.html";
+const ARRAY_IO_LINK: &str = "https://www.postgresql.org/docs/16/arrays.html#ARRAYS-IO";
+
+/// The most elements Postgres allows in an array.
+const MAX_ARRAY_SIZE: usize = 134_217_727;
+
+/// Returns the positions of every occurrence of a value in a one-dimensional array, counting
+/// from 1. NULL is found like any other value.
+#[derive(Debug)]
+pub struct ArrayPositions {
+    signature: Signature,
+}
+
+impl ArrayPositions {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayPositions {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_positions"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])?;
+        Ok(integer_list_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        if is_array(&arrays.value_type()) {
+            return exec_err!("searching for elements in multidimensional arrays is not supported");
+        }
+        let values = cast(&args[1], &arrays.value_type())?;
+        let mut list_builder = ListBuilder::new(Int64Builder::new());
+
+        for row in 0..arrays.len() {
+            if arrays.is_null(row) {
+                list_builder.append_null();
+                continue;
+            }
+            let elements = arrays.value(row);
+            let found = not_distinct(&elements, &Scalar::new(values.slice(row, 1)))?;
+            list_builder.append_value(
+                found
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, found)| *found == Some(true))
+                    .map(|(index, _)| Some(index as i64 + 1)),
+            );
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(list_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for ArrayPositions {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_positions(anycompatiblearray, anycompatible) → integer[]",
+            description: "Returns an array of the subscripts of all occurrences of the second argument in the array given as first argument. The array must be one-dimensional. Comparisons are done using IS NOT DISTINCT FROM semantics, so it is possible to search for NULL",
+            examples: &["array_positions(ARRAY['A','A','B','A'], 'A') → {1,2,4}"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns an array filled with copies of a value, with the lengths of its dimensions given by
+/// the second argument, which must be a constant array or `make_array` call so that the number
+/// of dimensions is known. Arrow arrays always start at 1, so the lower bounds, if given, must
+/// be 1.
+#[derive(Debug)]
+pub struct ArrayFill {
+    signature: Signature,
+}
+
+impl ArrayFill {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayFill {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_fill"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(nested_list_type(arg_types[0].clone(), 1))
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        arg_types: &[DataType],
+    ) -> Result<DataType> {
+        if matches!(&args[1], Expr::Literal(value) if value.is_null()) {
+            return plan_err!("dimension array or low bound array cannot be null");
+        }
+        for data_type in &arg_types[1..] {
+            if !matches!(list_type(data_type), Some(List(field)) if is_integer(field.data_type())) {
+                return plan_err!(
+                    "array_fill() takes integer arrays of dimensions, not {data_type}"
+                );
+            }
+        }
+        let dimensions = match &args[1] {
+            Expr::Literal(ScalarValue::List(array)) => array.value_length(0) as usize,
+            Expr::ScalarFunction(function) if function.name() == "make_array" => {
+                function.args.len()
+            }
+            _ => {
+                return plan_err!(
+                    "the dimensions of array_fill() must be a constant array, so that their number is known"
+                );
+            }
+        };
+        Ok(nested_list_type(arg_types[0].clone(), dimensions.max(1)))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let dimensions = integer_arrays(&args[1])?;
+        let lower_bounds = match args.get(2) {
+            Some(lower_bounds) => integer_arrays(lower_bounds)?,
+            None => vec![Some(vec![]); args[0].len()],
+        };
+        let mut rows = vec![];
+
+        for (row, (dimensions, lower_bounds)) in
+            dimensions.into_iter().zip(lower_bounds).enumerate()
+        {
+            let (Some(dimensions), Some(lower_bounds)) = (dimensions, lower_bounds) else {
+                return exec_err!("dimension array or low bound array cannot be null");
+            };
+            if args.len() == 3 && lower_bounds.len() != dimensions.len() {
+                return exec_err!("wrong number of array subscripts");
+            }
+            if dimensions.iter().chain(&lower_bounds).any(Option::is_none) {
+                return exec_err!("dimension values cannot be null");
+            }
+            if lower_bounds
+                .iter()
+                .any(|lower_bound| *lower_bound != Some(1))
+            {
+                return exec_err!("array_fill() only supports lower bounds of 1");
+            }
+            // An array without dimensions is empty.
+            let dimensions = if dimensions.is_empty() {
+                vec![Some(0)]
+            } else {
+                dimensions
+            };
+            let dimensions = dimensions
+                .into_iter()
+                .map(|length| usize::try_from(length.unwrap_or_default()).ok())
+                .collect::<Option<Vec<_>>>()
+                .filter(|dimensions| {
+                    dimensions
+                        .iter()
+                        .try_fold(1_usize, |size, length| size.checked_mul(*length))
+                        .is_some_and(|size| size <= MAX_ARRAY_SIZE)
+                })
+                .ok_or_else(|| {
+                    DataFusionError::Execution(format!(
+                        "array size exceeds the maximum allowed ({MAX_ARRAY_SIZE})"
+                    ))
+                })?;
+            rows.push(Some(fill(&args[0].slice(row, 1), &dimensions)?));
+        }
+
+        let item_type = rows
+            .iter()
+            .flatten()
+            .next()
+            .map_or(args[0].data_type().clone(), |elements| {
+                elements.data_type().clone()
+            });
+        Ok(ColumnarValue::Array(Arc::new(list_from_rows(
+            &item_type, rows,
+        )?)))
+    }
+}
+
+impl Documented for ArrayFill {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_fill(anyelement, integer[] [, integer[]]) → anyarray",
+            description: "Returns an array filled with copies of the given value, having dimensions of the lengths specified by the second argument. The optional third argument supplies lower-bound values for each dimension, which must be 1",
+            examples: &["array_fill(11, ARRAY[2,3]) → {{11,11,11},{11,11,11}}"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the dimensions of an array as text, e.g. `[1:2][1:3]`, or NULL for an empty array.
+#[derive(Debug)]
+pub struct ArrayDims {
+    signature: Signature,
+}
+
+impl ArrayDims {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayDims {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_dims"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])?;
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder = StringBuilder::new();
+
+        for dimensions in array_dimensions(&lists(&args[0])?)? {
+            match dimensions.flatten() {
+                Some(dimensions) => string_builder.append_value(
+                    dimensions
+                        .iter()
+                        .map(|length| format!("[1:{length}]"))
+                        .collect::<String>(),
+                ),
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for ArrayDims {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_dims(anyarray) → text",
+            description: "Returns a text representation of the array's dimensions",
+            examples: &["array_dims(ARRAY[[1,2,3], [4,5,6]]) → [1:2][1:3]"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the lower bound of a dimension of an array, which is always 1, or NULL if the array
+/// is empty or has no such dimension.
+#[derive(Debug)]
+pub struct ArrayLower {
+    signature: Signature,
+}
+
+impl ArrayLower {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayLower {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_lower"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        bound_return_type(self.name(), arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        bounds(args, |_| 1)
+    }
+}
+
+impl Documented for ArrayLower {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_lower(anyarray, integer) → integer",
+            description: "Returns the lower bound of the requested array dimension",
+            examples: &["array_lower(ARRAY[1,2,3], 1) → 1"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the upper bound of a dimension of an array, which is its length, or NULL if the
+/// array is empty or has no such dimension.
+#[derive(Debug)]
+pub struct ArrayUpper {
+    signature: Signature,
+}
+
+impl ArrayUpper {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayUpper {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_upper"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        bound_return_type(self.name(), arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        bounds(args, |length| length as i64)
+    }
+}
+
+impl Documented for ArrayUpper {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_upper(anyarray, integer) → integer",
+            description: "Returns the upper bound of the requested array dimension",
+            examples: &["array_upper(ARRAY[1,8,3,7], 1) → 4"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the number of elements of an array in all its dimensions, 0 if it is empty.
+#[derive(Debug)]
+pub struct Cardinality {
+    signature: Signature,
+}
+
+impl Cardinality {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Cardinality {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "cardinality"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])?;
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut int_builder = Int64Builder::new();
+
+        for dimensions in array_dimensions(&lists(&args[0])?)? {
+            int_builder.append_option(dimensions.map(|dimensions| {
+                dimensions.map_or(0, |dimensions| dimensions.iter().product::<usize>() as i64)
+            }));
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(int_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for Cardinality {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "cardinality(anyarray) → integer",
+            description:
+                "Returns the total number of elements in the array, or 0 if the array is empty",
+            examples: &["cardinality(ARRAY[[1,2],[3,4]]) → 4"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Joins the elements of an array, in all its dimensions, with a delimiter. NULL elements are
+/// left out, or written as the third argument if it is given and not NULL.
+#[derive(Debug)]
+pub struct ArrayToString {
+    signature: Signature,
+}
+
+impl ArrayToString {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayToString {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_to_string"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])?;
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        let delimiters = cast(&args[1], &Utf8)?;
+        let delimiters = string_values(&delimiters)?;
+        let null_strings = match args.get(2) {
+            Some(null_strings) => cast(null_strings, &Utf8)?,
+            None => new_null_array(&Utf8, arrays.len()),
+        };
+        let null_strings = string_values(&null_strings)?;
+        let mut string_builder = StringBuilder::new();
+
+        for (row, (delimiter, null_string)) in delimiters.into_iter().zip(null_strings).enumerate()
+        {
+            let Some(delimiter) = delimiter.filter(|_| arrays.is_valid(row)) else {
+                string_builder.append_null();
+                continue;
+            };
+            let elements = text_values(&flatten(arrays.value(row)))?
+                .into_iter()
+                .filter_map(|element| element.or_else(|| null_string.map(String::from)))
+                .collect::<Vec<_>>();
+            string_builder.append_value(elements.join(delimiter));
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for ArrayToString {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_to_string(array anyarray, delimiter text [, null_string text]) → text",
+            description: "Converts each array element to its text representation, and concatenates those separated by the delimiter string. If null_string is given and is not NULL, then NULL array entries are represented by that string; otherwise, they are omitted",
+            examples: &["array_to_string(ARRAY[1, 2, 3, NULL, 5], ',', '*') → 1,2,3,*,5"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Removes the last elements of the first dimension of an array.
+#[derive(Debug)]
+pub struct TrimArray {
+    signature: Signature,
+}
+
+impl TrimArray {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for TrimArray {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "trim_array"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        let counts = cast(&args[1], &Int64)?;
+        let rows = counts
+            .as_primitive::<Int64Type>()
+            .iter()
+            .enumerate()
+            .map(|(row, count)| {
+                let Some(count) = count.filter(|_| arrays.is_valid(row)) else {
+                    return Ok(None);
+                };
+                let elements = arrays.value(row);
+                match usize::try_from(count) {
+                    Ok(count) if count <= elements.len() => {
+                        Ok(Some(elements.slice(0, elements.len() - count)))
+                    }
+                    _ => exec_err!(
+                        "number of elements to trim must be between 0 and {}",
+                        elements.len()
+                    ),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(list_from_rows(
+            &arrays.value_type(),
+            rows,
+        )?)))
+    }
+}
+
+impl Documented for TrimArray {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "trim_array(array anyarray, n integer) → anyarray",
+            description: "Trims an array by removing the last n elements. If the array is multidimensional, only the first dimension is trimmed",
+            examples: &["trim_array(ARRAY[1,2,3,4,5,6], 2) → {1,2,3,4}"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns some elements of the first dimension of an array, chosen at random and in a random
+/// order. The choices are repeatable with [`RegisterOptions::with_random_seed`].
+///
+/// [`RegisterOptions::with_random_seed`]: crate::registry::RegisterOptions::with_random_seed
+#[derive(Debug)]
+pub struct ArraySample {
+    signature: Signature,
+    rng: Mutex<StdRng>,
+}
+
+impl ArraySample {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Volatile),
+            rng: Mutex::new(rng(seed)),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArraySample {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_sample"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        let counts = cast(&args[1], &Int64)?;
+        let mut rng = self.rng.lock().unwrap();
+        let rows = counts
+            .as_primitive::<Int64Type>()
+            .iter()
+            .enumerate()
+            .map(|(row, count)| {
+                let Some(count) = count.filter(|_| arrays.is_valid(row)) else {
+                    return Ok(None);
+                };
+                let elements = arrays.value(row);
+                match usize::try_from(count) {
+                    Ok(count) if count <= elements.len() => {
+                        let indices = sample(&mut *rng, elements.len(), count)
+                            .into_iter()
+                            .map(|index| index as u32)
+                            .collect::<UInt32Array>();
+                        Ok(Some(take(&elements, &indices, None)?))
+                    }
+                    _ => exec_err!("sample size must be between 0 and {}", elements.len()),
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(list_from_rows(
+            &arrays.value_type(),
+            rows,
+        )?)))
+    }
+}
+
+impl Documented for ArraySample {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_sample(array anyarray, n integer) → anyarray",
+            description: "Returns an array of n items randomly selected from array. n may not exceed the length of array's first dimension. If array is multi-dimensional, an \"item\" is a slice having a given first subscript",
+            examples: &["array_sample(ARRAY[1,2,3,4,5,6], 3) → {2,6,1}"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Shuffles the first dimension of an array. The order is repeatable with
+/// [`RegisterOptions::with_random_seed`].
+///
+/// [`RegisterOptions::with_random_seed`]: crate::registry::RegisterOptions::with_random_seed
+#[derive(Debug)]
+pub struct ArrayShuffle {
+    signature: Signature,
+    rng: Mutex<StdRng>,
+}
+
+impl ArrayShuffle {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Volatile),
+            rng: Mutex::new(rng(seed)),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ArrayShuffle {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "array_shuffle"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        let mut rng = self.rng.lock().unwrap();
+        let rows = (0..arrays.len())
+            .map(|row| {
+                if arrays.is_null(row) {
+                    return Ok(None);
+                }
+                let elements = arrays.value(row);
+                let mut indices = (0..elements.len() as u32).collect::<Vec<_>>();
+                indices.shuffle(&mut *rng);
+                Ok(Some(take(&elements, &UInt32Array::from(indices), None)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(list_from_rows(
+            &arrays.value_type(),
+            rows,
+        )?)))
+    }
+}
+
+impl Documented for ArrayShuffle {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "array_shuffle(anyarray) → anyarray",
+            description: "Randomly shuffles the first dimension of the array",
+            examples: &["array_shuffle(ARRAY[[1,2],[3,4],[5,6]]) → {{5,6},{1,2},{3,4}}"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Reads arrays from Postgres' array text, e.g. `{{1,2},{3,4}}` or `{a,"b,c",NULL}`, as COPY
+/// writes them. The second argument names the type of the elements, with a `[]` for each
+/// dimension past the first, e.g. `integer` or `text[][]`, and must be constant.
+#[derive(Debug)]
+pub struct PgArrayIn {
+    signature: Signature,
+    style: IntervalStyle,
+}
+
+impl PgArrayIn {
+    pub fn new(style: IntervalStyle) -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &[Utf8]]),
+                Volatility::Stable,
+            ),
+            style,
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgArrayIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_array_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(nested_list_type(Utf8, 1))
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        _arg_types: &[DataType],
+    ) -> Result<DataType> {
+        match args.get(1) {
+            Some(Expr::Literal(ScalarValue::Utf8(Some(name)))) => {
+                let (element_type, dimensions) = element_type(name)?;
+                Ok(nested_list_type(element_type.data_type, dimensions))
+            }
+            _ => plan_err!("the element type of pg_array_in() must be a constant string"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let (element_type, dimensions) = match args.get(1) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(name)))) => element_type(name)?,
+            _ => return exec_err!("the element type of pg_array_in() must be a constant string"),
+        };
+        let args = ColumnarValue::values_to_arrays(&args[..1])?;
+        let mut elements = vec![];
+        let mut arrays = vec![];
+
+        for text in string_values(&args[0])? {
+            let Some(text) = text else {
+                arrays.push(None);
+                continue;
+            };
+            let array = parse_array(text).map_err(execution_error)?;
+            if array
+                .lower_bounds
+                .iter()
+                .any(|lower_bound| *lower_bound != 1)
+            {
+                return exec_err!("pg_array_in() only supports lower bounds of 1");
+            }
+            let lengths = if array.dimensions.is_empty() {
+                vec![0; dimensions]
+            } else if array.dimensions.len() == dimensions {
+                array.dimensions
+            } else {
+                return exec_err!(
+                    "array \"{text}\" has {} dimensions, but its type has {dimensions}",
+                    array.dimensions.len()
+                );
+            };
+            arrays.push(Some((elements.len(), lengths)));
+            elements.extend(array.elements);
+        }
+
+        let elements = element_values(elements, &element_type, self.style)?;
+        let rows = arrays
+            .into_iter()
+            .map(|array| {
+                array
+                    .map(|(start, lengths)| {
+                        let size = lengths.iter().product::<usize>();
+                        nest(elements.slice(start, size), &lengths)
+                    })
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let item_type = nested_list_type(element_type.data_type, dimensions - 1);
+        Ok(ColumnarValue::Array(Arc::new(list_from_rows(
+            &item_type, rows,
+        )?)))
+    }
+}
+
+impl Documented for PgArrayIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_array_in(text, text) → anyarray",
+            description: "Converts Postgres' text for an array, as COPY writes it, to an array whose elements have the type named by the second argument. Each [] after the type name adds a dimension, so integer reads one-dimensional arrays of integers and integer[] two-dimensional ones",
+            examples: &[
+                "pg_array_in('{1,2,NULL}', 'integer') → {1,2,NULL}",
+                "pg_array_in('{{a,\"b,c\"},{d,e}}', 'text[]') → {{a,\"b,c\"},{d,e}}",
+            ],
+            link: Some(ARRAY_IO_LINK),
+        }
+    }
+}
+
+/// Writes arrays as Postgres' array text, with their elements written as Postgres writes their
+/// types and quoted where needed. Intervals are written in the session's interval style.
+#[derive(Debug)]
+pub struct PgArrayOut {
+    signature: Signature,
+    style: IntervalStyle,
+}
+
+impl PgArrayOut {
+    pub fn new(style: IntervalStyle) -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Stable),
+            style,
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgArrayOut {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_array_out"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        array_type(self.name(), &arg_types[0])?;
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let arrays = lists(&args[0])?;
+        let mut string_builder = StringBuilder::new();
+
+        for (row, dimensions) in array_dimensions(&arrays)?.into_iter().enumerate() {
+            let Some(dimensions) = dimensions else {
+                string_builder.append_null();
+                continue;
+            };
+            let elements = element_texts(&flatten(arrays.value(row)), self.style)?;
+            string_builder.append_value(format_array(&dimensions.unwrap_or_default(), &elements));
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for PgArrayOut {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_array_out(anyarray) → text",
+            description: "Converts an array to Postgres' text for it, as COPY writes it",
+            examples: &["pg_array_out(ARRAY[['a b','c'],[NULL,'']]) → {{\"a b\",c},{NULL,\"\"}}"],
+            link: Some(ARRAY_IO_LINK),
+        }
+    }
+}
+
+/// The set-returning unnest(), a table function with a row per element of its arrays, in all
+/// their dimensions. With several arrays, each is a column and the shorter ones are padded with
+/// NULLs. Its arguments must be constant.
+///
+/// An unquoted `unnest` in `FROM` is parsed as DataFusion's own UNNEST, so this one is called as
+/// `"unnest"(...)`.
+pub struct Unnest;
+
+impl TableFunctionImpl for Unnest {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if args.is_empty() {
+            return plan_err!("unnest() takes at least one array");
+        }
+        let props = ExecutionProps::new();
+        let simplifier = ExprSimplifier::new(
+            SimplifyContext::new(&props).with_schema(Arc::new(DFSchema::empty())),
+        );
+        let columns = args
+            .iter()
+            .map(|arg| match simplifier.simplify(arg.clone())? {
+                Expr::Literal(value) if is_array(&value.data_type()) => {
+                    let arrays = lists(&value.to_array()?)?;
+                    Ok(if arrays.is_null(0) {
+                        new_empty_array(leaf_type(arrays.data_type()))
+                    } else {
+                        flatten(arrays.value(0))
+                    })
+                }
+                _ => plan_err!("unnest() only takes constant arrays, not {arg}"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let rows = columns.iter().map(|column| column.len()).max().unwrap_or(0);
+        let columns = columns
+            .into_iter()
+            .map(|column| {
+                let padding = new_null_array(column.data_type(), rows - column.len());
+                Ok(concat(&[column.as_ref(), padding.as_ref()])?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let fields = columns
+            .iter()
+            .enumerate()
+            .map(|(index, column)| {
+                let name = match args.len() {
+                    1 => "unnest".to_string(),
+                    _ => format!("unnest_{}", index + 1),
+                };
+                Field::new(name, column.data_type().clone(), true)
+            })
+            .collect::<Vec<_>>();
+
+        let schema = Arc::new(Schema::new(fields));
+        let batch = RecordBatch::try_new(schema.clone(), columns)?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
+
+impl Documented for Unnest {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "unnest(anyarray, anyarray [, ...]) → setof anyelement, anyelement [, ...]",
+            description: "Expands multiple arrays (possibly of different data types) into a set of rows. If the arrays are not all the same length then the shorter ones are padded with NULLs",
+            examples: &["select * from \"unnest\"(ARRAY[1,2], ARRAY['foo','bar','baz']) → 1 foo, 2 bar, NULL baz"],
+            link: Some(ARRAY_FUNCTIONS_LINK),
+        }
+    }
+}
+
+pub(super) fn rng(seed: Option<u64>) -> StdRng {
+    match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    }
+}
+
+fn is_array(data_type: &DataType) -> bool {
+    matches!(data_type, List(_) | LargeList(_) | FixedSizeList(..))
+}
+
+fn is_integer(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | UInt64
+    )
+}
+
+/// Returns the type of the arrays a function reads, as [`lists`] returns them, or an error if
+/// `data_type` is not an array type.
+fn array_type(name: &str, data_type: &DataType) -> Result<DataType> {
+    match list_type(data_type) {
+        Some(data_type) => Ok(data_type),
+        None => plan_err!("{name}() takes an array, not {data_type}"),
+    }
+}
+
+/// Returns the List type of an array type, with the LargeList and FixedSizeList types in it
+/// replaced by List types too, or `None` if `data_type` is not an array type.
+fn list_type(data_type: &DataType) -> Option<DataType> {
+    match data_type {
+        List(field) | LargeList(field) | FixedSizeList(field, _) => {
+            let item_type = list_type(field.data_type()).unwrap_or(field.data_type().clone());
+            Some(List(Arc::new(Field::new("item", item_type, true))))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the type of an array of `dimensions` dimensions of `data_type`.
+fn nested_list_type(data_type: DataType, dimensions: usize) -> DataType {
+    (0..dimensions).fold(data_type, |data_type, _| {
+        List(Arc::new(Field::new("item", data_type, true)))
+    })
+}
+
+/// Returns the type of the elements of the innermost dimension of an array type.
+fn leaf_type(data_type: &DataType) -> &DataType {
+    match data_type {
+        List(field) | LargeList(field) | FixedSizeList(field, _) => leaf_type(field.data_type()),
+        data_type => data_type,
+    }
+}
+
+/// The type of the integer arrays array_positions returns.
+fn integer_list_type() -> DataType {
+    List(Arc::new(Field::new("item", Int64, true)))
+}
+
+/// Returns the arrays of an argument as a List array. Arrays of arrays are the dimensions of
+/// multidimensional arrays, as in Postgres.
+fn lists(array: &ArrayRef) -> Result<ListArray> {
+    let data_type = match list_type(array.data_type()) {
+        Some(data_type) => data_type,
+        None => return exec_err!("expected an array, not {}", array.data_type()),
+    };
+    Ok(cast(array, &data_type)?.as_list::<i32>().clone())
+}
+
+/// Returns the values of an array of integer arrays, such as the dimensions of array_fill.
+fn integer_arrays(array: &ArrayRef) -> Result<Vec<Option<Vec<Option<i64>>>>> {
+    let arrays = lists(array)?;
+    (0..arrays.len())
+        .map(|row| {
+            if arrays.is_null(row) {
+                return Ok(None);
+            }
+            let values = cast(&arrays.value(row), &Int64)?;
+            Ok(Some(values.as_primitive::<Int64Type>().iter().collect()))
+        })
+        .collect()
+}
+
+/// Returns the elements of an array in all its dimensions.
+fn flatten(mut elements: ArrayRef) -> ArrayRef {
+    while let List(_) = elements.data_type() {
+        elements = sublist_values(elements.as_list::<i32>());
+    }
+    elements
+}
+
+/// Returns the values of the lists of a List array, without those outside of its slice.
+fn sublist_values(lists: &ListArray) -> ArrayRef {
+    let offsets = lists.value_offsets();
+    let start = offsets[0] as usize;
+    let end = offsets[offsets.len() - 1] as usize;
+    lists.values().slice(start, end - start)
+}
+
+/// Returns the lengths of the dimensions of the arrays of a List array: `None` for a NULL array,
+/// and `Some(None)` for an empty one, which has no dimensions in Postgres. The arrays of a
+/// dimension must all have the same length.
+fn array_dimensions(arrays: &ListArray) -> Result<Vec<Option<Option<Vec<usize>>>>> {
+    (0..arrays.len())
+        .map(|row| {
+            if arrays.is_null(row) {
+                return Ok(None);
+            }
+            let mut elements = arrays.value(row);
+            let mut dimensions = vec![elements.len()];
+            while let List(_) = elements.data_type() {
+                let lists = elements.as_list::<i32>();
+                let length = if lists.is_empty() {
+                    0
+                } else {
+                    lists.value_length(0)
+                };
+                if lists.null_count() > 0
+                    || (0..lists.len()).any(|index| lists.value_length(index) != length)
+                {
+                    return exec_err!(
+                        "multidimensional arrays must have array expressions with matching dimensions"
+                    );
+                }
+                dimensions.push(length as usize);
+                elements = sublist_values(lists);
+            }
+            Ok(Some((!dimensions.contains(&0)).then_some(dimensions)))
+        })
+        .collect()
+}
+
+/// Returns the type array_lower and array_upper return, checking their arguments.
+fn bound_return_type(name: &str, arg_types: &[DataType]) -> Result<DataType> {
+    array_type(name, &arg_types[0])?;
+    if !is_integer(&arg_types[1]) {
+        return plan_err!(
+            "the dimension of {name}() must be an integer, not {}",
+            arg_types[1]
+        );
+    }
+    Ok(Int64)
+}
+
+/// Returns a bound of the requested dimension of each array: `bound` of the length of the
+/// dimension, or NULL if the array is empty or has no such dimension.
+fn bounds(args: &[ColumnarValue], bound: fn(usize) -> i64) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let dimensions = array_dimensions(&lists(&args[0])?)?;
+    let requested = cast(&args[1], &Int64)?;
+    let mut int_builder = Int64Builder::new();
+
+    for (dimensions, requested) in dimensions
+        .into_iter()
+        .zip(requested.as_primitive::<Int64Type>())
+    {
+        let length = dimensions
+            .flatten()
+            .zip(requested)
+            .and_then(|(dimensions, requested)| {
+                let index = usize::try_from(requested).ok()?.checked_sub(1)?;
+                dimensions.get(index).copied()
+            });
+        int_builder.append_option(length.map(bound));
+    }
+
+    Ok(ColumnarValue::Array(
+        Arc::new(int_builder.finish()) as ArrayRef
+    ))
+}
+
+/// Returns the elements of an array of `dimensions` filled with `value`, an array of one value:
+/// the arrays of its first dimension.
+fn fill(value: &ArrayRef, dimensions: &[usize]) -> Result<ArrayRef> {
+    let size = dimensions.iter().product::<usize>();
+    nest(
+        take(value, &UInt32Array::from(vec![0; size]), None)?,
+        dimensions,
+    )
+}
+
+/// Returns the arrays of the first dimension of an array of `dimensions`, given its elements in
+/// row-major order. An array with a dimension of length 0 is empty.
+fn nest(mut elements: ArrayRef, dimensions: &[usize]) -> Result<ArrayRef> {
+    let dimensions = if elements.is_empty() {
+        vec![0; dimensions.len()]
+    } else {
+        dimensions.to_vec()
+    };
+    for (level, length) in dimensions.iter().enumerate().skip(1).rev() {
+        let count = dimensions[..level].iter().product::<usize>();
+        let field = Arc::new(Field::new("item", elements.data_type().clone(), true));
+        let offsets = OffsetBuffer::from_lengths(std::iter::repeat(*length).take(count));
+        elements = Arc::new(ListArray::try_new(field, offsets, elements, None)?);
+    }
+    Ok(elements)
+}
+
+/// Returns a List array of arrays of elements of type `item_type`, NULL for the rows that are
+/// `None`.
+fn list_from_rows(item_type: &DataType, rows: Vec<Option<ArrayRef>>) -> Result<ListArray> {
+    let field: FieldRef = Arc::new(Field::new("item", item_type.clone(), true));
+    let offsets = OffsetBuffer::from_lengths(
+        rows.iter()
+            .map(|row| row.as_ref().map_or(0, |elements| elements.len())),
+    );
+    let nulls = NullBuffer::from(rows.iter().map(Option::is_some).collect::<Vec<_>>());
+    let elements = rows
+        .iter()
+        .flatten()
+        .map(|elements| elements.as_ref())
+        .collect::<Vec<&dyn Array>>();
+    let values = if elements.is_empty() {
+        new_empty_array(item_type)
+    } else {
+        concat(&elements)?
+    };
+    Ok(ListArray::try_new(field, offsets, values, Some(nulls))?)
+}
+
+/// The type of the elements of the arrays pg_array_in reads: the name Postgres gives it in its
+/// errors and the type of its values.
+#[derive(Debug, Clone, PartialEq)]
+struct ElementType {
+    name: &'static str,
+    data_type: DataType,
+}
+
+/// Returns the element type and the number of dimensions of the arrays of a Postgres type name,
+/// such as `int4`, `double precision`, `numeric(10,2)` or `text[]`, which has 2 dimensions.
+fn element_type(name: &str) -> Result<(ElementType, usize)> {
+    let mut base = name.trim().to_lowercase();
+    let mut dimensions = 1;
+    while let Some(rest) = base.trim_end().strip_suffix("[]") {
+        base = rest.to_string();
+        dimensions += 1;
+    }
+    let (base, modifiers) = match base.split_once('(') {
+        Some((base, modifiers)) => match modifiers.trim_end().strip_suffix(')') {
+            Some(modifiers) => (base.to_string(), Some(modifiers.to_string())),
+            None => return plan_err!("type \"{name}\" does not exist"),
+        },
+        None => (base, None),
+    };
+    let base = base.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let (name, data_type) = match base.as_str() {
+        "bool" | "boolean" => ("boolean", Boolean),
+        "int2" | "smallint" => ("smallint", Int16),
+        "int" | "int4" | "integer" => ("integer", Int32),
+        "int8" | "bigint" => ("bigint", Int64),
+        "float4" | "real" => ("real", Float32),
+        "float" | "float8" | "double precision" => ("double precision", Float64),
+        "numeric" | "decimal" => {
+            let Some(modifiers) = &modifiers else {
+                return plan_err!(
+                    "pg_array_in() needs the precision and scale of numeric elements, as in numeric(10,2)"
+                );
+            };
+            ("numeric", numeric_type(modifiers)?)
+        }
+        "text" | "varchar" | "character varying" | "char" | "character" | "bpchar" | "name" => {
+            ("text", Utf8)
+        }
+        "date" => ("date", Date32),
+        "time" | "time without time zone" => ("time", Time64(TimeUnit::Microsecond)),
+        "timestamp" | "timestamp without time zone" => {
+            ("timestamp", Timestamp(TimeUnit::Microsecond, None))
+        }
+        "timestamptz" | "timestamp with time zone" => (
+            "timestamp with time zone",
+            Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+        ),
+        "interval" => ("interval", Interval(IntervalUnit::MonthDayNano)),
+        "inet" => ("inet", Utf8),
+        "cidr" => ("cidr", Utf8),
+        _ => return plan_err!("type \"{base}\" does not exist"),
+    };
+    if modifiers.is_some() && name != "numeric" {
+        return plan_err!("type modifier is not allowed for type \"{name}\"");
+    }
+    Ok((ElementType { name, data_type }, dimensions))
+}
+
+/// Returns the decimal type of `numeric(precision, scale)`, given the modifiers in parentheses.
+fn numeric_type(modifiers: &str) -> Result<DataType> {
+    let modifiers = modifiers
+        .split(',')
+        .map(|modifier| modifier.trim().parse::<i64>().ok())
+        .collect::<Option<Vec<_>>>();
+    let (precision, scale) = match modifiers.as_deref() {
+        Some([precision]) => (*precision, 0),
+        Some([precision, scale]) => (*precision, *scale),
+        _ => return plan_err!("invalid NUMERIC type modifier"),
+    };
+    if !(1..=i64::from(DECIMAL128_MAX_PRECISION)).contains(&precision) {
+        return plan_err!(
+            "NUMERIC precision {precision} must be between 1 and {DECIMAL128_MAX_PRECISION}"
+        );
+    }
+    if !(0..=precision).contains(&scale) {
+        return plan_err!("NUMERIC scale {scale} must be between 0 and precision {precision}");
+    }
+    Ok(Decimal128(precision as u8, scale as i8))
+}
+
+/// Reads the text of array elements as values of `element_type`, as its input function does.
+fn element_values(
+    texts: Vec<Option<String>>,
+    element_type: &ElementType,
+    style: IntervalStyle,
+) -> Result<ArrayRef> {
+    match element_type.name {
+        "interval" => {
+            let intervals = texts
+                .iter()
+                .map(|text| {
+                    text.as_deref()
+                        .map(|text| parse_interval(text, style).map_err(execution_error))
+                        .transpose()?
+                        .map(interval_value)
+                        .transpose()
+                })
+                .collect::<Result<IntervalMonthDayNanoArray>>()?;
+            Ok(Arc::new(intervals))
+        }
+        "inet" | "cidr" => {
+            let addresses = texts
+                .iter()
+                .map(|text| {
+                    text.as_deref()
+                        .map(|text| network_text(text, element_type.name))
+                        .transpose()
+                })
+                .collect::<Result<StringArray>>()?;
+            Ok(Arc::new(addresses))
+        }
+        name => {
+            let strings = Arc::new(StringArray::from(texts)) as ArrayRef;
+            let values = cast(&strings, &element_type.data_type)?;
+            // The cast leaves NULL where it cannot read a value.
+            match (0..values.len()).find(|&index| values.is_null(index) && strings.is_valid(index))
+            {
+                Some(index) => exec_err!(
+                    "invalid input syntax for type {name}: \"{}\"",
+                    strings.as_string::<i32>().value(index)
+                ),
+                None => Ok(values),
+            }
+        }
+    }
+}
+
+/// Returns the canonical text of an inet or cidr value, which leaves out the netmask of a host
+/// address in an inet and always has it in a cidr.
+fn network_text(text: &str, name: &str) -> Result<String> {
+    if let Ok(address) = IpAddr::from_str(text) {
+        let max_prefix_len = if address.is_ipv4() { 32 } else { 128 };
+        return Ok(match name {
+            "cidr" => format!("{address}/{max_prefix_len}"),
+            _ => address.to_string(),
+        });
+    }
+    let network = IpNet::from_str(text).map_err(|_| {
+        DataFusionError::Execution(format!("invalid input syntax for type {name}: \"{text}\""))
+    })?;
+    if name == "cidr" && network.trunc() != network {
+        return exec_err!("invalid cidr value: \"{text}\": Value has bits set to right of mask.");
+    }
+    Ok(
+        if name == "inet" && network.prefix_len() == network.max_prefix_len() {
+            network.addr().to_string()
+        } else {
+            network.to_string()
+        },
+    )
+}
+
+/// Returns the text of array elements as Postgres writes their types: timestamps with a space
+/// before the time, and intervals in `style`.
+pub(super) fn element_texts(
+    elements: &ArrayRef,
+    style: IntervalStyle,
+) -> Result<Vec<Option<String>>> {
+    match elements.data_type() {
+        Interval(_) => Ok(intervals(elements)?
+            .into_iter()
+            .map(|interval| interval.map(|interval| format_interval(interval, style)))
+            .collect()),
+        Timestamp(_, zone) => {
+            let timestamps = cast(elements, &Timestamp(TimeUnit::Microsecond, zone.clone()))?;
+            Ok(timestamps
+                .as_primitive::<TimestampMicrosecondType>()
+                .iter()
+                .map(|timestamp| {
+                    timestamp.map(|timestamp| timestamp_text(timestamp, zone.is_some()))
+                })
+                .collect())
+        }
+        Time32(_) | Time64(_) => {
+            let times = cast(elements, &Time64(TimeUnit::Microsecond))?;
+            Ok(times
+                .as_primitive::<Time64MicrosecondType>()
+                .iter()
+                .map(|time| time.map(time_text))
+                .collect())
+        }
+        _ => text_values(elements),
+    }
+}
+
+/// Returns the text of a timestamp in microseconds, in UTC when it has a time zone.
+fn timestamp_text(timestamp: i64, has_zone: bool) -> String {
+    let Some(datetime) = DateTime::from_timestamp_micros(timestamp) else {
+        return timestamp.to_string();
+    };
+    let zone = if has_zone { "+00" } else { "" };
+    format!(
+        "{}{}{zone}",
+        datetime.format("%Y-%m-%d %H:%M:%S"),
+        fraction_text(timestamp.rem_euclid(1_000_000))
+    )
+}
+
+/// Returns the text of a time of day in microseconds.
+fn time_text(time: i64) -> String {
+    let seconds = time.div_euclid(1_000_000);
+    format!(
+        "{:02}:{:02}:{:02}{}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60,
+        fraction_text(time.rem_euclid(1_000_000))
+    )
+}
+
+/// Returns the fraction of a second of `micros` microseconds, without trailing zeros.
+fn fraction_text(micros: i64) -> String {
+    if micros == 0 {
+        return String::new();
+    }
+    format!(".{micros:06}").trim_end_matches('0').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::util::pretty::pretty_format_batches;
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::{register_postgres_udfs, register_postgres_udfs_with_options};
+    use crate::registry::RegisterOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_array_positions() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select array_positions(make_array('A', 'A', 'B', 'A'), 'A') as col_found, array_positions(make_array(1, NULL, 2, NULL), NULL) as col_null, array_positions(make_array(1, 2), 3) as col_missing",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+----------+-------------+
+| col_found | col_null | col_missing |
++-----------+----------+-------------+
+| [1, 2, 4] | [2, 4]   | []          |
++-----------+----------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select array_positions(make_array(make_array(1, 2), make_array(3, 4)), 1)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("searching for elements in multidimensional arrays is not supported"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_fill() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select array_fill(11, make_array(2, 3)) as col_fill, array_fill(7, make_array(3), make_array(1)) as col_lower_bounds, array_fill(7, make_array(0)) as col_empty",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------------------------+------------------+-----------+
+| col_fill                     | col_lower_bounds | col_empty |
++------------------------------+------------------+-----------+
+| [[11, 11, 11], [11, 11, 11]] | [7, 7, 7]        | []        |
++------------------------------+------------------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select array_fill(7, make_array(3), make_array(2))")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("array_fill() only supports lower bounds of 1"));
+
+        let result = ctx
+            .sql("select array_fill(7, make_array(-1))")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("array size exceeds the maximum allowed (134217727)"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_dimensions() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select array_dims(make_array(make_array(1, 2, 3), make_array(4, 5, 6))) as col_dims, array_lower(make_array(1, 2, 3), 1) as col_lower, array_upper(make_array(make_array(1, 2, 3), make_array(4, 5, 6)), 2) as col_upper, array_upper(make_array(1, 2, 3), 2) as col_no_dimension, cardinality(make_array(make_array(1, 2), make_array(3, 4))) as col_cardinality",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+-----------+-----------+------------------+-----------------+
+| col_dims   | col_lower | col_upper | col_no_dimension | col_cardinality |
++------------+-----------+-----------+------------------+-----------------+
+| [1:2][1:3] | 1         | 3         |                  | 4               |
++------------+-----------+-----------+------------------+-----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select array_dims(make_array(make_array(1, 2), make_array(3)))")
+            .await?
+            .collect()
+            .await;
+        assert!(result.unwrap_err().to_string().contains(
+            "multidimensional arrays must have array expressions with matching dimensions"
+        ));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_to_string() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select array_to_string(make_array(1, 2, 3, NULL, 5), ',', '*') as col_null_string, array_to_string(make_array(1, 2, 3, NULL, 5), ',') as col_without, array_to_string(make_array(make_array(1, 2), make_array(3, 4)), '-') as col_nested, array_to_string(make_array(true, false), '-') as col_booleans",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------------+-------------+------------+--------------+
+| col_null_string | col_without | col_nested | col_booleans |
++-----------------+-------------+------------+--------------+
+| 1,2,3,*,5       | 1,2,3,5     | 1-2-3-4    | t-f          |
++-----------------+-------------+------------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_trim_array() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select trim_array(make_array(1, 2, 3, 4, 5, 6), 2) as col_trim, trim_array(make_array(make_array(1, 2), make_array(3, 4), make_array(5, 6)), 1) as col_nested",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------+------------------+
+| col_trim     | col_nested       |
++--------------+------------------+
+| [1, 2, 3, 4] | [[1, 2], [3, 4]] |
++--------------+------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select trim_array(make_array(1, 2), 3)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("number of elements to trim must be between 0 and 2"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_array_sample_shuffle() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select cardinality(array_sample(make_array(1, 2, 3, 4, 5, 6), 3)) as col_sample, array_sort(array_shuffle(make_array(1, 2, 3, 4, 5, 6))) as col_shuffle",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+--------------------+
+| col_sample | col_shuffle        |
++------------+--------------------+
+| 3          | [1, 2, 3, 4, 5, 6] |
++------------+--------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select array_sample(make_array(1, 2), 3)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("sample size must be between 0 and 2"));
+
+        // The same seed makes the same choices.
+        let sql = "select array_sample(make_array(1, 2, 3, 4, 5, 6), 3), array_shuffle(make_array(1, 2, 3, 4, 5, 6))";
+        let mut results = vec![];
+        for _ in 0..2 {
+            let ctx = SessionContext::new();
+            register_postgres_udfs_with_options(
+                &ctx,
+                &RegisterOptions::default().with_random_seed(42),
+            )?;
+            let batches = ctx.sql(sql).await?.collect().await?;
+            results.push(pretty_format_batches(&batches)?.to_string());
+        }
+        assert_eq!(results[0], results[1]);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unnest() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select * from \"unnest\"(make_array(1, 2), make_array('foo', 'bar', 'baz'))")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+----------+
+| unnest_1 | unnest_2 |
++----------+----------+
+| 1        | foo      |
+| 2        | bar      |
+|          | baz      |
++----------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        // Unquoted, a single array is unnested by DataFusion's own UNNEST, as in Postgres.
+        let df = ctx
+            .sql("select * from unnest(make_array(1, 2)) as t(x)")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---+
+| x |
++---+
+| 1 |
+| 2 |
++---+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pg_array_in() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select pg_array_in('{1,2,NULL}', 'integer') as col_int, pg_array_in('{{a,"b,c"},{" d ",\"e}}', 'text[]') as col_text, pg_array_in('{}', 'int8[]') as col_empty, pg_array_in('{10.1.2.3/32,10.0.0.0/8}', 'inet') as col_inet, pg_array_in('{1.234,-5}', 'numeric(10,2)') as col_numeric, pg_array_in(NULL, 'boolean') as col_null"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+-----------------------+-----------+------------------------+---------------+----------+
+| col_int  | col_text              | col_empty | col_inet               | col_numeric   | col_null |
++----------+-----------------------+-----------+------------------------+---------------+----------+
+| [1, 2, ] | [[a, b,c], [ d , "e]] | []        | [10.1.2.3, 10.0.0.0/8] | [1.23, -5.00] |          |
++----------+-----------------------+-----------+------------------------+---------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select pg_array_in('{1,x}', 'integer')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid input syntax for type integer: \"x\""));
+
+        let result = ctx
+            .sql("select pg_array_in('{{1,2},{3}}', 'integer[]')")
+            .await?
+            .collect()
+            .await;
+        assert!(result.unwrap_err().to_string().contains(
+            "malformed array literal: \"{{1,2},{3}}\": Multidimensional arrays must have sub-arrays with matching dimensions."
+        ));
+
+        let result = ctx
+            .sql("select pg_array_in('{{1,2},{3,4}}', 'integer')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("array \"{{1,2},{3,4}}\" has 2 dimensions, but its type has 1"));
+
+        let result = ctx
+            .sql("select pg_array_in('{10.1.2.3/8}', 'cidr')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid cidr value: \"10.1.2.3/8\""));
+
+        let result = ctx.sql("select pg_array_in('{1}', 'foo')").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("type \"foo\" does not exist"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pg_array_out() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select pg_array_out(make_array('a b', 'NULL', '', NULL, 'x"y\z')) as col_text, pg_array_out(make_array(make_array(1.5, 2.0), make_array(3.0, 4.25))) as col_float, pg_array_out(make_array(true, false)) as col_bool, pg_array_out(pg_array_in('{"2001-02-03 04:05:06.5"}', 'timestamp')) as col_timestamp, pg_array_out(pg_array_in('{"2001-02-03 04:05:06+02"}', 'timestamptz')) as col_timestamptz, pg_array_out(pg_array_in('{"1 day",02:30:00}', 'interval')) as col_interval"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------------------------+--------------------+----------+---------------------------+----------------------------+--------------------+
+| col_text                         | col_float          | col_bool | col_timestamp             | col_timestamptz            | col_interval       |
++----------------------------------+--------------------+----------+---------------------------+----------------------------+--------------------+
+| {"a b","NULL","",NULL,"x\"y\\z"} | {{1.5,2},{3,4.25}} | {t,f}    | {"2001-02-03 04:05:06.5"} | {"2001-02-03 02:05:06+00"} | {"1 day",02:30:00} |
++----------------------------------+--------------------+----------+---------------------------+----------------------------+--------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx.sql("select pg_array_out(1)").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("pg_array_out() takes an array, not Int64"));
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
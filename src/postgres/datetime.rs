@@ -0,0 +1,1450 @@
+//! Postgres' date/time arithmetic: `age`, the `justify_*` and `make_*` functions, `date_trunc`,
+//! `date_part` and `date_bin`, and the time zones they read.
+//!
+//! Timestamps are microseconds since 1970-01-01 00:00:00, either in UTC or, for timestamps
+//! without time zone, in local time, and intervals are months, days and microseconds, as Postgres
+//! keeps them. The calendar fields are computed with Postgres' Julian day routines, so that years
+//! BC, the borrowing of `age` and the rounding of `date_part` are Postgres'. Time zone names are
+//! looked up in the time zone database bundled by `chrono-tz`.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, LocalResult, NaiveDateTime, Offset, TimeZone};
+use chrono_tz::Tz;
+
+use crate::postgres::formatting::{
+    c_general, date_to_julian, days_in_month, is_valid_julian, iso_week_to_julian, julian_to_date,
+    JULIAN_END, MIN_TIMESTAMP, UNIX_EPOCH_JULIAN,
+};
+
+pub(super) const USECS_PER_SEC: i64 = 1_000_000;
+pub(super) const USECS_PER_MINUTE: i64 = 60 * USECS_PER_SEC;
+pub(super) const USECS_PER_HOUR: i64 = 60 * USECS_PER_MINUTE;
+pub(super) const USECS_PER_DAY: i64 = 24 * USECS_PER_HOUR;
+
+/// The days in a month, as the `justify_*` functions and interval input count them.
+pub(super) const DAYS_PER_MONTH: i32 = 30;
+
+/// An interval as Postgres keeps it: months, days and microseconds, each with its own sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Interval {
+    pub(crate) months: i32,
+    pub(crate) days: i32,
+    pub(crate) micros: i64,
+}
+
+fn interval_out_of_range() -> anyhow::Error {
+    anyhow!("interval out of range")
+}
+
+fn timestamp_out_of_range() -> anyhow::Error {
+    anyhow!("timestamp out of range")
+}
+
+/// The calendar fields of a timestamp, as Postgres' `pg_tm` has them. The year is astronomical:
+/// 0 is 1 BC, -1 is 2 BC and so on.
+#[derive(Debug, Clone, Copy)]
+struct Fields {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    micros: i64,
+}
+
+impl Fields {
+    fn from_timestamp(timestamp: i64) -> Result<Fields> {
+        if timestamp < MIN_TIMESTAMP {
+            return Err(timestamp_out_of_range());
+        }
+        let time = timestamp.rem_euclid(USECS_PER_DAY);
+        let (year, month, day) =
+            julian_to_date(timestamp.div_euclid(USECS_PER_DAY) + UNIX_EPOCH_JULIAN);
+        Ok(Fields {
+            year,
+            month,
+            day,
+            hour: time / USECS_PER_HOUR,
+            minute: time / USECS_PER_MINUTE % 60,
+            second: time / USECS_PER_SEC % 60,
+            micros: time % USECS_PER_SEC,
+        })
+    }
+
+    fn julian(&self) -> i64 {
+        date_to_julian(self.year, self.month, self.day)
+    }
+
+    /// The microseconds since midnight.
+    fn time(&self) -> i64 {
+        ((self.hour * 60 + self.minute) * 60 + self.second) * USECS_PER_SEC + self.micros
+    }
+
+    /// The seconds and their fraction, in microseconds.
+    fn seconds(&self) -> i64 {
+        self.second * USECS_PER_SEC + self.micros
+    }
+
+    fn to_timestamp(self) -> Result<i64> {
+        if !is_valid_julian(self.year, self.month) {
+            return Err(timestamp_out_of_range());
+        }
+        let micros = i128::from(self.julian() - UNIX_EPOCH_JULIAN) * i128::from(USECS_PER_DAY)
+            + i128::from(self.time());
+        i64::try_from(micros)
+            .ok()
+            .filter(|micros| *micros >= MIN_TIMESTAMP)
+            .ok_or_else(timestamp_out_of_range)
+    }
+}
+
+/// Adds `seconds` to a timestamp, as when moving it from UTC to a time zone.
+fn shift(timestamp: i64, seconds: i32) -> Result<i64> {
+    timestamp
+        .checked_add(i64::from(seconds) * USECS_PER_SEC)
+        .ok_or_else(timestamp_out_of_range)
+}
+
+/// Returns the day of the week of a Julian day, 0 for Sunday, as Postgres' `j2day` does.
+fn weekday(julian: i64) -> i64 {
+    (julian + 1).rem_euclid(7)
+}
+
+/// Returns the Julian day on which the first ISO week of `year` starts.
+fn iso_year_start(year: i64) -> i64 {
+    let fourth = date_to_julian(year, 1, 4);
+    fourth - weekday(fourth - 1)
+}
+
+/// Returns the ISO year and week of a date, as Postgres' `date2isoyear` and `date2isoweek` do.
+fn iso_week(year: i64, month: i64, day: i64) -> (i64, i64) {
+    let julian = date_to_julian(year, month, day);
+    let mut year = year;
+    let mut start = iso_year_start(year);
+    if julian < start {
+        year -= 1;
+        start = iso_year_start(year);
+    }
+    let mut week = (julian - start) / 7 + 1;
+    if week >= 52 {
+        let next = iso_year_start(year + 1);
+        if julian >= next {
+            year += 1;
+            week = (julian - next) / 7 + 1;
+        }
+    }
+    (year, week)
+}
+
+/// Returns `numerator / denominator` as Postgres returns its exact numeric results as double
+/// precision: rounded once, from the decimal quotient.
+fn quotient(numerator: i128, denominator: i128) -> f64 {
+    let integer = numerator / denominator;
+    let fraction = (numerator % denominator).abs() * 10_i128.pow(20) / denominator;
+    let sign = if numerator < 0 && integer == 0 {
+        "-"
+    } else {
+        ""
+    };
+    format!("{sign}{integer}.{fraction:020}")
+        .parse()
+        .unwrap_or(f64::NAN)
+}
+
+/// A time zone: a fixed offset from UTC, in seconds east, or a zone of the time zone database.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Zone {
+    Fixed(i32),
+    Named(Tz),
+}
+
+/// The time zone abbreviations of Postgres' `Default` set that are read before the time zone
+/// database, with their offsets in seconds east of UTC.
+const ABBREVIATIONS: &[(&str, i32)] = &[
+    ("acdt", 37800),
+    ("acst", 34200),
+    ("adt", -10800),
+    ("aedt", 39600),
+    ("aest", 36000),
+    ("akdt", -28800),
+    ("akst", -32400),
+    ("ast", -14400),
+    ("awst", 28800),
+    ("bst", 3600),
+    ("cdt", -18000),
+    ("cest", 7200),
+    ("cet", 3600),
+    ("cst", -21600),
+    ("eat", 10800),
+    ("edt", -14400),
+    ("eest", 10800),
+    ("eet", 7200),
+    ("est", -18000),
+    ("gmt", 0),
+    ("hkt", 28800),
+    ("hst", -36000),
+    ("idt", 10800),
+    ("ist", 7200),
+    ("jst", 32400),
+    ("kst", 32400),
+    ("mdt", -21600),
+    ("mesz", 7200),
+    ("mez", 3600),
+    ("msk", 10800),
+    ("mst", -25200),
+    ("ndt", -9000),
+    ("nst", -12600),
+    ("nzdt", 46800),
+    ("nzst", 43200),
+    ("nzt", 43200),
+    ("pdt", -25200),
+    ("pkt", 18000),
+    ("pst", -28800),
+    ("sast", 7200),
+    ("sgt", 28800),
+    ("uct", 0),
+    ("ut", 0),
+    ("utc", 0),
+    ("wat", 3600),
+    ("wet", 0),
+    ("z", 0),
+    ("zulu", 0),
+];
+
+impl Zone {
+    pub(crate) const UTC: Zone = Zone::Fixed(0);
+
+    /// Reads a time zone as `date_trunc` and `AT TIME ZONE` do: an abbreviation such as `EST`, a
+    /// name of the time zone database such as `Europe/Paris`, or a POSIX time zone such as
+    /// `UTC+5` or `+05`, which are five hours west of UTC.
+    pub(crate) fn from_name(name: &str) -> Result<Zone> {
+        if let Some((_, offset)) = ABBREVIATIONS
+            .iter()
+            .find(|(abbreviation, _)| abbreviation.eq_ignore_ascii_case(name))
+        {
+            return Ok(Zone::Fixed(*offset));
+        }
+        if let Ok(zone) = Tz::from_str_insensitive(name) {
+            return Ok(Zone::Named(zone));
+        }
+        posix_offset(name)
+            .map(Zone::Fixed)
+            .ok_or_else(|| anyhow!("time zone \"{name}\" not recognized"))
+    }
+
+    /// Reads a time zone as the zone of a timestamp with time zone is read: an ISO 8601 offset
+    /// such as `+05:30`, which is east of UTC, or a zone `from_name` reads.
+    pub(crate) fn from_input(name: &str) -> Result<Zone> {
+        if name.starts_with(|c: char| c.is_ascii_digit()) {
+            bail!("invalid input syntax for type numeric time zone: \"{name}\": Numeric time zones must have \"-\" or \"+\" as first character.");
+        }
+        match iso_offset(name)? {
+            Some(offset) => Ok(Zone::Fixed(offset)),
+            None => Zone::from_name(name),
+        }
+    }
+
+    /// Returns the offset from UTC, in seconds east, at a timestamp in UTC.
+    pub(crate) fn offset_at(&self, timestamp: i64) -> i32 {
+        match self {
+            Zone::Fixed(offset) => *offset,
+            Zone::Named(zone) => zone
+                .offset_from_utc_datetime(&naive(timestamp))
+                .fix()
+                .local_minus_utc(),
+        }
+    }
+
+    /// Returns the offset from UTC, in seconds east, of a local time. A local time that is
+    /// skipped or repeated when the clocks change is read as the later of the times it may be,
+    /// as Postgres reads it.
+    fn local_offset(&self, local: i64) -> i32 {
+        let Zone::Named(zone) = self else {
+            return self.offset_at(local);
+        };
+        match zone.offset_from_local_datetime(&naive(local)) {
+            LocalResult::Single(offset) => offset.fix().local_minus_utc(),
+            LocalResult::Ambiguous(first, second) => first
+                .fix()
+                .local_minus_utc()
+                .min(second.fix().local_minus_utc()),
+            LocalResult::None => self
+                .offset_at(local.saturating_sub(USECS_PER_DAY))
+                .min(self.offset_at(local.saturating_add(USECS_PER_DAY))),
+        }
+    }
+
+    /// Returns the local time of a timestamp in UTC.
+    pub(crate) fn to_local(self, timestamp: i64) -> Result<i64> {
+        shift(timestamp, self.offset_at(timestamp))
+    }
+
+    /// Returns the timestamp in UTC of a local time.
+    pub(crate) fn to_utc(self, local: i64) -> Result<i64> {
+        shift(local, -self.local_offset(local))
+            .ok()
+            .filter(|timestamp| *timestamp >= MIN_TIMESTAMP)
+            .ok_or_else(timestamp_out_of_range)
+    }
+}
+
+/// Returns a timestamp in UTC as a chrono date and time, clamped to the dates chrono has.
+fn naive(timestamp: i64) -> NaiveDateTime {
+    match DateTime::from_timestamp_micros(timestamp) {
+        Some(datetime) => datetime.naive_utc(),
+        None if timestamp < 0 => NaiveDateTime::MIN,
+        None => NaiveDateTime::MAX,
+    }
+}
+
+/// Reads an ISO 8601 offset, `+hh`, `+hhmm` or `+hh:mm[:ss]`, as Postgres' `DecodeTimezone`
+/// does, and returns it in seconds east of UTC, or None when `name` is not an offset.
+fn iso_offset(name: &str) -> Result<Option<i32>> {
+    let Some(rest) = name.strip_prefix(['+', '-']) else {
+        return Ok(None);
+    };
+    let out_of_range = || anyhow!("numeric time zone \"{name}\" out of range");
+    let (mut hour, mut rest) = leading_number(rest).ok_or_else(out_of_range)?;
+    let (mut minute, mut second) = (0, 0);
+    if let Some(after) = rest.strip_prefix(':') {
+        (minute, rest) = leading_number(after).ok_or_else(out_of_range)?;
+        if let Some(after) = rest.strip_prefix(':') {
+            (second, rest) = leading_number(after).ok_or_else(out_of_range)?;
+        }
+    } else if rest.is_empty() && name.len() > 3 {
+        minute = hour % 100;
+        hour /= 100;
+    }
+    if hour > 15 || minute >= 60 || second >= 60 {
+        return Err(out_of_range());
+    }
+    if !rest.is_empty() {
+        return Ok(None);
+    }
+    let offset = (hour * 60 + minute) * 60 + second;
+    Ok(Some(if name.starts_with('-') {
+        -offset
+    } else {
+        offset
+    }))
+}
+
+/// Splits the leading digits off `text` and reads them as C's `strtol` does, no digits being 0.
+/// Returns None when the number overflows.
+fn leading_number(text: &str) -> Option<(i32, &str)> {
+    let end = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let number = if end == 0 {
+        0
+    } else {
+        text[..end].parse().ok()?
+    };
+    Some((number, &text[end..]))
+}
+
+/// Reads a POSIX time zone without daylight saving time, such as `UTC+5`, `<+05>5` or `+05`,
+/// whose offset is west of UTC, and returns its offset in seconds east of UTC.
+fn posix_offset(name: &str) -> Option<i32> {
+    let rest = match name.strip_prefix('<') {
+        Some(quoted) => &quoted[quoted.find('>')? + 1..],
+        None => {
+            let letters = name
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(name.len());
+            if (1..3).contains(&letters) {
+                return None;
+            }
+            &name[letters..]
+        }
+    };
+    let (sign, rest) = match rest.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, rest.strip_prefix('+').unwrap_or(rest)),
+    };
+    let mut parts = rest.split(':');
+    let hours = posix_number(parts.next()?, 167)?;
+    let minutes = parts
+        .next()
+        .map_or(Some(0), |part| posix_number(part, 59))?;
+    let seconds = parts
+        .next()
+        .map_or(Some(0), |part| posix_number(part, 59))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(-sign * ((hours * 60 + minutes) * 60 + seconds))
+}
+
+fn posix_number(text: &str, max: i32) -> Option<i32> {
+    if text.is_empty() || text.len() > 3 || !text.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok().filter(|number| *number <= max)
+}
+
+/// A field `date_trunc` truncates to or `date_part` extracts. The units from `Microsecond` to
+/// `Millennium` are in increasing size, for `date_trunc`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Unit {
+    Microsecond,
+    Millisecond,
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    Decade,
+    Century,
+    Millennium,
+    Timezone,
+    TimezoneHour,
+    TimezoneMinute,
+    Epoch,
+    Dow,
+    Doy,
+    Isodow,
+    Isoyear,
+    Julian,
+}
+
+/// The names of the units, from Postgres' `deltatktbl`, which both `date_trunc` and `date_part`
+/// read.
+pub(super) const UNITS: &[(&str, Unit)] = &[
+    ("c", Unit::Century),
+    ("cent", Unit::Century),
+    ("centuries", Unit::Century),
+    ("century", Unit::Century),
+    ("d", Unit::Day),
+    ("day", Unit::Day),
+    ("days", Unit::Day),
+    ("dec", Unit::Decade),
+    ("decade", Unit::Decade),
+    ("decades", Unit::Decade),
+    ("decs", Unit::Decade),
+    ("h", Unit::Hour),
+    ("hour", Unit::Hour),
+    ("hours", Unit::Hour),
+    ("hr", Unit::Hour),
+    ("hrs", Unit::Hour),
+    ("m", Unit::Minute),
+    ("microsecon", Unit::Microsecond),
+    ("mil", Unit::Millennium),
+    ("millennia", Unit::Millennium),
+    ("millennium", Unit::Millennium),
+    ("millisecon", Unit::Millisecond),
+    ("mils", Unit::Millennium),
+    ("min", Unit::Minute),
+    ("mins", Unit::Minute),
+    ("minute", Unit::Minute),
+    ("minutes", Unit::Minute),
+    ("mon", Unit::Month),
+    ("mons", Unit::Month),
+    ("month", Unit::Month),
+    ("months", Unit::Month),
+    ("ms", Unit::Millisecond),
+    ("msec", Unit::Millisecond),
+    ("msecond", Unit::Millisecond),
+    ("mseconds", Unit::Millisecond),
+    ("msecs", Unit::Millisecond),
+    ("qtr", Unit::Quarter),
+    ("quarter", Unit::Quarter),
+    ("s", Unit::Second),
+    ("sec", Unit::Second),
+    ("second", Unit::Second),
+    ("seconds", Unit::Second),
+    ("secs", Unit::Second),
+    ("timezone", Unit::Timezone),
+    ("timezone_h", Unit::TimezoneHour),
+    ("timezone_m", Unit::TimezoneMinute),
+    ("us", Unit::Microsecond),
+    ("usec", Unit::Microsecond),
+    ("usecond", Unit::Microsecond),
+    ("useconds", Unit::Microsecond),
+    ("usecs", Unit::Microsecond),
+    ("w", Unit::Week),
+    ("week", Unit::Week),
+    ("weeks", Unit::Week),
+    ("y", Unit::Year),
+    ("year", Unit::Year),
+    ("years", Unit::Year),
+    ("yr", Unit::Year),
+    ("yrs", Unit::Year),
+];
+
+/// The further units `date_part` reads, from Postgres' `datetktbl`.
+const PART_UNITS: &[(&str, Unit)] = &[
+    ("dow", Unit::Dow),
+    ("doy", Unit::Doy),
+    ("epoch", Unit::Epoch),
+    ("isodow", Unit::Isodow),
+    ("isoyear", Unit::Isoyear),
+    ("j", Unit::Julian),
+    ("jd", Unit::Julian),
+    ("julian", Unit::Julian),
+];
+
+/// The types whose fields `date_trunc` and `date_part` read, as their errors name them.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    Timestamp,
+    TimestampTz,
+    Interval,
+    Time,
+}
+
+impl Kind {
+    fn of(zone: Option<&Zone>) -> Kind {
+        match zone {
+            Some(_) => Kind::TimestampTz,
+            None => Kind::Timestamp,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Timestamp => "timestamp without time zone",
+            Kind::TimestampTz => "timestamp with time zone",
+            Kind::Interval => "interval",
+            Kind::Time => "time without time zone",
+        }
+    }
+}
+
+/// Looks a unit up as Postgres does: lowercased, and by its first 10 characters only.
+fn find_unit(unit: &str, kind: Kind, tables: &[&[(&str, Unit)]]) -> Result<Unit> {
+    let lowercase = unit.to_ascii_lowercase();
+    let key = &lowercase.as_bytes()[..lowercase.len().min(10)];
+    tables
+        .iter()
+        .flat_map(|table| table.iter())
+        .find(|(name, _)| name.as_bytes() == key)
+        .map(|(_, unit)| *unit)
+        .ok_or_else(|| {
+            anyhow!(
+                "unit \"{lowercase}\" not recognized for type {}",
+                kind.name()
+            )
+        })
+}
+
+fn not_supported(unit: &str, kind: Kind) -> anyhow::Error {
+    anyhow!(
+        "unit \"{}\" not supported for type {}",
+        unit.to_ascii_lowercase(),
+        kind.name()
+    )
+}
+
+/// Returns the field `unit` of a timestamp, as `date_part` does. With a zone, the timestamp is
+/// in UTC and the fields are those of its local time in the zone; without one it is a local
+/// time.
+pub(crate) fn timestamp_part(unit: &str, timestamp: i64, zone: Option<&Zone>) -> Result<f64> {
+    let kind = Kind::of(zone);
+    let field = find_unit(unit, kind, &[UNITS, PART_UNITS])?;
+    let offset = zone.map_or(0, |zone| zone.offset_at(timestamp));
+    let tm = Fields::from_timestamp(shift(timestamp, offset)?)?;
+    let value = match field {
+        Unit::Microsecond => tm.seconds(),
+        Unit::Millisecond => return Ok(quotient(tm.seconds().into(), 1_000)),
+        Unit::Second => return Ok(quotient(tm.seconds().into(), USECS_PER_SEC.into())),
+        Unit::Minute => tm.minute,
+        Unit::Hour => tm.hour,
+        Unit::Day => tm.day,
+        Unit::Week => iso_week(tm.year, tm.month, tm.day).1,
+        Unit::Month => tm.month,
+        Unit::Quarter => (tm.month - 1) / 3 + 1,
+        Unit::Year if tm.year > 0 => tm.year,
+        Unit::Year => tm.year - 1,
+        Unit::Decade if tm.year >= 0 => tm.year / 10,
+        Unit::Decade => -((8 - (tm.year - 1)) / 10),
+        Unit::Century if tm.year > 0 => (tm.year + 99) / 100,
+        Unit::Century => -((99 - (tm.year - 1)) / 100),
+        Unit::Millennium if tm.year > 0 => (tm.year + 999) / 1000,
+        Unit::Millennium => -((999 - (tm.year - 1)) / 1000),
+        Unit::Timezone | Unit::TimezoneHour | Unit::TimezoneMinute if zone.is_none() => {
+            return Err(not_supported(unit, kind))
+        }
+        Unit::Timezone => offset.into(),
+        Unit::TimezoneHour => (offset / 3600).into(),
+        Unit::TimezoneMinute => (offset / 60 % 60).into(),
+        Unit::Epoch => return Ok(quotient(timestamp.into(), USECS_PER_SEC.into())),
+        Unit::Dow => weekday(tm.julian()),
+        Unit::Isodow => match weekday(tm.julian()) {
+            0 => 7,
+            day => day,
+        },
+        Unit::Doy => tm.julian() - date_to_julian(tm.year, 1, 1) + 1,
+        Unit::Isoyear => match iso_week(tm.year, tm.month, tm.day).0 {
+            year if year <= 0 => year - 1,
+            year => year,
+        },
+        Unit::Julian => {
+            let micros = i128::from(tm.julian()) * i128::from(USECS_PER_DAY);
+            return Ok(quotient(
+                micros + i128::from(tm.time()),
+                USECS_PER_DAY.into(),
+            ));
+        }
+    };
+    Ok(value as f64)
+}
+
+/// The fields of an interval, as Postgres' `interval2itm` splits it: every field has the sign
+/// of the part of the interval it comes from.
+pub(super) struct IntervalFields {
+    pub(super) year: i64,
+    pub(super) month: i64,
+    pub(super) day: i64,
+    pub(super) hour: i64,
+    pub(super) minute: i64,
+    pub(super) second: i64,
+    pub(super) micros: i64,
+}
+
+impl IntervalFields {
+    pub(super) fn new(interval: Interval) -> IntervalFields {
+        let months = i64::from(interval.months);
+        IntervalFields {
+            year: months / 12,
+            month: months % 12,
+            day: interval.days.into(),
+            hour: interval.micros / USECS_PER_HOUR,
+            minute: interval.micros % USECS_PER_HOUR / USECS_PER_MINUTE,
+            second: interval.micros % USECS_PER_MINUTE / USECS_PER_SEC,
+            micros: interval.micros % USECS_PER_SEC,
+        }
+    }
+
+    fn seconds(&self) -> i64 {
+        self.second * USECS_PER_SEC + self.micros
+    }
+
+    fn to_interval(&self) -> Result<Interval> {
+        Ok(Interval {
+            months: i32::try_from(self.year * 12 + self.month)
+                .map_err(|_| interval_out_of_range())?,
+            days: i32::try_from(self.day).map_err(|_| interval_out_of_range())?,
+            micros: ((self.hour * 60 + self.minute) * 60 + self.second) * USECS_PER_SEC
+                + self.micros,
+        })
+    }
+}
+
+/// Returns the field `unit` of an interval, as `date_part` does.
+pub(crate) fn interval_part(unit: &str, interval: Interval) -> Result<f64> {
+    let field = find_unit(unit, Kind::Interval, &[UNITS, PART_UNITS])?;
+    let tm = IntervalFields::new(interval);
+    let value = match field {
+        Unit::Microsecond => tm.seconds(),
+        Unit::Millisecond => return Ok(quotient(tm.seconds().into(), 1_000)),
+        Unit::Second => return Ok(quotient(tm.seconds().into(), USECS_PER_SEC.into())),
+        Unit::Minute => tm.minute,
+        Unit::Hour => tm.hour,
+        Unit::Day => tm.day,
+        Unit::Month => tm.month,
+        Unit::Quarter => tm.month / 3 + 1,
+        Unit::Year => tm.year,
+        Unit::Decade => tm.year / 10,
+        Unit::Century => tm.year / 100,
+        Unit::Millennium => tm.year / 1000,
+        Unit::Epoch => {
+            // A year is 365.25 days and a month 30 days.
+            let months = i128::from(interval.months);
+            let seconds = 31_557_600 * (months / 12)
+                + 2_592_000 * (months % 12)
+                + 86_400 * i128::from(interval.days);
+            return Ok(quotient(
+                seconds * i128::from(USECS_PER_SEC) + i128::from(interval.micros),
+                USECS_PER_SEC.into(),
+            ));
+        }
+        _ => return Err(not_supported(unit, Kind::Interval)),
+    };
+    Ok(value as f64)
+}
+
+/// Returns the field `unit` of a time of day in microseconds, as `date_part` does.
+pub(crate) fn time_part(unit: &str, time: i64) -> Result<f64> {
+    let field = find_unit(unit, Kind::Time, &[UNITS, PART_UNITS])?;
+    let seconds = time % USECS_PER_MINUTE;
+    Ok(match field {
+        Unit::Microsecond => seconds as f64,
+        Unit::Millisecond => quotient(seconds.into(), 1_000),
+        Unit::Second => quotient(seconds.into(), USECS_PER_SEC.into()),
+        Unit::Minute => (time / USECS_PER_MINUTE % 60) as f64,
+        Unit::Hour => (time / USECS_PER_HOUR) as f64,
+        Unit::Epoch => quotient(time.into(), USECS_PER_SEC.into()),
+        _ => return Err(not_supported(unit, Kind::Time)),
+    })
+}
+
+/// Truncates a timestamp to `unit`, as `date_trunc` does. With a zone, the timestamp is in UTC
+/// and is truncated in the zone's local time; without one it is a local time.
+pub(crate) fn trunc_timestamp(unit: &str, timestamp: i64, zone: Option<&Zone>) -> Result<i64> {
+    let kind = Kind::of(zone);
+    let field = find_unit(unit, kind, &[UNITS])?;
+    if field > Unit::Millennium {
+        return Err(not_supported(unit, kind));
+    }
+    let offset = zone.map_or(0, |zone| zone.offset_at(timestamp));
+    let mut tm = Fields::from_timestamp(shift(timestamp, offset)?)?;
+
+    match field {
+        Unit::Week => {
+            // The week is an ISO week, which starts on a Monday and may start the year before.
+            let (year, week) = iso_week(tm.year, tm.month, tm.day);
+            (tm.year, tm.month, tm.day) = julian_to_date(iso_week_to_julian(year, week));
+        }
+        Unit::Millennium if tm.year > 0 => tm.year = (tm.year + 999) / 1000 * 1000 - 999,
+        Unit::Millennium => tm.year = -((999 - (tm.year - 1)) / 1000) * 1000 + 1,
+        Unit::Century if tm.year > 0 => tm.year = (tm.year + 99) / 100 * 100 - 99,
+        Unit::Century => tm.year = -((99 - (tm.year - 1)) / 100) * 100 + 1,
+        Unit::Decade if tm.year > 0 => tm.year = tm.year / 10 * 10,
+        Unit::Decade => tm.year = -((8 - (tm.year - 1)) / 10) * 10,
+        _ => {}
+    }
+    if field >= Unit::Year {
+        tm.month = 1;
+    }
+    if field >= Unit::Quarter {
+        tm.month = 3 * ((tm.month - 1) / 3) + 1;
+    }
+    if field >= Unit::Month {
+        tm.day = 1;
+    }
+    if field >= Unit::Day {
+        tm.hour = 0;
+    }
+    if field >= Unit::Hour {
+        tm.minute = 0;
+    }
+    if field >= Unit::Minute {
+        tm.second = 0;
+    }
+    if field >= Unit::Second {
+        tm.micros = 0;
+    }
+    if field == Unit::Millisecond {
+        tm.micros = tm.micros / 1000 * 1000;
+    }
+
+    let local = tm.to_timestamp()?;
+    match zone {
+        // From a day up the offset may have changed since the truncated time.
+        Some(zone) if field >= Unit::Day => zone.to_utc(local),
+        Some(_) => shift(local, -offset),
+        None => Ok(local),
+    }
+}
+
+/// Truncates an interval to `unit`, as `date_trunc` does.
+pub(crate) fn trunc_interval(unit: &str, interval: Interval) -> Result<Interval> {
+    let field = find_unit(unit, Kind::Interval, &[UNITS])?;
+    if field == Unit::Week {
+        bail!(
+            "{}: Months usually have fractional weeks.",
+            not_supported(unit, Kind::Interval)
+        );
+    }
+    if field > Unit::Millennium {
+        return Err(not_supported(unit, Kind::Interval));
+    }
+    let mut tm = IntervalFields::new(interval);
+    if field >= Unit::Millennium {
+        tm.year = tm.year / 1000 * 1000;
+    }
+    if field >= Unit::Century {
+        tm.year = tm.year / 100 * 100;
+    }
+    if field >= Unit::Decade {
+        tm.year = tm.year / 10 * 10;
+    }
+    if field >= Unit::Year {
+        tm.month = 0;
+    }
+    if field >= Unit::Quarter {
+        tm.month = 3 * (tm.month / 3);
+    }
+    if field >= Unit::Month {
+        tm.day = 0;
+    }
+    if field >= Unit::Day {
+        tm.hour = 0;
+    }
+    if field >= Unit::Hour {
+        tm.minute = 0;
+    }
+    if field >= Unit::Minute {
+        tm.second = 0;
+    }
+    if field >= Unit::Second {
+        tm.micros = 0;
+    }
+    if field == Unit::Millisecond {
+        tm.micros = tm.micros / 1000 * 1000;
+    }
+    tm.to_interval()
+}
+
+/// Subtracts two local times field by field, as `age` does, so that the result is in years,
+/// months and days rather than in days only: `age('2001-04-10', '1957-06-13')` is 43 years 9
+/// mons 27 days.
+pub(crate) fn age(first: i64, second: i64) -> Result<Interval> {
+    let tm1 = Fields::from_timestamp(first)?;
+    let tm2 = Fields::from_timestamp(second)?;
+    let sign = if first < second { -1 } else { 1 };
+    let mut micros = sign * (tm1.micros - tm2.micros);
+    let mut seconds = sign * (tm1.second - tm2.second);
+    let mut minutes = sign * (tm1.minute - tm2.minute);
+    let mut hours = sign * (tm1.hour - tm2.hour);
+    let mut days = sign * (tm1.day - tm2.day);
+    let mut months = sign * (tm1.month - tm2.month);
+    let mut years = sign * (tm1.year - tm2.year);
+
+    // Borrow from the larger fields, a month being as long as the month of the later date.
+    let later = if first < second { tm1 } else { tm2 };
+    while micros < 0 {
+        micros += USECS_PER_SEC;
+        seconds -= 1;
+    }
+    while seconds < 0 {
+        seconds += 60;
+        minutes -= 1;
+    }
+    while minutes < 0 {
+        minutes += 60;
+        hours -= 1;
+    }
+    while hours < 0 {
+        hours += 24;
+        days -= 1;
+    }
+    while days < 0 {
+        days += days_in_month(later.year, later.month as usize);
+        months -= 1;
+    }
+    while months < 0 {
+        months += 12;
+        years -= 1;
+    }
+
+    IntervalFields {
+        year: sign * years,
+        month: sign * months,
+        day: sign * days,
+        hour: sign * hours,
+        minute: sign * minutes,
+        second: sign * seconds,
+        micros: sign * micros,
+    }
+    .to_interval()
+}
+
+/// Moves whole 30 day periods of an interval to its months, as `justify_days` does.
+pub(crate) fn justify_days(interval: Interval) -> Result<Interval> {
+    let Interval {
+        mut months,
+        mut days,
+        micros,
+    } = interval;
+    months = months
+        .checked_add(days / DAYS_PER_MONTH)
+        .ok_or_else(interval_out_of_range)?;
+    days %= DAYS_PER_MONTH;
+    if months > 0 && days < 0 {
+        days += DAYS_PER_MONTH;
+        months -= 1;
+    } else if months < 0 && days > 0 {
+        days -= DAYS_PER_MONTH;
+        months += 1;
+    }
+    Ok(Interval {
+        months,
+        days,
+        micros,
+    })
+}
+
+/// Moves whole 24 hour periods of an interval to its days, as `justify_hours` does.
+pub(crate) fn justify_hours(interval: Interval) -> Result<Interval> {
+    let Interval {
+        months,
+        mut days,
+        mut micros,
+    } = interval;
+    days = i32::try_from(micros / USECS_PER_DAY)
+        .ok()
+        .and_then(|whole_days| days.checked_add(whole_days))
+        .ok_or_else(interval_out_of_range)?;
+    micros %= USECS_PER_DAY;
+    if days > 0 && micros < 0 {
+        micros += USECS_PER_DAY;
+        days -= 1;
+    } else if days < 0 && micros > 0 {
+        micros -= USECS_PER_DAY;
+        days += 1;
+    }
+    Ok(Interval {
+        months,
+        days,
+        micros,
+    })
+}
+
+/// Moves whole 24 hour periods of an interval to its days and whole 30 day periods to its
+/// months, and gives the fields the same sign, as `justify_interval` does.
+pub(crate) fn justify_interval(interval: Interval) -> Result<Interval> {
+    let Interval {
+        mut months,
+        mut days,
+        mut micros,
+    } = interval;
+    // Moving the days first keeps the days from overflowing when the hours are added to them.
+    if (days > 0 && micros > 0) || (days < 0 && micros < 0) {
+        months = months
+            .checked_add(days / DAYS_PER_MONTH)
+            .ok_or_else(interval_out_of_range)?;
+        days %= DAYS_PER_MONTH;
+    }
+    days += (micros / USECS_PER_DAY) as i32;
+    micros %= USECS_PER_DAY;
+    months = months
+        .checked_add(days / DAYS_PER_MONTH)
+        .ok_or_else(interval_out_of_range)?;
+    days %= DAYS_PER_MONTH;
+
+    if months > 0 && (days < 0 || (days == 0 && micros < 0)) {
+        days += DAYS_PER_MONTH;
+        months -= 1;
+    } else if months < 0 && (days > 0 || (days == 0 && micros > 0)) {
+        days -= DAYS_PER_MONTH;
+        months += 1;
+    }
+    if days > 0 && micros < 0 {
+        micros += USECS_PER_DAY;
+        days -= 1;
+    } else if days < 0 && micros > 0 {
+        micros -= USECS_PER_DAY;
+        days += 1;
+    }
+    Ok(Interval {
+        months,
+        days,
+        micros,
+    })
+}
+
+/// Checks a date as Postgres' `ValidateDate` does, reading negative years as years BC, and
+/// returns its astronomical year and its Julian day.
+fn date_julian(year: i32, month: i32, day: i32) -> Result<(i64, i64)> {
+    let (year, month, day) = (i64::from(year), i64::from(month), i64::from(day));
+    let out_of_range = |year| anyhow!("date field value out of range: {year}-{month:02}-{day:02}");
+    if year == 0 {
+        return Err(out_of_range(year));
+    }
+    let year = if year < 0 { year + 1 } else { year };
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || day > days_in_month(year, month as usize)
+    {
+        return Err(out_of_range(year));
+    }
+    if !is_valid_julian(year, month) {
+        bail!("date out of range: {year}-{month:02}-{day:02}");
+    }
+    Ok((year, date_to_julian(year, month, day)))
+}
+
+/// Returns the days since 1970-01-01 of a date, as `make_date` does. Negative years are years BC.
+pub(crate) fn make_date(year: i32, month: i32, day: i32) -> Result<i32> {
+    let (astronomical_year, julian) = date_julian(year, month, day)?;
+    if julian >= JULIAN_END {
+        bail!("date out of range: {astronomical_year}-{month:02}-{day:02}");
+    }
+    Ok((julian - UNIX_EPOCH_JULIAN) as i32)
+}
+
+/// Formats seconds as Postgres' errors print them, with `%02g`.
+fn seconds_text(seconds: f64) -> String {
+    format!("{:0>2}", c_general(seconds))
+}
+
+/// Returns the microseconds since midnight of a time of day, as `make_time` does. The time may
+/// be 24:00:00, and the seconds 60.
+pub(crate) fn make_time(hour: i32, minute: i32, second: f64) -> Result<i64> {
+    let out_of_range = || {
+        anyhow!(
+            "time field value out of range: {hour}:{minute:02}:{}",
+            seconds_text(second)
+        )
+    };
+    if !(0..=24).contains(&hour) || !(0..60).contains(&minute) || second.is_nan() {
+        return Err(out_of_range());
+    }
+    let micros = (second * USECS_PER_SEC as f64).round_ties_even();
+    if !(0.0..=(60 * USECS_PER_SEC) as f64).contains(&micros) {
+        return Err(out_of_range());
+    }
+    let time = (i64::from(hour) * 60 + i64::from(minute)) * USECS_PER_MINUTE + micros as i64;
+    if time > USECS_PER_DAY {
+        return Err(out_of_range());
+    }
+    Ok(time)
+}
+
+/// Returns a local time in microseconds since 1970-01-01, as `make_timestamp` does. Negative
+/// years are years BC.
+pub(crate) fn make_timestamp(
+    year: i32,
+    month: i32,
+    day: i32,
+    hour: i32,
+    minute: i32,
+    second: f64,
+) -> Result<i64> {
+    let (astronomical_year, julian) = date_julian(year, month, day)?;
+    let time = make_time(hour, minute, second)?;
+    let timestamp =
+        i128::from(julian - UNIX_EPOCH_JULIAN) * i128::from(USECS_PER_DAY) + i128::from(time);
+    i64::try_from(timestamp)
+        .ok()
+        .filter(|timestamp| *timestamp >= MIN_TIMESTAMP)
+        .ok_or_else(|| {
+            anyhow!(
+                "timestamp out of range: {astronomical_year}-{month:02}-{day:02} {hour}:{minute:02}:{}",
+                seconds_text(second)
+            )
+        })
+}
+
+/// Returns an interval of the given parts, as `make_interval` does.
+pub(crate) fn make_interval(
+    years: i32,
+    months: i32,
+    weeks: i32,
+    days: i32,
+    hours: i32,
+    minutes: i32,
+    seconds: f64,
+) -> Result<Interval> {
+    let micros = (seconds * USECS_PER_SEC as f64).round_ties_even();
+    // i64::MAX is not a double, so the upper bound is excluded.
+    if !(i64::MIN as f64..i64::MAX as f64).contains(&micros) {
+        return Err(interval_out_of_range());
+    }
+    let months = years
+        .checked_mul(12)
+        .and_then(|years| years.checked_add(months));
+    let days = weeks
+        .checked_mul(7)
+        .and_then(|weeks| weeks.checked_add(days));
+    let micros = i64::from(hours)
+        .checked_mul(USECS_PER_HOUR)
+        .and_then(|hours| hours.checked_add(i64::from(minutes) * USECS_PER_MINUTE))
+        .and_then(|time| time.checked_add(micros as i64));
+    match (months, days, micros) {
+        (Some(months), Some(days), Some(micros)) => Ok(Interval {
+            months,
+            days,
+            micros,
+        }),
+        _ => Err(interval_out_of_range()),
+    }
+}
+
+/// Returns the start of the bin of `stride` that a timestamp is in, the bins starting at
+/// `origin`, as `date_bin` does.
+pub(crate) fn date_bin(stride: Interval, timestamp: i64, origin: i64) -> Result<i64> {
+    if stride.months != 0 {
+        bail!("timestamps cannot be binned into intervals containing months or years");
+    }
+    let stride = i64::from(stride.days)
+        .checked_mul(USECS_PER_DAY)
+        .and_then(|days| days.checked_add(stride.micros))
+        .ok_or_else(interval_out_of_range)?;
+    if stride <= 0 {
+        bail!("stride must be greater than zero");
+    }
+    let difference = timestamp
+        .checked_sub(origin)
+        .ok_or_else(interval_out_of_range)?;
+    let remainder = difference % stride;
+    let start = origin + (difference - remainder);
+    if remainder < 0 {
+        // Bins before the origin start before the timestamp too.
+        return start
+            .checked_sub(stride)
+            .filter(|start| *start >= MIN_TIMESTAMP)
+            .ok_or_else(timestamp_out_of_range);
+    }
+    Ok(start)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn timestamp(text: &str) -> i64 {
+        NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S%.f")
+            .unwrap()
+            .and_utc()
+            .timestamp_micros()
+    }
+
+    fn text(timestamp: i64) -> String {
+        naive(timestamp).format("%Y-%m-%d %H:%M:%S%.f").to_string()
+    }
+
+    fn interval(months: i32, days: i32, micros: i64) -> Interval {
+        Interval {
+            months,
+            days,
+            micros,
+        }
+    }
+
+    #[test]
+    fn test_zones() {
+        assert_eq!(Zone::from_name("EST").unwrap(), Zone::Fixed(-18000));
+        assert_eq!(
+            Zone::from_name("europe/paris").unwrap(),
+            Zone::Named(Tz::Europe__Paris)
+        );
+        assert_eq!(Zone::from_name("UTC+5").unwrap(), Zone::Fixed(-18000));
+        assert_eq!(Zone::from_name("+05:30").unwrap(), Zone::Fixed(-19800));
+        assert_eq!(Zone::from_name("5").unwrap(), Zone::Fixed(-18000));
+        assert_eq!(
+            Zone::from_name("foo").unwrap_err().to_string(),
+            "time zone \"foo\" not recognized"
+        );
+
+        assert_eq!(Zone::from_input("+2").unwrap(), Zone::Fixed(7200));
+        assert_eq!(Zone::from_input("-0330").unwrap(), Zone::Fixed(-12600));
+        assert_eq!(Zone::from_input("UTC+5").unwrap(), Zone::Fixed(-18000));
+        assert_eq!(
+            Zone::from_input("+16").unwrap_err().to_string(),
+            "numeric time zone \"+16\" out of range"
+        );
+        assert_eq!(
+            Zone::from_input("5").unwrap_err().to_string(),
+            "invalid input syntax for type numeric time zone: \"5\": Numeric time zones must have \"-\" or \"+\" as first character."
+        );
+    }
+
+    #[test]
+    fn test_local_times_across_clock_changes() {
+        let zone = Zone::from_name("America/New_York").unwrap();
+        // 02:30 is skipped and 01:30 repeated; both are read as the later time.
+        let skipped = zone.to_utc(timestamp("2024-03-10 02:30:00")).unwrap();
+        assert_eq!(text(skipped), "2024-03-10 07:30:00");
+        let repeated = zone.to_utc(timestamp("2024-11-03 01:30:00")).unwrap();
+        assert_eq!(text(repeated), "2024-11-03 06:30:00");
+    }
+
+    #[test]
+    fn test_timestamp_part() {
+        let ts = timestamp("2001-02-16 20:38:40.123456");
+        let part = |unit| timestamp_part(unit, ts, None).unwrap();
+        assert_eq!(part("epoch"), 982355920.123456);
+        assert_eq!(part("julian"), 2451957.860186614);
+        assert_eq!(part("second"), 40.123456);
+        assert_eq!(part("MICROSECONDS"), 40123456.0);
+        assert_eq!(part("microsecondsxyz"), 40123456.0);
+        assert_eq!(part("century"), 21.0);
+        assert_eq!(part("doy"), 47.0);
+        assert_eq!(part("isodow"), 5.0);
+
+        let bc = timestamp("-0043-03-15 12:00:00");
+        let part = |unit| timestamp_part(unit, bc, None).unwrap();
+        assert_eq!(part("year"), -44.0);
+        assert_eq!(part("decade"), -5.0);
+        assert_eq!(part("week"), 11.0);
+        assert_eq!(part("julian"), 1705428.5);
+
+        let sunday = timestamp("2021-01-03 00:00:00");
+        assert_eq!(timestamp_part("isoyear", sunday, None).unwrap(), 2020.0);
+        assert_eq!(timestamp_part("week", sunday, None).unwrap(), 53.0);
+        assert_eq!(timestamp_part("isodow", sunday, None).unwrap(), 7.0);
+
+        let zone = Zone::from_name("America/New_York").unwrap();
+        let part = |unit| timestamp_part(unit, ts, Some(&zone)).unwrap();
+        assert_eq!(part("hour"), 15.0);
+        assert_eq!(part("timezone"), -18000.0);
+        assert_eq!(part("timezone_hour"), -5.0);
+        assert_eq!(part("julian"), 2451957.6518532806);
+
+        let error = |unit| timestamp_part(unit, ts, None).unwrap_err().to_string();
+        assert_eq!(
+            error("timezone_hour"),
+            "unit \"timezone_hour\" not supported for type timestamp without time zone"
+        );
+        assert_eq!(
+            error("Nanosecond"),
+            "unit \"nanosecond\" not recognized for type timestamp without time zone"
+        );
+    }
+
+    #[test]
+    fn test_interval_and_time_part() {
+        let positive = interval(27, 4, 18367891000);
+        assert_eq!(interval_part("epoch", positive).unwrap(), 71255167.891);
+        assert_eq!(interval_part("second", positive).unwrap(), 7.891);
+        assert_eq!(interval_part("quarter", positive).unwrap(), 2.0);
+        let negative = interval(-17, -4, -18367891000);
+        assert_eq!(interval_part("quarter", negative).unwrap(), 0.0);
+        assert_eq!(interval_part("milliseconds", negative).unwrap(), -7891.0);
+        assert_eq!(
+            interval_part("epoch", interval(300, 0, 0)).unwrap(),
+            788940000.0
+        );
+        assert_eq!(
+            interval_part("dow", positive).unwrap_err().to_string(),
+            "unit \"dow\" not supported for type interval"
+        );
+
+        let time = 47655500000;
+        assert_eq!(time_part("second", time).unwrap(), 15.5);
+        assert_eq!(time_part("epoch", time).unwrap(), 47655.5);
+        assert_eq!(
+            time_part("day", time).unwrap_err().to_string(),
+            "unit \"day\" not supported for type time without time zone"
+        );
+    }
+
+    #[test]
+    fn test_trunc_timestamp() {
+        let trunc = |unit, value| text(trunc_timestamp(unit, timestamp(value), None).unwrap());
+        assert_eq!(trunc("hours", "2001-02-16 20:38:40"), "2001-02-16 20:00:00");
+        assert_eq!(trunc("week", "2001-01-01 12:00:00"), "2001-01-01 00:00:00");
+        assert_eq!(trunc("week", "2021-01-03 12:00:00"), "2020-12-28 00:00:00");
+        assert_eq!(
+            trunc("quarter", "2001-08-16 20:38:40"),
+            "2001-07-01 00:00:00"
+        );
+        assert_eq!(
+            trunc("century", "2000-12-31 00:00:00"),
+            "1901-01-01 00:00:00"
+        );
+        assert_eq!(
+            trunc("millennium", "0000-06-01 00:00:00"),
+            "-0999-01-01 00:00:00"
+        );
+        assert_eq!(
+            trunc("decade", "-0004-06-01 00:00:00"),
+            "-0010-01-01 00:00:00"
+        );
+        assert_eq!(
+            trunc("milliseconds", "2001-02-16 20:38:40.123456"),
+            "2001-02-16 20:38:40.123"
+        );
+
+        let trunc_in = |unit, value, zone| {
+            let zone = Zone::from_name(zone).unwrap();
+            text(trunc_timestamp(unit, timestamp(value), Some(&zone)).unwrap())
+        };
+        assert_eq!(
+            trunc_in("day", "2001-02-16 20:38:40", "+05"),
+            "2001-02-16 05:00:00"
+        );
+        assert_eq!(
+            trunc_in("day", "2001-02-16 20:38:40", "Europe/Paris"),
+            "2001-02-15 23:00:00"
+        );
+        assert_eq!(
+            trunc_in("day", "2024-03-10 12:00:00", "America/New_York"),
+            "2024-03-10 05:00:00"
+        );
+        assert_eq!(
+            trunc_in("hour", "2024-11-03 06:30:00", "America/New_York"),
+            "2024-11-03 06:00:00"
+        );
+
+        let error = |unit| trunc_timestamp(unit, 0, None).unwrap_err().to_string();
+        assert_eq!(
+            error("dow"),
+            "unit \"dow\" not recognized for type timestamp without time zone"
+        );
+        assert_eq!(
+            error("timezone"),
+            "unit \"timezone\" not supported for type timestamp without time zone"
+        );
+    }
+
+    #[test]
+    fn test_trunc_interval() {
+        let value = interval(27, 4, 18367890000);
+        assert_eq!(
+            trunc_interval("hour", value).unwrap(),
+            interval(27, 4, 18000000000)
+        );
+        assert_eq!(
+            trunc_interval("quarter", interval(29, 0, 0)).unwrap(),
+            interval(27, 0, 0)
+        );
+        assert_eq!(
+            trunc_interval("millisecond", interval(0, 0, -1234500)).unwrap(),
+            interval(0, 0, -1234000)
+        );
+        assert_eq!(
+            trunc_interval("week", value).unwrap_err().to_string(),
+            "unit \"week\" not supported for type interval: Months usually have fractional weeks."
+        );
+    }
+
+    #[test]
+    fn test_age() {
+        let age = |first, second| age(timestamp(first), timestamp(second)).unwrap();
+        assert_eq!(
+            age("2001-04-10 00:00:00", "1957-06-13 00:00:00"),
+            interval(525, 27, 0)
+        );
+        assert_eq!(
+            age("1957-06-13 00:00:00", "2001-04-10 00:00:00"),
+            interval(-525, -27, 0)
+        );
+        assert_eq!(
+            age("2001-03-01 00:00:00", "2001-01-31 12:00:00"),
+            interval(1, 0, 12 * USECS_PER_HOUR)
+        );
+        assert_eq!(
+            age("2000-03-01 00:00:00", "2000-02-28 23:59:59.5"),
+            interval(0, 1, 500000)
+        );
+    }
+
+    #[test]
+    fn test_justify() {
+        assert_eq!(justify_days(interval(0, 35, 0)).unwrap(), interval(1, 5, 0));
+        assert_eq!(
+            justify_days(interval(1, -35, 0)).unwrap(),
+            interval(0, -5, 0)
+        );
+        assert_eq!(
+            justify_hours(interval(0, 0, 27 * USECS_PER_HOUR)).unwrap(),
+            interval(0, 1, 3 * USECS_PER_HOUR)
+        );
+        assert_eq!(
+            justify_hours(interval(0, 1, -27 * USECS_PER_HOUR)).unwrap(),
+            interval(0, 0, -3 * USECS_PER_HOUR)
+        );
+        assert_eq!(
+            justify_interval(interval(1, 0, -USECS_PER_HOUR)).unwrap(),
+            interval(0, 29, 23 * USECS_PER_HOUR)
+        );
+        assert_eq!(
+            justify_interval(interval(1, -31, -USECS_PER_HOUR)).unwrap(),
+            interval(0, -1, -USECS_PER_HOUR)
+        );
+        assert_eq!(
+            justify_interval(interval(i32::MAX, 30, 0))
+                .unwrap_err()
+                .to_string(),
+            "interval out of range"
+        );
+    }
+
+    #[test]
+    fn test_make() {
+        let date = |year, month, day| {
+            let days = make_date(year, month, day)?;
+            let date = NaiveDate::from_num_days_from_ce_opt(days + 719163).unwrap();
+            Ok::<_, anyhow::Error>(date.format("%Y-%m-%d").to_string())
+        };
+        assert_eq!(date(2013, 7, 15).unwrap(), "2013-07-15");
+        assert_eq!(date(-44, 3, 15).unwrap(), "-0043-03-15");
+        let error = |result: Result<String>| result.unwrap_err().to_string();
+        assert_eq!(
+            error(date(0, 7, 15)),
+            "date field value out of range: 0-07-15"
+        );
+        assert_eq!(
+            error(date(-44, 2, 30)),
+            "date field value out of range: -43-02-30"
+        );
+        assert_eq!(error(date(-4714, 1, 1)), "date out of range: -4713-01-01");
+
+        assert_eq!(make_time(8, 15, 23.5).unwrap(), 29723500000);
+        assert_eq!(make_time(24, 0, 0.0).unwrap(), USECS_PER_DAY);
+        assert_eq!(
+            make_time(8, 15, 60.5).unwrap_err().to_string(),
+            "time field value out of range: 8:15:60.5"
+        );
+        assert_eq!(
+            make_time(25, 0, 0.0).unwrap_err().to_string(),
+            "time field value out of range: 25:00:00"
+        );
+
+        let local = make_timestamp(2013, 7, 15, 8, 15, 23.5).unwrap();
+        assert_eq!(text(local), "2013-07-15 08:15:23.500");
+        let zone = Zone::from_input("America/New_York").unwrap();
+        assert_eq!(text(zone.to_utc(local).unwrap()), "2013-07-15 12:15:23.500");
+        assert_eq!(
+            make_timestamp(294277, 7, 15, 8, 15, 6.0)
+                .unwrap_err()
+                .to_string(),
+            "timestamp out of range: 294277-07-15 8:15:06"
+        );
+
+        assert_eq!(
+            make_interval(1, 2, 3, 4, 5, 6, 7.5).unwrap(),
+            interval(14, 25, 18367500000)
+        );
+        assert_eq!(
+            make_interval(0, 0, 0, 0, 0, 0, f64::NAN)
+                .unwrap_err()
+                .to_string(),
+            "interval out of range"
+        );
+    }
+
+    #[test]
+    fn test_date_bin() {
+        let bin =
+            |stride, value, origin| date_bin(stride, timestamp(value), timestamp(origin)).map(text);
+        let minutes = interval(0, 0, 15 * USECS_PER_MINUTE);
+        assert_eq!(
+            bin(minutes, "2020-02-11 15:44:17", "2001-01-01 00:00:00").unwrap(),
+            "2020-02-11 15:30:00"
+        );
+        assert_eq!(
+            bin(minutes, "2020-02-11 15:44:17", "2030-01-01 00:02:30").unwrap(),
+            "2020-02-11 15:32:30"
+        );
+        assert_eq!(
+            bin(
+                interval(0, 1, 2 * USECS_PER_HOUR),
+                "1999-02-11 15:44:17",
+                "2001-01-01 00:00:00"
+            )
+            .unwrap(),
+            "1999-02-10 22:00:00"
+        );
+        assert_eq!(
+            bin(
+                interval(1, 0, 0),
+                "2020-02-11 15:44:17",
+                "2001-01-01 00:00:00"
+            )
+            .unwrap_err()
+            .to_string(),
+            "timestamps cannot be binned into intervals containing months or years"
+        );
+        assert_eq!(
+            bin(
+                interval(0, 0, -USECS_PER_HOUR),
+                "2020-02-11 15:44:17",
+                "2001-01-01 00:00:00"
+            )
+            .unwrap_err()
+            .to_string(),
+            "stride must be greater than zero"
+        );
+    }
+}
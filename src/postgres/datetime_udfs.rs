@@ -0,0 +1,1706 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Date32Array, Float64Array, IntervalMonthDayNanoArray,
+    StringArray, Time64MicrosecondArray, TimestampMicrosecondArray,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType::{
+    Boolean, Date32, Date64, Float64, Int64, Null, Time32, Time64, Timestamp, Utf8,
+};
+use datafusion::arrow::datatypes::{
+    DataType, Float64Type, Int64Type, IntervalMonthDayNanoType, IntervalUnit,
+    Time64MicrosecondType, TimeUnit, TimestampMicrosecondType,
+};
+use datafusion::common::{plan_err, DataFusionError};
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::{Any, Exact};
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{string_values, STRING_TYPES};
+use crate::postgres::datetime::{self, Interval, Zone};
+use crate::postgres::interval::{format_interval, parse_interval, IntervalStyle};
+use crate::postgres::string_udfs::execution_error;
+
+const DATETIME_LINK: &str = "https://www.postgresql.org/docs/16/functions-datetime.html";
+const INTERVAL_LINK: &str =
+    "https://www.postgresql.org/docs/16/datatype-datetime.html#DATATYPE-INTERVAL-INPUT";
+
+/// The type of the intervals the functions return, which keeps months, days and nanoseconds
+/// apart as Postgres keeps months, days and microseconds.
+const INTERVAL: DataType = DataType::Interval(IntervalUnit::MonthDayNano);
+
+/// The microseconds in a day.
+const USECS_PER_DAY: i64 = 86_400_000_000;
+
+/// Subtracts timestamps field by field, so that the interval is in years, months and days rather
+/// than in days only. With one argument, the timestamp is subtracted from today at midnight UTC.
+/// Timestamps with a time zone are subtracted in their local time.
+#[derive(Debug)]
+pub struct Age {
+    signature: Signature,
+}
+
+impl Age {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(1), Any(2)], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Age {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "age"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if let Some(arg_type) = arg_types.iter().find(|arg_type| !is_timestamp(arg_type)) {
+            return plan_err!("age() cannot subtract values of type {arg_type}");
+        }
+        Ok(INTERVAL)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let firsts = local_times(&args[0])?;
+        let seconds = match args.get(1) {
+            Some(seconds) => local_times(seconds)?,
+            None => {
+                let today = Utc::now().timestamp_micros().div_euclid(USECS_PER_DAY) * USECS_PER_DAY;
+                vec![Some(today); firsts.len()]
+            }
+        };
+        // With one argument, the timestamp is the second one.
+        let (firsts, seconds) = match args.len() {
+            1 => (seconds, firsts),
+            _ => (firsts, seconds),
+        };
+
+        let intervals = firsts
+            .into_iter()
+            .zip(seconds)
+            .map(|(first, second)| match (first, second) {
+                (Some(first), Some(second)) => datetime::age(first, second)
+                    .map_err(execution_error)
+                    .and_then(interval_value)
+                    .map(Some),
+                _ => Ok(None),
+            })
+            .collect::<Result<IntervalMonthDayNanoArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(intervals) as ArrayRef))
+    }
+}
+
+impl Documented for Age {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "age(timestamp, timestamp) → interval",
+            description: "Subtract arguments, producing a \"symbolic\" result that uses years and months, rather than just days; with one argument, subtract it from current_date (at midnight)",
+            examples: &[
+                "age(timestamp '2001-04-10', timestamp '1957-06-13') → 43 years 9 mons 27 days",
+                "age(timestamp '1957-06-13') → 62 years 6 mons 10 days",
+            ],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Returns the start of the bin of a stride that a timestamp is in, the bins starting at an
+/// origin, which is 1970-01-01 00:00:00 by default.
+#[derive(Debug)]
+pub struct DateBin {
+    signature: Signature,
+}
+
+impl DateBin {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for DateBin {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "date_bin"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_interval(&arg_types[0]) {
+            return plan_err!(
+                "the stride of date_bin() must be an interval, not {}",
+                arg_types[0]
+            );
+        }
+        if let Some(arg_type) = arg_types[1..]
+            .iter()
+            .find(|arg_type| !is_timestamp(arg_type))
+        {
+            return plan_err!("date_bin() cannot bin values of type {arg_type}");
+        }
+        Ok(timestamp_type(&arg_types[1]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let strides = intervals(&args[0])?;
+        let (values, _) = timestamps(&args[1])?;
+        let origins = match args.get(2) {
+            Some(origins) => timestamps(origins)?.0,
+            None => vec![Some(0); values.len()],
+        };
+
+        let bins = strides
+            .into_iter()
+            .zip(values)
+            .zip(origins)
+            .map(
+                |((stride, timestamp), origin)| match (stride, timestamp, origin) {
+                    (Some(stride), Some(timestamp), Some(origin)) => {
+                        datetime::date_bin(stride, timestamp, origin)
+                            .map(Some)
+                            .map_err(execution_error)
+                    }
+                    _ => Ok(None),
+                },
+            )
+            .collect::<Result<TimestampMicrosecondArray>>()?;
+
+        timestamp_result(bins, &timestamp_type(args[1].data_type()))
+    }
+}
+
+impl Documented for DateBin {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "date_bin(interval, timestamp, timestamp) → timestamp",
+            description: "Bin input into specified interval aligned with specified origin",
+            examples: &["date_bin('15 minutes', timestamp '2001-02-16 20:38:40', timestamp '2001-02-16 20:05:00') → 2001-02-16 20:35:00"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Returns a field of a date, a timestamp, a time or an interval, as `EXTRACT` does. Besides the
+/// fields DataFusion reads, it reads `isodow`, `isoyear`, `julian` and the `timezone` fields,
+/// and timestamps with a time zone are read in their local time.
+#[derive(Debug)]
+pub struct DatePart {
+    signature: Signature,
+}
+
+impl DatePart {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for DatePart {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "date_part"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_text(&arg_types[0]) {
+            return plan_err!(
+                "the field of date_part() must be a string, not {}",
+                arg_types[0]
+            );
+        }
+        if !is_timestamp(&arg_types[1])
+            && !is_interval(&arg_types[1])
+            && !matches!(arg_types[1], Time32(_) | Time64(_))
+        {
+            return plan_err!("date_part() cannot read fields of type {}", arg_types[1]);
+        }
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let units = cast(&args[0], &Utf8)?;
+        let units = string_values(&units)?;
+
+        let parts = match args[1].data_type() {
+            DataType::Interval(_) => units
+                .into_iter()
+                .zip(intervals(&args[1])?)
+                .map(|(unit, interval)| match (unit, interval) {
+                    (Some(unit), Some(interval)) => datetime::interval_part(unit, interval)
+                        .map(Some)
+                        .map_err(execution_error),
+                    _ => Ok(None),
+                })
+                .collect::<Result<Float64Array>>()?,
+            Time32(_) | Time64(_) => {
+                let times = cast(&args[1], &Time64(TimeUnit::Microsecond))?;
+                units
+                    .into_iter()
+                    .zip(times.as_primitive::<Time64MicrosecondType>())
+                    .map(|(unit, time)| match (unit, time) {
+                        (Some(unit), Some(time)) => datetime::time_part(unit, time)
+                            .map(Some)
+                            .map_err(execution_error),
+                        _ => Ok(None),
+                    })
+                    .collect::<Result<Float64Array>>()?
+            }
+            _ => {
+                let (timestamps, zone) = timestamps(&args[1])?;
+                units
+                    .into_iter()
+                    .zip(timestamps)
+                    .map(|(unit, timestamp)| match (unit, timestamp) {
+                        (Some(unit), Some(timestamp)) => {
+                            datetime::timestamp_part(unit, timestamp, zone.as_ref())
+                                .map(Some)
+                                .map_err(execution_error)
+                        }
+                        _ => Ok(None),
+                    })
+                    .collect::<Result<Float64Array>>()?
+            }
+        };
+
+        Ok(ColumnarValue::Array(Arc::new(parts) as ArrayRef))
+    }
+}
+
+impl Documented for DatePart {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "date_part(text, timestamp) → double precision",
+            description: "Get timestamp subfield (equivalent to extract)",
+            examples: &[
+                "date_part('hour', timestamp '2001-02-16 20:38:40') → 20",
+                "date_part('isodow', date '2021-01-03') → 7",
+            ],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Truncates a timestamp or an interval to a precision. Timestamps with a time zone are
+/// truncated in their local time, or in the zone of the third argument, which gives a
+/// timestamp with time zone.
+#[derive(Debug)]
+pub struct DateTrunc {
+    signature: Signature,
+}
+
+impl DateTrunc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for DateTrunc {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "date_trunc"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_text(&arg_types[0]) {
+            return plan_err!(
+                "the precision of date_trunc() must be a string, not {}",
+                arg_types[0]
+            );
+        }
+        if arg_types.get(2).is_some_and(|arg_type| !is_text(arg_type)) {
+            return plan_err!(
+                "the time zone of date_trunc() must be a string, not {}",
+                arg_types[2]
+            );
+        }
+        match (&arg_types[1], arg_types.len()) {
+            (DataType::Interval(_), 2) => Ok(INTERVAL),
+            (DataType::Interval(_), _) => {
+                plan_err!("date_trunc() cannot truncate an interval in a time zone")
+            }
+            (arg_type, 2) if is_timestamp(arg_type) => Ok(timestamp_type(arg_type)),
+            (Timestamp(unit, zone), _) => Ok(Timestamp(
+                *unit,
+                Some(zone.clone().unwrap_or_else(|| "+00:00".into())),
+            )),
+            (arg_type, _) if is_timestamp(arg_type) => {
+                Ok(Timestamp(TimeUnit::Microsecond, Some("+00:00".into())))
+            }
+            (arg_type, _) => plan_err!("date_trunc() cannot truncate values of type {arg_type}"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let units = cast(&args[0], &Utf8)?;
+        let units = string_values(&units)?;
+
+        if let DataType::Interval(_) = args[1].data_type() {
+            let intervals = units
+                .into_iter()
+                .zip(intervals(&args[1])?)
+                .map(|(unit, interval)| match (unit, interval) {
+                    (Some(unit), Some(interval)) => datetime::trunc_interval(unit, interval)
+                        .map_err(execution_error)
+                        .and_then(interval_value)
+                        .map(Some),
+                    _ => Ok(None),
+                })
+                .collect::<Result<IntervalMonthDayNanoArray>>()?;
+            return Ok(ColumnarValue::Array(Arc::new(intervals) as ArrayRef));
+        }
+
+        let (timestamps, zone) = timestamps(&args[1])?;
+        let truncated = match args.get(2) {
+            Some(zones) => {
+                // Timestamps without time zone are read as UTC, the session's time zone.
+                let zones = cast(zones, &Utf8)?;
+                units
+                    .into_iter()
+                    .zip(timestamps)
+                    .zip(string_values(&zones)?)
+                    .map(|((unit, timestamp), zone)| match (unit, timestamp, zone) {
+                        (Some(unit), Some(timestamp), Some(zone)) => Zone::from_name(zone)
+                            .and_then(|zone| {
+                                datetime::trunc_timestamp(unit, timestamp, Some(&zone))
+                            })
+                            .map(Some)
+                            .map_err(execution_error),
+                        _ => Ok(None),
+                    })
+                    .collect::<Result<TimestampMicrosecondArray>>()?
+            }
+            None => units
+                .into_iter()
+                .zip(timestamps)
+                .map(|(unit, timestamp)| match (unit, timestamp) {
+                    (Some(unit), Some(timestamp)) => {
+                        datetime::trunc_timestamp(unit, timestamp, zone.as_ref())
+                            .map(Some)
+                            .map_err(execution_error)
+                    }
+                    _ => Ok(None),
+                })
+                .collect::<Result<TimestampMicrosecondArray>>()?,
+        };
+
+        let arg_types: Vec<DataType> = args.iter().map(|arg| arg.data_type().clone()).collect();
+        timestamp_result(truncated, &self.return_type(&arg_types)?)
+    }
+}
+
+impl Documented for DateTrunc {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "date_trunc(text, timestamp [, text]) → timestamp",
+            description: "Truncate to specified precision, in the time zone given by the third argument if there is one",
+            examples: &[
+                "date_trunc('hour', timestamp '2001-02-16 20:38:40') → 2001-02-16 20:00:00",
+                "date_trunc('day', timestamptz '2001-02-16 20:38:40+00', 'Australia/Sydney') → 2001-02-16 13:00:00+00",
+                "date_trunc('hour', interval '2 days 3 hours 40 minutes') → 2 days 03:00:00",
+            ],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Reads intervals from Postgres' interval text, in any of its formats. The session's interval
+/// style decides how a leading minus applies, as it does in Postgres.
+#[derive(Debug)]
+pub struct IntervalIn {
+    signature: Signature,
+    style: IntervalStyle,
+}
+
+impl IntervalIn {
+    pub fn new(style: IntervalStyle) -> Self {
+        Self {
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Stable),
+            style,
+        }
+    }
+}
+
+impl ScalarUDFImpl for IntervalIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "interval_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(INTERVAL)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let intervals = string_values(&args[0])?
+            .into_iter()
+            .map(|text| {
+                text.map(|text| parse_interval(text, self.style).map_err(execution_error))
+                    .transpose()?
+                    .map(interval_value)
+                    .transpose()
+            })
+            .collect::<Result<IntervalMonthDayNanoArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(intervals) as ArrayRef))
+    }
+}
+
+impl Documented for IntervalIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "interval_in(text) → interval",
+            description: "Converts text in any of Postgres' interval formats to an interval",
+            examples: &[
+                "interval_in('1 year 2 mons 3 days 04:05:06') → 1 year 2 mons 3 days 04:05:06",
+                "interval_in('P1Y2M3DT4H5M6S') → 1 year 2 mons 3 days 04:05:06",
+                "interval_in('@ 1 hour ago') → -01:00:00",
+            ],
+            link: Some(INTERVAL_LINK),
+        }
+    }
+}
+
+/// Writes intervals as Postgres' interval text, in the given style or else the session's.
+#[derive(Debug)]
+pub struct IntervalOut {
+    signature: Signature,
+    style: IntervalStyle,
+}
+
+impl IntervalOut {
+    pub fn new(style: IntervalStyle) -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(1), Any(2)], Volatility::Stable),
+            style,
+        }
+    }
+}
+
+impl ScalarUDFImpl for IntervalOut {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "interval_out"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_interval(&arg_types[0]) {
+            return plan_err!(
+                "interval_out() cannot write values of type {}",
+                arg_types[0]
+            );
+        }
+        if arg_types.get(1).is_some_and(|style| !is_text(style)) {
+            return plan_err!(
+                "the style of interval_out() must be a string, not {}",
+                arg_types[1]
+            );
+        }
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let intervals = intervals(&args[0])?;
+        let styles = match args.get(1) {
+            Some(styles) => string_values(&cast(styles, &Utf8)?)?
+                .into_iter()
+                .map(|style| {
+                    style
+                        .map(|style| style.parse().map_err(execution_error))
+                        .transpose()
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![Some(self.style); intervals.len()],
+        };
+        let texts = intervals
+            .into_iter()
+            .zip(styles)
+            .map(|(interval, style)| Some(format_interval(interval?, style?)))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(texts) as ArrayRef))
+    }
+}
+
+impl Documented for IntervalOut {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "interval_out(interval [, text]) → text",
+            description:
+                "Converts an interval to text in the given or the session's interval style",
+            examples: &[
+                "interval_out(interval '1 day 2 hours') → 1 day 02:00:00",
+                "interval_out(interval '1 day 2 hours', 'iso_8601') → P1DT2H",
+            ],
+            link: Some(INTERVAL_LINK),
+        }
+    }
+}
+
+/// Tests whether a date, a timestamp or an interval is finite. DataFusion has no infinite dates,
+/// timestamps or intervals, so every value that is not null is.
+#[derive(Debug)]
+pub struct Isfinite {
+    signature: Signature,
+}
+
+impl Isfinite {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Isfinite {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "isfinite"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_timestamp(&arg_types[0]) && !is_interval(&arg_types[0]) {
+            return plan_err!("isfinite() cannot test values of type {}", arg_types[0]);
+        }
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let nulls = args[0].logical_nulls();
+        let finite = (0..args[0].len())
+            .map(|row| (!nulls.as_ref().is_some_and(|nulls| nulls.is_null(row))).then_some(true))
+            .collect::<BooleanArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(finite) as ArrayRef))
+    }
+}
+
+impl Documented for Isfinite {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "isfinite(timestamp) → boolean",
+            description: "Test for finite date, timestamp or interval (not +/-infinity)",
+            examples: &["isfinite(date '2001-02-16') → true"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Moves whole 30 day periods of an interval to its months.
+#[derive(Debug)]
+pub struct JustifyDays {
+    signature: Signature,
+}
+
+impl JustifyDays {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JustifyDays {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "justify_days"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        justify_return_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        justify(args, datetime::justify_days)
+    }
+}
+
+impl Documented for JustifyDays {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "justify_days(interval) → interval",
+            description: "Adjust interval so 30-day time periods are represented as months",
+            examples: &["justify_days(interval '35 days') → 1 mon 5 days"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Moves whole 24 hour periods of an interval to its days.
+#[derive(Debug)]
+pub struct JustifyHours {
+    signature: Signature,
+}
+
+impl JustifyHours {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JustifyHours {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "justify_hours"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        justify_return_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        justify(args, datetime::justify_hours)
+    }
+}
+
+impl Documented for JustifyHours {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "justify_hours(interval) → interval",
+            description: "Adjust interval so 24-hour time periods are represented as days",
+            examples: &["justify_hours(interval '27 hours') → 1 day 03:00:00"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Moves whole 24 hour periods of an interval to its days and whole 30 day periods to its
+/// months, and gives its months, days and time the same sign.
+#[derive(Debug)]
+pub struct JustifyInterval {
+    signature: Signature,
+}
+
+impl JustifyInterval {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JustifyInterval {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "justify_interval"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        justify_return_type(self.name(), &arg_types[0])
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        justify(args, datetime::justify_interval)
+    }
+}
+
+impl Documented for JustifyInterval {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "justify_interval(interval) → interval",
+            description: "Adjust interval using justify_days and justify_hours, with additional sign adjustments",
+            examples: &["justify_interval(interval '1 mon -1 hour') → 29 days 23:00:00"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Creates a date from year, month and day fields. Negative years are years BC.
+#[derive(Debug)]
+pub struct MakeDate {
+    signature: Signature,
+}
+
+impl MakeDate {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![Int64, Int64, Int64], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MakeDate {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "make_date"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Date32)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let dates = integer_rows(&args)?
+            .unwrap_or_default()
+            .into_iter()
+            .map(|fields| {
+                fields
+                    .map(|fields| datetime::make_date(fields[0], fields[1], fields[2]))
+                    .transpose()
+                    .map_err(execution_error)
+            })
+            .collect::<Result<Date32Array>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(dates) as ArrayRef))
+    }
+}
+
+impl Documented for MakeDate {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "make_date(year int, month int, day int) → date",
+            description: "Create date from year, month and day fields (negative years signify BC)",
+            examples: &["make_date(2013, 7, 15) → 2013-07-15"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Creates an interval from years, months, weeks, days, hours, minutes and seconds, each of
+/// which defaults to zero. The fields are positional, as DataFusion drops the names of named
+/// arguments.
+#[derive(Debug)]
+pub struct MakeInterval {
+    signature: Signature,
+}
+
+impl MakeInterval {
+    pub fn new() -> Self {
+        let mut signatures = vec![Any(0)];
+        signatures.extend((1..=6).map(|fields| Exact(vec![Int64; fields])));
+        signatures.push(Exact(vec![
+            Int64, Int64, Int64, Int64, Int64, Int64, Float64,
+        ]));
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MakeInterval {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "make_interval"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(INTERVAL)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let rows = args.first().map_or(1, |arg| arg.len());
+        // Without arguments, the function is passed a null array as long as the batch.
+        let integers = match args.first().map(|arg| arg.data_type()) {
+            Some(Null) => &args[..0],
+            _ => &args[..args.len().min(6)],
+        };
+        let fields = integer_rows(integers)?;
+        let seconds = match args.get(6) {
+            Some(seconds) => floats(seconds)?,
+            None => vec![Some(0.0); rows],
+        };
+
+        let intervals = (0..rows)
+            .map(|row| {
+                let fields = match &fields {
+                    Some(fields) => fields[row].clone(),
+                    None => Some(vec![]),
+                };
+                let (Some(mut fields), Some(seconds)) = (fields, seconds[row]) else {
+                    return Ok(None);
+                };
+                fields.resize(6, 0);
+                datetime::make_interval(
+                    fields[0], fields[1], fields[2], fields[3], fields[4], fields[5], seconds,
+                )
+                .map_err(execution_error)
+                .and_then(interval_value)
+                .map(Some)
+            })
+            .collect::<Result<IntervalMonthDayNanoArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(intervals) as ArrayRef))
+    }
+}
+
+impl Documented for MakeInterval {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "make_interval([years int [, months int [, weeks int [, days int [, hours int [, mins int [, secs double precision]]]]]]]) → interval",
+            description: "Create interval from years, months, weeks, days, hours, minutes and seconds fields, each of which can default to zero",
+            examples: &["make_interval(0, 0, 0, 10) → 10 days"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Creates a time of day from hour, minute and seconds fields.
+#[derive(Debug)]
+pub struct MakeTime {
+    signature: Signature,
+}
+
+impl MakeTime {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![Int64, Int64, Float64], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MakeTime {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "make_time"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Time64(TimeUnit::Microsecond))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let times = integer_rows(&args[..2])?
+            .unwrap_or_default()
+            .into_iter()
+            .zip(floats(&args[2])?)
+            .map(|(fields, seconds)| match (fields, seconds) {
+                (Some(fields), Some(seconds)) => datetime::make_time(fields[0], fields[1], seconds)
+                    .map(Some)
+                    .map_err(execution_error),
+                _ => Ok(None),
+            })
+            .collect::<Result<Time64MicrosecondArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(times) as ArrayRef))
+    }
+}
+
+impl Documented for MakeTime {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "make_time(hour int, min int, sec double precision) → time",
+            description: "Create time from hour, minute and seconds fields",
+            examples: &["make_time(8, 15, 23.5) → 08:15:23.5"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Creates a timestamp without time zone from year, month, day, hour, minute and seconds
+/// fields. Negative years are years BC.
+#[derive(Debug)]
+pub struct MakeTimestamp {
+    signature: Signature,
+}
+
+impl MakeTimestamp {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(
+                vec![Int64, Int64, Int64, Int64, Int64, Float64],
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MakeTimestamp {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "make_timestamp"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Timestamp(TimeUnit::Microsecond, None))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let timestamps = local_timestamps(&args)?
+            .into_iter()
+            .collect::<TimestampMicrosecondArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(timestamps) as ArrayRef))
+    }
+}
+
+impl Documented for MakeTimestamp {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "make_timestamp(year int, month int, day int, hour int, min int, sec double precision) → timestamp",
+            description: "Create timestamp from year, month, day, hour, minute and seconds fields (negative years signify BC)",
+            examples: &["make_timestamp(2013, 7, 15, 8, 15, 23.5) → 2013-07-15 08:15:23.5"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Creates a timestamp with time zone from year, month, day, hour, minute and seconds fields
+/// in a time zone, which is UTC by default. Negative years are years BC.
+#[derive(Debug)]
+pub struct MakeTimestamptz {
+    signature: Signature,
+}
+
+impl MakeTimestamptz {
+    pub fn new() -> Self {
+        let fields = vec![Int64, Int64, Int64, Int64, Int64, Float64];
+        let mut signatures = vec![Exact(fields.clone())];
+        signatures.extend(
+            STRING_TYPES
+                .iter()
+                .map(|zone| Exact([fields.clone(), vec![zone.clone()]].concat())),
+        );
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MakeTimestamptz {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "make_timestamptz"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Timestamp(TimeUnit::Microsecond, Some("+00:00".into())))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let locals = local_timestamps(&args[..6])?;
+        let zones = match args.get(6) {
+            Some(zones) => string_values(zones)?,
+            None => vec![Some("UTC"); locals.len()],
+        };
+
+        let timestamps = locals
+            .into_iter()
+            .zip(zones)
+            .map(|(local, zone)| match (local, zone) {
+                (Some(local), Some(zone)) => Zone::from_input(zone)
+                    .and_then(|zone| zone.to_utc(local))
+                    .map(Some)
+                    .map_err(execution_error),
+                _ => Ok(None),
+            })
+            .collect::<Result<TimestampMicrosecondArray>>()?;
+
+        Ok(ColumnarValue::Array(
+            Arc::new(timestamps.with_timezone("+00:00")) as ArrayRef,
+        ))
+    }
+}
+
+impl Documented for MakeTimestamptz {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "make_timestamptz(year int, month int, day int, hour int, min int, sec double precision [, timezone text]) → timestamp with time zone",
+            description: "Create timestamp with time zone from year, month, day, hour, minute and seconds fields (negative years signify BC); if timezone is not specified, UTC is used",
+            examples: &["make_timestamptz(2013, 7, 15, 8, 15, 23.5, 'America/New_York') → 2013-07-15 12:15:23.5+00"],
+            link: Some(DATETIME_LINK),
+        }
+    }
+}
+
+/// Whether `data_type` is a type the functions read timestamps from: a timestamp, a date or a
+/// string.
+fn is_timestamp(data_type: &DataType) -> bool {
+    matches!(data_type, Timestamp(_, _) | Date32 | Date64) || is_text(data_type)
+}
+
+/// Whether `data_type` is a type the functions read intervals from: an interval or a string.
+fn is_interval(data_type: &DataType) -> bool {
+    matches!(data_type, DataType::Interval(_)) || is_text(data_type)
+}
+
+/// Whether `data_type` is a string type, or Null.
+fn is_text(data_type: &DataType) -> bool {
+    STRING_TYPES.contains(data_type) || data_type == &Null
+}
+
+/// Returns the type of the timestamps a function returns for timestamps of type `data_type`:
+/// the type itself for a timestamp, which keeps its unit and zone, and a timestamp in
+/// microseconds without time zone for a date or a string.
+fn timestamp_type(data_type: &DataType) -> DataType {
+    match data_type {
+        Timestamp(_, _) => data_type.clone(),
+        _ => Timestamp(TimeUnit::Microsecond, None),
+    }
+}
+
+/// Returns the timestamps of a timestamp, date or string array in microseconds, with the zone of
+/// a timestamp with time zone. Timestamps with a zone are in UTC, the others local times.
+fn timestamps(array: &ArrayRef) -> Result<(Vec<Option<i64>>, Option<Zone>)> {
+    let (data_type, zone) = match array.data_type() {
+        Timestamp(_, Some(zone)) => (
+            Timestamp(TimeUnit::Microsecond, Some(zone.clone())),
+            Some(Zone::from_input(zone).map_err(execution_error)?),
+        ),
+        _ => (Timestamp(TimeUnit::Microsecond, None), None),
+    };
+    let timestamps = cast(array, &data_type)?;
+    let timestamps = timestamps.as_primitive::<TimestampMicrosecondType>();
+    Ok((timestamps.iter().collect(), zone))
+}
+
+/// Returns the local times of a timestamp, date or string array in microseconds.
+fn local_times(array: &ArrayRef) -> Result<Vec<Option<i64>>> {
+    let (timestamps, zone) = timestamps(array)?;
+    let Some(zone) = zone else {
+        return Ok(timestamps);
+    };
+    timestamps
+        .into_iter()
+        .map(|timestamp| {
+            timestamp
+                .map(|timestamp| zone.to_local(timestamp))
+                .transpose()
+                .map_err(execution_error)
+        })
+        .collect()
+}
+
+/// Returns timestamps in microseconds as an array of `data_type`, a timestamp type.
+fn timestamp_result(
+    timestamps: TimestampMicrosecondArray,
+    data_type: &DataType,
+) -> Result<ColumnarValue> {
+    let zone = match data_type {
+        Timestamp(_, zone) => zone.clone(),
+        _ => None,
+    };
+    let timestamps = Arc::new(timestamps.with_timezone_opt(zone)) as ArrayRef;
+    Ok(ColumnarValue::Array(cast(&timestamps, data_type)?))
+}
+
+/// Returns the intervals of an interval or string array, with their nanoseconds truncated to
+/// microseconds.
+pub(super) fn intervals(array: &ArrayRef) -> Result<Vec<Option<Interval>>> {
+    let intervals = cast(array, &INTERVAL)?;
+    Ok(intervals
+        .as_primitive::<IntervalMonthDayNanoType>()
+        .iter()
+        .map(|interval| {
+            interval.map(|interval| {
+                let (months, days, nanos) = IntervalMonthDayNanoType::to_parts(interval);
+                Interval {
+                    months,
+                    days,
+                    micros: nanos / 1000,
+                }
+            })
+        })
+        .collect())
+}
+
+/// Returns the value of an interval in a MonthDayNano interval array.
+pub(super) fn interval_value(interval: Interval) -> Result<i128> {
+    let nanos = interval
+        .micros
+        .checked_mul(1000)
+        .ok_or_else(|| DataFusionError::Execution("interval out of range".to_string()))?;
+    Ok(IntervalMonthDayNanoType::make_value(
+        interval.months,
+        interval.days,
+        nanos,
+    ))
+}
+
+/// Returns the type the `justify_*` functions return for an argument of type `data_type`.
+fn justify_return_type(name: &str, data_type: &DataType) -> Result<DataType> {
+    if !is_interval(data_type) {
+        return plan_err!("{name}() cannot adjust values of type {data_type}");
+    }
+    Ok(INTERVAL)
+}
+
+/// Adjusts the intervals of the argument of a `justify_*` function with `adjust`.
+fn justify(
+    args: &[ColumnarValue],
+    adjust: fn(Interval) -> anyhow::Result<Interval>,
+) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let intervals = intervals(&args[0])?
+        .into_iter()
+        .map(|interval| {
+            interval
+                .map(|interval| adjust(interval).map_err(execution_error))
+                .transpose()?
+                .map(interval_value)
+                .transpose()
+        })
+        .collect::<Result<IntervalMonthDayNanoArray>>()?;
+
+    Ok(ColumnarValue::Array(Arc::new(intervals) as ArrayRef))
+}
+
+/// Returns the values of an Int64 argument, which Postgres reads as integers.
+fn integers(array: &ArrayRef) -> Result<Vec<Option<i32>>> {
+    cast(array, &Int64)?
+        .as_primitive::<Int64Type>()
+        .iter()
+        .map(|value| {
+            value
+                .map(|value| {
+                    i32::try_from(value)
+                        .map_err(|_| DataFusionError::Execution("integer out of range".to_string()))
+                })
+                .transpose()
+        })
+        .collect()
+}
+
+/// Returns the values of a Float64 argument.
+fn floats(array: &ArrayRef) -> Result<Vec<Option<f64>>> {
+    Ok(cast(array, &Float64)?
+        .as_primitive::<Float64Type>()
+        .iter()
+        .collect())
+}
+
+/// Returns the rows of integer arguments, a row being null if any of its values is, or `None`
+/// without arguments.
+fn integer_rows(args: &[ArrayRef]) -> Result<Option<Vec<Option<Vec<i32>>>>> {
+    let Some(rows) = args.first().map(|arg| arg.len()) else {
+        return Ok(None);
+    };
+    let columns = args.iter().map(integers).collect::<Result<Vec<_>>>()?;
+    Ok(Some(
+        (0..rows)
+            .map(|row| columns.iter().map(|column| column[row]).collect())
+            .collect(),
+    ))
+}
+
+/// Returns the local times of year, month, day, hour, minute and seconds arguments, as
+/// `make_timestamp` reads them.
+fn local_timestamps(args: &[ArrayRef]) -> Result<Vec<Option<i64>>> {
+    integer_rows(&args[..5])?
+        .unwrap_or_default()
+        .into_iter()
+        .zip(floats(&args[5])?)
+        .map(|(fields, seconds)| match (fields, seconds) {
+            (Some(fields), Some(seconds)) => datetime::make_timestamp(
+                fields[0], fields[1], fields[2], fields[3], fields[4], seconds,
+            )
+            .map(Some)
+            .map_err(execution_error),
+            _ => Ok(None),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::{register_postgres_udfs, register_postgres_udfs_with_options};
+    use crate::registry::RegisterOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_age() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select age(timestamp '2001-04-10', timestamp '1957-06-13') as col_age, age(date '1957-06-13', date '2001-04-10') as col_negative, age(current_date) as col_today",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------------------------------------------------+------------------------------------------------------------+-------------------------------------------------------+
+| col_age                                                  | col_negative                                               | col_today                                             |
++----------------------------------------------------------+------------------------------------------------------------+-------------------------------------------------------+
+| 0 years 525 mons 27 days 0 hours 0 mins 0.000000000 secs | 0 years -525 mons -27 days 0 hours 0 mins 0.000000000 secs | 0 years 0 mons 0 days 0 hours 0 mins 0.000000000 secs |
++----------------------------------------------------------+------------------------------------------------------------+-------------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_date_bin() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select date_bin(interval '15 minutes', timestamp '2020-02-11 15:44:17', timestamp '2001-01-01') as col_bin, date_bin(interval '15 minutes', timestamp '2020-02-11 15:44:17') as col_default",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------------------+---------------------+
+| col_bin             | col_default         |
++---------------------+---------------------+
+| 2020-02-11T15:30:00 | 2020-02-11T15:30:00 |
++---------------------+---------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select date_bin(interval '1 month', timestamp '2020-02-11 15:44:17')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("timestamps cannot be binned into intervals containing months or years"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_date_part() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select date_part('hour', timestamp '2001-02-16 20:38:40') as col_hour, date_part('isoyear', date '2021-01-03') as col_isoyear, extract(isodow from date '2021-01-03') as col_isodow, date_part('julian', date '2021-01-03') as col_julian, date_part('timezone_hour', arrow_cast('2021-01-03T10:00:00+05:30', 'Timestamp(Microsecond, Some(\"+05:30\"))')) as col_zone",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+-------------+------------+------------+----------+
+| col_hour | col_isoyear | col_isodow | col_julian | col_zone |
++----------+-------------+------------+------------+----------+
+| 20.0     | 2020.0      | 7.0        | 2459218.0  | 5.0      |
++----------+-------------+------------+------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select date_part('isodow', interval '1 day')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("unit \"isodow\" not supported for type interval"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_date_trunc() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select date_trunc('hour', timestamp '2001-02-16 20:38:40') as col_hour, date_trunc('week', date '2021-01-03') as col_week, date_trunc('day', make_timestamptz(2001, 2, 16, 20, 38, 40), 'Australia/Sydney') as col_zone, date_trunc('hour', interval '2 days 3 hours 40 minutes') as col_interval",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------------------+---------------------+----------------------+-------------------------------------------------------+
+| col_hour            | col_week            | col_zone             | col_interval                                          |
++---------------------+---------------------+----------------------+-------------------------------------------------------+
+| 2001-02-16T20:00:00 | 2020-12-28T00:00:00 | 2001-02-16T13:00:00Z | 0 years 0 mons 2 days 3 hours 0 mins 0.000000000 secs |
++---------------------+---------------------+----------------------+-------------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select date_trunc('day', timestamp '2001-02-16 20:38:40', 'Mars/Olympus')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("time zone \"Mars/Olympus\" not recognized"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interval_in_out() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select interval_in('1 year 2 mons 3 days 04:05:06') as col_in, interval_out(interval_in('@ 1 hour ago')) as col_postgres, interval_out(interval_in('P1Y2M3DT4H5M6S'), 'postgres_verbose') as col_verbose, interval_out(interval '1 day 2 hours', 'iso_8601') as col_iso",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------------------------------------------+--------------+----------------------------------------------+---------+
+| col_in                                                 | col_postgres | col_verbose                                  | col_iso |
++--------------------------------------------------------+--------------+----------------------------------------------+---------+
+| 0 years 14 mons 3 days 4 hours 5 mins 6.000000000 secs | -01:00:00    | @ 1 year 2 mons 3 days 4 hours 5 mins 6 secs | P1DT2H  |
++--------------------------------------------------------+--------------+----------------------------------------------+---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select interval_in('1 days 1 day')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid input syntax for type interval: \"1 days 1 day\""));
+
+        let result = ctx
+            .sql("select interval_out(interval '1 day', 'iso')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid value for parameter \"IntervalStyle\": \"iso\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_interval_style() -> Result<()> {
+        let ctx = SessionContext::new();
+        let options = RegisterOptions::default().with_interval_style(IntervalStyle::SqlStandard);
+        register_postgres_udfs_with_options(&ctx, &options)?;
+        let df = ctx
+            .sql(
+                "select interval_out(interval_in('-1 2:03:04')) as col_standard, interval_out(interval_in('-1 2:03:04'), 'postgres') as col_postgres",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------+-------------------+
+| col_standard | col_postgres      |
++--------------+-------------------+
+| -1 2:03:04   | -1 days -02:03:04 |
++--------------+-------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_isfinite() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select isfinite(date '2001-02-16') as col_date, isfinite(timestamp '2001-02-16 21:28:30') as col_timestamp, isfinite(null) as col_null",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+---------------+----------+
+| col_date | col_timestamp | col_null |
++----------+---------------+----------+
+| true     | true          |          |
++----------+---------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_justify() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select justify_days(interval '35 days') as col_days, justify_hours(interval '27 hours') as col_hours, justify_interval(interval '1 month -1 hour') as col_interval",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------------------------------------------------+-------------------------------------------------------+---------------------------------------------------------+
+| col_days                                              | col_hours                                             | col_interval                                            |
++-------------------------------------------------------+-------------------------------------------------------+---------------------------------------------------------+
+| 0 years 1 mons 5 days 0 hours 0 mins 0.000000000 secs | 0 years 0 mons 1 days 3 hours 0 mins 0.000000000 secs | 0 years 0 mons 29 days 23 hours 0 mins 0.000000000 secs |
++-------------------------------------------------------+-------------------------------------------------------+---------------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_make() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select make_date(2013, 7, 15) as col_date, make_time(8, 15, 23.5) as col_time, make_timestamp(2013, 7, 15, 8, 15, 23.5) as col_timestamp, make_timestamptz(2013, 7, 15, 8, 15, 23.5, 'America/New_York') as col_timestamptz",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+--------------+-------------------------+--------------------------+
+| col_date   | col_time     | col_timestamp           | col_timestamptz          |
++------------+--------------+-------------------------+--------------------------+
+| 2013-07-15 | 08:15:23.500 | 2013-07-15T08:15:23.500 | 2013-07-15T12:15:23.500Z |
++------------+--------------+-------------------------+--------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select make_date(2013, 2, 30)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("date field value out of range: 2013-02-30"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_make_interval() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select make_interval(0, 0, 0, 10) as col_days, make_interval(1, 2, 1, 3, 1, 2, 3.0) as col_seconds, make_interval() as col_empty",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------------------------------------------+---------------------------------------------------------+-------------------------------------------------------+
+| col_days                                               | col_seconds                                             | col_empty                                             |
++--------------------------------------------------------+---------------------------------------------------------+-------------------------------------------------------+
+| 0 years 0 mons 10 days 0 hours 0 mins 0.000000000 secs | 0 years 14 mons 10 days 1 hours 2 mins 3.000000000 secs | 0 years 0 mons 0 days 0 hours 0 mins 0.000000000 secs |
++--------------------------------------------------------+---------------------------------------------------------+-------------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,2569 @@
+//! Postgres' data type formatting: `to_char` for numbers and timestamps, and `to_number`,
+//! `to_date` and `to_timestamp`, which read a string as a template describes it.
+//!
+//! A template is split into keywords, such as `YYYY` or `9`, and literal text the way Postgres'
+//! formatting.c splits it, and is then printed or read keyword by keyword with the same rules, so
+//! that the padding, the rounding and the leniency of the parsing are Postgres'. The locale is the
+//! C locale: `D` is `.`, `G` is `,`, `L` is a space and the names of days and months are English.
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, NaiveDateTime, Timelike};
+
+/// How a keyword is written in a template, which is how the names it prints are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Case {
+    Upper,
+    Lower,
+    Capitalized,
+}
+
+impl Case {
+    fn apply(self, name: &str) -> String {
+        match self {
+            Case::Upper => name.to_ascii_uppercase(),
+            Case::Lower => name.to_ascii_lowercase(),
+            Case::Capitalized => name.to_string(),
+        }
+    }
+}
+
+/// A keyword of a template, with the `FM` prefix and `TH` suffix of date templates.
+#[derive(Debug, Clone, Copy)]
+struct Keyword<'a, K> {
+    field: K,
+    name: &'a str,
+    case: Case,
+    fill: bool,
+    ordinal: Option<Case>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Node<'a, K> {
+    Keyword(Keyword<'a, K>),
+    /// Quoted text, or a character that is not part of a keyword.
+    Char(char),
+    Space(char),
+    /// Punctuation in a date template, which matches any punctuation when reading.
+    Separator(char),
+}
+
+/// Splits `template` into keywords of `keywords` and literal text. Keywords are matched longest
+/// first, in the case they are listed in or in lower case, and text in double quotes is literal.
+fn parse_template<'a, K: Copy>(
+    template: &'a str,
+    keywords: &[(&'static str, K)],
+    date: bool,
+) -> Vec<Node<'a, K>> {
+    let mut nodes = vec![];
+    let mut rest = template;
+    while !rest.is_empty() {
+        let mut fill = false;
+        if date {
+            if let Some(prefix) = ["FM", "fm", "TM", "tm"]
+                .into_iter()
+                .find(|prefix| rest.starts_with(prefix))
+            {
+                fill = prefix.eq_ignore_ascii_case("FM");
+                rest = &rest[prefix.len()..];
+            }
+        }
+
+        if let Some((field, name, case)) = find_keyword(rest, keywords) {
+            rest = &rest[name.len()..];
+            let mut ordinal = None;
+            if date {
+                if let Some(suffix) = ["TH", "th", "SP"]
+                    .into_iter()
+                    .find(|suffix| rest.starts_with(suffix))
+                {
+                    ordinal = match suffix {
+                        "TH" => Some(Case::Upper),
+                        "th" => Some(Case::Lower),
+                        _ => None,
+                    };
+                    rest = &rest[suffix.len()..];
+                }
+            }
+            nodes.push(Node::Keyword(Keyword {
+                field,
+                name,
+                case,
+                fill,
+                ordinal,
+            }));
+            continue;
+        }
+
+        let mut chars = rest.chars();
+        let Some(c) = chars.next() else {
+            break;
+        };
+        if c == '"' {
+            loop {
+                match chars.next() {
+                    None | Some('"') => break,
+                    Some('\\') => match chars.clone().next() {
+                        Some(quoted) => {
+                            chars.next();
+                            nodes.push(Node::Char(quoted));
+                        }
+                        None => nodes.push(Node::Char('\\')),
+                    },
+                    Some(c) => nodes.push(Node::Char(c)),
+                }
+            }
+            rest = chars.as_str();
+        } else {
+            // Outside quotes, a backslash only escapes a double quote.
+            let c = if c == '\\' && chars.as_str().starts_with('"') {
+                chars.next();
+                '"'
+            } else {
+                c
+            };
+            rest = chars.as_str();
+            nodes.push(if date && is_separator(c) {
+                Node::Separator(c)
+            } else if is_space(c) {
+                Node::Space(c)
+            } else {
+                Node::Char(c)
+            });
+        }
+    }
+    nodes
+}
+
+fn find_keyword<'a, K: Copy>(
+    rest: &'a str,
+    keywords: &[(&'static str, K)],
+) -> Option<(K, &'a str, Case)> {
+    keywords
+        .iter()
+        .filter_map(|(name, field)| {
+            let text = rest.get(..name.len())?;
+            let case = if text == *name {
+                if name.bytes().any(|b| b.is_ascii_lowercase()) {
+                    Case::Capitalized
+                } else {
+                    Case::Upper
+                }
+            } else if text
+                .bytes()
+                .zip(name.bytes())
+                .all(|(t, n)| t == n.to_ascii_lowercase())
+            {
+                Case::Lower
+            } else {
+                return None;
+            };
+            Some((*field, text, case))
+        })
+        .max_by_key(|(_, text, _)| text.len())
+}
+
+/// Whether `c` is whitespace to C's `isspace`.
+fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r')
+}
+
+/// Whether `c` is printable ASCII punctuation, which separates the fields of a date.
+fn is_separator(c: char) -> bool {
+    c.is_ascii_graphic() && !c.is_ascii_alphanumeric()
+}
+
+/// Returns `st`, `nd`, `rd` or `th` for the number ending `number`.
+fn ordinal_suffix(number: &str, case: Case) -> String {
+    let digits = number.as_bytes();
+    let last = match digits {
+        [.., b'1', _] => b'0',
+        [.., last] => *last,
+        [] => b'0',
+    };
+    let suffix = match last {
+        b'1' => "st",
+        b'2' => "nd",
+        b'3' => "rd",
+        _ => "th",
+    };
+    case.apply(suffix)
+}
+
+// Numbers
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumberField {
+    Comma,
+    Point,
+    Zero,
+    Nine,
+    Blank,
+    Currency,
+    Decimal,
+    Exponent,
+    Fill,
+    Group,
+    Local,
+    Minus,
+    Plus,
+    Bracket,
+    Roman,
+    SignAnywhere,
+    Spell,
+    Sign,
+    Ordinal,
+    Multiply,
+}
+
+const NUMBER_KEYWORDS: &[(&str, NumberField)] = &[
+    (",", NumberField::Comma),
+    (".", NumberField::Point),
+    ("0", NumberField::Zero),
+    ("9", NumberField::Nine),
+    ("B", NumberField::Blank),
+    ("C", NumberField::Currency),
+    ("D", NumberField::Decimal),
+    ("EEEE", NumberField::Exponent),
+    ("FM", NumberField::Fill),
+    ("G", NumberField::Group),
+    ("L", NumberField::Local),
+    ("MI", NumberField::Minus),
+    ("PL", NumberField::Plus),
+    ("PR", NumberField::Bracket),
+    ("RN", NumberField::Roman),
+    ("SG", NumberField::SignAnywhere),
+    ("SP", NumberField::Spell),
+    ("S", NumberField::Sign),
+    ("TH", NumberField::Ordinal),
+    ("V", NumberField::Multiply),
+];
+
+/// Where the `S` of a template puts the sign: before or after the number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum LocaleSign {
+    #[default]
+    None,
+    Pre,
+    Post,
+}
+
+/// What the keywords of a number template ask for: how many digits go before and after the
+/// decimal point, where the zero padding starts, and how the sign is written.
+#[derive(Debug, Clone, Default)]
+struct NumberFormat {
+    pre: usize,
+    post: usize,
+    multi: usize,
+    zero_start: usize,
+    zero_end: usize,
+    lsign: LocaleSign,
+    pre_lsign_num: usize,
+    decimal: bool,
+    zero: bool,
+    blank: bool,
+    fill: bool,
+    bracket: bool,
+    minus: bool,
+    plus: bool,
+    roman: bool,
+    multiply: bool,
+    eeee: bool,
+}
+
+impl NumberFormat {
+    fn new(nodes: &[Node<NumberField>]) -> Result<Self> {
+        let mut format = Self::default();
+        for node in nodes {
+            if let Node::Keyword(keyword) = node {
+                format.add(keyword.field)?;
+            }
+        }
+        Ok(format)
+    }
+
+    fn add(&mut self, field: NumberField) -> Result<()> {
+        if self.eeee && field != NumberField::Exponent {
+            bail!("\"EEEE\" must be the last pattern used");
+        }
+        match field {
+            NumberField::Nine => {
+                if self.bracket {
+                    bail!("\"9\" must be ahead of \"PR\"");
+                }
+                if self.multiply {
+                    self.multi += 1;
+                } else if self.decimal {
+                    self.post += 1;
+                } else {
+                    self.pre += 1;
+                }
+            }
+            NumberField::Zero => {
+                if self.bracket {
+                    bail!("\"0\" must be ahead of \"PR\"");
+                }
+                if !self.zero && !self.decimal {
+                    self.zero = true;
+                    self.zero_start = self.pre + 1;
+                }
+                if self.decimal {
+                    self.post += 1;
+                } else {
+                    self.pre += 1;
+                }
+                self.zero_end = self.pre + self.post;
+            }
+            NumberField::Blank => {
+                if self.pre == 0 && self.post == 0 && !self.zero {
+                    self.blank = true;
+                }
+            }
+            NumberField::Decimal | NumberField::Point => {
+                if self.decimal {
+                    bail!("multiple decimal points");
+                }
+                if self.multiply {
+                    bail!("cannot use \"V\" and decimal point together");
+                }
+                self.decimal = true;
+            }
+            NumberField::Fill => self.fill = true,
+            NumberField::Sign => {
+                if self.lsign != LocaleSign::None {
+                    bail!("cannot use \"S\" twice");
+                }
+                if self.plus || self.minus || self.bracket {
+                    bail!("cannot use \"S\" and \"PL\"/\"MI\"/\"SG\"/\"PR\" together");
+                }
+                if !self.decimal {
+                    self.lsign = LocaleSign::Pre;
+                    self.pre_lsign_num = self.pre;
+                } else {
+                    self.lsign = LocaleSign::Post;
+                }
+            }
+            NumberField::Minus => {
+                if self.lsign != LocaleSign::None {
+                    bail!("cannot use \"S\" and \"MI\" together");
+                }
+                self.minus = true;
+            }
+            NumberField::Plus => {
+                if self.lsign != LocaleSign::None {
+                    bail!("cannot use \"S\" and \"PL\" together");
+                }
+                self.plus = true;
+            }
+            NumberField::SignAnywhere => {
+                if self.lsign != LocaleSign::None {
+                    bail!("cannot use \"S\" and \"SG\" together");
+                }
+                self.minus = true;
+                self.plus = true;
+            }
+            NumberField::Bracket => {
+                if self.lsign != LocaleSign::None || self.plus || self.minus {
+                    bail!("cannot use \"PR\" and \"S\"/\"PL\"/\"MI\"/\"SG\" together");
+                }
+                self.bracket = true;
+            }
+            NumberField::Roman => self.roman = true,
+            NumberField::Multiply => {
+                if self.decimal {
+                    bail!("cannot use \"V\" and decimal point together");
+                }
+                self.multiply = true;
+            }
+            NumberField::Exponent => {
+                if self.eeee {
+                    bail!("cannot use \"EEEE\" twice");
+                }
+                if self.blank
+                    || self.fill
+                    || self.lsign != LocaleSign::None
+                    || self.bracket
+                    || self.minus
+                    || self.plus
+                    || self.roman
+                    || self.multiply
+                {
+                    bail!("\"EEEE\" is incompatible with other formats: \"EEEE\" may only be used together with digit and decimal point patterns.");
+                }
+                self.eeee = true;
+            }
+            NumberField::Comma
+            | NumberField::Currency
+            | NumberField::Group
+            | NumberField::Local
+            | NumberField::Spell
+            | NumberField::Ordinal => {}
+        }
+        Ok(())
+    }
+}
+
+/// A number for [`to_char_number`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Number {
+    Integer(i64),
+    /// A float and the number of decimal digits its type holds: 15 for double precision and 6
+    /// for real.
+    Float(f64, usize),
+    /// A decimal and its scale.
+    Decimal(i128, i8),
+}
+
+/// A decimal number as its digits and the number of them that follow the decimal point, as
+/// Postgres' numeric type rounds and prints it.
+#[derive(Debug, Clone)]
+struct Digits {
+    negative: bool,
+    digits: Vec<u8>,
+    scale: usize,
+}
+
+impl Digits {
+    fn new(value: i128, scale: i8) -> Self {
+        let mut digits = value.unsigned_abs().to_string().into_bytes();
+        let scale = if scale < 0 {
+            digits.resize(digits.len() + scale.unsigned_abs() as usize, b'0');
+            0
+        } else {
+            scale as usize
+        };
+        Self {
+            negative: value < 0,
+            digits,
+            scale,
+        }
+        .normalized()
+    }
+
+    /// Keeps one digit before the decimal point, and no sign on zero.
+    fn normalized(mut self) -> Self {
+        if self.digits.len() <= self.scale {
+            let zeros = self.scale + 1 - self.digits.len();
+            self.digits.splice(0..0, vec![b'0'; zeros]);
+        }
+        let leading = self.digits[..self.digits.len() - self.scale - 1]
+            .iter()
+            .take_while(|d| **d == b'0')
+            .count();
+        self.digits.drain(..leading);
+        if self.digits.iter().all(|d| *d == b'0') {
+            self.negative = false;
+        }
+        self
+    }
+
+    /// Multiplies the number by 10 to the power `places`.
+    fn shift(mut self, places: usize) -> Self {
+        if self.scale >= places {
+            self.scale -= places;
+        } else {
+            let zeros = places - self.scale;
+            self.digits.resize(self.digits.len() + zeros, b'0');
+            self.scale = 0;
+        }
+        self.normalized()
+    }
+
+    /// Rounds the number to `scale` digits after the decimal point, half away from zero.
+    fn round(mut self, scale: usize) -> Self {
+        if self.scale <= scale {
+            self.digits
+                .resize(self.digits.len() + scale - self.scale, b'0');
+        } else {
+            let cut = self.digits.len() - (self.scale - scale);
+            let round_up = self.digits[cut] >= b'5';
+            self.digits.truncate(cut);
+            if round_up {
+                let mut carry = true;
+                for digit in self.digits.iter_mut().rev() {
+                    if *digit == b'9' {
+                        *digit = b'0';
+                    } else {
+                        *digit += 1;
+                        carry = false;
+                        break;
+                    }
+                }
+                if carry {
+                    self.digits.insert(0, b'1');
+                }
+            }
+        }
+        self.scale = scale;
+        self.normalized()
+    }
+
+    /// Prints the number in scientific notation with `scale` digits after the decimal point.
+    fn to_scientific(&self, scale: usize) -> String {
+        let (significand, exponent) = match self.digits.iter().position(|d| *d != b'0') {
+            None => (Digits::new(0, 0), 0),
+            Some(first) => {
+                let significant = self.digits[first..].to_vec();
+                let exponent = significant.len() as i64 - 1 - self.scale as i64;
+                let scale = significant.len() - 1;
+                let significand = Digits {
+                    negative: self.negative,
+                    digits: significant,
+                    scale,
+                };
+                (significand, exponent)
+            }
+        };
+        format!("{}e{exponent:+03}", significand.round(scale))
+    }
+}
+
+impl std::fmt::Display for Digits {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (integer, fraction) = self.digits.split_at(self.digits.len() - self.scale);
+        if self.negative {
+            f.write_str("-")?;
+        }
+        f.write_str(std::str::from_utf8(integer).map_err(|_| std::fmt::Error)?)?;
+        if self.scale > 0 {
+            f.write_str(".")?;
+            f.write_str(std::str::from_utf8(fraction).map_err(|_| std::fmt::Error)?)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints `number` as `template` describes, e.g. `to_char(-125.8, '999D99S')` is `125.80-`.
+pub(crate) fn to_char_number(number: Number, template: &str) -> Result<String> {
+    let nodes = parse_template(template, NUMBER_KEYWORDS, false);
+    let mut format = NumberFormat::new(&nodes)?;
+    let mut out_pre_spaces = 0;
+    let mut sign = '\0';
+
+    let digits = if format.roman {
+        let value = match number {
+            Number::Integer(value) => {
+                i32::try_from(value).map_err(|_| anyhow!("integer out of range"))?
+            }
+            Number::Float(value, _) => value.round_ties_even() as i32,
+            Number::Decimal(value, scale) => Digits::new(value, scale)
+                .round(0)
+                .to_string()
+                .parse()
+                .map_err(|_| anyhow!("integer out of range"))?,
+        };
+        to_roman(value)
+    } else if format.eeee {
+        let digits = match number {
+            Number::Float(value, _) if !value.is_finite() => {
+                let mut digits = vec![b'#'; format.pre + format.post + 6];
+                digits[0] = b' ';
+                digits[format.pre + 1] = b'.';
+                String::from_utf8(digits)?
+            }
+            Number::Float(value, _) => {
+                let digits = format!("{value:+.*e}", format.post);
+                let (significand, exponent) = digits.split_once('e').unwrap_or((&digits, "0"));
+                let exponent: i32 = exponent.parse()?;
+                format!("{significand}e{exponent:+03}")
+            }
+            Number::Integer(value) => Digits::new(value.into(), 0).to_scientific(format.post),
+            Number::Decimal(value, scale) => Digits::new(value, scale).to_scientific(format.post),
+        };
+        match digits.strip_prefix('+') {
+            Some(digits) => format!(" {digits}"),
+            None if !digits.starts_with(['-', ' ']) => format!(" {digits}"),
+            None => digits,
+        }
+    } else {
+        let digits = match number {
+            Number::Integer(value) => {
+                let value = if format.multiply {
+                    format.pre += format.multi;
+                    10_i64
+                        .checked_pow(format.multi as u32)
+                        .and_then(|multi| value.checked_mul(multi))
+                        .ok_or_else(|| anyhow!("bigint out of range"))?
+                } else {
+                    value
+                };
+                if format.post > 0 {
+                    format!("{value}.{}", "0".repeat(format.post))
+                } else {
+                    value.to_string()
+                }
+            }
+            Number::Float(value, precision) => {
+                let value = if format.multiply {
+                    format.pre += format.multi;
+                    value * 10_f64.powi(format.multi as i32)
+                } else {
+                    value
+                };
+                let pre_len = c_fixed(value.abs(), 0).len();
+                if pre_len >= precision {
+                    format.post = 0;
+                } else if pre_len + format.post > precision {
+                    format.post = precision - pre_len;
+                }
+                c_fixed(value, format.post)
+            }
+            Number::Decimal(value, scale) => {
+                let mut digits = Digits::new(value, scale);
+                if format.multiply {
+                    digits = digits.shift(format.multi);
+                    format.pre += format.multi;
+                }
+                digits.round(format.post).to_string()
+            }
+        };
+        let digits = match digits.strip_prefix('-') {
+            Some(digits) => {
+                sign = '-';
+                digits.to_string()
+            }
+            None => {
+                sign = '+';
+                digits
+            }
+        };
+        let pre_len = digits.find('.').unwrap_or(digits.len());
+        if pre_len < format.pre {
+            out_pre_spaces = format.pre - pre_len;
+            digits
+        } else if pre_len > format.pre {
+            let mut digits = vec![b'#'; format.pre + format.post + 1];
+            digits[format.pre] = b'.';
+            String::from_utf8(digits)?
+        } else {
+            digits
+        }
+    };
+
+    if format.eeee {
+        return Ok(digits);
+    }
+    Ok(NumberWriter::new(format, &digits, out_pre_spaces, sign).write(&nodes))
+}
+
+/// Prints `value` with `precision` digits after the decimal point, as C's `%.*f` does.
+fn c_fixed(value: f64, precision: usize) -> String {
+    if value.is_nan() {
+        if value.is_sign_negative() {
+            "-nan"
+        } else {
+            "nan"
+        }
+        .to_string()
+    } else if value.is_infinite() {
+        if value < 0.0 { "-inf" } else { "inf" }.to_string()
+    } else {
+        format!("{value:.precision$}")
+    }
+}
+
+/// Writes the roman numeral for `value`, or 15 `#` if it is not between 1 and 3999.
+fn to_roman(value: i32) -> String {
+    const NUMERALS: [(i32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    if !(1..=3999).contains(&value) {
+        return "#".repeat(15);
+    }
+    let mut rest = value;
+    let mut roman = String::new();
+    for (number, numeral) in NUMERALS {
+        while rest >= number {
+            roman.push_str(numeral);
+            rest -= number;
+        }
+    }
+    roman
+}
+
+/// Writes a number, already rounded to the digits of the template, through the template's
+/// keywords, as Postgres' `NUM_processor` does.
+struct NumberWriter {
+    format: NumberFormat,
+    number: Vec<char>,
+    number_p: usize,
+    out: String,
+    sign: char,
+    sign_wrote: bool,
+    num_in: bool,
+    num_curr: usize,
+    num_count: isize,
+    out_pre_spaces: usize,
+    last_relevant: Option<usize>,
+}
+
+impl NumberWriter {
+    fn new(mut format: NumberFormat, number: &str, out_pre_spaces: usize, sign: char) -> Self {
+        let number: Vec<char> = number.chars().collect();
+        let mut out_pre_spaces = out_pre_spaces;
+        format.zero_start = format.zero_start.saturating_sub(1);
+        if format.roman {
+            format = NumberFormat {
+                fill: format.fill,
+                roman: true,
+                ..NumberFormat::default()
+            };
+            out_pre_spaces = 0;
+        }
+
+        let sign_wrote = if format.plus || format.minus {
+            format.minus || !format.plus
+        } else {
+            if sign != '-' && format.fill {
+                format.bracket = false;
+            }
+            if format.lsign == LocaleSign::Pre && format.pre == format.pre_lsign_num {
+                format.lsign = LocaleSign::Post;
+            }
+            sign == '+' && format.fill && format.lsign == LocaleSign::None
+        };
+
+        let mut num_count = (format.post + format.pre) as isize - 1;
+        let mut last_relevant = None;
+        if format.fill && format.decimal {
+            last_relevant = last_relevant_digit(&number);
+            if let Some(relevant) = last_relevant {
+                if format.zero_end > out_pre_spaces {
+                    let last_zero = (number.len() - 1).min(format.zero_end - out_pre_spaces);
+                    if relevant < last_zero {
+                        last_relevant = Some(last_zero);
+                    }
+                }
+            }
+        }
+        if !sign_wrote && out_pre_spaces == 0 {
+            num_count += 1;
+        }
+
+        Self {
+            format,
+            number,
+            number_p: 0,
+            out: String::new(),
+            sign,
+            sign_wrote,
+            num_in: false,
+            num_curr: 0,
+            num_count,
+            out_pre_spaces,
+            last_relevant,
+        }
+    }
+
+    fn write(mut self, nodes: &[Node<NumberField>]) -> String {
+        for node in nodes {
+            let keyword = match node {
+                Node::Keyword(keyword) => keyword,
+                Node::Char(c) | Node::Space(c) | Node::Separator(c) => {
+                    self.out.push(*c);
+                    continue;
+                }
+            };
+            let fill = self.format.fill;
+            match keyword.field {
+                NumberField::Nine
+                | NumberField::Zero
+                | NumberField::Point
+                | NumberField::Decimal => self.write_digit(keyword.field),
+                NumberField::Comma | NumberField::Group => {
+                    if self.num_in {
+                        self.out.push(',');
+                    } else if !fill {
+                        self.out.push(' ');
+                    }
+                }
+                NumberField::Local => self.out.push(' '),
+                NumberField::Roman => {
+                    let roman: String = self.number[self.number_p..].iter().collect();
+                    let roman = keyword.case.apply(&roman);
+                    if fill {
+                        self.out.push_str(&roman);
+                    } else {
+                        self.out.push_str(&format!("{roman:>15}"));
+                    }
+                }
+                NumberField::Ordinal
+                    if !(self.format.roman
+                        || self.number.first() == Some(&'#')
+                        || self.sign == '-'
+                        || self.format.decimal) =>
+                {
+                    let number: String = self.number.iter().collect();
+                    self.out.push_str(&ordinal_suffix(&number, keyword.case));
+                }
+                NumberField::Minus => {
+                    if self.sign == '-' {
+                        self.out.push('-');
+                    } else if !fill {
+                        self.out.push(' ');
+                    }
+                }
+                NumberField::Plus => {
+                    if self.sign == '+' {
+                        self.out.push('+');
+                    } else if !fill {
+                        self.out.push(' ');
+                    }
+                }
+                NumberField::SignAnywhere => self.out.push(self.sign),
+                _ => {}
+            }
+        }
+        // Postgres writes into a C string, so a NUL ends the output.
+        match self.out.find('\0') {
+            Some(end) => self.out[..end].to_string(),
+            None => self.out,
+        }
+    }
+
+    fn write_digit(&mut self, field: NumberField) {
+        let format = &self.format;
+        if format.roman {
+            return;
+        }
+        let at = |number: &[char], i: usize| number.get(i).copied().unwrap_or('\0');
+        let predec_space =
+            !format.zero && self.number_p == 0 && at(&self.number, 0) == '0' && format.post != 0;
+        let relevant_is_point = self
+            .last_relevant
+            .is_some_and(|i| at(&self.number, i) == '.');
+
+        if !self.sign_wrote
+            && (self.num_curr >= self.out_pre_spaces
+                || (format.zero && format.zero_start == self.num_curr))
+            && (!predec_space || relevant_is_point)
+        {
+            if format.lsign != LocaleSign::None {
+                if format.lsign == LocaleSign::Pre {
+                    self.out.push(if self.sign == '-' { '-' } else { '+' });
+                    self.sign_wrote = true;
+                }
+            } else if format.bracket {
+                self.out.push(if self.sign == '+' { ' ' } else { '<' });
+                self.sign_wrote = true;
+            } else if self.sign == '+' {
+                if !format.fill {
+                    self.out.push(' ');
+                }
+                self.sign_wrote = true;
+            } else if self.sign == '-' {
+                self.out.push('-');
+                self.sign_wrote = true;
+            }
+        }
+
+        if self.num_curr < self.out_pre_spaces
+            && (format.zero_start > self.num_curr || !format.zero)
+        {
+            if !format.fill {
+                self.out.push(' ');
+            }
+        } else if format.zero
+            && self.num_curr < self.out_pre_spaces
+            && format.zero_start <= self.num_curr
+        {
+            self.out.push('0');
+            self.num_in = true;
+        } else {
+            let current = at(&self.number, self.number_p);
+            if current == '.' {
+                self.out.push('.');
+            } else if self.last_relevant.is_some_and(|i| self.number_p > i)
+                && field != NumberField::Zero
+            {
+            } else if predec_space {
+                if !format.fill {
+                    self.out.push(' ');
+                } else if relevant_is_point {
+                    self.out.push('0');
+                }
+            } else {
+                self.out.push(current);
+                self.num_in = true;
+            }
+            if self.number_p < self.number.len() {
+                self.number_p += 1;
+            }
+        }
+
+        let mut end =
+            self.num_count + isize::from(self.out_pre_spaces > 0) + isize::from(format.decimal);
+        if self.last_relevant == Some(self.number_p) {
+            end = self.num_curr as isize;
+        }
+        if self.num_curr as isize + 1 == end {
+            if self.sign_wrote && format.bracket {
+                self.out.push(if self.sign == '+' { ' ' } else { '>' });
+            } else if format.lsign == LocaleSign::Post {
+                self.out.push(if self.sign == '-' { '-' } else { '+' });
+            }
+        }
+        self.num_curr += 1;
+    }
+}
+
+/// Returns the position of the last non-zero digit after the decimal point, or of the point.
+fn last_relevant_digit(number: &[char]) -> Option<usize> {
+    let point = number.iter().position(|c| *c == '.')?;
+    Some(
+        number[point + 1..]
+            .iter()
+            .rposition(|c| *c != '0')
+            .map_or(point, |i| point + 1 + i),
+    )
+}
+
+/// Returns the scale of the numbers `to_number` reads with `template`, or None if the template
+/// is empty.
+pub(crate) fn to_number_scale(template: &str) -> Result<Option<i8>> {
+    if template.is_empty() {
+        return Ok(None);
+    }
+    let nodes = parse_template(template, NUMBER_KEYWORDS, false);
+    let format = NumberFormat::new(&nodes)?;
+    i8::try_from(format.post + format.multi)
+        .ok()
+        .filter(|scale| *scale <= 38)
+        .map(Some)
+        .ok_or_else(|| anyhow!("value overflows numeric format"))
+}
+
+/// Reads `input` as `template` describes and returns it with the scale of
+/// [`to_number_scale`], e.g. `to_number('12,454.8-', '99G999D9S')` is `-124548` with scale 1.
+/// Returns None if the template is empty.
+pub(crate) fn to_number(input: &str, template: &str) -> Result<Option<i128>> {
+    let Some(scale) = to_number_scale(template)? else {
+        return Ok(None);
+    };
+    let nodes = parse_template(template, NUMBER_KEYWORDS, false);
+    let format = NumberFormat::new(&nodes)?;
+    if format.roman {
+        bail!("\"RN\" not supported for input");
+    }
+    if format.eeee {
+        bail!("\"EEEE\" not supported for input");
+    }
+
+    let mut reader = NumberReader {
+        format: &format,
+        input: input.chars().collect(),
+        p: 0,
+        number: vec![' '],
+        read_pre: 0,
+        read_post: 0,
+        read_dec: false,
+    };
+    reader.read(&nodes);
+    if reader.number.last() == Some(&'.') {
+        reader.number.pop();
+    }
+    let number: String = reader.number.iter().collect();
+
+    let invalid = || anyhow!("invalid input syntax for type numeric: \"{number}\"");
+    let digits: String = number.chars().filter(char::is_ascii_digit).collect();
+    if digits.is_empty() {
+        return Err(invalid());
+    }
+    let value: i128 = digits
+        .parse()
+        .map_err(|_| anyhow!("value overflows numeric format"))?;
+    let value = 10_i128
+        .checked_pow((scale as usize - reader.read_post - format.multi) as u32)
+        .and_then(|power| value.checked_mul(power))
+        .filter(|value| *value < 10_i128.pow(38))
+        .ok_or_else(|| anyhow!("value overflows numeric format"))?;
+    Ok(Some(if number.starts_with('-') {
+        -value
+    } else {
+        value
+    }))
+}
+
+/// Reads a number through the keywords of a template, as Postgres' `NUM_processor` does.
+struct NumberReader<'a> {
+    format: &'a NumberFormat,
+    input: Vec<char>,
+    p: usize,
+    /// The sign, or a space, followed by the digits and decimal point read.
+    number: Vec<char>,
+    read_pre: usize,
+    read_post: usize,
+    read_dec: bool,
+}
+
+impl NumberReader<'_> {
+    fn at(&self, i: usize) -> char {
+        self.input.get(i).copied().unwrap_or('\0')
+    }
+
+    fn overloaded(&self) -> bool {
+        self.p >= self.input.len()
+    }
+
+    fn read(&mut self, nodes: &[Node<NumberField>]) {
+        for node in nodes {
+            if self.overloaded() {
+                break;
+            }
+            let keyword = match node {
+                Node::Keyword(keyword) => keyword,
+                _ => {
+                    self.p += 1;
+                    continue;
+                }
+            };
+            let current = self.at(self.p);
+            match keyword.field {
+                NumberField::Nine
+                | NumberField::Zero
+                | NumberField::Point
+                | NumberField::Decimal => self.read_digit(keyword.field),
+                NumberField::Comma | NumberField::Group => {
+                    if self.format.fill || current != ',' {
+                        continue;
+                    }
+                }
+                NumberField::Local => {
+                    self.skip_non_data(1);
+                    continue;
+                }
+                NumberField::Ordinal => {
+                    if !self.format.decimal {
+                        self.skip_non_data(2);
+                    }
+                    continue;
+                }
+                NumberField::Minus if current == '-' => self.number[0] = '-',
+                NumberField::Plus if current == '+' => self.number[0] = '+',
+                NumberField::SignAnywhere if current == '-' || current == '+' => {
+                    self.number[0] = current
+                }
+                NumberField::Minus | NumberField::Plus | NumberField::SignAnywhere => {
+                    self.skip_non_data(1);
+                    continue;
+                }
+                _ => continue,
+            }
+            self.p += 1;
+        }
+    }
+
+    fn skip_non_data(&mut self, count: usize) {
+        for _ in 0..count {
+            if self.overloaded() || "0123456789.,+-".contains(self.at(self.p)) {
+                break;
+            }
+            self.p += 1;
+        }
+    }
+
+    fn read_digit(&mut self, field: NumberField) {
+        let format = self.format;
+        if self.overloaded() {
+            return;
+        }
+        if self.at(self.p) == ' ' {
+            self.p += 1;
+        }
+        if self.overloaded() {
+            return;
+        }
+
+        if self.number[0] == ' '
+            && matches!(field, NumberField::Zero | NumberField::Nine)
+            && self.read_pre + self.read_post == 0
+        {
+            let current = self.at(self.p);
+            if format.lsign == LocaleSign::Pre {
+                if current == '-' || current == '+' {
+                    self.p += 1;
+                    self.number[0] = current;
+                }
+            } else if current == '-' || (format.bracket && current == '<') {
+                self.number[0] = '-';
+                self.p += 1;
+            } else if current == '+' {
+                self.number[0] = '+';
+                self.p += 1;
+            }
+        }
+        if self.overloaded() {
+            return;
+        }
+
+        let mut read = false;
+        let current = self.at(self.p);
+        if current.is_ascii_digit() {
+            if self.read_dec && self.read_post == format.post {
+                return;
+            }
+            self.number.push(current);
+            if self.read_dec {
+                self.read_post += 1;
+            } else {
+                self.read_pre += 1;
+            }
+            read = true;
+        } else if format.decimal && !self.read_dec && current == '.' {
+            self.number.push('.');
+            self.read_dec = true;
+            read = true;
+        }
+        if self.overloaded() {
+            return;
+        }
+
+        if self.number[0] == ' ' && self.read_pre + self.read_post > 0 {
+            if format.lsign != LocaleSign::None
+                && read
+                && self.p + 1 < self.input.len()
+                && !self.at(self.p + 1).is_ascii_digit()
+            {
+                let next = self.at(self.p + 1);
+                if next == '-' || next == '+' {
+                    self.p += 1;
+                    self.number[0] = next;
+                }
+            } else if !read && format.lsign == LocaleSign::None && (format.plus || format.minus) {
+                let current = self.at(self.p);
+                if current == '-' || current == '+' {
+                    self.number[0] = current;
+                }
+            }
+        }
+    }
+}
+
+// Dates and times
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DateField {
+    EraLong,
+    Era,
+    MeridiemLong,
+    Meridiem,
+    Century,
+    DayName,
+    DayAbbrev,
+    DayOfYear,
+    DayOfMonth,
+    DayOfWeek,
+    Fraction(u32),
+    FixedFormat,
+    Hour24,
+    Hour12,
+    IsoDayOfYear,
+    IsoDayOfWeek,
+    IsoWeek,
+    IsoYear(u32),
+    Julian,
+    Minute,
+    Month,
+    MonthName,
+    MonthAbbrev,
+    Millisecond,
+    Microsecond,
+    Offset,
+    Quarter,
+    RomanMonth,
+    SecondOfDay,
+    Second,
+    TzHour,
+    TzMinute,
+    TzName,
+    WeekOfYear,
+    WeekOfMonth,
+    YearComma,
+    Year(u32),
+}
+
+const DATE_KEYWORDS: &[(&str, DateField)] = &[
+    ("A.D.", DateField::EraLong),
+    ("A.M.", DateField::MeridiemLong),
+    ("AD", DateField::Era),
+    ("AM", DateField::Meridiem),
+    ("B.C.", DateField::EraLong),
+    ("BC", DateField::Era),
+    ("CC", DateField::Century),
+    ("DAY", DateField::DayName),
+    ("DDD", DateField::DayOfYear),
+    ("DD", DateField::DayOfMonth),
+    ("DY", DateField::DayAbbrev),
+    ("Day", DateField::DayName),
+    ("Dy", DateField::DayAbbrev),
+    ("D", DateField::DayOfWeek),
+    ("FF1", DateField::Fraction(1)),
+    ("FF2", DateField::Fraction(2)),
+    ("FF3", DateField::Fraction(3)),
+    ("FF4", DateField::Fraction(4)),
+    ("FF5", DateField::Fraction(5)),
+    ("FF6", DateField::Fraction(6)),
+    ("FX", DateField::FixedFormat),
+    ("HH24", DateField::Hour24),
+    ("HH12", DateField::Hour12),
+    ("HH", DateField::Hour12),
+    ("IDDD", DateField::IsoDayOfYear),
+    ("ID", DateField::IsoDayOfWeek),
+    ("IW", DateField::IsoWeek),
+    ("IYYY", DateField::IsoYear(4)),
+    ("IYY", DateField::IsoYear(3)),
+    ("IY", DateField::IsoYear(2)),
+    ("I", DateField::IsoYear(1)),
+    ("J", DateField::Julian),
+    ("MI", DateField::Minute),
+    ("MM", DateField::Month),
+    ("MONTH", DateField::MonthName),
+    ("MON", DateField::MonthAbbrev),
+    ("MS", DateField::Millisecond),
+    ("Month", DateField::MonthName),
+    ("Mon", DateField::MonthAbbrev),
+    ("OF", DateField::Offset),
+    ("P.M.", DateField::MeridiemLong),
+    ("PM", DateField::Meridiem),
+    ("Q", DateField::Quarter),
+    ("RM", DateField::RomanMonth),
+    ("SSSSS", DateField::SecondOfDay),
+    ("SSSS", DateField::SecondOfDay),
+    ("SS", DateField::Second),
+    ("TZH", DateField::TzHour),
+    ("TZM", DateField::TzMinute),
+    ("TZ", DateField::TzName),
+    ("US", DateField::Microsecond),
+    ("WW", DateField::WeekOfYear),
+    ("W", DateField::WeekOfMonth),
+    ("Y,YYY", DateField::YearComma),
+    ("YYYY", DateField::Year(4)),
+    ("YYY", DateField::Year(3)),
+    ("YY", DateField::Year(2)),
+    ("Y", DateField::Year(1)),
+];
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const DAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+/// The roman numerals of the months, longest first so that reading one matches it whole.
+const ROMAN_MONTHS: [&str; 12] = [
+    "XII", "XI", "X", "IX", "VIII", "VII", "VI", "V", "IV", "III", "II", "I",
+];
+
+/// Which calendar a date field belongs to; a template cannot mix ISO weeks with months.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum DateMode {
+    #[default]
+    None,
+    Gregorian,
+    IsoWeek,
+}
+
+impl DateField {
+    fn mode(self) -> DateMode {
+        match self {
+            DateField::DayOfYear
+            | DateField::DayOfMonth
+            | DateField::DayOfWeek
+            | DateField::Month
+            | DateField::MonthName
+            | DateField::MonthAbbrev
+            | DateField::RomanMonth
+            | DateField::WeekOfYear
+            | DateField::WeekOfMonth
+            | DateField::YearComma
+            | DateField::Year(_) => DateMode::Gregorian,
+            DateField::IsoDayOfYear
+            | DateField::IsoDayOfWeek
+            | DateField::IsoWeek
+            | DateField::IsoYear(_) => DateMode::IsoWeek,
+            _ => DateMode::None,
+        }
+    }
+
+    /// Whether the field is a number, which `TH` can follow.
+    fn is_digit(self) -> bool {
+        !matches!(
+            self,
+            DateField::EraLong
+                | DateField::Era
+                | DateField::MeridiemLong
+                | DateField::Meridiem
+                | DateField::DayName
+                | DateField::DayAbbrev
+                | DateField::FixedFormat
+                | DateField::MonthName
+                | DateField::MonthAbbrev
+                | DateField::Offset
+                | DateField::RomanMonth
+                | DateField::TzHour
+                | DateField::TzName
+        )
+    }
+}
+
+/// Prints `datetime` as `template` describes, e.g. `to_char(timestamp '2024-03-05 14:07:09',
+/// 'FMDay, DDth Mon YYYY HH12:MI PM')` is `Tuesday, 05th Mar 2024 02:07 PM`. The time zone is
+/// the offset from UTC in seconds and the abbreviation of a timestamp with time zone. Returns
+/// None if the template is empty.
+pub(crate) fn to_char_timestamp(
+    datetime: &NaiveDateTime,
+    offset: i32,
+    zone: Option<&str>,
+    template: &str,
+) -> Option<String> {
+    if template.is_empty() {
+        return None;
+    }
+    let mut out = String::new();
+    for node in parse_template(template, DATE_KEYWORDS, true) {
+        match node {
+            Node::Keyword(keyword) => {
+                let start = out.len();
+                write_date_field(&mut out, &keyword, datetime, offset, zone);
+                if let Some(case) = keyword.ordinal {
+                    if keyword.field.is_digit() && keyword.field != DateField::TzMinute {
+                        let suffix = ordinal_suffix(&out[start..], case);
+                        out.push_str(&suffix);
+                    }
+                }
+            }
+            Node::Char(c) | Node::Space(c) | Node::Separator(c) => out.push(c),
+        }
+    }
+    Some(out)
+}
+
+fn write_date_field(
+    out: &mut String,
+    keyword: &Keyword<DateField>,
+    datetime: &NaiveDateTime,
+    offset: i32,
+    zone: Option<&str>,
+) {
+    let width = |width: usize| if keyword.fill { 0 } else { width };
+    let padded = |name: &str, width: usize| {
+        let name = keyword.case.apply(name);
+        if keyword.fill {
+            name
+        } else {
+            format!("{name:<width$}")
+        }
+    };
+    let date = datetime.date();
+    let year = i64::from(date.year());
+    let iso_year = i64::from(date.iso_week().year());
+    let hour = datetime.hour();
+    let micros = datetime.nanosecond() / 1000;
+    let text = match keyword.field {
+        DateField::EraLong => keyword.case.apply(if year <= 0 { "B.C." } else { "A.D." }),
+        DateField::Era => keyword.case.apply(if year <= 0 { "BC" } else { "AD" }),
+        DateField::MeridiemLong => keyword.case.apply(if hour >= 12 { "P.M." } else { "A.M." }),
+        DateField::Meridiem => keyword.case.apply(if hour >= 12 { "PM" } else { "AM" }),
+        DateField::Century => {
+            let century = if year > 0 {
+                (year - 1) / 100 + 1
+            } else {
+                year / 100 - 1
+            };
+            if (-99..=99).contains(&century) {
+                format!(
+                    "{century:0width$}",
+                    width = width(if century >= 0 { 2 } else { 3 })
+                )
+            } else {
+                century.to_string()
+            }
+        }
+        DateField::DayName => padded(DAYS[date.weekday().num_days_from_sunday() as usize], 9),
+        DateField::DayAbbrev => keyword
+            .case
+            .apply(&DAYS[date.weekday().num_days_from_sunday() as usize][..3]),
+        DateField::DayOfYear => format!("{:0width$}", date.ordinal(), width = width(3)),
+        DateField::DayOfMonth => format!("{:0width$}", date.day(), width = width(2)),
+        DateField::DayOfWeek => (date.weekday().num_days_from_sunday() + 1).to_string(),
+        DateField::Fraction(digits) => format!(
+            "{:0width$}",
+            micros / 10_u32.pow(6 - digits),
+            width = digits as usize
+        ),
+        DateField::FixedFormat => String::new(),
+        DateField::Hour24 => format!("{hour:0width$}", width = width(2)),
+        DateField::Hour12 => {
+            let hour = match hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            format!("{hour:0width$}", width = width(2))
+        }
+        DateField::IsoDayOfYear => {
+            let day = (date.iso_week().week() - 1) * 7 + date.weekday().number_from_monday();
+            format!("{day:0width$}", width = width(3))
+        }
+        DateField::IsoDayOfWeek => date.weekday().number_from_monday().to_string(),
+        DateField::IsoWeek => format!("{:0width$}", date.iso_week().week(), width = width(2)),
+        DateField::IsoYear(digits) => write_year(adjust_year(iso_year), digits, width),
+        DateField::Julian => {
+            date_to_julian(year, date.month().into(), date.day().into()).to_string()
+        }
+        DateField::Minute => format!("{:0width$}", datetime.minute(), width = width(2)),
+        DateField::Month => format!("{:0width$}", date.month(), width = width(2)),
+        DateField::MonthName => padded(MONTHS[date.month0() as usize], 9),
+        DateField::MonthAbbrev => keyword.case.apply(&MONTHS[date.month0() as usize][..3]),
+        DateField::Millisecond => format!("{:03}", micros / 1000),
+        DateField::Microsecond => format!("{micros:06}"),
+        DateField::Offset => {
+            let sign = if offset >= 0 { '+' } else { '-' };
+            let offset = offset.unsigned_abs();
+            let mut text = format!("{sign}{:0width$}", offset / 3600, width = width(2));
+            match offset % 3600 {
+                0 => {}
+                seconds => text.push_str(&format!(":{:02}", seconds / 60)),
+            }
+            text
+        }
+        DateField::Quarter => ((date.month() - 1) / 3 + 1).to_string(),
+        DateField::RomanMonth => padded(ROMAN_MONTHS[12 - date.month() as usize], 4),
+        DateField::SecondOfDay => datetime.num_seconds_from_midnight().to_string(),
+        DateField::Second => format!("{:0width$}", datetime.second(), width = width(2)),
+        DateField::TzHour => {
+            let sign = if offset >= 0 { '+' } else { '-' };
+            format!("{sign}{:02}", offset.unsigned_abs() / 3600)
+        }
+        DateField::TzMinute => format!("{:02}", offset.unsigned_abs() % 3600 / 60),
+        DateField::TzName => zone
+            .map(|zone| keyword.case.apply(zone))
+            .unwrap_or_default(),
+        DateField::WeekOfYear => {
+            format!("{:0width$}", (date.ordinal() - 1) / 7 + 1, width = width(2))
+        }
+        DateField::WeekOfMonth => ((date.day() - 1) / 7 + 1).to_string(),
+        DateField::YearComma => {
+            let year = adjust_year(year);
+            format!("{},{:03}", year / 1000, year % 1000)
+        }
+        DateField::Year(digits) => write_year(adjust_year(year), digits, width),
+    };
+    out.push_str(&text);
+}
+
+/// Returns the year of the AD or BC era for an astronomical year, in which 0 is 1 BC.
+fn adjust_year(year: i64) -> i64 {
+    if year <= 0 {
+        1 - year
+    } else {
+        year
+    }
+}
+
+fn write_year(year: i64, digits: u32, width: impl Fn(usize) -> usize) -> String {
+    let year = match digits {
+        4 => year,
+        digits => year % 10_i64.pow(digits),
+    };
+    format!("{year:0width$}", width = width(digits as usize))
+}
+
+/// The fields read by `to_date` and `to_timestamp`, zero when the template does not set them.
+#[derive(Debug, Default)]
+struct DateInput {
+    mode: DateMode,
+    hh: i64,
+    pm: i64,
+    mi: i64,
+    ss: i64,
+    ssss: i64,
+    d: i64,
+    dd: i64,
+    ddd: i64,
+    mm: i64,
+    ms: i64,
+    year: i64,
+    bc: i64,
+    ww: i64,
+    w: i64,
+    cc: i64,
+    j: i64,
+    us: i64,
+    yysz: i64,
+    /// The digits of an `FF1` to `FF6` field, to which `to_timestamp` rounds the fraction.
+    precision: Option<u32>,
+    clock12: bool,
+    tzsign: i64,
+    tzh: i64,
+    tzm: i64,
+}
+
+impl DateInput {
+    fn set_mode(&mut self, mode: DateMode) -> Result<()> {
+        if mode != DateMode::None {
+            if self.mode == DateMode::None {
+                self.mode = mode;
+            } else if self.mode != mode {
+                bail!("invalid combination of date conventions: Do not mix Gregorian and ISO week date conventions in a formatting template.");
+            }
+        }
+        Ok(())
+    }
+}
+
+fn set_field(field: &mut i64, value: i64, name: &str) -> Result<()> {
+    if *field != 0 && *field != value {
+        bail!("conflicting values for \"{name}\" field in formatting string: This value contradicts a previous setting for the same field type.");
+    }
+    *field = value;
+    Ok(())
+}
+
+/// Reads a date as a template describes it, as Postgres' `DCH_from_char` does.
+struct DateReader<'a> {
+    nodes: &'a [Node<'a, DateField>],
+    input: Vec<char>,
+    pos: usize,
+}
+
+impl DateReader<'_> {
+    fn at(&self, i: usize) -> char {
+        self.input.get(i).copied().unwrap_or('\0')
+    }
+
+    fn skip_spaces(&mut self) -> i64 {
+        let start = self.pos;
+        while self.pos < self.input.len() && is_space(self.input[self.pos]) {
+            self.pos += 1;
+        }
+        (self.pos - start) as i64
+    }
+
+    /// Reads an integer as C's `strtol` does from `start`, returning it, or None if it
+    /// overflows, and where it ends, which is `start` if there are no digits.
+    fn strtol(input: &[char], start: usize) -> (Option<i64>, usize) {
+        let mut pos = start;
+        while pos < input.len() && is_space(input[pos]) {
+            pos += 1;
+        }
+        let negative = input.get(pos) == Some(&'-');
+        if matches!(input.get(pos), Some('-' | '+')) {
+            pos += 1;
+        }
+        let digits_start = pos;
+        let mut value: Option<i64> = Some(0);
+        while pos < input.len() && input[pos].is_ascii_digit() {
+            let digit = i64::from(input[pos] as u8 - b'0');
+            value = value
+                .and_then(|value| value.checked_mul(10))
+                .and_then(|value| value.checked_add(digit));
+            pos += 1;
+        }
+        if pos == digits_start {
+            return (Some(0), start);
+        }
+        (
+            value.map(|value| if negative { -value } else { value }),
+            pos,
+        )
+    }
+
+    fn is_next_separator(&self, index: usize) -> bool {
+        if let Node::Keyword(keyword) = &self.nodes[index] {
+            if keyword.ordinal.is_some() {
+                return true;
+            }
+        }
+        match self.nodes.get(index + 1) {
+            None => true,
+            Some(Node::Keyword(next)) => !next.field.is_digit(),
+            Some(Node::Char(c) | Node::Space(c) | Node::Separator(c)) => !c.is_ascii_digit(),
+        }
+    }
+
+    /// Reads the integer of the field at `index`, `len` characters of it unless the field has
+    /// `FM` or is followed by a separator, and returns it with the number of characters read.
+    fn read_int(&mut self, index: usize, len: usize) -> Result<(i64, usize)> {
+        let Node::Keyword(keyword) = &self.nodes[index] else {
+            unreachable!("only keywords are read as integers");
+        };
+        let name = keyword.name;
+        let init = self.pos;
+        self.skip_spaces();
+        let copy: String = self.input[self.pos.min(self.input.len())..]
+            .iter()
+            .take(len)
+            .collect();
+        let result = if keyword.fill || self.is_next_separator(index) {
+            let (value, end) = Self::strtol(&self.input, init);
+            self.pos = end;
+            value
+        } else {
+            let remain = self.input.len().saturating_sub(self.pos);
+            if remain < len {
+                bail!("source string too short for \"{name}\" formatting field: Field requires {len} characters, but only {remain} remain.");
+            }
+            let copied: Vec<char> = copy.chars().collect();
+            let (value, used) = Self::strtol(&copied, 0);
+            if used > 0 && used < len {
+                bail!("invalid value \"{copy}\" for \"{name}\": Field requires {len} characters, but only {used} could be parsed.");
+            }
+            self.pos += used;
+            value
+        };
+        if self.pos == init {
+            bail!("invalid value \"{copy}\" for \"{name}\": Value must be an integer.");
+        }
+        let value = result
+            .filter(|value| i32::try_from(*value).is_ok())
+            .ok_or_else(|| anyhow!("value for \"{name}\" in source string is out of range: Value must be in the range -2147483648 to 2147483647."))?;
+        Ok((value, self.pos - init))
+    }
+
+    /// Reads one of `names`, ignoring case, and returns its index.
+    fn read_name(&mut self, names: &[&str], keyword: &Keyword<DateField>) -> Result<i64> {
+        let rest = &self.input[self.pos.min(self.input.len())..];
+        for (index, name) in names.iter().enumerate() {
+            let len = name.chars().count();
+            if rest.len() >= len
+                && rest[..len]
+                    .iter()
+                    .zip(name.chars())
+                    .all(|(c, n)| c.eq_ignore_ascii_case(&n))
+            {
+                self.pos += len;
+                return Ok(index as i64);
+            }
+        }
+        let copy: String = rest.iter().take_while(|c| !is_space(**c)).collect();
+        bail!(
+            "invalid value \"{copy}\" for \"{}\": The given value did not match any of the allowed values for this field.",
+            keyword.name
+        )
+    }
+
+    /// Skips the two characters of a `TH` suffix.
+    fn skip_ordinal(&mut self, keyword: &Keyword<DateField>) {
+        if keyword.ordinal.is_some() {
+            for _ in 0..2 {
+                if self.pos < self.input.len() {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// Reads `Y,YYY` as C's `sscanf("%d,%03d")` does, returning the year and its length.
+    fn read_year_comma(&self) -> Option<(i64, usize)> {
+        let (millennia, mut pos) = Self::strtol(&self.input, self.pos);
+        let millennia = millennia.filter(|_| pos > self.pos)?;
+        if self.at(pos) != ',' {
+            return None;
+        }
+        pos += 1;
+        while pos < self.input.len() && is_space(self.input[pos]) {
+            pos += 1;
+        }
+        let start = pos;
+        let negative = self.at(pos) == '-';
+        if matches!(self.at(pos), '-' | '+') {
+            pos += 1;
+        }
+        let digits_start = pos;
+        while pos - start < 3 && self.at(pos).is_ascii_digit() {
+            pos += 1;
+        }
+        if pos == digits_start {
+            return None;
+        }
+        let years: i64 = self.input[digits_start..pos]
+            .iter()
+            .collect::<String>()
+            .parse()
+            .ok()?;
+        let years = if negative { -years } else { years };
+        Some((years + millennia * 1000, pos - self.pos))
+    }
+
+    fn read(&mut self) -> Result<DateInput> {
+        let mut out = DateInput::default();
+        let mut fx = false;
+        let mut extra_skip: i64 = 0;
+        let nodes = self.nodes;
+        for (index, node) in nodes.iter().enumerate() {
+            if self.pos >= self.input.len() {
+                break;
+            }
+            let is_fx =
+                matches!(node, Node::Keyword(keyword) if keyword.field == DateField::FixedFormat);
+            if !fx && !is_fx && (matches!(node, Node::Keyword(_)) || index == 0) {
+                extra_skip += self.skip_spaces();
+            }
+
+            let keyword = match node {
+                Node::Space(_) | Node::Separator(_) => {
+                    if fx {
+                        self.pos += 1;
+                    } else {
+                        extra_skip -= 1;
+                        let current = self.at(self.pos);
+                        if is_space(current) || is_separator(current) {
+                            self.pos += 1;
+                            extra_skip += 1;
+                        }
+                    }
+                    continue;
+                }
+                Node::Char(_) => {
+                    if !fx && extra_skip > 0 {
+                        extra_skip -= 1;
+                    } else {
+                        self.pos += 1;
+                    }
+                    continue;
+                }
+                Node::Keyword(keyword) => keyword,
+            };
+
+            out.set_mode(keyword.field.mode())?;
+            let name = keyword.name;
+            match keyword.field {
+                DateField::FixedFormat => fx = true,
+                DateField::MeridiemLong | DateField::Meridiem => {
+                    let names: &[&str] = if keyword.field == DateField::MeridiemLong {
+                        &["a.m.", "p.m."]
+                    } else {
+                        &["am", "pm"]
+                    };
+                    let value = self.read_name(names, keyword)?;
+                    set_field(&mut out.pm, value, name)?;
+                    out.clock12 = true;
+                }
+                DateField::Hour12 => {
+                    let (value, _) = self.read_int(index, 2)?;
+                    set_field(&mut out.hh, value, name)?;
+                    out.clock12 = true;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Hour24 => {
+                    let (value, _) = self.read_int(index, 2)?;
+                    set_field(&mut out.hh, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Minute => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.mi, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Second => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.ss, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Millisecond => {
+                    let (value, len) = self.read_int(index, 3)?;
+                    set_field(&mut out.ms, value, name)?;
+                    // 25 is 0.25 and 250 is 0.25 too, but 025 is 0.025.
+                    out.ms *= match len {
+                        1 => 100,
+                        2 => 10,
+                        _ => 1,
+                    };
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Fraction(_) | DateField::Microsecond => {
+                    let digits = match keyword.field {
+                        DateField::Fraction(digits) => {
+                            out.precision = Some(digits);
+                            digits as usize
+                        }
+                        _ => 6,
+                    };
+                    let (value, len) = self.read_int(index, digits)?;
+                    set_field(&mut out.us, value, name)?;
+                    out.us *= 10_i64.pow(6_u32.saturating_sub(len as u32));
+                    self.skip_ordinal(keyword);
+                }
+                DateField::SecondOfDay => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.ssss, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::TzName | DateField::Offset => {
+                    bail!("formatting field \"{name}\" is only supported in to_char");
+                }
+                DateField::TzHour => {
+                    // A minus sign skipped as a separator is the sign of the offset.
+                    let current = self.at(self.pos);
+                    if matches!(current, '+' | '-' | ' ') {
+                        out.tzsign = if current == '-' { -1 } else { 1 };
+                        self.pos += 1;
+                    } else if extra_skip > 0 && self.pos > 0 && self.at(self.pos - 1) == '-' {
+                        out.tzsign = -1;
+                    } else {
+                        out.tzsign = 1;
+                    }
+                    let (value, _) = self.read_int(index, 2)?;
+                    set_field(&mut out.tzh, value, name)?;
+                }
+                DateField::TzMinute => {
+                    if out.tzsign == 0 {
+                        out.tzsign = 1;
+                    }
+                    let (value, _) = self.read_int(index, 2)?;
+                    set_field(&mut out.tzm, value, name)?;
+                }
+                DateField::EraLong | DateField::Era => {
+                    let names: &[&str] = if keyword.field == DateField::EraLong {
+                        &["a.d.", "b.c."]
+                    } else {
+                        &["ad", "bc"]
+                    };
+                    let value = self.read_name(names, keyword)?;
+                    set_field(&mut out.bc, value, name)?;
+                }
+                DateField::MonthName | DateField::MonthAbbrev => {
+                    let names: Vec<&str> = if keyword.field == DateField::MonthName {
+                        MONTHS.to_vec()
+                    } else {
+                        MONTHS.iter().map(|month| &month[..3]).collect()
+                    };
+                    let value = self.read_name(&names, keyword)?;
+                    set_field(&mut out.mm, value + 1, name)?;
+                }
+                DateField::Month => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.mm, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::DayName | DateField::DayAbbrev => {
+                    let names: Vec<&str> = if keyword.field == DateField::DayName {
+                        DAYS.to_vec()
+                    } else {
+                        DAYS.iter().map(|day| &day[..3]).collect()
+                    };
+                    let value = self.read_name(&names, keyword)?;
+                    set_field(&mut out.d, value, name)?;
+                    out.d += 1;
+                }
+                DateField::DayOfYear => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.ddd, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::IsoDayOfYear => {
+                    let (value, _) = self.read_int(index, 3)?;
+                    set_field(&mut out.ddd, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::DayOfMonth => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.dd, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::DayOfWeek => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.d, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::IsoDayOfWeek => {
+                    let (value, _) = self.read_int(index, 1)?;
+                    set_field(&mut out.d, value, name)?;
+                    // Sunday is 1, as in Gregorian weeks.
+                    out.d += 1;
+                    if out.d > 7 {
+                        out.d = 1;
+                    }
+                    self.skip_ordinal(keyword);
+                }
+                DateField::WeekOfYear | DateField::IsoWeek => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.ww, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Quarter => {
+                    // The quarter is read but ignored, as it does not give a date.
+                    self.read_int(index, name.len())?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Century => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.cc, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::YearComma => {
+                    let (value, len) = self
+                        .read_year_comma()
+                        .ok_or_else(|| anyhow!("invalid input string for \"Y,YYY\""))?;
+                    set_field(&mut out.year, value, name)?;
+                    out.yysz = 4;
+                    self.pos += len;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Year(digits) | DateField::IsoYear(digits) => {
+                    let (value, len) = self.read_int(index, name.len())?;
+                    set_field(&mut out.year, value, name)?;
+                    if digits < 4 && len < 4 {
+                        out.year = adjust_partial_year_to_2020(out.year);
+                    }
+                    out.yysz = digits.into();
+                    self.skip_ordinal(keyword);
+                }
+                DateField::RomanMonth => {
+                    let value = self.read_name(&ROMAN_MONTHS, keyword)?;
+                    set_field(&mut out.mm, 12 - value, name)?;
+                }
+                DateField::WeekOfMonth => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.w, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+                DateField::Julian => {
+                    let (value, _) = self.read_int(index, name.len())?;
+                    set_field(&mut out.j, value, name)?;
+                    self.skip_ordinal(keyword);
+                }
+            }
+
+            if !fx {
+                extra_skip = self.skip_spaces();
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// Moves a year of less than 4 digits to the closest to 2020, e.g. 70 to 1970 and 69 to 2069.
+fn adjust_partial_year_to_2020(year: i64) -> i64 {
+    if year < 70 {
+        year + 2000
+    } else if year < 100 {
+        year + 1900
+    } else if year < 520 {
+        year + 2000
+    } else if year < 1000 {
+        year + 1000
+    } else {
+        year
+    }
+}
+
+/// A date and time read by `to_date` or `to_timestamp`, in an astronomical year, with the offset
+/// from UTC in seconds if the input has one and the digits of the fraction if the template sets
+/// them.
+#[derive(Debug)]
+struct DateTime {
+    year: i64,
+    month: i64,
+    day: i64,
+    hour: i64,
+    minute: i64,
+    second: i64,
+    micros: i64,
+    offset: Option<i64>,
+    precision: Option<u32>,
+}
+
+/// Reads `input` as `template` describes it and checks the fields, as Postgres'
+/// `do_to_timestamp` does.
+fn read_date_time(input: &str, template: &str) -> Result<DateTime> {
+    let nodes = parse_template(template, DATE_KEYWORDS, true);
+    let mut reader = DateReader {
+        nodes: &nodes,
+        input: input.chars().collect(),
+        pos: 0,
+    };
+    let mut tmfc = reader.read()?;
+    let out_of_range = || anyhow!("date/time field value out of range: \"{input}\"");
+
+    let mut tm = DateTime {
+        year: 0,
+        month: 1,
+        day: 1,
+        hour: 0,
+        minute: 0,
+        second: 0,
+        micros: 0,
+        offset: None,
+        precision: tmfc.precision,
+    };
+    let (mut has_year, mut has_month, mut has_day) = (false, false, false);
+
+    if tmfc.ssss != 0 {
+        tm.hour = tmfc.ssss / 3600;
+        tm.minute = tmfc.ssss % 3600 / 60;
+        tm.second = tmfc.ssss % 60;
+    }
+    if tmfc.ss != 0 {
+        tm.second = tmfc.ss;
+    }
+    if tmfc.mi != 0 {
+        tm.minute = tmfc.mi;
+    }
+    if tmfc.hh != 0 {
+        tm.hour = tmfc.hh;
+    }
+
+    if tmfc.clock12 {
+        if tm.hour < 1 || tm.hour > 12 {
+            bail!(
+                "hour \"{}\" is invalid for the 12-hour clock: Use the 24-hour clock, or give an hour between 1 and 12.",
+                tm.hour
+            );
+        }
+        if tmfc.pm != 0 && tm.hour < 12 {
+            tm.hour += 12;
+        } else if tmfc.pm == 0 && tm.hour == 12 {
+            tm.hour = 0;
+        }
+    }
+
+    if tmfc.year != 0 {
+        // With a century, a year of 2 digits or less is the year in that century; the 21st
+        // century runs from 2001 to 2100 and the 6th century BC from 600 BC to 501 BC.
+        if tmfc.cc != 0 && tmfc.yysz <= 2 {
+            if tmfc.bc != 0 {
+                tmfc.cc = -tmfc.cc;
+            }
+            tm.year = tmfc.year % 100;
+            if tm.year != 0 {
+                if tmfc.cc >= 0 {
+                    tm.year += (tmfc.cc - 1) * 100;
+                } else {
+                    tm.year = (tmfc.cc + 1) * 100 - tm.year + 1;
+                }
+            } else {
+                tm.year = tmfc.cc * 100 + if tmfc.cc >= 0 { 0 } else { 1 };
+            }
+        } else {
+            tm.year = tmfc.year;
+            if tmfc.bc != 0 {
+                tm.year = -tm.year;
+            }
+            if tm.year < 0 {
+                tm.year += 1;
+            }
+        }
+        has_year = true;
+    } else if tmfc.cc != 0 {
+        if tmfc.bc != 0 {
+            tmfc.cc = -tmfc.cc;
+        }
+        tm.year = if tmfc.cc >= 0 {
+            (tmfc.cc - 1) * 100 + 1
+        } else {
+            tmfc.cc * 100 + 1
+        };
+        has_year = true;
+    }
+
+    if tmfc.j != 0 {
+        (tm.year, tm.month, tm.day) = julian_to_date(tmfc.j);
+        (has_year, has_month, has_day) = (true, true, true);
+    }
+
+    if tmfc.ww != 0 {
+        if tmfc.mode == DateMode::IsoWeek {
+            // Without a day of the week, the date is the Monday of the week.
+            let mut julian = iso_week_to_julian(tm.year, tmfc.ww);
+            if tmfc.d != 0 {
+                julian += if tmfc.d > 1 { tmfc.d - 2 } else { 6 };
+            }
+            (tm.year, tm.month, tm.day) = julian_to_date(julian);
+            (has_year, has_month, has_day) = (true, true, true);
+        } else {
+            tmfc.ddd = (tmfc.ww - 1) * 7 + 1;
+        }
+    }
+
+    if tmfc.w != 0 {
+        tmfc.dd = (tmfc.w - 1) * 7 + 1;
+    }
+    if tmfc.dd != 0 {
+        tm.day = tmfc.dd;
+        has_day = true;
+    }
+    if tmfc.mm != 0 {
+        tm.month = tmfc.mm;
+        has_month = true;
+    }
+
+    if tmfc.ddd != 0 && (tm.month <= 1 || tm.day <= 1) {
+        if tm.year == 0 && tmfc.bc == 0 {
+            bail!("cannot calculate day of year without year information");
+        }
+        if tmfc.mode == DateMode::IsoWeek {
+            let first = iso_week_to_julian(tm.year, 1) - 1;
+            (tm.year, tm.month, tm.day) = julian_to_date(first + tmfc.ddd);
+            (has_year, has_month, has_day) = (true, true, true);
+        } else {
+            const DAYS_BEFORE: [[i64; 13]; 2] = [
+                [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334, 365],
+                [0, 31, 60, 91, 121, 152, 182, 213, 244, 274, 305, 335, 366],
+            ];
+            let days_before = &DAYS_BEFORE[usize::from(is_leap(tm.year))];
+            let month = (1..=12)
+                .find(|month| tmfc.ddd <= days_before[*month])
+                .unwrap_or(13);
+            if tm.month <= 1 {
+                tm.month = month as i64;
+            }
+            if tm.day <= 1 {
+                tm.day = tmfc.ddd - days_before[month - 1];
+            }
+            (has_month, has_day) = (true, true);
+        }
+    }
+
+    tm.micros = tmfc.ms * 1000 + tmfc.us;
+
+    if (has_month && !(1..=12).contains(&tm.month))
+        || (has_day && !(1..=31).contains(&tm.day))
+        || (has_year && has_month && has_day && tm.day > days_in_month(tm.year, tm.month as usize))
+    {
+        return Err(out_of_range());
+    }
+    if !(0..24).contains(&tm.hour)
+        || !(0..60).contains(&tm.minute)
+        || !(0..60).contains(&tm.second)
+        || !(0..1_000_000).contains(&tm.micros)
+    {
+        return Err(out_of_range());
+    }
+
+    if tmfc.tzsign != 0 {
+        if !(0..=15).contains(&tmfc.tzh) || !(0..60).contains(&tmfc.tzm) {
+            bail!("time zone displacement out of range: \"{input}\"");
+        }
+        tm.offset = Some(tmfc.tzsign * (tmfc.tzh * 3600 + tmfc.tzm * 60));
+    }
+    Ok(tm)
+}
+
+fn is_leap(year: i64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+pub(super) fn days_in_month(year: i64, month: usize) -> i64 {
+    const DAYS: [[i64; 12]; 2] = [
+        [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
+        [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31],
+    ];
+    DAYS[usize::from(is_leap(year))][month - 1]
+}
+
+/// The Julian day of 1970-01-01.
+pub(super) const UNIX_EPOCH_JULIAN: i64 = 2440588;
+
+/// The Julian day after the last date Postgres supports, 5874897-12-31.
+pub(super) const JULIAN_END: i64 = 2147483494;
+
+/// Whether the date is on or after 4714-11-24 BC, the first day of the Julian day count, and
+/// before June 5874898.
+pub(super) fn is_valid_julian(year: i64, month: i64) -> bool {
+    (year > -4713 || (year == -4713 && month >= 11))
+        && (year < 5874898 || (year == 5874898 && month < 6))
+}
+
+/// Returns the Julian day of a date, as Postgres' `date2j` does.
+pub(super) fn date_to_julian(year: i64, month: i64, day: i64) -> i64 {
+    let (year, month) = if month > 2 {
+        (year + 4800, month + 1)
+    } else {
+        (year + 4799, month + 13)
+    };
+    let century = year / 100;
+    year * 365 - 32167 + year / 4 - century + century / 4 + 7834 * month / 256 + day
+}
+
+/// Returns the date of a Julian day, as Postgres' `j2date` does.
+pub(super) fn julian_to_date(julian_day: i64) -> (i64, i64, i64) {
+    let mut julian = (julian_day as u32).wrapping_add(32044);
+    let mut quad = julian / 146097;
+    let extra = (julian - quad * 146097) * 4 + 3;
+    julian = julian.wrapping_add(60 + quad * 3 + extra / 146097);
+    quad = julian / 1461;
+    julian -= quad * 1461;
+    let mut year = julian * 4 / 1461;
+    julian = if year != 0 {
+        (julian + 305) % 365
+    } else {
+        (julian + 306) % 366
+    } + 123;
+    year = year.wrapping_add(quad.wrapping_mul(4));
+    let quad = julian * 2141 / 65536;
+    let day = julian - 7834 * quad / 256;
+    let month = (quad + 10) % 12 + 1;
+    (
+        i64::from(year as i32) - 4800,
+        i64::from(month),
+        i64::from(day),
+    )
+}
+
+/// Returns the Julian day of the Monday of an ISO week, as Postgres' `isoweek2j` does.
+pub(super) fn iso_week_to_julian(year: i64, week: i64) -> i64 {
+    let fourth = date_to_julian(year, 1, 4);
+    let weekday = (fourth - 1 + 1).rem_euclid(7);
+    (week - 1) * 7 + (fourth - weekday)
+}
+
+/// Reads `input` as `template` describes and returns the days since 1970-01-01, e.g.
+/// `to_date('05 Dec 2000', 'DD Mon YYYY')`.
+pub(crate) fn to_date(input: &str, template: &str) -> Result<i32> {
+    let tm = read_date_time(input, template)?;
+    let out_of_range = || anyhow!("date out of range: \"{input}\"");
+    if !is_valid_julian(tm.year, tm.month) {
+        return Err(out_of_range());
+    }
+    let julian = date_to_julian(tm.year, tm.month, tm.day);
+    if !(0..JULIAN_END).contains(&julian) {
+        return Err(out_of_range());
+    }
+    i32::try_from(julian - UNIX_EPOCH_JULIAN).map_err(|_| out_of_range())
+}
+
+/// Reads `input` as `template` describes and returns the microseconds since 1970-01-01
+/// 00:00:00 UTC, e.g. `to_timestamp('05 Dec 2000 14:30 +02', 'DD Mon YYYY HH24:MI TZH')`. A
+/// time without an offset is in UTC.
+pub(crate) fn to_timestamp(input: &str, template: &str) -> Result<i64> {
+    let tm = read_date_time(input, template)?;
+    let out_of_range = || anyhow!("timestamp out of range");
+    if !is_valid_julian(tm.year, tm.month) {
+        return Err(out_of_range());
+    }
+    let days = date_to_julian(tm.year, tm.month, tm.day) - UNIX_EPOCH_JULIAN;
+    let seconds = (tm.hour * 60 + tm.minute) * 60 + tm.second - tm.offset.unwrap_or(0);
+    let micros = days
+        .checked_mul(86_400_000_000)
+        .and_then(|micros| micros.checked_add(seconds * 1_000_000 + tm.micros))
+        .filter(|micros| *micros >= MIN_TIMESTAMP)
+        .ok_or_else(out_of_range)?;
+    Ok(match tm.precision {
+        Some(digits) => round_micros(micros, digits),
+        None => micros,
+    })
+}
+
+/// Rounds a timestamp to `digits` digits of a second, half away from 2000-01-01 as Postgres
+/// rounds timestamps with a precision.
+fn round_micros(micros: i64, digits: u32) -> i64 {
+    const POSTGRES_EPOCH: i64 = 946_684_800_000_000;
+    let scale = 10_i64.pow(6 - digits);
+    let micros = micros - POSTGRES_EPOCH;
+    let rounded = (micros.abs() + scale / 2) / scale * scale;
+    rounded * micros.signum() + POSTGRES_EPOCH
+}
+
+/// The microseconds since 1970-01-01 of 4714-11-24 00:00:00 BC, the first timestamp.
+pub(super) const MIN_TIMESTAMP: i64 = -210_866_803_200_000_000;
+
+/// Returns the timestamp `seconds` after 1970-01-01 00:00:00 UTC, in microseconds.
+pub(crate) fn epoch_to_timestamp(seconds: f64) -> Result<i64> {
+    if seconds.is_nan() {
+        bail!("timestamp cannot be NaN");
+    }
+    let micros = (seconds * 1e6).round_ties_even();
+    if !(MIN_TIMESTAMP as f64..i64::MAX as f64).contains(&micros) {
+        bail!("timestamp out of range: \"{}\"", c_general(seconds));
+    }
+    Ok(micros as i64)
+}
+
+/// Prints `value` as C's `%g` does, with 6 significant digits.
+pub(super) fn c_general(value: f64) -> String {
+    if !value.is_finite() {
+        return c_fixed(value, 0);
+    }
+    let scientific = format!("{value:.5e}");
+    let (significand, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+    let exponent: i32 = exponent.parse().unwrap_or(0);
+    let trim = |number: &str| {
+        if number.contains('.') {
+            number
+                .trim_end_matches('0')
+                .trim_end_matches('.')
+                .to_string()
+        } else {
+            number.to_string()
+        }
+    };
+    if !(-4..6).contains(&exponent) {
+        format!("{}e{exponent:+03}", trim(significand))
+    } else {
+        trim(&format!("{value:.*}", (5 - exponent) as usize))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn number(value: &str, template: &str) -> String {
+        let (digits, scale) = match value.split_once('.') {
+            Some((integer, fraction)) => (format!("{integer}{fraction}"), fraction.len()),
+            None => (value.to_string(), 0),
+        };
+        to_char_number(
+            Number::Decimal(digits.parse().unwrap(), scale as i8),
+            template,
+        )
+        .unwrap()
+    }
+
+    fn timestamp(template: &str) -> String {
+        let datetime = NaiveDate::from_ymd_opt(2024, 3, 5)
+            .unwrap()
+            .and_hms_micro_opt(14, 7, 9, 123456)
+            .unwrap();
+        to_char_timestamp(&datetime, 0, None, template).unwrap()
+    }
+
+    fn date(input: &str, template: &str) -> Result<String> {
+        let days = to_date(input, template)?;
+        let date = NaiveDate::from_num_days_from_ce_opt(days + 719163).unwrap();
+        Ok(date.format("%Y-%m-%d").to_string())
+    }
+
+    fn timestamp_from(input: &str, template: &str) -> Result<String> {
+        let micros = to_timestamp(input, template)?;
+        let datetime = chrono::DateTime::from_timestamp_micros(micros).unwrap();
+        Ok(datetime.format("%Y-%m-%d %H:%M:%S%.f").to_string())
+    }
+
+    #[test]
+    fn test_to_char_numeric() {
+        assert_eq!(number("0", "999"), "   0");
+        assert_eq!(number("0", "9.99"), "  .00");
+        assert_eq!(number("0", "FM99.99"), "0.");
+        assert_eq!(number("-12.45", "999D99S"), " 12.45-");
+        assert_eq!(number("-12.45", "999PR"), " <12>");
+        assert_eq!(number("12.4", "SG999"), "+ 12");
+        assert_eq!(number("1234.5", "9G999D99"), " 1,234.50");
+        assert_eq!(number("1234.5", "99"), " ##");
+        assert_eq!(number("485", "RN"), "        CDLXXXV");
+        assert_eq!(number("485", "FMrn"), "cdlxxxv");
+        assert_eq!(number("12", "999th"), "  12th");
+        assert_eq!(number("-125.8", "L999"), " -126");
+        assert_eq!(number("99.995", "9.99EEEE"), " 10.00e+01");
+        assert_eq!(number("12", "99V9"), " 120");
+        assert_eq!(number("12.4", "\"Total: \"999"), "Total:   12");
+    }
+
+    #[test]
+    fn test_to_char_float_and_integer() {
+        let float = |value, template| to_char_number(Number::Float(value, 15), template).unwrap();
+        assert_eq!(float(0.0004859, "9.99EEEE"), " 4.86e-04");
+        assert_eq!(float(-1234.567, "FM9,999.999"), "-1,234.567");
+        assert_eq!(float(1234567.891, "9990999.9"), " 1234567.9");
+        let integer = |value, template| to_char_number(Number::Integer(value), template).unwrap();
+        assert_eq!(integer(-485, "FMPL999"), "-485");
+        assert_eq!(integer(125, "9.99EEEE"), " 1.25e+02");
+    }
+
+    #[test]
+    fn test_number_template_errors() {
+        let error = |template| {
+            to_char_number(Number::Integer(1), template)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error("9.9.9"), "multiple decimal points");
+        assert_eq!(error("S9S"), "cannot use \"S\" twice");
+        assert_eq!(error("9PR9"), "\"9\" must be ahead of \"PR\"");
+        assert_eq!(
+            error("FM9.99EEEE"),
+            "\"EEEE\" is incompatible with other formats: \"EEEE\" may only be used together with digit and decimal point patterns."
+        );
+    }
+
+    #[test]
+    fn test_to_number() {
+        let read = |input, template| {
+            let scale = to_number_scale(template).unwrap().unwrap();
+            let value = to_number(input, template).unwrap().unwrap();
+            (value, scale)
+        };
+        assert_eq!(read("12,454.8-", "99G999D9S"), (-124548, 1));
+        assert_eq!(
+            read("<564646.654564>", "999999.999999PR"),
+            (-564646654564, 6)
+        );
+        assert_eq!(read("5.01-", "FM9.999999MI"), (-5010000, 6));
+        assert_eq!(read("$1,234.56", "L99,999.99"), (123456, 2));
+        assert_eq!(read("42nd", "99th"), (42, 0));
+        assert_eq!(read("12345", "999V99"), (12345, 2));
+        assert_eq!(read("123.456", "999.9"), (1234, 1));
+        assert_eq!(to_number("1", "").unwrap(), None);
+        assert_eq!(
+            to_number("abc", "999").unwrap_err().to_string(),
+            "invalid input syntax for type numeric: \" \""
+        );
+        assert_eq!(
+            to_number("12", "RN").unwrap_err().to_string(),
+            "\"RN\" not supported for input"
+        );
+    }
+
+    #[test]
+    fn test_to_char_timestamp() {
+        assert_eq!(
+            timestamp("YYYY-MM-DD HH24:MI:SS.US"),
+            "2024-03-05 14:07:09.123456"
+        );
+        assert_eq!(
+            timestamp(r#"FMDay, FMDD  HH12:MI:SS PM "quoted \" text" \"x DDth ddTH Month MON"#),
+            "Tuesday, 5  02:07:09 PM quoted \" text \"x 05th 05TH March     MAR"
+        );
+        assert_eq!(
+            timestamp("rm|day|dy|mon|month|a.m."),
+            "iii |tuesday  |tue|mar|march    |p.m."
+        );
+        assert_eq!(
+            timestamp("J IDDD D ID W WW CC SSSSS"),
+            "2460375 065 3 2 1 10 21 50829"
+        );
+        assert_eq!(timestamp("TZ|tzh|TZM|OF|Tz"), "|+00|00|+00|Tz");
+        let bc = NaiveDate::from_ymd_opt(-56, 6, 1)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap();
+        assert_eq!(
+            to_char_timestamp(&bc, 0, None, "YYYY BC CC Y,YYY").unwrap(),
+            "0057 BC -01 0,057"
+        );
+        assert_eq!(
+            to_char_timestamp(&bc, 19800, Some("IST"), "TZ tz OF TZH:TZM").unwrap(),
+            "IST ist +05:30 +05:30"
+        );
+    }
+
+    #[test]
+    fn test_to_date() {
+        assert_eq!(date("05 Dec 2000", "DD Mon YYYY").unwrap(), "2000-12-05");
+        assert_eq!(date("20001205", "YYYYMMDD").unwrap(), "2000-12-05");
+        assert_eq!(date("3 4 21 01", "W MM CC YY").unwrap(), "2001-04-15");
+        assert_eq!(date("2458872", "J").unwrap(), "2020-01-23");
+        assert_eq!(date("44-02-01 BC", "YYYY-MM-DD BC").unwrap(), "-0043-02-01");
+        assert_eq!(date("2016 366", "YYYY DDD").unwrap(), "2016-12-31");
+        assert_eq!(date("2020 53 7", "IYYY IW ID").unwrap(), "2021-01-03");
+        assert_eq!(date("1,999", "Y,YYY").unwrap(), "1999-01-01");
+        assert_eq!(date("21 99", "CC YY").unwrap(), "2099-01-01");
+        assert_eq!(date("2024 1 IV", "YYYY DD RM").unwrap(), "2024-04-01");
+
+        let error = |input, template| date(input, template).unwrap_err().to_string();
+        assert_eq!(
+            error("2011 x12 x18", "YYYYxMMxDD"),
+            "invalid value \"x1\" for \"MM\": Value must be an integer."
+        );
+        assert_eq!(
+            error("2016-02-30", "YYYY-MM-DD"),
+            "date/time field value out of range: \"2016-02-30\""
+        );
+        assert_eq!(
+            error("12", "YYYYMM"),
+            "source string too short for \"YYYY\" formatting field: Field requires 4 characters, but only 2 remain."
+        );
+        assert_eq!(
+            error("xxx", "Mon"),
+            "invalid value \"xxx\" for \"Mon\": The given value did not match any of the allowed values for this field."
+        );
+        assert_eq!(
+            error("05 12 2000 06", "DD MM YYYY DD"),
+            "conflicting values for \"DD\" field in formatting string: This value contradicts a previous setting for the same field type."
+        );
+        assert_eq!(
+            error("2024 1", "IYYY MM"),
+            "invalid combination of date conventions: Do not mix Gregorian and ISO week date conventions in a formatting template."
+        );
+        assert_eq!(
+            error("1999 12 01 TZ", "YYYY MM DD TZ"),
+            "formatting field \"TZ\" is only supported in to_char"
+        );
+        assert_eq!(
+            error("5874898-01-01", "YYYY-MM-DD"),
+            "date out of range: \"5874898-01-01\""
+        );
+    }
+
+    #[test]
+    fn test_to_timestamp() {
+        assert_eq!(
+            timestamp_from("2011-12-18 11:38 PM", "YYYY-MM-DD HH12:MI PM").unwrap(),
+            "2011-12-18 23:38:00"
+        );
+        assert_eq!(
+            timestamp_from("2011-12-18 11:38 +05:30", "YYYY-MM-DD HH24:MI TZH:TZM").unwrap(),
+            "2011-12-18 06:08:00"
+        );
+        assert_eq!(
+            timestamp_from("2000 -10", "YYYY  TZH").unwrap(),
+            "1999-12-31 14:00:00"
+        );
+        assert_eq!(
+            timestamp_from("15:12:02.020.001230", "HH24:MI:SS.MS.US").unwrap(),
+            "0000-01-01 15:12:02.021230"
+        );
+        assert_eq!(
+            timestamp_from("2000January09Sunday", "YYYYFMMonthDDFMDay").unwrap(),
+            "2000-01-09 00:00:00"
+        );
+        assert_eq!(
+            timestamp_from("97/Feb/16", "FXYY/Mon/DD").unwrap(),
+            "1997-02-16 00:00:00"
+        );
+        assert_eq!(
+            timestamp_from("1,582nd VIII 21", "Y,YYYth FMRM DD").unwrap(),
+            "1582-08-21 00:00:00"
+        );
+        assert_eq!(
+            timestamp_from("2011-12-18 13:38 PM", "YYYY-MM-DD HH12:MI PM")
+                .unwrap_err()
+                .to_string(),
+            "hour \"13\" is invalid for the 12-hour clock: Use the 24-hour clock, or give an hour between 1 and 12."
+        );
+        assert_eq!(
+            timestamp_from("2011-12-18 11:38 +16", "YYYY-MM-DD HH24:MI TZH")
+                .unwrap_err()
+                .to_string(),
+            "time zone displacement out of range: \"2011-12-18 11:38 +16\""
+        );
+        assert_eq!(epoch_to_timestamp(1284352323.5).unwrap(), 1284352323500000);
+        assert_eq!(
+            epoch_to_timestamp(1e20).unwrap_err().to_string(),
+            "timestamp out of range: \"1e+20\""
+        );
+    }
+}
@@ -0,0 +1,748 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Offset, TimeZone};
+use datafusion::arrow::array::timezone::Tz;
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, Date32Array, Decimal128Array, StringBuilder,
+    TimestampMicrosecondArray,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType::{
+    Date32, Date64, Decimal128, Float32, Float64, Int16, Int32, Int64, Int8, Null, Timestamp,
+    UInt16, UInt32, UInt64, UInt8, Utf8,
+};
+use datafusion::arrow::datatypes::{
+    DataType, Decimal128Type, Float32Type, Float64Type, Int64Type, TimeUnit,
+    TimestampMicrosecondType, UInt64Type, DECIMAL128_MAX_PRECISION,
+};
+use datafusion::arrow::temporal_conversions::timestamp_us_to_datetime;
+use datafusion::common::{plan_err, DataFusionError, ExprSchema, ScalarValue};
+use datafusion::error::Result;
+use datafusion::functions::datetime;
+use datafusion::logical_expr::TypeSignature::Exact;
+use datafusion::logical_expr::{
+    ColumnarValue, Expr, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{exact_signatures, string_values, STRING_TYPES};
+use crate::postgres::formatting::{self, Number};
+use crate::postgres::string_udfs::execution_error;
+
+const FORMATTING_LINK: &str = "https://www.postgresql.org/docs/16/functions-formatting.html";
+
+/// Converts a number or a timestamp to text as a template describes, e.g. `FM9G999D00` or
+/// `YYYY-MM-DD HH24:MI`. Timestamps with a time zone are written in that time zone, and dates
+/// as midnight UTC.
+#[derive(Debug)]
+pub struct ToChar {
+    signature: Signature,
+}
+
+impl ToChar {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToChar {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_char"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if !is_formattable(&arg_types[0]) {
+            return plan_err!("to_char() cannot format values of type {}", arg_types[0]);
+        }
+        if !STRING_TYPES.contains(&arg_types[1]) && arg_types[1] != Null {
+            return plan_err!(
+                "the template of to_char() must be a string, not {}",
+                arg_types[1]
+            );
+        }
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let templates = cast(&args[1], &Utf8)?;
+        let templates = string_values(&templates)?;
+        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
+
+        match args[0].data_type() {
+            Timestamp(_, zone) => {
+                let timestamps = cast(&args[0], &Timestamp(TimeUnit::Microsecond, zone.clone()))?;
+                let zone = zone.as_deref().map(str::parse::<Tz>).transpose()?;
+                let timestamps = timestamps.as_primitive::<TimestampMicrosecondType>();
+                for (timestamp, template) in timestamps.iter().zip(templates) {
+                    let (Some(timestamp), Some(template)) = (timestamp, template) else {
+                        string_builder.append_null();
+                        continue;
+                    };
+                    string_builder.append_option(format_timestamp(timestamp, zone, template)?);
+                }
+            }
+            Date32 | Date64 => {
+                let utc = Some("+00:00".parse::<Tz>()?);
+                let timestamps = cast(&args[0], &Timestamp(TimeUnit::Microsecond, None))?;
+                let timestamps = timestamps.as_primitive::<TimestampMicrosecondType>();
+                for (timestamp, template) in timestamps.iter().zip(templates) {
+                    let (Some(timestamp), Some(template)) = (timestamp, template) else {
+                        string_builder.append_null();
+                        continue;
+                    };
+                    string_builder.append_option(format_timestamp(timestamp, utc, template)?);
+                }
+            }
+            Null => {
+                for _ in 0..args[0].len() {
+                    string_builder.append_null();
+                }
+            }
+            _ => {
+                for (number, template) in numbers(&args[0])?.into_iter().zip(templates) {
+                    let (Some(number), Some(template)) = (number, template) else {
+                        string_builder.append_null();
+                        continue;
+                    };
+                    let text =
+                        formatting::to_char_number(number, template).map_err(execution_error)?;
+                    string_builder.append_value(text);
+                }
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for ToChar {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_char(timestamp, text) → text",
+            description: "Converts a time stamp or a number to a string according to the given format; the number may be integer, bigint, numeric, real or double precision",
+            examples: &[
+                "to_char(timestamp '2002-04-20 17:31:12.66', 'HH12:MI:SS') → 05:31:12",
+                "to_char(-125.8, '999D99S') → 125.80-",
+            ],
+            link: Some(FORMATTING_LINK),
+        }
+    }
+}
+
+/// Converts text to a number as a template describes, e.g. `to_number('12,454.8-',
+/// '99G999D9S')` is `-12454.8`. The scale of the result is the number of digits the template
+/// has after the decimal point, so the template must be a constant.
+#[derive(Debug)]
+pub struct ToNumber {
+    signature: Signature,
+}
+
+impl ToNumber {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &STRING_TYPES]),
+                Volatility::Stable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToNumber {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_number"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Decimal128(DECIMAL128_MAX_PRECISION, 0))
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        _arg_types: &[DataType],
+    ) -> Result<DataType> {
+        match args.get(1) {
+            Some(Expr::Literal(ScalarValue::Utf8(Some(template)))) => Ok(Decimal128(
+                DECIMAL128_MAX_PRECISION,
+                number_scale(template)?,
+            )),
+            _ => Err(DataFusionError::Plan(
+                "the template argument to to_number() must be a constant string".to_string(),
+            )),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let template = match args.get(1) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(template)))) => template,
+            _ => {
+                return Err(DataFusionError::Execution(
+                    "the template argument to to_number() must be a constant string".to_string(),
+                ))
+            }
+        };
+        let scale = number_scale(template)?;
+        let args = ColumnarValue::values_to_arrays(&args[..1])?;
+
+        let numbers = string_values(&args[0])?
+            .into_iter()
+            .map(|input| match input {
+                Some(input) => formatting::to_number(input, template).map_err(execution_error),
+                None => Ok(None),
+            })
+            .collect::<Result<Decimal128Array>>()?
+            .with_precision_and_scale(DECIMAL128_MAX_PRECISION, scale)?;
+
+        Ok(ColumnarValue::Array(Arc::new(numbers) as ArrayRef))
+    }
+}
+
+impl Documented for ToNumber {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_number(text, text) → numeric",
+            description: "Converts string to numeric according to the given format",
+            examples: &["to_number('12,454.8-', '99G999D9S') → -12454.8"],
+            link: Some(FORMATTING_LINK),
+        }
+    }
+}
+
+/// Converts text to a date as a template describes, e.g. `to_date('05 Dec 2000', 'DD Mon
+/// YYYY')` is `2000-12-05`. With a single string or integer argument, which Postgres does not
+/// accept, it is DataFusion's `to_date`, which it replaces.
+#[derive(Debug)]
+pub struct ToDate {
+    signature: Signature,
+    builtin: Arc<ScalarUDF>,
+}
+
+impl ToDate {
+    pub fn new() -> Self {
+        let mut signatures = exact_signatures(&[&STRING_TYPES, &STRING_TYPES]);
+        signatures.extend([Exact(vec![Utf8]), Exact(vec![Int32]), Exact(vec![Int64])]);
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Stable),
+            builtin: datetime::to_date(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToDate {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_date"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types.len() == 1 {
+            return self.builtin.return_type(arg_types);
+        }
+        Ok(Date32)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        if args.len() == 1 {
+            return self.builtin.invoke(args);
+        }
+
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let inputs = string_values(&args[0])?;
+        let templates = string_values(&args[1])?;
+
+        let dates = inputs
+            .into_iter()
+            .zip(templates)
+            .map(|(input, template)| match (input, template) {
+                (Some(input), Some(template)) => formatting::to_date(input, template)
+                    .map(Some)
+                    .map_err(execution_error),
+                _ => Ok(None),
+            })
+            .collect::<Result<Date32Array>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(dates) as ArrayRef))
+    }
+}
+
+impl Documented for ToDate {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_date(text, text) → date",
+            description: "Converts string to date according to the given format",
+            examples: &["to_date('05 Dec 2000', 'DD Mon YYYY') → 2000-12-05"],
+            link: Some(FORMATTING_LINK),
+        }
+    }
+}
+
+/// Converts text to a timestamp with time zone as a template describes, or seconds since
+/// 1970-01-01 00:00:00 UTC to a timestamp. Times without a `TZH` field are in UTC. With a single
+/// string argument, which Postgres does not accept, it is DataFusion's `to_timestamp`, which it
+/// replaces.
+#[derive(Debug)]
+pub struct ToTimestamp {
+    signature: Signature,
+    builtin: Arc<ScalarUDF>,
+}
+
+impl ToTimestamp {
+    pub fn new() -> Self {
+        let mut signatures = exact_signatures(&[&STRING_TYPES, &STRING_TYPES]);
+        signatures.extend([Exact(vec![Float64]), Exact(vec![Utf8])]);
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Stable),
+            builtin: datetime::to_timestamp(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToTimestamp {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_timestamp"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if arg_types == [Utf8] {
+            return self.builtin.return_type(arg_types);
+        }
+        Ok(Timestamp(TimeUnit::Microsecond, Some("+00:00".into())))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        if args.len() == 1 && args[0].data_type() == Utf8 {
+            return self.builtin.invoke(args);
+        }
+
+        let args = ColumnarValue::values_to_arrays(args)?;
+
+        let timestamps = if args.len() == 1 {
+            args[0]
+                .as_primitive::<Float64Type>()
+                .iter()
+                .map(|seconds| {
+                    seconds
+                        .map(formatting::epoch_to_timestamp)
+                        .transpose()
+                        .map_err(execution_error)
+                })
+                .collect::<Result<TimestampMicrosecondArray>>()?
+        } else {
+            let inputs = string_values(&args[0])?;
+            let templates = string_values(&args[1])?;
+            inputs
+                .into_iter()
+                .zip(templates)
+                .map(|(input, template)| match (input, template) {
+                    (Some(input), Some(template)) => formatting::to_timestamp(input, template)
+                        .map(Some)
+                        .map_err(execution_error),
+                    _ => Ok(None),
+                })
+                .collect::<Result<TimestampMicrosecondArray>>()?
+        };
+
+        Ok(ColumnarValue::Array(
+            Arc::new(timestamps.with_timezone("+00:00")) as ArrayRef,
+        ))
+    }
+}
+
+impl Documented for ToTimestamp {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_timestamp(text, text) → timestamp with time zone",
+            description: "Converts string to time stamp according to the given format, or converts Unix epoch (seconds since 1970-01-01 00:00:00+00) to time stamp",
+            examples: &[
+                "to_timestamp('05 Dec 2000', 'DD Mon YYYY') → 2000-12-05 00:00:00+00",
+                "to_timestamp(1284352323) → 2010-09-13 04:32:03+00",
+            ],
+            link: Some(FORMATTING_LINK),
+        }
+    }
+}
+
+/// Whether to_char can format values of `data_type`.
+fn is_formattable(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        Int8 | Int16
+            | Int32
+            | Int64
+            | UInt8
+            | UInt16
+            | UInt32
+            | UInt64
+            | Float32
+            | Float64
+            | Decimal128(_, _)
+            | Timestamp(_, _)
+            | Date32
+            | Date64
+            | Null
+    )
+}
+
+/// Returns the numbers of an integer, float or decimal array.
+fn numbers(array: &ArrayRef) -> Result<Vec<Option<Number>>> {
+    Ok(match array.data_type() {
+        // Floats are formatted with the digits of Postgres' real and double precision types.
+        Float32 => array
+            .as_primitive::<Float32Type>()
+            .iter()
+            .map(|value| value.map(|value| Number::Float(value.into(), 6)))
+            .collect(),
+        Float64 => array
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|value| value.map(|value| Number::Float(value, 15)))
+            .collect(),
+        Decimal128(_, scale) => array
+            .as_primitive::<Decimal128Type>()
+            .iter()
+            .map(|value| value.map(|value| Number::Decimal(value, *scale)))
+            .collect(),
+        UInt64 => array
+            .as_primitive::<UInt64Type>()
+            .iter()
+            .map(|value| {
+                value.map(|value| match i64::try_from(value) {
+                    Ok(value) => Number::Integer(value),
+                    Err(_) => Number::Decimal(value.into(), 0),
+                })
+            })
+            .collect(),
+        _ => cast(array, &Int64)?
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|value| value.map(Number::Integer))
+            .collect(),
+    })
+}
+
+/// Formats a timestamp, in microseconds since 1970-01-01 00:00:00 UTC, in `zone` if it has one.
+fn format_timestamp(timestamp: i64, zone: Option<Tz>, template: &str) -> Result<Option<String>> {
+    let datetime = timestamp_us_to_datetime(timestamp)
+        .ok_or_else(|| DataFusionError::Execution("timestamp out of range".to_string()))?;
+    let Some(zone) = zone else {
+        return Ok(formatting::to_char_timestamp(&datetime, 0, None, template));
+    };
+    let offset = zone
+        .offset_from_utc_datetime(&datetime)
+        .fix()
+        .local_minus_utc();
+    let local = datetime + Duration::seconds(offset.into());
+    Ok(formatting::to_char_timestamp(
+        &local,
+        offset,
+        Some(&zone_abbreviation(offset)),
+        template,
+    ))
+}
+
+/// Returns the abbreviation `TZ` writes for a time zone: `UTC`, or the offset from UTC as the
+/// time zone database abbreviates zones without a name, e.g. `+04` or `-0930`.
+fn zone_abbreviation(offset: i32) -> String {
+    if offset == 0 {
+        return "UTC".to_string();
+    }
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.unsigned_abs() / 60;
+    match minutes % 60 {
+        0 => format!("{sign}{:02}", minutes / 60),
+        _ => format!("{sign}{:02}{:02}", minutes / 60, minutes % 60),
+    }
+}
+
+/// Returns the scale of the numbers to_number reads with `template`.
+fn number_scale(template: &str) -> Result<i8> {
+    Ok(formatting::to_number_scale(template)
+        .map_err(execution_error)?
+        .unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::register_postgres_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_to_char() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select to_char(timestamp '2002-04-20 17:31:12.66', 'HH12:MI:SS') as col_time, to_char(timestamp '2024-03-05 14:07:09', 'FMDay, DDth Mon YYYY') as col_names, to_char(date '2024-03-05', 'YYYY-MM-DD TZ') as col_date, to_char(-125.8, '999D99S') as col_float, to_char(485, 'FMRN') as col_roman, to_char(cast(1234.5 as decimal(10, 2)), 'FM9G999D00') as col_decimal",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+------------------------+----------------+-----------+-----------+-------------+
+| col_time | col_names              | col_date       | col_float | col_roman | col_decimal |
++----------+------------------------+----------------+-----------+-----------+-------------+
+| 05:31:12 | Tuesday, 05th Mar 2024 | 2024-03-05 UTC | 125.80-   | CDLXXXV   | 1,234.50    |
++----------+------------------------+----------------+-----------+-----------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select to_char(arrow_cast(to_timestamp('2024-03-05 14:07:09', 'YYYY-MM-DD HH24:MI:SS'), 'Timestamp(Microsecond, Some(\"+05:30\"))'), 'HH24:MI TZ OF') as col_zone, to_char(null, 'YYYY') as col_null, to_char(12, '') as col_empty",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------+----------+-----------+
+| col_zone           | col_null | col_empty |
++--------------------+----------+-----------+
+| 19:37 +0530 +05:30 |          |           |
++--------------------+----------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_char(12, '9.9.9')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("multiple decimal points"));
+
+        let result = ctx.sql("select to_char('12', '99')").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("to_char() cannot format values of type Utf8"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_to_number() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select to_number('12,454.8-', '99G999D9S') as col_sign, to_number('<564646.654564>', '999999.999999PR') as col_bracket, to_number('$1,234.56', 'L99,999.99') as col_currency, to_number('42nd', '99th') as col_ordinal",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------+----------------+--------------+-------------+
+| col_sign | col_bracket    | col_currency | col_ordinal |
++----------+----------------+--------------+-------------+
+| -12454.8 | -564646.654564 | 1234.56      | 42          |
++----------+----------------+--------------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_number('abc', '999')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid input syntax for type numeric: \" \""));
+
+        let result = ctx.sql("select to_number('12', concat('9', '9'))").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("the template argument to to_number() must be a constant string"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_to_date() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select to_date('05 Dec 2000', 'DD Mon YYYY') as col_names, to_date('20001205', 'YYYYMMDD') as col_digits, to_date('2020 53 7', 'IYYY IW ID') as col_iso, to_date(null, 'YYYY') as col_null",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+------------+------------+----------+
+| col_names  | col_digits | col_iso    | col_null |
++------------+------------+------------+----------+
+| 2000-12-05 | 2000-12-05 | 2021-01-03 |          |
++------------+------------+------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_date('2016-02-30', 'YYYY-MM-DD')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("date/time field value out of range: \"2016-02-30\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_to_timestamp() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select to_timestamp('05 Dec 2000', 'DD Mon YYYY') as col_date, to_timestamp('2011-12-18 11:38 PM +05:30', 'YYYY-MM-DD HH12:MI PM TZH:TZM') as col_zone, to_timestamp(1284352323.5) as col_epoch, to_timestamp(1284352323) as col_integer",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------------+----------------------+--------------------------+----------------------+
+| col_date             | col_zone             | col_epoch                | col_integer          |
++----------------------+----------------------+--------------------------+----------------------+
+| 2000-12-05T00:00:00Z | 2011-12-18T18:08:00Z | 2010-09-13T04:32:03.500Z | 2010-09-13T04:32:03Z |
++----------------------+----------------------+--------------------------+----------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_timestamp('2011-12-18 13:38 PM', 'YYYY-MM-DD HH12:MI PM')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("hour \"13\" is invalid for the 12-hour clock"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_one_argument_forms_are_datafusion_builtins() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select to_date('2024-01-01') as col_date, to_timestamp('2024-01-01T10:30:00') as col_timestamp, to_timestamp(1284352323) as col_epoch",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+---------------------+----------------------+
+| col_date   | col_timestamp       | col_epoch            |
++------------+---------------------+----------------------+
+| 2024-01-01 | 2024-01-01T10:30:00 | 2010-09-13T04:32:03Z |
++------------+---------------------+----------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,1053 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Float32Array, ListArray, StringArray,
+};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType::{
+    Boolean, FixedSizeList, Float32, Int32, LargeList, List, Utf8,
+};
+use datafusion::arrow::datatypes::{DataType, Field, Float32Type, Int32Type};
+use datafusion::common::{exec_err, ScalarValue};
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::Any;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{exact_signatures, string_values, STRING_TYPES};
+use crate::postgres::string_udfs::execution_error;
+use crate::postgres::tsearch::{
+    format_tsquery, format_tsvector, headline, matches, parse_tsquery, parse_tsvector,
+    phraseto_tsquery, plainto_tsquery, rank, rank_cover_density, rank_weights, to_tsquery,
+    to_tsvector, websearch_to_tsquery, Config, TsQuery, TsVector,
+};
+
+const TEXT_SEARCH_FUNCTIONS_LINK: &str =
+    "https://www.postgresql.org/docs/16/functions-textsearch.html";
+const TEXT_SEARCH_CONTROLS_LINK: &str =
+    "https://www.postgresql.org/docs/16/textsearch-controls.html";
+const TEXT_SEARCH_TYPES_LINK: &str = "https://www.postgresql.org/docs/16/datatype-textsearch.html";
+
+/// The configuration used when none is given, Postgres' usual `default_text_search_config`.
+const DEFAULT_CONFIG: Config = Config::English;
+
+/// Reads tsvectors from Postgres' text for them, e.g. `'fat':2A cat`, and writes them in their
+/// canonical form: lexemes sorted and quoted, each once, with sorted positions.
+#[derive(Debug)]
+pub struct PgTsvectorIn {
+    signature: Signature,
+}
+
+impl PgTsvectorIn {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&STRING_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgTsvectorIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_tsvector_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let vectors = tsvector_values(&args[0])?;
+        Ok(ColumnarValue::Array(tsvector_array(vectors)))
+    }
+}
+
+impl Documented for PgTsvectorIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_tsvector_in(text) → tsvector",
+            description: "Converts Postgres' text for a tsvector to a tsvector, whose lexemes are sorted and merged, and the positions of each sorted. tsvectors are written as this canonical text",
+            examples: &["pg_tsvector_in('a fat:2,1 cat:3A fat') → 'a' 'cat':3A 'fat':1,2"],
+            link: Some(TEXT_SEARCH_TYPES_LINK),
+        }
+    }
+}
+
+/// Reads tsqueries from Postgres' text for them, e.g. `fat & (rat | cat:*)`, and writes them in
+/// their canonical form, with quoted lexemes and only the parentheses needed.
+#[derive(Debug)]
+pub struct PgTsqueryIn {
+    signature: Signature,
+}
+
+impl PgTsqueryIn {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&STRING_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgTsqueryIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_tsquery_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let queries = tsquery_values(&args[0])?;
+        Ok(ColumnarValue::Array(tsquery_array(queries)))
+    }
+}
+
+impl Documented for PgTsqueryIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_tsquery_in(text) → tsquery",
+            description: "Converts Postgres' text for a tsquery to a tsquery. tsqueries are written as their canonical text, with quoted lexemes and only the parentheses needed",
+            examples: &["pg_tsquery_in('(fat & rat) <-> cat:*B') → ( 'fat' & 'rat' ) <-> 'cat':*B"],
+            link: Some(TEXT_SEARCH_TYPES_LINK),
+        }
+    }
+}
+
+/// Converts documents to tsvectors of their normalized lexemes, with the configuration named by
+/// the optional first argument, `english` by default.
+#[derive(Debug)]
+pub struct ToTsvector {
+    signature: Signature,
+}
+
+impl ToTsvector {
+    pub fn new() -> Self {
+        let mut signatures = exact_signatures(&[&STRING_TYPES]);
+        signatures.extend(exact_signatures(&[&STRING_TYPES, &STRING_TYPES]));
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToTsvector {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_tsvector"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let (configs, documents) = match args.as_slice() {
+            [config, document] => (config_values(config)?, string_values(document)?),
+            _ => (
+                vec![Some(DEFAULT_CONFIG); args[0].len()],
+                string_values(&args[0])?,
+            ),
+        };
+        let vectors = configs
+            .into_iter()
+            .zip(documents)
+            .map(|(config, document)| Some(to_tsvector(config?, document?)))
+            .collect();
+
+        Ok(ColumnarValue::Array(tsvector_array(vectors)))
+    }
+}
+
+impl Documented for ToTsvector {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_tsvector([config regconfig,] document text) → tsvector",
+            description: "Converts text to a tsvector, normalizing words according to the specified or default configuration. Position information is included in the result. The configurations are simple and english",
+            examples: &["to_tsvector('english', 'The Fat Rats') → 'fat':2 'rat':3"],
+            link: Some(TEXT_SEARCH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The functions that make a tsquery from text, each reading the text its own way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryParser {
+    /// Reads the text as a tsquery, and normalizes its lexemes.
+    Tsquery,
+    /// ANDs the words of the text.
+    Plain,
+    /// Joins the words of the text with `<->`, as a phrase.
+    Phrase,
+    /// Reads the text as a web search engine does, with quoted phrases, `or` and `-`.
+    Websearch,
+}
+
+impl QueryParser {
+    pub const ALL: [QueryParser; 4] = [
+        QueryParser::Tsquery,
+        QueryParser::Plain,
+        QueryParser::Phrase,
+        QueryParser::Websearch,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            QueryParser::Tsquery => "to_tsquery",
+            QueryParser::Plain => "plainto_tsquery",
+            QueryParser::Phrase => "phraseto_tsquery",
+            QueryParser::Websearch => "websearch_to_tsquery",
+        }
+    }
+
+    fn parse(self, config: Config, text: &str) -> anyhow::Result<TsQuery> {
+        match self {
+            QueryParser::Tsquery => to_tsquery(config, text),
+            QueryParser::Plain => plainto_tsquery(config, text),
+            QueryParser::Phrase => phraseto_tsquery(config, text),
+            QueryParser::Websearch => websearch_to_tsquery(config, text),
+        }
+    }
+}
+
+/// Converts text to a tsquery, normalizing its words with the configuration named by the
+/// optional first argument, `english` by default. Each of the functions reads the text its own
+/// way.
+#[derive(Debug)]
+pub struct ToTsquery {
+    signature: Signature,
+    parser: QueryParser,
+}
+
+impl ToTsquery {
+    pub fn new(parser: QueryParser) -> Self {
+        let mut signatures = exact_signatures(&[&STRING_TYPES]);
+        signatures.extend(exact_signatures(&[&STRING_TYPES, &STRING_TYPES]));
+        Self {
+            signature: Signature::one_of(signatures, Volatility::Immutable),
+            parser,
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToTsquery {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.parser.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let (configs, texts) = match args.as_slice() {
+            [config, text] => (config_values(config)?, string_values(text)?),
+            _ => (
+                vec![Some(DEFAULT_CONFIG); args[0].len()],
+                string_values(&args[0])?,
+            ),
+        };
+        let queries = configs
+            .into_iter()
+            .zip(texts)
+            .map(|(config, text)| match (config, text) {
+                (Some(config), Some(text)) => self
+                    .parser
+                    .parse(config, text)
+                    .map(Some)
+                    .map_err(execution_error),
+                _ => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(ColumnarValue::Array(tsquery_array(queries)))
+    }
+}
+
+impl Documented for ToTsquery {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (_, _, &[_]) = match self.parser {
+            QueryParser::Tsquery => (
+                "to_tsquery([config regconfig,] query text) → tsquery",
+                "Converts text to a tsquery, normalizing words according to the specified or default configuration. The words must be combined by valid tsquery operators",
+                &["to_tsquery('english', 'The & Fat & Rats') → 'fat' & 'rat'"],
+            ),
+            QueryParser::Plain => (
+                "plainto_tsquery([config regconfig,] query text) → tsquery",
+                "Converts text to a tsquery, normalizing words according to the specified or default configuration. Any punctuation in the string is ignored (it does not determine query operators). The resulting query matches documents containing all non-stopwords in the text",
+                &["plainto_tsquery('english', 'The Fat Rats') → 'fat' & 'rat'"],
+            ),
+            QueryParser::Phrase => (
+                "phraseto_tsquery([config regconfig,] query text) → tsquery",
+                "Converts text to a tsquery, normalizing words according to the specified or default configuration. Any punctuation in the string is ignored (it does not determine query operators). The resulting query matches phrases containing all non-stopwords in the text",
+                &["phraseto_tsquery('english', 'The Fat Rats') → 'fat' <-> 'rat'"],
+            ),
+            QueryParser::Websearch => (
+                "websearch_to_tsquery([config regconfig,] query text) → tsquery",
+                "Converts text to a tsquery, normalizing words according to the specified or default configuration. Quoted word sequences are converted to phrase tests. The word \"or\" is understood as producing an OR operator, and a dash produces a NOT operator; other punctuation is ignored. This approximates the behavior of some common web search tools",
+                &["websearch_to_tsquery('english', '\"fat rat\" -cat dog') → 'fat' <-> 'rat' & !'cat' & 'dog'"],
+            ),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(TEXT_SEARCH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The arguments of the functions behind Postgres' `@@` operator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchArguments {
+    /// A tsvector and a tsquery.
+    VectorQuery,
+    /// A tsquery and a tsvector.
+    QueryVector,
+    /// A document, converted with `to_tsvector`, and a tsquery.
+    TextQuery,
+    /// A document, converted with `to_tsvector`, and a query, converted with `plainto_tsquery`.
+    TextText,
+}
+
+impl MatchArguments {
+    pub const ALL: [MatchArguments; 4] = [
+        MatchArguments::VectorQuery,
+        MatchArguments::QueryVector,
+        MatchArguments::TextQuery,
+        MatchArguments::TextText,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            MatchArguments::VectorQuery => "ts_match_vq",
+            MatchArguments::QueryVector => "ts_match_qv",
+            MatchArguments::TextQuery => "ts_match_tq",
+            MatchArguments::TextText => "ts_match_tt",
+        }
+    }
+}
+
+/// Does a document match a query? These are the functions of Postgres' `@@` operator, for each
+/// of its pairs of argument types.
+#[derive(Debug)]
+pub struct TsMatch {
+    signature: Signature,
+    arguments: MatchArguments,
+}
+
+impl TsMatch {
+    pub fn new(arguments: MatchArguments) -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+            arguments,
+        }
+    }
+}
+
+impl ScalarUDFImpl for TsMatch {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.arguments.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let (vectors, queries) = match self.arguments {
+            MatchArguments::VectorQuery => (tsvector_values(&args[0])?, tsquery_values(&args[1])?),
+            MatchArguments::QueryVector => (tsvector_values(&args[1])?, tsquery_values(&args[0])?),
+            MatchArguments::TextQuery => (document_values(&args[0])?, tsquery_values(&args[1])?),
+            MatchArguments::TextText => {
+                let queries = string_values(&args[1])?
+                    .into_iter()
+                    .map(|text| {
+                        text.map(|text| plainto_tsquery(DEFAULT_CONFIG, text))
+                            .transpose()
+                            .map_err(execution_error)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                (document_values(&args[0])?, queries)
+            }
+        };
+        let matched = vectors
+            .iter()
+            .zip(&queries)
+            .map(|(vector, query)| Some(matches(vector.as_ref()?, query.as_ref()?)))
+            .collect::<BooleanArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(matched) as ArrayRef))
+    }
+}
+
+impl Documented for TsMatch {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (_, _, &[_]) = match self.arguments {
+            MatchArguments::VectorQuery => (
+                "ts_match_vq(tsvector, tsquery) → boolean",
+                "Does tsvector match tsquery? This is the tsvector @@ tsquery operator",
+                &["ts_match_vq(to_tsvector('fat cats ate rats'), to_tsquery('cat & rat')) → true"],
+            ),
+            MatchArguments::QueryVector => (
+                "ts_match_qv(tsquery, tsvector) → boolean",
+                "Does tsvector match tsquery? This is the tsquery @@ tsvector operator",
+                &["ts_match_qv(to_tsquery('cat & rat'), to_tsvector('fat cats ate rats')) → true"],
+            ),
+            MatchArguments::TextQuery => (
+                "ts_match_tq(text, tsquery) → boolean",
+                "Does the text string, after implicit invocation of to_tsvector(), match tsquery? This is the text @@ tsquery operator",
+                &["ts_match_tq('fat cats ate rats', to_tsquery('cat & rat')) → true"],
+            ),
+            MatchArguments::TextText => (
+                "ts_match_tt(text, text) → boolean",
+                "Does the text string, after implicit invocation of to_tsvector(), match the query string, after implicit invocation of plainto_tsquery()? This is the text @@ text operator",
+                &["ts_match_tt('fat cats ate rats', 'cat rat') → true"],
+            ),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(TEXT_SEARCH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Ranks how well documents match a query, as `ts_rank` does from the frequency of its lexemes,
+/// or as `ts_rank_cd` does from the density of its covers. The optional first argument weighs
+/// the positions of weights D, C, B and A, and the optional last one normalizes the rank by the
+/// length of the document.
+#[derive(Debug)]
+pub struct TsRank {
+    signature: Signature,
+    cover_density: bool,
+}
+
+impl TsRank {
+    pub fn new(cover_density: bool) -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3), Any(4)], Volatility::Immutable),
+            cover_density,
+        }
+    }
+}
+
+impl ScalarUDFImpl for TsRank {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        if self.cover_density {
+            "ts_rank_cd"
+        } else {
+            "ts_rank"
+        }
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float32)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let (weights, args) = match args[0].data_type() {
+            List(_) | LargeList(_) | FixedSizeList(_, _) => {
+                (Some(weight_values(&args[0])?), &args[1..])
+            }
+            _ => (None, &args[..]),
+        };
+        if args.len() < 2 || args.len() > 3 {
+            return exec_err!("{} expects a tsvector and a tsquery", self.name());
+        }
+        let vectors = tsvector_values(&args[0])?;
+        let queries = tsquery_values(&args[1])?;
+        let normalizations = match args.get(2) {
+            Some(normalizations) => cast(normalizations, &Int32)?
+                .as_primitive::<Int32Type>()
+                .iter()
+                .collect(),
+            None => vec![Some(0); vectors.len()],
+        };
+
+        let ranks = (0..vectors.len())
+            .map(|row| {
+                let weights = match &weights {
+                    Some(weights) => match &weights[row] {
+                        Some(weights) => Some(weights.as_slice()),
+                        None => return Ok(None),
+                    },
+                    None => None,
+                };
+                let (Some(vector), Some(query), Some(normalization)) =
+                    (&vectors[row], &queries[row], normalizations[row])
+                else {
+                    return Ok(None);
+                };
+                let weights = rank_weights(weights).map_err(execution_error)?;
+                Ok(Some(if self.cover_density {
+                    rank_cover_density(&weights, vector, query, normalization)
+                } else {
+                    rank(&weights, vector, query, normalization)
+                }))
+            })
+            .collect::<Result<Float32Array>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(ranks) as ArrayRef))
+    }
+}
+
+impl Documented for TsRank {
+    fn documentation(&self) -> Documentation {
+        if self.cover_density {
+            Documentation {
+                syntax: "ts_rank_cd([weights real[],] vector tsvector, query tsquery [, normalization integer]) → real",
+                description: "Computes a score showing how well the vector matches the query, using a cover density algorithm. The weights are those of D, C, B and A, by default {0.1, 0.2, 0.4, 1.0}. The normalization is a bit mask: 1 divides by 1 + log of the document length, 2 by its length, 4 by the mean harmonic distance between extents, 8 by the number of unique words, 16 by 1 + log of that number and 32 gives rank / (rank + 1)",
+                examples: &["ts_rank_cd(to_tsvector('raining cats and dogs'), 'cat') → 0.1"],
+                link: Some(TEXT_SEARCH_CONTROLS_LINK),
+            }
+        } else {
+            Documentation {
+                syntax: "ts_rank([weights real[],] vector tsvector, query tsquery [, normalization integer]) → real",
+                description: "Computes a score showing how well the vector matches the query, based on the frequency of its matching lexemes. The weights are those of D, C, B and A, by default {0.1, 0.2, 0.4, 1.0}. The normalization is a bit mask: 1 divides by 1 + log of the document length, 2 by its length, 8 by the number of unique words, 16 by 1 + log of that number and 32 gives rank / (rank + 1)",
+                examples: &["ts_rank(to_tsvector('raining cats and dogs'), 'cat') → 0.06079271"],
+                link: Some(TEXT_SEARCH_CONTROLS_LINK),
+            }
+        }
+    }
+}
+
+/// Displays the fragments of documents that match a query, with the matching words highlighted.
+/// The configuration is an optional first argument, `english` by default, and the options an
+/// optional last one; with three arguments, the first is the configuration when it is a constant
+/// naming one.
+#[derive(Debug)]
+pub struct TsHeadline {
+    signature: Signature,
+}
+
+impl TsHeadline {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3), Any(4)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for TsHeadline {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ts_headline"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let has_config = match args {
+            [_, _, _, _] => true,
+            [ColumnarValue::Scalar(config), _, _] => config_scalar(config).is_some(),
+            _ => false,
+        };
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let (configs, args) = if has_config {
+            (config_values(&args[0])?, &args[1..])
+        } else {
+            (vec![Some(DEFAULT_CONFIG); args[0].len()], &args[..])
+        };
+        let documents = string_values(&args[0])?;
+        let queries = tsquery_values(&args[1])?;
+        let options = match args.get(2) {
+            Some(options) => string_values(options)?,
+            None => vec![Some(""); documents.len()],
+        };
+
+        let headlines = (0..documents.len())
+            .map(|row| {
+                let (Some(config), Some(document), Some(query), Some(options)) =
+                    (configs[row], documents[row], &queries[row], options[row])
+                else {
+                    return Ok(None);
+                };
+                headline(config, document, query, options)
+                    .map(Some)
+                    .map_err(execution_error)
+            })
+            .collect::<Result<StringArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(headlines) as ArrayRef))
+    }
+}
+
+impl Documented for TsHeadline {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "ts_headline([config regconfig,] document text, query tsquery [, options text]) → text",
+            description: "Displays, in an abbreviated form, the match(es) for the query in the document, which must be raw text not a tsvector. Words in the document are normalized according to the specified or default configuration before matching to the query. The options are a comma-separated list of StartSel, StopSel, MaxWords, MinWords, ShortWord, HighlightAll, MaxFragments and FragmentDelimiter",
+            examples: &["ts_headline('The fat cat ate the rat.', 'cat') → The fat <b>cat</b> ate the rat."],
+            link: Some(TEXT_SEARCH_CONTROLS_LINK),
+        }
+    }
+}
+
+/// Returns the configuration a constant names, if it names one.
+fn config_scalar(value: &ScalarValue) -> Option<Config> {
+    match value {
+        ScalarValue::Utf8(Some(name)) | ScalarValue::LargeUtf8(Some(name)) => {
+            Config::from_name(name).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Reads the configurations named by a text argument.
+fn config_values(array: &ArrayRef) -> Result<Vec<Option<Config>>> {
+    string_values(array)?
+        .into_iter()
+        .map(|name| {
+            name.map(|name| Config::from_name(name).map_err(execution_error))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Reads tsvectors from their text.
+fn tsvector_values(array: &ArrayRef) -> Result<Vec<Option<TsVector>>> {
+    string_values(array)?
+        .into_iter()
+        .map(|text| {
+            text.map(|text| parse_tsvector(text).map_err(execution_error))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Converts documents to tsvectors with the default configuration.
+fn document_values(array: &ArrayRef) -> Result<Vec<Option<TsVector>>> {
+    Ok(string_values(array)?
+        .into_iter()
+        .map(|document| document.map(|document| to_tsvector(DEFAULT_CONFIG, document)))
+        .collect())
+}
+
+/// Reads tsqueries from their text.
+fn tsquery_values(array: &ArrayRef) -> Result<Vec<Option<TsQuery>>> {
+    string_values(array)?
+        .into_iter()
+        .map(|text| {
+            text.map(|text| parse_tsquery(text).map_err(execution_error))
+                .transpose()
+        })
+        .collect()
+}
+
+/// Reads the weight arrays of ts_rank and ts_rank_cd, which must be one-dimensional.
+fn weight_values(array: &ArrayRef) -> Result<Vec<Option<Vec<Option<f32>>>>> {
+    let (List(field) | LargeList(field) | FixedSizeList(field, _)) = array.data_type() else {
+        return exec_err!("expected an array of weights, not {}", array.data_type());
+    };
+    if let List(_) | LargeList(_) | FixedSizeList(_, _) = field.data_type() {
+        return exec_err!("array of weight must be one-dimensional");
+    }
+    let weights = cast(array, &List(Arc::new(Field::new("item", Float32, true))))?;
+    let weights: &ListArray = weights.as_list::<i32>();
+    Ok((0..weights.len())
+        .map(|row| {
+            weights.is_valid(row).then(|| {
+                weights
+                    .value(row)
+                    .as_primitive::<Float32Type>()
+                    .iter()
+                    .collect()
+            })
+        })
+        .collect())
+}
+
+fn tsvector_array(vectors: Vec<Option<TsVector>>) -> ArrayRef {
+    let texts = vectors
+        .iter()
+        .map(|vector| vector.as_ref().map(format_tsvector))
+        .collect::<StringArray>();
+    Arc::new(texts) as ArrayRef
+}
+
+fn tsquery_array(queries: Vec<Option<TsQuery>>) -> ArrayRef {
+    let texts = queries
+        .iter()
+        .map(|query| query.as_ref().map(format_tsquery))
+        .collect::<StringArray>();
+    Arc::new(texts) as ArrayRef
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::register_postgres_udfs;
+
+    use super::*;
+
+    const PRODUCTS: &str = "(values (1, 'Lightweight running shoes for trail and road'), (2, 'A pair of shoes that runs small'), (3, 'Running shorts with zip pocket'), (4, 'Dress shoes in brown leather'), (5, NULL)) as products(id, body)";
+
+    #[tokio::test]
+    async fn test_to_tsvector() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(&format!("select id, to_tsvector(body) as col_english, to_tsvector('simple', body) as col_simple from {PRODUCTS}"))
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+-----------------------------------------------------+--------------------------------------------------------------------------+
+| id | col_english                                         | col_simple                                                               |
++----+-----------------------------------------------------+--------------------------------------------------------------------------+
+| 1  | 'lightweight':1 'road':7 'run':2 'shoe':3 'trail':5 | 'and':6 'for':4 'lightweight':1 'road':7 'running':2 'shoes':3 'trail':5 |
+| 2  | 'pair':2 'run':6 'shoe':4 'small':7                 | 'a':1 'of':3 'pair':2 'runs':6 'shoes':4 'small':7 'that':5              |
+| 3  | 'pocket':5 'run':1 'short':2 'zip':4                | 'pocket':5 'running':1 'shorts':2 'with':3 'zip':4                       |
+| 4  | 'brown':4 'dress':1 'leather':5 'shoe':2            | 'brown':4 'dress':1 'in':3 'leather':5 'shoes':2                         |
+| 5  |                                                     |                                                                          |
++----+-----------------------------------------------------+--------------------------------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_tsvector('german', 'x')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("text search configuration \"german\" does not exist"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_to_tsquery() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select to_tsquery('english', 'shoe:* & !dress') as col_to, plainto_tsquery('Running, shoes!') as col_plain, phraseto_tsquery('simple', 'The running shoes') as col_phrase, websearch_to_tsquery('\"trail shoes\" -dress') as col_web")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------------------+----------------+---------------------------------+-------------------------------+
+| col_to              | col_plain      | col_phrase                      | col_web                       |
++---------------------+----------------+---------------------------------+-------------------------------+
+| 'shoe':* & !'dress' | 'run' & 'shoe' | 'the' <-> 'running' <-> 'shoes' | 'trail' <-> 'shoe' & !'dress' |
++---------------------+----------------+---------------------------------+-------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select to_tsquery('shoe &')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("no operand in tsquery: \"shoe &\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pg_tsvector_tsquery_in() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select pg_tsvector_in('a fat:2,1 cat:3A fat') as col_vector, pg_tsvector_in('b:3 a:2 c a:1') as col_sorted, pg_tsquery_in('!fat & (rat <-> cat:*B)') as col_query, pg_tsquery_in(NULL) as col_null")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------------------+-------------------+-----------------------------+----------+
+| col_vector             | col_sorted        | col_query                   | col_null |
++------------------------+-------------------+-----------------------------+----------+
+| 'a' 'cat':3A 'fat':1,2 | 'a':1,2 'b':3 'c' | !'fat' & 'rat' <-> 'cat':*B |          |
++------------------------+-------------------+-----------------------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select pg_tsvector_in('a:0')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("wrong position info in tsvector: \"a:0\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_match() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(&format!("select id from {PRODUCTS} where ts_match_vq(to_tsvector('english', body), plainto_tsquery('english', 'running shoes'))"))
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+
+| id |
++----+
+| 1  |
+| 2  |
++----+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(&format!("select id, ts_match_qv(to_tsquery('shoe & !dress'), to_tsvector(body)) as col_qv, ts_match_tq(body, to_tsquery('run <-> short')) as col_tq, ts_match_tt(body, 'brown leathers') as col_tt from {PRODUCTS}"))
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+--------+--------+--------+
+| id | col_qv | col_tq | col_tt |
++----+--------+--------+--------+
+| 1  | true   | false  | false  |
+| 2  | true   | false  | false  |
+| 3  | false  | true   | false  |
+| 4  | false  | false  | true   |
+| 5  |        |        |        |
++----+--------+--------+--------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_rank() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(&format!("select id, ts_rank(to_tsvector(body), to_tsquery('run | shoe')) as col_rank, ts_rank_cd(to_tsvector(body), to_tsquery('run | shoe'), 2) as col_rank_cd from {PRODUCTS}"))
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+-------------+-------------+
+| id | col_rank    | col_rank_cd |
++----+-------------+-------------+
+| 1  | 0.06079271  | 0.04        |
+| 2  | 0.06079271  | 0.05        |
+| 3  | 0.030396355 | 0.025       |
+| 4  | 0.030396355 | 0.025       |
+| 5  |             |             |
++----+-------------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql("select ts_rank(make_array(0.1, 0.2, 0.4, 1.0), 'run:1A shoe:2B,5', 'shoe') as col_weights, ts_rank(make_array(1, 1, 1, 0), 'run:1A shoe:2B,5', 'run & shoe') as col_zero, ts_rank_cd(make_array(0.5, 0.5, 0.5, 0.5), 'run:1A shoe:2B,5', 'run <-> shoe', 32) as col_cover, ts_rank('run:1A shoe:2B,5', 'shoe', 1) as col_normalized")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------+----------+------------+----------------+
+| col_weights | col_zero | col_cover  | col_normalized |
++-------------+----------+------------+----------------+
+| 0.25836906  | 0.0      | 0.33333334 | 0.12918453     |
++-------------+----------+------------+----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        for (sql, message) in [
+            (
+                "select ts_rank_cd(make_array(0.1, 0.2), 'a:1', 'a')",
+                "array of weight is too short",
+            ),
+            (
+                "select ts_rank(make_array(make_array(0.1, 0.2), make_array(0.4, 1.0)), 'a:1', 'a')",
+                "array of weight must be one-dimensional",
+            ),
+            (
+                "select ts_rank(make_array(0.1, 0.2, 0.4, 1.5), 'a:1', 'a')",
+                "weight out of range",
+            ),
+        ] {
+            let result = ctx.sql(sql).await?.collect().await;
+            assert!(result.unwrap_err().to_string().contains(message));
+        }
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_ts_headline() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(&format!("select id, ts_headline('english', body, to_tsquery('shoe'), 'StartSel=[, StopSel=]') as col_selected, ts_headline(body, to_tsquery('run & shoe'), 'MaxWords=4, MinWords=2') as col_short, ts_headline('simple', body, to_tsquery('simple', 'shoes')) as col_simple from {PRODUCTS}"))
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+------------------------------------------------+-------------------------------+-----------------------------------------------------+
+| id | col_selected                                   | col_short                     | col_simple                                          |
++----+------------------------------------------------+-------------------------------+-----------------------------------------------------+
+| 1  | Lightweight running [shoes] for trail and road | <b>running</b> <b>shoes</b>   | Lightweight running <b>shoes</b> for trail and road |
+| 2  | A pair of [shoes] that runs small              | <b>shoes</b> that <b>runs</b> | A pair of <b>shoes</b> that runs small              |
+| 3  | Running shorts with zip pocket                 | <b>Running</b> shorts         | Running shorts with zip pocket                      |
+| 4  | Dress [shoes] in brown leather                 | Dress <b>shoes</b>            | Dress <b>shoes</b> in brown leather                 |
+| 5  |                                                |                               |                                                     |
++----+------------------------------------------------+-------------------------------+-----------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select ts_headline('shoes', 'shoe', 'MinWords=0')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("MinWords should be positive"));
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,1231 @@
+//! Postgres' interval text: reading it as `interval_in` does, and writing it in each of the
+//! `IntervalStyle`s.
+//!
+//! Intervals are read in Postgres' own format, e.g. `1 year 2 mons 3 days 04:05:06`, its verbose
+//! format, e.g. `@ 1 hour ago`, the SQL standard's, e.g. `1-2 3 4:05:06`, and ISO 8601's, e.g.
+//! `P1Y2M3DT4H5M6S` or `P0001-02-03T04:05:06`, following `DecodeInterval` and
+//! `DecodeISO8601Interval`. They are written following `EncodeInterval`.
+
+use std::fmt::Write;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::postgres::datetime::{
+    Interval, IntervalFields, Unit, DAYS_PER_MONTH, UNITS, USECS_PER_DAY, USECS_PER_HOUR,
+    USECS_PER_MINUTE, USECS_PER_SEC,
+};
+
+/// The formats Postgres writes intervals in, which its `IntervalStyle` setting chooses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IntervalStyle {
+    /// `1 year 2 mons 3 days 04:05:06`, Postgres' default.
+    #[default]
+    Postgres,
+    /// `@ 1 year 2 mons 3 days 4 hours 5 mins 6 secs`.
+    PostgresVerbose,
+    /// `+1-2 +3 +4:05:06`, or `1-2` and `3 4:05:06` for the intervals the SQL standard has.
+    SqlStandard,
+    /// `P1Y2M3DT4H5M6S`.
+    Iso8601,
+}
+
+impl IntervalStyle {
+    /// Returns the name of the style, as `SET IntervalStyle` reads it.
+    pub fn name(self) -> &'static str {
+        match self {
+            IntervalStyle::Postgres => "postgres",
+            IntervalStyle::PostgresVerbose => "postgres_verbose",
+            IntervalStyle::SqlStandard => "sql_standard",
+            IntervalStyle::Iso8601 => "iso_8601",
+        }
+    }
+}
+
+impl FromStr for IntervalStyle {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Self> {
+        [
+            IntervalStyle::Postgres,
+            IntervalStyle::PostgresVerbose,
+            IntervalStyle::SqlStandard,
+            IntervalStyle::Iso8601,
+        ]
+        .into_iter()
+        .find(|style| style.name().eq_ignore_ascii_case(name))
+        .ok_or_else(|| {
+            anyhow!("invalid value for parameter \"IntervalStyle\": \"{name}\": Available values: postgres, postgres_verbose, sql_standard, iso_8601.")
+        })
+    }
+}
+
+/// Why an interval could not be read, which Postgres reports with the text of the interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParseError {
+    BadFormat,
+    Overflow,
+}
+
+type Parse<T> = std::result::Result<T, ParseError>;
+
+/// Reads an interval as `interval_in` does. In the `sql_standard` style, a leading `-` applies
+/// to every field when no other field has a sign, so `-1 2:03:04` is all negative.
+pub(crate) fn parse_interval(text: &str, style: IntervalStyle) -> Result<Interval> {
+    let fields = match tokenize(text).and_then(|fields| decode(&fields, style)) {
+        Err(ParseError::BadFormat) => decode_iso8601(text.as_bytes()),
+        fields => fields,
+    };
+    match fields {
+        Ok(fields) => fields
+            .to_interval()
+            .ok_or_else(|| anyhow!("interval out of range")),
+        Err(ParseError::BadFormat) => bail!("invalid input syntax for type interval: \"{text}\""),
+        Err(ParseError::Overflow) => bail!("interval field value out of range: \"{text}\""),
+    }
+}
+
+/// The fields an interval is read into, Postgres' `pg_itm_in`.
+#[derive(Debug, Default)]
+struct Fields {
+    years: i32,
+    months: i32,
+    days: i32,
+    micros: i64,
+}
+
+impl Fields {
+    fn to_interval(&self) -> Option<Interval> {
+        let months = i64::from(self.years) * 12 + i64::from(self.months);
+        Some(Interval {
+            months: i32::try_from(months).ok()?,
+            days: self.days,
+            micros: self.micros,
+        })
+    }
+
+    /// Adds `value` and `fraction` times `scale` microseconds.
+    fn add_micros(&mut self, value: i64, fraction: f64, scale: i64) -> Parse<()> {
+        self.micros = value
+            .checked_mul(scale)
+            .and_then(|micros| micros.checked_add(self.micros))
+            .ok_or(ParseError::Overflow)?;
+        self.add_fraction_micros(fraction, scale)
+    }
+
+    /// Adds `fraction` times `scale` microseconds, rounded to a microsecond.
+    fn add_fraction_micros(&mut self, fraction: f64, scale: i64) -> Parse<()> {
+        if fraction == 0.0 {
+            return Ok(());
+        }
+        let fraction = fraction * scale as f64;
+        let micros = fraction as i64;
+        let micros = micros + (fraction - micros as f64).round_ties_even() as i64;
+        self.micros = self
+            .micros
+            .checked_add(micros)
+            .ok_or(ParseError::Overflow)?;
+        Ok(())
+    }
+
+    fn add_days(&mut self, value: i64, scale: i32) -> Parse<()> {
+        self.days = i32::try_from(value)
+            .ok()
+            .and_then(|value| value.checked_mul(scale))
+            .and_then(|days| days.checked_add(self.days))
+            .ok_or(ParseError::Overflow)?;
+        Ok(())
+    }
+
+    /// Adds `fraction` times `scale` days, the fraction of a day as microseconds.
+    fn add_fraction_days(&mut self, fraction: f64, scale: i32) -> Parse<()> {
+        if fraction == 0.0 {
+            return Ok(());
+        }
+        let fraction = fraction * f64::from(scale);
+        let days = fraction as i32;
+        self.days = self.days.checked_add(days).ok_or(ParseError::Overflow)?;
+        self.add_fraction_micros(fraction - f64::from(days), USECS_PER_DAY)
+    }
+
+    fn add_months(&mut self, value: i64) -> Parse<()> {
+        self.months = i32::try_from(value)
+            .ok()
+            .and_then(|value| value.checked_add(self.months))
+            .ok_or(ParseError::Overflow)?;
+        Ok(())
+    }
+
+    fn add_years(&mut self, value: i64, scale: i32) -> Parse<()> {
+        self.years = i32::try_from(value)
+            .ok()
+            .and_then(|value| value.checked_mul(scale))
+            .and_then(|years| years.checked_add(self.years))
+            .ok_or(ParseError::Overflow)?;
+        Ok(())
+    }
+
+    /// Adds `fraction` times `scale` years, as months rounded to a month.
+    fn add_fraction_years(&mut self, fraction: f64, scale: i32) -> Parse<()> {
+        let months = (fraction * f64::from(scale) * 12.0).round_ties_even() as i32;
+        self.months = self
+            .months
+            .checked_add(months)
+            .ok_or(ParseError::Overflow)?;
+        Ok(())
+    }
+}
+
+/// The kinds of fields `ParseDateTime` splits a date, time or interval into.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    /// Digits, possibly with a fraction: `1`, `1.5` or `.5`.
+    Number,
+    /// Digits followed by a colon: `04:05:06`.
+    Time,
+    /// A date, or anything else with embedded punctuation: `1-2`, `2001-02-03` or `day2`.
+    Date,
+    /// A sign followed by digits: `-1`, `+04:05` or `-1-2`.
+    Zone,
+    /// Letters: `years` or `ago`.
+    String,
+    /// A sign followed by letters: `-infinity`.
+    Special,
+}
+
+/// The words of Postgres' `datetktbl` made of letters only. A word followed by a digit or a
+/// `+` starts a date unless it is one of these, so that `day2` is not read as `day 2`.
+const DATE_TOKENS: &[&str] = &[
+    "ad",
+    "allballs",
+    "am",
+    "apr",
+    "april",
+    "at",
+    "aug",
+    "august",
+    "bc",
+    "d",
+    "dec",
+    "december",
+    "dow",
+    "doy",
+    "dst",
+    "epoch",
+    "feb",
+    "february",
+    "fri",
+    "friday",
+    "h",
+    "infinity",
+    "isodow",
+    "isoyear",
+    "j",
+    "jan",
+    "january",
+    "jd",
+    "jul",
+    "julian",
+    "july",
+    "jun",
+    "june",
+    "m",
+    "mar",
+    "march",
+    "may",
+    "mm",
+    "mon",
+    "monday",
+    "nov",
+    "november",
+    "now",
+    "oct",
+    "october",
+    "on",
+    "pm",
+    "s",
+    "sat",
+    "saturday",
+    "sep",
+    "sept",
+    "september",
+    "sun",
+    "sunday",
+    "t",
+    "thu",
+    "thur",
+    "thurs",
+    "thursday",
+    "today",
+    "tomorrow",
+    "tue",
+    "tues",
+    "tuesday",
+    "wed",
+    "wednesday",
+    "weds",
+    "y",
+    "yesterday",
+];
+
+/// The most fields, and the most bytes of fields, Postgres reads.
+const MAX_FIELDS: usize = 25;
+const MAX_FIELD_BYTES: usize = 256;
+
+/// Whether a byte is a space to C's `isspace`.
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r')
+}
+
+/// Splits an interval into fields as Postgres' `ParseDateTime` does: letters are lowercased,
+/// spaces and other punctuation separate the fields.
+fn tokenize(text: &str) -> Parse<Vec<(FieldType, String)>> {
+    let bytes = text.as_bytes();
+    let peek = |position: usize| bytes.get(position).copied().unwrap_or(0);
+    let mut fields = vec![];
+    let mut used = 0;
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let byte = bytes[position];
+        if is_space(byte) {
+            position += 1;
+            continue;
+        }
+        if fields.len() >= MAX_FIELDS {
+            return Err(ParseError::BadFormat);
+        }
+        let start = position;
+        let field_type = if byte.is_ascii_digit() {
+            position += 1;
+            while peek(position).is_ascii_digit() {
+                position += 1;
+            }
+            match peek(position) {
+                b':' => {
+                    position += 1;
+                    while matches!(peek(position), b'0'..=b'9' | b':' | b'.') {
+                        position += 1;
+                    }
+                    FieldType::Time
+                }
+                delimiter @ (b'-' | b'/' | b'.') => {
+                    position += 1;
+                    if peek(position).is_ascii_digit() {
+                        while peek(position).is_ascii_digit() {
+                            position += 1;
+                        }
+                        // Only a second delimiter like the first makes a date of a number.
+                        if peek(position) == delimiter {
+                            while peek(position).is_ascii_digit() || peek(position) == delimiter {
+                                position += 1;
+                            }
+                            FieldType::Date
+                        } else if delimiter == b'.' {
+                            FieldType::Number
+                        } else {
+                            FieldType::Date
+                        }
+                    } else {
+                        while peek(position).is_ascii_alphanumeric() || peek(position) == delimiter
+                        {
+                            position += 1;
+                        }
+                        FieldType::Date
+                    }
+                }
+                _ => FieldType::Number,
+            }
+        } else if byte == b'.' {
+            position += 1;
+            while peek(position).is_ascii_digit() {
+                position += 1;
+            }
+            FieldType::Number
+        } else if byte.is_ascii_alphabetic() {
+            while peek(position).is_ascii_alphabetic() {
+                position += 1;
+            }
+            let word = text[start..position].to_ascii_lowercase();
+            let is_date = match peek(position) {
+                b'-' | b'/' | b'.' => true,
+                b'+' | b'0'..=b'9' => !DATE_TOKENS.contains(&truncate_token(&word)),
+                _ => false,
+            };
+            if is_date {
+                position += 1;
+                while matches!(peek(position), b'+' | b'-' | b'/' | b'_' | b'.' | b':')
+                    || peek(position).is_ascii_alphanumeric()
+                {
+                    position += 1;
+                }
+                FieldType::Date
+            } else {
+                FieldType::String
+            }
+        } else if byte == b'+' || byte == b'-' {
+            position += 1;
+            while is_space(peek(position)) {
+                position += 1;
+            }
+            // The spaces after the sign are not part of the field.
+            let sign = position;
+            if peek(position).is_ascii_digit() {
+                while matches!(peek(position), b'0'..=b'9' | b':' | b'.' | b'-') {
+                    position += 1;
+                }
+                let field = format!("{}{}", byte as char, &text[sign..position]);
+                used += field.len() + 1;
+                fields.push((FieldType::Zone, field));
+                continue;
+            } else if peek(position).is_ascii_alphabetic() {
+                while peek(position).is_ascii_alphabetic() {
+                    position += 1;
+                }
+                let field = format!("{}{}", byte as char, &text[sign..position]);
+                used += field.len() + 1;
+                fields.push((FieldType::Special, field.to_ascii_lowercase()));
+                continue;
+            } else {
+                return Err(ParseError::BadFormat);
+            }
+        } else if byte.is_ascii_punctuation() {
+            position += 1;
+            continue;
+        } else {
+            return Err(ParseError::BadFormat);
+        };
+
+        used += position - start + 1;
+        if used > MAX_FIELD_BYTES {
+            return Err(ParseError::BadFormat);
+        }
+        fields.push((field_type, text[start..position].to_ascii_lowercase()));
+    }
+    if used > MAX_FIELD_BYTES {
+        return Err(ParseError::BadFormat);
+    }
+    Ok(fields)
+}
+
+/// Returns the first 10 bytes of a token, which is all Postgres compares of them.
+fn truncate_token(token: &str) -> &str {
+    &token[..token.len().min(10)]
+}
+
+/// A bit of the mask of the fields an interval has read, so that none is read twice.
+fn unit_mask(unit: Unit) -> u32 {
+    1 << unit as u32
+}
+
+/// The fields a time of day sets.
+fn time_mask() -> u32 {
+    [
+        Unit::Hour,
+        Unit::Minute,
+        Unit::Second,
+        Unit::Millisecond,
+        Unit::Microsecond,
+    ]
+    .into_iter()
+    .map(unit_mask)
+    .sum()
+}
+
+/// What a number is read as: the unit after it, or the unit the field after it implies.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum NumberUnit {
+    /// No unit yet: a number is seconds.
+    None,
+    Unit(Unit),
+    /// After `ago`, a number without a unit is an error.
+    Invalid,
+}
+
+/// Reads the fields of an interval as Postgres' `DecodeInterval` does, from the last one so
+/// that the unit after a number is known when reading it.
+fn decode(fields: &[(FieldType, String)], style: IntervalStyle) -> Parse<Fields> {
+    let mut tm = Fields::default();
+    let force_negative = style == IntervalStyle::SqlStandard
+        && fields
+            .first()
+            .is_some_and(|(_, field)| field.starts_with('-'))
+        && !fields[1..]
+            .iter()
+            .any(|(_, field)| field.starts_with(['-', '+']));
+    let mut unit = NumberUnit::None;
+    let mut is_before = false;
+    let mut fmask = 0;
+
+    for (field_type, field) in fields.iter().rev() {
+        let tmask;
+        match field_type {
+            FieldType::Time => {
+                tm.micros = decode_time(field)?;
+                if force_negative && tm.micros > 0 {
+                    tm.micros = -tm.micros;
+                }
+                tmask = time_mask();
+                unit = NumberUnit::Unit(Unit::Day);
+            }
+            FieldType::Zone if field[1..].contains(':') && decode_time(&field[1..]).is_ok() => {
+                tm.micros = decode_time(&field[1..])?;
+                if field.starts_with('-') {
+                    tm.micros = -tm.micros;
+                }
+                if force_negative && tm.micros > 0 {
+                    tm.micros = -tm.micros;
+                }
+                tmask = time_mask();
+                // A number before a time is days, as in `1 +02:03`.
+                unit = NumberUnit::Unit(Unit::Day);
+            }
+            FieldType::Zone | FieldType::Date | FieldType::Number => {
+                let negative = field.starts_with('-');
+                let field_unit = match unit {
+                    NumberUnit::None => Unit::Second,
+                    NumberUnit::Unit(unit) => unit,
+                    NumberUnit::Invalid => return Err(ParseError::BadFormat),
+                };
+                let (mut value, rest) = parse_integer(field)?;
+                let (field_unit, mut fraction) = if let Some(rest) = rest.strip_prefix('-') {
+                    // The SQL standard's years-months.
+                    let (months, rest) = parse_integer(rest)?;
+                    if !(0..12).contains(&months) {
+                        return Err(ParseError::Overflow);
+                    }
+                    if !rest.is_empty() {
+                        return Err(ParseError::BadFormat);
+                    }
+                    let months = if negative { -months } else { months };
+                    value = value
+                        .checked_mul(12)
+                        .and_then(|value| value.checked_add(months))
+                        .ok_or(ParseError::Overflow)?;
+                    (Unit::Month, 0.0)
+                } else if rest.starts_with('.') {
+                    let fraction = parse_fraction(rest)?;
+                    (field_unit, if negative { -fraction } else { fraction })
+                } else if rest.is_empty() {
+                    (field_unit, 0.0)
+                } else {
+                    return Err(ParseError::BadFormat);
+                };
+                unit = NumberUnit::Unit(field_unit);
+                if force_negative {
+                    value = -value.abs();
+                    fraction = -fraction.abs();
+                }
+
+                tmask = match field_unit {
+                    Unit::Microsecond => {
+                        tm.add_micros(value, fraction, 1)?;
+                        unit_mask(Unit::Microsecond)
+                    }
+                    Unit::Millisecond => {
+                        tm.add_micros(value, fraction, 1000)?;
+                        unit_mask(Unit::Millisecond)
+                    }
+                    Unit::Second => {
+                        tm.add_micros(value, fraction, USECS_PER_SEC)?;
+                        // A fraction of a second sets the milliseconds and microseconds too.
+                        if fraction == 0.0 {
+                            unit_mask(Unit::Second)
+                        } else {
+                            unit_mask(Unit::Second)
+                                | unit_mask(Unit::Millisecond)
+                                | unit_mask(Unit::Microsecond)
+                        }
+                    }
+                    Unit::Minute => {
+                        tm.add_micros(value, fraction, USECS_PER_MINUTE)?;
+                        unit_mask(Unit::Minute)
+                    }
+                    Unit::Hour => {
+                        tm.add_micros(value, fraction, USECS_PER_HOUR)?;
+                        unit = NumberUnit::Unit(Unit::Day);
+                        unit_mask(Unit::Hour)
+                    }
+                    Unit::Day => {
+                        tm.add_days(value, 1)?;
+                        tm.add_fraction_micros(fraction, USECS_PER_DAY)?;
+                        unit_mask(Unit::Day)
+                    }
+                    Unit::Week => {
+                        tm.add_days(value, 7)?;
+                        tm.add_fraction_days(fraction, 7)?;
+                        unit_mask(Unit::Week)
+                    }
+                    Unit::Month => {
+                        tm.add_months(value)?;
+                        tm.add_fraction_days(fraction, DAYS_PER_MONTH)?;
+                        unit_mask(Unit::Month)
+                    }
+                    Unit::Year | Unit::Decade | Unit::Century | Unit::Millennium => {
+                        let scale = match field_unit {
+                            Unit::Year => 1,
+                            Unit::Decade => 10,
+                            Unit::Century => 100,
+                            _ => 1000,
+                        };
+                        tm.add_years(value, scale)?;
+                        tm.add_fraction_years(fraction, scale)?;
+                        unit_mask(field_unit)
+                    }
+                    _ => return Err(ParseError::BadFormat),
+                };
+            }
+            FieldType::String | FieldType::Special => {
+                let token = truncate_token(field);
+                if token == "ago" {
+                    is_before = true;
+                    unit = NumberUnit::Invalid;
+                } else {
+                    let (_, field_unit) = UNITS
+                        .iter()
+                        .find(|(name, _)| *name == token)
+                        .ok_or(ParseError::BadFormat)?;
+                    unit = NumberUnit::Unit(*field_unit);
+                }
+                tmask = 0;
+            }
+        }
+
+        if tmask & fmask != 0 {
+            return Err(ParseError::BadFormat);
+        }
+        fmask |= tmask;
+    }
+
+    if fmask == 0 {
+        return Err(ParseError::BadFormat);
+    }
+    if is_before {
+        tm.micros = tm.micros.checked_neg().ok_or(ParseError::Overflow)?;
+        tm.days = tm.days.checked_neg().ok_or(ParseError::Overflow)?;
+        tm.months = tm.months.checked_neg().ok_or(ParseError::Overflow)?;
+        tm.years = tm.years.checked_neg().ok_or(ParseError::Overflow)?;
+    }
+    Ok(tm)
+}
+
+/// Reads a time of an interval, `h:mm`, `h:mm:ss` or `mm:ss.fff`, as microseconds.
+fn decode_time(text: &str) -> Parse<i64> {
+    let (hour, rest) = parse_integer(text)?;
+    let rest = rest.strip_prefix(':').ok_or(ParseError::BadFormat)?;
+    let (minute, rest) = parse_int(rest)?;
+    let (hour, minute, second, micros) = if rest.is_empty() {
+        (hour, minute, 0, 0)
+    } else if rest.starts_with('.') {
+        // With a fraction, two fields are minutes and seconds.
+        let micros = parse_fractional_second(rest)?;
+        let minutes = i32::try_from(hour).map_err(|_| ParseError::Overflow)?;
+        (0, minutes, minute, micros)
+    } else if let Some(rest) = rest.strip_prefix(':') {
+        let (second, rest) = parse_int(rest)?;
+        let micros = if rest.starts_with('.') {
+            parse_fractional_second(rest)?
+        } else if rest.is_empty() {
+            0
+        } else {
+            return Err(ParseError::BadFormat);
+        };
+        (hour, minute, second, micros)
+    } else {
+        return Err(ParseError::BadFormat);
+    };
+
+    if hour < 0
+        || !(0..60).contains(&minute)
+        || !(0..=60).contains(&second)
+        || !(0..=USECS_PER_SEC).contains(&micros)
+    {
+        return Err(ParseError::Overflow);
+    }
+    hour.checked_mul(USECS_PER_HOUR)
+        .and_then(|micros| micros.checked_add(i64::from(minute) * USECS_PER_MINUTE))
+        .and_then(|micros| micros.checked_add(i64::from(second) * USECS_PER_SEC))
+        .and_then(|time| time.checked_add(micros))
+        .ok_or(ParseError::Overflow)
+}
+
+/// Reads a leading integer as C's `strtoll` does, an integer without digits being 0 and
+/// ending nothing. An integer out of range is an overflow.
+fn parse_integer(text: &str) -> Parse<(i64, &str)> {
+    let bytes = text.as_bytes();
+    let mut end = usize::from(
+        bytes
+            .first()
+            .is_some_and(|byte| matches!(byte, b'+' | b'-')),
+    );
+    let digits = end;
+    while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+        end += 1;
+    }
+    if end == digits {
+        return Ok((0, text));
+    }
+    let value = text[..end].parse().map_err(|_| ParseError::Overflow)?;
+    Ok((value, &text[end..]))
+}
+
+/// Reads a leading integer as `strtoint` does, an `int` rather than a `long long`.
+fn parse_int(text: &str) -> Parse<(i32, &str)> {
+    let (value, rest) = parse_integer(text)?;
+    let value = i32::try_from(value).map_err(|_| ParseError::Overflow)?;
+    Ok((value, rest))
+}
+
+/// Reads a leading number with a fraction and an exponent as C's `strtod` does, returning
+/// `None` if there is no number.
+fn parse_double(text: &str) -> Option<(f64, &str)> {
+    let bytes = text.as_bytes();
+    let digits_from = |start: usize| {
+        let mut end = start;
+        while bytes.get(end).is_some_and(u8::is_ascii_digit) {
+            end += 1;
+        }
+        end
+    };
+    let integer = digits_from(0);
+    let mut end = integer;
+    if bytes.get(end) == Some(&b'.') {
+        end = digits_from(end + 1);
+    }
+    if end == 0 || (end == 1 && integer == 0) {
+        return None;
+    }
+    if matches!(bytes.get(end), Some(b'e' | b'E')) {
+        let sign = end + 1 + usize::from(matches!(bytes.get(end + 1), Some(b'+' | b'-')));
+        let exponent = digits_from(sign);
+        if exponent > sign {
+            end = exponent;
+        }
+    }
+    Some((text[..end].parse().ok()?, &text[end..]))
+}
+
+/// Reads the fraction after a number, `.` alone being 0.
+fn parse_fraction(text: &str) -> Parse<f64> {
+    if text == "." {
+        return Ok(0.0);
+    }
+    match parse_double(text) {
+        Some((fraction, "")) => Ok(fraction),
+        _ => Err(ParseError::BadFormat),
+    }
+}
+
+/// Reads the fraction of a second of a time as microseconds.
+fn parse_fractional_second(text: &str) -> Parse<i64> {
+    match parse_double(text) {
+        Some((fraction, "")) => Ok((fraction * USECS_PER_SEC as f64).round_ties_even() as i64),
+        _ => Err(ParseError::BadFormat),
+    }
+}
+
+/// Reads a number of an ISO 8601 interval, which may be negative and may have a fraction,
+/// returning its integer part, its fraction with the same sign, and where it ends.
+fn parse_iso8601_number(bytes: &[u8], start: usize) -> Parse<(i64, f64, usize)> {
+    let negative = bytes.get(start) == Some(&b'-');
+    let sign = if negative { -1 } else { 1 };
+    let mut begin = start + usize::from(negative);
+    let mut end = begin;
+    let mut integer = 0;
+    let mut fraction = 0.0;
+    // The text is ASCII up to the number's end, so it can be sliced there.
+    let text = std::str::from_utf8(&bytes[begin..]).unwrap_or_else(|error| {
+        std::str::from_utf8(&bytes[begin..begin + error.valid_up_to()]).unwrap_or_default()
+    });
+    if text.starts_with(|c: char| c.is_ascii_digit()) {
+        let (value, rest) = parse_integer(text).map_err(|_| ParseError::BadFormat)?;
+        integer = value * sign;
+        end = begin + text.len() - rest.len();
+    }
+    if bytes.get(end) == Some(&b'.') {
+        if bytes.get(end + 1).is_some_and(u8::is_ascii_digit) {
+            let text = &text[end - begin..];
+            let (value, rest) = parse_double(text).ok_or(ParseError::BadFormat)?;
+            fraction = value * sign as f64;
+            end += text.len() - rest.len();
+        } else {
+            end += 1;
+            // A `.` without digits on either side is not a number.
+            begin += 1;
+        }
+    }
+    if end == begin {
+        return Err(ParseError::BadFormat);
+    }
+    Ok((integer, fraction, end))
+}
+
+/// Returns the number of digits of a number of an ISO 8601 interval, after its sign.
+fn iso8601_integer_width(bytes: &[u8], start: usize) -> usize {
+    let start = start + usize::from(bytes.get(start) == Some(&b'-'));
+    bytes[start..]
+        .iter()
+        .take_while(|byte| byte.is_ascii_digit())
+        .count()
+}
+
+/// Reads an ISO 8601 interval as Postgres' `DecodeISO8601Interval` does: with designators,
+/// `P1Y2M3DT4H5M6S`, or in the alternative format, `P0001-02-03T04:05:06` or
+/// `P00010203T040506`.
+fn decode_iso8601(bytes: &[u8]) -> Parse<Fields> {
+    let mut tm = Fields::default();
+    if bytes.len() < 2 || bytes[0] != b'P' {
+        return Err(ParseError::BadFormat);
+    }
+    let peek = |position: usize| bytes.get(position).copied().unwrap_or(0);
+    let mut date_part = true;
+    let mut have_field = false;
+    let mut position = 1;
+
+    while position < bytes.len() {
+        if bytes[position] == b'T' {
+            date_part = false;
+            have_field = false;
+            position += 1;
+            continue;
+        }
+        let field_start = position;
+        let (value, fraction, end) = parse_iso8601_number(bytes, position)?;
+        let unit = peek(end);
+        position = end + 1;
+
+        if date_part {
+            match unit {
+                b'Y' => {
+                    tm.add_years(value, 1)?;
+                    tm.add_fraction_years(fraction, 1)?;
+                }
+                b'M' => {
+                    tm.add_months(value)?;
+                    tm.add_fraction_days(fraction, DAYS_PER_MONTH)?;
+                }
+                b'W' => {
+                    tm.add_days(value, 7)?;
+                    tm.add_fraction_days(fraction, 7)?;
+                }
+                b'D' => {
+                    tm.add_days(value, 1)?;
+                    tm.add_fraction_micros(fraction, USECS_PER_DAY)?;
+                }
+                b'T' | 0 if iso8601_integer_width(bytes, field_start) == 8 && !have_field => {
+                    // The basic alternative format, YYYYMMDD.
+                    tm.add_years(value / 10000, 1)?;
+                    tm.add_months((value / 100) % 100)?;
+                    tm.add_days(value % 100, 1)?;
+                    tm.add_fraction_micros(fraction, USECS_PER_DAY)?;
+                    if unit == 0 {
+                        return Ok(tm);
+                    }
+                    date_part = false;
+                    have_field = false;
+                    continue;
+                }
+                b'T' | 0 | b'-' => {
+                    // The extended alternative format, YYYY-MM-DD.
+                    if have_field {
+                        return Err(ParseError::BadFormat);
+                    }
+                    tm.add_years(value, 1)?;
+                    tm.add_fraction_years(fraction, 1)?;
+                    if unit == 0 {
+                        return Ok(tm);
+                    }
+                    if unit == b'T' {
+                        date_part = false;
+                        have_field = false;
+                        continue;
+                    }
+
+                    let (value, fraction, end) = parse_iso8601_number(bytes, position)?;
+                    tm.add_months(value)?;
+                    tm.add_fraction_days(fraction, DAYS_PER_MONTH)?;
+                    position = end;
+                    match peek(position) {
+                        0 => return Ok(tm),
+                        b'T' => {
+                            date_part = false;
+                            have_field = false;
+                            continue;
+                        }
+                        b'-' => position += 1,
+                        _ => return Err(ParseError::BadFormat),
+                    }
+
+                    let (value, fraction, end) = parse_iso8601_number(bytes, position)?;
+                    tm.add_days(value, 1)?;
+                    tm.add_fraction_micros(fraction, USECS_PER_DAY)?;
+                    position = end;
+                    match peek(position) {
+                        0 => return Ok(tm),
+                        b'T' => {
+                            date_part = false;
+                            have_field = false;
+                            continue;
+                        }
+                        _ => return Err(ParseError::BadFormat),
+                    }
+                }
+                _ => return Err(ParseError::BadFormat),
+            }
+        } else {
+            match unit {
+                b'H' => tm.add_micros(value, fraction, USECS_PER_HOUR)?,
+                b'M' => tm.add_micros(value, fraction, USECS_PER_MINUTE)?,
+                b'S' => tm.add_micros(value, fraction, USECS_PER_SEC)?,
+                0 if iso8601_integer_width(bytes, field_start) == 6 && !have_field => {
+                    // The basic alternative format, HHMMSS.
+                    tm.add_micros(value / 10000, 0.0, USECS_PER_HOUR)?;
+                    tm.add_micros((value / 100) % 100, 0.0, USECS_PER_MINUTE)?;
+                    tm.add_micros(value % 100, 0.0, USECS_PER_SEC)?;
+                    tm.add_fraction_micros(fraction, 1)?;
+                    return Ok(tm);
+                }
+                0 | b':' => {
+                    // The extended alternative format, HH:MM:SS.
+                    if have_field {
+                        return Err(ParseError::BadFormat);
+                    }
+                    tm.add_micros(value, fraction, USECS_PER_HOUR)?;
+                    if unit == 0 {
+                        return Ok(tm);
+                    }
+
+                    let (value, fraction, end) = parse_iso8601_number(bytes, position)?;
+                    tm.add_micros(value, fraction, USECS_PER_MINUTE)?;
+                    position = end;
+                    match peek(position) {
+                        0 => return Ok(tm),
+                        b':' => position += 1,
+                        _ => return Err(ParseError::BadFormat),
+                    }
+
+                    let (value, fraction, end) = parse_iso8601_number(bytes, position)?;
+                    tm.add_micros(value, fraction, USECS_PER_SEC)?;
+                    return match peek(end) {
+                        0 => Ok(tm),
+                        _ => Err(ParseError::BadFormat),
+                    };
+                }
+                _ => return Err(ParseError::BadFormat),
+            }
+        }
+        have_field = true;
+    }
+    Ok(tm)
+}
+
+/// Writes an interval in `style`, as Postgres' `EncodeInterval` does.
+pub(crate) fn format_interval(interval: Interval, style: IntervalStyle) -> String {
+    let IntervalFields {
+        year: years,
+        month: months,
+        day: days,
+        hour: hours,
+        minute: minutes,
+        second: seconds,
+        micros,
+    } = IntervalFields::new(interval);
+    let mut text = String::new();
+    match style {
+        IntervalStyle::SqlStandard => {
+            let parts = [years, months, days, hours, minutes, seconds, micros];
+            let has_negative = parts.iter().any(|part| *part < 0);
+            let has_positive = parts.iter().any(|part| *part > 0);
+            let has_year_month = years != 0 || months != 0;
+            let has_day_time = parts[2..].iter().any(|part| *part != 0);
+            let is_standard = !(has_negative && has_positive || has_year_month && has_day_time);
+            let sign = |negative: bool| if negative { '-' } else { '+' };
+
+            if !has_negative && !has_positive {
+                text.push('0');
+            } else if !is_standard {
+                let time_negative = hours < 0 || minutes < 0 || seconds < 0 || micros < 0;
+                let _ = write!(
+                    text,
+                    "{}{}-{} {}{} {}{}:{:02}:",
+                    sign(years < 0 || months < 0),
+                    years.unsigned_abs(),
+                    months.unsigned_abs(),
+                    sign(days < 0),
+                    days.unsigned_abs(),
+                    sign(time_negative),
+                    hours.unsigned_abs(),
+                    minutes.unsigned_abs()
+                );
+                append_seconds(&mut text, seconds, micros, true);
+            } else {
+                // The interval is all negative or all positive, so its sign is written once.
+                if has_negative {
+                    text.push('-');
+                }
+                if has_year_month {
+                    let _ = write!(text, "{}-{}", years.abs(), months.abs());
+                } else if days != 0 {
+                    let _ = write!(text, "{} {}:{:02}:", days.abs(), hours.abs(), minutes.abs());
+                    append_seconds(&mut text, seconds, micros, true);
+                } else {
+                    let _ = write!(text, "{}:{:02}:", hours.abs(), minutes.abs());
+                    append_seconds(&mut text, seconds, micros, true);
+                }
+            }
+        }
+        IntervalStyle::Iso8601 => {
+            if interval.months == 0 && interval.days == 0 && interval.micros == 0 {
+                return "PT0S".to_string();
+            }
+            text.push('P');
+            for (value, designator) in [(years, 'Y'), (months, 'M'), (days, 'D')] {
+                if value != 0 {
+                    let _ = write!(text, "{value}{designator}");
+                }
+            }
+            if interval.micros != 0 {
+                text.push('T');
+            }
+            for (value, designator) in [(hours, 'H'), (minutes, 'M')] {
+                if value != 0 {
+                    let _ = write!(text, "{value}{designator}");
+                }
+            }
+            if seconds != 0 || micros != 0 {
+                if seconds < 0 || micros < 0 {
+                    text.push('-');
+                }
+                append_seconds(&mut text, seconds, micros, false);
+                text.push('S');
+            }
+        }
+        IntervalStyle::Postgres => {
+            let mut is_zero = true;
+            let mut is_before = false;
+            for (value, unit) in [(years, "year"), (months, "mon"), (days, "day")] {
+                if value == 0 {
+                    continue;
+                }
+                let _ = write!(
+                    text,
+                    "{}{}{value} {unit}{}",
+                    if is_zero { "" } else { " " },
+                    if is_before && value > 0 { "+" } else { "" },
+                    if value != 1 { "s" } else { "" }
+                );
+                is_before = value < 0;
+                is_zero = false;
+            }
+            if is_zero || interval.micros != 0 {
+                let minus = hours < 0 || minutes < 0 || seconds < 0 || micros < 0;
+                let _ = write!(
+                    text,
+                    "{}{}{:02}:{:02}:",
+                    if is_zero { "" } else { " " },
+                    if minus {
+                        "-"
+                    } else if is_before {
+                        "+"
+                    } else {
+                        ""
+                    },
+                    hours.unsigned_abs(),
+                    minutes.unsigned_abs()
+                );
+                append_seconds(&mut text, seconds, micros, true);
+            }
+        }
+        IntervalStyle::PostgresVerbose => {
+            let mut is_zero = true;
+            let mut is_before = false;
+            text.push('@');
+            for (value, unit) in [
+                (years, "year"),
+                (months, "mon"),
+                (days, "day"),
+                (hours, "hour"),
+                (minutes, "min"),
+            ] {
+                if value == 0 {
+                    continue;
+                }
+                // The first field sets the sign, which is written as "ago".
+                let value = if is_zero {
+                    is_before = value < 0;
+                    value.abs()
+                } else if is_before {
+                    -value
+                } else {
+                    value
+                };
+                let _ = write!(text, " {value} {unit}{}", if value == 1 { "" } else { "s" });
+                is_zero = false;
+            }
+            if seconds != 0 || micros != 0 {
+                text.push(' ');
+                if seconds < 0 || (seconds == 0 && micros < 0) {
+                    if is_zero {
+                        is_before = true;
+                    } else if !is_before {
+                        text.push('-');
+                    }
+                } else if is_before {
+                    text.push('-');
+                }
+                append_seconds(&mut text, seconds, micros, false);
+                let plural = seconds.abs() != 1 || micros != 0;
+                let _ = write!(text, " sec{}", if plural { "s" } else { "" });
+                is_zero = false;
+            }
+            if is_zero {
+                text.push_str(" 0");
+            }
+            if is_before {
+                text.push_str(" ago");
+            }
+        }
+    }
+    text
+}
+
+/// Writes the seconds of an interval without their sign, with their fraction of a second
+/// without trailing zeros, and with two digits at least if `fill_zeros`.
+fn append_seconds(text: &mut String, seconds: i64, micros: i64, fill_zeros: bool) {
+    if fill_zeros {
+        let _ = write!(text, "{:02}", seconds.unsigned_abs());
+    } else {
+        let _ = write!(text, "{}", seconds.unsigned_abs());
+    }
+    if micros != 0 {
+        let fraction = format!("{:06}", micros.unsigned_abs());
+        let _ = write!(text, ".{}", fraction.trim_end_matches('0'));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(text: &str, style: IntervalStyle) -> String {
+        match parse_interval(text, style) {
+            Ok(interval) => format_interval(interval, style),
+            Err(error) => error.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_styles() {
+        assert_eq!(
+            "SQL_Standard".parse::<IntervalStyle>().unwrap(),
+            IntervalStyle::SqlStandard
+        );
+        assert_eq!(
+            "iso".parse::<IntervalStyle>().unwrap_err().to_string(),
+            "invalid value for parameter \"IntervalStyle\": \"iso\": Available values: postgres, postgres_verbose, sql_standard, iso_8601."
+        );
+    }
+
+    #[test]
+    fn test_parse_interval() {
+        let cases = [
+            (
+                "1 year 2 mons 3 days 04:05:06",
+                "1 year 2 mons 3 days 04:05:06",
+            ),
+            ("P1Y2M3DT4H5M6S", "1 year 2 mons 3 days 04:05:06"),
+            ("P0001-02-03T04:05:06", "1 year 2 mons 3 days 04:05:06"),
+            ("P00010203T040506", "1 year 2 mons 3 days 04:05:06"),
+            ("1-2 3 4:05:06", "1 year 2 mons 3 days 04:05:06"),
+            ("@ 1 hour ago", "-01:00:00"),
+            ("1 day 2", "1 day 00:00:02"),
+            ("day 1", "00:00:01"),
+            ("1 +02:03", "1 day 02:03:00"),
+            ("-1 1:00:00", "-1 days +01:00:00"),
+            ("1 mon1 hour", "1 mon 01:00:00"),
+            ("1.5 years", "1 year 6 mons"),
+            ("1 week 2.5 days", "9 days 12:00:00"),
+            ("0.1 ms", "00:00:00.0001"),
+            ("1:2", "01:02:00"),
+            ("1:2.5", "00:01:02.5"),
+            ("1.5", "00:00:01.5"),
+            ("abc", "invalid input syntax for type interval: \"abc\""),
+            ("", "invalid input syntax for type interval: \"\""),
+            (
+                "1 2 days",
+                "invalid input syntax for type interval: \"1 2 days\"",
+            ),
+            (
+                "1 days 1 day",
+                "invalid input syntax for type interval: \"1 days 1 day\"",
+            ),
+            ("1 ago", "invalid input syntax for type interval: \"1 ago\""),
+            ("1:60", "interval field value out of range: \"1:60\""),
+            (
+                "99999999999 years",
+                "interval field value out of range: \"99999999999 years\"",
+            ),
+            ("178956971 years", "interval out of range"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(roundtrip(text, IntervalStyle::Postgres), expected, "{text}");
+        }
+
+        // A leading minus makes the whole interval negative in the SQL standard style only.
+        assert_eq!(
+            roundtrip("-1 2:03:04", IntervalStyle::Postgres),
+            "-1 days +02:03:04"
+        );
+        assert_eq!(
+            roundtrip("-1 2:03:04", IntervalStyle::SqlStandard),
+            "-1 2:03:04"
+        );
+    }
+
+    #[test]
+    fn test_format_interval() {
+        let cases = [
+            (
+                "1 year 2 mons 3 days 04:05:06.5",
+                [
+                    "1 year 2 mons 3 days 04:05:06.5",
+                    "@ 1 year 2 mons 3 days 4 hours 5 mins 6.5 secs",
+                    "+1-2 +3 +4:05:06.5",
+                    "P1Y2M3DT4H5M6.5S",
+                ],
+            ),
+            (
+                "-1 year -2 mons +3 days -04:05:06",
+                [
+                    "-1 years -2 mons +3 days -04:05:06",
+                    "@ 1 year 2 mons -3 days 4 hours 5 mins 6 secs ago",
+                    "-1-2 +3 -4:05:06",
+                    "P-1Y-2M3DT-4H-5M-6S",
+                ],
+            ),
+            ("1-2", ["1 year 2 mons", "@ 1 year 2 mons", "1-2", "P1Y2M"]),
+            (
+                "-3 days -1 hour",
+                [
+                    "-3 days -01:00:00",
+                    "@ 3 days 1 hour ago",
+                    "-3 1:00:00",
+                    "P-3DT-1H",
+                ],
+            ),
+            ("0", ["00:00:00", "@ 0", "0", "PT0S"]),
+        ];
+        let styles = [
+            IntervalStyle::Postgres,
+            IntervalStyle::PostgresVerbose,
+            IntervalStyle::SqlStandard,
+            IntervalStyle::Iso8601,
+        ];
+        for (text, expected) in cases {
+            let interval = parse_interval(text, IntervalStyle::Postgres).unwrap();
+            for (style, expected) in styles.into_iter().zip(expected) {
+                assert_eq!(format_interval(interval, style), expected, "{text}");
+            }
+        }
+    }
+}
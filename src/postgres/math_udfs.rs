@@ -15,6 +15,10 @@ use rand::thread_rng;
 use rand_distr::Normal;
 use rust_decimal::Decimal;
 
+use crate::catalog::{Documentation, Documented};
+
+const MATH_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/16/functions-math.html";
+
 /// Inverse cosine, result in degrees.
 #[derive(Debug)]
 pub struct Acosd {
@@ -73,6 +77,17 @@ impl ScalarUDFImpl for Acosd {
     }
 }
 
+impl Documented for Acosd {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "acosd(double precision) → double precision",
+            description: "Inverse cosine, result in degrees",
+            examples: &["acosd(0.5) → 60"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Cosine, argument in degrees.
 #[derive(Debug)]
 pub struct Cosd {
@@ -126,6 +141,17 @@ impl ScalarUDFImpl for Cosd {
     }
 }
 
+impl Documented for Cosd {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "cosd(double precision) → double precision",
+            description: "Cosine, argument in degrees",
+            examples: &["cosd(60) → 0.5"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Cotangent, argument in degrees.
 #[derive(Debug)]
 pub struct Cotd {
@@ -179,6 +205,17 @@ impl ScalarUDFImpl for Cotd {
     }
 }
 
+impl Documented for Cotd {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "cotd(double precision) → double precision",
+            description: "Cotangent, argument in degrees",
+            examples: &["cotd(45) → 1"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Inverse sine, result in degrees.
 #[derive(Debug)]
 pub struct Asind {
@@ -237,6 +274,17 @@ impl ScalarUDFImpl for Asind {
     }
 }
 
+impl Documented for Asind {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "asind(double precision) → double precision",
+            description: "Inverse sine, result in degrees",
+            examples: &["asind(0.5) → 30"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Sine, argument in degrees.
 #[derive(Debug)]
 pub struct Sind {
@@ -290,6 +338,17 @@ impl ScalarUDFImpl for Sind {
     }
 }
 
+impl Documented for Sind {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "sind(double precision) → double precision",
+            description: "Sine, argument in degrees",
+            examples: &["sind(30) → 0.5"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Inverse tangent, result in degrees.
 #[derive(Debug)]
 pub struct Atand {
@@ -343,6 +402,17 @@ impl ScalarUDFImpl for Atand {
     }
 }
 
+impl Documented for Atand {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "atand(double precision) → double precision",
+            description: "Inverse tangent, result in degrees",
+            examples: &["atand(1) → 45"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Tangent, argument in degrees.
 #[derive(Debug)]
 pub struct Tand {
@@ -396,6 +466,17 @@ impl ScalarUDFImpl for Tand {
     }
 }
 
+impl Documented for Tand {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "tand(double precision) → double precision",
+            description: "Tangent, argument in degrees",
+            examples: &["tand(45) → 1"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Nearest integer greater than or equal to argument (same as ceil).
 #[derive(Debug)]
 pub struct Ceiling {
@@ -443,6 +524,17 @@ impl ScalarUDFImpl for Ceiling {
     }
 }
 
+impl Documented for Ceiling {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "ceiling(numeric) → numeric",
+            description: "Nearest integer greater than or equal to argument (same as ceil)",
+            examples: &["ceiling(95.3) → 96"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Integer quotient of y/x (truncates towards zero)
 #[derive(Debug)]
 pub struct Div {
@@ -496,6 +588,17 @@ impl ScalarUDFImpl for Div {
     }
 }
 
+impl Documented for Div {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "div(y numeric, x numeric) → numeric",
+            description: "Integer quotient of y/x (truncates towards zero)",
+            examples: &["div(9, 4) → 2"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Error function
 #[derive(Debug)]
 pub struct Erf {
@@ -591,6 +694,17 @@ impl ScalarUDFImpl for Erf {
     }
 }
 
+impl Documented for Erf {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "erf(double precision) → double precision",
+            description: "Error function",
+            examples: &["erf(1.0) → 0.8427007929497149"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Complementary error function
 #[derive(Debug)]
 pub struct Erfc {
@@ -685,6 +799,17 @@ impl ScalarUDFImpl for Erfc {
     }
 }
 
+impl Documented for Erfc {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "erfc(double precision) → double precision",
+            description: "Complementary error function (1 - erf(x), without loss of precision for large inputs)",
+            examples: &["erfc(1.0) → 0.15729920705028513"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct RandomNormal {
     signature: Signature,
@@ -832,6 +957,17 @@ impl ScalarUDFImpl for RandomNormal {
     }
 }
 
+impl Documented for RandomNormal {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "random_normal([mean double precision [, stddev double precision]]) → double precision",
+            description: "Returns a random value from the normal distribution with the given parameters; mean defaults to 0.0 and stddev defaults to 1.0",
+            examples: &["random_normal(0.0, 1.0) → 0.051285419"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Mod {
     signature: Signature,
@@ -891,6 +1027,17 @@ impl ScalarUDFImpl for Mod {
     }
 }
 
+impl Documented for Mod {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "mod(y numeric_type, x numeric_type) → numeric_type",
+            description: "Remainder of y/x; available for smallint, integer, bigint, and numeric",
+            examples: &["mod(9, 4) → 1"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct Sign {
     signature: Signature,
@@ -984,6 +1131,17 @@ impl ScalarUDFImpl for Sign {
     }
 }
 
+impl Documented for Sign {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "sign(numeric) → numeric",
+            description: "Sign of the argument (-1, 0, or +1)",
+            examples: &["sign(-8.4) → -1"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct MinScale {
     signature: Signature,
@@ -1043,6 +1201,17 @@ impl ScalarUDFImpl for MinScale {
     }
 }
 
+impl Documented for MinScale {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "min_scale(numeric) → integer",
+            description: "Minimum scale (number of fractional decimal digits) needed to represent the supplied value precisely",
+            examples: &["min_scale(8.4100) → 2"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
 #[cfg(feature = "postgres")]
 #[cfg(test)]
 mod tests {
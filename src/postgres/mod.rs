@@ -1,10 +1,26 @@
 #![cfg(feature = "postgres")]
 #![allow(deprecated)]
 
+use std::cell::RefCell;
+
 use datafusion::error::Result;
-use datafusion::logical_expr::ScalarUDF;
 use datafusion::prelude::SessionContext;
 
+use crate::catalog::register_functions_table;
+use crate::postgres::array_udfs::{
+    ArrayDims, ArrayFill, ArrayLower, ArrayPositions, ArraySample, ArrayShuffle, ArrayToString,
+    ArrayUpper, Cardinality, PgArrayIn, PgArrayOut, TrimArray, Unnest,
+};
+use crate::postgres::datetime_udfs::{
+    Age, DateBin, DatePart, DateTrunc, IntervalIn, IntervalOut, Isfinite, JustifyDays,
+    JustifyHours, JustifyInterval, MakeDate, MakeInterval, MakeTime, MakeTimestamp,
+    MakeTimestamptz,
+};
+use crate::postgres::formatting_udfs::{ToChar, ToDate, ToNumber, ToTimestamp};
+use crate::postgres::fts_udfs::{
+    MatchArguments, PgTsqueryIn, PgTsvectorIn, QueryParser, ToTsquery, ToTsvector, TsHeadline,
+    TsMatch, TsRank,
+};
 use crate::postgres::math_udfs::{
     Acosd, Asind, Atand, Ceiling, Cosd, Cotd, Div, Erf, Erfc, MinScale, Mod, RandomNormal, Sign,
     Sind, Tand,
@@ -13,45 +29,238 @@ use crate::postgres::network_udfs::{
     Broadcast, Family, Host, HostMask, InetMerge, InetSameFamily, MaskLen, Netmask, Network,
     SetMaskLen,
 };
+use crate::postgres::range_udfs::{
+    Isempty, Lower, LowerInc, LowerInf, PgRangeIn, PgRangeOut, RangeAdjacent, RangeAgg,
+    RangeConstructor, RangeContainedBy, RangeContains, RangeIntersect, RangeIntersectAgg,
+    RangeMerge, RangeMinus, RangeOverlaps, RangeType, RangeUnion, Upper, UpperInc, UpperInf,
+};
+use crate::postgres::regex_udfs::{
+    RegexpCount, RegexpInstr, RegexpLike, RegexpMatch, RegexpMatches, RegexpReplace, RegexpSubstr,
+};
+use crate::postgres::string_udfs::{
+    Format, Initcap, Overlay, ParseIdent, QuoteIdent, QuoteLiteral, QuoteNullable,
+    RegexpSplitToArray, SplitPart, StringToArray, ToAscii, Unistr,
+};
+use crate::postgres::uuid_udfs::{
+    GenRandomUuid, PgUuidIn, PgUuidOut, UuidConstant, UuidExtractTimestamp, UuidExtractVersion,
+    UuidGenerateV1, UuidGenerateV3, UuidGenerateV5, UuidGenerateV7, UUID_CONSTANTS,
+};
+use crate::registry::{Dialect, RegisterOptions, Registrar};
 
+mod array;
+mod array_udfs;
+mod datetime;
+mod datetime_udfs;
+mod formatting;
+mod formatting_udfs;
+mod fts_udfs;
+mod interval;
 mod math_udfs;
 mod network_udfs;
+mod range;
+mod range_udfs;
+mod regex_udfs;
+mod regexp;
+mod string_udfs;
+mod text;
+mod tsearch;
+mod tsparser;
+mod uuid_udfs;
+
+pub use interval::IntervalStyle;
 
 pub fn register_postgres_udfs(ctx: &SessionContext) -> Result<()> {
-    register_network_udfs(ctx)?;
-    register_math_udfs(ctx)?;
-    Ok(())
-}
-
-fn register_math_udfs(ctx: &SessionContext) -> Result<()> {
-    ctx.register_udf(ScalarUDF::from(Acosd::new()));
-    ctx.register_udf(ScalarUDF::from(Cosd::new()));
-    ctx.register_udf(ScalarUDF::from(Cotd::new()));
-    ctx.register_udf(ScalarUDF::from(Asind::new()));
-    ctx.register_udf(ScalarUDF::from(Sind::new()));
-    ctx.register_udf(ScalarUDF::from(Atand::new()));
-    ctx.register_udf(ScalarUDF::from(Tand::new()));
-    ctx.register_udf(ScalarUDF::from(Ceiling::new()));
-    ctx.register_udf(ScalarUDF::from(Div::new()));
-    ctx.register_udf(ScalarUDF::from(Erf::new()));
-    ctx.register_udf(ScalarUDF::from(Erfc::new()));
-    ctx.register_udf(ScalarUDF::from(RandomNormal::new()));
-    ctx.register_udf(ScalarUDF::from(Mod::new()));
-    ctx.register_udf(ScalarUDF::from(Sign::new()));
-    ctx.register_udf(ScalarUDF::from(MinScale::new()));
-    Ok(())
-}
-
-fn register_network_udfs(ctx: &SessionContext) -> Result<()> {
-    ctx.register_udf(ScalarUDF::from(Broadcast::new()));
-    ctx.register_udf(ScalarUDF::from(Family::new()));
-    ctx.register_udf(ScalarUDF::from(Host::new()));
-    ctx.register_udf(ScalarUDF::from(HostMask::new()));
-    ctx.register_udf(ScalarUDF::from(InetSameFamily::new()));
-    ctx.register_udf(ScalarUDF::from(InetMerge::new()));
-    ctx.register_udf(ScalarUDF::from(MaskLen::new()));
-    ctx.register_udf(ScalarUDF::from(Netmask::new()));
-    ctx.register_udf(ScalarUDF::from(Network::new()));
-    ctx.register_udf(ScalarUDF::from(SetMaskLen::new()));
+    register_postgres_udfs_with_options(ctx, &RegisterOptions::default())
+}
+
+/// Registers the Postgres functions selected by `options`, e.g. under a `pg.` prefix.
+pub fn register_postgres_udfs_with_options(
+    ctx: &SessionContext,
+    options: &RegisterOptions,
+) -> Result<()> {
+    let functions = RefCell::new(vec![]);
+    register_all(&Registrar::new(
+        ctx,
+        &functions,
+        Dialect::Postgres,
+        options,
+    )?)?;
+    register_functions_table(ctx, functions.into_inner());
+    Ok(())
+}
+
+pub(crate) fn register_all(registrar: &Registrar) -> Result<()> {
+    register_network_udfs(&registrar.category("Network Address Functions"))?;
+    register_math_udfs(&registrar.category("Mathematics Functions"))?;
+    register_string_udfs(&registrar.category("String Functions"))?;
+    register_regex_udfs(&registrar.category("Pattern Matching Functions"))?;
+    register_formatting_udfs(&registrar.category("Data Type Formatting Functions"))?;
+    register_datetime_udfs(&registrar.category("Date/Time Functions"))?;
+    register_array_udfs(&registrar.category("Array Functions"))?;
+    register_range_udfs(&registrar.category("Range Functions"))?;
+    register_uuid_udfs(&registrar.category("UUID Functions"))?;
+    register_fts_udfs(&registrar.category("Text Search Functions"))?;
+    Ok(())
+}
+
+fn register_math_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Acosd::new());
+    registrar.register_udf(Cosd::new());
+    registrar.register_udf(Cotd::new());
+    registrar.register_udf(Asind::new());
+    registrar.register_udf(Sind::new());
+    registrar.register_udf(Atand::new());
+    registrar.register_udf(Tand::new());
+    registrar.register_udf(Ceiling::new());
+    registrar.register_udf(Div::new());
+    registrar.register_udf(Erf::new());
+    registrar.register_udf(Erfc::new());
+    registrar.register_udf(RandomNormal::new());
+    registrar.register_udf(Mod::new());
+    registrar.register_udf(Sign::new());
+    registrar.register_udf(MinScale::new());
+    Ok(())
+}
+
+fn register_network_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Broadcast::new());
+    registrar.register_udf(Family::new());
+    registrar.register_udf(Host::new());
+    registrar.register_udf(HostMask::new());
+    registrar.register_udf(InetSameFamily::new());
+    registrar.register_udf(InetMerge::new());
+    registrar.register_udf(MaskLen::new());
+    registrar.register_udf(Netmask::new());
+    registrar.register_udf(Network::new());
+    registrar.register_udf(SetMaskLen::new());
+    Ok(())
+}
+
+fn register_string_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Format::new());
+    registrar.register_udf(Initcap::new());
+    registrar.register_udf(Overlay::new());
+    registrar.register_udf(ParseIdent::new());
+    registrar.register_udf(QuoteIdent::new());
+    registrar.register_udf(QuoteLiteral::new());
+    registrar.register_udf(QuoteNullable::new());
+    registrar.register_udf(RegexpSplitToArray::new());
+    registrar.register_udf(SplitPart::new());
+    registrar.register_udf(StringToArray::new());
+    registrar.register_udf(ToAscii::new());
+    registrar.register_udf(Unistr::new());
+    Ok(())
+}
+
+fn register_regex_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(RegexpCount::new());
+    registrar.register_udf(RegexpInstr::new());
+    registrar.register_udf(RegexpLike::new());
+    registrar.register_udf(RegexpMatch::new());
+    registrar.register_udtf("regexp_matches", RegexpMatches);
+    registrar.register_udf(RegexpReplace::new());
+    registrar.register_udf(RegexpSubstr::new());
+    Ok(())
+}
+
+fn register_formatting_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(ToChar::new());
+    registrar.register_udf(ToDate::new());
+    registrar.register_udf(ToNumber::new());
+    registrar.register_udf(ToTimestamp::new());
+    Ok(())
+}
+
+fn register_datetime_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Age::new());
+    registrar.register_udf(DateBin::new());
+    registrar.register_udf(DatePart::new());
+    registrar.register_udf(DateTrunc::new());
+    registrar.register_udf(IntervalIn::new(registrar.interval_style()));
+    registrar.register_udf(IntervalOut::new(registrar.interval_style()));
+    registrar.register_udf(Isfinite::new());
+    registrar.register_udf(JustifyDays::new());
+    registrar.register_udf(JustifyHours::new());
+    registrar.register_udf(JustifyInterval::new());
+    registrar.register_udf(MakeDate::new());
+    registrar.register_udf(MakeInterval::new());
+    registrar.register_udf(MakeTime::new());
+    registrar.register_udf(MakeTimestamp::new());
+    registrar.register_udf(MakeTimestamptz::new());
+    Ok(())
+}
+
+fn register_array_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(ArrayDims::new());
+    registrar.register_udf(ArrayFill::new());
+    registrar.register_udf(ArrayLower::new());
+    registrar.register_udf(ArrayPositions::new());
+    registrar.register_udf(ArraySample::new(registrar.random_seed()));
+    registrar.register_udf(ArrayShuffle::new(registrar.random_seed()));
+    registrar.register_udf(ArrayToString::new());
+    registrar.register_udf(ArrayUpper::new());
+    registrar.register_udf(Cardinality::new());
+    registrar.register_udf(PgArrayIn::new(registrar.interval_style()));
+    registrar.register_udf(PgArrayOut::new(registrar.interval_style()));
+    registrar.register_udf(TrimArray::new());
+    registrar.register_udtf("unnest", Unnest);
+    Ok(())
+}
+
+fn register_range_udfs(registrar: &Registrar) -> Result<()> {
+    for range_type in RangeType::ALL {
+        registrar.register_udf(RangeConstructor::new(range_type));
+    }
+    registrar.register_udf(Isempty::new());
+    registrar.register_udf(Lower::new());
+    registrar.register_udf(LowerInc::new());
+    registrar.register_udf(LowerInf::new());
+    registrar.register_udf(PgRangeIn::new());
+    registrar.register_udf(PgRangeOut::new());
+    registrar.register_udf(RangeAdjacent::new());
+    registrar.register_udaf(RangeAgg::new());
+    registrar.register_udf(RangeContainedBy::new());
+    registrar.register_udf(RangeContains::new());
+    registrar.register_udf(RangeIntersect::new());
+    registrar.register_udaf(RangeIntersectAgg::new());
+    registrar.register_udf(RangeMerge::new());
+    registrar.register_udf(RangeMinus::new());
+    registrar.register_udf(RangeOverlaps::new());
+    registrar.register_udf(RangeUnion::new());
+    registrar.register_udf(Upper::new());
+    registrar.register_udf(UpperInc::new());
+    registrar.register_udf(UpperInf::new());
+    Ok(())
+}
+
+fn register_uuid_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(GenRandomUuid::new(registrar.random_seed()));
+    registrar.register_udf(PgUuidIn::new());
+    registrar.register_udf(PgUuidOut::new());
+    for (name, uuid) in UUID_CONSTANTS {
+        registrar.register_udf(UuidConstant::new(name, uuid));
+    }
+    registrar.register_udf(UuidExtractTimestamp::new());
+    registrar.register_udf(UuidExtractVersion::new());
+    registrar.register_udf(UuidGenerateV1::new(registrar.random_seed()));
+    registrar.register_udf(UuidGenerateV3::new());
+    registrar.register_udf(UuidGenerateV5::new());
+    registrar.register_udf(UuidGenerateV7::new(registrar.random_seed()));
+    Ok(())
+}
+
+fn register_fts_udfs(registrar: &Registrar) -> Result<()> {
+    for arguments in MatchArguments::ALL {
+        registrar.register_udf(TsMatch::new(arguments));
+    }
+    registrar.register_udf(PgTsqueryIn::new());
+    registrar.register_udf(PgTsvectorIn::new());
+    for parser in QueryParser::ALL {
+        registrar.register_udf(ToTsquery::new(parser));
+    }
+    registrar.register_udf(ToTsvector::new());
+    registrar.register_udf(TsHeadline::new());
+    registrar.register_udf(TsRank::new(false));
+    registrar.register_udf(TsRank::new(true));
     Ok(())
 }
@@ -2,14 +2,21 @@ use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::str::FromStr;
 use std::sync::Arc;
 
-use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, StringBuilder, UInt8Array};
+use datafusion::arrow::array::{Array, ArrayRef, BooleanArray, UInt8Array};
 use datafusion::arrow::datatypes::DataType;
-use datafusion::arrow::datatypes::DataType::{Boolean, Int64, UInt8, Utf8};
+use datafusion::arrow::datatypes::DataType::{Boolean, Int64, UInt8};
 use datafusion::common::DataFusionError;
 use datafusion::error::Result;
 use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
 use ipnet::{IpNet, Ipv4Net, Ipv6Net};
 
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{
+    exact_signatures, string_return_type, string_values, StringOutput, STRING_TYPES,
+};
+
+const NETWORK_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/16/functions-net.html";
+
 /// Gives the broadcast address for the network.
 /// Returns NULL for columns with NULL values.
 #[derive(Debug)]
@@ -20,7 +27,7 @@ pub struct Broadcast {
 impl Broadcast {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -38,15 +45,16 @@ impl ScalarUDFImpl for Broadcast {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let broadcast_address = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -63,9 +71,18 @@ impl ScalarUDFImpl for Broadcast {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Broadcast {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "broadcast(inet) → inet",
+            description: "Broadcast address for network",
+            examples: &["broadcast('192.168.1.5/24') → 192.168.1.255/24"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -79,7 +96,7 @@ pub struct Family {
 impl Family {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -104,8 +121,8 @@ impl ScalarUDFImpl for Family {
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
         let mut int8array = UInt8Array::builder(args[0].len());
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let family = if ip_string.parse::<Ipv4Net>().is_ok() {
                     4
@@ -131,6 +148,17 @@ impl ScalarUDFImpl for Family {
     }
 }
 
+impl Documented for Family {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "family(inet) → int",
+            description: "Extract family of address; 4 for IPv4, 6 for IPv6",
+            examples: &["family('::1') → 6"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Gives the host address for the network.
 /// Returns NULL for columns with NULL values.
 #[derive(Debug)]
@@ -141,7 +169,7 @@ pub struct Host {
 impl Host {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -159,15 +187,16 @@ impl ScalarUDFImpl for Host {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let host_address = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -184,9 +213,18 @@ impl ScalarUDFImpl for Host {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Host {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "host(inet) → text",
+            description: "Extract IP address as text",
+            examples: &["host('192.168.1.5/24') → 192.168.1.5"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -200,7 +238,7 @@ pub struct HostMask {
 impl HostMask {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -218,15 +256,16 @@ impl ScalarUDFImpl for HostMask {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let hostmask = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -243,9 +282,18 @@ impl ScalarUDFImpl for HostMask {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for HostMask {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "hostmask(inet) → inet",
+            description: "Construct host mask for network",
+            examples: &["hostmask('192.168.23.20/30') → 0.0.0.3"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -259,7 +307,7 @@ pub struct InetSameFamily {
 impl InetSameFamily {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(2, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(2, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -284,8 +332,8 @@ impl ScalarUDFImpl for InetSameFamily {
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
         let mut boolean_array = BooleanArray::builder(args[0].len());
-        let first_inputs = datafusion::common::cast::as_string_array(&args[0])?;
-        let second_inputs = datafusion::common::cast::as_string_array(&args[1])?;
+        let first_inputs = string_values(&args[0])?;
+        let second_inputs = string_values(&args[1])?;
 
         if first_inputs.len() != second_inputs.len() {
             return Err(DataFusionError::Internal(
@@ -294,8 +342,8 @@ impl ScalarUDFImpl for InetSameFamily {
         }
 
         first_inputs
-            .iter()
-            .zip(second_inputs.iter())
+            .into_iter()
+            .zip(second_inputs)
             .try_for_each(|(first, second)| {
                 if let (Some(first), Some(second)) = (first, second) {
                     let first_ip = IpAddr::from_str(first)
@@ -331,6 +379,17 @@ impl ScalarUDFImpl for InetSameFamily {
     }
 }
 
+impl Documented for InetSameFamily {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "inet_same_family(inet, inet) → boolean",
+            description: "Are the addresses from the same family?",
+            examples: &["inet_same_family('192.168.1.5/24', '::1') → false"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Returns the smallest network which includes both of the given networks.
 /// Returns NULL if any of the columns contain NULL values.
 #[derive(Debug)]
@@ -341,7 +400,7 @@ pub struct InetMerge {
 impl InetMerge {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(2, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(2, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -359,15 +418,16 @@ impl ScalarUDFImpl for InetMerge {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let first_inputs = datafusion::common::cast::as_string_array(&args[0])?;
-        let second_inputs = datafusion::common::cast::as_string_array(&args[1])?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let first_inputs = string_values(&args[0])?;
+        let second_inputs = string_values(&args[1])?;
 
         if first_inputs.len() != second_inputs.len() {
             return Err(DataFusionError::Internal(
@@ -376,8 +436,8 @@ impl ScalarUDFImpl for InetMerge {
         }
 
         first_inputs
-            .iter()
-            .zip(second_inputs.iter())
+            .into_iter()
+            .zip(second_inputs)
             .try_for_each(|(first, second)| {
                 if let (Some(first), Some(second)) = (first, second) {
                     let first_net = if !first.contains('/') {
@@ -479,9 +539,18 @@ impl ScalarUDFImpl for InetMerge {
                 }
             })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for InetMerge {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "inet_merge(inet, inet) → cidr",
+            description: "The smallest network which includes both of the given networks",
+            examples: &["inet_merge('192.168.1.5/24', '192.168.2.5/24') → 192.168.0.0/22"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -495,7 +564,7 @@ pub struct MaskLen {
 impl MaskLen {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -520,8 +589,8 @@ impl ScalarUDFImpl for MaskLen {
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
         let mut int8array = UInt8Array::builder(args[0].len());
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let prefix_len = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -544,6 +613,17 @@ impl ScalarUDFImpl for MaskLen {
     }
 }
 
+impl Documented for MaskLen {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "masklen(inet) → int",
+            description: "Extract netmask length",
+            examples: &["masklen('192.168.1.5/24') → 24"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// Constructs netmask for network.
 /// Returns NULL for columns with NULL values.
 #[derive(Debug)]
@@ -554,7 +634,7 @@ pub struct Netmask {
 impl Netmask {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -572,15 +652,16 @@ impl ScalarUDFImpl for Netmask {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let netmask = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -597,9 +678,18 @@ impl ScalarUDFImpl for Netmask {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Netmask {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "netmask(inet) → inet",
+            description: "Construct netmask for network",
+            examples: &["netmask('192.168.1.5/24') → 255.255.255.0"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -613,7 +703,7 @@ pub struct Network {
 impl Network {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -631,15 +721,16 @@ impl ScalarUDFImpl for Network {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let ip_string = datafusion::common::cast::as_string_array(&args[0])?;
-        ip_string.iter().try_for_each(|ip_string| {
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let ip_string = string_values(&args[0])?;
+        ip_string.into_iter().try_for_each(|ip_string| {
             if let Some(ip_string) = ip_string {
                 let network = IpNet::from_str(ip_string)
                     .map_err(|e| {
@@ -656,9 +747,18 @@ impl ScalarUDFImpl for Network {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Network {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "network(inet) → cidr",
+            description: "Extract network part of address",
+            examples: &["network('192.168.1.5/24') → 192.168.1.0/24"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -674,7 +774,10 @@ pub struct SetMaskLen {
 impl SetMaskLen {
     pub fn new() -> Self {
         Self {
-            signature: Signature::exact(vec![Utf8, Int64], Volatility::Immutable),
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &[Int64]]),
+                Volatility::Immutable,
+            ),
         }
     }
 }
@@ -692,14 +795,15 @@ impl ScalarUDFImpl for SetMaskLen {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
-        let cidr_strings = datafusion::common::cast::as_string_array(&args[0])?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let cidr_strings = string_values(&args[0])?;
         let prefix_lengths = datafusion::common::cast::as_int64_array(&args[1])?;
 
         if cidr_strings.len() != prefix_lengths.len() {
@@ -709,7 +813,7 @@ impl ScalarUDFImpl for SetMaskLen {
         }
 
         for i in 0..cidr_strings.len() {
-            let input_string = cidr_strings.value(i);
+            let input_string = cidr_strings[i].unwrap_or_default();
             let prefix: u8 = prefix_lengths.value(i) as u8;
 
             if input_string.is_empty() || prefix == 0 {
@@ -765,9 +869,18 @@ impl ScalarUDFImpl for SetMaskLen {
             string_builder.append_value(new_cidr.to_string());
         }
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for SetMaskLen {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "set_masklen(inet, int) → inet",
+            description: "Set netmask length for inet value",
+            examples: &["set_masklen('192.168.1.5/24', 16) → 192.168.1.5/16"],
+            link: Some(NETWORK_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -799,6 +912,7 @@ fn bit_in_common(l: &[u8], r: &[u8], n: usize) -> usize {
 mod tests {
     use crate::common::test_utils::set_up_network_data_test;
     use crate::postgres::register_postgres_udfs;
+    use datafusion::arrow::array::StringViewArray;
     use datafusion::assert_batches_sorted_eq;
     use datafusion::prelude::SessionContext;
 
@@ -1296,6 +1410,56 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_large_strings() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, arrow_typeof(broadcast(arrow_cast(cidr, 'LargeUtf8'))) as col_type, \
+                set_masklen(arrow_cast(ip, 'LargeUtf8'), 8) as col_masklen, \
+                inet_same_family(arrow_cast(cidr, 'LargeUtf8'), ip) as col_same_family \
+                from network_table where index IN (1, 4, 6)",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------+----------------+-----------------+
+| index | col_type  | col_masklen    | col_same_family |
++-------+-----------+----------------+-----------------+
+| 1     | LargeUtf8 | 192.168.1.5/8  | true            |
+| 4     | LargeUtf8 | 2001:db8::/8   | true            |
+| 6     | LargeUtf8 |                |                 |
++-------+-----------+----------------+-----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_views() -> Result<()> {
+        let cidrs = ColumnarValue::Array(Arc::new(StringViewArray::from(vec![
+            Some("10.0.0.0/16"),
+            None,
+        ])));
+        let ColumnarValue::Array(result) = Broadcast::new().invoke(&[cidrs])? else {
+            unreachable!("broadcast returns an array")
+        };
+
+        assert_eq!(result.data_type(), &DataType::Utf8View);
+        assert_eq!(string_values(&result)?, vec![Some("10.0.255.255"), None]);
+        Ok(())
+    }
+
     fn register_udfs_for_test() -> Result<SessionContext> {
         let ctx = set_up_network_data_test()?;
         register_postgres_udfs(&ctx)?;
@@ -0,0 +1,654 @@
+//! Postgres' range types, following `rangetypes.c` and `multirangetypes.c`: ranges of any
+//! ordered subtype, their operators, their text, and the multiranges that `range_agg` makes of
+//! them.
+//!
+//! A range is empty, or has a lower and an upper bound, each either infinite or a value that is
+//! in the range or not, e.g. `[1,10)` for the integers from 1 to 9. An infinite bound is never
+//! inclusive. The ranges of a discrete subtype, such as the integers, are kept in their canonical
+//! `[)` form, so that equal ranges have equal bounds.
+
+use std::cmp::Ordering;
+
+use anyhow::{anyhow, bail, Result};
+
+/// A range of values of type `T`. An infinite bound is `None`, and an empty range has neither
+/// bounds nor inclusive bounds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Range<T> {
+    pub(crate) lower: Option<T>,
+    pub(crate) upper: Option<T>,
+    pub(crate) lower_inc: bool,
+    pub(crate) upper_inc: bool,
+    pub(crate) empty: bool,
+}
+
+/// A bound of a range, as Postgres' `RangeBound`: its value, `None` when it is infinite,
+/// whether it is in the range, and whether it is the lower bound.
+#[derive(Debug)]
+struct Bound<'a, T> {
+    value: Option<&'a T>,
+    inclusive: bool,
+    lower: bool,
+}
+
+// Derived, these would need `T: Copy`, though a bound only borrows its value.
+impl<T> Clone for Bound<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Bound<'_, T> {}
+
+impl<T: Ord + Clone> Range<T> {
+    /// The empty range.
+    pub(crate) fn empty() -> Self {
+        Self {
+            lower: None,
+            upper: None,
+            lower_inc: false,
+            upper_inc: false,
+            empty: true,
+        }
+    }
+
+    /// Returns the range between two bounds, which is empty when they are equal and not both
+    /// inclusive, as `range_serialize` does.
+    pub(crate) fn new(
+        lower: Option<T>,
+        upper: Option<T>,
+        lower_inc: bool,
+        upper_inc: bool,
+    ) -> Result<Self> {
+        if let (Some(lower), Some(upper)) = (&lower, &upper) {
+            if lower > upper {
+                bail!("range lower bound must be less than or equal to range upper bound");
+            }
+        }
+        Ok(Self::from_bounds(
+            Bound {
+                value: lower.as_ref(),
+                inclusive: lower_inc,
+                lower: true,
+            },
+            Bound {
+                value: upper.as_ref(),
+                inclusive: upper_inc,
+                lower: false,
+            },
+        ))
+    }
+
+    /// Returns the canonical form of a range of a discrete subtype, whose lower bound is
+    /// inclusive and whose upper bound is exclusive. `successor` returns the value after a
+    /// value, or an error if there is none.
+    pub(crate) fn canonicalize(self, successor: impl Fn(&T) -> Result<T>) -> Result<Self> {
+        if self.empty {
+            return Ok(self);
+        }
+        let lower = match &self.lower {
+            Some(lower) if !self.lower_inc => Some(successor(lower)?),
+            lower => lower.clone(),
+        };
+        let upper = match &self.upper {
+            Some(upper) if self.upper_inc => Some(successor(upper)?),
+            upper => upper.clone(),
+        };
+        let lower_inc = lower.is_some();
+        Ok(match (&lower, &upper) {
+            (Some(lower), Some(upper)) if lower >= upper => Self::empty(),
+            _ => Self {
+                lower,
+                upper,
+                lower_inc,
+                upper_inc: false,
+                empty: false,
+            },
+        })
+    }
+
+    /// Whether `other` is in this range, as `@>` does.
+    pub(crate) fn contains(&self, other: &Self) -> bool {
+        if other.empty {
+            return true;
+        }
+        if self.empty {
+            return false;
+        }
+        compare_bounds(self.lower_bound(), other.lower_bound()).is_le()
+            && compare_bounds(self.upper_bound(), other.upper_bound()).is_ge()
+    }
+
+    /// Whether `value` is in this range, as `@>` does for an element.
+    pub(crate) fn contains_element(&self, value: &T) -> bool {
+        if self.empty {
+            return false;
+        }
+        let above_lower = match &self.lower {
+            Some(lower) => match value.cmp(lower) {
+                Ordering::Less => false,
+                Ordering::Equal => self.lower_inc,
+                Ordering::Greater => true,
+            },
+            None => true,
+        };
+        let below_upper = match &self.upper {
+            Some(upper) => match value.cmp(upper) {
+                Ordering::Less => true,
+                Ordering::Equal => self.upper_inc,
+                Ordering::Greater => false,
+            },
+            None => true,
+        };
+        above_lower && below_upper
+    }
+
+    /// Whether the ranges have a value in common, as `&&` does.
+    pub(crate) fn overlaps(&self, other: &Self) -> bool {
+        if self.empty || other.empty {
+            return false;
+        }
+        let overlaps_at = |first: &Self, second: &Self| {
+            compare_bounds(first.lower_bound(), second.lower_bound()).is_ge()
+                && compare_bounds(first.lower_bound(), second.upper_bound()).is_le()
+        };
+        overlaps_at(self, other) || overlaps_at(other, self)
+    }
+
+    /// Whether one range ends where the other starts, without a value in common or a value
+    /// between them, as `-|-` does. The ranges of a discrete subtype must be canonical.
+    pub(crate) fn is_adjacent(&self, other: &Self) -> bool {
+        if self.empty || other.empty {
+            return false;
+        }
+        let meet = |upper: Bound<T>, lower: Bound<T>| {
+            compare_bound_values(upper, lower).is_eq() && upper.inclusive != lower.inclusive
+        };
+        meet(self.upper_bound(), other.lower_bound())
+            || meet(other.upper_bound(), self.lower_bound())
+    }
+
+    /// Returns the smallest range that contains both ranges, as `range_merge` does.
+    pub(crate) fn merge(&self, other: &Self) -> Self {
+        if self.empty {
+            return other.clone();
+        }
+        if other.empty {
+            return self.clone();
+        }
+        let lower = std::cmp::min_by(self.lower_bound(), other.lower_bound(), |first, second| {
+            compare_bounds(*first, *second)
+        });
+        let upper = std::cmp::max_by(self.upper_bound(), other.upper_bound(), |first, second| {
+            compare_bounds(*first, *second)
+        });
+        Self::from_bounds(lower, upper)
+    }
+
+    /// Returns the union of the ranges, as `+` does, which must not leave a gap.
+    pub(crate) fn union(&self, other: &Self) -> Result<Self> {
+        if !self.empty && !other.empty && !self.overlaps(other) && !self.is_adjacent(other) {
+            bail!("result of range union would not be contiguous");
+        }
+        Ok(self.merge(other))
+    }
+
+    /// Returns the values the ranges have in common, as `*` does.
+    pub(crate) fn intersection(&self, other: &Self) -> Self {
+        if !self.overlaps(other) {
+            return Self::empty();
+        }
+        let lower = std::cmp::max_by(self.lower_bound(), other.lower_bound(), |first, second| {
+            compare_bounds(*first, *second)
+        });
+        let upper = std::cmp::min_by(self.upper_bound(), other.upper_bound(), |first, second| {
+            compare_bounds(*first, *second)
+        });
+        Self::from_bounds(lower, upper)
+    }
+
+    /// Returns the values of this range that are not in `other`, as `-` does, which must not
+    /// split the range in two.
+    pub(crate) fn difference(&self, other: &Self) -> Result<Self> {
+        if self.empty || other.empty {
+            return Ok(self.clone());
+        }
+        let (lower1, upper1) = (self.lower_bound(), self.upper_bound());
+        let (lower2, upper2) = (other.lower_bound(), other.upper_bound());
+        let lower1_lower2 = compare_bounds(lower1, lower2);
+        let lower1_upper2 = compare_bounds(lower1, upper2);
+        let upper1_lower2 = compare_bounds(upper1, lower2);
+        let upper1_upper2 = compare_bounds(upper1, upper2);
+
+        if lower1_lower2.is_lt() && upper1_upper2.is_gt() {
+            bail!("result of range difference would not be contiguous");
+        }
+        if lower1_upper2.is_gt() || upper1_lower2.is_lt() {
+            return Ok(self.clone());
+        }
+        if lower1_lower2.is_ge() && upper1_upper2.is_le() {
+            return Ok(Self::empty());
+        }
+        if lower1_lower2.is_le() && upper1_lower2.is_ge() && upper1_upper2.is_le() {
+            // The lower bound of `other` becomes the upper bound of the result.
+            let upper = Bound {
+                value: lower2.value,
+                inclusive: !lower2.inclusive,
+                lower: false,
+            };
+            return Ok(Self::from_bounds(lower1, upper));
+        }
+        if lower1_lower2.is_ge() && upper1_upper2.is_ge() && lower1_upper2.is_le() {
+            let lower = Bound {
+                value: upper2.value,
+                inclusive: !upper2.inclusive,
+                lower: true,
+            };
+            return Ok(Self::from_bounds(lower, upper1));
+        }
+        Err(anyhow!("unexpected case in range_minus"))
+    }
+
+    /// Orders ranges as `range_cmp` does: the empty range first, then by their lower bounds,
+    /// then by their upper bounds.
+    pub(crate) fn compare(&self, other: &Self) -> Ordering {
+        match (self.empty, other.empty) {
+            (true, true) => Ordering::Equal,
+            (true, false) => Ordering::Less,
+            (false, true) => Ordering::Greater,
+            (false, false) => compare_bounds(self.lower_bound(), other.lower_bound())
+                .then_with(|| compare_bounds(self.upper_bound(), other.upper_bound())),
+        }
+    }
+
+    fn lower_bound(&self) -> Bound<'_, T> {
+        Bound {
+            value: self.lower.as_ref(),
+            inclusive: self.lower_inc,
+            lower: true,
+        }
+    }
+
+    fn upper_bound(&self) -> Bound<'_, T> {
+        Bound {
+            value: self.upper.as_ref(),
+            inclusive: self.upper_inc,
+            lower: false,
+        }
+    }
+
+    /// Returns the range between two bounds, the lower not above the upper.
+    fn from_bounds(lower: Bound<T>, upper: Bound<T>) -> Self {
+        let empty = match (lower.value, upper.value) {
+            (Some(lower_value), Some(upper_value)) => {
+                lower_value == upper_value && !(lower.inclusive && upper.inclusive)
+            }
+            _ => false,
+        };
+        if empty {
+            return Self::empty();
+        }
+        Self {
+            lower: lower.value.cloned(),
+            upper: upper.value.cloned(),
+            lower_inc: lower.inclusive && lower.value.is_some(),
+            upper_inc: upper.inclusive && upper.value.is_some(),
+            empty: false,
+        }
+    }
+}
+
+/// Orders two bounds by where they are on the line of values, as `range_cmp_bounds` does: an
+/// exclusive lower bound is just after its value, and an exclusive upper bound just before it.
+fn compare_bounds<T: Ord>(first: Bound<T>, second: Bound<T>) -> Ordering {
+    let ordering = compare_bound_values(first, second);
+    if ordering.is_ne() || first.value.is_none() {
+        return ordering;
+    }
+    let position = |bound: Bound<T>| match (bound.inclusive, bound.lower) {
+        (true, _) => Ordering::Equal,
+        (false, true) => Ordering::Greater,
+        (false, false) => Ordering::Less,
+    };
+    position(first).cmp(&position(second))
+}
+
+/// Orders two bounds by their values alone, as `range_cmp_bound_values` does. An infinite lower
+/// bound is before every value and an infinite upper bound after every value.
+fn compare_bound_values<T: Ord>(first: Bound<T>, second: Bound<T>) -> Ordering {
+    let infinity = |bound: Bound<T>| {
+        if bound.lower {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        }
+    };
+    match (first.value, second.value) {
+        (Some(first), Some(second)) => first.cmp(second),
+        (None, Some(_)) => infinity(first),
+        (Some(_), None) => infinity(second).reverse(),
+        (None, None) if first.lower == second.lower => Ordering::Equal,
+        (None, None) => infinity(first),
+    }
+}
+
+/// Returns the multirange of a set of ranges, as `multirange_canonicalize` does: its ranges
+/// in order, without the empty ones, and with the ones that overlap or are adjacent merged.
+pub(crate) fn multirange<T: Ord + Clone>(mut ranges: Vec<Range<T>>) -> Vec<Range<T>> {
+    ranges.retain(|range| !range.empty);
+    ranges.sort_by(Range::compare);
+    let mut merged: Vec<Range<T>> = vec![];
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if last.overlaps(&range) || last.is_adjacent(&range) => {
+                *last = last.merge(&range);
+            }
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// Reads a range as `range_parse` does, returning the text of its bounds, `None` for an
+/// infinite one. The bounds are not checked against each other.
+pub(crate) fn parse_range(text: &str) -> Result<Range<String>> {
+    let malformed = |detail: &str| anyhow!("malformed range literal: \"{text}\": {detail}");
+    let rest = text.trim_start_matches(is_space);
+
+    if rest
+        .get(..5)
+        .is_some_and(|keyword| keyword.eq_ignore_ascii_case("empty"))
+    {
+        if !rest[5..].chars().all(is_space) {
+            return Err(malformed("Junk after \"empty\" key word."));
+        }
+        return Ok(Range {
+            lower: None,
+            upper: None,
+            lower_inc: false,
+            upper_inc: false,
+            empty: true,
+        });
+    }
+
+    let (lower_inc, rest) = match rest.chars().next() {
+        Some('[') => (true, &rest[1..]),
+        Some('(') => (false, &rest[1..]),
+        _ => return Err(malformed("Missing left parenthesis or bracket.")),
+    };
+    let (lower, rest) = parse_bound(rest).ok_or_else(|| malformed("Unexpected end of input."))?;
+    let rest = rest
+        .strip_prefix(',')
+        .ok_or_else(|| malformed("Missing comma after lower bound."))?;
+    let (upper, rest) = parse_bound(rest).ok_or_else(|| malformed("Unexpected end of input."))?;
+    let (upper_inc, rest) = match rest.chars().next() {
+        Some(']') => (true, &rest[1..]),
+        Some(')') => (false, &rest[1..]),
+        _ => return Err(malformed("Too many commas.")),
+    };
+    if !rest.chars().all(is_space) {
+        return Err(malformed("Junk after right parenthesis or bracket."));
+    }
+    Ok(Range {
+        lower_inc: lower_inc && lower.is_some(),
+        upper_inc: upper_inc && upper.is_some(),
+        lower,
+        upper,
+        empty: false,
+    })
+}
+
+/// Writes a range as `range_out` does, given the text of its bounds.
+pub(crate) fn format_range(range: &Range<String>) -> String {
+    if range.empty {
+        return "empty".to_string();
+    }
+    let mut text = String::new();
+    text.push(if range.lower_inc { '[' } else { '(' });
+    if let Some(lower) = &range.lower {
+        write_bound(&mut text, lower);
+    }
+    text.push(',');
+    if let Some(upper) = &range.upper {
+        write_bound(&mut text, upper);
+    }
+    text.push(if range.upper_inc { ']' } else { ')' });
+    text
+}
+
+/// Writes a multirange as `multirange_out` does, given the text of the bounds of its ranges.
+pub(crate) fn format_multirange(ranges: &[Range<String>]) -> String {
+    let ranges = ranges.iter().map(format_range).collect::<Vec<_>>();
+    format!("{{{}}}", ranges.join(","))
+}
+
+/// Reads the text of a bound up to the `,`, `)` or `]` after it, with the double quotes around
+/// parts of it removed and the characters escaped by a backslash or a doubled quote kept.
+/// Returns `None` for an infinite, empty bound, and `None` overall at the end of the input.
+fn parse_bound(text: &str) -> Option<(Option<String>, &str)> {
+    if text.starts_with([',', ')', ']']) {
+        return Some((None, text));
+    }
+    let mut bound = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.char_indices().peekable();
+    loop {
+        let (index, character) = chars.next()?;
+        match character {
+            ',' | ')' | ']' if !in_quotes => return Some((Some(bound), &text[index..])),
+            '\\' => bound.push(chars.next()?.1),
+            '"' if !in_quotes => in_quotes = true,
+            '"' => match chars.peek() {
+                Some((_, '"')) => bound.push(chars.next()?.1),
+                _ => in_quotes = false,
+            },
+            _ => bound.push(character),
+        }
+    }
+}
+
+/// Writes the text of a bound, in double quotes if it is empty or has characters that would
+/// be read as part of the range, with `"` and `\` doubled.
+fn write_bound(text: &mut String, bound: &str) {
+    let needs_quotes = bound.is_empty()
+        || bound.chars().any(|character| {
+            matches!(character, '"' | '\\' | '(' | ')' | '[' | ']' | ',') || is_space(character)
+        });
+    if !needs_quotes {
+        text.push_str(bound);
+        return;
+    }
+    text.push('"');
+    for character in bound.chars() {
+        if matches!(character, '"' | '\\') {
+            text.push(character);
+        }
+        text.push(character);
+    }
+    text.push('"');
+}
+
+/// Whether a character is whitespace to Postgres' range reader.
+fn is_space(character: char) -> bool {
+    matches!(character, ' ' | '\t' | '\n' | '\r' | '\x0B' | '\x0C')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reads an integer range, canonical like `int4range`.
+    fn range(text: &str) -> Range<i64> {
+        let range = parse_range(text).unwrap();
+        if range.empty {
+            return Range::empty();
+        }
+        let range = Range::new(
+            range.lower.map(|lower| lower.trim().parse().unwrap()),
+            range.upper.map(|upper| upper.trim().parse().unwrap()),
+            range.lower_inc,
+            range.upper_inc,
+        )
+        .unwrap();
+        range.canonicalize(|value| Ok(value + 1)).unwrap()
+    }
+
+    fn texts(range: &Range<i64>) -> Range<String> {
+        Range {
+            lower: range.lower.map(|lower| lower.to_string()),
+            upper: range.upper.map(|upper| upper.to_string()),
+            lower_inc: range.lower_inc,
+            upper_inc: range.upper_inc,
+            empty: range.empty,
+        }
+    }
+
+    fn text(range: &Range<i64>) -> String {
+        format_range(&texts(range))
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(text(&range("[1,10)")), "[1,10)");
+        assert_eq!(text(&range("(1,10]")), "[2,11)");
+        assert_eq!(text(&range("[,5]")), "(,6)");
+        assert_eq!(text(&range("(,)")), "(,)");
+        assert_eq!(text(&range("[3,3)")), "empty");
+        assert_eq!(text(&range("(3,4)")), "empty");
+        assert_eq!(text(&range(" EmPty ")), "empty");
+        assert_eq!(text(&range("[ 1 , \"10\" )")), "[1,10)");
+
+        let detail = |text: &str| {
+            let message = parse_range(text).unwrap_err().to_string();
+            message
+                .strip_prefix(&format!("malformed range literal: \"{text}\": "))
+                .unwrap()
+                .to_string()
+        };
+        assert_eq!(detail("empty x"), "Junk after \"empty\" key word.");
+        assert_eq!(detail("[1,10"), "Unexpected end of input.");
+        assert_eq!(detail("1,10)"), "Missing left parenthesis or bracket.");
+        assert_eq!(detail("[1 10)"), "Missing comma after lower bound.");
+        assert_eq!(detail("[1,10,)"), "Too many commas.");
+        assert_eq!(
+            detail("[1,10) x"),
+            "Junk after right parenthesis or bracket."
+        );
+        assert_eq!(
+            Range::new(Some(10), Some(1), true, false)
+                .unwrap_err()
+                .to_string(),
+            "range lower bound must be less than or equal to range upper bound"
+        );
+
+        assert_eq!(
+            parse_range("[\"a\"\"b\\\\c\",\"d e\")").unwrap(),
+            Range {
+                lower: Some("a\"b\\c".to_string()),
+                upper: Some("d e".to_string()),
+                lower_inc: true,
+                upper_inc: false,
+                empty: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_range() {
+        let range = Range {
+            lower: Some("2001-01-01 00:00:00".to_string()),
+            upper: Some("a\"b".to_string()),
+            lower_inc: true,
+            upper_inc: false,
+            empty: false,
+        };
+        assert_eq!(format_range(&range), "[\"2001-01-01 00:00:00\",\"a\"\"b\")");
+        assert_eq!(
+            format_multirange(&[texts(&self::range("[1,2)")), Range::empty()]),
+            "{[1,2),empty}"
+        );
+        assert_eq!(format_multirange(&[]), "{}");
+    }
+
+    #[test]
+    fn test_operators() {
+        assert!(range("[1,10)").contains(&range("[2,5)")));
+        assert!(range("[1,10)").contains(&range("empty")));
+        assert!(!range("empty").contains(&range("[2,5)")));
+        assert!(range("[1,2)").contains_element(&1));
+        assert!(!range("[1,2)").contains_element(&2));
+        assert!(range("(,)").contains_element(&2));
+        assert!(range("[1,5)").overlaps(&range("[4,8)")));
+        assert!(!range("[1,5)").overlaps(&range("[5,8)")));
+        assert!(range("[1,3)").is_adjacent(&range("[3,5)")));
+        assert!(range("[3,5)").is_adjacent(&range("[1,3)")));
+        assert!(!range("[1,3)").is_adjacent(&range("[4,5)")));
+
+        assert_eq!(text(&range("[1,3)").merge(&range("[7,9)"))), "[1,9)");
+        assert_eq!(
+            text(&range("[1,3)").union(&range("[3,9)")).unwrap()),
+            "[1,9)"
+        );
+        assert_eq!(
+            range("[1,5)")
+                .union(&range("[7,9)"))
+                .unwrap_err()
+                .to_string(),
+            "result of range union would not be contiguous"
+        );
+        assert_eq!(
+            text(&range("[1,10)").intersection(&range("[5,20)"))),
+            "[5,10)"
+        );
+        assert_eq!(
+            text(&range("[1,5)").intersection(&range("[5,20)"))),
+            "empty"
+        );
+        assert_eq!(
+            text(&range("[1,10)").difference(&range("[5,20)")).unwrap()),
+            "[1,5)"
+        );
+        assert_eq!(
+            text(&range("[1,10)").difference(&range("[0,5)")).unwrap()),
+            "[5,10)"
+        );
+        assert_eq!(
+            text(&range("[1,10)").difference(&range("[0,20)")).unwrap()),
+            "empty"
+        );
+        assert_eq!(
+            text(&range("[1,10)").difference(&range("[20,30)")).unwrap()),
+            "[1,10)"
+        );
+        assert_eq!(
+            range("[1,5)")
+                .difference(&range("[2,3)"))
+                .unwrap_err()
+                .to_string(),
+            "result of range difference would not be contiguous"
+        );
+
+        let continuous = |lower, upper, lower_inc, upper_inc| {
+            Range::new(Some(lower), Some(upper), lower_inc, upper_inc).unwrap()
+        };
+        assert!(continuous(1, 3, true, true).is_adjacent(&continuous(3, 5, false, false)));
+        assert!(!continuous(1, 3, true, true).is_adjacent(&continuous(3, 5, true, false)));
+        assert_eq!(
+            continuous(1, 3, true, true)
+                .difference(&continuous(1, 3, false, true))
+                .unwrap(),
+            continuous(1, 1, true, true)
+        );
+    }
+
+    #[test]
+    fn test_multirange() {
+        let ranges = ["[1,3)", "[2,5)", "[6,7)", "[5,6)", "empty", "[10,12)"].map(range);
+        let ranges = multirange(ranges.to_vec())
+            .iter()
+            .map(texts)
+            .collect::<Vec<_>>();
+        assert_eq!(format_multirange(&ranges), "{[1,7),[10,12)}");
+        assert_eq!(multirange::<i64>(vec![Range::empty()]), vec![]);
+    }
+}
@@ -0,0 +1,2244 @@
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, BooleanArray, Date32Array, Float64Array, Int32Array, Int64Array,
+    ListArray, StringArray, StructArray, TimestampMicrosecondArray,
+};
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType::{
+    Boolean, Date32, Float64, Int32, Int64, LargeUtf8, List, Struct, Timestamp, Utf8,
+};
+use datafusion::arrow::datatypes::{
+    DataType, Date32Type, Field, Fields, Float64Type, Int32Type, Int64Type, TimeUnit,
+    TimestampMicrosecondType,
+};
+use datafusion::common::{exec_err, plan_err, ExprSchema, ScalarValue};
+use datafusion::error::Result;
+use datafusion::functions::string;
+use datafusion::logical_expr::function::AccumulatorArgs;
+use datafusion::logical_expr::TypeSignature::Any;
+use datafusion::logical_expr::{
+    Accumulator, AggregateUDFImpl, ColumnarValue, Expr, ScalarUDF, ScalarUDFImpl, Signature,
+    Volatility,
+};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{exact_signatures, string_values, STRING_TYPES};
+use crate::postgres::array_udfs::element_texts;
+use crate::postgres::interval::IntervalStyle;
+use crate::postgres::range::{format_multirange, format_range, multirange, parse_range, Range};
+use crate::postgres::string_udfs::{execution_error, text_values};
+
+const RANGE_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/16/functions-range.html";
+const RANGE_TYPES_LINK: &str = "https://www.postgresql.org/docs/16/rangetypes.html";
+
+/// The fields of the struct a range is stored in.
+const RANGE_FIELDS: [&str; 5] = ["lower", "upper", "lower_inc", "upper_inc", "empty"];
+
+/// Builds ranges of one of Postgres' built-in range types from their bounds, e.g.
+/// `int4range(1, 10)` for `[1,10)`. A NULL bound is infinite, and the optional third argument
+/// says which bounds are inclusive.
+#[derive(Debug)]
+pub struct RangeConstructor {
+    signature: Signature,
+    range_type: RangeType,
+}
+
+impl RangeConstructor {
+    pub fn new(range_type: RangeType) -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Immutable),
+            range_type,
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeConstructor {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.range_type.name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(self.range_type.data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let lower = subtype_values(self.range_type, &args[0])?;
+        let upper = subtype_values(self.range_type, &args[1])?;
+        let flags = match args.get(2) {
+            Some(flags) => text_values(flags)?,
+            None => vec![Some("[)".to_string()); args[0].len()],
+        };
+
+        let ranges = flags
+            .iter()
+            .enumerate()
+            .map(|(row, flags)| {
+                let Some(flags) = flags else {
+                    return exec_err!("range constructor flags argument must not be null");
+                };
+                let (lower_inc, upper_inc) = bound_flags(flags)?;
+                self.range_type
+                    .range(lower[row], upper[row], lower_inc, upper_inc)
+                    .map(Some)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ColumnarValue::Array(range_array(self.range_type, &ranges)?))
+    }
+}
+
+impl Documented for RangeConstructor {
+    fn documentation(&self) -> Documentation {
+        let (syntax, examples): (_, &[_]) = match self.range_type {
+            RangeType::Int4 => (
+                "int4range(integer, integer [, text]) → int4range",
+                &["int4range(1, 10, '(]') → [2,11)"],
+            ),
+            RangeType::Int8 => (
+                "int8range(bigint, bigint [, text]) → int8range",
+                &["int8range(NULL, 5, '[]') → (,6)"],
+            ),
+            RangeType::Num => (
+                "numrange(numeric, numeric [, text]) → numrange",
+                &["numrange(1.5, NULL, '()') → (1.5,)"],
+            ),
+            RangeType::Ts => (
+                "tsrange(timestamp, timestamp [, text]) → tsrange",
+                &["tsrange('2001-01-01', '2001-01-02') → [\"2001-01-01 00:00:00\",\"2001-01-02 00:00:00\")"],
+            ),
+            RangeType::Tstz => (
+                "tstzrange(timestamptz, timestamptz [, text]) → tstzrange",
+                &["tstzrange('2001-01-01T00:00:00+02:00', NULL) → [\"2000-12-31 22:00:00+00\",)"],
+            ),
+            RangeType::Date => (
+                "daterange(date, date [, text]) → daterange",
+                &["daterange('2001-01-01', '2001-01-05', '(]') → [2001-01-02,2001-01-06)"],
+            ),
+        };
+        Documentation {
+            syntax,
+            description: "Constructs a range from its lower and upper bounds, either of which is infinite when NULL. The third argument is one of '[]', '[)', '(]' or '()', saying whether each bound is inclusive, and defaults to '[)'. Ranges of integers and dates are kept in their canonical [) form",
+            examples,
+            link: Some(RANGE_TYPES_LINK),
+        }
+    }
+}
+
+/// Reads ranges from Postgres' range text, e.g. `[1,10)`, `(,5]` or `empty`. The second
+/// argument names the range type and must be constant.
+#[derive(Debug)]
+pub struct PgRangeIn {
+    signature: Signature,
+}
+
+impl PgRangeIn {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &[Utf8]]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgRangeIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_range_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        plan_err!("pg_range_in() chooses its return type from its arguments")
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        _arg_types: &[DataType],
+    ) -> Result<DataType> {
+        match args.get(1) {
+            Some(Expr::Literal(ScalarValue::Utf8(Some(name)))) => {
+                Ok(RangeType::from_name(name)?.data_type())
+            }
+            _ => plan_err!("the range type of pg_range_in() must be a constant string"),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let range_type = match args.get(1) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(name)))) => {
+                RangeType::from_name(name)?
+            }
+            _ => return exec_err!("the range type of pg_range_in() must be a constant string"),
+        };
+        let args = ColumnarValue::values_to_arrays(&args[..1])?;
+        let texts = string_values(&args[0])?
+            .into_iter()
+            .map(|text| text.map(parse_range).transpose())
+            .collect::<anyhow::Result<Vec<_>>>()
+            .map_err(execution_error)?;
+
+        // The subtypes' input functions ignore the whitespace around a value.
+        let bound_values = |bounds: Vec<Option<&str>>| {
+            let bounds = bounds
+                .into_iter()
+                .map(|bound| bound.map(str::trim))
+                .collect::<StringArray>();
+            subtype_values(range_type, &(Arc::new(bounds) as ArrayRef))
+        };
+        let lower = bound_values(
+            texts
+                .iter()
+                .map(|range| range.as_ref()?.lower.as_deref())
+                .collect(),
+        )?;
+        let upper = bound_values(
+            texts
+                .iter()
+                .map(|range| range.as_ref()?.upper.as_deref())
+                .collect(),
+        )?;
+
+        let ranges = texts
+            .iter()
+            .enumerate()
+            .map(|(row, range)| match range {
+                Some(range) if range.empty => Ok(Some(Range::empty())),
+                Some(range) => range_type
+                    .range(lower[row], upper[row], range.lower_inc, range.upper_inc)
+                    .map(Some),
+                None => Ok(None),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(ColumnarValue::Array(range_array(range_type, &ranges)?))
+    }
+}
+
+impl Documented for PgRangeIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_range_in(text, text) → anyrange",
+            description: "Converts Postgres' text for a range, such as [1,10) or empty, to a range of the type named by the second argument: int4range, int8range, numrange, tsrange, tstzrange or daterange",
+            examples: &[
+                "pg_range_in('(1,10]', 'int4range') → [2,11)",
+                "pg_range_in('[1.5,)', 'numrange') → [1.5,)",
+            ],
+            link: Some(RANGE_TYPES_LINK),
+        }
+    }
+}
+
+/// Writes ranges and multiranges as Postgres' text for them, e.g. `[1,10)` or `{[1,3),[5,7)}`.
+#[derive(Debug)]
+pub struct PgRangeOut {
+    signature: Signature,
+}
+
+impl PgRangeOut {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgRangeOut {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_range_out"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        if multirange_type(&arg_types[0]).is_none() {
+            range_type(self.name(), &arg_types[0])?;
+        }
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let texts = if multirange_type(args[0].data_type()).is_some() {
+            let (range_type, multiranges) = multiranges(&args[0])?;
+            multiranges
+                .into_iter()
+                .map(|ranges| {
+                    ranges
+                        .map(|ranges| Ok(format_multirange(&range_texts(range_type, &ranges)?)))
+                        .transpose()
+                })
+                .collect::<Result<StringArray>>()?
+        } else {
+            let (range_type, ranges) = ranges(&args[0])?;
+            let texts = range_texts(
+                range_type,
+                &ranges.iter().flatten().cloned().collect::<Vec<_>>(),
+            )?;
+            let mut texts = texts.iter();
+            ranges
+                .iter()
+                .map(|range| range.as_ref().and_then(|_| texts.next()).map(format_range))
+                .collect::<StringArray>()
+        };
+        Ok(ColumnarValue::Array(Arc::new(texts)))
+    }
+}
+
+impl Documented for PgRangeOut {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_range_out(anyrange) → text",
+            description: "Converts a range or a multirange to Postgres' text for it",
+            examples: &[
+                "pg_range_out(int4range(1, 10, '[]')) → [1,11)",
+                "pg_range_out(daterange('2001-01-01', NULL)) → [2001-01-01,)",
+            ],
+            link: Some(RANGE_TYPES_LINK),
+        }
+    }
+}
+
+/// Returns the lower bound of a range, which is NULL when the range is empty or the bound is
+/// infinite. Any other argument goes to DataFusion's `lower`, which it replaces, so strings are
+/// still converted to lower case.
+#[derive(Debug)]
+pub struct Lower {
+    signature: Signature,
+    builtin: Arc<ScalarUDF>,
+}
+
+impl Lower {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+            builtin: string::lower(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Lower {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "lower"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            Struct(_) => Ok(range_type(self.name(), &arg_types[0])?.subtype()),
+            arg_type => self.builtin.return_type(&[case_argument_type(arg_type)]),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        match args[0].data_type() {
+            Struct(_) => range_bounds(args, |range| range.lower),
+            _ => change_case(&self.builtin, args),
+        }
+    }
+}
+
+impl Documented for Lower {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "lower(anyrange) → anyelement",
+            description: "Extracts the lower bound of the range, NULL if the range is empty or the lower bound is infinite. Given a string, converts it to lower case",
+            examples: &[
+                "lower(numrange(1.1, 2.2)) → 1.1",
+                "lower('TOM') → tom",
+            ],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the upper bound of a range, which is NULL when the range is empty or the bound is
+/// infinite. Any other argument goes to DataFusion's `upper`, which it replaces, so strings are
+/// still converted to upper case.
+#[derive(Debug)]
+pub struct Upper {
+    signature: Signature,
+    builtin: Arc<ScalarUDF>,
+}
+
+impl Upper {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+            builtin: string::upper(),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Upper {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "upper"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        match &arg_types[0] {
+            Struct(_) => Ok(range_type(self.name(), &arg_types[0])?.subtype()),
+            arg_type => self.builtin.return_type(&[case_argument_type(arg_type)]),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        match args[0].data_type() {
+            Struct(_) => range_bounds(args, |range| range.upper),
+            _ => change_case(&self.builtin, args),
+        }
+    }
+}
+
+impl Documented for Upper {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "upper(anyrange) → anyelement",
+            description: "Extracts the upper bound of the range, NULL if the range is empty or the upper bound is infinite. Given a string, converts it to upper case",
+            examples: &[
+                "upper(numrange(1.1, 2.2)) → 2.2",
+                "upper('tom') → TOM",
+            ],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns whether a range is empty.
+#[derive(Debug)]
+pub struct Isempty {
+    signature: Signature,
+}
+
+impl Isempty {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Isempty {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "isempty"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        range_type(self.name(), &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        range_flags(args, |range| range.empty)
+    }
+}
+
+impl Documented for Isempty {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "isempty(anyrange) → boolean",
+            description: "Is the range empty?",
+            examples: &["isempty(numrange(1.1, 2.2)) → false"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns whether the lower bound of a range is inclusive.
+#[derive(Debug)]
+pub struct LowerInc {
+    signature: Signature,
+}
+
+impl LowerInc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for LowerInc {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "lower_inc"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        range_type(self.name(), &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        range_flags(args, |range| range.lower_inc)
+    }
+}
+
+impl Documented for LowerInc {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "lower_inc(anyrange) → boolean",
+            description: "Is the range's lower bound inclusive?",
+            examples: &["lower_inc(numrange(1.1, 2.2)) → true"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns whether the upper bound of a range is inclusive.
+#[derive(Debug)]
+pub struct UpperInc {
+    signature: Signature,
+}
+
+impl UpperInc {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UpperInc {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "upper_inc"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        range_type(self.name(), &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        range_flags(args, |range| range.upper_inc)
+    }
+}
+
+impl Documented for UpperInc {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "upper_inc(anyrange) → boolean",
+            description: "Is the range's upper bound inclusive?",
+            examples: &["upper_inc(numrange(1.1, 2.2)) → false"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns whether the lower bound of a range is infinite. An empty range has no infinite
+/// bound.
+#[derive(Debug)]
+pub struct LowerInf {
+    signature: Signature,
+}
+
+impl LowerInf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for LowerInf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "lower_inf"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        range_type(self.name(), &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        range_flags(args, |range| !range.empty && range.lower.is_none())
+    }
+}
+
+impl Documented for LowerInf {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "lower_inf(anyrange) → boolean",
+            description:
+                "Does the range have no lower bound? (A lower bound of -Infinity returns false.)",
+            examples: &["lower_inf(int4range(NULL, 5)) → true"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns whether the upper bound of a range is infinite. An empty range has no infinite
+/// bound.
+#[derive(Debug)]
+pub struct UpperInf {
+    signature: Signature,
+}
+
+impl UpperInf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UpperInf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "upper_inf"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        range_type(self.name(), &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        range_flags(args, |range| !range.empty && range.upper.is_none())
+    }
+}
+
+impl Documented for UpperInf {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "upper_inf(anyrange) → boolean",
+            description:
+                "Does the range have no upper bound? (An upper bound of Infinity returns false.)",
+            examples: &["upper_inf(int4range(1, NULL)) → true"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the smallest range that includes both ranges, even if they leave a gap between them.
+#[derive(Debug)]
+pub struct RangeMerge {
+    signature: Signature,
+}
+
+impl RangeMerge {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeMerge {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_merge"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(same_range_type(self.name(), arg_types)?.data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        combine_ranges(args, |range, other| Ok(range.merge(other)))
+    }
+}
+
+impl Documented for RangeMerge {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_merge(anyrange, anyrange) → anyrange",
+            description: "Computes the smallest range that includes both of the given ranges",
+            examples: &["range_merge(int4range(1, 3), int4range(7, 9)) → [1,9)"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `+` operator for ranges: the union of two ranges, which must overlap or be
+/// adjacent so that the union is a single range.
+#[derive(Debug)]
+pub struct RangeUnion {
+    signature: Signature,
+}
+
+impl RangeUnion {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeUnion {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_union"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(same_range_type(self.name(), arg_types)?.data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        combine_ranges(args, |range, other| {
+            range.union(other).map_err(execution_error)
+        })
+    }
+}
+
+impl Documented for RangeUnion {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_union(anyrange, anyrange) → anyrange",
+            description: "Computes the union of the ranges, as the + operator does. The ranges must overlap or be adjacent, so that the union is a single range",
+            examples: &["range_union(numrange(5, 15), numrange(10, 20)) → [5,20)"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `*` operator for ranges: the intersection of two ranges.
+#[derive(Debug)]
+pub struct RangeIntersect {
+    signature: Signature,
+}
+
+impl RangeIntersect {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeIntersect {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_intersect"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(same_range_type(self.name(), arg_types)?.data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        combine_ranges(args, |range, other| Ok(range.intersection(other)))
+    }
+}
+
+impl Documented for RangeIntersect {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_intersect(anyrange, anyrange) → anyrange",
+            description: "Computes the intersection of the ranges, as the * operator does",
+            examples: &["range_intersect(int4range(5, 15), int4range(10, 20)) → [10,15)"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `-` operator for ranges: the values of the first range that are not in the
+/// second, which must not split the first range in two.
+#[derive(Debug)]
+pub struct RangeMinus {
+    signature: Signature,
+}
+
+impl RangeMinus {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeMinus {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_minus"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(same_range_type(self.name(), arg_types)?.data_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        combine_ranges(args, |range, other| {
+            range.difference(other).map_err(execution_error)
+        })
+    }
+}
+
+impl Documented for RangeMinus {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_minus(anyrange, anyrange) → anyrange",
+            description: "Computes the difference of the ranges, as the - operator does. The second range must not be contained in the first in such a way that the difference would not be a single range",
+            examples: &["range_minus(int4range(5, 15), int4range(10, 20)) → [5,10)"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `@>` operator for ranges: whether the first range contains the second argument,
+/// a range or an element.
+#[derive(Debug)]
+pub struct RangeContains {
+    signature: Signature,
+}
+
+impl RangeContains {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeContains {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_contains"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        containment_type(self.name(), &arg_types[0], &arg_types[1])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        contains(&args[0], &args[1])
+    }
+}
+
+impl Documented for RangeContains {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_contains(anyrange, anyrange or anyelement) → boolean",
+            description:
+                "Does the first range contain the second range or element? As the @> operator does",
+            examples: &[
+                "range_contains(int4range(2, 4), int4range(2, 3)) → true",
+                "range_contains(int4range(1, 2), 1) → true",
+            ],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `<@` operator for ranges: whether the first argument, a range or an element, is
+/// contained in the second range.
+#[derive(Debug)]
+pub struct RangeContainedBy {
+    signature: Signature,
+}
+
+impl RangeContainedBy {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeContainedBy {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_contained_by"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        containment_type(self.name(), &arg_types[1], &arg_types[0])?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        contains(&args[1], &args[0])
+    }
+}
+
+impl Documented for RangeContainedBy {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_contained_by(anyrange or anyelement, anyrange) → boolean",
+            description: "Is the first range or element contained in the second range? As the <@ operator does",
+            examples: &[
+                "range_contained_by(int4range(2, 4), int4range(1, 7)) → true",
+                "range_contained_by(42, int4range(1, 7)) → false",
+            ],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `&&` operator for ranges: whether two ranges have a value in common.
+#[derive(Debug)]
+pub struct RangeOverlaps {
+    signature: Signature,
+}
+
+impl RangeOverlaps {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeOverlaps {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_overlaps"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        same_range_type(self.name(), arg_types)?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        compare_ranges(&args[0], &args[1], Range::overlaps)
+    }
+}
+
+impl Documented for RangeOverlaps {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_overlaps(anyrange, anyrange) → boolean",
+            description: "Do the ranges overlap, that is, have any elements in common? As the && operator does",
+            examples: &["range_overlaps(int8range(3, 7), int8range(4, 12)) → true"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Postgres' `-|-` operator for ranges: whether one range ends where the other starts.
+#[derive(Debug)]
+pub struct RangeAdjacent {
+    signature: Signature,
+}
+
+impl RangeAdjacent {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RangeAdjacent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_adjacent"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        same_range_type(self.name(), arg_types)?;
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        compare_ranges(&args[0], &args[1], Range::is_adjacent)
+    }
+}
+
+impl Documented for RangeAdjacent {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_adjacent(anyrange, anyrange) → boolean",
+            description: "Are the ranges adjacent, with no value between them and none in common? As Postgres' adjacency operator does",
+            examples: &["range_adjacent(numrange(1.1, 2.2), numrange(2.2, 3.3)) → true"],
+            link: Some(RANGE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// An aggregate that returns the union of its ranges as a multirange: a list of the ranges
+/// that are left once the ones that overlap or are adjacent are merged, in order. Returns NULL
+/// when there are no ranges and an empty list when they are all empty.
+#[derive(Debug)]
+pub struct RangeAgg {
+    signature: Signature,
+}
+
+impl RangeAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for RangeAgg {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(range_type(self.name(), &arg_types[0])?.multirange_type())
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        match multirange_type(acc_args.data_type) {
+            Some(range_type) => Ok(Box::new(RangeAggAccumulator {
+                range_type,
+                ranges: None,
+            })),
+            None => exec_err!(
+                "range_agg() returns a multirange, not {}",
+                acc_args.data_type
+            ),
+        }
+    }
+}
+
+impl Documented for RangeAgg {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_agg(anyrange) → anymultirange",
+            description: "An aggregate that computes the union of the non-null input values, as a list of ranges",
+            examples: &["range_agg(X) over [1,3), [2,5) and [7,9) → {[1,5),[7,9)}"],
+            link: Some("https://www.postgresql.org/docs/16/functions-aggregate.html"),
+        }
+    }
+}
+
+/// Gathers the ranges seen so far, `None` until there is one. Its state is the multirange of
+/// those ranges.
+#[derive(Debug)]
+struct RangeAggAccumulator {
+    range_type: RangeType,
+    ranges: Option<Vec<Range<Value>>>,
+}
+
+impl RangeAggAccumulator {
+    fn add(&mut self, ranges: impl IntoIterator<Item = Range<Value>>) {
+        let mut ranges = ranges.into_iter().peekable();
+        if ranges.peek().is_none() {
+            return;
+        }
+        let mut gathered = self.ranges.take().unwrap_or_default();
+        gathered.extend(ranges);
+        self.ranges = Some(multirange(gathered));
+    }
+}
+
+impl Accumulator for RangeAggAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (_, ranges) = ranges(&values[0])?;
+        self.add(ranges.into_iter().flatten());
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let multiranges = multirange_array(self.range_type, vec![self.ranges.clone()])?;
+        ScalarValue::try_from_array(&multiranges, 0)
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+            + self.ranges.as_ref().map_or(0, |ranges| {
+                ranges.capacity() * std::mem::size_of::<Range<Value>>()
+            })
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        let (_, multiranges) = multiranges(&states[0])?;
+        for ranges in multiranges.into_iter().flatten() {
+            // A multirange of empty ranges still says that there were ranges.
+            self.ranges.get_or_insert_with(Vec::new);
+            self.add(ranges);
+        }
+        Ok(())
+    }
+}
+
+/// An aggregate that returns the intersection of its ranges.
+#[derive(Debug)]
+pub struct RangeIntersectAgg {
+    signature: Signature,
+}
+
+impl RangeIntersectAgg {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for RangeIntersectAgg {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "range_intersect_agg"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(range_type(self.name(), &arg_types[0])?.data_type())
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(RangeIntersectAggAccumulator {
+            range_type: range_type(self.name(), acc_args.data_type)?,
+            range: None,
+        }))
+    }
+}
+
+impl Documented for RangeIntersectAgg {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "range_intersect_agg(anyrange) → anyrange",
+            description: "An aggregate that computes the intersection of the non-null input values",
+            examples: &["range_intersect_agg(X) over [1,10), [2,5) and NULL → [2,5)"],
+            link: Some("https://www.postgresql.org/docs/16/functions-aggregate.html"),
+        }
+    }
+}
+
+/// Keeps the intersection of the ranges seen so far, `None` until there is one. Its state is
+/// that range.
+#[derive(Debug)]
+struct RangeIntersectAggAccumulator {
+    range_type: RangeType,
+    range: Option<Range<Value>>,
+}
+
+impl Accumulator for RangeIntersectAggAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        let (_, ranges) = ranges(&values[0])?;
+        for range in ranges.into_iter().flatten() {
+            self.range = Some(match &self.range {
+                Some(intersection) => intersection.intersection(&range),
+                None => range,
+            });
+        }
+        Ok(())
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let ranges = range_array(self.range_type, &[self.range.clone()])?;
+        ScalarValue::try_from_array(&ranges, 0)
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self)
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![self.evaluate()?])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        self.update_batch(states)
+    }
+}
+
+/// Postgres' built-in range types, each stored as a struct of its bounds, as [`RANGE_FIELDS`]
+/// names them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeType {
+    Int4,
+    Int8,
+    /// numrange, whose bounds are stored as doubles.
+    Num,
+    Ts,
+    Tstz,
+    Date,
+}
+
+impl RangeType {
+    pub(crate) const ALL: [RangeType; 6] = [
+        RangeType::Int4,
+        RangeType::Int8,
+        RangeType::Num,
+        RangeType::Ts,
+        RangeType::Tstz,
+        RangeType::Date,
+    ];
+
+    fn name(self) -> &'static str {
+        match self {
+            RangeType::Int4 => "int4range",
+            RangeType::Int8 => "int8range",
+            RangeType::Num => "numrange",
+            RangeType::Ts => "tsrange",
+            RangeType::Tstz => "tstzrange",
+            RangeType::Date => "daterange",
+        }
+    }
+
+    fn from_name(name: &str) -> Result<Self> {
+        let lowercase = name.trim().to_lowercase();
+        match Self::ALL
+            .into_iter()
+            .find(|range_type| range_type.name() == lowercase)
+        {
+            Some(range_type) => Ok(range_type),
+            None => plan_err!("\"{name}\" is not a range type"),
+        }
+    }
+
+    /// The name of the type of the bounds in Postgres' errors.
+    fn subtype_name(self) -> &'static str {
+        match self {
+            RangeType::Int4 => "integer",
+            RangeType::Int8 => "bigint",
+            RangeType::Num => "numeric",
+            RangeType::Ts => "timestamp",
+            RangeType::Tstz => "timestamp with time zone",
+            RangeType::Date => "date",
+        }
+    }
+
+    fn subtype(self) -> DataType {
+        match self {
+            RangeType::Int4 => Int32,
+            RangeType::Int8 => Int64,
+            RangeType::Num => Float64,
+            RangeType::Ts => Timestamp(TimeUnit::Microsecond, None),
+            RangeType::Tstz => Timestamp(TimeUnit::Microsecond, Some("+00:00".into())),
+            RangeType::Date => Date32,
+        }
+    }
+
+    fn fields(self) -> Fields {
+        Fields::from(vec![
+            Field::new(RANGE_FIELDS[0], self.subtype(), true),
+            Field::new(RANGE_FIELDS[1], self.subtype(), true),
+            Field::new(RANGE_FIELDS[2], Boolean, false),
+            Field::new(RANGE_FIELDS[3], Boolean, false),
+            Field::new(RANGE_FIELDS[4], Boolean, false),
+        ])
+    }
+
+    fn data_type(self) -> DataType {
+        Struct(self.fields())
+    }
+
+    fn multirange_type(self) -> DataType {
+        List(Arc::new(Field::new("item", self.data_type(), true)))
+    }
+
+    /// Returns the range type stored as `data_type`, if it is one.
+    fn of(data_type: &DataType) -> Option<Self> {
+        let Struct(fields) = data_type else {
+            return None;
+        };
+        if !fields
+            .iter()
+            .map(|field| field.name().as_str())
+            .eq(RANGE_FIELDS)
+        {
+            return None;
+        }
+        Self::ALL
+            .into_iter()
+            .find(|range_type| &range_type.subtype() == fields[0].data_type())
+    }
+
+    /// Returns the range between two bounds, in its canonical form for a discrete subtype.
+    fn range(
+        self,
+        lower: Option<Value>,
+        upper: Option<Value>,
+        lower_inc: bool,
+        upper_inc: bool,
+    ) -> Result<Range<Value>> {
+        let range = Range::new(lower, upper, lower_inc, upper_inc).map_err(execution_error)?;
+        let max = match self {
+            RangeType::Int4 => i64::from(i32::MAX),
+            RangeType::Int8 => i64::MAX,
+            RangeType::Date => i64::from(i32::MAX),
+            RangeType::Num | RangeType::Ts | RangeType::Tstz => return Ok(range),
+        };
+        range
+            .canonicalize(|value| match value {
+                Value::Integer(value) if *value < max => Ok(Value::Integer(value + 1)),
+                _ => Err(anyhow::anyhow!("{} out of range", self.subtype_name())),
+            })
+            .map_err(execution_error)
+    }
+}
+
+/// A bound of a range: an integer for the integers, dates and timestamps, and a double for
+/// numrange.
+#[derive(Debug, Clone, Copy)]
+enum Value {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Ord for Value {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Value::Integer(value), Value::Integer(other)) => value.cmp(other),
+            (Value::Float(value), Value::Float(other)) => value.total_cmp(other),
+            (Value::Integer(value), Value::Float(other)) => (*value as f64).total_cmp(other),
+            (Value::Float(value), Value::Integer(other)) => value.total_cmp(&(*other as f64)),
+        }
+    }
+}
+
+impl PartialOrd for Value {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Value {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
+    }
+}
+
+impl Eq for Value {}
+
+/// Returns the range type of an argument, or an error naming the function it was given to.
+fn range_type(name: &str, data_type: &DataType) -> Result<RangeType> {
+    match RangeType::of(data_type) {
+        Some(range_type) => Ok(range_type),
+        None => plan_err!("{name}() expects a range, not {data_type}"),
+    }
+}
+
+/// Returns the range type of the two arguments of a function of two ranges, which must be the
+/// same.
+fn same_range_type(name: &str, arg_types: &[DataType]) -> Result<RangeType> {
+    let first = range_type(name, &arg_types[0])?;
+    let second = range_type(name, &arg_types[1])?;
+    if first != second {
+        return plan_err!(
+            "{name}() cannot combine {} and {}",
+            first.name(),
+            second.name()
+        );
+    }
+    Ok(first)
+}
+
+/// Checks the arguments of `@>` or `<@`: a range, and a range of the same type or an element.
+fn containment_type(name: &str, range: &DataType, other: &DataType) -> Result<RangeType> {
+    match RangeType::of(other) {
+        Some(_) => same_range_type(name, &[range.clone(), other.clone()]),
+        None => range_type(name, range),
+    }
+}
+
+/// Returns the range type of a multirange, a list of ranges, if `data_type` is one.
+fn multirange_type(data_type: &DataType) -> Option<RangeType> {
+    match data_type {
+        List(field) => RangeType::of(field.data_type()),
+        _ => None,
+    }
+}
+
+/// Returns which bounds a range constructor's flags make inclusive.
+fn bound_flags(flags: &str) -> Result<(bool, bool)> {
+    match flags {
+        "[]" => Ok((true, true)),
+        "[)" => Ok((true, false)),
+        "(]" => Ok((false, true)),
+        "()" => Ok((false, false)),
+        _ => exec_err!(
+            "invalid range bound flags: Valid values are \"[]\", \"[)\", \"(]\", and \"()\"."
+        ),
+    }
+}
+
+/// Reads the values of an array as bounds of `range_type`, casting them to its subtype. Text
+/// that cannot be cast is reported as the subtype's input function reports it.
+fn subtype_values(range_type: RangeType, array: &ArrayRef) -> Result<Vec<Option<Value>>> {
+    let values = cast(array, &range_type.subtype())?;
+    // The cast leaves NULL where it cannot convert a value.
+    if let Some(index) =
+        (0..values.len()).find(|&index| values.is_null(index) && array.is_valid(index))
+    {
+        let name = range_type.subtype_name();
+        return match string_values(array) {
+            Ok(texts) => exec_err!(
+                "invalid input syntax for type {name}: \"{}\"",
+                texts[index].unwrap_or_default()
+            ),
+            Err(_) => exec_err!("{name} out of range"),
+        };
+    }
+    Ok(match range_type {
+        RangeType::Int4 => values
+            .as_primitive::<Int32Type>()
+            .iter()
+            .map(|value| value.map(|value| Value::Integer(value.into())))
+            .collect(),
+        RangeType::Int8 => values
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|value| value.map(Value::Integer))
+            .collect(),
+        // Adding zero turns -0 into 0, which Postgres' numeric doesn't tell apart.
+        RangeType::Num => values
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|value| value.map(|value| Value::Float(value + 0.0)))
+            .collect(),
+        RangeType::Ts | RangeType::Tstz => values
+            .as_primitive::<TimestampMicrosecondType>()
+            .iter()
+            .map(|value| value.map(Value::Integer))
+            .collect(),
+        RangeType::Date => values
+            .as_primitive::<Date32Type>()
+            .iter()
+            .map(|value| value.map(|value| Value::Integer(value.into())))
+            .collect(),
+    })
+}
+
+/// Builds an array of the subtype of `range_type` from bounds of that type.
+fn subtype_array(range_type: RangeType, values: &[Option<Value>]) -> ArrayRef {
+    let integers = values.iter().map(|value| match value {
+        Some(Value::Integer(value)) => Some(*value),
+        _ => None,
+    });
+    match range_type {
+        RangeType::Int4 => Arc::new(
+            integers
+                .map(|value| value.map(|value| value as i32))
+                .collect::<Int32Array>(),
+        ),
+        RangeType::Int8 => Arc::new(integers.collect::<Int64Array>()),
+        RangeType::Num => Arc::new(
+            values
+                .iter()
+                .map(|value| match value {
+                    Some(Value::Float(value)) => Some(*value),
+                    _ => None,
+                })
+                .collect::<Float64Array>(),
+        ),
+        RangeType::Ts => Arc::new(integers.collect::<TimestampMicrosecondArray>()),
+        RangeType::Tstz => Arc::new(
+            integers
+                .collect::<TimestampMicrosecondArray>()
+                .with_timezone("+00:00"),
+        ),
+        RangeType::Date => Arc::new(
+            integers
+                .map(|value| value.map(|value| value as i32))
+                .collect::<Date32Array>(),
+        ),
+    }
+}
+
+/// Reads an array of ranges.
+fn ranges(array: &ArrayRef) -> Result<(RangeType, Vec<Option<Range<Value>>>)> {
+    let Some(range_type) = RangeType::of(array.data_type()) else {
+        return exec_err!("expected a range, not {}", array.data_type());
+    };
+    let ranges = array.as_struct();
+    let lower = subtype_values(range_type, ranges.column(0))?;
+    let upper = subtype_values(range_type, ranges.column(1))?;
+    let lower_inc = ranges.column(2).as_boolean();
+    let upper_inc = ranges.column(3).as_boolean();
+    let empty = ranges.column(4).as_boolean();
+
+    let ranges = (0..ranges.len())
+        .map(|row| {
+            ranges.is_valid(row).then(|| Range {
+                lower: lower[row],
+                upper: upper[row],
+                lower_inc: lower_inc.value(row),
+                upper_inc: upper_inc.value(row),
+                empty: empty.value(row),
+            })
+        })
+        .collect();
+    Ok((range_type, ranges))
+}
+
+/// Builds an array of ranges of `range_type`.
+fn range_array(range_type: RangeType, ranges: &[Option<Range<Value>>]) -> Result<ArrayRef> {
+    let bounds = |bound: fn(&Range<Value>) -> Option<Value>| {
+        let values = ranges
+            .iter()
+            .map(|range| range.as_ref().and_then(bound))
+            .collect::<Vec<_>>();
+        subtype_array(range_type, &values)
+    };
+    let flags = |flag: fn(&Range<Value>) -> bool| {
+        let values = ranges
+            .iter()
+            .map(|range| range.as_ref().is_some_and(flag))
+            .collect::<Vec<_>>();
+        Arc::new(BooleanArray::from(values)) as ArrayRef
+    };
+    let columns = vec![
+        bounds(|range| range.lower),
+        bounds(|range| range.upper),
+        flags(|range| range.lower_inc),
+        flags(|range| range.upper_inc),
+        flags(|range| range.empty),
+    ];
+    let nulls = NullBuffer::from(ranges.iter().map(Option::is_some).collect::<Vec<_>>());
+    Ok(Arc::new(StructArray::try_new(
+        range_type.fields(),
+        columns,
+        Some(nulls),
+    )?))
+}
+
+/// Reads an array of multiranges, leaving out any NULL ranges in them.
+fn multiranges(array: &ArrayRef) -> Result<(RangeType, Vec<Option<Vec<Range<Value>>>>)> {
+    let lists = array.as_list::<i32>();
+    let (range_type, ranges) = ranges(lists.values())?;
+    let multiranges = lists
+        .value_offsets()
+        .windows(2)
+        .enumerate()
+        .map(|(row, offsets)| {
+            lists.is_valid(row).then(|| {
+                ranges[offsets[0] as usize..offsets[1] as usize]
+                    .iter()
+                    .flatten()
+                    .cloned()
+                    .collect()
+            })
+        })
+        .collect();
+    Ok((range_type, multiranges))
+}
+
+/// Builds an array of multiranges of `range_type`.
+fn multirange_array(
+    range_type: RangeType,
+    multiranges: Vec<Option<Vec<Range<Value>>>>,
+) -> Result<ArrayRef> {
+    let offsets = OffsetBuffer::from_lengths(
+        multiranges
+            .iter()
+            .map(|ranges| ranges.as_ref().map_or(0, Vec::len)),
+    );
+    let nulls = NullBuffer::from(multiranges.iter().map(Option::is_some).collect::<Vec<_>>());
+    let ranges = multiranges
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(Some)
+        .collect::<Vec<_>>();
+    Ok(Arc::new(ListArray::try_new(
+        Arc::new(Field::new("item", range_type.data_type(), true)),
+        offsets,
+        range_array(range_type, &ranges)?,
+        Some(nulls),
+    )?))
+}
+
+/// Returns ranges with their bounds written as Postgres writes their subtype.
+fn range_texts(range_type: RangeType, ranges: &[Range<Value>]) -> Result<Vec<Range<String>>> {
+    let bound_texts = |bound: fn(&Range<Value>) -> Option<Value>| {
+        let values = ranges.iter().map(bound).collect::<Vec<_>>();
+        element_texts(
+            &subtype_array(range_type, &values),
+            IntervalStyle::default(),
+        )
+    };
+    let lower = bound_texts(|range| range.lower)?;
+    let upper = bound_texts(|range| range.upper)?;
+    Ok(ranges
+        .iter()
+        .zip(lower.into_iter().zip(upper))
+        .map(|(range, (lower, upper))| Range {
+            lower,
+            upper,
+            lower_inc: range.lower_inc,
+            upper_inc: range.upper_inc,
+            empty: range.empty,
+        })
+        .collect())
+}
+
+/// Returns the type DataFusion's `lower` and `upper` coerce an argument that is not a range to.
+fn case_argument_type(arg_type: &DataType) -> DataType {
+    match arg_type {
+        LargeUtf8 => LargeUtf8,
+        _ => Utf8,
+    }
+}
+
+/// Calls DataFusion's `lower` or `upper` with an argument that is not a range, coerced to a
+/// string as DataFusion does when it calls the function itself.
+fn change_case(builtin: &ScalarUDF, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let strings = cast(&args[0], &case_argument_type(args[0].data_type()))?;
+    builtin.invoke(&[ColumnarValue::Array(strings)])
+}
+
+/// Returns a bound of each range.
+fn range_bounds(
+    args: &[ColumnarValue],
+    bound: fn(&Range<Value>) -> Option<Value>,
+) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let (range_type, ranges) = ranges(&args[0])?;
+    let values = ranges
+        .iter()
+        .map(|range| range.as_ref().and_then(bound))
+        .collect::<Vec<_>>();
+    Ok(ColumnarValue::Array(subtype_array(range_type, &values)))
+}
+
+/// Returns a flag of each range.
+fn range_flags(args: &[ColumnarValue], flag: fn(&Range<Value>) -> bool) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let (_, ranges) = ranges(&args[0])?;
+    let flags = ranges
+        .iter()
+        .map(|range| range.as_ref().map(flag))
+        .collect::<BooleanArray>();
+    Ok(ColumnarValue::Array(Arc::new(flags)))
+}
+
+/// Combines the ranges of two arguments into a range.
+fn combine_ranges(
+    args: &[ColumnarValue],
+    combine: impl Fn(&Range<Value>, &Range<Value>) -> Result<Range<Value>>,
+) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let (range_type, left) = ranges(&args[0])?;
+    let (_, right) = ranges(&args[1])?;
+    let combined = left
+        .iter()
+        .zip(&right)
+        .map(|(range, other)| match (range, other) {
+            (Some(range), Some(other)) => combine(range, other).map(Some),
+            _ => Ok(None),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    Ok(ColumnarValue::Array(range_array(range_type, &combined)?))
+}
+
+/// Compares the ranges of two arrays.
+fn compare_ranges(
+    left: &ArrayRef,
+    right: &ArrayRef,
+    compare: impl Fn(&Range<Value>, &Range<Value>) -> bool,
+) -> Result<ColumnarValue> {
+    let (_, left) = ranges(left)?;
+    let (_, right) = ranges(right)?;
+    let results = left
+        .iter()
+        .zip(&right)
+        .map(|(range, other)| Some(compare(range.as_ref()?, other.as_ref()?)))
+        .collect::<BooleanArray>();
+    Ok(ColumnarValue::Array(Arc::new(results)))
+}
+
+/// Returns whether each range contains the range or element beside it.
+fn contains(left: &ArrayRef, right: &ArrayRef) -> Result<ColumnarValue> {
+    if RangeType::of(right.data_type()).is_some() {
+        return compare_ranges(left, right, Range::contains);
+    }
+    let (range_type, ranges) = ranges(left)?;
+    let elements = subtype_values(range_type, right)?;
+    let results = ranges
+        .iter()
+        .zip(&elements)
+        .map(|(range, element)| Some(range.as_ref()?.contains_element(element.as_ref()?)))
+        .collect::<BooleanArray>();
+    Ok(ColumnarValue::Array(Arc::new(results)))
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::register_postgres_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_range_constructors() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select int4range(1, 10) as col_range, pg_range_out(int4range(1, 10, '(]')) as col_int4, pg_range_out(int8range(NULL, 5, '[]')) as col_int8, pg_range_out(numrange(1.5, NULL, '()')) as col_num, pg_range_out(int4range(3, 3)) as col_empty",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------------------------------------------------------------------+----------+----------+---------+-----------+
+| col_range                                                              | col_int4 | col_int8 | col_num | col_empty |
++------------------------------------------------------------------------+----------+----------+---------+-----------+
+| {lower: 1, upper: 10, lower_inc: true, upper_inc: false, empty: false} | [2,11)   | (,6)     | (1.5,)  | empty     |
++------------------------------------------------------------------------+----------+----------+---------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select pg_range_out(daterange('2001-01-01', '2001-01-05', '(]')) as col_date, pg_range_out(tsrange('2001-01-01', '2001-01-02 10:00:00')) as col_ts, pg_range_out(tstzrange('2001-01-01T00:00:00+02:00', NULL)) as col_tstz",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------------------+-----------------------------------------------+-----------------------------+
+| col_date                | col_ts                                        | col_tstz                    |
++-------------------------+-----------------------------------------------+-----------------------------+
+| [2001-01-02,2001-01-06) | ["2001-01-01 00:00:00","2001-01-02 10:00:00") | ["2000-12-31 22:00:00+00",) |
++-------------------------+-----------------------------------------------+-----------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select int4range(1, 10, 'x')")
+            .await?
+            .collect()
+            .await;
+        assert!(result.unwrap_err().to_string().contains(
+            "invalid range bound flags: Valid values are \"[]\", \"[)\", \"(]\", and \"()\"."
+        ));
+
+        let result = ctx
+            .sql("select int4range(1, 10, NULL)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("range constructor flags argument must not be null"));
+
+        let result = ctx.sql("select int4range(10, 1)").await?.collect().await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("range lower bound must be less than or equal to range upper bound"));
+
+        let result = ctx
+            .sql("select int4range(1, 2147483647, '[]')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("integer out of range"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pg_range_in() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select column1 as text, pg_range_out(pg_range_in(column1, 'int4range')) as range from (values ('[1,10)'), ('(1,10]'), ('[,5]'), ('(,)'), ('[3,3)'), (' EmPty '), ('[ 1 , 10 )'), ('["1","10")'), (NULL))"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+--------+
+| text       | range  |
++------------+--------+
+|            |        |
+|  EmPty     | empty  |
+| (,)        | (,)    |
+| (1,10]     | [2,11) |
+| ["1","10") | [1,10) |
+| [ 1 , 10 ) | [1,10) |
+| [,5]       | (,6)   |
+| [1,10)     | [1,10) |
+| [3,3)      | empty  |
++------------+--------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                r#"select pg_range_out(pg_range_in('[1.5,"2.5")', 'numrange')) as col_num, pg_range_out(pg_range_in('[2001-01-01, 2001-01-02 10:00:00)', 'tsrange')) as col_ts"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+-----------------------------------------------+
+| col_num   | col_ts                                        |
++-----------+-----------------------------------------------+
+| [1.5,2.5) | ["2001-01-01 00:00:00","2001-01-02 10:00:00") |
++-----------+-----------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select pg_range_in('[1,10', 'int4range')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("malformed range literal: \"[1,10\": Unexpected end of input."));
+
+        let result = ctx
+            .sql("select pg_range_in('[a,b)', 'int4range')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid input syntax for type integer: \"a\""));
+
+        let result = ctx
+            .sql("select pg_range_in('[10,1)', 'int4range')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("range lower bound must be less than or equal to range upper bound"));
+
+        let result = ctx.sql("select pg_range_in('[1,2)', 'textrange')").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("\"textrange\" is not a range type"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_range_accessors() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select lower(int4range(1, 5, '(]')) as col_lower, upper(int4range(1, 5, '(]')) as col_upper, lower(int4range(NULL, 5)) as col_infinite, upper(pg_range_in('empty', 'int4range')) as col_empty",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+-----------+--------------+-----------+
+| col_lower | col_upper | col_infinite | col_empty |
++-----------+-----------+--------------+-----------+
+| 2         | 6         |              |           |
++-----------+-----------+--------------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select column1 as text, isempty(r) as isempty, lower_inc(r) as lower_inc, upper_inc(r) as upper_inc, lower_inf(r) as lower_inf, upper_inf(r) as upper_inf from (select column1, pg_range_in(column1, 'numrange') as r from (values ('[1,5]'), ('(,5)'), ('empty'), (NULL)))",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+---------+-----------+-----------+-----------+-----------+
+| text  | isempty | lower_inc | upper_inc | lower_inf | upper_inf |
++-------+---------+-----------+-----------+-----------+-----------+
+|       |         |           |           |           |           |
+| (,5)  | false   | false     | false     | true      | false     |
+| [1,5] | false   | true      | true      | false     | false     |
+| empty | true    | false     | false     | false     | false     |
++-------+---------+-----------+-----------+-----------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx.sql("select isempty(1)").await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("isempty() expects a range, not Int64"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_range_operators() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select range_contains(int4range(1, 2), 1) as col_element, range_contains(int4range(1, 10), int4range(2, 5)) as col_range, range_contained_by(int4range(2, 5), int4range(1, 3)) as col_contained, range_contained_by(7, int4range(1, 7)) as col_contained_element, range_overlaps(int4range(1, 5), int4range(5, 8)) as col_overlaps, range_adjacent(int4range(1, 3), int4range(3, 5)) as col_adjacent, range_adjacent(numrange(1, 3, '[]'), numrange(3, 5, '()')) as col_adjacent_num, range_adjacent(numrange(1, 3, '[]'), numrange(3, 5)) as col_overlapping_num",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------+-----------+---------------+-----------------------+--------------+--------------+------------------+---------------------+
+| col_element | col_range | col_contained | col_contained_element | col_overlaps | col_adjacent | col_adjacent_num | col_overlapping_num |
++-------------+-----------+---------------+-----------------------+--------------+--------------+------------------+---------------------+
+| true        | true      | false         | false                 | false        | true         | true             | false               |
++-------------+-----------+---------------+-----------------------+--------------+--------------+------------------+---------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select pg_range_out(range_union(int4range(1, 5), int4range(5, 9))) as col_union, pg_range_out(range_intersect(int4range(1, 10), int4range(5, 20))) as col_intersect, pg_range_out(range_minus(int4range(1, 10), int4range(5, 20))) as col_minus, pg_range_out(range_merge(int4range(1, 3), int4range(7, 9))) as col_merge, pg_range_out(range_intersect(int4range(1, 5), int4range(5, 9))) as col_disjoint",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+---------------+-----------+-----------+--------------+
+| col_union | col_intersect | col_minus | col_merge | col_disjoint |
++-----------+---------------+-----------+-----------+--------------+
+| [1,9)     | [5,10)        | [1,5)     | [1,9)     | empty        |
++-----------+---------------+-----------+-----------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select range_union(int4range(1, 5), int4range(7, 9))")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("result of range union would not be contiguous"));
+
+        let result = ctx
+            .sql("select range_minus(int4range(1, 5), int4range(2, 3))")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("result of range difference would not be contiguous"));
+
+        let result = ctx
+            .sql("select range_overlaps(int4range(1, 5), numrange(2, 3))")
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("range_overlaps() cannot combine int4range and numrange"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_range_agg() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select pg_range_out(range_agg(r)) as col_agg, pg_range_out(range_intersect_agg(r)) as col_intersect_agg from (select pg_range_in(column1, 'int4range') as r from (values ('[1,3)'), ('[2,5)'), ('[6,7)'), ('[5,6)'), (NULL), ('empty'), ('[10,12)')))",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------------+-------------------+
+| col_agg         | col_intersect_agg |
++-----------------+-------------------+
+| {[1,7),[10,12)} | empty             |
++-----------------+-------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select pg_range_out(range_agg(r)) as col_empty, pg_range_out(range_agg(n)) as col_null, pg_range_out(range_intersect_agg(i)) as col_intersect_agg from (select pg_range_in(column1, 'int4range') as r, pg_range_in(column2, 'int4range') as n, pg_range_in(column3, 'int4range') as i from (values ('empty', CAST(NULL AS VARCHAR), '[1,10)'), ('empty', NULL, '[2,5)'), ('empty', NULL, NULL)))",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+----------+-------------------+
+| col_empty | col_null | col_intersect_agg |
++-----------+----------+-------------------+
+| {}        |          | [2,5)             |
++-----------+----------+-------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_lower_and_upper_of_strings() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select lower('ABC') as col_lower, lower(NULL) as col_null, upper(1) as col_number, upper(arrow_cast('abc', 'Dictionary(Int32, Utf8)')) as col_dictionary",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+----------+------------+----------------+
+| col_lower | col_null | col_number | col_dictionary |
++-----------+----------+------------+----------------+
+| abc       |          | 1          | ABC            |
++-----------+----------+------------+----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,913 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, BooleanBuilder, Int64Builder, ListBuilder, StringBuilder,
+};
+use datafusion::arrow::datatypes::DataType::{Boolean, Int64};
+use datafusion::arrow::datatypes::{DataType, Field, Schema};
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::cast::as_int64_array;
+use datafusion::common::{plan_err, ScalarValue};
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::datasource::{MemTable, TableProvider};
+use datafusion::error::Result;
+use datafusion::logical_expr::{
+    ColumnarValue, Expr, ScalarUDFImpl, Signature, TypeSignature, Volatility,
+};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{
+    exact_signatures, string_return_type, string_values, StringOutput, STRING_TYPES,
+};
+use crate::postgres::regexp::{self, PgRegex, RegexCache};
+use crate::postgres::string_udfs::{execution_error, text_list_type};
+
+const PATTERN_MATCHING_LINK: &str = "https://www.postgresql.org/docs/16/functions-matching.html";
+
+/// Returns the substrings of the first match of a regular expression in a string: the captured
+/// groups, or the whole match if the pattern has none. Returns NULL if there is no match.
+#[derive(Debug)]
+pub struct RegexpMatch {
+    signature: Signature,
+}
+
+impl RegexpMatch {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                optional_signatures(&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES], 2),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpMatch {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_match"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(text_list_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut list_builder = ListBuilder::new(StringBuilder::new());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let flags = optional_strings(&args, 2, "")?;
+        let mut regexes = RegexCache::default();
+
+        for ((string, pattern), flags) in strings.into_iter().zip(patterns).zip(flags) {
+            let (Some(string), Some(pattern), Some(flags)) = (string, pattern, flags) else {
+                list_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            match regexp::regexp_match(string, regex).map_err(execution_error)? {
+                Some(groups) => list_builder.append_value(groups),
+                None => list_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(list_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for RegexpMatch {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_match(string text, pattern text [, flags text]) → text[]",
+            description: "Returns substrings within the first match of the POSIX regular expression pattern to the string",
+            examples: &[
+                "regexp_match('foobarbequebaz', '(bar)(beque)') → {bar,beque}",
+                "regexp_match('foobarbequebaz', 'bar.*que') → {barbeque}",
+            ],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Returns the number of times a regular expression matches in a string, without overlaps.
+#[derive(Debug)]
+pub struct RegexpCount {
+    signature: Signature,
+}
+
+impl RegexpCount {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                optional_signatures(&[&STRING_TYPES, &STRING_TYPES, &[Int64], &STRING_TYPES], 2),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpCount {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_count"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut int64_builder = Int64Builder::with_capacity(args[0].len());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let starts = optional_integers(&args, 2, 1)?;
+        let flags = optional_strings(&args, 3, "")?;
+        let mut regexes = RegexCache::default();
+
+        for i in 0..args[0].len() {
+            let (Some(string), Some(pattern), Some(start), Some(flags)) =
+                (strings[i], patterns[i], starts[i], flags[i])
+            else {
+                int64_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            int64_builder
+                .append_value(regexp::regexp_count(string, regex, start).map_err(execution_error)?);
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(int64_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for RegexpCount {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_count(string text, pattern text [, start integer [, flags text]]) → integer",
+            description: "Returns the number of times the POSIX regular expression pattern matches in the string",
+            examples: &["regexp_count('123456789012', '\\d\\d\\d', 2) → 3"],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Returns the position of the N'th match of a regular expression in a string, or of one of
+/// its groups, or the position after it. Returns 0 if there is no such match.
+#[derive(Debug)]
+pub struct RegexpInstr {
+    signature: Signature,
+}
+
+impl RegexpInstr {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                optional_signatures(
+                    &[
+                        &STRING_TYPES,
+                        &STRING_TYPES,
+                        &[Int64],
+                        &[Int64],
+                        &[Int64],
+                        &STRING_TYPES,
+                        &[Int64],
+                    ],
+                    2,
+                ),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpInstr {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_instr"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut int64_builder = Int64Builder::with_capacity(args[0].len());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let starts = optional_integers(&args, 2, 1)?;
+        let ns = optional_integers(&args, 3, 1)?;
+        let end_options = optional_integers(&args, 4, 0)?;
+        let flags = optional_strings(&args, 5, "")?;
+        let subexprs = optional_integers(&args, 6, 0)?;
+        let mut regexes = RegexCache::default();
+
+        for i in 0..args[0].len() {
+            let (
+                Some(string),
+                Some(pattern),
+                Some(start),
+                Some(n),
+                Some(end_option),
+                Some(flags),
+                Some(subexpr),
+            ) = (
+                strings[i],
+                patterns[i],
+                starts[i],
+                ns[i],
+                end_options[i],
+                flags[i],
+                subexprs[i],
+            )
+            else {
+                int64_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            int64_builder.append_value(
+                regexp::regexp_instr(string, regex, start, n, end_option, subexpr)
+                    .map_err(execution_error)?,
+            );
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(int64_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for RegexpInstr {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_instr(string text, pattern text [, start integer [, N integer [, endoption integer [, flags text [, subexpr integer]]]]]) → integer",
+            description: "Returns the position within string where the N'th match of the POSIX regular expression pattern occurs, or zero if there is no such match",
+            examples: &[
+                "regexp_instr('ABCDEF', 'c(.)(..)', 1, 1, 0, 'i') → 3",
+                "regexp_instr('ABCDEF', 'c(.)(..)', 1, 1, 0, 'i', 2) → 5",
+            ],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Checks whether a regular expression matches in a string.
+#[derive(Debug)]
+pub struct RegexpLike {
+    signature: Signature,
+}
+
+impl RegexpLike {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                optional_signatures(&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES], 2),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpLike {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_like"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut boolean_builder = BooleanBuilder::with_capacity(args[0].len());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let flags = optional_strings(&args, 2, "")?;
+        let mut regexes = RegexCache::default();
+
+        for ((string, pattern), flags) in strings.into_iter().zip(patterns).zip(flags) {
+            let (Some(string), Some(pattern), Some(flags)) = (string, pattern, flags) else {
+                boolean_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            regex
+                .reject_global("regexp_like")
+                .map_err(execution_error)?;
+            boolean_builder.append_value(regex.is_match(string));
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(boolean_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for RegexpLike {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_like(string text, pattern text [, flags text]) → boolean",
+            description: "Checks whether a match of the POSIX regular expression pattern occurs within string",
+            examples: &["regexp_like('Hello World', 'world$', 'i') → true"],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Returns the N'th match of a regular expression in a string, or one of its groups. Returns
+/// NULL if there is no such match.
+#[derive(Debug)]
+pub struct RegexpSubstr {
+    signature: Signature,
+}
+
+impl RegexpSubstr {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                optional_signatures(
+                    &[
+                        &STRING_TYPES,
+                        &STRING_TYPES,
+                        &[Int64],
+                        &[Int64],
+                        &STRING_TYPES,
+                        &[Int64],
+                    ],
+                    2,
+                ),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpSubstr {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_substr"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let starts = optional_integers(&args, 2, 1)?;
+        let ns = optional_integers(&args, 3, 1)?;
+        let flags = optional_strings(&args, 4, "")?;
+        let subexprs = optional_integers(&args, 5, 0)?;
+        let mut regexes = RegexCache::default();
+
+        for i in 0..args[0].len() {
+            let (Some(string), Some(pattern), Some(start), Some(n), Some(flags), Some(subexpr)) = (
+                strings[i],
+                patterns[i],
+                starts[i],
+                ns[i],
+                flags[i],
+                subexprs[i],
+            ) else {
+                string_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            match regexp::regexp_substr(string, regex, start, n, subexpr)
+                .map_err(execution_error)?
+            {
+                Some(substring) => string_builder.append_value(substring),
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for RegexpSubstr {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_substr(string text, pattern text [, start integer [, N integer [, flags text [, subexpr integer]]]]) → text",
+            description: "Returns the substring within string that matches the N'th occurrence of the POSIX regular expression pattern, or NULL if there is no such match",
+            examples: &[
+                "regexp_substr('ABCDEF', 'c(.)(..)', 1, 1, 'i') → CDEF",
+                "regexp_substr('ABCDEF', 'c(.)(..)', 1, 1, 'i', 2) → EF",
+            ],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Replaces the first match of a regular expression in a string, every match with the `g` flag,
+/// or the N'th match, or every match if N is 0.
+#[derive(Debug)]
+pub struct RegexpReplace {
+    signature: Signature,
+}
+
+impl RegexpReplace {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    optional_signatures(
+                        &[
+                            &STRING_TYPES,
+                            &STRING_TYPES,
+                            &STRING_TYPES,
+                            &[Int64],
+                            &[Int64],
+                            &STRING_TYPES,
+                        ],
+                        3,
+                    ),
+                    // The flags can also follow the replacement, without a start.
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpReplace {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_replace"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let replacements = string_values(&args[2])?;
+        let flags_only = args.len() == 4 && args[3].data_type() != &Int64;
+        let (starts, ns, flags) = if flags_only {
+            (
+                vec![Some(1); args[0].len()],
+                vec![Some(None); args[0].len()],
+                string_values(&args[3])?,
+            )
+        } else {
+            (
+                optional_integers(&args, 3, 1)?,
+                match args.get(4) {
+                    Some(ns) => as_int64_array(ns)?.iter().map(|n| n.map(Some)).collect(),
+                    None => vec![Some(None); args[0].len()],
+                },
+                optional_strings(&args, 5, "")?,
+            )
+        };
+        let mut regexes = RegexCache::default();
+
+        for i in 0..args[0].len() {
+            // A NULL N is a NULL argument, unlike an N that is not given.
+            let (Some(string), Some(pattern), Some(replacement), Some(start), Some(n), Some(flags)) = (
+                strings[i],
+                patterns[i],
+                replacements[i],
+                starts[i],
+                ns[i],
+                flags[i],
+            ) else {
+                string_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            string_builder.append_value(
+                regexp::regexp_replace(string, regex, replacement, start, n)
+                    .map_err(execution_error)?,
+            );
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for RegexpReplace {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_replace(string text, pattern text, replacement text [, start integer [, N integer]] [, flags text]) → text",
+            description: "Replaces the substring that is the first match to the POSIX regular expression pattern, or all such matches if the g flag is used, or the N'th match, or all such matches if N is zero",
+            examples: &[
+                "regexp_replace('Thomas', '.[mN]a.', 'M') → ThM",
+                "regexp_replace('A PostgreSQL function', 'a|e|i|o|u', 'X', 1, 3, 'i') → A PostgrXSQL function",
+            ],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// The set-returning regexp_matches(), a table function with one row of substrings per match: of
+/// the first match, or of every match with the `g` flag. Its arguments must be literals.
+pub struct RegexpMatches;
+
+impl TableFunctionImpl for RegexpMatches {
+    fn call(&self, args: &[Expr]) -> Result<Arc<dyn TableProvider>> {
+        if !(2..=3).contains(&args.len()) {
+            return plan_err!("regexp_matches() takes a string, a pattern and optional flags");
+        }
+        let args = args
+            .iter()
+            .map(|arg| match arg {
+                Expr::Literal(ScalarValue::Utf8(value) | ScalarValue::LargeUtf8(value)) => {
+                    Ok(value.as_deref())
+                }
+                Expr::Literal(ScalarValue::Null) => Ok(None),
+                _ => plan_err!("regexp_matches() only takes string literals, not {arg}"),
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut list_builder = ListBuilder::new(StringBuilder::new());
+        if let (Some(string), Some(pattern), Some(flags)) =
+            (args[0], args[1], args.get(2).copied().unwrap_or(Some("")))
+        {
+            let regex = PgRegex::new(pattern, flags).map_err(execution_error)?;
+            for groups in regexp::regexp_matches(string, &regex) {
+                list_builder.append_value(groups);
+            }
+        }
+
+        let schema = Arc::new(Schema::new(vec![Field::new(
+            "regexp_matches",
+            text_list_type(),
+            false,
+        )]));
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(list_builder.finish()) as ArrayRef],
+        )?;
+        Ok(Arc::new(MemTable::try_new(schema, vec![vec![batch]])?))
+    }
+}
+
+impl Documented for RegexpMatches {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_matches(string text, pattern text [, flags text]) → setof text[]",
+            description: "Returns substrings within the first match of the POSIX regular expression pattern to the string, or substrings within all such matches if the g flag is used",
+            examples: &["select * from regexp_matches('foobarbequebaz', 'ba.', 'g') → {bar}, {baz}"],
+            link: Some(PATTERN_MATCHING_LINK),
+        }
+    }
+}
+
+/// Returns the signatures of a function taking one type of each of `arguments`, of which those
+/// after the first `required` can be left out from the end.
+fn optional_signatures(arguments: &[&[DataType]], required: usize) -> Vec<TypeSignature> {
+    (required..=arguments.len())
+        .flat_map(|count| exact_signatures(&arguments[..count]))
+        .collect()
+}
+
+/// Returns the strings of the argument at `index`, or `default` for every row if it is not given.
+fn optional_strings<'a>(
+    args: &'a [ArrayRef],
+    index: usize,
+    default: &'a str,
+) -> Result<Vec<Option<&'a str>>> {
+    match args.get(index) {
+        Some(values) => string_values(values),
+        None => Ok(vec![Some(default); args[0].len()]),
+    }
+}
+
+/// Returns the integers of the argument at `index`, or `default` for every row if it is not
+/// given.
+fn optional_integers(args: &[ArrayRef], index: usize, default: i64) -> Result<Vec<Option<i64>>> {
+    match args.get(index) {
+        Some(values) => Ok(as_int64_array(values)?.iter().collect()),
+        None => Ok(vec![Some(default); args[0].len()]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{LargeStringArray, StringViewArray};
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_strings_data_test;
+    use crate::postgres::register_postgres_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_regexp_match() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, regexp_match(name, '(\w)(\w*)$') as col_groups, regexp_match(name, 'o.', 'i') as col_flags, regexp_match(name, '(x)?[.]') as col_missing FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------+-----------+-------------+
+| index | col_groups | col_flags | col_missing |
++-------+------------+-----------+-------------+
+| 1     | [T, HOMAS] | [OM]      |             |
+| 2     | [n, eil]   | [o']      |             |
+| 3     | [s, elect] |           |             |
+| 4     | [b, ]      |           | []          |
++-------+------------+-----------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select regexp_match('abc', 'b', 'g')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("regexp_match() does not support the \"global\" option"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regexp_matches() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select * from regexp_matches('foobarbequebazilbarfbonk', '(b[^b]+)(b[^b]+)', 'g')",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------+
+| regexp_matches |
++----------------+
+| [bar, beque]   |
+| [bazil, barf]  |
++----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql("select * from regexp_matches('foobarbequebaz', 'ba.')")
+            .await?;
+        let batches = df.clone().collect().await?;
+        let expected = [
+            "+----------------+",
+            "| regexp_matches |",
+            "+----------------+",
+            "| [bar]          |",
+            "+----------------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx.sql("select * from regexp_matches('abc', NULL)").await?;
+        assert_eq!(df.count().await?, 0);
+
+        assert!(ctx
+            .sql("select * from regexp_matches(upper('abc'), 'b')")
+            .await
+            .is_err());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regexp_count_instr_like() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, regexp_count(name, '[aeiou]') as col_count, regexp_count(name, '[aeiou]', 3, 'i') as col_count_start, regexp_instr(name, '[aeiou]', 1, 2) as col_instr, regexp_instr(name, '(e)(.)', 1, 1, 1, 'i', 1) as col_instr_end, regexp_like(name, '\mthomas\M', 'i') as col_like FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------+-----------------+-----------+---------------+----------+
+| index | col_count | col_count_start | col_instr | col_instr_end | col_like |
++-------+-----------+-----------------+-----------+---------------+----------+
+| 1     | 1         | 2               | 0         | 0             | true     |
+| 2     | 3         | 2               | 4         | 5             | false    |
+| 3     | 2         | 1               | 4         | 3             | false    |
+| 4     | 1         | 0               | 0         | 0             | false    |
++-------+-----------+-----------------+-----------+---------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select regexp_count('abc', 'b', 0)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid value for parameter \"start\": 0"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regexp_substr() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select regexp_substr('ABCDEFGHI', '(c..)(...)', 1, 1, 'i', 2) as col_group, regexp_substr('abcabc', 'b', 3) as col_start, regexp_substr('abc', 'x') as col_none, regexp_substr('abab', 'b', 1, 2) as col_n"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+-----------+----------+-------+
+| col_group | col_start | col_none | col_n |
++-----------+-----------+----------+-------+
+| FGH       | b         |          | b     |
++-----------+-----------+----------+-------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regexp_replace() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, regexp_replace(name, '[aeiou]', '<\&>') as col_first, regexp_replace(name, '([aeiou])', '\1\1', 'gi') as col_global, regexp_replace(name, '[aeiou]', '_', 4) as col_start, regexp_replace(name, '[aeiou]', '_', 1, 2) as col_n, regexp_replace(name, '[aeiou]', '_', 1, 0, 'i') as col_all FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-------------+---------------+-----------+-----------+-----------+
+| index | col_first   | col_global    | col_start | col_n     | col_all   |
++-------+-------------+---------------+-----------+-----------+-----------+
+| 1     | h<i> THOMAS | hii THOOMAAS  | hi THOMAS | hi THOMAS | h_ TH_M_S |
+| 2     | <o>'neil    | oo'neeiil     | o'n_il    | o'n_il    | _'n__l    |
+| 3     | s<e>lect    | seeleect      | sel_ct    | sel_ct    | s_l_ct    |
+| 4     | <a>.b       | aa.b          | a.b       | a.b       | _.b       |
++-------+-------------+---------------+-----------+-----------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select regexp_replace('abc', 'b', 'X', 1, -1)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid value for parameter \"n\": -1"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_large_strings_and_views() -> Result<()> {
+        let strings: ArrayRef = Arc::new(LargeStringArray::from(vec![Some("abcabc"), None]));
+        let result = RegexpReplace::new().invoke(&[
+            ColumnarValue::Array(strings),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("b".to_string()))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("X".to_string()))),
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some("g".to_string()))),
+        ])?;
+        let ColumnarValue::Array(result) = result else {
+            panic!("Expected an array");
+        };
+        assert_eq!(
+            result.as_any().downcast_ref::<LargeStringArray>().unwrap(),
+            &LargeStringArray::from(vec![Some("aXcaXc"), None])
+        );
+
+        let strings: ArrayRef = Arc::new(StringViewArray::from(vec![
+            Some("a string longer than twelve bytes"),
+            Some("short"),
+        ]));
+        let result = RegexpSubstr::new().invoke(&[
+            ColumnarValue::Array(strings),
+            ColumnarValue::Scalar(ScalarValue::LargeUtf8(Some(r"\w+$".to_string()))),
+        ])?;
+        let ColumnarValue::Array(result) = result else {
+            panic!("Expected an array");
+        };
+        assert_eq!(
+            result.as_any().downcast_ref::<StringViewArray>().unwrap(),
+            &StringViewArray::from(vec![Some("bytes"), Some("short")])
+        );
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = set_up_strings_data_test()?;
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,847 @@
+//! Postgres' regular expressions, "AREs", run with the regex crate.
+//!
+//! Patterns are translated to the syntax of the regex crate: the constraint escapes `\m`, `\M`,
+//! `\y` and `\Y`, and `[[:<:]]` and `[[:>:]]`, become word boundaries, and the escapes that mean
+//! something else in the regex crate, such as `\b` for a backspace, are rewritten. The flags, such
+//! as `i` or `n`, and the embedded options `***=` and `(?i)` become options of the regex crate.
+//! Back references and lookaround constraints have no equivalent and fail to compile.
+//!
+//! Positions and counts are in characters, as in a UTF8 database.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, bail, Result};
+use regex::{Captures, Regex, RegexBuilder};
+
+/// A pattern compiled with the flags it was given.
+#[derive(Debug)]
+pub(crate) struct PgRegex {
+    regex: Regex,
+    /// The `g` flag, which asks for every match rather than the first one.
+    global: bool,
+}
+
+impl PgRegex {
+    pub(crate) fn new(pattern: &str, flags: &str) -> Result<Self> {
+        let mut options = RegexOptions::parse(flags)?;
+        let pattern = options.parse_embedded(pattern)?;
+        let translated = if options.literal {
+            regex::escape(pattern)
+        } else {
+            translate(pattern, &options)?
+        };
+
+        let regex = RegexBuilder::new(&translated)
+            .case_insensitive(options.case_insensitive)
+            .dot_matches_new_line(!options.newline_sensitive_dot)
+            .multi_line(options.newline_sensitive_anchors)
+            .ignore_whitespace(options.expanded && !options.literal)
+            .build()
+            .map_err(|err| anyhow!("invalid regular expression: {err}"))?;
+        Ok(Self {
+            regex,
+            global: options.global,
+        })
+    }
+
+    pub(crate) fn is_global(&self) -> bool {
+        self.global
+    }
+
+    /// Fails if the `g` flag was given to `function`, which only looks for one match.
+    pub(crate) fn reject_global(&self, function: &str) -> Result<()> {
+        if self.global {
+            bail!("{function}() does not support the \"global\" option");
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_match(&self, string: &str) -> bool {
+        self.regex.is_match(string)
+    }
+
+    /// Returns the matches in `string` from the byte offset `start`, without overlaps. An empty
+    /// match moves the search one character further.
+    fn captures_from<'h>(&self, string: &'h str, start: usize) -> Vec<Captures<'h>> {
+        let mut matches = vec![];
+        let mut search_start = start;
+        while let Some(captures) = self.regex.captures_at(string, search_start) {
+            let found = captures.get(0).expect("group 0 is the match");
+            search_start = found.end();
+            if found.is_empty() {
+                search_start += string[search_start..]
+                    .chars()
+                    .next()
+                    .map_or(1, char::len_utf8);
+            }
+            matches.push(captures);
+            if search_start > string.len() {
+                break;
+            }
+        }
+        matches
+    }
+}
+
+/// The patterns compiled for a batch, so that a pattern used by every row is compiled once.
+#[derive(Default)]
+pub(crate) struct RegexCache<'a> {
+    compiled: HashMap<(&'a str, &'a str), PgRegex>,
+}
+
+impl<'a> RegexCache<'a> {
+    /// Patterns compiled at most, so that a batch of distinct patterns does not keep them all.
+    const CAPACITY: usize = 256;
+
+    pub(crate) fn get(&mut self, pattern: &'a str, flags: &'a str) -> Result<&PgRegex> {
+        if !self.compiled.contains_key(&(pattern, flags)) {
+            if self.compiled.len() >= Self::CAPACITY {
+                self.compiled.clear();
+            }
+            self.compiled
+                .insert((pattern, flags), PgRegex::new(pattern, flags)?);
+        }
+        Ok(&self.compiled[&(pattern, flags)])
+    }
+}
+
+/// The flags of a pattern, see "ARE Embedded-Option Letters" in the Postgres documentation.
+#[derive(Debug, Default)]
+struct RegexOptions {
+    global: bool,
+    case_insensitive: bool,
+    /// `.` and bracket expressions with `^` do not match a newline.
+    newline_sensitive_dot: bool,
+    /// `^` and `$` match at the start and end of lines.
+    newline_sensitive_anchors: bool,
+    expanded: bool,
+    literal: bool,
+}
+
+impl RegexOptions {
+    fn parse(flags: &str) -> Result<Self> {
+        let mut options = Self::default();
+        for flag in flags.chars() {
+            if flag == 'g' {
+                options.global = true;
+            } else {
+                options.set(flag)?;
+            }
+        }
+        Ok(options)
+    }
+
+    fn set(&mut self, flag: char) -> Result<()> {
+        match flag {
+            'c' => self.case_insensitive = false,
+            'i' => self.case_insensitive = true,
+            'n' | 'm' => {
+                (self.newline_sensitive_dot, self.newline_sensitive_anchors) = (true, true)
+            }
+            'p' => (self.newline_sensitive_dot, self.newline_sensitive_anchors) = (true, false),
+            's' => (self.newline_sensitive_dot, self.newline_sensitive_anchors) = (false, false),
+            'w' => (self.newline_sensitive_dot, self.newline_sensitive_anchors) = (false, true),
+            'x' => self.expanded = true,
+            't' => self.expanded = false,
+            'q' => self.literal = true,
+            // Extended REs are, but for a few escapes, a subset of AREs.
+            'e' => {}
+            'b' => bail!("basic regular expressions are not supported"),
+            _ => bail!("invalid regular expression option: \"{flag}\""),
+        }
+        Ok(())
+    }
+
+    /// Applies the director `***=` or `***:` and the embedded options `(?...)` at the start of
+    /// `pattern`, and returns the rest of it.
+    fn parse_embedded<'p>(&mut self, pattern: &'p str) -> Result<&'p str> {
+        if let Some(rest) = pattern.strip_prefix("***=") {
+            self.literal = true;
+            return Ok(rest);
+        }
+        let pattern = pattern.strip_prefix("***:").unwrap_or(pattern);
+        if self.literal {
+            return Ok(pattern);
+        }
+        let Some(options) = pattern.strip_prefix("(?") else {
+            return Ok(pattern);
+        };
+        let Some(end) = options.find(')') else {
+            return Ok(pattern);
+        };
+        if !options[..end].chars().all(|c| c.is_ascii_alphabetic()) {
+            return Ok(pattern);
+        }
+        for flag in options[..end].chars() {
+            if flag == 'g' {
+                bail!("invalid embedded option \"g\"");
+            }
+            self.set(flag)?;
+        }
+        Ok(&options[end + 1..])
+    }
+}
+
+/// Translates the ARE `pattern` to the syntax of the regex crate.
+fn translate(pattern: &str, options: &RegexOptions) -> Result<String> {
+    let chars = pattern.chars().collect::<Vec<_>>();
+    let mut out = String::with_capacity(pattern.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let rest = &chars[i..];
+        match rest[0] {
+            '\\' => i += translate_escape(rest, false, &mut out)?,
+            '[' if starts_with(rest, "[[:<:]]") => {
+                out.push_str(r"\b{start}");
+                i += 7;
+            }
+            '[' if starts_with(rest, "[[:>:]]") => {
+                out.push_str(r"\b{end}");
+                i += 7;
+            }
+            '[' => i += translate_bracket(rest, options, &mut out)?,
+            '(' if starts_with(rest, "(?=")
+                || starts_with(rest, "(?!")
+                || starts_with(rest, "(?<=")
+                || starts_with(rest, "(?<!") =>
+            {
+                bail!("lookahead and lookbehind constraints are not supported");
+            }
+            // A brace that does not start a bound, like `a{b`, is an ordinary character.
+            '{' => match bound_length(&rest[1..]) {
+                Some(length) => {
+                    out.extend(&rest[..length + 1]);
+                    i += length + 1;
+                }
+                None => {
+                    out.push_str(r"\{");
+                    i += 1;
+                }
+            },
+            c @ (']' | '}') => {
+                out.push('\\');
+                out.push(c);
+                i += 1;
+            }
+            c => {
+                out.push(c);
+                i += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn starts_with(chars: &[char], prefix: &str) -> bool {
+    prefix
+        .chars()
+        .enumerate()
+        .all(|(i, c)| chars.get(i) == Some(&c))
+}
+
+/// Returns the length of the bound that `chars`, which follow a `{`, start with, such as `2}`,
+/// `2,}` or `2,5}`, or `None` if they do not start with one.
+fn bound_length(chars: &[char]) -> Option<usize> {
+    let digits = |chars: &[char]| chars.iter().take_while(|c| c.is_ascii_digit()).count();
+    let low = digits(chars);
+    if low == 0 {
+        return None;
+    }
+    let end = match chars.get(low) {
+        Some('}') => low,
+        Some(',') => low + 1 + digits(&chars[low + 1..]),
+        _ => return None,
+    };
+    (chars.get(end) == Some(&'}')).then_some(end + 1)
+}
+
+/// Translates the escape at the start of `chars` and returns its length in characters.
+fn translate_escape(chars: &[char], in_bracket: bool, out: &mut String) -> Result<usize> {
+    let Some(&c) = chars.get(1) else {
+        bail!("invalid regular expression: invalid escape \\ sequence");
+    };
+    let hex = |count: usize| -> Result<u32> {
+        let digits = chars.get(2..2 + count).unwrap_or_default();
+        if digits.len() < count || !digits.iter().all(char::is_ascii_hexdigit) {
+            bail!("invalid regular expression: invalid escape \\ sequence");
+        }
+        Ok(u32::from_str_radix(&digits.iter().collect::<String>(), 16)?)
+    };
+    let mut length = 2;
+
+    match c {
+        'm' | 'M' | 'y' | 'Y' | 'A' | 'Z' if in_bracket => {
+            bail!("invalid regular expression: invalid escape \\ sequence")
+        }
+        'm' => out.push_str(r"\b{start}"),
+        'M' => out.push_str(r"\b{end}"),
+        'y' => out.push_str(r"\b"),
+        'Y' => out.push_str(r"\B"),
+        'A' => out.push_str(r"\A"),
+        'Z' => out.push_str(r"\z"),
+        'd' | 'D' | 's' | 'S' | 'w' | 'W' | 'a' | 'f' | 'n' | 'r' | 't' | 'v' => {
+            out.push('\\');
+            out.push(c);
+        }
+        'b' => out.push_str(r"\x08"),
+        'B' => out.push_str(r"\\"),
+        'e' => out.push_str(r"\x1B"),
+        '0' => out.push_str(r"\x00"),
+        '1'..='9' => bail!("back references are not supported"),
+        'c' => {
+            let Some(control) = chars.get(2) else {
+                bail!("invalid regular expression: invalid escape \\ sequence");
+            };
+            out.push_str(&format!(r"\x{{{:X}}}", u32::from(*control) & 0x1F));
+            length = 3;
+        }
+        'u' => {
+            out.push_str(&format!(r"\x{{{:X}}}", hex(4)?));
+            length = 6;
+        }
+        'U' => {
+            out.push_str(&format!(r"\x{{{:X}}}", hex(8)?));
+            length = 10;
+        }
+        'x' => {
+            let digits = chars[2..]
+                .iter()
+                .take_while(|c| c.is_ascii_hexdigit())
+                .count();
+            out.push_str(&format!(r"\x{{{:X}}}", hex(digits)?));
+            length = 2 + digits;
+        }
+        c if c.is_alphanumeric() => {
+            bail!("invalid regular expression: invalid escape \\ sequence")
+        }
+        c => out.push_str(&regex::escape(&c.to_string())),
+    }
+    Ok(length)
+}
+
+/// Translates the bracket expression at the start of `chars` and returns its length in
+/// characters.
+fn translate_bracket(chars: &[char], options: &RegexOptions, out: &mut String) -> Result<usize> {
+    let mut i = 1;
+    out.push('[');
+    let negated = chars.get(i) == Some(&'^');
+    if negated {
+        out.push('^');
+        i += 1;
+    }
+    // A `]` first in the brackets is an ordinary character.
+    if chars.get(i) == Some(&']') {
+        out.push_str(r"\]");
+        i += 1;
+    }
+
+    loop {
+        let rest = &chars[i.min(chars.len())..];
+        match rest.first() {
+            None => bail!("invalid regular expression: brackets [] not balanced"),
+            Some(']') => {
+                if negated && options.newline_sensitive_dot {
+                    out.push_str(r"\n");
+                }
+                out.push(']');
+                return Ok(i + 1);
+            }
+            Some('\\') => i += translate_escape(rest, true, out)?,
+            Some('[') if matches!(rest.get(1), Some(':' | '.' | '=')) => {
+                let delimiter = rest[1];
+                let end = (2..rest.len())
+                    .find(|&end| rest[end] == delimiter && rest.get(end + 1) == Some(&']'))
+                    .ok_or_else(|| {
+                        anyhow!("invalid regular expression: brackets [] not balanced")
+                    })?;
+                let name = rest[2..end].iter().collect::<String>();
+                match delimiter {
+                    ':' => out.push_str(&format!("[:{name}:]")),
+                    // Collating elements and equivalence classes of one character are that
+                    // character, since the regex crate has no collation.
+                    _ if name.chars().count() == 1 => out.push_str(&regex::escape(&name)),
+                    _ => bail!("invalid regular expression: invalid collating element"),
+                }
+                i += end + 2;
+            }
+            // Characters that the regex crate would read as nested classes or set operations.
+            Some(c @ ('[' | '&' | '~')) => {
+                out.push('\\');
+                out.push(*c);
+                i += 1;
+            }
+            Some(c) => {
+                out.push(*c);
+                i += 1;
+            }
+        }
+    }
+}
+
+/// Returns the byte offset of the 1-based character position `start` of `string`, or `None` if
+/// it is past the end of `string`.
+fn start_offset(string: &str, start: i64) -> Result<Option<usize>> {
+    if start <= 0 {
+        bail!("invalid value for parameter \"start\": {start}");
+    }
+    let skipped = start as usize - 1;
+    Ok(string
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain(std::iter::once(string.len()))
+        .nth(skipped))
+}
+
+/// Returns the 1-based character position of the byte offset `offset` of `string`.
+fn char_position(string: &str, offset: usize) -> i64 {
+    string[..offset].chars().count() as i64 + 1
+}
+
+/// The captured groups of a match, or the whole match if the pattern has no groups.
+fn groups<'h>(captures: &Captures<'h>) -> Vec<Option<&'h str>> {
+    if captures.len() == 1 {
+        return vec![captures.get(0).map(|found| found.as_str())];
+    }
+    captures
+        .iter()
+        .skip(1)
+        .map(|group| group.map(|group| group.as_str()))
+        .collect()
+}
+
+/// Returns the groups of the first match of `regex` in `string`, like regexp_match().
+pub(crate) fn regexp_match<'h>(
+    string: &'h str,
+    regex: &PgRegex,
+) -> Result<Option<Vec<Option<&'h str>>>> {
+    if regex.global {
+        bail!("regexp_match() does not support the \"global\" option: Use the regexp_matches function instead.");
+    }
+    Ok(regex
+        .regex
+        .captures(string)
+        .map(|captures| groups(&captures)))
+}
+
+/// Returns the groups of every match of `regex` in `string` if it has the `g` flag, or of the
+/// first one, like regexp_matches().
+pub(crate) fn regexp_matches<'h>(string: &'h str, regex: &PgRegex) -> Vec<Vec<Option<&'h str>>> {
+    let mut matches = regex.captures_from(string, 0);
+    if !regex.global {
+        matches.truncate(1);
+    }
+    matches.iter().map(groups).collect()
+}
+
+/// Counts the matches of `regex` in `string` from the character position `start`.
+pub(crate) fn regexp_count(string: &str, regex: &PgRegex, start: i64) -> Result<i64> {
+    regex.reject_global("regexp_count")?;
+    Ok(match start_offset(string, start)? {
+        Some(offset) => regex.captures_from(string, offset).len() as i64,
+        None => 0,
+    })
+}
+
+/// Returns the `n`th match of `regex` in `string` from the character position `start`.
+fn nth_match<'h>(
+    string: &'h str,
+    regex: &PgRegex,
+    start: i64,
+    n: i64,
+    subexpr: i64,
+) -> Result<Option<Captures<'h>>> {
+    let offset = start_offset(string, start)?;
+    if n <= 0 {
+        bail!("invalid value for parameter \"n\": {n}");
+    }
+    if subexpr < 0 {
+        bail!("invalid value for parameter \"subexpr\": {subexpr}");
+    }
+    let Some(offset) = offset else {
+        return Ok(None);
+    };
+    Ok(regex
+        .captures_from(string, offset)
+        .into_iter()
+        .nth(n as usize - 1))
+}
+
+/// Returns the character position of the `n`th match of `regex` in `string` from `start`, or
+/// of its group `subexpr` if not 0, or the position after it if `end_option` is 1. Returns 0 if
+/// there is no such match.
+pub(crate) fn regexp_instr(
+    string: &str,
+    regex: &PgRegex,
+    start: i64,
+    n: i64,
+    end_option: i64,
+    subexpr: i64,
+) -> Result<i64> {
+    regex.reject_global("regexp_instr")?;
+    if end_option != 0 && end_option != 1 {
+        bail!("invalid value for parameter \"endoption\": {end_option}");
+    }
+    let found = nth_match(string, regex, start, n, subexpr)?
+        .and_then(|captures| captures.get(subexpr as usize));
+    Ok(match found {
+        Some(found) if end_option == 0 => char_position(string, found.start()),
+        Some(found) => char_position(string, found.end()),
+        None => 0,
+    })
+}
+
+/// Returns the `n`th match of `regex` in `string` from `start`, or its group `subexpr` if not 0.
+pub(crate) fn regexp_substr<'h>(
+    string: &'h str,
+    regex: &PgRegex,
+    start: i64,
+    n: i64,
+    subexpr: i64,
+) -> Result<Option<&'h str>> {
+    regex.reject_global("regexp_substr")?;
+    Ok(nth_match(string, regex, start, n, subexpr)?
+        .and_then(|captures| captures.get(subexpr as usize))
+        .map(|found| found.as_str()))
+}
+
+/// Replaces the `n`th match of `regex` in `string` from `start` with `replacement`, or every
+/// match if `n` is 0. Without `n`, every match is replaced if `regex` has the `g` flag and the
+/// first one otherwise. In `replacement`, `\1` to `\9` are the groups of the match and `\&` the
+/// whole match.
+pub(crate) fn regexp_replace(
+    string: &str,
+    regex: &PgRegex,
+    replacement: &str,
+    start: i64,
+    n: Option<i64>,
+) -> Result<String> {
+    let offset = start_offset(string, start)?;
+    let n = match n {
+        Some(n) if n < 0 => bail!("invalid value for parameter \"n\": {n}"),
+        Some(n) => n,
+        None if regex.global => 0,
+        None => 1,
+    };
+    let Some(offset) = offset else {
+        return Ok(string.to_string());
+    };
+
+    let mut out = String::with_capacity(string.len());
+    let mut copied = 0;
+    for (index, captures) in regex.captures_from(string, offset).iter().enumerate() {
+        if n != 0 && index as i64 + 1 != n {
+            continue;
+        }
+        let found = captures.get(0).expect("group 0 is the match");
+        out.push_str(&string[copied..found.start()]);
+        expand_replacement(replacement, captures, &mut out);
+        copied = found.end();
+    }
+    out.push_str(&string[copied..]);
+    Ok(out)
+}
+
+fn expand_replacement(replacement: &str, captures: &Captures, out: &mut String) {
+    let mut chars = replacement.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.peek() {
+            Some(digit @ '1'..='9') => {
+                let group = digit.to_digit(10).expect("a digit") as usize;
+                if let Some(found) = captures.get(group) {
+                    out.push_str(found.as_str());
+                }
+                chars.next();
+            }
+            Some('&') => {
+                out.push_str(&captures[0]);
+                chars.next();
+            }
+            Some('\\') => {
+                out.push('\\');
+                chars.next();
+            }
+            _ => out.push('\\'),
+        }
+    }
+}
+
+/// Splits `string` at the matches of `regex`, like regexp_split_to_array(). Empty matches at the
+/// start or end of the string, or right after another match, do not split it.
+pub(crate) fn regexp_split<'h>(string: &'h str, regex: &PgRegex) -> Result<Vec<&'h str>> {
+    regex.reject_global("regexp_split_to_array")?;
+    let mut fields = vec![];
+    let mut field_start = 0;
+    let mut previous_match_end = 0;
+
+    for captures in regex.captures_from(string, 0) {
+        let found = captures.get(0).expect("group 0 is the match");
+        if found.start() < string.len() && found.end() > previous_match_end {
+            fields.push(&string[field_start..found.start()]);
+            field_start = found.end();
+        }
+        previous_match_end = found.end();
+    }
+    fields.push(&string[field_start..]);
+    Ok(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regex(pattern: &str, flags: &str) -> PgRegex {
+        PgRegex::new(pattern, flags).unwrap()
+    }
+
+    fn first_match<'h>(
+        string: &'h str,
+        pattern: &str,
+        flags: &str,
+    ) -> Option<Vec<Option<&'h str>>> {
+        regexp_match(string, &regex(pattern, flags)).unwrap()
+    }
+
+    fn error(pattern: &str, flags: &str) -> String {
+        PgRegex::new(pattern, flags).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(
+            first_match("hello world", r"\mw\w*", ""),
+            Some(vec![Some("world")])
+        );
+        assert_eq!(
+            first_match("foo bar", "[[:<:]]b..[[:>:]]", ""),
+            Some(vec![Some("bar")])
+        );
+        assert_eq!(
+            first_match("foobar bar", r"\yba\w\y", ""),
+            Some(vec![Some("bar")])
+        );
+        assert_eq!(first_match("ab", r"a\M", ""), None);
+        assert_eq!(first_match("a{b", "a{b", ""), Some(vec![Some("a{b")]));
+        assert_eq!(first_match("aaa", "a{2}", ""), Some(vec![Some("aa")]));
+        assert_eq!(first_match("a\\b", r"a\Bb", ""), Some(vec![Some("a\\b")]));
+        assert_eq!(
+            first_match("a\u{8}b", r"a\bb", ""),
+            Some(vec![Some("a\u{8}b")])
+        );
+        assert_eq!(first_match("a1", "[[:digit:]]", ""), Some(vec![Some("1")]));
+        assert_eq!(first_match("[", "[[]", ""), Some(vec![Some("[")]));
+        assert_eq!(first_match("a]", "[]a]+", ""), Some(vec![Some("a]")]));
+        assert_eq!(first_match("a&b", "[&]", ""), Some(vec![Some("&")]));
+        assert_eq!(first_match("bab", "[[=a=]]", ""), Some(vec![Some("a")]));
+        assert_eq!(first_match("x=y", "[[.=.]]", ""), Some(vec![Some("=")]));
+        assert_eq!(first_match("é", r"é", ""), Some(vec![Some("é")]));
+        assert_eq!(first_match("é", r"\xe9", ""), Some(vec![Some("é")]));
+        assert_eq!(first_match("abc", "***=a.c", ""), None);
+        assert_eq!(first_match("a.c", "***=a.c", ""), Some(vec![Some("a.c")]));
+        assert_eq!(first_match("aBc", "(?i)b", ""), Some(vec![Some("B")]));
+        assert_eq!(first_match("aBc", "b", "i"), Some(vec![Some("B")]));
+        assert_eq!(first_match("AB", "a", "c"), None);
+        assert_eq!(first_match("a b", "a b", "x"), None);
+    }
+
+    #[test]
+    fn test_newlines() {
+        assert_eq!(first_match("ab\ncd", "b.c", ""), Some(vec![Some("b\nc")]));
+        assert_eq!(first_match("ab\ncd", "^c.", ""), None);
+        assert_eq!(first_match("ab\ncd", "^c.", "n"), Some(vec![Some("cd")]));
+        assert_eq!(first_match("ab\ncd", "[^x]+", "n"), Some(vec![Some("ab")]));
+        assert_eq!(first_match("ab\ncd", "b.c", "p"), None);
+        assert_eq!(first_match("ab\ncd", "^c", "p"), None);
+        assert_eq!(first_match("ab\ncd", "^c.", "w"), Some(vec![Some("cd")]));
+        assert_eq!(first_match("ab\ncd", "b.c", "w"), Some(vec![Some("b\nc")]));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(error("a", "z"), "invalid regular expression option: \"z\"");
+        assert_eq!(error("(a)b\\1", ""), "back references are not supported");
+        assert_eq!(
+            error("a(?=b)", ""),
+            "lookahead and lookbehind constraints are not supported"
+        );
+        assert!(error("(a", "").starts_with("invalid regular expression: "));
+        assert_eq!(
+            regexp_match("abc", &regex("b", "g")).unwrap_err().to_string(),
+            "regexp_match() does not support the \"global\" option: Use the regexp_matches function instead."
+        );
+    }
+
+    #[test]
+    fn test_regexp_match() {
+        assert_eq!(
+            first_match("foobarbequebaz", "(bar)(beque)", ""),
+            Some(vec![Some("bar"), Some("beque")])
+        );
+        assert_eq!(
+            first_match("foobarbequebaz", "bar.*que", ""),
+            Some(vec![Some("barbeque")])
+        );
+        assert_eq!(first_match("abc", "x", ""), None);
+        assert_eq!(first_match("abc", "(x)?b", ""), Some(vec![None]));
+    }
+
+    #[test]
+    fn test_regexp_matches() {
+        assert_eq!(
+            regexp_matches("foobarbequebazilbarfbonk", &regex("(b[^b]+)(b[^b]+)", "g")),
+            vec![
+                vec![Some("bar"), Some("beque")],
+                vec![Some("bazil"), Some("barf")]
+            ]
+        );
+        assert_eq!(
+            regexp_matches("abcabc", &regex("b", "")),
+            vec![vec![Some("b")]]
+        );
+        assert_eq!(
+            regexp_matches("ab", &regex("", "g")),
+            vec![vec![Some("")], vec![Some("")], vec![Some("")]]
+        );
+        assert!(regexp_matches("abc", &regex("x", "g")).is_empty());
+    }
+
+    #[test]
+    fn test_regexp_count() {
+        let count =
+            |string, pattern, start, flags| regexp_count(string, &regex(pattern, flags), start);
+        assert_eq!(count("ABCABCAXYaxy", "A.", 1, "i").unwrap(), 4);
+        assert_eq!(count("abcabc", "b", 3, "").unwrap(), 1);
+        assert_eq!(count("aaa", "aa", 1, "").unwrap(), 1);
+        assert_eq!(count("abc", "", 1, "").unwrap(), 4);
+        assert_eq!(count("abc", "^b", 2, "").unwrap(), 0);
+        assert_eq!(count("abc", "b", 9, "").unwrap(), 0);
+        assert_eq!(
+            count("abc", "b", 0, "").unwrap_err().to_string(),
+            "invalid value for parameter \"start\": 0"
+        );
+        assert_eq!(
+            count("abc", "b", 1, "g").unwrap_err().to_string(),
+            "regexp_count() does not support the \"global\" option"
+        );
+    }
+
+    #[test]
+    fn test_regexp_instr() {
+        let instr = |string, pattern, start, n, end_option, flags, subexpr| {
+            regexp_instr(
+                string,
+                &regex(pattern, flags),
+                start,
+                n,
+                end_option,
+                subexpr,
+            )
+        };
+        assert_eq!(
+            instr("ABCDEFGHI", "(c..)(...)", 1, 1, 0, "i", 2).unwrap(),
+            6
+        );
+        assert_eq!(instr("ABCDEF", "c(.)(..)", 1, 1, 1, "i", 0).unwrap(), 7);
+        assert_eq!(instr("abc", "x", 1, 1, 0, "", 0).unwrap(), 0);
+        assert_eq!(instr("abcabc", "b", 3, 1, 0, "", 0).unwrap(), 5);
+        assert_eq!(instr("aéb", "b", 1, 1, 0, "", 0).unwrap(), 3);
+        assert_eq!(instr("abc", "b", 1, 1, 0, "", 3).unwrap(), 0);
+        assert_eq!(
+            instr("abc", "b", 1, 0, 0, "", 0).unwrap_err().to_string(),
+            "invalid value for parameter \"n\": 0"
+        );
+        assert_eq!(
+            instr("abc", "b", 1, 1, 2, "", 0).unwrap_err().to_string(),
+            "invalid value for parameter \"endoption\": 2"
+        );
+        assert_eq!(
+            instr("abc", "b", 1, 1, 0, "", -1).unwrap_err().to_string(),
+            "invalid value for parameter \"subexpr\": -1"
+        );
+    }
+
+    #[test]
+    fn test_regexp_substr() {
+        let substr = |string, pattern, start, n, flags, subexpr| {
+            regexp_substr(string, &regex(pattern, flags), start, n, subexpr)
+        };
+        assert_eq!(
+            substr("ABCDEFGHI", "(c..)(...)", 1, 1, "i", 2).unwrap(),
+            Some("FGH")
+        );
+        assert_eq!(substr("abcabc", "b", 3, 1, "", 0).unwrap(), Some("b"));
+        assert_eq!(substr("abc", "(x)?b", 1, 1, "", 1).unwrap(), None);
+        assert_eq!(substr("abc", "b", 1, 1, "", 3).unwrap(), None);
+        assert_eq!(substr("abab", "b", 1, 2, "", 0).unwrap(), Some("b"));
+        assert_eq!(substr("abab", "b", 1, 3, "", 0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_regexp_replace() {
+        let replace = |string, pattern, replacement, start, n, flags| {
+            regexp_replace(string, &regex(pattern, flags), replacement, start, n).unwrap()
+        };
+        assert_eq!(replace("Thomas", ".[mN]a.", "M", 1, None, ""), "ThM");
+        assert_eq!(
+            replace("fooBarbaz", "b(..)", r"X\1Y", 1, None, "gi"),
+            "fooXarYXazY"
+        );
+        assert_eq!(
+            replace("A PostgreSQL function", "a|e|i|o|u", "X", 1, Some(0), "i"),
+            "X PXstgrXSQL fXnctXXn"
+        );
+        assert_eq!(
+            replace("A PostgreSQL function", "a|e|i|o|u", "X", 1, Some(3), "i"),
+            "A PostgrXSQL function"
+        );
+        assert_eq!(replace("abcabc", "b", "X", 3, None, ""), "abcaXc");
+        assert_eq!(replace("abcabc", "b", "X", 1, Some(2), "g"), "abcaXc");
+        assert_eq!(replace("abc", "b", r"[\&\\]", 1, None, ""), r"a[b\]c");
+        assert_eq!(replace("abc", "(x)?b", r"[\1]", 1, None, ""), "a[]c");
+        assert_eq!(replace("abc", "b", r"\x\", 1, None, ""), r"a\x\c");
+        assert_eq!(replace("aaa", "", "X", 1, None, "g"), "XaXaXaX");
+        assert_eq!(
+            regexp_replace("abc", &regex("b", ""), "X", 1, Some(-1))
+                .unwrap_err()
+                .to_string(),
+            "invalid value for parameter \"n\": -1"
+        );
+    }
+
+    #[test]
+    fn test_regexp_split() {
+        let split = |string, pattern, flags| regexp_split(string, &regex(pattern, flags)).unwrap();
+        assert_eq!(
+            split("hello world", r"\s*", ""),
+            vec!["h", "e", "l", "l", "o", "w", "o", "r", "l", "d"]
+        );
+        assert_eq!(split("a1b22c", r"\d+", ""), vec!["a", "b", "c"]);
+        assert_eq!(split("abc", "", ""), vec!["a", "b", "c"]);
+        assert_eq!(split("a,b,", ",", ""), vec!["a", "b", ""]);
+        assert_eq!(split("", "x", ""), vec![""]);
+        assert_eq!(split("aBc", "b", "i"), vec!["a", "c"]);
+        assert_eq!(split("a.b", ".", "q"), vec!["a", "b"]);
+        assert_eq!(split("ab\ncd", "b.c", ""), vec!["a", "d"]);
+        assert_eq!(split("ab\ncd", "^c", "n"), vec!["ab\n", "d"]);
+        assert_eq!(
+            regexp_split("abc", &regex("b", "g"))
+                .unwrap_err()
+                .to_string(),
+            "regexp_split_to_array() does not support the \"global\" option"
+        );
+    }
+
+    #[test]
+    fn test_cache() {
+        let mut cache = RegexCache::default();
+        assert!(cache.get("a+", "").unwrap().is_match("caa"));
+        assert!(cache.get("A+", "i").unwrap().is_match("caa"));
+        assert!(cache.get("a+", "g").unwrap().is_global());
+        assert_eq!(cache.compiled.len(), 3);
+        assert!(cache.get("a", "z").is_err());
+    }
+}
@@ -0,0 +1,1280 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, AsArray, ListBuilder, StringBuilder};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::DataType::{Boolean, Float32, Float64, Int64, List, Utf8};
+use datafusion::arrow::datatypes::{DataType, Field, Float32Type, Float64Type};
+use datafusion::common::cast::{as_boolean_array, as_int64_array};
+use datafusion::common::DataFusionError;
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::Uniform;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{
+    exact_signatures, string_return_type, string_values, StringOutput, STRING_TYPES,
+};
+use crate::postgres::regexp::{self, RegexCache};
+use crate::postgres::text;
+
+const STRING_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/16/functions-string.html";
+
+/// Formats arguments according to a format string, like sprintf but with Postgres' `%s`, `%I`
+/// and `%L` conversions. Returns NULL if the format string is NULL.
+#[derive(Debug)]
+pub struct Format {
+    signature: Signature,
+}
+
+impl Format {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Format {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "format"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let columns = args.iter().map(text_values).collect::<Result<Vec<_>>>()?;
+
+        for (row, format) in columns[0].iter().enumerate() {
+            let Some(format) = format else {
+                string_builder.append_null();
+                continue;
+            };
+            let row_args = columns[1..]
+                .iter()
+                .map(|column| column[row].clone())
+                .collect::<Vec<_>>();
+            string_builder.append_value(text::format(format, &row_args).map_err(execution_error)?);
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Format {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "format(formatstr text [, formatarg \"any\" [, ...] ]) → text",
+            description: "Formats arguments according to a format string, with %s for text, %I for an SQL identifier and %L for an SQL literal",
+            examples: &["format('Hello %s, %1$s', 'World') → Hello World, World"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the given string suitably quoted to be used as an identifier in an SQL statement
+/// string. Quotes are added only if necessary.
+#[derive(Debug)]
+pub struct QuoteIdent {
+    signature: Signature,
+}
+
+impl QuoteIdent {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for QuoteIdent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "quote_ident"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+
+        for ident in string_values(&args[0])? {
+            match ident {
+                Some(ident) => string_builder.append_value(text::quote_ident(ident)),
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for QuoteIdent {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "quote_ident(text) → text",
+            description: "Returns the given string suitably quoted to be used as an identifier in an SQL statement string",
+            examples: &["quote_ident('Foo bar') → \"Foo bar\""],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the given value, converted to text, suitably quoted to be used as a string literal
+/// in an SQL statement string. Returns NULL for NULL.
+#[derive(Debug)]
+pub struct QuoteLiteral {
+    signature: Signature,
+}
+
+impl QuoteLiteral {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for QuoteLiteral {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "quote_literal"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
+
+        for value in text_values(&args[0])? {
+            match value {
+                Some(value) => string_builder.append_value(text::quote_literal(&value)),
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for QuoteLiteral {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "quote_literal(anyelement) → text",
+            description: "Returns the given value converted to text, suitably quoted to be used as a string literal in an SQL statement string",
+            examples: &["quote_literal(E'O\\'Reilly') → 'O''Reilly'", "quote_literal(42.5) → '42.5'"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Like quote_literal, but returns the string `NULL`, unquoted, for NULL.
+#[derive(Debug)]
+pub struct QuoteNullable {
+    signature: Signature,
+}
+
+impl QuoteNullable {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for QuoteNullable {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "quote_nullable"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder = StringBuilder::with_capacity(args[0].len(), u8::MAX as usize);
+
+        for value in text_values(&args[0])? {
+            match value {
+                Some(value) => string_builder.append_value(text::quote_literal(&value)),
+                None => string_builder.append_value("NULL"),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(string_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for QuoteNullable {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "quote_nullable(anyelement) → text",
+            description: "Returns the given value converted to text, suitably quoted to be used as a string literal in an SQL statement string, or NULL unquoted for NULL",
+            examples: &["quote_nullable(NULL) → NULL", "quote_nullable(42.5) → '42.5'"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Splits a string at occurrences of a delimiter and returns the n'th field, counting from one,
+/// or from the end when n is negative. Returns an empty string if there is no such field.
+#[derive(Debug)]
+pub struct SplitPart {
+    signature: Signature,
+}
+
+impl SplitPart {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &[Int64]]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for SplitPart {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "split_part"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let strings = string_values(&args[0])?;
+        let delimiters = string_values(&args[1])?;
+        let fields = as_int64_array(&args[2])?;
+
+        for ((string, delimiter), n) in strings.into_iter().zip(delimiters).zip(fields) {
+            match (string, delimiter, n) {
+                (Some(string), Some(delimiter), Some(n)) => string_builder
+                    .append_value(text::split_part(string, delimiter, n).map_err(execution_error)?),
+                _ => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for SplitPart {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "split_part(string text, delimiter text, n integer) → text",
+            description: "Splits string at occurrences of delimiter and returns the n'th field (counting from one), or counting from the end when n is negative",
+            examples: &[
+                "split_part('abc~@~def~@~ghi', '~@~', 2) → def",
+                "split_part('abc,def,ghi,jkl', ',', -2) → ghi",
+            ],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Splits a string at occurrences of a delimiter into an array, or into characters if the
+/// delimiter is NULL. Fields equal to the optional null string become NULL.
+#[derive(Debug)]
+pub struct StringToArray {
+    signature: Signature,
+}
+
+impl StringToArray {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES]),
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for StringToArray {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "string_to_array"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(text_list_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut list_builder = ListBuilder::new(StringBuilder::new());
+        let strings = string_values(&args[0])?;
+        let delimiters = string_values(&args[1])?;
+        let null_strings = match args.get(2) {
+            Some(null_strings) => string_values(null_strings)?,
+            None => vec![None; strings.len()],
+        };
+
+        for ((string, delimiter), null_string) in
+            strings.into_iter().zip(delimiters).zip(null_strings)
+        {
+            match string {
+                Some(string) => {
+                    list_builder.append_value(text::string_to_array(string, delimiter, null_string))
+                }
+                None => list_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(list_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for StringToArray {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "string_to_array(string text, delimiter text [, null_string text]) → text[]",
+            description: "Splits the string at occurrences of delimiter and forms the resulting fields into a text array. If delimiter is NULL, each character becomes an element; fields matching null_string are replaced by NULL",
+            examples: &["string_to_array('xx~~yy~~zz', '~~', 'yy') → {xx,NULL,zz}"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Splits a string using a POSIX regular expression as the delimiter, producing an array of
+/// results. The optional flags are those of Postgres, e.g. `i` for a case insensitive match.
+#[derive(Debug)]
+pub struct RegexpSplitToArray {
+    signature: Signature,
+}
+
+impl RegexpSplitToArray {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES]),
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RegexpSplitToArray {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "regexp_split_to_array"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(text_list_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut list_builder = ListBuilder::new(StringBuilder::new());
+        let strings = string_values(&args[0])?;
+        let patterns = string_values(&args[1])?;
+        let flags = match args.get(2) {
+            Some(flags) => string_values(flags)?,
+            None => vec![Some(""); strings.len()],
+        };
+        let mut regexes = RegexCache::default();
+
+        for ((string, pattern), flags) in strings.into_iter().zip(patterns).zip(flags) {
+            let (Some(string), Some(pattern), Some(flags)) = (string, pattern, flags) else {
+                list_builder.append_null();
+                continue;
+            };
+            let regex = regexes.get(pattern, flags).map_err(execution_error)?;
+            let fields = regexp::regexp_split(string, regex).map_err(execution_error)?;
+            list_builder.append_value(fields.into_iter().map(Some));
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(list_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for RegexpSplitToArray {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "regexp_split_to_array(string text, pattern text [, flags text]) → text[]",
+            description: "Splits string using a POSIX regular expression as the delimiter, producing an array of results",
+            examples: &["regexp_split_to_array('hello world', '\\s+') → {hello,world}"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Replaces the substring of a string that starts at the start'th character and extends for
+/// count characters with another string. If count is omitted, it defaults to the length of the
+/// new string.
+#[derive(Debug)]
+pub struct Overlay {
+    signature: Signature,
+}
+
+impl Overlay {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &[Int64]]),
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES, &[Int64], &[Int64]]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Overlay {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "overlay"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let strings = string_values(&args[0])?;
+        let replacements = string_values(&args[1])?;
+        let starts = as_int64_array(&args[2])?;
+        let counts = args
+            .get(3)
+            .map(|counts| as_int64_array(counts))
+            .transpose()?;
+
+        for (i, (string, replacement)) in strings.into_iter().zip(replacements).enumerate() {
+            let count = match counts {
+                Some(counts) if counts.is_null(i) => None,
+                Some(counts) => Some(Some(counts.value(i))),
+                None => Some(None),
+            };
+            match (string, replacement, starts.is_valid(i), count) {
+                (Some(string), Some(replacement), true, Some(count)) => string_builder
+                    .append_value(
+                        text::overlay(string, replacement, starts.value(i), count)
+                            .map_err(execution_error)?,
+                    ),
+                _ => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Overlay {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "overlay(string text PLACING newsubstring text FROM start integer [ FOR count integer ]) → text",
+            description: "Replaces the substring of string that starts at the start'th character and extends for count characters with newsubstring. If count is omitted, it defaults to the length of newsubstring",
+            examples: &["overlay('Txxxxas' placing 'hom' from 2 for 4) → Thomas"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Converts the first letter of each word to upper case and the rest to lower case. Words are
+/// sequences of alphanumeric characters separated by non-alphanumeric characters.
+#[derive(Debug)]
+pub struct Initcap {
+    signature: Signature,
+}
+
+impl Initcap {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Initcap {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "initcap"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+
+        for string in string_values(&args[0])? {
+            match string {
+                Some(string) => string_builder.append_value(text::initcap(string)),
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Initcap {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "initcap(text) → text",
+            description: "Converts the first letter of each word to upper case and the rest to lower case. Words are sequences of alphanumeric characters separated by non-alphanumeric characters",
+            examples: &["initcap('hi THOMAS') → Hi Thomas"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Converts a string to ASCII from another encoding, which may be LATIN1 (the default), LATIN2,
+/// LATIN9 or WIN1250, by replacing its accented letters with unaccented ones. Since the strings
+/// are Unicode, the characters are read as the characters of that encoding.
+#[derive(Debug)]
+pub struct ToAscii {
+    signature: Signature,
+}
+
+impl ToAscii {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    vec![Uniform(1, STRING_TYPES.to_vec())],
+                    exact_signatures(&[&STRING_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ToAscii {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "to_ascii"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+        let strings = string_values(&args[0])?;
+        let encodings = match args.get(1) {
+            Some(encodings) => string_values(encodings)?,
+            None => vec![Some("LATIN1"); strings.len()],
+        };
+
+        for (string, encoding) in strings.into_iter().zip(encodings) {
+            match (string, encoding) {
+                (Some(string), Some(encoding)) => string_builder
+                    .append_value(text::to_ascii(string, encoding).map_err(execution_error)?),
+                _ => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for ToAscii {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "to_ascii(string text [, encoding name]) → text",
+            description: "Converts string to ASCII from another encoding, which may be LATIN1 (the default), LATIN2, LATIN9 or WIN1250, mostly by dropping accents",
+            examples: &["to_ascii('Karél') → Karel"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Splits a qualified identifier into an array of identifiers, removing any quoting of the
+/// individual identifiers and lower casing the unquoted ones. Unless strict is false, extra
+/// characters after the last identifier are an error.
+#[derive(Debug)]
+pub struct ParseIdent {
+    signature: Signature,
+}
+
+impl ParseIdent {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    vec![Uniform(1, STRING_TYPES.to_vec())],
+                    exact_signatures(&[&STRING_TYPES, &[Boolean]]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for ParseIdent {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "parse_ident"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(text_list_type())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut list_builder = ListBuilder::new(StringBuilder::new());
+        let strings = string_values(&args[0])?;
+        let stricts = match args.get(1) {
+            Some(stricts) => as_boolean_array(stricts)?.iter().collect(),
+            None => vec![Some(true); strings.len()],
+        };
+
+        for (string, strict) in strings.into_iter().zip(stricts) {
+            match (string, strict) {
+                (Some(string), Some(strict)) => {
+                    let names = text::parse_ident(string, strict).map_err(execution_error)?;
+                    list_builder.append_value(names.into_iter().map(Some));
+                }
+                _ => list_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(list_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for ParseIdent {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "parse_ident(qualified_identifier text [, strict_mode boolean DEFAULT true]) → text[]",
+            description: "Splits qualified_identifier into an array of identifiers, removing any quoting of individual identifiers",
+            examples: &["parse_ident('\"SomeSchema\".someTable') → {SomeSchema,sometable}"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Evaluates the Unicode escapes of a string: `\XXXX`, `\+XXXXXX`, `\uXXXX` and `\UXXXXXXXX`
+/// for a code point, and `\\` for a backslash.
+#[derive(Debug)]
+pub struct Unistr {
+    signature: Signature,
+}
+
+impl Unistr {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, STRING_TYPES.to_vec(), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Unistr {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "unistr"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(string_return_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut string_builder =
+            StringOutput::new(&string_return_type(args[0].data_type()), args[0].len());
+
+        for string in string_values(&args[0])? {
+            match string {
+                Some(string) => {
+                    string_builder.append_value(text::unistr(string).map_err(execution_error)?)
+                }
+                None => string_builder.append_null(),
+            }
+        }
+
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Unistr {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "unistr(text) → text",
+            description: "Evaluates escaped Unicode characters in the argument: \\XXXX, \\+XXXXXX, \\uXXXX or \\UXXXXXXXX",
+            examples: &["unistr('d\\0061t\\+000061') → data"],
+            link: Some(STRING_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The type of the text arrays returned by string_to_array, regexp_split_to_array and
+/// parse_ident.
+pub(super) fn text_list_type() -> DataType {
+    List(Arc::new(Field::new("item", Utf8, true)))
+}
+
+/// Returns the values of `array` as Postgres writes them as text, e.g. `t` for true.
+pub(super) fn text_values(array: &ArrayRef) -> Result<Vec<Option<String>>> {
+    let to_strings = |values: Vec<Option<&str>>| -> Vec<Option<String>> {
+        values
+            .into_iter()
+            .map(|value| value.map(String::from))
+            .collect()
+    };
+    match array.data_type() {
+        data_type if STRING_TYPES.contains(data_type) => Ok(to_strings(string_values(array)?)),
+        Boolean => Ok(as_boolean_array(array)?
+            .iter()
+            .map(|value| value.map(|value| if value { "t" } else { "f" }.to_string()))
+            .collect()),
+        // Written like Postgres does, without a trailing `.0` for whole numbers.
+        Float64 => Ok(array
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|value| value.map(|value| value.to_string()))
+            .collect()),
+        Float32 => Ok(array
+            .as_primitive::<Float32Type>()
+            .iter()
+            .map(|value| value.map(|value| value.to_string()))
+            .collect()),
+        _ => Ok(to_strings(string_values(&cast(array, &Utf8)?)?)),
+    }
+}
+
+pub(super) fn execution_error(err: anyhow::Error) -> DataFusionError {
+    DataFusionError::Execution(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{LargeStringArray, StringViewArray};
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_strings_data_test;
+    use crate::postgres::register_postgres_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_format() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, format('%s|%I|%L|%5s|%-5s|', name, name, name, index, flag) as col_result FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------------------------------------------+
+| index | col_result                                     |
++-------+------------------------------------------------+
+| 1     | hi THOMAS|"hi THOMAS"|'hi THOMAS'|    1|t    | |
+| 2     | o'neil|"o'neil"|'o''neil'|    2|f    |         |
+| 3     | select|"select"|'select'|    3|t    |          |
+| 4     | a.b|"a.b"|'a.b'|    4|     |                   |
++-------+------------------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(r#"select format('%2$s %s %1$L %3$L', 1.5, 'a', NULL) as col_result, format(NULL, 1) as col_null"#)
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------------+----------+
+| col_result    | col_null |
++---------------+----------+
+| a  '1.5' NULL |          |
++---------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select format('%s %s', 'a')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("too few arguments for format()"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_quote() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, quote_ident(name) as ident, quote_literal(name) as literal, quote_nullable(flag) as nullable FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-------------+-------------+----------+
+| index | ident       | literal     | nullable |
++-------+-------------+-------------+----------+
+| 1     | "hi THOMAS" | 'hi THOMAS' | 't'      |
+| 2     | "o'neil"    | 'o''neil'   | 'f'      |
+| 3     | "select"    | 'select'    | 't'      |
+| 4     | "a.b"       | 'a.b'       | NULL     |
++-------+-------------+-------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(r#"select quote_ident('foo_1') as ident, quote_literal(E'a\\b') as literal, quote_literal(NULL) as literal_null"#)
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+---------+--------------+
+| ident | literal | literal_null |
++-------+---------+--------------+
+| foo_1 | E'a\\b' |              |
++-------+---------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_split_part() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select split_part('abc,def,ghi', ',', 2) as second, split_part('abc,def,ghi', ',', -1) as last, split_part('abc,def,ghi', ',', 4) as missing, split_part(NULL, ',', 1) as col_null"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+------+---------+----------+
+| second | last | missing | col_null |
++--------+------+---------+----------+
+| def    | ghi  |         |          |
++--------+------+---------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select split_part('a,b', ',', 0)")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("field position must not be zero"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_string_to_array() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select string_to_array('xx~~yy~~zz', '~~', 'yy') as with_null, string_to_array('abc', NULL) as chars, string_to_array('', ',') as empty, string_to_array(NULL, ',') as col_null"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------+-----------+-------+----------+
+| with_null  | chars     | empty | col_null |
++------------+-----------+-------+----------+
+| [xx, , zz] | [a, b, c] | []    |          |
++------------+-----------+-------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(r#"select array_element(string_to_array('xx~~yy~~zz', '~~', 'yy'), 2) is null as is_null"#)
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------+
+| is_null |
++---------+
+| true    |
++---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_regexp_split_to_array() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select regexp_split_to_array('hello world', '\s+') as words, regexp_split_to_array('a1B22b', 'b', 'i') as flags, regexp_split_to_array('abc', '') as chars"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------+-------------+-----------+
+| words          | flags       | chars     |
++----------------+-------------+-----------+
+| [hello, world] | [a1, 22, ]  | [a, b, c] |
++----------------+-------------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select regexp_split_to_array('abc', 'b', 'g')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("regexp_split_to_array() does not support the \"global\" option"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_overlay() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select overlay('Txxxxas', 'hom', 2, 4) as col_for, overlay('abc', 'XY', 3) as col_no_for, overlay('abc', 'X', 2, -1) as col_negative"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------+------------+--------------+
+| col_for | col_no_for | col_negative |
++---------+------------+--------------+
+| Thomas  | abXY       | aXabc        |
++---------+------------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_initcap_to_ascii_unistr() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, initcap(name) as col_initcap, to_ascii(accented) as col_ascii, unistr(escaped) as col_unistr FROM strings_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-------------+-----------+------------+
+| index | col_initcap | col_ascii | col_unistr |
++-------+-------------+-----------+------------+
+| 1     | Hi Thomas   | Karel     | data       |
+| 2     | O'Neil      | Francois  | é          |
+| 3     | Select      | Zurich    | \          |
+| 4     | A.B         |           |            |
++-------+-------------+-----------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx.sql(r#"select unistr('\D83D')"#).await?.collect().await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid Unicode surrogate pair"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_parse_ident() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select parse_ident('"SomeSchema".someTable') as col_quoted, parse_ident('a.b junk', false) as col_not_strict"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------------------+----------------+
+| col_quoted              | col_not_strict |
++-------------------------+----------------+
+| [SomeSchema, sometable] | [a, b]         |
++-------------------------+----------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx.sql("select parse_ident('a.')").await?.collect().await;
+        assert!(result.unwrap_err().to_string().contains(
+            "string is not a valid identifier: \"a.\": No valid identifier after \".\"."
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_strings_and_views() -> Result<()> {
+        let strings: [ArrayRef; 2] = [
+            Arc::new(LargeStringArray::from(vec![Some("hi THOMAS"), None])),
+            Arc::new(StringViewArray::from(vec![Some("hi THOMAS"), None])),
+        ];
+        for strings in strings {
+            let result = Initcap::new().invoke(&[ColumnarValue::Array(strings.clone())])?;
+            let ColumnarValue::Array(result) = result else {
+                panic!("expected an array")
+            };
+
+            assert_eq!(result.data_type(), strings.data_type());
+            assert_eq!(string_values(&result)?, vec![Some("Hi Thomas"), None]);
+        }
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = set_up_strings_data_test()?;
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,959 @@
+//! Postgres' string functions that DataFusion does not have, or whose results differ from
+//! Postgres', such as format(), quote_ident(), parse_ident() and unistr().
+//!
+//! Strings are counted in characters, as in a UTF8 database, and errors have the messages of
+//! Postgres.
+
+use anyhow::{anyhow, bail, Result};
+
+/// The keywords that quote_ident() quotes: every keyword of Postgres but the unreserved ones.
+const QUOTED_KEYWORDS: [&str; 159] = [
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "authorization",
+    "between",
+    "bigint",
+    "binary",
+    "bit",
+    "boolean",
+    "both",
+    "case",
+    "cast",
+    "char",
+    "character",
+    "check",
+    "coalesce",
+    "collate",
+    "collation",
+    "column",
+    "concurrently",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "dec",
+    "decimal",
+    "default",
+    "deferrable",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "exists",
+    "extract",
+    "false",
+    "fetch",
+    "float",
+    "for",
+    "foreign",
+    "freeze",
+    "from",
+    "full",
+    "grant",
+    "greatest",
+    "group",
+    "grouping",
+    "having",
+    "ilike",
+    "in",
+    "initially",
+    "inner",
+    "inout",
+    "int",
+    "integer",
+    "intersect",
+    "interval",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "json",
+    "json_array",
+    "json_arrayagg",
+    "json_object",
+    "json_objectagg",
+    "json_scalar",
+    "json_serialize",
+    "lateral",
+    "leading",
+    "least",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "national",
+    "natural",
+    "nchar",
+    "none",
+    "normalize",
+    "not",
+    "notnull",
+    "null",
+    "nullif",
+    "numeric",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "out",
+    "outer",
+    "overlaps",
+    "overlay",
+    "placing",
+    "position",
+    "precision",
+    "primary",
+    "real",
+    "references",
+    "returning",
+    "right",
+    "row",
+    "select",
+    "session_user",
+    "setof",
+    "similar",
+    "smallint",
+    "some",
+    "substring",
+    "symmetric",
+    "system_user",
+    "table",
+    "tablesample",
+    "then",
+    "time",
+    "timestamp",
+    "to",
+    "trailing",
+    "treat",
+    "trim",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "values",
+    "varchar",
+    "variadic",
+    "verbose",
+    "when",
+    "where",
+    "window",
+    "with",
+    "xmlattributes",
+    "xmlconcat",
+    "xmlelement",
+    "xmlexists",
+    "xmlforest",
+    "xmlnamespaces",
+    "xmlparse",
+    "xmlpi",
+    "xmlroot",
+    "xmlserialize",
+    "xmltable",
+];
+
+/// Formats `args` according to `format`, like Postgres' format(). `%s` writes its argument as
+/// text, `%I` as an identifier and `%L` as a literal. A conversion can pick its argument with
+/// `n$`, be left-aligned with `-` and have a width, either given or read from an argument with `*`.
+pub(crate) fn format(format: &str, args: &[Option<String>]) -> Result<String> {
+    let mut out = String::with_capacity(format.len());
+    let mut chars = format.chars().peekable();
+    // The position of the next argument, counted from 1 as in Postgres.
+    let mut arg = 1;
+
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&'%') {
+            chars.next();
+            out.push('%');
+            continue;
+        }
+
+        let spec = FormatSpec::parse(&mut chars)?;
+        let conversion = chars.next().ok_or_else(unterminated_format)?;
+        if !matches!(conversion, 's' | 'I' | 'L') {
+            bail!("unrecognized format() type specifier \"{conversion}\"");
+        }
+
+        let mut width = spec.width;
+        if let Some(width_position) = spec.width_position {
+            if width_position > 0 {
+                arg = width_position;
+            }
+            width = match format_argument(args, arg)? {
+                Some(value) => parse_integer(value)?,
+                None => 0,
+            };
+            arg += 1;
+        }
+        if let Some(position) = spec.position {
+            arg = position;
+        }
+        let value = format_argument(args, arg)?;
+        arg += 1;
+
+        let value = match (conversion, value.as_deref()) {
+            ('s', value) => value.unwrap_or_default().to_string(),
+            ('I', Some(value)) => quote_ident(value),
+            ('I', None) => bail!("null values cannot be formatted as an SQL identifier"),
+            (_, Some(value)) => quote_literal(value),
+            (_, None) => "NULL".to_string(),
+        };
+        pad(&mut out, &value, width, spec.left_align);
+    }
+
+    Ok(out)
+}
+
+/// What comes between `%` and the conversion character of a format() conversion.
+struct FormatSpec {
+    position: Option<usize>,
+    left_align: bool,
+    width: i64,
+    /// `Some(0)` for `*`, which reads the width from the next argument, and `Some(n)` for `*n$`.
+    width_position: Option<usize>,
+}
+
+impl FormatSpec {
+    fn parse(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Self> {
+        let mut spec = FormatSpec {
+            position: None,
+            left_align: false,
+            width: 0,
+            width_position: None,
+        };
+
+        if let Some(n) = parse_digits(chars)? {
+            if chars.peek() != Some(&'$') {
+                // Just a width, followed by the conversion.
+                spec.width = n;
+                return Ok(spec);
+            }
+            spec.position = Some(argument_position(n)?);
+            chars.next();
+        }
+        while chars.peek() == Some(&'-') {
+            spec.left_align = true;
+            chars.next();
+        }
+        if chars.peek() == Some(&'*') {
+            chars.next();
+            match parse_digits(chars)? {
+                Some(n) => {
+                    if chars.peek() != Some(&'$') {
+                        bail!("width argument position must be ended by \"$\"");
+                    }
+                    spec.width_position = Some(argument_position(n)?);
+                    chars.next();
+                }
+                None => spec.width_position = Some(0),
+            }
+        } else if let Some(n) = parse_digits(chars)? {
+            spec.width = n;
+        }
+        Ok(spec)
+    }
+}
+
+/// Reads the digits at the start of `chars`, if any, as a number that fits in an int.
+fn parse_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<Option<i64>> {
+    let mut number: Option<i64> = None;
+    while let Some(digit) = chars.peek().and_then(|c| c.to_digit(10)) {
+        chars.next();
+        let value = number.unwrap_or(0) * 10 + i64::from(digit);
+        if value > i64::from(i32::MAX) {
+            bail!("number is out of range");
+        }
+        number = Some(value);
+    }
+    if number.is_some() && chars.peek().is_none() {
+        return Err(unterminated_format());
+    }
+    Ok(number)
+}
+
+fn argument_position(n: i64) -> Result<usize> {
+    if n == 0 {
+        bail!("format specifies argument 0, but arguments are numbered from 1");
+    }
+    Ok(n as usize)
+}
+
+fn format_argument(args: &[Option<String>], position: usize) -> Result<&Option<String>> {
+    args.get(position - 1)
+        .ok_or_else(|| anyhow!("too few arguments for format()"))
+}
+
+fn unterminated_format() -> anyhow::Error {
+    anyhow!("unterminated format() type specifier")
+}
+
+fn parse_integer(value: &str) -> Result<i64> {
+    let value = value.trim();
+    match value.parse::<i64>() {
+        Ok(n) if i32::try_from(n).is_ok() => Ok(n),
+        Ok(_) => bail!("value \"{value}\" is out of range for type integer"),
+        Err(_) => bail!("invalid input syntax for type integer: \"{value}\""),
+    }
+}
+
+/// Writes `value` padded with spaces to `width` characters, on the left unless `left_align` is
+/// set or the width is negative.
+fn pad(out: &mut String, value: &str, width: i64, left_align: bool) {
+    let padding = (width.unsigned_abs() as usize).saturating_sub(value.chars().count());
+    if left_align || width < 0 {
+        out.push_str(value);
+        out.push_str(&" ".repeat(padding));
+    } else {
+        out.push_str(&" ".repeat(padding));
+        out.push_str(value);
+    }
+}
+
+/// Returns `ident` quoted as an SQL identifier, unless it would not need quotes: a lower case
+/// name that is not a reserved keyword.
+pub(crate) fn quote_ident(ident: &str) -> String {
+    let is_safe = ident
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+        && ident
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        && !QUOTED_KEYWORDS.contains(&ident);
+    if is_safe {
+        ident.to_string()
+    } else {
+        format!("\"{}\"", ident.replace('"', "\"\""))
+    }
+}
+
+/// Returns `value` quoted as an SQL string literal. A string holding backslashes is written as
+/// an escape string, `E'...'`, with its backslashes doubled.
+pub(crate) fn quote_literal(value: &str) -> String {
+    if value.contains('\\') {
+        format!("E'{}'", value.replace('\\', "\\\\").replace('\'', "''"))
+    } else {
+        format!("'{}'", value.replace('\'', "''"))
+    }
+}
+
+/// Returns the `n`th field of `string` split at `delimiter`, counting from the end if `n` is
+/// negative, or an empty string if there is no such field.
+pub(crate) fn split_part<'a>(string: &'a str, delimiter: &str, n: i64) -> Result<&'a str> {
+    if n == 0 {
+        bail!("field position must not be zero");
+    }
+    if string.is_empty() {
+        return Ok("");
+    }
+    if delimiter.is_empty() {
+        return Ok(if n == 1 || n == -1 { string } else { "" });
+    }
+
+    let fields = string.split(delimiter).collect::<Vec<_>>();
+    let index = if n > 0 {
+        usize::try_from(n - 1).ok()
+    } else {
+        usize::try_from(fields.len() as i64 + n).ok()
+    };
+    Ok(index
+        .and_then(|index| fields.get(index))
+        .copied()
+        .unwrap_or(""))
+}
+
+/// Splits `string` at `delimiter`, or into characters if `delimiter` is NULL, with the fields
+/// equal to `null_string` read as NULL. An empty string gives an empty array, and an empty
+/// delimiter an array of the whole string.
+pub(crate) fn string_to_array<'a>(
+    string: &'a str,
+    delimiter: Option<&str>,
+    null_string: Option<&str>,
+) -> Vec<Option<&'a str>> {
+    if string.is_empty() {
+        return vec![];
+    }
+    let fields: Vec<&str> = match delimiter {
+        None => string
+            .char_indices()
+            .map(|(start, c)| &string[start..start + c.len_utf8()])
+            .collect(),
+        Some("") => vec![string],
+        Some(delimiter) => string.split(delimiter).collect(),
+    };
+    fields
+        .into_iter()
+        .map(|field| (Some(field) != null_string).then_some(field))
+        .collect()
+}
+
+/// Replaces `count` characters of `string` from the 1-based position `start` with
+/// `replacement`. `count` is the length of `replacement` if not given.
+pub(crate) fn overlay(
+    string: &str,
+    replacement: &str,
+    start: i64,
+    count: Option<i64>,
+) -> Result<String> {
+    let count = count.unwrap_or(replacement.chars().count() as i64);
+    if start < 1 {
+        bail!("negative substring length not allowed");
+    }
+    let end = start
+        .checked_add(count)
+        .filter(|end| i32::try_from(*end).is_ok())
+        .ok_or_else(|| anyhow!("integer out of range"))?;
+
+    let prefix = string.chars().take(start as usize - 1);
+    let suffix = string.chars().skip(end.max(1) as usize - 1);
+    Ok(prefix.chain(replacement.chars()).chain(suffix).collect())
+}
+
+/// Upper cases the first letter of each word of `string` and lower cases the others, where words
+/// are sequences of letters and digits.
+pub(crate) fn initcap(string: &str) -> String {
+    let mut in_word = false;
+    string
+        .chars()
+        .map(|c| {
+            let converted = if in_word {
+                single_char(c.to_lowercase())
+            } else {
+                single_char(c.to_uppercase())
+            };
+            in_word = c.is_alphanumeric();
+            converted.unwrap_or(c)
+        })
+        .collect()
+}
+
+/// Returns the character of a case conversion, unless it gives several, like `ß` upper cased,
+/// which Postgres leaves as they are.
+fn single_char(mut chars: impl Iterator<Item = char>) -> Option<char> {
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// The encodings to_ascii() converts from, with their characters from 0x80 to 0xFF and the ASCII
+/// characters Postgres replaces them with.
+const ASCII_TABLES: [(&str, &str, &str); 4] = [
+    (
+        "LATIN1",
+        "\u{80}\u{81}\u{82}\u{83}\u{84}\u{85}\u{86}\u{87}\u{88}\u{89}\u{8a}\u{8b}\u{8c}\u{8d}\u{8e}\u{8f}\u{90}\u{91}\u{92}\u{93}\u{94}\u{95}\u{96}\u{97}\u{98}\u{99}\u{9a}\u{9b}\u{9c}\u{9d}\u{9e}\u{9f}\u{a0}¡¢£¤¥¦§¨©ª«¬\u{ad}®¯°±²³´µ¶·¸¹º»¼½¾¿ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ",
+        "                                  cL Y  \"Ca  -R     'u .,      ?AAAAAAACEEEEIIII NOOOOOxOUUUUYTBaaaaaaaceeeeiiii nooooo/ouuuuyty",
+    ),
+    (
+        "LATIN2",
+        "\u{80}\u{81}\u{82}\u{83}\u{84}\u{85}\u{86}\u{87}\u{88}\u{89}\u{8a}\u{8b}\u{8c}\u{8d}\u{8e}\u{8f}\u{90}\u{91}\u{92}\u{93}\u{94}\u{95}\u{96}\u{97}\u{98}\u{99}\u{9a}\u{9b}\u{9c}\u{9d}\u{9e}\u{9f}\u{a0}Ą˘Ł¤ĽŚ§¨ŠŞŤŹ\u{ad}ŽŻ°ą˛ł´ľśˇ¸šşťź˝žżŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢßŕáâăäĺćçčéęëěíîďđńňóôőö÷řůúűüýţ˙",
+        "                                 A L LS \"SSTZ-ZZ a,l'ls ,sstz\"zzRAAAALCCCEEEEIIDDNNOOOOxRUUUUYTBraaaalccceeeeiiddnnoooo/ruuuuyt.",
+    ),
+    (
+        "LATIN9",
+        "\u{80}\u{81}\u{82}\u{83}\u{84}\u{85}\u{86}\u{87}\u{88}\u{89}\u{8a}\u{8b}\u{8c}\u{8d}\u{8e}\u{8f}\u{90}\u{91}\u{92}\u{93}\u{94}\u{95}\u{96}\u{97}\u{98}\u{99}\u{9a}\u{9b}\u{9c}\u{9d}\u{9e}\u{9f}\u{a0}¡¢£€¥Š§š©ª«¬\u{ad}®¯°±²³Žµ¶·ž¹º»ŒœŸ¿ÀÁÂÃÄÅÆÇÈÉÊËÌÍÎÏÐÑÒÓÔÕÖ×ØÙÚÛÜÝÞßàáâãäåæçèéêëìíîïðñòóôõö÷øùúûüýþÿ",
+        "                                  cL YS sCa  -R     Zu .z   EeY?AAAAAAACEEEEIIII NOOOOOxOUUUUYTBaaaaaaaceeeeiiii nooooo/ouuuuyty",
+    ),
+    (
+        "WIN1250",
+        "€\u{81}‚\u{83}„…†‡\u{88}‰Š‹ŚŤŽŹ\u{90}‘’“”•–—\u{98}™š›śťžź\u{a0}ˇ˘Ł¤Ą¦§¨©Ş«¬\u{ad}®Ż°±˛ł´µ¶·¸ąş»Ľ˝ľżŔÁÂĂÄĹĆÇČÉĘËĚÍÎĎĐŃŇÓÔŐÖ×ŘŮÚŰÜÝŢßŕáâăäĺćçčéęëěíîďđńňóôőö÷řůúűüýţ˙",
+        "  ' \"    %S<STZZ `'\"\".--  s>stzz   L A  \"CS  -RZ  ,l'u .,as L\"lzRAAAALCCCEEEEIIDDNNOOOOxRUUUUYTBraaaalccceeeeiiddnnoooo/ruuuuyt ",
+    ),
+];
+
+/// The other encodings of Postgres, which to_ascii() does not convert from.
+const OTHER_ENCODINGS: [&str; 38] = [
+    "SQL_ASCII",
+    "EUC_JP",
+    "EUC_CN",
+    "EUC_KR",
+    "EUC_TW",
+    "EUC_JIS_2004",
+    "UTF8",
+    "MULE_INTERNAL",
+    "LATIN3",
+    "LATIN4",
+    "LATIN5",
+    "LATIN6",
+    "LATIN7",
+    "LATIN8",
+    "LATIN10",
+    "WIN1256",
+    "WIN1258",
+    "WIN866",
+    "WIN874",
+    "KOI8R",
+    "WIN1251",
+    "WIN1252",
+    "ISO_8859_5",
+    "ISO_8859_6",
+    "ISO_8859_7",
+    "ISO_8859_8",
+    "WIN1253",
+    "WIN1254",
+    "WIN1255",
+    "WIN1257",
+    "KOI8U",
+    "SJIS",
+    "BIG5",
+    "GBK",
+    "UHC",
+    "GB18030",
+    "JOHAB",
+    "SHIFT_JIS_2004",
+];
+
+/// Replaces the accented letters and symbols of `string` with ASCII characters, the way
+/// Postgres' to_ascii() does for text in `encoding`, one of LATIN1, LATIN2, LATIN9 and WIN1250.
+/// Fails for characters `encoding` does not have.
+pub(crate) fn to_ascii(string: &str, encoding: &str) -> Result<String> {
+    // Like Postgres, encoding names are matched ignoring case and punctuation, e.g. "latin-1".
+    let normalized = |name: &str| {
+        name.chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect::<String>()
+    };
+    let name = match normalized(encoding).as_str() {
+        "iso88591" => "LATIN1",
+        "iso88592" => "LATIN2",
+        "iso885915" => "LATIN9",
+        "windows1250" => "WIN1250",
+        name => ASCII_TABLES
+            .iter()
+            .map(|(table_name, _, _)| *table_name)
+            .chain(OTHER_ENCODINGS)
+            .find(|known| normalized(known) == name)
+            .ok_or_else(|| anyhow!("{encoding} is not a valid encoding name"))?,
+    };
+    let Some((_, characters, ascii)) = ASCII_TABLES.iter().find(|(table, _, _)| *table == name)
+    else {
+        bail!("encoding conversion from {name} to ASCII not supported");
+    };
+
+    string
+        .chars()
+        .map(|c| {
+            if c.is_ascii() {
+                return Ok(c);
+            }
+            characters
+                .chars()
+                .position(|character| character == c)
+                .map(|index| char::from(ascii.as_bytes()[index]))
+                .ok_or_else(|| {
+                    let bytes = c
+                        .to_string()
+                        .bytes()
+                        .map(|byte| format!("0x{byte:02x}"))
+                        .collect::<Vec<_>>();
+                    anyhow!(
+                        "character with byte sequence {} in encoding \"UTF8\" has no equivalent in encoding \"{name}\"",
+                        bytes.join(" ")
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Splits the qualified identifier `string`, e.g. `public."My Table"`, into its names, lower
+/// casing the ones that are not quoted. Unless `strict`, anything after the identifier is
+/// ignored.
+pub(crate) fn parse_ident(string: &str, strict: bool) -> Result<Vec<String>> {
+    let not_valid = |detail: Option<&str>| match detail {
+        Some(detail) => anyhow!("string is not a valid identifier: \"{string}\": {detail}"),
+        None => anyhow!("string is not a valid identifier: \"{string}\""),
+    };
+    let is_space = |c: char| matches!(c, ' ' | '\t' | '\n' | '\r' | '\u{c}' | '\u{b}');
+    let is_ident_start = |c: char| c.is_ascii_alphabetic() || c == '_' || !c.is_ascii();
+    let is_ident_cont = |c: char| is_ident_start(c) || c.is_ascii_digit() || c == '$';
+
+    let mut names = vec![];
+    let mut chars = string.chars().peekable();
+    let mut after_dot = false;
+    while chars.next_if(|c| is_space(*c)).is_some() {}
+
+    loop {
+        if chars.next_if_eq(&'"').is_some() {
+            let mut name = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') if chars.next_if_eq(&'"').is_some() => name.push('"'),
+                    Some('"') => break,
+                    Some(c) => name.push(c),
+                    None => return Err(not_valid(Some("String has unclosed double quotes."))),
+                }
+            }
+            if name.is_empty() {
+                return Err(not_valid(Some("Quoted identifier must not be empty.")));
+            }
+            names.push(name);
+        } else if let Some(first) = chars.next_if(|c| is_ident_start(*c)) {
+            let mut name = String::from(first);
+            while let Some(c) = chars.next_if(|c| is_ident_cont(*c)) {
+                name.push(c);
+            }
+            names.push(name.to_ascii_lowercase());
+        } else if chars.peek() == Some(&'.') {
+            return Err(not_valid(Some("No valid identifier before \".\".")));
+        } else if after_dot {
+            return Err(not_valid(Some("No valid identifier after \".\".")));
+        } else {
+            return Err(not_valid(None));
+        }
+
+        while chars.next_if(|c| is_space(*c)).is_some() {}
+        match chars.next() {
+            Some('.') => {
+                after_dot = true;
+                while chars.next_if(|c| is_space(*c)).is_some() {}
+            }
+            None => break,
+            Some(_) if strict => return Err(not_valid(None)),
+            Some(_) => break,
+        }
+    }
+    Ok(names)
+}
+
+/// Decodes the Unicode escapes of `string`: `\XXXX`, `\+XXXXXX`, `\uXXXX` and `\UXXXXXXXX`, with
+/// UTF-16 surrogate pairs, and `\\` for a backslash.
+pub(crate) fn unistr(string: &str) -> Result<String> {
+    let mut out = String::with_capacity(string.len());
+    let mut rest = string;
+    // The first half of a surrogate pair, waiting for the second.
+    let mut pair_first: Option<u32> = None;
+
+    while let Some(c) = rest.chars().next() {
+        if c != '\\' {
+            if pair_first.is_some() {
+                bail!("invalid Unicode surrogate pair");
+            }
+            out.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+        if rest.starts_with("\\\\") {
+            if pair_first.is_some() {
+                bail!("invalid Unicode surrogate pair");
+            }
+            out.push('\\');
+            rest = &rest[2..];
+            continue;
+        }
+
+        let (code_point, length) = if let Some(code_point) = hex_digits(&rest[1..], 4) {
+            (code_point, 5)
+        } else if let Some(code_point) = rest.strip_prefix("\\u").and_then(|hex| hex_digits(hex, 4))
+        {
+            (code_point, 6)
+        } else if let Some(code_point) = rest.strip_prefix("\\+").and_then(|hex| hex_digits(hex, 6))
+        {
+            (code_point, 8)
+        } else if let Some(code_point) = rest.strip_prefix("\\U").and_then(|hex| hex_digits(hex, 8))
+        {
+            (code_point, 10)
+        } else {
+            bail!("invalid Unicode escape: Unicode escapes must be \\XXXX, \\+XXXXXX, \\uXXXX, or \\UXXXXXXXX.");
+        };
+        rest = &rest[length..];
+
+        if code_point > 0x10FFFF {
+            bail!("invalid Unicode code point: {code_point:04X}");
+        }
+        let is_second_half = (0xDC00..=0xDFFF).contains(&code_point);
+        let code_point = match pair_first.take() {
+            Some(first) if is_second_half => {
+                0x10000 + ((first - 0xD800) << 10) + code_point - 0xDC00
+            }
+            Some(_) => bail!("invalid Unicode surrogate pair"),
+            None if is_second_half => bail!("invalid Unicode surrogate pair"),
+            None if (0xD800..=0xDBFF).contains(&code_point) => {
+                pair_first = Some(code_point);
+                continue;
+            }
+            None => code_point,
+        };
+        out.push(
+            char::from_u32(code_point)
+                .ok_or_else(|| anyhow!("invalid Unicode code point: {code_point:04X}"))?,
+        );
+    }
+
+    if pair_first.is_some() {
+        bail!("invalid Unicode surrogate pair");
+    }
+    Ok(out)
+}
+
+/// Reads the `count` hex digits at the start of `string`.
+fn hex_digits(string: &str, count: usize) -> Option<u32> {
+    let digits = string.get(..count)?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    u32::from_str_radix(digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(values: &[Option<&str>]) -> Vec<Option<String>> {
+        values.iter().map(|value| value.map(String::from)).collect()
+    }
+
+    fn format_error(format_string: &str, values: &[Option<&str>]) -> String {
+        format(format_string, &args(values))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_format() {
+        let format = |format_string: &str, values: &[Option<&str>]| {
+            format(format_string, &args(values)).unwrap()
+        };
+        assert_eq!(
+            format(
+                "%s, %I, %L, %L, %%",
+                &[Some("a"), Some("Foo bar"), Some("it's\\x"), None]
+            ),
+            "a, \"Foo bar\", E'it''s\\\\x', NULL, %"
+        );
+        assert_eq!(
+            format("%3$s %s", &[Some("1"), Some("2"), Some("3"), Some("4")]),
+            "3 4"
+        );
+        assert_eq!(format("%1$s %1$s %s", &[Some("a"), Some("b")]), "a a b");
+        assert_eq!(
+            format(
+                "|%*s|%-*s|%*s|",
+                &[
+                    Some("4"),
+                    Some("x"),
+                    Some("-3"),
+                    Some("y"),
+                    Some("-2"),
+                    Some("z")
+                ]
+            ),
+            "|   x|y  |z |"
+        );
+        assert_eq!(
+            format("|%2$*1$s|%s|", &[Some("3"), Some("a"), Some("b")]),
+            "|  a|b|"
+        );
+        assert_eq!(format("|%3s|%-4L|", &[None, None]), "|   |NULL|");
+        assert_eq!(format("|%*s|", &[None, Some("a")]), "|a|");
+        assert_eq!(
+            format("|%--3s|%5I|", &[Some("a"), Some("B")]),
+            "|a  |  \"B\"|"
+        );
+        assert_eq!(format("é%s", &[Some("ü")]), "éü");
+    }
+
+    #[test]
+    fn test_format_errors() {
+        assert_eq!(
+            format_error("%s %s", &[Some("a")]),
+            "too few arguments for format()"
+        );
+        assert_eq!(
+            format_error("%z", &[Some("a")]),
+            "unrecognized format() type specifier \"z\""
+        );
+        assert_eq!(
+            format_error("abc %", &[]),
+            "unterminated format() type specifier"
+        );
+        assert_eq!(
+            format_error("%1", &[Some("a")]),
+            "unterminated format() type specifier"
+        );
+        assert_eq!(
+            format_error("%0$s", &[Some("a")]),
+            "format specifies argument 0, but arguments are numbered from 1"
+        );
+        assert_eq!(
+            format_error("%*3s", &[Some("1"), Some("2"), Some("3")]),
+            "width argument position must be ended by \"$\""
+        );
+        assert_eq!(
+            format_error("%*s", &[Some("x"), Some("a")]),
+            "invalid input syntax for type integer: \"x\""
+        );
+        assert_eq!(
+            format_error("%99999999999s", &[Some("a")]),
+            "number is out of range"
+        );
+        assert_eq!(
+            format_error("%I", &[None]),
+            "null values cannot be formatted as an SQL identifier"
+        );
+    }
+
+    #[test]
+    fn test_quote() {
+        assert_eq!(quote_ident("foo_1"), "foo_1");
+        assert_eq!(quote_ident("Foo bar"), "\"Foo bar\"");
+        assert_eq!(quote_ident("select"), "\"select\"");
+        assert_eq!(quote_ident("a$b"), "\"a$b\"");
+        assert_eq!(quote_ident("a\"b"), "\"a\"\"b\"");
+        assert_eq!(quote_ident(""), "\"\"");
+        assert_eq!(quote_ident("1a"), "\"1a\"");
+        assert_eq!(quote_literal("O'Reilly"), "'O''Reilly'");
+        assert_eq!(quote_literal("a\\b"), "E'a\\\\b'");
+    }
+
+    #[test]
+    fn test_split_part() {
+        assert_eq!(split_part("a,b,c", ",", 2).unwrap(), "b");
+        assert_eq!(split_part("a,b,c", ",", -1).unwrap(), "c");
+        assert_eq!(split_part("a,b,c", ",", -3).unwrap(), "a");
+        assert_eq!(split_part("a,b,c", ",", 4).unwrap(), "");
+        assert_eq!(split_part("a,b,c", ",", -4).unwrap(), "");
+        assert_eq!(split_part("a,b,c", "", 1).unwrap(), "a,b,c");
+        assert_eq!(split_part("a,b,c", "", 2).unwrap(), "");
+        assert_eq!(split_part("", ",", 1).unwrap(), "");
+        assert_eq!(
+            split_part("a,b", ",", 0).unwrap_err().to_string(),
+            "field position must not be zero"
+        );
+    }
+
+    #[test]
+    fn test_string_to_array() {
+        assert_eq!(
+            string_to_array("a,*,c", Some(","), Some("*")),
+            vec![Some("a"), None, Some("c")]
+        );
+        assert_eq!(
+            string_to_array("aé", None, None),
+            vec![Some("a"), Some("é")]
+        );
+        assert_eq!(string_to_array("abc", Some(""), None), vec![Some("abc")]);
+        assert_eq!(string_to_array("", Some(","), None), vec![]);
+        assert_eq!(
+            string_to_array("a,b,", Some(","), None),
+            vec![Some("a"), Some("b"), Some("")]
+        );
+    }
+
+    #[test]
+    fn test_overlay() {
+        assert_eq!(overlay("Txxxxas", "hom", 2, Some(4)).unwrap(), "Thomas");
+        assert_eq!(overlay("abc", "X", 2, Some(-1)).unwrap(), "aXabc");
+        assert_eq!(overlay("abc", "X", 1, Some(0)).unwrap(), "Xabc");
+        assert_eq!(overlay("abc", "XY", 3, None).unwrap(), "abXY");
+        assert_eq!(overlay("abc", "X", 9, None).unwrap(), "abcX");
+        assert_eq!(overlay("çaé", "X", 2, None).unwrap(), "çXé");
+        assert_eq!(
+            overlay("abc", "X", 0, None).unwrap_err().to_string(),
+            "negative substring length not allowed"
+        );
+    }
+
+    #[test]
+    fn test_initcap() {
+        assert_eq!(
+            initcap("hi THOMAS o'neil 2nd éTÉ ßa"),
+            "Hi Thomas O'Neil 2nd Été ßa"
+        );
+        assert_eq!(initcap("ǆemal İstanbul"), "Ǆemal İstanbul");
+    }
+
+    #[test]
+    fn test_to_ascii() {
+        assert_eq!(to_ascii("Karél Ærø", "LATIN1").unwrap(), "Karel Aro");
+        assert_eq!(to_ascii("Łódź", "latin-2").unwrap(), "Lodz");
+        assert_eq!(to_ascii("Šž€", "LATIN9").unwrap(), "Sz ");
+        assert_eq!(to_ascii("Š", "WIN1250").unwrap(), "S");
+        assert_eq!(
+            to_ascii("a€", "LATIN1").unwrap_err().to_string(),
+            "character with byte sequence 0xe2 0x82 0xac in encoding \"UTF8\" has no equivalent in encoding \"LATIN1\""
+        );
+        assert_eq!(
+            to_ascii("a", "utf-8").unwrap_err().to_string(),
+            "encoding conversion from UTF8 to ASCII not supported"
+        );
+        assert_eq!(
+            to_ascii("a", "FOO").unwrap_err().to_string(),
+            "FOO is not a valid encoding name"
+        );
+    }
+
+    #[test]
+    fn test_parse_ident() {
+        assert_eq!(
+            parse_ident("SomeSchema.\"SomeTable\"", true).unwrap(),
+            vec!["someschema", "SomeTable"]
+        );
+        assert_eq!(
+            parse_ident(" a . \"b\"\"c\" ", true).unwrap(),
+            vec!["a", "b\"c"]
+        );
+        assert_eq!(parse_ident("a.b junk", false).unwrap(), vec!["a", "b"]);
+
+        let error = |string| parse_ident(string, true).unwrap_err().to_string();
+        assert_eq!(
+            error("a."),
+            "string is not a valid identifier: \"a.\": No valid identifier after \".\"."
+        );
+        assert_eq!(
+            error(".a"),
+            "string is not a valid identifier: \".a\": No valid identifier before \".\"."
+        );
+        assert_eq!(
+            error("\"a"),
+            "string is not a valid identifier: \"\"a\": String has unclosed double quotes."
+        );
+        assert_eq!(
+            error("\"\""),
+            "string is not a valid identifier: \"\"\"\": Quoted identifier must not be empty."
+        );
+        assert_eq!(error("a b"), "string is not a valid identifier: \"a b\"");
+        assert_eq!(error("1a"), "string is not a valid identifier: \"1a\"");
+    }
+
+    #[test]
+    fn test_unistr() {
+        assert_eq!(unistr(r"d\0061t\+000061").unwrap(), "data");
+        assert_eq!(unistr(r"é\U0001F600\\").unwrap(), "é😀\\");
+        assert_eq!(unistr(r"\D83D\uDE00").unwrap(), "😀");
+        assert_eq!(unistr(r"\110000").unwrap(), "ᄀ00");
+        assert_eq!(
+            unistr(r"a\D83Dx").unwrap_err().to_string(),
+            "invalid Unicode surrogate pair"
+        );
+        assert_eq!(
+            unistr(r"\+110000").unwrap_err().to_string(),
+            "invalid Unicode code point: 110000"
+        );
+        assert!(unistr(r"\xyz")
+            .unwrap_err()
+            .to_string()
+            .starts_with("invalid Unicode escape"));
+    }
+}
@@ -0,0 +1,2888 @@
+//! Postgres' full text search, following `tsvector.c`, `tsquery.c`, `to_tsany.c`,
+//! `tsvector_op.c`, `tsrank.c` and the headline of `wparser_def.c`: documents as `tsvector`s of
+//! normalized lexemes and their positions, queries as `tsquery`s of lexemes and operators, the
+//! `simple` and `english` configurations that make them from text, and matching, ranking and
+//! headlines.
+//!
+//! A `tsquery` is kept as Postgres keeps it, in Polish notation: an operator comes before its
+//! right operand, which is followed by its left one, at `left` items from the operator.
+
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, bail, Result};
+use rust_stemmers::{Algorithm, Stemmer};
+
+use crate::postgres::tsparser::{is_alpha, is_space, tokenize, TokenType};
+
+/// Words of this many bytes or more are not indexed.
+const MAX_STRING_LEN: usize = 2048;
+/// The largest position, and the largest distance of a phrase operator.
+const MAX_ENTRY_POS: u16 = 1 << 14;
+/// A lexeme keeps at most this many positions, less one.
+const MAX_NUM_POS: usize = 256;
+
+const DEFAULT_WEIGHTS: [f32; 4] = [0.1, 0.2, 0.4, 1.0];
+
+/// The stop words of the `english` configuration, from Postgres' `english.stop`.
+const ENGLISH_STOP_WORDS: &str = include_str!("tsearch_data/english.stop");
+
+/// A text search configuration, which turns the tokens of the default parser into lexemes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Config {
+    /// Lowercases every word.
+    Simple,
+    /// Stems words with the Snowball English stemmer, and drops English stop words.
+    English,
+}
+
+impl Config {
+    /// Returns the configuration of a name, as `regconfig` does. The names may be qualified
+    /// with `pg_catalog`.
+    pub(crate) fn from_name(name: &str) -> Result<Self> {
+        let unqualified = name.trim();
+        let unqualified = match unqualified.split_once('.') {
+            Some((schema, config)) if schema.trim().eq_ignore_ascii_case("pg_catalog") => {
+                config.trim()
+            }
+            _ => unqualified,
+        };
+        match unqualified.to_lowercase().as_str() {
+            "simple" => Ok(Self::Simple),
+            "english" => Ok(Self::English),
+            _ => bail!("text search configuration \"{name}\" does not exist"),
+        }
+    }
+
+    /// Returns the lexemes of a token: `None` when the configuration does not index its type,
+    /// and no lexeme when it is a stop word.
+    fn lexize(self, token_type: TokenType, token: &str) -> Option<Vec<String>> {
+        use TokenType::*;
+        let stem = match token_type {
+            AsciiWord | Word | AsciiHword | Hword | HwordAsciipart | HwordPart => true,
+            NumWord | NumHword | HwordNumpart | Email | Url | Host | UrlPath | File | Sfloat
+            | Float | Int | Uint | Version => false,
+            Tag | Entity | Blank | Protocol => return None,
+        };
+        let lowered = token.to_lowercase();
+        if lowered.is_empty() {
+            return Some(vec![]);
+        }
+        match self {
+            Self::English if stem => {
+                // As Postgres, the stemmer is only given words that could be in a language.
+                if token.len() > 1000 {
+                    Some(vec![lowered])
+                } else if is_english_stop_word(&lowered) {
+                    Some(vec![])
+                } else {
+                    Some(vec![english_stemmer().stem(&lowered).into_owned()])
+                }
+            }
+            _ => Some(vec![lowered]),
+        }
+    }
+
+    /// Returns the lexemes of a text with their positions, as `parsetext`.
+    fn parse_text(self, text: &str) -> Vec<(String, u16)> {
+        let mut words = vec![];
+        let mut position = 0_u32;
+        for (token_type, token) in tokenize(text) {
+            if token.len() >= MAX_STRING_LEN {
+                continue;
+            }
+            let Some(lexemes) = self.lexize(token_type, &token) else {
+                continue;
+            };
+            position += 1;
+            for lexeme in lexemes {
+                words.push((lexeme, limit_position(position)));
+            }
+        }
+        words
+    }
+}
+
+fn english_stemmer() -> &'static Stemmer {
+    static STEMMER: OnceLock<Stemmer> = OnceLock::new();
+    STEMMER.get_or_init(|| Stemmer::create(Algorithm::English))
+}
+
+fn is_english_stop_word(word: &str) -> bool {
+    ENGLISH_STOP_WORDS
+        .lines()
+        .any(|stop_word| stop_word == word)
+}
+
+fn limit_position(position: u32) -> u16 {
+    position.min(u32::from(MAX_ENTRY_POS) - 1) as u16
+}
+
+/// A position of a lexeme in a document, with its weight, from 3 for `A` down to 0 for `D`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Position {
+    pub(crate) pos: u16,
+    pub(crate) weight: u8,
+}
+
+/// A lexeme of a `tsvector`, with its positions, which may have been left out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Lexeme {
+    pub(crate) word: String,
+    pub(crate) positions: Vec<Position>,
+}
+
+/// A document, as its distinct lexemes in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TsVector {
+    pub(crate) lexemes: Vec<Lexeme>,
+}
+
+impl TsVector {
+    /// The number of lexemes, counting each position, as `cnt_length`.
+    fn length(&self) -> usize {
+        self.lexemes
+            .iter()
+            .map(|lexeme| lexeme.positions.len().max(1))
+            .sum()
+    }
+
+    /// Returns the lexemes that match an operand: the one equal to it, or the ones it is a prefix
+    /// of.
+    fn find(&self, operand: &Operand) -> &[Lexeme] {
+        &self.lexemes[self.find_indices(operand)]
+    }
+
+    fn find_indices(&self, operand: &Operand) -> Range<usize> {
+        let start = self
+            .lexemes
+            .partition_point(|lexeme| lexeme.word.as_str() < operand.word.as_str());
+        let rest = &self.lexemes[start..];
+        let count = if operand.prefix {
+            rest.iter()
+                .take_while(|lexeme| lexeme.word.starts_with(&operand.word))
+                .count()
+        } else {
+            usize::from(
+                rest.first()
+                    .is_some_and(|lexeme| lexeme.word == operand.word),
+            )
+        };
+        start..start + count
+    }
+}
+
+/// Makes a `tsvector` of lexemes and their positions, as `make_tsvector`.
+fn make_tsvector(mut words: Vec<(String, u16)>) -> TsVector {
+    words.sort();
+    let mut lexemes: Vec<Lexeme> = vec![];
+    for (word, pos) in words {
+        match lexemes.last_mut() {
+            Some(last) if last.word == word => {
+                let last_pos = last.positions.last().map(|position| position.pos);
+                if last.positions.len() < MAX_NUM_POS - 1
+                    && last_pos != Some(MAX_ENTRY_POS - 1)
+                    && last_pos != Some(pos)
+                {
+                    last.positions.push(Position { pos, weight: 0 });
+                }
+            }
+            _ => lexemes.push(Lexeme {
+                word,
+                positions: vec![Position { pos, weight: 0 }],
+            }),
+        }
+    }
+    TsVector { lexemes }
+}
+
+/// Returns the `tsvector` of a document, as `to_tsvector`.
+pub(crate) fn to_tsvector(config: Config, text: &str) -> TsVector {
+    make_tsvector(config.parse_text(text))
+}
+
+/// Parses the text of a `tsvector`, as `tsvectorin`: its lexemes, quoted when they have spaces
+/// or quotes, each followed by its positions, e.g. `'a':1A 'fat':2,4 'cat'`.
+pub(crate) fn parse_tsvector(text: &str) -> Result<TsVector> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let parser = ValueParser {
+        buffer: text,
+        is_query: false,
+        is_web: false,
+    };
+    let mut lexemes = vec![];
+    let mut start = 0;
+    while let Some((word, positions, end)) = parser.next_value(&chars, start)? {
+        if word.len() >= MAX_STRING_LEN {
+            bail!(
+                "word is too long ({} bytes, max {} bytes)",
+                word.len(),
+                MAX_STRING_LEN - 1
+            );
+        }
+        lexemes.push(Lexeme { word, positions });
+        start = end;
+    }
+
+    lexemes.sort_by(|first, second| first.word.cmp(&second.word));
+    let mut unique: Vec<Lexeme> = vec![];
+    for lexeme in lexemes {
+        match unique.last_mut() {
+            Some(last) if last.word == lexeme.word => last.positions.extend(lexeme.positions),
+            _ => unique.push(lexeme),
+        }
+    }
+    for lexeme in &mut unique {
+        lexeme.positions = unique_positions(std::mem::take(&mut lexeme.positions));
+    }
+    Ok(TsVector { lexemes: unique })
+}
+
+/// Sorts positions, keeping the highest weight of equal ones, as `uniquePos`.
+fn unique_positions(mut positions: Vec<Position>) -> Vec<Position> {
+    if positions.len() <= 1 {
+        return positions;
+    }
+    positions.sort_by_key(|position| position.pos);
+    let mut unique: Vec<Position> = vec![];
+    for position in positions {
+        match unique.last_mut() {
+            Some(last) if last.pos == position.pos => {
+                last.weight = last.weight.max(position.weight)
+            }
+            _ => {
+                unique.push(position);
+                if unique.len() > MAX_NUM_POS - 1 || position.pos == MAX_ENTRY_POS - 1 {
+                    break;
+                }
+            }
+        }
+    }
+    unique
+}
+
+/// Formats a `tsvector` as Postgres does, e.g. `'cat':3 'fat':2,4`.
+pub(crate) fn format_tsvector(vector: &TsVector) -> String {
+    let mut text = String::new();
+    for (index, lexeme) in vector.lexemes.iter().enumerate() {
+        if index > 0 {
+            text.push(' ');
+        }
+        write_quoted(&mut text, &lexeme.word);
+        for (index, position) in lexeme.positions.iter().enumerate() {
+            text.push(if index == 0 { ':' } else { ',' });
+            text.push_str(&position.pos.to_string());
+            match position.weight {
+                3 => text.push('A'),
+                2 => text.push('B'),
+                1 => text.push('C'),
+                _ => {}
+            }
+        }
+    }
+    text
+}
+
+/// Writes a lexeme in quotes, doubling its quotes and backslashes.
+fn write_quoted(text: &mut String, word: &str) {
+    text.push('\'');
+    for character in word.chars() {
+        if matches!(character, '\'' | '\\') {
+            text.push(character);
+        }
+        text.push(character);
+    }
+    text.push('\'');
+}
+
+/// Reads the lexemes of a `tsvector` and the operands of a `tsquery`, as `gettoken_tsvector`.
+struct ValueParser<'a> {
+    /// The whole text, for errors.
+    buffer: &'a str,
+    /// Whether operators end an unquoted value, and a colon starts its weights rather than its
+    /// positions.
+    is_query: bool,
+    /// Whether quotes and backslashes are plain characters, and double quotes end a value, as in
+    /// `websearch_to_tsquery`.
+    is_web: bool,
+}
+
+impl ValueParser<'_> {
+    fn syntax_error(&self) -> anyhow::Error {
+        let kind = if self.is_query { "tsquery" } else { "tsvector" };
+        anyhow!("syntax error in {kind}: \"{}\"", self.buffer)
+    }
+
+    /// Returns the value at `start`, its positions and where it ends, or `None` at the end of
+    /// the text.
+    fn next_value(
+        &self,
+        chars: &[char],
+        start: usize,
+    ) -> Result<Option<(String, Vec<Position>, usize)>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum State {
+            WaitWord,
+            WaitEndWord,
+            WaitNextChar,
+            WaitEndComplex,
+            WaitCharComplex,
+            WaitPosInfo,
+            InPosInfo,
+            WaitPosDelim,
+        }
+        use State::*;
+
+        let mut word = String::new();
+        let mut positions: Vec<Position> = vec![];
+        let mut state = WaitWord;
+        let mut old_state = WaitWord;
+        let mut index = start;
+        loop {
+            let character = chars.get(index).copied();
+            match state {
+                WaitWord => match character {
+                    None => return Ok(None),
+                    Some('\'') if !self.is_web => state = WaitEndComplex,
+                    Some('\\') if !self.is_web => {
+                        state = WaitNextChar;
+                        old_state = WaitEndWord;
+                    }
+                    Some(c) if (self.is_query && is_operator(c)) || (self.is_web && c == '"') => {
+                        return Err(self.syntax_error())
+                    }
+                    Some(c) if !is_space(c) => {
+                        word.push(c);
+                        state = WaitEndWord;
+                    }
+                    _ => {}
+                },
+                WaitNextChar => match character {
+                    None => bail!("there is no escaped character: \"{}\"", self.buffer),
+                    Some(c) => {
+                        word.push(c);
+                        state = old_state;
+                    }
+                },
+                WaitEndWord => match character {
+                    Some('\\') if !self.is_web => {
+                        state = WaitNextChar;
+                        old_state = WaitEndWord;
+                    }
+                    None => return Ok(Some((word, positions, index))),
+                    Some(c)
+                        if is_space(c)
+                            || (self.is_query && is_operator(c))
+                            || (self.is_web && c == '"') =>
+                    {
+                        return Ok(Some((word, positions, index)))
+                    }
+                    Some(':') => {
+                        if self.is_query {
+                            return Ok(Some((word, positions, index)));
+                        }
+                        state = InPosInfo;
+                    }
+                    Some(c) => word.push(c),
+                },
+                WaitEndComplex => match character {
+                    Some('\'') if !self.is_web => state = WaitCharComplex,
+                    Some('\\') if !self.is_web => {
+                        state = WaitNextChar;
+                        old_state = WaitEndComplex;
+                    }
+                    None => return Err(self.syntax_error()),
+                    Some(c) => word.push(c),
+                },
+                WaitCharComplex => match character {
+                    Some('\'') if !self.is_web => {
+                        word.push('\'');
+                        state = WaitEndComplex;
+                    }
+                    _ => {
+                        if word.is_empty() {
+                            return Err(self.syntax_error());
+                        }
+                        if self.is_query {
+                            return Ok(Some((word, positions, index)));
+                        }
+                        state = WaitPosInfo;
+                        // Checks the same character again.
+                        continue;
+                    }
+                },
+                WaitPosInfo => match character {
+                    Some(':') => state = InPosInfo,
+                    _ => return Ok(Some((word, positions, index))),
+                },
+                InPosInfo => match character {
+                    Some(c) if c.is_ascii_digit() => {
+                        let digits = chars[index..]
+                            .iter()
+                            .take_while(|c| c.is_ascii_digit())
+                            .collect::<String>();
+                        let pos = digits
+                            .parse::<u64>()
+                            .map_or(u64::from(MAX_ENTRY_POS), |pos| {
+                                pos.min(u64::from(MAX_ENTRY_POS))
+                            });
+                        let pos = limit_position(pos as u32);
+                        if pos == 0 {
+                            bail!("wrong position info in tsvector: \"{}\"", self.buffer);
+                        }
+                        positions.push(Position { pos, weight: 0 });
+                        state = WaitPosDelim;
+                    }
+                    _ => return Err(self.syntax_error()),
+                },
+                WaitPosDelim => match character {
+                    Some(',') => state = InPosInfo,
+                    Some(c @ ('a' | 'A' | '*' | 'b' | 'B' | 'c' | 'C' | 'd' | 'D')) => {
+                        let last = positions.last_mut().expect("a position");
+                        if last.weight != 0 {
+                            return Err(self.syntax_error());
+                        }
+                        last.weight = match c.to_ascii_lowercase() {
+                            'a' | '*' => 3,
+                            'b' => 2,
+                            'c' => 1,
+                            _ => 0,
+                        };
+                    }
+                    None => return Ok(Some((word, positions, index))),
+                    Some(c) if is_space(c) => return Ok(Some((word, positions, index))),
+                    Some(c) if !c.is_ascii_digit() => return Err(self.syntax_error()),
+                    _ => {}
+                },
+            }
+            index += 1;
+        }
+    }
+}
+
+/// Whether a character ends an unquoted operand of a `tsquery`.
+fn is_operator(character: char) -> bool {
+    matches!(character, '!' | '&' | '|' | '(' | ')' | '<')
+}
+
+/// The operators of a `tsquery`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Operator {
+    Not,
+    And,
+    Or,
+    /// Followed by, at a distance.
+    Phrase,
+}
+
+impl Operator {
+    fn priority(self) -> i32 {
+        match self {
+            Self::Not => 4,
+            Self::Phrase => 3,
+            Self::And => 2,
+            Self::Or => 1,
+        }
+    }
+}
+
+/// An operand of a `tsquery`: a lexeme, the weights it matches as a bitmask of `A` to `D` from
+/// the highest bit down, or all of them when none is given, and whether it matches the lexemes it
+/// is a prefix of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Operand {
+    pub(crate) word: String,
+    pub(crate) weight: u8,
+    pub(crate) prefix: bool,
+}
+
+impl Operand {
+    fn matches_weight(&self, weight: u8) -> bool {
+        self.weight == 0 || self.weight & (1 << weight) != 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum QueryItem {
+    Operand(Operand),
+    /// A stop word left out of the query, which is taken out once the query is made.
+    Stop,
+    Operator {
+        operator: Operator,
+        distance: u16,
+        /// How far the left operand is from the operator.
+        left: usize,
+    },
+}
+
+/// A query, as its items in Polish notation. An empty query has no items.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct TsQuery {
+    pub(crate) items: Vec<QueryItem>,
+}
+
+/// How the text of a query is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum QuerySyntax {
+    /// The `tsquery` syntax of operands and operators.
+    Standard,
+    /// The whole text is one operand, as in `plainto_tsquery`.
+    Plain,
+    /// The syntax of web search engines, as in `websearch_to_tsquery`.
+    Web,
+}
+
+enum QueryToken {
+    Value(String, u8, bool),
+    Operator(Operator, u16),
+    Open,
+    Close,
+    End,
+}
+
+/// Builds a query from the text of one, as `parse_tsquery`.
+struct QueryParser<'a> {
+    buffer: &'a str,
+    chars: Vec<char>,
+    index: usize,
+    syntax: QuerySyntax,
+    waiting_operand: bool,
+    first_operand: bool,
+    open: usize,
+    /// The items so far, in reverse Polish notation.
+    items: Vec<QueryItem>,
+}
+
+impl<'a> QueryParser<'a> {
+    fn new(buffer: &'a str, syntax: QuerySyntax) -> Self {
+        Self {
+            buffer,
+            chars: buffer.chars().collect(),
+            index: 0,
+            syntax,
+            waiting_operand: true,
+            first_operand: true,
+            open: 0,
+            items: vec![],
+        }
+    }
+
+    fn syntax_error(&self) -> anyhow::Error {
+        anyhow!("syntax error in tsquery: \"{}\"", self.buffer)
+    }
+
+    fn character(&self) -> Option<char> {
+        self.chars.get(self.index).copied()
+    }
+
+    fn value_parser(&self) -> ValueParser<'a> {
+        ValueParser {
+            buffer: self.buffer,
+            is_query: true,
+            is_web: self.syntax == QuerySyntax::Web,
+        }
+    }
+
+    fn next_token(&mut self) -> Result<QueryToken> {
+        match self.syntax {
+            QuerySyntax::Standard => self.next_standard_token(),
+            QuerySyntax::Plain => Ok(if self.first_operand {
+                self.first_operand = false;
+                QueryToken::Value(self.buffer.to_string(), 0, false)
+            } else {
+                QueryToken::End
+            }),
+            QuerySyntax::Web => self.next_web_token(),
+        }
+    }
+
+    /// As `gettoken_query_standard`.
+    fn next_standard_token(&mut self) -> Result<QueryToken> {
+        loop {
+            let character = self.character();
+            if self.waiting_operand {
+                match character {
+                    Some('!') => {
+                        self.index += 1;
+                        self.first_operand = false;
+                        return Ok(QueryToken::Operator(Operator::Not, 0));
+                    }
+                    Some('(') => {
+                        self.index += 1;
+                        self.first_operand = false;
+                        self.open += 1;
+                        return Ok(QueryToken::Open);
+                    }
+                    Some(':') => return Err(self.syntax_error()),
+                    Some(c) if is_space(c) => {}
+                    _ => {
+                        let value = self.value_parser().next_value(&self.chars, self.index)?;
+                        let Some((word, _, end)) = value else {
+                            if self.first_operand {
+                                return Ok(QueryToken::End);
+                            }
+                            bail!("no operand in tsquery: \"{}\"", self.buffer);
+                        };
+                        self.index = end;
+                        let (weight, prefix) = self.modifiers();
+                        self.waiting_operand = false;
+                        self.first_operand = false;
+                        return Ok(QueryToken::Value(word, weight, prefix));
+                    }
+                }
+            } else {
+                match character {
+                    Some('&') => {
+                        self.index += 1;
+                        self.waiting_operand = true;
+                        return Ok(QueryToken::Operator(Operator::And, 0));
+                    }
+                    Some('|') => {
+                        self.index += 1;
+                        self.waiting_operand = true;
+                        return Ok(QueryToken::Operator(Operator::Or, 0));
+                    }
+                    Some('<') => {
+                        let Some(distance) = self.phrase_operator()? else {
+                            return Err(self.syntax_error());
+                        };
+                        self.waiting_operand = true;
+                        return Ok(QueryToken::Operator(Operator::Phrase, distance));
+                    }
+                    Some(')') => {
+                        self.index += 1;
+                        if self.open == 0 {
+                            return Err(self.syntax_error());
+                        }
+                        self.open -= 1;
+                        return Ok(QueryToken::Close);
+                    }
+                    None if self.open > 0 => return Err(self.syntax_error()),
+                    None => return Ok(QueryToken::End),
+                    Some(c) if !is_space(c) => return Err(self.syntax_error()),
+                    _ => {}
+                }
+            }
+            self.index += 1;
+        }
+    }
+
+    /// As `gettoken_query_websearch`. Characters that are operators in the `tsquery` syntax are
+    /// skipped, and a query that ends with an operator gets a stop word to operate on.
+    fn next_web_token(&mut self) -> Result<QueryToken> {
+        loop {
+            let character = self.character();
+            if self.waiting_operand {
+                match character {
+                    Some('-') => {
+                        self.index += 1;
+                        self.first_operand = false;
+                        return Ok(QueryToken::Operator(Operator::Not, 0));
+                    }
+                    Some('"') => {
+                        // Everything in double quotes is one operand.
+                        let start = self.index + 1;
+                        let end = self.chars[start..]
+                            .iter()
+                            .position(|&c| c == '"')
+                            .map_or(self.chars.len(), |end| start + end);
+                        self.index = (end + 1).min(self.chars.len());
+                        self.waiting_operand = false;
+                        self.first_operand = false;
+                        let value = self.chars[start..end].iter().collect();
+                        return Ok(QueryToken::Value(value, 0, false));
+                    }
+                    Some(c) if is_operator(c) => self.first_operand = false,
+                    Some(c) if is_space(c) => {}
+                    _ => {
+                        let value = self.value_parser().next_value(&self.chars, self.index)?;
+                        let Some((word, _, end)) = value else {
+                            if !self.first_operand {
+                                self.items.push(QueryItem::Stop);
+                            }
+                            return Ok(QueryToken::End);
+                        };
+                        self.index = end;
+                        self.waiting_operand = false;
+                        self.first_operand = false;
+                        return Ok(QueryToken::Value(word, 0, false));
+                    }
+                }
+            } else {
+                match character {
+                    None => return Ok(QueryToken::End),
+                    _ if self.or_operator() => {
+                        self.index += 2;
+                        self.waiting_operand = true;
+                        return Ok(QueryToken::Operator(Operator::Or, 0));
+                    }
+                    Some(c) if is_operator(c) || is_space(c) => {}
+                    Some(_) => {
+                        self.waiting_operand = true;
+                        return Ok(QueryToken::Operator(Operator::And, 0));
+                    }
+                }
+            }
+            self.index += 1;
+        }
+    }
+
+    /// Whether an `or` that is not part of a word, and is followed by an operand, is next, as
+    /// `parse_or_operator`.
+    fn or_operator(&self) -> bool {
+        let rest = &self.chars[self.index..];
+        if rest.len() < 2
+            || !rest[0].eq_ignore_ascii_case(&'o')
+            || !rest[1].eq_ignore_ascii_case(&'r')
+        {
+            return false;
+        }
+        match rest.get(2) {
+            None => false,
+            Some(&c) if c == '-' || c == '_' || is_alpha(c) || c.is_ascii_digit() => false,
+            Some(_) => rest[3..].iter().any(|&c| !is_space(c)),
+        }
+    }
+
+    /// Reads the weights and the prefix mark after an operand, as `get_modifiers`.
+    fn modifiers(&mut self) -> (u8, bool) {
+        let mut weight = 0;
+        let mut prefix = false;
+        if self.character() != Some(':') {
+            return (weight, prefix);
+        }
+        self.index += 1;
+        while let Some(character) = self.character() {
+            match character.to_ascii_lowercase() {
+                'a' => weight |= 1 << 3,
+                'b' => weight |= 1 << 2,
+                'c' => weight |= 1 << 1,
+                'd' => weight |= 1,
+                '*' => prefix = true,
+                _ => break,
+            }
+            self.index += 1;
+        }
+        (weight, prefix)
+    }
+
+    /// Reads a phrase operator, `<->` or `<N>`, and returns its distance, as
+    /// `parse_phrase_operator`.
+    fn phrase_operator(&mut self) -> Result<Option<u16>> {
+        let rest = &self.chars[self.index + 1..];
+        let (distance, length) = match rest.first() {
+            Some('-') => (1, 1),
+            Some(c) if c.is_ascii_digit() => {
+                let digits = rest
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>();
+                match digits.parse::<u16>() {
+                    Ok(distance) if distance <= MAX_ENTRY_POS => (distance, digits.len()),
+                    _ => bail!(
+                        "distance in phrase operator must be an integer value between zero and {MAX_ENTRY_POS} inclusive"
+                    ),
+                }
+            }
+            _ => return Ok(None),
+        };
+        // The operator has to be followed by something.
+        if rest.get(length) != Some(&'>') || rest.len() <= length + 1 {
+            return Ok(None);
+        }
+        self.index += length + 2;
+        Ok(Some(distance))
+    }
+
+    fn push_value(&mut self, word: &str, weight: u8, prefix: bool) -> Result<()> {
+        if word.len() >= MAX_STRING_LEN {
+            bail!("word is too long in tsquery: \"{}\"", self.buffer);
+        }
+        self.items.push(QueryItem::Operand(Operand {
+            word: word.to_string(),
+            weight,
+            prefix,
+        }));
+        Ok(())
+    }
+
+    fn push_operator(&mut self, operator: Operator, distance: u16) {
+        self.items.push(QueryItem::Operator {
+            operator,
+            distance,
+            left: 0,
+        });
+    }
+
+    /// Reads operands and operators up to the end, or a closing parenthesis, as `makepol`.
+    fn make_polish(
+        &mut self,
+        push_value: &mut dyn FnMut(&mut Self, &str, u8, bool) -> Result<()>,
+    ) -> Result<()> {
+        let mut operators: Vec<(Operator, u16)> = vec![];
+        loop {
+            match self.next_token()? {
+                QueryToken::Value(word, weight, prefix) => push_value(self, &word, weight, prefix)?,
+                QueryToken::Operator(operator, distance) => {
+                    self.pop_operators(&mut operators, operator);
+                    operators.push((operator, distance));
+                }
+                QueryToken::Open => self.make_polish(push_value)?,
+                QueryToken::Close => {
+                    self.pop_operators(&mut operators, Operator::Or);
+                    return Ok(());
+                }
+                QueryToken::End => {
+                    self.pop_operators(&mut operators, Operator::Or);
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Pushes the stacked operators that bind tighter than the next one, as `cleanOpStack`.
+    /// `!` is right associative, the others left associative.
+    fn pop_operators(&mut self, operators: &mut Vec<(Operator, u16)>, next: Operator) {
+        while let Some(&(operator, distance)) = operators.last() {
+            let done = if next == Operator::Not {
+                next.priority() >= operator.priority()
+            } else {
+                next.priority() > operator.priority()
+            };
+            if done {
+                break;
+            }
+            operators.pop();
+            self.push_operator(operator, distance);
+        }
+    }
+
+    /// Finishes the query: its items in Polish notation, without its stop words.
+    fn finish(self) -> TsQuery {
+        let mut items = self.items;
+        if items.is_empty() {
+            return TsQuery::default();
+        }
+        items.reverse();
+        let mut query = TsQuery { items };
+        find_left_operands(&mut query.items, 0);
+        if query.items.contains(&QueryItem::Stop) {
+            query = remove_stop_words(&query);
+        }
+        query
+    }
+}
+
+/// Sets how far each operator is from its left operand, as `findoprnd`, and returns where the
+/// item at `index` ends.
+fn find_left_operands(items: &mut [QueryItem], index: usize) -> usize {
+    match items[index] {
+        QueryItem::Operand(_) | QueryItem::Stop => index + 1,
+        QueryItem::Operator {
+            operator: Operator::Not,
+            ..
+        } => find_left_operands(items, index + 1),
+        QueryItem::Operator { .. } => {
+            let left = find_left_operands(items, index + 1);
+            if let QueryItem::Operator { left: offset, .. } = &mut items[index] {
+                *offset = left - index;
+            }
+            find_left_operands(items, left)
+        }
+    }
+}
+
+/// A query as a tree, to take its stop words out.
+enum Node {
+    Item(QueryItem),
+    Not(Box<Node>),
+    Binary(Operator, u16, Box<Node>, Box<Node>),
+}
+
+fn make_tree(items: &[QueryItem], index: usize) -> Node {
+    match &items[index] {
+        QueryItem::Operator {
+            operator: Operator::Not,
+            ..
+        } => Node::Not(Box::new(make_tree(items, index + 1))),
+        QueryItem::Operator {
+            operator,
+            distance,
+            left,
+        } => Node::Binary(
+            *operator,
+            *distance,
+            Box::new(make_tree(items, index + left)),
+            Box::new(make_tree(items, index + 1)),
+        ),
+        item => Node::Item(item.clone()),
+    }
+}
+
+fn flatten_tree(node: Node, items: &mut Vec<QueryItem>) {
+    match node {
+        Node::Item(item) => items.push(item),
+        Node::Not(operand) => {
+            items.push(QueryItem::Operator {
+                operator: Operator::Not,
+                distance: 0,
+                left: 0,
+            });
+            flatten_tree(*operand, items);
+        }
+        Node::Binary(operator, distance, left, right) => {
+            let index = items.len();
+            items.push(QueryItem::Operator {
+                operator,
+                distance,
+                left: 0,
+            });
+            flatten_tree(*right, items);
+            let offset = items.len() - index;
+            if let QueryItem::Operator { left, .. } = &mut items[index] {
+                *left = offset;
+            }
+            flatten_tree(*left, items);
+        }
+    }
+}
+
+/// Takes the stop words out of a query, as `cleanup_tsquery_stopwords`. The distances of the
+/// phrase operators grow by the stop words they were over, so `cat <-> the <-> dog` becomes
+/// `cat <2> dog`.
+fn remove_stop_words(query: &TsQuery) -> TsQuery {
+    let tree = make_tree(&query.items, 0);
+    let mut items = vec![];
+    if let (Some(tree), _, _) = clean_stop_words(tree) {
+        flatten_tree(tree, &mut items);
+    }
+    TsQuery { items }
+}
+
+/// Returns a tree without its stop words, with the distances that the phrase operators above
+/// it have to add on its left and on its right, as `clean_stopword_intree`.
+fn clean_stop_words(node: Node) -> (Option<Node>, u16, u16) {
+    match node {
+        Node::Item(QueryItem::Stop) => (None, 0, 0),
+        Node::Item(item) => (Some(Node::Item(item)), 0, 0),
+        Node::Not(operand) => {
+            let (operand, left_add, right_add) = clean_stop_words(*operand);
+            match operand {
+                Some(operand) => (Some(Node::Not(Box::new(operand))), left_add, right_add),
+                None => (None, left_add, right_add),
+            }
+        }
+        Node::Binary(operator, distance, left, right) => {
+            let (left, left_left, left_right) = clean_stop_words(*left);
+            let (right, right_left, right_right) = clean_stop_words(*right);
+            let is_phrase = operator == Operator::Phrase;
+            let distance_add = if is_phrase { distance } else { 0 };
+            match (left, right) {
+                (None, None) => {
+                    if is_phrase {
+                        let add = left_left + distance_add + right_right;
+                        (None, add, add)
+                    } else {
+                        (None, 0, 0)
+                    }
+                }
+                (None, Some(right)) => {
+                    if is_phrase {
+                        (
+                            Some(right),
+                            left_right + distance_add + right_left,
+                            right_right,
+                        )
+                    } else {
+                        (Some(right), right_left, right_right)
+                    }
+                }
+                (Some(left), None) => {
+                    if is_phrase {
+                        (
+                            Some(left),
+                            left_left,
+                            left_right + distance_add + right_left,
+                        )
+                    } else {
+                        (Some(left), left_left, left_right)
+                    }
+                }
+                (Some(left), Some(right)) => {
+                    if is_phrase {
+                        let distance = distance + left_right + right_left;
+                        let node =
+                            Node::Binary(operator, distance, Box::new(left), Box::new(right));
+                        (Some(node), left_left, right_right)
+                    } else {
+                        let node =
+                            Node::Binary(operator, distance, Box::new(left), Box::new(right));
+                        (Some(node), 0, 0)
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parses the text of a `tsquery`, as `tsqueryin`: lexemes, with weights and prefix marks as
+/// in `'fat':AB* & rat`, joined by `&`, `|`, `!`, `<->` and `<N>`, and grouped in parentheses.
+pub(crate) fn parse_tsquery(text: &str) -> Result<TsQuery> {
+    let mut parser = QueryParser::new(text, QuerySyntax::Standard);
+    parser
+        .make_polish(&mut |parser, word, weight, prefix| parser.push_value(word, weight, prefix))?;
+    Ok(parser.finish())
+}
+
+/// Returns the query of a text in a syntax, with its operands normalized by a configuration,
+/// as `pushval_morph`. The lexemes of an operand are joined by `joiner`.
+fn morph_query(
+    config: Config,
+    text: &str,
+    syntax: QuerySyntax,
+    joiner: Operator,
+) -> Result<TsQuery> {
+    let mut parser = QueryParser::new(text, syntax);
+    parser.make_polish(&mut |parser, operand, weight, prefix| {
+        let words = config.parse_text(operand);
+        if words.is_empty() {
+            parser.items.push(QueryItem::Stop);
+            return Ok(());
+        }
+        let mut last_pos = 0;
+        let mut count = 0;
+        for (word, pos) in words {
+            // Stands in for the stop words that were left out, so that phrases keep their
+            // distances.
+            if last_pos > 0 {
+                while last_pos + 1 < pos {
+                    parser.items.push(QueryItem::Stop);
+                    if count > 0 {
+                        parser.push_operator(joiner, 1);
+                    }
+                    count += 1;
+                    last_pos += 1;
+                }
+            }
+            last_pos = pos;
+            parser.push_value(&word, weight, prefix)?;
+            if count > 0 {
+                parser.push_operator(joiner, 1);
+            }
+            count += 1;
+        }
+        Ok(())
+    })?;
+    Ok(parser.finish())
+}
+
+/// Returns the query of a text in the `tsquery` syntax, as `to_tsquery`.
+pub(crate) fn to_tsquery(config: Config, text: &str) -> Result<TsQuery> {
+    morph_query(config, text, QuerySyntax::Standard, Operator::Phrase)
+}
+
+/// Returns the query of the words of a text, all of which have to match, as `plainto_tsquery`.
+pub(crate) fn plainto_tsquery(config: Config, text: &str) -> Result<TsQuery> {
+    morph_query(config, text, QuerySyntax::Plain, Operator::And)
+}
+
+/// Returns the query of the words of a text as a phrase, as `phraseto_tsquery`.
+pub(crate) fn phraseto_tsquery(config: Config, text: &str) -> Result<TsQuery> {
+    morph_query(config, text, QuerySyntax::Plain, Operator::Phrase)
+}
+
+/// Returns the query of a text in the syntax of web search engines, as
+/// `websearch_to_tsquery`: quoted phrases, `or` and `-` for not.
+pub(crate) fn websearch_to_tsquery(config: Config, text: &str) -> Result<TsQuery> {
+    morph_query(config, text, QuerySyntax::Web, Operator::Phrase)
+}
+
+/// Formats a `tsquery` as Postgres does, e.g. `'fat' & ( 'rat' | 'cat' )`.
+pub(crate) fn format_tsquery(query: &TsQuery) -> String {
+    let mut text = String::new();
+    if !query.items.is_empty() {
+        write_infix(&query.items, 0, -1, false, &mut text);
+    }
+    text
+}
+
+/// Writes the item at `index` and its operands, as `infix`, and returns where it ends.
+fn write_infix(
+    items: &[QueryItem],
+    index: usize,
+    parent_priority: i32,
+    right_of_phrase: bool,
+    text: &mut String,
+) -> usize {
+    match &items[index] {
+        QueryItem::Operand(operand) => {
+            write_quoted(text, &operand.word);
+            if operand.weight != 0 || operand.prefix {
+                text.push(':');
+                if operand.prefix {
+                    text.push('*');
+                }
+                for (bit, weight) in [(3, 'A'), (2, 'B'), (1, 'C'), (0, 'D')] {
+                    if operand.weight & (1 << bit) != 0 {
+                        text.push(weight);
+                    }
+                }
+            }
+            index + 1
+        }
+        QueryItem::Stop => index + 1,
+        QueryItem::Operator {
+            operator,
+            distance,
+            left,
+        } => {
+            let priority = operator.priority();
+            let parenthesis =
+                priority < parent_priority || (*operator == Operator::Phrase && right_of_phrase);
+            if parenthesis {
+                text.push_str("( ");
+            }
+            let end = if *operator == Operator::Not {
+                text.push('!');
+                write_infix(items, index + 1, priority, false, text)
+            } else {
+                let mut right = String::new();
+                write_infix(
+                    items,
+                    index + 1,
+                    priority,
+                    *operator == Operator::Phrase,
+                    &mut right,
+                );
+                let end = write_infix(items, index + left, priority, false, text);
+                match operator {
+                    Operator::Or => text.push_str(" | "),
+                    Operator::And => text.push_str(" & "),
+                    Operator::Phrase if *distance == 1 => text.push_str(" <-> "),
+                    _ => text.push_str(&format!(" <{distance}> ")),
+                }
+                text.push_str(&right);
+                end
+            };
+            if parenthesis {
+                text.push_str(" )");
+            }
+            end
+        }
+    }
+}
+
+/// Whether a query matches, maybe matches without positions to check phrases with, or does not
+/// match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Ternary {
+    No,
+    Yes,
+    Maybe,
+}
+
+/// Where an operand, or a phrase, matches, as `ExecPhraseData`: the positions of its ends, or
+/// every position but those when `negate` is set, and how many positions it spans, less one.
+#[derive(Debug, Default)]
+struct PhraseData {
+    positions: Vec<u16>,
+    negate: bool,
+    width: u16,
+}
+
+type CheckOperand<'a> = dyn Fn(usize, &Operand, Option<&mut PhraseData>) -> Ternary + 'a;
+
+/// Whether the query item at `index` matches, given how its operands match, as `TS_execute`.
+/// Phrases that cannot be checked for lack of positions do not match.
+fn execute(items: &[QueryItem], check: &CheckOperand) -> bool {
+    !items.is_empty() && execute_item(items, 0, check) != Ternary::No
+}
+
+fn execute_item(items: &[QueryItem], index: usize, check: &CheckOperand) -> Ternary {
+    match &items[index] {
+        QueryItem::Operand(operand) => check(index, operand, None),
+        QueryItem::Stop => Ternary::No,
+        QueryItem::Operator { operator, left, .. } => match operator {
+            Operator::Not => match execute_item(items, index + 1, check) {
+                Ternary::No => Ternary::Yes,
+                Ternary::Yes => Ternary::No,
+                Ternary::Maybe => Ternary::Maybe,
+            },
+            Operator::And => {
+                let left = execute_item(items, index + left, check);
+                if left == Ternary::No {
+                    return Ternary::No;
+                }
+                match execute_item(items, index + 1, check) {
+                    Ternary::No => Ternary::No,
+                    Ternary::Yes => left,
+                    Ternary::Maybe => Ternary::Maybe,
+                }
+            }
+            Operator::Or => {
+                let left = execute_item(items, index + left, check);
+                if left == Ternary::Yes {
+                    return Ternary::Yes;
+                }
+                match execute_item(items, index + 1, check) {
+                    Ternary::No => left,
+                    Ternary::Yes => Ternary::Yes,
+                    Ternary::Maybe => Ternary::Maybe,
+                }
+            }
+            Operator::Phrase => match execute_phrase(items, index, check, None) {
+                Ternary::Maybe => Ternary::No,
+                result => result,
+            },
+        },
+    }
+}
+
+const EMIT_LEFT_ONLY: u8 = 1;
+const EMIT_RIGHT_ONLY: u8 = 1 << 1;
+const EMIT_BOTH: u8 = 1 << 2;
+
+/// Merges the positions of two operands, offset to align them, as `TS_phrase_output`.
+fn phrase_output(
+    data: Option<&mut PhraseData>,
+    left: &PhraseData,
+    right: &PhraseData,
+    emit: u8,
+    left_offset: u16,
+    right_offset: u16,
+) -> Ternary {
+    let mut output = vec![];
+    let (mut left_index, mut right_index) = (0, 0);
+    while left_index < left.positions.len() || right_index < right.positions.len() {
+        let left_pos = match left.positions.get(left_index) {
+            Some(&pos) => u32::from(pos) + u32::from(left_offset),
+            None if emit & EMIT_RIGHT_ONLY == 0 => break,
+            None => u32::MAX,
+        };
+        let right_pos = match right.positions.get(right_index) {
+            Some(&pos) => u32::from(pos) + u32::from(right_offset),
+            None if emit & EMIT_LEFT_ONLY == 0 => break,
+            None => u32::MAX,
+        };
+        let mut output_pos = 0;
+        match left_pos.cmp(&right_pos) {
+            Ordering::Less => {
+                if emit & EMIT_LEFT_ONLY != 0 {
+                    output_pos = left_pos;
+                }
+                left_index += 1;
+            }
+            Ordering::Equal => {
+                if emit & EMIT_BOTH != 0 {
+                    output_pos = right_pos;
+                }
+                left_index += 1;
+                right_index += 1;
+            }
+            Ordering::Greater => {
+                if emit & EMIT_RIGHT_ONLY != 0 {
+                    output_pos = right_pos;
+                }
+                right_index += 1;
+            }
+        }
+        if output_pos > 0 {
+            if data.is_none() {
+                return Ternary::Yes;
+            }
+            output.push(output_pos as u16);
+        }
+    }
+    match data {
+        Some(data) if !output.is_empty() => {
+            data.positions = output;
+            Ternary::Yes
+        }
+        _ => Ternary::No,
+    }
+}
+
+/// Whether the query item at `index` matches, and where, as `TS_phrase_execute`.
+fn execute_phrase(
+    items: &[QueryItem],
+    index: usize,
+    check: &CheckOperand,
+    mut data: Option<&mut PhraseData>,
+) -> Ternary {
+    let (operator, distance, left) = match &items[index] {
+        QueryItem::Operand(operand) => return check(index, operand, data),
+        QueryItem::Stop => return Ternary::No,
+        QueryItem::Operator {
+            operator,
+            distance,
+            left,
+        } => (*operator, *distance, *left),
+    };
+    let mut left_data = PhraseData::default();
+    let mut right_data = PhraseData::default();
+    match operator {
+        Operator::Not => {
+            let mut operand_data = PhraseData::default();
+            let result = execute_phrase(items, index + 1, check, Some(&mut operand_data));
+            let width = operand_data.width;
+            match result {
+                Ternary::No => {
+                    if let Some(data) = data {
+                        *data = PhraseData {
+                            positions: vec![],
+                            negate: true,
+                            width,
+                        };
+                    }
+                    Ternary::Yes
+                }
+                Ternary::Yes if !operand_data.positions.is_empty() => {
+                    operand_data.negate = !operand_data.negate;
+                    if let Some(data) = data {
+                        *data = operand_data;
+                    }
+                    Ternary::Yes
+                }
+                Ternary::Yes => Ternary::No,
+                Ternary::Maybe => Ternary::Maybe,
+            }
+        }
+        Operator::Phrase | Operator::And => {
+            let left_match = execute_phrase(items, index + left, check, Some(&mut left_data));
+            if left_match == Ternary::No {
+                return Ternary::No;
+            }
+            let right_match = execute_phrase(items, index + 1, check, Some(&mut right_data));
+            if right_match == Ternary::No {
+                return Ternary::No;
+            }
+            if left_match == Ternary::Maybe || right_match == Ternary::Maybe {
+                return Ternary::Maybe;
+            }
+            let (left_offset, right_offset) = if operator == Operator::Phrase {
+                if let Some(data) = data.as_deref_mut() {
+                    data.width = distance + left_data.width + right_data.width;
+                }
+                (distance + right_data.width, 0)
+            } else {
+                let width = left_data.width.max(right_data.width);
+                if let Some(data) = data.as_deref_mut() {
+                    data.width = width;
+                }
+                (width - left_data.width, width - right_data.width)
+            };
+            match (left_data.negate, right_data.negate) {
+                (true, true) => {
+                    let emit = EMIT_BOTH | EMIT_LEFT_ONLY | EMIT_RIGHT_ONLY;
+                    phrase_output(
+                        data.as_deref_mut(),
+                        &left_data,
+                        &right_data,
+                        emit,
+                        left_offset,
+                        right_offset,
+                    );
+                    if let Some(data) = data {
+                        data.negate = true;
+                    }
+                    Ternary::Yes
+                }
+                (true, false) => phrase_output(
+                    data,
+                    &left_data,
+                    &right_data,
+                    EMIT_RIGHT_ONLY,
+                    left_offset,
+                    right_offset,
+                ),
+                (false, true) => phrase_output(
+                    data,
+                    &left_data,
+                    &right_data,
+                    EMIT_LEFT_ONLY,
+                    left_offset,
+                    right_offset,
+                ),
+                (false, false) => phrase_output(
+                    data,
+                    &left_data,
+                    &right_data,
+                    EMIT_BOTH,
+                    left_offset,
+                    right_offset,
+                ),
+            }
+        }
+        Operator::Or => {
+            let left_match = execute_phrase(items, index + left, check, Some(&mut left_data));
+            let right_match = execute_phrase(items, index + 1, check, Some(&mut right_data));
+            if left_match == Ternary::No && right_match == Ternary::No {
+                return Ternary::No;
+            }
+            if left_match == Ternary::Maybe || right_match == Ternary::Maybe {
+                return Ternary::Maybe;
+            }
+            if left_match == Ternary::No {
+                left_data.width = 0;
+            }
+            if right_match == Ternary::No {
+                right_data.width = 0;
+            }
+            let width = left_data.width.max(right_data.width);
+            let (left_offset, right_offset) = (width - left_data.width, width - right_data.width);
+            if let Some(data) = data.as_deref_mut() {
+                data.width = width;
+            }
+            let emit = match (left_data.negate, right_data.negate) {
+                (true, true) => EMIT_BOTH,
+                (true, false) => EMIT_LEFT_ONLY,
+                (false, true) => EMIT_RIGHT_ONLY,
+                (false, false) => {
+                    let emit = EMIT_BOTH | EMIT_LEFT_ONLY | EMIT_RIGHT_ONLY;
+                    return phrase_output(
+                        data,
+                        &left_data,
+                        &right_data,
+                        emit,
+                        left_offset,
+                        right_offset,
+                    );
+                }
+            };
+            phrase_output(
+                data.as_deref_mut(),
+                &left_data,
+                &right_data,
+                emit,
+                left_offset,
+                right_offset,
+            );
+            if let Some(data) = data {
+                data.negate = true;
+            }
+            Ternary::Yes
+        }
+    }
+}
+
+/// How a lexeme of a document matches an operand, as `checkclass_str`: its positions of the
+/// weights the operand asks for, or maybe when they have been left out.
+fn check_lexeme(lexeme: &Lexeme, operand: &Operand, data: Option<&mut PhraseData>) -> Ternary {
+    if lexeme.positions.is_empty() || (operand.weight == 0 && data.is_none()) {
+        return if data.is_some() {
+            Ternary::Maybe
+        } else {
+            Ternary::Yes
+        };
+    }
+    let positions = lexeme
+        .positions
+        .iter()
+        .filter(|position| operand.matches_weight(position.weight))
+        .map(|position| position.pos)
+        .collect::<Vec<_>>();
+    if positions.is_empty() {
+        return Ternary::No;
+    }
+    if let Some(data) = data {
+        data.positions = positions;
+    }
+    Ternary::Yes
+}
+
+/// How a document matches an operand, as `checkcondition_str`. A prefix matches at the
+/// positions of all the lexemes it is a prefix of.
+fn check_operand(vector: &TsVector, operand: &Operand, data: Option<&mut PhraseData>) -> Ternary {
+    let lexemes = vector.find(operand);
+    if !operand.prefix {
+        return match lexemes.first() {
+            Some(lexeme) => check_lexeme(lexeme, operand, data),
+            None => Ternary::No,
+        };
+    }
+    let mut result = Ternary::No;
+    let mut positions = vec![];
+    for lexeme in lexemes {
+        let mut lexeme_data = PhraseData::default();
+        let wanted = data.is_some().then_some(&mut lexeme_data);
+        match check_lexeme(lexeme, operand, wanted) {
+            Ternary::No => {}
+            Ternary::Maybe if data.is_some() => return Ternary::Maybe,
+            lexeme_result => {
+                if data.is_some() {
+                    positions.extend(lexeme_data.positions);
+                } else if lexeme_result == Ternary::Yes || result == Ternary::No {
+                    result = lexeme_result;
+                    if result == Ternary::Yes {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    if let Some(data) = data {
+        if !positions.is_empty() {
+            positions.sort_unstable();
+            positions.dedup();
+            data.positions = positions;
+            result = Ternary::Yes;
+        }
+    }
+    result
+}
+
+/// Whether a document matches a query, as `@@`. An empty query matches nothing.
+pub(crate) fn matches(vector: &TsVector, query: &TsQuery) -> bool {
+    execute(&query.items, &|_, operand, data| {
+        check_operand(vector, operand, data)
+    })
+}
+
+/// Returns the weights of `ts_rank` and `ts_rank_cd`, for `D` to `A`, from the ones given, as
+/// `getWeights`. Negative weights stand for the defaults.
+pub(crate) fn rank_weights(weights: Option<&[Option<f32>]>) -> Result<[f32; 4]> {
+    let Some(weights) = weights else {
+        return Ok(DEFAULT_WEIGHTS);
+    };
+    if weights.len() < 4 {
+        bail!("array of weight is too short");
+    }
+    let mut result = DEFAULT_WEIGHTS;
+    for (index, weight) in weights.iter().take(4).enumerate() {
+        let weight = weight.ok_or_else(|| anyhow!("array of weight must not contain nulls"))?;
+        if weight >= 0.0 {
+            result[index] = weight;
+        }
+        if result[index] > 1.0 {
+            bail!("weight out of range");
+        }
+    }
+    Ok(result)
+}
+
+/// Returns a weight of how close two lexemes are, as `word_distance`.
+fn word_distance(distance: i32) -> f32 {
+    if distance > 100 {
+        return 1e-30;
+    }
+    (1.0 / (1.005 + 0.05 * (f64::from(distance as f32) / 1.5 - 2.0).exp())) as f32
+}
+
+/// The distinct operands of a query, in order, as `SortAndUniqItems`.
+fn distinct_operands(query: &TsQuery) -> Vec<&Operand> {
+    let mut operands = query
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            QueryItem::Operand(operand) => Some(operand),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    operands.sort_by(|first, second| first.word.cmp(&second.word));
+    operands.dedup_by(|first, second| first.word == second.word);
+    operands
+}
+
+/// The weights of the positions of a lexeme, with a position without weight when they have been
+/// left out.
+fn weights_of(lexeme: &Lexeme) -> Vec<u8> {
+    if lexeme.positions.is_empty() {
+        vec![0]
+    } else {
+        lexeme
+            .positions
+            .iter()
+            .map(|position| position.weight)
+            .collect()
+    }
+}
+
+/// Ranks a document for a query that any of the operands matches, as `calc_rank_or`.
+fn rank_or(weights: &[f32; 4], vector: &TsVector, query: &TsQuery) -> f32 {
+    let operands = distinct_operands(query);
+    let mut rank = 0.0_f32;
+    for operand in &operands {
+        for lexeme in vector.find(operand) {
+            let mut sum = 0.0_f32;
+            let mut max_weight = -1.0_f32;
+            let mut max_index = 0;
+            for (index, weight) in weights_of(lexeme).into_iter().enumerate() {
+                let weight = weights[usize::from(weight)];
+                let square = ((index + 1) * (index + 1)) as f32;
+                sum += weight / square;
+                if weight > max_weight {
+                    max_weight = weight;
+                    max_index = index;
+                }
+            }
+            let square = ((max_index + 1) * (max_index + 1)) as f32;
+            rank = (f64::from(rank)
+                + f64::from(max_weight + sum - max_weight / square) / 1.64493406685)
+                as f32;
+        }
+    }
+    if !operands.is_empty() {
+        rank /= operands.len() as f32;
+    }
+    rank
+}
+
+/// Ranks a document for a query that all the operands match, by how close they are, as
+/// `calc_rank_and`.
+fn rank_and(weights: &[f32; 4], vector: &TsVector, query: &TsQuery) -> f32 {
+    let operands = distinct_operands(query);
+    if operands.len() < 2 {
+        return rank_or(weights, vector, query);
+    }
+    // The positions of the operands so far, with a position past the others when they have been
+    // left out.
+    let mut seen: Vec<(Vec<Position>, bool)> = vec![];
+    let mut rank = -1.0_f32;
+    for operand in &operands {
+        let mut current = None;
+        for lexeme in vector.find(operand) {
+            let positions = if lexeme.positions.is_empty() {
+                (
+                    vec![Position {
+                        pos: MAX_ENTRY_POS - 1,
+                        weight: 0,
+                    }],
+                    true,
+                )
+            } else {
+                (lexeme.positions.clone(), false)
+            };
+            for (previous, previous_null) in &seen {
+                for position in &positions.0 {
+                    for previous_position in previous {
+                        let mut distance =
+                            (i32::from(position.pos) - i32::from(previous_position.pos)).abs();
+                        if distance != 0 || positions.1 || *previous_null {
+                            if distance == 0 {
+                                distance = i32::from(MAX_ENTRY_POS);
+                            }
+                            let weight = weights[usize::from(position.weight)]
+                                * weights[usize::from(previous_position.weight)]
+                                * word_distance(distance);
+                            let weight = f64::from(weight).sqrt() as f32;
+                            rank = if rank < 0.0 {
+                                weight
+                            } else {
+                                (1.0 - (1.0 - f64::from(rank)) * (1.0 - f64::from(weight))) as f32
+                            };
+                        }
+                    }
+                }
+            }
+            current = Some(positions);
+        }
+        // As Postgres, only the last lexeme of a prefix counts for the operands after it.
+        if let Some(current) = current {
+            seen.push(current);
+        }
+    }
+    rank
+}
+
+const NORM_LOG_LENGTH: i32 = 0x01;
+const NORM_LENGTH: i32 = 0x02;
+const NORM_EXTENT_DISTANCE: i32 = 0x04;
+const NORM_UNIQUE: i32 = 0x08;
+const NORM_LOG_UNIQUE: i32 = 0x10;
+const NORM_RANK_PLUS_ONE: i32 = 0x20;
+
+/// Ranks a document for a query by how often, and how close, the lexemes of the query are in
+/// it, as `ts_rank`. `normalization` is a bitmask of how the rank is scaled for the length of
+/// the document.
+pub(crate) fn rank(
+    weights: &[f32; 4],
+    vector: &TsVector,
+    query: &TsQuery,
+    normalization: i32,
+) -> f32 {
+    if vector.lexemes.is_empty() || query.items.is_empty() {
+        return 0.0;
+    }
+    let all = matches!(
+        query.items[0],
+        QueryItem::Operator {
+            operator: Operator::And | Operator::Phrase,
+            ..
+        }
+    );
+    let mut rank = if all {
+        rank_and(weights, vector, query)
+    } else {
+        rank_or(weights, vector, query)
+    };
+    if rank < 0.0 {
+        rank = 1e-20;
+    }
+    let unique = vector.lexemes.len();
+    if normalization & NORM_LOG_LENGTH != 0 {
+        rank = (f64::from(rank) / ((vector.length() as f64 + 1.0).ln() / 2.0_f64.ln())) as f32;
+    }
+    if normalization & NORM_LENGTH != 0 {
+        let length = vector.length();
+        if length > 0 {
+            rank /= length as f32;
+        }
+    }
+    if normalization & NORM_UNIQUE != 0 {
+        rank /= unique as f32;
+    }
+    if normalization & NORM_LOG_UNIQUE != 0 {
+        rank = (f64::from(rank) / ((unique as f64 + 1.0).ln() / 2.0_f64.ln())) as f32;
+    }
+    if normalization & NORM_RANK_PLUS_ONE != 0 {
+        rank /= rank + 1.0;
+    }
+    rank
+}
+
+/// A position of the document that matches operands of the query, as `DocRepresentation`.
+struct DocumentPosition {
+    position: Position,
+    /// The query items of the operands.
+    items: Vec<usize>,
+}
+
+/// Returns the positions of the document that match operands of the query, in order, as
+/// `get_docrep`.
+fn document_positions(vector: &TsVector, query: &TsQuery) -> Vec<DocumentPosition> {
+    // The position, the lexeme and the query item of each match.
+    let mut matches = vec![];
+    for (item, query_item) in query.items.iter().enumerate() {
+        let QueryItem::Operand(operand) = query_item else {
+            continue;
+        };
+        for index in vector.find_indices(operand) {
+            for position in &vector.lexemes[index].positions {
+                if operand.matches_weight(position.weight) {
+                    matches.push((*position, index, item));
+                }
+            }
+        }
+    }
+    matches.sort_by_key(|(position, lexeme, _)| (position.pos, position.weight, *lexeme));
+
+    let mut positions: Vec<(DocumentPosition, usize)> = vec![];
+    for (position, lexeme, item) in matches {
+        match positions.last_mut() {
+            Some((last, last_lexeme)) if last.position == position && *last_lexeme == lexeme => {
+                last.items.push(item);
+            }
+            _ => positions.push((
+                DocumentPosition {
+                    position,
+                    items: vec![item],
+                },
+                lexeme,
+            )),
+        }
+    }
+    positions
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect()
+}
+
+/// The positions of the operands that a part of the document has, as `QueryRepresentation`.
+/// Filled from the right, they are kept in reverse order and reversed when used.
+struct OperandPositions {
+    positions: Vec<Option<Vec<u16>>>,
+    reverse: bool,
+}
+
+impl OperandPositions {
+    fn new(size: usize, reverse: bool) -> Self {
+        Self {
+            positions: vec![None; size],
+            reverse,
+        }
+    }
+
+    fn add(&mut self, position: &DocumentPosition) {
+        for &item in &position.items {
+            let positions = self.positions[item].get_or_insert_with(Vec::new);
+            if positions.last() != Some(&position.position.pos) {
+                positions.push(position.position.pos);
+            }
+        }
+    }
+
+    fn check(&self, item: usize, data: Option<&mut PhraseData>) -> Ternary {
+        let Some(positions) = &self.positions[item] else {
+            return Ternary::No;
+        };
+        if let Some(data) = data {
+            data.positions = positions.clone();
+            if self.reverse {
+                data.positions.reverse();
+            }
+        }
+        Ternary::Yes
+    }
+}
+
+/// A cover: the shortest part of the document, from `begin` to `end`, that matches the query.
+struct Cover {
+    /// Where to look for the next cover.
+    next: usize,
+    begin: usize,
+    end: usize,
+    first_pos: i32,
+    last_pos: i32,
+}
+
+/// Finds the next cover of the document, as `Cover`.
+fn next_cover(positions: &[DocumentPosition], query: &TsQuery, cover: &mut Cover) -> bool {
+    loop {
+        let mut operands = OperandPositions::new(query.items.len(), false);
+        cover.first_pos = i32::MAX;
+        cover.last_pos = 0;
+        let mut last = None;
+        for (index, position) in positions.iter().enumerate().skip(cover.next) {
+            operands.add(position);
+            if execute(&query.items, &|item, _, data| operands.check(item, data)) {
+                if i32::from(position.position.pos) > cover.last_pos {
+                    cover.last_pos = i32::from(position.position.pos);
+                    cover.end = index;
+                    last = Some(index);
+                }
+                break;
+            }
+        }
+        let Some(last) = last else {
+            return false;
+        };
+
+        let mut operands = OperandPositions::new(query.items.len(), true);
+        let mut begin = last;
+        for index in (cover.next..=last).rev() {
+            begin = index;
+            operands.add(&positions[index]);
+            if execute(&query.items, &|item, _, data| operands.check(item, data)) {
+                if i32::from(positions[index].position.pos) < cover.first_pos {
+                    cover.begin = index;
+                    cover.first_pos = i32::from(positions[index].position.pos);
+                }
+                break;
+            }
+        }
+
+        if cover.first_pos <= cover.last_pos {
+            cover.next = begin + 1;
+            return true;
+        }
+        cover.next += 1;
+    }
+}
+
+/// Ranks a document for a query by its covers, the shortest parts of it that match the query,
+/// as `ts_rank_cd`.
+pub(crate) fn rank_cover_density(
+    weights: &[f32; 4],
+    vector: &TsVector,
+    query: &TsQuery,
+    normalization: i32,
+) -> f32 {
+    let inverse_weights = weights.map(|weight| 1.0 / f64::from(weight));
+    let positions = document_positions(vector, query);
+    if positions.is_empty() {
+        return 0.0;
+    }
+
+    let mut rank = 0.0_f64;
+    let mut sum_distance = 0.0_f64;
+    let mut previous_extent = 0.0_f64;
+    let mut extents = 0;
+    let mut cover = Cover {
+        next: 0,
+        begin: 0,
+        end: 0,
+        first_pos: 0,
+        last_pos: 0,
+    };
+    while next_cover(&positions, query, &mut cover) {
+        let inverse_sum = positions[cover.begin..=cover.end]
+            .iter()
+            .map(|position| inverse_weights[usize::from(position.position.weight)])
+            .sum::<f64>();
+        let length = (cover.end - cover.begin) as i32;
+        let density = f64::from(length + 1) / inverse_sum;
+        let mut noise = (cover.last_pos - cover.first_pos) - length;
+        if noise < 0 {
+            noise = length / 2;
+        }
+        rank += density / f64::from(1 + noise);
+
+        let extent = f64::from(cover.last_pos + cover.first_pos) / 2.0;
+        if extents > 0 && extent > previous_extent {
+            sum_distance += 1.0 / (extent - previous_extent);
+        }
+        previous_extent = extent;
+        extents += 1;
+    }
+
+    let unique = vector.lexemes.len() as f64;
+    if normalization & NORM_LOG_LENGTH != 0 {
+        rank /= (vector.length() as f64 + 1.0).ln();
+    }
+    if normalization & NORM_LENGTH != 0 {
+        let length = vector.length();
+        if length > 0 {
+            rank /= length as f64;
+        }
+    }
+    if normalization & NORM_EXTENT_DISTANCE != 0 && extents > 0 && sum_distance > 0.0 {
+        rank /= f64::from(extents) / sum_distance;
+    }
+    if normalization & NORM_UNIQUE != 0 {
+        rank /= unique;
+    }
+    if normalization & NORM_LOG_UNIQUE != 0 {
+        rank /= (unique + 1.0).ln() / 2.0_f64.ln();
+    }
+    if normalization & NORM_RANK_PLUS_ONE != 0 {
+        rank /= rank + 1.0;
+    }
+    rank as f32
+}
+
+/// The options of `ts_headline`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HeadlineOptions {
+    max_words: i32,
+    min_words: i32,
+    short_word: i32,
+    max_fragments: i32,
+    start_sel: String,
+    stop_sel: String,
+    fragment_delimiter: String,
+    highlight_all: bool,
+}
+
+impl HeadlineOptions {
+    /// Parses options such as `MaxWords=10, StartSel="<em>"`, as `prsd_headline`.
+    fn parse(text: &str) -> Result<Self> {
+        let mut options = Self {
+            max_words: 35,
+            min_words: 15,
+            short_word: 3,
+            max_fragments: 0,
+            start_sel: "<b>".to_string(),
+            stop_sel: "</b>".to_string(),
+            fragment_delimiter: " ... ".to_string(),
+            highlight_all: false,
+        };
+        let integer = |value: &str| {
+            value
+                .trim()
+                .parse::<i32>()
+                .map_err(|_| anyhow!("invalid input syntax for type integer: \"{value}\""))
+        };
+        for (name, value) in parse_parameters(text)? {
+            match name.to_lowercase().as_str() {
+                "maxwords" => options.max_words = integer(&value)?,
+                "minwords" => options.min_words = integer(&value)?,
+                "shortword" => options.short_word = integer(&value)?,
+                "maxfragments" => options.max_fragments = integer(&value)?,
+                "startsel" => options.start_sel = value,
+                "stopsel" => options.stop_sel = value,
+                "fragmentdelimiter" => options.fragment_delimiter = value,
+                "highlightall" => {
+                    options.highlight_all = matches!(
+                        value.to_lowercase().as_str(),
+                        "1" | "on" | "true" | "t" | "y" | "yes"
+                    )
+                }
+                _ => bail!("unrecognized headline parameter: \"{name}\""),
+            }
+        }
+        if !options.highlight_all {
+            if options.min_words >= options.max_words {
+                bail!("MinWords should be less than MaxWords");
+            }
+            if options.min_words <= 0 {
+                bail!("MinWords should be positive");
+            }
+            if options.short_word < 0 {
+                bail!("ShortWord should be >= 0");
+            }
+            if options.max_fragments < 0 {
+                bail!("MaxFragments should be >= 0");
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Parses a list of `name = value` parameters separated by commas or spaces, with values in
+/// single or double quotes when they have either, as `deserialize_deflist`.
+fn parse_parameters(text: &str) -> Result<Vec<(String, String)>> {
+    let error = || anyhow!("invalid parameter list format: \"{text}\"");
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut parameters = vec![];
+    let mut index = 0;
+    let skip = |index: &mut usize, separators: bool| {
+        while chars
+            .get(*index)
+            .is_some_and(|&c| is_space(c) || (separators && c == ','))
+        {
+            *index += 1;
+        }
+    };
+    // Reads a quoted text, in which quotes are doubled.
+    let quoted = |index: &mut usize, quote: char| {
+        let mut quoted = String::new();
+        *index += 1;
+        loop {
+            match chars.get(*index) {
+                None => return Err(error()),
+                Some(&c) if c == quote && chars.get(*index + 1) == Some(&quote) => {
+                    quoted.push(c);
+                    *index += 2;
+                }
+                Some(&c) if c == quote => {
+                    *index += 1;
+                    return Ok(quoted);
+                }
+                Some(&c) => {
+                    quoted.push(c);
+                    *index += 1;
+                }
+            }
+        }
+    };
+    let unquoted = |index: &mut usize, end: fn(char) -> bool| {
+        let start = *index;
+        while chars.get(*index).is_some_and(|&c| !is_space(c) && !end(c)) {
+            *index += 1;
+        }
+        chars[start..*index].iter().collect::<String>()
+    };
+    loop {
+        skip(&mut index, true);
+        if index >= chars.len() {
+            return Ok(parameters);
+        }
+        let name = if chars[index] == '"' {
+            quoted(&mut index, '"')?
+        } else {
+            unquoted(&mut index, |c| c == '=')
+        };
+        skip(&mut index, false);
+        if name.is_empty() || chars.get(index) != Some(&'=') {
+            return Err(error());
+        }
+        index += 1;
+        skip(&mut index, false);
+        let value = match chars.get(index) {
+            Some(&quote @ ('\'' | '"')) => quoted(&mut index, quote)?,
+            Some(_) => unquoted(&mut index, |c| c == ','),
+            None => return Err(error()),
+        };
+        parameters.push((name, value));
+    }
+}
+
+/// A token of a document in a headline, as `HeadlineWordEntry`.
+#[derive(Debug, Clone)]
+struct HeadlineWord {
+    token_type: TokenType,
+    text: String,
+    pos: u16,
+    /// The query item of the operand the word matches.
+    item: Option<usize>,
+    /// Whether the word is a copy, for another operand it matches.
+    repeated: bool,
+    selected: bool,
+    shown: bool,
+    replace: bool,
+    skip: bool,
+}
+
+impl HeadlineWord {
+    fn is_word(&self) -> bool {
+        use TokenType::*;
+        !matches!(
+            self.token_type,
+            Blank | Tag | Url | NumHword | AsciiHword | Hword
+        )
+    }
+
+    /// Whether a headline should not end at the word.
+    fn is_bad_end(&self, short_word: i32) -> bool {
+        use TokenType::*;
+        let bad = !self.is_word()
+            || matches!(
+                self.token_type,
+                Sfloat | Version | Float | Int | Uint | Protocol | Entity
+            )
+            || self.text.len() as i32 <= short_word;
+        bad && !self.is_interesting()
+    }
+
+    fn is_interesting(&self) -> bool {
+        self.item.is_some() && !self.repeated
+    }
+}
+
+/// Returns the tokens of a document, with the query operands they match, as `hlparsetext`.
+fn headline_words(config: Config, text: &str, query: &TsQuery) -> Result<Vec<HeadlineWord>> {
+    let mut words = vec![];
+    let mut position = 0_u32;
+    for (token_type, token) in tokenize(text) {
+        if token.len() >= MAX_STRING_LEN {
+            bail!("word is too long to be indexed");
+        }
+        let lexemes = config.lexize(token_type, &token);
+        words.push(HeadlineWord {
+            token_type,
+            text: token,
+            pos: 0,
+            item: None,
+            repeated: false,
+            selected: false,
+            shown: false,
+            replace: false,
+            skip: false,
+        });
+        let Some(lexemes) = lexemes else {
+            continue;
+        };
+        position += 1;
+        for lexeme in lexemes {
+            let index = words.len() - 1;
+            words[index].pos = limit_position(position);
+            for (item, query_item) in query.items.iter().enumerate() {
+                let QueryItem::Operand(operand) = query_item else {
+                    continue;
+                };
+                let matches = if operand.prefix {
+                    lexeme.starts_with(&operand.word)
+                } else {
+                    lexeme == operand.word
+                };
+                if !matches {
+                    continue;
+                }
+                if words[index].item.is_some() {
+                    let mut repeated = words[index].clone();
+                    repeated.item = Some(item);
+                    repeated.repeated = true;
+                    words.push(repeated);
+                } else {
+                    words[index].item = Some(item);
+                }
+            }
+        }
+    }
+    Ok(words)
+}
+
+/// Finds the first and shortest part of the words from `*start` on, of at most `max_cover`
+/// words, that matches the query, as `hlCover`, and returns its first and last words.
+fn headline_cover(
+    words: &[HeadlineWord],
+    query: &TsQuery,
+    max_cover: usize,
+    start: usize,
+) -> Option<(usize, usize)> {
+    if query.items.is_empty() {
+        return None;
+    }
+    let next_item = |from: usize| (from..words.len()).find(|&index| words[index].item.is_some());
+    let mut first = next_item(start)?;
+    loop {
+        let mut next_first = None;
+        let mut last = first;
+        loop {
+            let part = &words[first..=last];
+            let check = |item: usize, _: &Operand, data: Option<&mut PhraseData>| {
+                let mut positions: Vec<u16> = vec![];
+                for word in part.iter().filter(|word| word.item == Some(item)) {
+                    if data.is_none() {
+                        return Ternary::Yes;
+                    }
+                    if positions.last().filter(|&&last| last >= word.pos).is_none() {
+                        positions.push(word.pos);
+                    }
+                }
+                match data {
+                    Some(data) if !positions.is_empty() => {
+                        data.positions = positions;
+                        Ternary::Yes
+                    }
+                    _ => Ternary::No,
+                }
+            };
+            if execute(&query.items, &check) {
+                return Some((first, last));
+            }
+            let Some(next) = next_item(last + 1) else {
+                if last == first {
+                    return None;
+                }
+                break;
+            };
+            if last == first {
+                next_first = Some(next);
+            }
+            if next - first >= max_cover {
+                break;
+            }
+            last = next;
+        }
+        first = next_first?;
+    }
+}
+
+/// Shows the words from `start` to `end`, and highlights the ones that match the query, as
+/// `mark_fragment`.
+fn mark_fragment(words: &mut [HeadlineWord], highlight_all: bool, start: usize, end: isize) {
+    use TokenType::*;
+    for word in words.iter_mut().take((end + 1).max(0) as usize).skip(start) {
+        if word.item.is_some() {
+            word.selected = true;
+        }
+        if !highlight_all && word.token_type == Tag {
+            word.replace = true;
+        } else if matches!(word.token_type, Url | NumHword | AsciiHword | Hword) {
+            word.skip = true;
+        }
+        word.shown = !word.repeated;
+    }
+}
+
+/// Chooses the part of the document to show, when there is one fragment, as `mark_hl_words`.
+fn mark_headline_words(
+    words: &mut [HeadlineWord],
+    query: &TsQuery,
+    options: &HeadlineOptions,
+    max_cover: usize,
+) {
+    let max_words = options.max_words;
+    let min_words = options.min_words;
+    let short_word = options.short_word;
+    let len = words.len() as isize;
+    let (mut best_begin, mut best_end): (isize, isize) = (-1, -1);
+    let mut best_length = -1;
+    let mut best_cover = false;
+
+    if !options.highlight_all {
+        let mut start = 0;
+        while let Some((p, q)) = headline_cover(words, query, max_cover, start) {
+            let (p, q) = (p as isize, q as isize);
+            let word = |i: isize| &words[i as usize];
+            let bad_end = |i: isize| word(i).is_bad_end(short_word);
+
+            // Counts the words and the interesting words of the cover, up to `max_words`.
+            let mut current_length = 0;
+            let mut interesting = 0;
+            let mut begin = p;
+            let mut end = p;
+            let mut i = p;
+            while i <= q && current_length < max_words {
+                if word(i).is_word() {
+                    current_length += 1;
+                }
+                if word(i).is_interesting() {
+                    interesting += 1;
+                }
+                end = i;
+                i += 1;
+            }
+
+            if current_length < max_words {
+                // Lengthens the headline forward to a good end, and then backward.
+                i -= 1;
+                while i < len && current_length < max_words {
+                    if i > q {
+                        if word(i).is_word() {
+                            current_length += 1;
+                        }
+                        if word(i).is_interesting() {
+                            interesting += 1;
+                        }
+                    }
+                    end = i;
+                    if bad_end(i) || current_length < min_words {
+                        i += 1;
+                        continue;
+                    }
+                    break;
+                }
+                if current_length < min_words {
+                    i = p - 1;
+                    while i >= 0 {
+                        if word(i).is_word() {
+                            current_length += 1;
+                        }
+                        if word(i).is_interesting() {
+                            interesting += 1;
+                        }
+                        if current_length >= max_words
+                            || (!bad_end(i) && current_length >= min_words)
+                        {
+                            break;
+                        }
+                        i -= 1;
+                    }
+                    begin = i.max(0);
+                }
+            } else if current_length > min_words {
+                // Shortens the headline to a good end.
+                i = i.min(q);
+                while current_length > min_words {
+                    if !bad_end(i) {
+                        break;
+                    }
+                    if word(i).is_word() {
+                        current_length -= 1;
+                    }
+                    if word(i).is_interesting() {
+                        interesting -= 1;
+                    }
+                    end = i - 1;
+                    i -= 1;
+                }
+            }
+
+            // Prefers headlines with the whole cover, then the most interesting words, then
+            // a good end.
+            let includes_cover = begin <= p && end >= q;
+            if (includes_cover && !best_cover)
+                || (includes_cover == best_cover
+                    && (interesting > best_length
+                        || (interesting == best_length && !bad_end(end) && bad_end(best_end))))
+            {
+                best_cover = includes_cover;
+                (best_begin, best_end, best_length) = (begin, end, interesting);
+            }
+            start = p as usize + 1;
+        }
+
+        if best_length < 0 {
+            // Shows the start of the document when nothing matches.
+            let mut current_length = 0;
+            best_begin = 0;
+            best_end = -1;
+            let mut i = 0;
+            while i < len && current_length < min_words {
+                if words[i as usize].is_word() {
+                    current_length += 1;
+                }
+                best_end = i;
+                i += 1;
+            }
+        }
+    } else {
+        best_begin = 0;
+        best_end = len - 1;
+    }
+
+    mark_fragment(words, options.highlight_all, best_begin as usize, best_end);
+}
+
+/// A fragment of a cover, as `CoverPos`.
+struct Fragment {
+    start: usize,
+    end: usize,
+    interesting: i32,
+    length: i32,
+    chosen: bool,
+    excluded: bool,
+}
+
+/// Chooses the parts of the document to show, when there are several fragments, as
+/// `mark_hl_fragments`.
+fn mark_headline_fragments(
+    words: &mut [HeadlineWord],
+    query: &TsQuery,
+    options: &HeadlineOptions,
+    max_cover: usize,
+) {
+    let max_words = options.max_words;
+    let short_word = options.short_word;
+    let mut fragments: Vec<Fragment> = vec![];
+
+    let mut start = 0;
+    while let Some((p, q)) = headline_cover(words, query, max_cover, start) {
+        // Splits the cover in fragments of at most `max_words` words, each of which starts and
+        // ends with a word of the query.
+        let mut first = p;
+        while first <= q {
+            let mut end = q;
+            if let Some(interesting) = (first..=end).find(|&i| words[i].is_interesting()) {
+                first = interesting;
+            } else {
+                first = end;
+            }
+            let mut length = 0;
+            let mut interesting = 0;
+            let mut i = first;
+            while i <= end && length < max_words {
+                if words[i].is_word() {
+                    length += 1;
+                }
+                if words[i].is_interesting() {
+                    interesting += 1;
+                }
+                i += 1;
+            }
+            if end > i {
+                end = i;
+                let mut j = end;
+                loop {
+                    end = j;
+                    if words[j].is_interesting() {
+                        break;
+                    }
+                    if words[j].is_word() {
+                        length -= 1;
+                    }
+                    if j == first {
+                        break;
+                    }
+                    j -= 1;
+                }
+            }
+            fragments.push(Fragment {
+                start: first,
+                end,
+                interesting,
+                length,
+                chosen: false,
+                excluded: false,
+            });
+            first = end + 1;
+        }
+        start = p + 1;
+    }
+
+    let mut marked = 0;
+    for _ in 0..options.max_fragments {
+        let mut best: Option<usize> = None;
+        for (index, fragment) in fragments.iter().enumerate() {
+            if fragment.chosen || fragment.excluded {
+                continue;
+            }
+            let better = match best {
+                None => fragment.interesting > 0,
+                Some(best) => {
+                    let best = &fragments[best];
+                    best.interesting < fragment.interesting
+                        || (best.interesting == fragment.interesting
+                            && best.length > fragment.length)
+                }
+            };
+            if better {
+                best = Some(index);
+            }
+        }
+        let Some(best) = best else {
+            break;
+        };
+        fragments[best].chosen = true;
+        let mut start = fragments[best].start;
+        let mut end = fragments[best].end;
+        let mut length = fragments[best].length;
+        if length < max_words {
+            // Stretches the fragment on both sides, up to the words already shown.
+            let max_stretch = (max_words - length) / 2;
+            let mut stretch = 0;
+            let mut marker = start;
+            let mut i = start as isize - 1;
+            while i >= 0 && stretch < max_stretch && !words[i as usize].shown {
+                if words[i as usize].is_word() {
+                    length += 1;
+                    stretch += 1;
+                }
+                marker = i as usize;
+                i -= 1;
+            }
+            let mut i = marker;
+            while i < start && words[i].is_bad_end(short_word) {
+                if words[i].is_word() {
+                    length -= 1;
+                }
+                i += 1;
+            }
+            start = i;
+
+            let mut marker = end;
+            let mut i = end + 1;
+            while i < words.len() && length < max_words && !words[i].shown {
+                if words[i].is_word() {
+                    length += 1;
+                }
+                marker = i;
+                i += 1;
+            }
+            let mut i = marker;
+            while i > end && words[i].is_bad_end(short_word) {
+                if words[i].is_word() {
+                    length -= 1;
+                }
+                i -= 1;
+            }
+            end = i;
+        }
+        fragments[best].start = start;
+        fragments[best].end = end;
+        fragments[best].length = length;
+        mark_fragment(words, options.highlight_all, start, end as isize);
+        marked += 1;
+        for (index, fragment) in fragments.iter_mut().enumerate() {
+            if index != best
+                && ((fragment.start >= start && fragment.start <= end)
+                    || (fragment.end >= start && fragment.end <= end)
+                    || (fragment.start < start && fragment.end > end))
+            {
+                fragment.excluded = true;
+            }
+        }
+    }
+
+    if marked == 0 {
+        let mut length = 0;
+        let mut end: isize = -1;
+        let mut i = 0;
+        while i < words.len() && length < options.min_words {
+            if words[i].is_word() {
+                length += 1;
+            }
+            end = i as isize;
+            i += 1;
+        }
+        mark_fragment(words, options.highlight_all, 0, end);
+    }
+}
+
+/// Returns the part of a document that best matches a query, with the words of the query
+/// highlighted, as `ts_headline`.
+pub(crate) fn headline(
+    config: Config,
+    text: &str,
+    query: &TsQuery,
+    options: &str,
+) -> Result<String> {
+    let options = HeadlineOptions::parse(options)?;
+    let mut words = headline_words(config, text, query)?;
+
+    let mut max_cover = (options.max_words * 10).max(100) as usize;
+    if options.max_fragments > 0 {
+        max_cover *= options.max_fragments as usize;
+        mark_headline_fragments(&mut words, query, &options, max_cover);
+    } else {
+        mark_headline_words(&mut words, query, &options, max_cover);
+    }
+
+    let mut headline = String::new();
+    let mut in_fragment = false;
+    let mut fragments = 0;
+    for word in words.iter().filter(|word| !word.repeated) {
+        if !word.shown {
+            in_fragment = false;
+            continue;
+        }
+        if !in_fragment {
+            in_fragment = true;
+            fragments += 1;
+            if fragments > 1 {
+                headline.push_str(&options.fragment_delimiter);
+            }
+        }
+        if word.replace {
+            headline.push(' ');
+        } else if !word.skip {
+            if word.selected {
+                headline.push_str(&options.start_sel);
+            }
+            headline.push_str(&word.text);
+            if word.selected {
+                headline.push_str(&options.stop_sel);
+            }
+        }
+    }
+    Ok(headline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCUMENT: &str = "The most common type of search is to find all documents containing given query terms and return them in order of their similarity to the query.";
+
+    fn vector(text: &str) -> String {
+        format_tsvector(&parse_tsvector(text).unwrap())
+    }
+
+    fn query(text: &str) -> String {
+        format_tsquery(&parse_tsquery(text).unwrap())
+    }
+
+    #[test]
+    fn test_to_tsvector() -> Result<()> {
+        let text = "The quick brown foxes jumped over the lazy dogs";
+        assert_eq!(
+            format_tsvector(&to_tsvector(Config::English, text)),
+            "'brown':3 'dog':9 'fox':4 'jump':5 'lazi':8 'quick':2"
+        );
+        assert_eq!(
+            format_tsvector(&to_tsvector(Config::Simple, text)),
+            "'brown':3 'dogs':9 'foxes':4 'jumped':5 'lazy':8 'over':6 'quick':2 'the':1,7"
+        );
+        assert_eq!(
+            format_tsvector(&to_tsvector(
+                Config::English,
+                "foo-bar baz@example.com http://www.example.com/path 3.14 <b>bold</b>"
+            )),
+            "'/path':7 '3.14':8 'bar':3 'baz@example.com':4 'bold':9 'foo':2 'foo-bar':1 'www.example.com':6 'www.example.com/path':5"
+        );
+        assert_eq!(
+            format_tsvector(&to_tsvector(Config::English, "the and of")),
+            ""
+        );
+
+        assert_eq!(Config::from_name("pg_catalog.English")?, Config::English);
+        assert_eq!(Config::from_name("simple")?, Config::Simple);
+        assert_eq!(
+            Config::from_name("german").unwrap_err().to_string(),
+            "text search configuration \"german\" does not exist"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_tsvector() {
+        assert_eq!(vector("a fat cat"), "'a' 'cat' 'fat'");
+        assert_eq!(
+            vector("'a' 'fat':1,2A 'cat':3B,1C"),
+            "'a' 'cat':1C,3B 'fat':1,2A"
+        );
+        assert_eq!(vector("fat:2,4,1 cat:3A,3B"), "'cat':3A 'fat':1,2,4");
+        assert_eq!(vector("b:3 a:2 c a:1"), "'a':1,2 'b':3 'c'");
+        assert_eq!(
+            vector(r"'it''s' 'back\\slash' 'sp ace'"),
+            r"'back\\slash' 'it''s' 'sp ace'"
+        );
+        assert_eq!(vector("z:20000"), "'z':16383");
+        assert_eq!(vector(" "), "");
+
+        let error = |text: &str| parse_tsvector(text).unwrap_err().to_string();
+        assert_eq!(error("a:0"), "wrong position info in tsvector: \"a:0\"");
+        assert_eq!(error("a:1AB"), "syntax error in tsvector: \"a:1AB\"");
+        assert_eq!(error("'a"), "syntax error in tsvector: \"'a\"");
+        assert_eq!(error(r"a\"), r#"there is no escaped character: "a\""#);
+    }
+
+    #[test]
+    fn test_parse_tsquery() {
+        assert_eq!(query("fat & (rat | cat)"), "'fat' & ( 'rat' | 'cat' )");
+        assert_eq!(query("!fat & !(rat | cat)"), "!'fat' & !( 'rat' | 'cat' )");
+        assert_eq!(query("a | b & c | d"), "'a' | 'b' & 'c' | 'd'");
+        assert_eq!(query("(fat <-> rat) <2> cat"), "'fat' <-> 'rat' <2> 'cat'");
+        assert_eq!(
+            query("fat <-> (rat <-> cat)"),
+            "'fat' <-> ( 'rat' <-> 'cat' )"
+        );
+        assert_eq!(
+            query("(a & b) <-> (c | d)"),
+            "( 'a' & 'b' ) <-> ( 'c' | 'd' )"
+        );
+        assert_eq!(query("fat:AB* & 'sp ace':c"), "'fat':*AB & 'sp ace':C");
+        assert_eq!(query(""), "");
+
+        let error = |text: &str| parse_tsquery(text).unwrap_err().to_string();
+        assert_eq!(error("a b"), "syntax error in tsquery: \"a b\"");
+        assert_eq!(error("(a"), "syntax error in tsquery: \"(a\"");
+        assert_eq!(error("a &"), "no operand in tsquery: \"a &\"");
+        assert_eq!(
+            error("a <16385> b"),
+            "distance in phrase operator must be an integer value between zero and 16384 inclusive"
+        );
+    }
+
+    #[test]
+    fn test_to_tsquery() -> Result<()> {
+        let english = |function: fn(Config, &str) -> Result<TsQuery>, text: &str| {
+            function(Config::English, text).map(|query| format_tsquery(&query))
+        };
+        assert_eq!(english(to_tsquery, "fat & rats")?, "'fat' & 'rat'");
+        assert_eq!(
+            english(to_tsquery, "supernovae:ab* & stars")?,
+            "'supernova':*AB & 'star'"
+        );
+        assert_eq!(
+            english(to_tsquery, "'fat rats' <-> cat")?,
+            "'fat' <-> 'rat' <-> 'cat'"
+        );
+        assert_eq!(
+            english(to_tsquery, "cat <-> the <-> dog")?,
+            "'cat' <2> 'dog'"
+        );
+        assert_eq!(english(to_tsquery, "the & cat")?, "'cat'");
+        assert_eq!(english(to_tsquery, "the")?, "");
+        assert_eq!(english(plainto_tsquery, "The Fat Rats")?, "'fat' & 'rat'");
+        assert_eq!(
+            english(phraseto_tsquery, "The Fat Rats")?,
+            "'fat' <-> 'rat'"
+        );
+        assert_eq!(
+            english(phraseto_tsquery, "the cats of the world")?,
+            "'cat' <3> 'world'"
+        );
+        assert_eq!(
+            english(websearch_to_tsquery, "\"supernovae stars\" -crab")?,
+            "'supernova' <-> 'star' & !'crab'"
+        );
+        assert_eq!(
+            english(websearch_to_tsquery, "\"sad cat\" or \"fat rat\"")?,
+            "'sad' <-> 'cat' | 'fat' <-> 'rat'"
+        );
+        assert_eq!(
+            english(websearch_to_tsquery, "signal -\"segmentation fault\"")?,
+            "'signal' & !( 'segment' <-> 'fault' )"
+        );
+        assert_eq!(
+            english(websearch_to_tsquery, "\"\"\" )( dummy \\\\ query <->")?,
+            "'dummi' <-> 'queri'"
+        );
+        assert_eq!(english(websearch_to_tsquery, "cat or")?, "'cat'");
+        assert_eq!(
+            format_tsquery(&to_tsquery(Config::Simple, "The & Fat")?),
+            "'the' & 'fat'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_matches() {
+        let matches = |vector: &str, query: &str| {
+            matches(
+                &parse_tsvector(vector).unwrap(),
+                &parse_tsquery(query).unwrap(),
+            )
+        };
+        let fat_cat = "'a':1 'fat':2 'cat':3";
+        assert!(matches(fat_cat, "fat & cat"));
+        assert!(!matches(fat_cat, "cat & rat"));
+        assert!(matches(fat_cat, "fat <-> cat"));
+        assert!(!matches(fat_cat, "cat <-> fat"));
+        assert!(matches(fat_cat, "a <2> cat"));
+        assert!(matches(fat_cat, "a <-> !cat"));
+        assert!(!matches(fat_cat, "a <-> !fat"));
+        assert!(matches(fat_cat, "(a | fat) <-> cat"));
+        assert!(matches(fat_cat, "f:* <-> c:*"));
+        assert!(!matches(fat_cat, "fat:A"));
+        assert!(matches("'fat':2A 'cat':3", "fat:A & cat:D"));
+        assert!(matches("'fat':2,5 'cat':3,7", "fat <2> cat"));
+        // Without positions, phrases cannot match.
+        assert!(matches("a fat cat", "fat & !dog"));
+        assert!(!matches("a fat cat", "fat <-> cat"));
+        assert!(!matches(fat_cat, ""));
+    }
+
+    #[test]
+    fn test_rank() -> Result<()> {
+        let vector = parse_tsvector("'a':1A 'fat':2B,4C 'cat':5D,8A 'rat':6,9B 'sat':10")?;
+        let rank = |query: &str, normalization: i32| {
+            rank(
+                &DEFAULT_WEIGHTS,
+                &vector,
+                &parse_tsquery(query).unwrap(),
+                normalization,
+            )
+        };
+        assert_eq!(rank("fat & cat", 0), 0.8169424);
+        assert_eq!(rank("fat | cat", 0), 0.4711435);
+        assert_eq!(rank("fat & cat", 1), 0.25771663);
+        assert_eq!(rank("fat & cat", 32), 0.4496248);
+        assert_eq!(rank("dog", 0), 0.0);
+
+        let rank_cd = |query: &str, normalization: i32| {
+            rank_cover_density(
+                &DEFAULT_WEIGHTS,
+                &vector,
+                &parse_tsquery(query).unwrap(),
+                normalization,
+            )
+        };
+        assert_eq!(rank_cd("fat & cat", 0), 0.13333334);
+        assert_eq!(rank_cd("fat & cat", 2), 0.016666668);
+        assert_eq!(rank_cd("fat <-> rat", 0), 0.0);
+
+        assert_eq!(rank_weights(None)?, DEFAULT_WEIGHTS);
+        assert_eq!(
+            rank_weights(Some(&[Some(0.5), Some(-1.0), Some(0.0), Some(1.0)]))?,
+            [0.5, 0.2, 0.0, 1.0]
+        );
+        let error = |weights: &[Option<f32>]| rank_weights(Some(weights)).unwrap_err().to_string();
+        assert_eq!(error(&[Some(0.1)]), "array of weight is too short");
+        assert_eq!(
+            error(&[Some(0.1), None, Some(0.1), Some(0.1)]),
+            "array of weight must not contain nulls"
+        );
+        assert_eq!(
+            error(&[Some(0.1), Some(2.0), Some(0.1), Some(0.1)]),
+            "weight out of range"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_headline() -> Result<()> {
+        let headline = |query: &str, options: &str| {
+            let query = to_tsquery(Config::English, query)?;
+            headline(Config::English, DOCUMENT, &query, options)
+        };
+        assert_eq!(
+            headline("query & similarity", "")?,
+            "containing given <b>query</b> terms and return them in order of their <b>similarity</b> to the <b>query</b>."
+        );
+        assert_eq!(
+            headline(
+                "query & similarity",
+                "MaxWords=6, MinWords=3, StartSel=<em>, StopSel=</em>"
+            )?,
+            "<em>similarity</em> to the <em>query</em>"
+        );
+        assert_eq!(
+            headline("search | order", "MaxFragments=2, MaxWords=5, MinWords=2")?,
+            "type of <b>search</b> ... them in <b>order</b> of their"
+        );
+        assert_eq!(
+            headline("document:* <-> contain", "MaxWords=10, MinWords=5")?,
+            "<b>documents</b> <b>containing</b> given query terms"
+        );
+        assert_eq!(headline("dog", "MaxWords=4, MinWords=2")?, "The most");
+
+        let html = "<p>Postgres <b>full text</b> search</p>";
+        let query = parse_tsquery("search")?;
+        assert_eq!(
+            super::headline(Config::English, html, &query, "")?,
+            " Postgres  full text  <b>search</b> "
+        );
+        assert_eq!(
+            super::headline(Config::English, html, &query, "HighlightAll=true")?,
+            "<p>Postgres <b>full text</b> <b>search</b></p>"
+        );
+
+        let error = |options: &str| headline("search", options).unwrap_err().to_string();
+        assert_eq!(
+            error("MinWords=40"),
+            "MinWords should be less than MaxWords"
+        );
+        assert_eq!(error("MinWords=0"), "MinWords should be positive");
+        assert_eq!(error("Foo=1"), "unrecognized headline parameter: \"Foo\"");
+        assert_eq!(
+            error("MaxWords"),
+            "invalid parameter list format: \"MaxWords\""
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,1250 @@
+//! Postgres' default text search parser, following `wparser_def.c`: a state machine that splits
+//! text into words, numbers, hosts, URLs, tags and the blanks between them, which is what
+//! `to_tsvector` and `ts_headline` see of a document.
+//!
+//! Each state has a list of actions, tried in order. The first whose character class matches
+//! the current character is taken: it moves to the next character, pushes the current state to
+//! try a longer token, pops back to the pushed state to try its next action, or ends the token.
+
+use State::*;
+use TokenType::*;
+
+/// The token types of the default parser, in `ts_token_type` order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum TokenType {
+    AsciiWord = 1,
+    Word,
+    NumWord,
+    Email,
+    Url,
+    Host,
+    Sfloat,
+    Version,
+    HwordNumpart,
+    HwordPart,
+    HwordAsciipart,
+    Blank,
+    Tag,
+    Protocol,
+    NumHword,
+    AsciiHword,
+    Hword,
+    UrlPath,
+    File,
+    Float,
+    Int,
+    Uint,
+    Entity,
+}
+
+/// Splits a text into its tokens, each with its type. The parts of a compound token, such as the
+/// words of a hyphenated word or the host of a URL, follow it.
+pub(crate) fn tokenize(text: &str) -> Vec<(TokenType, String)> {
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut parser = Parser::new(&chars);
+    let mut tokens = vec![];
+    while let Some(token_type) = parser.next_token() {
+        let token = &chars[parser.token..parser.token + parser.token_len];
+        tokens.push((token_type, token.iter().collect()));
+    }
+    tokens
+}
+
+/// What an action checks of the current character, as the `p_is*` functions do.
+#[derive(Debug, Clone, Copy)]
+enum Predicate {
+    IsEof,
+    IsEqC(char),
+    IsIgnore,
+    IsAsciiLetter,
+    IsAlpha,
+    IsDigit,
+    IsAlnum,
+    IsNotAlnum,
+    IsSpace,
+    IsHexDigit,
+    IsSpecial,
+    IsStopHost,
+    IsHost,
+    IsUrlChar,
+    IsUrlPath,
+    /// Matches anything, and ends the list of actions of a state.
+    Any,
+}
+
+use Predicate::*;
+
+/// The handlers some actions call before they are carried out.
+#[derive(Debug, Clone, Copy)]
+enum Special {
+    /// Notes the start and the end of `<script>` and `<style>`, whose contents are ignored.
+    Tags,
+    /// Goes back to the start of a URL, to return its host and its path after it.
+    FullUrl,
+    /// Goes back to the start of a hyphenated word, to return its parts after it.
+    Hyphen,
+    /// Goes back to the start of a signed number followed by a version.
+    VerVersion,
+}
+
+const NEXT: u8 = 0;
+const BINGO: u8 = 1 << 1;
+const POP: u8 = 1 << 2;
+const PUSH: u8 = 1 << 3;
+const RERUN: u8 = 1 << 4;
+const CLEAR: u8 = 1 << 5;
+const MERGE: u8 = 1 << 6;
+const CLRALL: u8 = 1 << 7;
+
+#[derive(Debug, Clone, Copy)]
+struct Action {
+    predicate: Predicate,
+    flags: u8,
+    to_state: Option<State>,
+    token_type: Option<TokenType>,
+    special: Option<Special>,
+}
+
+impl Action {
+    const fn new(
+        predicate: Predicate,
+        flags: u8,
+        to_state: Option<State>,
+        token_type: Option<TokenType>,
+        special: Option<Special>,
+    ) -> Self {
+        Self {
+            predicate,
+            flags,
+            to_state,
+            token_type,
+            special,
+        }
+    }
+}
+
+/// Where the parser is: the current character, the token so far, and the state, as
+/// `TParserPosition`. `pushed_at` is the action that pushed a position over this one.
+#[derive(Debug, Clone, Copy)]
+struct Position {
+    pos: usize,
+    char_len: usize,
+    token_len: usize,
+    state: State,
+    pushed_at: Option<usize>,
+}
+
+struct Parser<'a> {
+    chars: &'a [char],
+    /// The current position is the last one.
+    positions: Vec<Position>,
+    ignore: bool,
+    want_host: bool,
+    token: usize,
+    token_len: usize,
+    token_type: Option<TokenType>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(chars: &'a [char]) -> Self {
+        Self {
+            chars,
+            positions: vec![Position {
+                pos: 0,
+                char_len: 0,
+                token_len: 0,
+                state: Base,
+                pushed_at: None,
+            }],
+            ignore: false,
+            want_host: false,
+            token: 0,
+            token_len: 0,
+            token_type: None,
+        }
+    }
+
+    /// A parser of the rest of the text, to check whether a host or a URL path starts at the
+    /// current character.
+    fn rest(&self) -> Parser<'a> {
+        Parser::new(&self.chars[self.current().pos..])
+    }
+
+    fn current(&self) -> &Position {
+        self.positions.last().expect("a position")
+    }
+
+    fn current_mut(&mut self) -> &mut Position {
+        self.positions.last_mut().expect("a position")
+    }
+
+    /// Finds the next token, as `TParserGet`, and returns its type.
+    fn next_token(&mut self) -> Option<TokenType> {
+        let len = self.chars.len();
+        if self.current().pos >= len {
+            return None;
+        }
+        self.token = self.current().pos;
+        self.current_mut().pushed_at = None;
+
+        let mut action = None;
+        while self.current().pos <= len {
+            let current = self.current_mut();
+            current.char_len = usize::from(current.pos < len);
+            let state = current.state;
+            // After a pop, the actions of the state go on after the one that pushed.
+            let mut index = current.pushed_at.take().map_or(0, |index| index + 1);
+            let actions = ACTIONS[state as usize];
+            while !matches!(actions[index].predicate, Any) && !self.check(actions[index].predicate)
+            {
+                index += 1;
+            }
+            let item = actions[index];
+            action = Some(item);
+
+            match item.special {
+                Some(Special::Tags) => self.special_tags(),
+                Some(Special::FullUrl) => {
+                    self.want_host = true;
+                    self.rewind();
+                }
+                Some(Special::Hyphen) => self.rewind(),
+                Some(Special::VerVersion) => {
+                    self.rewind();
+                    self.current_mut().token_len = 0;
+                }
+                None => {}
+            }
+
+            if item.flags & BINGO != 0 {
+                let current = self.current_mut();
+                let token_len = current.token_len;
+                current.token_len = 0;
+                self.token_len = token_len;
+                self.token_type = item.token_type;
+            }
+
+            if item.flags & POP != 0 {
+                self.positions.pop();
+            } else if item.flags & PUSH != 0 {
+                let current = self.current_mut();
+                current.pushed_at = Some(index);
+                let mut pushed = *current;
+                pushed.pushed_at = None;
+                self.positions.push(pushed);
+            } else if item.flags & CLEAR != 0 {
+                let below = self.positions.len() - 2;
+                self.positions.remove(below);
+            } else if item.flags & CLRALL != 0 {
+                let current = self.positions.pop().expect("a position");
+                self.positions = vec![current];
+            } else if item.flags & MERGE != 0 {
+                let merged = self.positions.pop().expect("a position");
+                let current = self.current_mut();
+                current.pos = merged.pos;
+                current.char_len = merged.char_len;
+                current.token_len = merged.token_len;
+            }
+
+            if let Some(state) = item.to_state {
+                self.current_mut().state = state;
+            }
+
+            if item.flags & BINGO != 0 || (self.current().pos >= len && item.flags & RERUN == 0) {
+                break;
+            }
+            if item.flags & (RERUN | POP) != 0 {
+                continue;
+            }
+
+            let current = self.current_mut();
+            if current.char_len > 0 {
+                current.pos += current.char_len;
+                current.token_len += current.char_len;
+            }
+        }
+
+        action
+            .filter(|action| action.flags & BINGO != 0)
+            .and(self.token_type)
+    }
+
+    /// Goes back to the start of the token so far.
+    fn rewind(&mut self) {
+        let current = self.current_mut();
+        current.pos -= current.token_len;
+    }
+
+    fn character(&self) -> Option<char> {
+        let current = self.current();
+        if current.char_len == 0 {
+            None
+        } else {
+            self.chars.get(current.pos).copied()
+        }
+    }
+
+    fn check(&mut self, predicate: Predicate) -> bool {
+        let character = self.character();
+        let is = |class: fn(char) -> bool| character.is_some_and(class);
+        match predicate {
+            IsEof => character.is_none(),
+            IsEqC(expected) => character == Some(expected),
+            IsIgnore => self.ignore,
+            IsAsciiLetter => is(|c| c.is_ascii_alphabetic()),
+            IsAlpha => is(is_alpha),
+            IsDigit => is(|c| c.is_ascii_digit()),
+            IsAlnum => is(|c| is_alpha(c) || c.is_ascii_digit()),
+            IsNotAlnum => !is(|c| is_alpha(c) || c.is_ascii_digit()),
+            IsSpace => is(is_space),
+            IsHexDigit => is(|c| c.is_ascii_hexdigit()),
+            IsSpecial => is(is_special),
+            IsStopHost => std::mem::take(&mut self.want_host),
+            IsHost => {
+                let mut rest = self.rest();
+                rest.want_host = true;
+                let found = rest.next_token() == Some(Host);
+                self.take_from(found.then_some(rest))
+            }
+            IsUrlChar => is(is_url_char),
+            IsUrlPath => {
+                let mut rest = self.rest();
+                let mut first = *rest.current();
+                first.state = InUrlPathFirst;
+                rest.positions.push(first);
+                let found = rest.next_token() == Some(UrlPath);
+                self.take_from(found.then_some(rest))
+            }
+            Any => true,
+        }
+    }
+
+    /// Adds the token that a parser of the rest of the text found to the token so far.
+    fn take_from(&mut self, rest: Option<Parser>) -> bool {
+        let Some(rest) = rest else {
+            return false;
+        };
+        let token_len = rest.token_len;
+        let char_len = rest.current().char_len;
+        let current = self.current_mut();
+        current.pos += token_len;
+        current.token_len += token_len;
+        current.char_len = char_len;
+        true
+    }
+
+    fn special_tags(&mut self) {
+        let token = self.chars[self.token..self.token + self.current().token_len]
+            .iter()
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match token.as_str() {
+            "</script" | "</style" => self.ignore = false,
+            "<script" | "<style" => self.ignore = true,
+            _ => {}
+        }
+    }
+}
+
+/// Whether a character is a letter, as `iswalpha` in a UTF-8 locale.
+pub(crate) fn is_alpha(character: char) -> bool {
+    character.is_alphabetic()
+}
+
+/// Whether a character is a space, as `iswspace`, which does not count the no-break spaces.
+pub(crate) fn is_space(character: char) -> bool {
+    character.is_whitespace() && !matches!(character, '\u{a0}' | '\u{2007}' | '\u{202f}')
+}
+
+/// Whether a character is a combining mark, or another character of no width, which Postgres
+/// keeps in the word it follows.
+fn is_special(character: char) -> bool {
+    matches!(
+        u32::from(character),
+        0x0300..=0x036f
+            | 0x0483..=0x0489
+            | 0x0591..=0x05bd
+            | 0x0610..=0x061a
+            | 0x064b..=0x065f
+            | 0x0900..=0x0903
+            | 0x093a..=0x094f
+            | 0x1ab0..=0x1aff
+            | 0x1dc0..=0x1dff
+            | 0x200b..=0x200f
+            | 0x20d0..=0x20ff
+            | 0xfe00..=0xfe0f
+            | 0xfe20..=0xfe2f
+    )
+}
+
+/// Whether a character can be in a URL, following RFC 3986.
+fn is_url_char(character: char) -> bool {
+    character.is_ascii()
+        && character > ' '
+        && character != '\u{7f}'
+        && !matches!(
+            character,
+            '"' | '<' | '>' | '\\' | '^' | '`' | '{' | '|' | '}'
+        )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Base,
+    InNumWord,
+    InAsciiWord,
+    InWord,
+    InUnsignedInt,
+    InSignedIntFirst,
+    InSignedInt,
+    InSpace,
+    InUDecimalFirst,
+    InUDecimal,
+    InDecimalFirst,
+    InDecimal,
+    InVerVersion,
+    InSVerVersion,
+    InVersionFirst,
+    InVersion,
+    InMantissaFirst,
+    InMantissaSign,
+    InMantissa,
+    InXmlEntityFirst,
+    InXmlEntity,
+    InXmlEntityNumFirst,
+    InXmlEntityNum,
+    InXmlEntityHexNumFirst,
+    InXmlEntityHexNum,
+    InXmlEntityEnd,
+    InTagFirst,
+    InXmlBegin,
+    InTagCloseFirst,
+    InTagName,
+    InTagBeginEnd,
+    InTag,
+    InTagEscapeK,
+    InTagEscapeKK,
+    InTagBackSlashed,
+    InTagEnd,
+    InCommentFirst,
+    InCommentLast,
+    InComment,
+    InCloseCommentFirst,
+    InCloseCommentLast,
+    InCommentEnd,
+    InHostFirstDomain,
+    InHostDomainSecond,
+    InHostDomain,
+    InPortFirst,
+    InPort,
+    InHostFirstAN,
+    InHost,
+    InEmail,
+    InFileFirst,
+    InFileTwiddle,
+    InPathFirst,
+    InPathFirstFirst,
+    InPathSecond,
+    InFile,
+    InFileNext,
+    InUrlPathFirst,
+    InUrlPathStart,
+    InUrlPath,
+    InFullUrl,
+    InProtocolFirst,
+    InProtocolSecond,
+    InProtocolEnd,
+    InHyphenAsciiWordFirst,
+    InHyphenAsciiWord,
+    InHyphenWordFirst,
+    InHyphenWord,
+    InHyphenNumWordFirst,
+    InHyphenNumWord,
+    InHyphenDigitLookahead,
+    InParseHyphen,
+    InParseHyphenHyphen,
+    InHyphenWordPart,
+    InHyphenAsciiWordPart,
+    InHyphenNumWordPart,
+    InHyphenUnsignedInt,
+}
+
+static ACTIONS: [&[Action]; 77] = [
+    // Base
+    &[
+        Action::new(IsEof, NEXT, None, None, None),
+        Action::new(IsEqC('<'), PUSH, Some(InTagFirst), None, None),
+        Action::new(IsIgnore, NEXT, Some(InSpace), None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InAsciiWord), None, None),
+        Action::new(IsAlpha, NEXT, Some(InWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InUnsignedInt), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InSignedIntFirst), None, None),
+        Action::new(IsEqC('+'), PUSH, Some(InSignedIntFirst), None, None),
+        Action::new(IsEqC('&'), PUSH, Some(InXmlEntityFirst), None, None),
+        Action::new(IsEqC('~'), PUSH, Some(InFileTwiddle), None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InPathFirstFirst), None, None),
+        Action::new(Any, NEXT, Some(InSpace), None, None),
+    ],
+    // InNumWord
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(NumWord), None),
+        Action::new(IsAlnum, NEXT, Some(InNumWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InNumWord), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InFileNext), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenNumWordFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(NumWord), None),
+    ],
+    // InAsciiWord
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(AsciiWord), None),
+        Action::new(IsAsciiLetter, NEXT, None, None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InHostFirstDomain), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InFileNext), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenAsciiWordFirst), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(IsEqC(':'), PUSH, Some(InProtocolFirst), None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(IsDigit, PUSH, Some(InHost), None, None),
+        Action::new(IsDigit, NEXT, Some(InNumWord), None, None),
+        Action::new(IsAlpha, NEXT, Some(InWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InWord), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(AsciiWord), None),
+    ],
+    // InWord
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Word), None),
+        Action::new(IsAlpha, NEXT, None, None, None),
+        Action::new(IsSpecial, NEXT, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InNumWord), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenWordFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Word), None),
+    ],
+    // InUnsignedInt
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Uint), None),
+        Action::new(IsDigit, NEXT, None, None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InHostFirstDomain), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InUDecimalFirst), None, None),
+        Action::new(IsEqC('e'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(IsEqC('E'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(IsAsciiLetter, PUSH, Some(InHost), None, None),
+        Action::new(IsAlpha, NEXT, Some(InNumWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InNumWord), None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Uint), None),
+    ],
+    // InSignedIntFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InSignedInt), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InSignedInt
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Int), None),
+        Action::new(IsDigit, NEXT, None, None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InDecimalFirst), None, None),
+        Action::new(IsEqC('e'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(IsEqC('E'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Int), None),
+    ],
+    // InSpace
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsEqC('<'), BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsIgnore, NEXT, None, None, None),
+        Action::new(IsEqC('-'), BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsEqC('+'), BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsEqC('&'), BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsEqC('/'), BINGO, Some(Base), Some(Blank), None),
+        Action::new(IsNotAlnum, NEXT, Some(InSpace), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Blank), None),
+    ],
+    // InUDecimalFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InUDecimal), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InUDecimal
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Float), None),
+        Action::new(IsDigit, NEXT, Some(InUDecimal), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InVersionFirst), None, None),
+        Action::new(IsEqC('e'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(IsEqC('E'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Float), None),
+    ],
+    // InDecimalFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InDecimal), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InDecimal
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Float), None),
+        Action::new(IsDigit, NEXT, Some(InDecimal), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InVerVersion), None, None),
+        Action::new(IsEqC('e'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(IsEqC('E'), PUSH, Some(InMantissaFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Float), None),
+    ],
+    // InVerVersion
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(
+            IsDigit,
+            RERUN,
+            Some(InSVerVersion),
+            None,
+            Some(Special::VerVersion),
+        ),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InSVerVersion
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(
+            IsDigit,
+            BINGO | CLRALL,
+            Some(InUnsignedInt),
+            Some(Blank),
+            None,
+        ),
+        Action::new(Any, NEXT, None, None, None),
+    ],
+    // InVersionFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InVersion), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InVersion
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Version), None),
+        Action::new(IsDigit, NEXT, Some(InVersion), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InVersionFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Version), None),
+    ],
+    // InMantissaFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InMantissa), None, None),
+        Action::new(IsEqC('+'), NEXT, Some(InMantissaSign), None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InMantissaSign), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InMantissaSign
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, CLEAR, Some(InMantissa), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InMantissa
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(Sfloat), None),
+        Action::new(IsDigit, NEXT, Some(InMantissa), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(Sfloat), None),
+    ],
+    // InXmlEntityFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('#'), NEXT, Some(InXmlEntityNumFirst), None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC(':'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntity
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAlnum, NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC(':'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC('.'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InXmlEntity), None, None),
+        Action::new(IsEqC(';'), NEXT, Some(InXmlEntityEnd), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntityNumFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('x'), NEXT, Some(InXmlEntityHexNumFirst), None, None),
+        Action::new(IsEqC('X'), NEXT, Some(InXmlEntityHexNumFirst), None, None),
+        Action::new(IsDigit, NEXT, Some(InXmlEntityNum), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntityNum
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InXmlEntityNum), None, None),
+        Action::new(IsEqC(';'), NEXT, Some(InXmlEntityEnd), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntityHexNumFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsHexDigit, NEXT, Some(InXmlEntityHexNum), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntityHexNum
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsHexDigit, NEXT, Some(InXmlEntityHexNum), None, None),
+        Action::new(IsEqC(';'), NEXT, Some(InXmlEntityEnd), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlEntityEnd
+    &[Action::new(
+        Any,
+        BINGO | CLEAR,
+        Some(Base),
+        Some(Entity),
+        None,
+    )],
+    // InTagFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InTagCloseFirst), None, None),
+        Action::new(IsEqC('!'), PUSH, Some(InCommentFirst), None, None),
+        Action::new(IsEqC('?'), PUSH, Some(InXmlBegin), None, None),
+        Action::new(IsAsciiLetter, PUSH, Some(InTagName), None, None),
+        Action::new(IsEqC(':'), PUSH, Some(InTagName), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InTagName), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InXmlBegin
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('x'), NEXT, Some(InTag), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InTagCloseFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InTagName), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InTagName
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InTagBeginEnd), None, None),
+        Action::new(IsEqC('>'), NEXT, Some(InTagEnd), None, Some(Special::Tags)),
+        Action::new(IsSpace, NEXT, Some(InTag), None, Some(Special::Tags)),
+        Action::new(IsAlnum, NEXT, None, None, None),
+        Action::new(IsEqC(':'), NEXT, None, None, None),
+        Action::new(IsEqC('_'), NEXT, None, None, None),
+        Action::new(IsEqC('.'), NEXT, None, None, None),
+        Action::new(IsEqC('-'), NEXT, None, None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InTagBeginEnd
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('>'), NEXT, Some(InTagEnd), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InTag
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('>'), NEXT, Some(InTagEnd), None, Some(Special::Tags)),
+        Action::new(IsEqC('\''), NEXT, Some(InTagEscapeK), None, None),
+        Action::new(IsEqC('"'), NEXT, Some(InTagEscapeKK), None, None),
+        Action::new(IsAsciiLetter, NEXT, None, None, None),
+        Action::new(IsDigit, NEXT, None, None, None),
+        Action::new(IsEqC('='), NEXT, None, None, None),
+        Action::new(IsEqC('-'), NEXT, None, None, None),
+        Action::new(IsEqC('_'), NEXT, None, None, None),
+        Action::new(IsEqC('#'), NEXT, None, None, None),
+        Action::new(IsEqC('/'), NEXT, None, None, None),
+        Action::new(IsEqC(':'), NEXT, None, None, None),
+        Action::new(IsEqC('.'), NEXT, None, None, None),
+        Action::new(IsEqC('&'), NEXT, None, None, None),
+        Action::new(IsEqC('?'), NEXT, None, None, None),
+        Action::new(IsEqC('%'), NEXT, None, None, None),
+        Action::new(IsEqC('~'), NEXT, None, None, None),
+        Action::new(IsSpace, NEXT, None, None, Some(Special::Tags)),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InTagEscapeK
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('\\'), PUSH, Some(InTagBackSlashed), None, None),
+        Action::new(IsEqC('\''), NEXT, Some(InTag), None, None),
+        Action::new(Any, NEXT, Some(InTagEscapeK), None, None),
+    ],
+    // InTagEscapeKK
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('\\'), PUSH, Some(InTagBackSlashed), None, None),
+        Action::new(IsEqC('"'), NEXT, Some(InTag), None, None),
+        Action::new(Any, NEXT, Some(InTagEscapeKK), None, None),
+    ],
+    // InTagBackSlashed
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(Any, MERGE, None, None, None),
+    ],
+    // InTagEnd
+    &[Action::new(
+        Any,
+        BINGO | CLRALL,
+        Some(Base),
+        Some(Tag),
+        None,
+    )],
+    // InCommentFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InCommentLast), None, None),
+        Action::new(IsEqC('D'), NEXT, Some(InTag), None, None),
+        Action::new(IsEqC('d'), NEXT, Some(InTag), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InCommentLast
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InComment), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InComment
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InCloseCommentFirst), None, None),
+        Action::new(Any, NEXT, None, None, None),
+    ],
+    // InCloseCommentFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InCloseCommentLast), None, None),
+        Action::new(Any, NEXT, Some(InComment), None, None),
+    ],
+    // InCloseCommentLast
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('-'), NEXT, None, None, None),
+        Action::new(IsEqC('>'), NEXT, Some(InCommentEnd), None, None),
+        Action::new(Any, NEXT, Some(InComment), None, None),
+    ],
+    // InCommentEnd
+    &[Action::new(
+        Any,
+        BINGO | CLRALL,
+        Some(Base),
+        Some(Tag),
+        None,
+    )],
+    // InHostFirstDomain
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHostDomainSecond), None, None),
+        Action::new(IsDigit, NEXT, Some(InHost), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHostDomainSecond
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHostDomain), None, None),
+        Action::new(IsDigit, PUSH, Some(InHost), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InHostFirstDomain), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHostDomain
+    &[
+        Action::new(IsEof, BINGO | CLRALL, Some(Base), Some(Host), None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHostDomain), None, None),
+        Action::new(IsDigit, PUSH, Some(InHost), None, None),
+        Action::new(IsEqC(':'), PUSH, Some(InPortFirst), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InHostFirstDomain), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(IsDigit, POP, None, None, None),
+        Action::new(
+            IsStopHost,
+            BINGO | CLRALL,
+            Some(InUrlPathStart),
+            Some(Host),
+            None,
+        ),
+        Action::new(IsEqC('/'), PUSH, Some(InFullUrl), None, None),
+        Action::new(Any, BINGO | CLRALL, Some(Base), Some(Host), None),
+    ],
+    // InPortFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InPort), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InPort
+    &[
+        Action::new(IsEof, BINGO | CLRALL, Some(Base), Some(Host), None),
+        Action::new(IsDigit, NEXT, Some(InPort), None, None),
+        Action::new(
+            IsStopHost,
+            BINGO | CLRALL,
+            Some(InUrlPathStart),
+            Some(Host),
+            None,
+        ),
+        Action::new(IsEqC('/'), PUSH, Some(InFullUrl), None, None),
+        Action::new(Any, BINGO | CLRALL, Some(Base), Some(Host), None),
+    ],
+    // InHostFirstAN
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InHost), None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHost), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHost
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InHost), None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHost), None, None),
+        Action::new(IsEqC('@'), PUSH, Some(InEmail), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InHostFirstDomain), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(IsEqC('_'), PUSH, Some(InHostFirstAN), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InEmail
+    &[
+        Action::new(IsStopHost, POP, None, None, None),
+        Action::new(IsHost, BINGO | CLRALL, Some(Base), Some(Email), None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InFileFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InFile), None, None),
+        Action::new(IsDigit, NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('.'), NEXT, Some(InPathFirst), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('~'), PUSH, Some(InFileTwiddle), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InFileTwiddle
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InFile), None, None),
+        Action::new(IsDigit, NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InFileFirst), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InPathFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InFile), None, None),
+        Action::new(IsDigit, NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('.'), NEXT, Some(InPathSecond), None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InFileFirst), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InPathFirstFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('.'), NEXT, Some(InPathSecond), None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InFileFirst), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InPathSecond
+    &[
+        Action::new(IsEof, BINGO | CLEAR, Some(Base), Some(File), None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(IsEqC('/'), BINGO | CLEAR, Some(Base), Some(File), None),
+        Action::new(IsSpace, BINGO | CLEAR, Some(Base), Some(File), None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InFile
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(File), None),
+        Action::new(IsAsciiLetter, NEXT, Some(InFile), None, None),
+        Action::new(IsDigit, NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('.'), PUSH, Some(InFileNext), None, None),
+        Action::new(IsEqC('_'), NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('-'), NEXT, Some(InFile), None, None),
+        Action::new(IsEqC('/'), PUSH, Some(InFileFirst), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(File), None),
+    ],
+    // InFileNext
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, CLEAR, Some(InFile), None, None),
+        Action::new(IsDigit, CLEAR, Some(InFile), None, None),
+        Action::new(IsEqC('_'), CLEAR, Some(InFile), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InUrlPathFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsUrlChar, NEXT, Some(InUrlPath), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InUrlPathStart
+    &[Action::new(Any, NEXT, Some(InUrlPath), None, None)],
+    // InUrlPath
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(UrlPath), None),
+        Action::new(IsUrlChar, NEXT, Some(InUrlPath), None, None),
+        Action::new(Any, BINGO, Some(Base), Some(UrlPath), None),
+    ],
+    // InFullUrl
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(
+            IsUrlPath,
+            BINGO | CLRALL,
+            Some(Base),
+            Some(Url),
+            Some(Special::FullUrl),
+        ),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InProtocolFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InProtocolSecond), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InProtocolSecond
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsEqC('/'), NEXT, Some(InProtocolEnd), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InProtocolEnd
+    &[Action::new(
+        Any,
+        BINGO | CLRALL,
+        Some(Base),
+        Some(Protocol),
+        None,
+    )],
+    // InHyphenAsciiWordFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHyphenAsciiWord), None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenDigitLookahead), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHyphenAsciiWord
+    &[
+        Action::new(
+            IsEof,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(AsciiHword),
+            Some(Special::Hyphen),
+        ),
+        Action::new(IsAsciiLetter, NEXT, Some(InHyphenAsciiWord), None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenAsciiWordFirst), None, None),
+        Action::new(
+            Any,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(AsciiHword),
+            Some(Special::Hyphen),
+        ),
+    ],
+    // InHyphenWordFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenDigitLookahead), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHyphenWord
+    &[
+        Action::new(
+            IsEof,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(Hword),
+            Some(Special::Hyphen),
+        ),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenWordFirst), None, None),
+        Action::new(
+            Any,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(Hword),
+            Some(Special::Hyphen),
+        ),
+    ],
+    // InHyphenNumWordFirst
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenDigitLookahead), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHyphenNumWord
+    &[
+        Action::new(
+            IsEof,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(NumHword),
+            Some(Special::Hyphen),
+        ),
+        Action::new(IsAlnum, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InHyphenNumWordFirst), None, None),
+        Action::new(
+            Any,
+            BINGO | CLRALL,
+            Some(InParseHyphen),
+            Some(NumHword),
+            Some(Special::Hyphen),
+        ),
+    ],
+    // InHyphenDigitLookahead
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenDigitLookahead), None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenNumWord), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InParseHyphen
+    &[
+        Action::new(IsEof, RERUN, Some(Base), None, None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHyphenAsciiWordPart), None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWordPart), None, None),
+        Action::new(IsDigit, PUSH, Some(InHyphenUnsignedInt), None, None),
+        Action::new(IsEqC('-'), PUSH, Some(InParseHyphenHyphen), None, None),
+        Action::new(Any, RERUN, Some(Base), None, None),
+    ],
+    // InParseHyphenHyphen
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(
+            IsAlnum,
+            BINGO | CLEAR,
+            Some(InParseHyphen),
+            Some(Blank),
+            None,
+        ),
+        Action::new(
+            IsSpecial,
+            BINGO | CLEAR,
+            Some(InParseHyphen),
+            Some(Blank),
+            None,
+        ),
+        Action::new(Any, POP, None, None, None),
+    ],
+    // InHyphenWordPart
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(HwordPart), None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWordPart), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenWordPart), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenNumWordPart), None, None),
+        Action::new(Any, BINGO, Some(InParseHyphen), Some(HwordPart), None),
+    ],
+    // InHyphenAsciiWordPart
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(HwordAsciipart), None),
+        Action::new(IsAsciiLetter, NEXT, Some(InHyphenAsciiWordPart), None, None),
+        Action::new(IsAlpha, NEXT, Some(InHyphenWordPart), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenWordPart), None, None),
+        Action::new(IsDigit, NEXT, Some(InHyphenNumWordPart), None, None),
+        Action::new(Any, BINGO, Some(InParseHyphen), Some(HwordAsciipart), None),
+    ],
+    // InHyphenNumWordPart
+    &[
+        Action::new(IsEof, BINGO, Some(Base), Some(HwordNumpart), None),
+        Action::new(IsAlnum, NEXT, Some(InHyphenNumWordPart), None, None),
+        Action::new(IsSpecial, NEXT, Some(InHyphenNumWordPart), None, None),
+        Action::new(Any, BINGO, Some(InParseHyphen), Some(HwordNumpart), None),
+    ],
+    // InHyphenUnsignedInt
+    &[
+        Action::new(IsEof, POP, None, None, None),
+        Action::new(IsDigit, NEXT, None, None, None),
+        Action::new(IsAlpha, CLEAR, Some(InHyphenNumWordPart), None, None),
+        Action::new(IsSpecial, CLEAR, Some(InHyphenNumWordPart), None, None),
+        Action::new(Any, POP, None, None, None),
+    ],
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expected(tokens: &[(TokenType, &str)]) -> Vec<(TokenType, String)> {
+        tokens
+            .iter()
+            .map(|(token_type, token)| (*token_type, token.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            tokenize("foo-bar baz@example.com 3.14"),
+            expected(&[
+                (AsciiHword, "foo-bar"),
+                (HwordAsciipart, "foo"),
+                (Blank, "-"),
+                (HwordAsciipart, "bar"),
+                (Blank, " "),
+                (Email, "baz@example.com"),
+                (Blank, " "),
+                (Float, "3.14"),
+            ])
+        );
+        assert_eq!(
+            tokenize("See http://www.postgresql.org/docs/ v1.2.3 <b>Ünï</b>&amp;-42"),
+            expected(&[
+                (AsciiWord, "See"),
+                (Blank, " "),
+                (Protocol, "http://"),
+                (Url, "www.postgresql.org/docs/"),
+                (Host, "www.postgresql.org"),
+                (UrlPath, "/docs/"),
+                (Blank, " "),
+                (File, "v1.2.3"),
+                (Blank, " "),
+                (Tag, "<b>"),
+                (Word, "Ünï"),
+                (Tag, "</b>"),
+                (Entity, "&amp;"),
+                (Int, "-42"),
+            ])
+        );
+        assert_eq!(
+            tokenize("abc123 /usr/local/foo.txt 1e10"),
+            expected(&[
+                (NumWord, "abc123"),
+                (Blank, " "),
+                (File, "/usr/local/foo.txt"),
+                (Blank, " "),
+                (Sfloat, "1e10"),
+            ])
+        );
+        assert_eq!(tokenize(""), vec![]);
+    }
+}
@@ -0,0 +1,1013 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, FixedSizeBinaryArray, Int16Array, StringArray,
+    TimestampMicrosecondArray,
+};
+use datafusion::arrow::datatypes::DataType::{
+    FixedSizeBinary, Int16, LargeUtf8, Null, Timestamp, Utf8, Utf8View,
+};
+use datafusion::arrow::datatypes::{DataType, TimeUnit};
+use datafusion::common::{exec_err, ScalarValue};
+use datafusion::error::Result;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+use rand::rngs::StdRng;
+use rand::Rng;
+use uuid::{Builder, Uuid};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{exact_signatures, string_values, STRING_TYPES};
+use crate::postgres::array_udfs::rng;
+
+const UUID_FUNCTIONS_LINK: &str = "https://www.postgresql.org/docs/17/functions-uuid.html";
+const UUID_OSSP_LINK: &str = "https://www.postgresql.org/docs/16/uuid-ossp.html";
+const UUID_TYPE_LINK: &str = "https://www.postgresql.org/docs/16/datatype-uuid.html";
+
+/// The types read as UUIDs: their 16 bytes, or their text.
+const UUID_TYPES: [DataType; 4] = [FixedSizeBinary(16), Utf8, LargeUtf8, Utf8View];
+
+/// The 100-nanosecond intervals from the start of the Gregorian calendar, 1582-10-15, to the
+/// Unix epoch, as counted by version 1 UUIDs.
+const GREGORIAN_TICKS: i64 = 0x01B2_1DD2_1381_4000;
+
+/// The smallest step, in nanoseconds, that changes the 12 bits of a version 7 UUID that follow
+/// its milliseconds.
+const SUB_MILLISECOND_STEP: i64 = 1_000_000 / 4096 + 1;
+
+/// The UUIDs uuid-ossp has a function for: the nil UUID and the namespaces of RFC 4122.
+pub(crate) const UUID_CONSTANTS: [(&str, Uuid); 5] = [
+    ("uuid_nil", Uuid::nil()),
+    ("uuid_ns_dns", Uuid::NAMESPACE_DNS),
+    ("uuid_ns_oid", Uuid::NAMESPACE_OID),
+    ("uuid_ns_url", Uuid::NAMESPACE_URL),
+    ("uuid_ns_x500", Uuid::NAMESPACE_X500),
+];
+
+/// Generates random, version 4, UUIDs. They are repeatable with
+/// [`RegisterOptions::with_random_seed`].
+///
+/// [`RegisterOptions::with_random_seed`]: crate::registry::RegisterOptions::with_random_seed
+#[derive(Debug)]
+pub struct GenRandomUuid {
+    signature: Signature,
+    aliases: Vec<String>,
+    rng: Mutex<StdRng>,
+}
+
+impl GenRandomUuid {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Volatile),
+            aliases: vec!["uuid_generate_v4".to_string()],
+            rng: Mutex::new(rng(seed)),
+        }
+    }
+}
+
+impl ScalarUDFImpl for GenRandomUuid {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "gen_random_uuid"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let mut rng = self.rng.lock().unwrap();
+        let uuids = (0..row_count(args))
+            .map(|_| Some(Builder::from_random_bytes(rng.gen()).into_uuid()))
+            .collect();
+
+        Ok(ColumnarValue::Array(uuid_array(uuids)?))
+    }
+}
+
+impl Documented for GenRandomUuid {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "gen_random_uuid() → uuid",
+            description: "Generates a version 4 (random) UUID",
+            examples: &["gen_random_uuid() → 5b30857f-0bfa-48b5-ac0b-5c64e28078d1"],
+            link: Some(UUID_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Generates version 1 UUIDs from the current time. There is no MAC address to use, so the node
+/// is a random multicast address, as for uuid-ossp's `uuid_generate_v1mc()`, and the clock
+/// sequence is random too. They are repeatable, but for the time, with
+/// [`RegisterOptions::with_random_seed`].
+///
+/// [`RegisterOptions::with_random_seed`]: crate::registry::RegisterOptions::with_random_seed
+#[derive(Debug)]
+pub struct UuidGenerateV1 {
+    signature: Signature,
+    aliases: Vec<String>,
+    rng: Mutex<StdRng>,
+}
+
+impl UuidGenerateV1 {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Volatile),
+            aliases: vec!["uuid_generate_v1mc".to_string()],
+            rng: Mutex::new(rng(seed)),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidGenerateV1 {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_generate_v1"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let mut rng = self.rng.lock().unwrap();
+        let uuids = (0..row_count(args))
+            .map(|_| {
+                let ticks = (now_nanos() / 100 + GREGORIAN_TICKS) as u64;
+                let clock_sequence = (rng.gen::<u16>() & 0x3fff) | 0x8000;
+                let mut node = [0; 8];
+                node[..2].copy_from_slice(&clock_sequence.to_be_bytes());
+                rng.fill(&mut node[2..]);
+                // The multicast bit, which no MAC address has.
+                node[2] |= 0x01;
+                Some(Uuid::from_fields(
+                    ticks as u32,
+                    (ticks >> 32) as u16,
+                    ((ticks >> 48) as u16 & 0x0fff) | 0x1000,
+                    &node,
+                ))
+            })
+            .collect();
+
+        Ok(ColumnarValue::Array(uuid_array(uuids)?))
+    }
+}
+
+impl Documented for UuidGenerateV1 {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_generate_v1() → uuid",
+            description: "Generates a version 1 UUID, from the current time and a random multicast node in place of the computer's MAC address",
+            examples: &["uuid_generate_v1() → 8b5a54be-8d1e-11ef-8cf5-6b8f2c8e04a3"],
+            link: Some(UUID_OSSP_LINK),
+        }
+    }
+}
+
+/// Generates version 3 UUIDs, the MD5 hash of a namespace UUID and a name.
+#[derive(Debug)]
+pub struct UuidGenerateV3 {
+    signature: Signature,
+}
+
+impl UuidGenerateV3 {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&UUID_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidGenerateV3 {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_generate_v3"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        name_based_uuids(args, Uuid::new_v3)
+    }
+}
+
+impl Documented for UuidGenerateV3 {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_generate_v3(namespace uuid, name text) → uuid",
+            description: "Generates a version 3 UUID in the given namespace using the specified input name. The namespace should be one of the constants given by the uuid_ns_ functions",
+            examples: &["uuid_generate_v3(uuid_ns_url(), 'http://www.postgresql.org') → cf16fe52-3365-3a1f-8572-288d8d2aaa46"],
+            link: Some(UUID_OSSP_LINK),
+        }
+    }
+}
+
+/// Generates version 5 UUIDs, the SHA-1 hash of a namespace UUID and a name.
+#[derive(Debug)]
+pub struct UuidGenerateV5 {
+    signature: Signature,
+}
+
+impl UuidGenerateV5 {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&UUID_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidGenerateV5 {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_generate_v5"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        name_based_uuids(args, Uuid::new_v5)
+    }
+}
+
+impl Documented for UuidGenerateV5 {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_generate_v5(namespace uuid, name text) → uuid",
+            description: "Generates a version 5 UUID, which works like a version 3 UUID except that SHA-1 is used as a hashing method",
+            examples: &["uuid_generate_v5(uuid_ns_url(), 'http://www.postgresql.org') → e1ee1ad4-cd4e-5889-962a-4f605a68d94e"],
+            link: Some(UUID_OSSP_LINK),
+        }
+    }
+}
+
+/// Generates version 7 UUIDs, which start with the milliseconds since the Unix epoch and so sort
+/// by the time they were made. As in Postgres, the 12 bits after the milliseconds hold a
+/// fraction of the millisecond, moved on whenever the clock has not, so that the UUIDs a
+/// function makes always increase. The rest is random, and repeatable with
+/// [`RegisterOptions::with_random_seed`].
+///
+/// [`RegisterOptions::with_random_seed`]: crate::registry::RegisterOptions::with_random_seed
+#[derive(Debug)]
+pub struct UuidGenerateV7 {
+    signature: Signature,
+    rng: Mutex<StdRng>,
+    last_nanos: Mutex<i64>,
+}
+
+impl UuidGenerateV7 {
+    pub fn new(seed: Option<u64>) -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Volatile),
+            rng: Mutex::new(rng(seed)),
+            last_nanos: Mutex::new(0),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidGenerateV7 {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_generate_v7"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let mut rng = self.rng.lock().unwrap();
+        let mut last_nanos = self.last_nanos.lock().unwrap();
+        let uuids = (0..row_count(args))
+            .map(|_| {
+                let nanos = now_nanos().max(*last_nanos + SUB_MILLISECOND_STEP);
+                *last_nanos = nanos;
+                let fraction = (nanos % 1_000_000 * 4096 / 1_000_000) as u16;
+                let mut random_bytes: [u8; 10] = rng.gen();
+                random_bytes[..2].copy_from_slice(&fraction.to_be_bytes());
+                let millis = (nanos / 1_000_000) as u64;
+                Some(Builder::from_unix_timestamp_millis(millis, &random_bytes).into_uuid())
+            })
+            .collect();
+
+        Ok(ColumnarValue::Array(uuid_array(uuids)?))
+    }
+}
+
+impl Documented for UuidGenerateV7 {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_generate_v7() → uuid",
+            description: "Generates a version 7 (time-ordered) UUID. The timestamp is computed using UNIX timestamp with millisecond precision + sub-millisecond timestamp + random",
+            examples: &["uuid_generate_v7() → 019535d9-3df7-79fb-b466-fa907fa17f9e"],
+            link: Some(UUID_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns one of the constant UUIDs of uuid-ossp, such as the namespaces for
+/// `uuid_generate_v3` and `uuid_generate_v5`.
+#[derive(Debug)]
+pub struct UuidConstant {
+    signature: Signature,
+    name: &'static str,
+    uuid: Uuid,
+}
+
+impl UuidConstant {
+    pub fn new(name: &'static str, uuid: Uuid) -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Immutable),
+            name,
+            uuid,
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidConstant {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, _args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        Ok(ColumnarValue::Scalar(ScalarValue::FixedSizeBinary(
+            16,
+            Some(self.uuid.as_bytes().to_vec()),
+        )))
+    }
+}
+
+impl Documented for UuidConstant {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (_, _, &[_]) = match self.name {
+            "uuid_nil" => (
+                "uuid_nil() → uuid",
+                "A \"nil\" UUID constant, which does not occur as a real UUID",
+                &["uuid_nil() → 00000000-0000-0000-0000-000000000000"],
+            ),
+            "uuid_ns_dns" => (
+                "uuid_ns_dns() → uuid",
+                "Constant designating the DNS namespace for UUIDs",
+                &["uuid_ns_dns() → 6ba7b810-9dad-11d1-80b4-00c04fd430c8"],
+            ),
+            "uuid_ns_oid" => (
+                "uuid_ns_oid() → uuid",
+                "Constant designating the ISO object identifier (OID) namespace for UUIDs. (This pertains to ASN.1 OIDs, which are unrelated to the OIDs used in PostgreSQL.)",
+                &["uuid_ns_oid() → 6ba7b812-9dad-11d1-80b4-00c04fd430c8"],
+            ),
+            "uuid_ns_url" => (
+                "uuid_ns_url() → uuid",
+                "Constant designating the URL namespace for UUIDs",
+                &["uuid_ns_url() → 6ba7b811-9dad-11d1-80b4-00c04fd430c8"],
+            ),
+            _ => (
+                "uuid_ns_x500() → uuid",
+                "Constant designating the X.500 distinguished name (DN) namespace for UUIDs",
+                &["uuid_ns_x500() → 6ba7b814-9dad-11d1-80b4-00c04fd430c8"],
+            ),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(UUID_OSSP_LINK),
+        }
+    }
+}
+
+/// Returns the version of a UUID, or NULL when it is not of the RFC 4122 variant.
+#[derive(Debug)]
+pub struct UuidExtractVersion {
+    signature: Signature,
+}
+
+impl UuidExtractVersion {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&UUID_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidExtractVersion {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_extract_version"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int16)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let versions = uuid_values(&args[0])?
+            .into_iter()
+            .map(|uuid| {
+                let bytes = uuid?.into_bytes();
+                is_rfc4122(&bytes).then_some((bytes[6] >> 4) as i16)
+            })
+            .collect::<Int16Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(versions) as ArrayRef))
+    }
+}
+
+impl Documented for UuidExtractVersion {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_extract_version(uuid) → smallint",
+            description: "Extracts the version from a UUID of the variant described by RFC 4122. For other variants, this function returns null. For example, for a UUID generated by gen_random_uuid, this function will return 4",
+            examples: &["uuid_extract_version('41db1265-8bc1-4ab3-992f-885799a4af1d') → 4"],
+            link: Some(UUID_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Returns the time a version 1 or version 7 UUID was made, and NULL for other UUIDs.
+#[derive(Debug)]
+pub struct UuidExtractTimestamp {
+    signature: Signature,
+}
+
+impl UuidExtractTimestamp {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&UUID_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UuidExtractTimestamp {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "uuid_extract_timestamp"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Timestamp(TimeUnit::Microsecond, Some("+00:00".into())))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let timestamps = uuid_values(&args[0])?
+            .into_iter()
+            .map(|uuid| {
+                let bytes = uuid?.into_bytes();
+                if !is_rfc4122(&bytes) {
+                    return None;
+                }
+                match bytes[6] >> 4 {
+                    1 => {
+                        let ticks = u64::from_be_bytes([
+                            bytes[6] & 0x0f,
+                            bytes[7],
+                            bytes[4],
+                            bytes[5],
+                            bytes[0],
+                            bytes[1],
+                            bytes[2],
+                            bytes[3],
+                        ]);
+                        Some((ticks / 10) as i64 - GREGORIAN_TICKS / 10)
+                    }
+                    7 => {
+                        let mut millis = [0; 8];
+                        millis[2..].copy_from_slice(&bytes[..6]);
+                        Some(i64::from_be_bytes(millis) * 1000)
+                    }
+                    _ => None,
+                }
+            })
+            .collect::<TimestampMicrosecondArray>();
+
+        Ok(ColumnarValue::Array(
+            Arc::new(timestamps.with_timezone("+00:00")) as ArrayRef,
+        ))
+    }
+}
+
+impl Documented for UuidExtractTimestamp {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "uuid_extract_timestamp(uuid) → timestamp with time zone",
+            description: "Extracts a timestamp with time zone from a UUID of version 1 or 7. For other versions, this function returns null. Note that the extracted timestamp is not necessarily exactly equal to the time the UUID was generated; this depends on the implementation that generated the UUID",
+            examples: &["uuid_extract_timestamp('c232ab00-9414-11ec-b3c8-9f6bdeced846') → 2022-02-22 19:22:22+00"],
+            link: Some(UUID_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Reads UUIDs from Postgres' text for them: 32 hex digits, in either case, optionally in
+/// braces, with a hyphen allowed after any group of four digits.
+#[derive(Debug)]
+pub struct PgUuidIn {
+    signature: Signature,
+}
+
+impl PgUuidIn {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&STRING_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgUuidIn {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_uuid_in"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(FixedSizeBinary(16))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        Ok(ColumnarValue::Array(uuid_array(uuid_values(&args[0])?)?))
+    }
+}
+
+impl Documented for PgUuidIn {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_uuid_in(text) → uuid",
+            description: "Converts Postgres' text for a UUID to a UUID. Upper-case digits, braces around the digits and a hyphen after any group of four digits are accepted",
+            examples: &["pg_uuid_in('{A0EEBC99-9C0B4EF8-BB6D6BB9-BD380A11}') → a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11"],
+            link: Some(UUID_TYPE_LINK),
+        }
+    }
+}
+
+/// Writes UUIDs in their standard form, as lower-case hex digits in groups of 8, 4, 4, 4 and 12
+/// separated by hyphens.
+#[derive(Debug)]
+pub struct PgUuidOut {
+    signature: Signature,
+}
+
+impl PgUuidOut {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(exact_signatures(&[&UUID_TYPES]), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for PgUuidOut {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pg_uuid_out"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let texts = uuid_values(&args[0])?
+            .into_iter()
+            .map(|uuid| uuid.map(|uuid| uuid.hyphenated().to_string()))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(texts) as ArrayRef))
+    }
+}
+
+impl Documented for PgUuidOut {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pg_uuid_out(uuid) → text",
+            description: "Converts a UUID to Postgres' text for it, lower-case hex digits in groups of 8, 4, 4, 4 and 12 separated by hyphens",
+            examples: &["pg_uuid_out(uuid_ns_url()) → 6ba7b811-9dad-11d1-80b4-00c04fd430c8"],
+            link: Some(UUID_TYPE_LINK),
+        }
+    }
+}
+
+/// Hashes a namespace and a name into a UUID for each row, or NULL when either is NULL.
+fn name_based_uuids(
+    args: &[ColumnarValue],
+    generate: impl Fn(&Uuid, &[u8]) -> Uuid,
+) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let uuids = uuid_values(&args[0])?
+        .into_iter()
+        .zip(string_values(&args[1])?)
+        .map(|(namespace, name)| Some(generate(&namespace?, name?.as_bytes())))
+        .collect();
+
+    Ok(ColumnarValue::Array(uuid_array(uuids)?))
+}
+
+/// Reads UUIDs stored as 16 bytes or written as text.
+fn uuid_values(array: &ArrayRef) -> Result<Vec<Option<Uuid>>> {
+    match array.data_type() {
+        FixedSizeBinary(16) => array
+            .as_fixed_size_binary()
+            .iter()
+            .map(|bytes| {
+                bytes
+                    .map(|bytes| {
+                        Uuid::from_slice(bytes).or_else(|err| exec_err!("invalid uuid: {err}"))
+                    })
+                    .transpose()
+            })
+            .collect(),
+        Null => Ok(vec![None; array.len()]),
+        data_type if STRING_TYPES.contains(data_type) => string_values(array)?
+            .into_iter()
+            .map(|text| text.map(parse_uuid).transpose())
+            .collect(),
+        t => exec_err!("expected a uuid, not {t}"),
+    }
+}
+
+fn uuid_array(uuids: Vec<Option<Uuid>>) -> Result<ArrayRef> {
+    let bytes = uuids.into_iter().map(|uuid| uuid.map(Uuid::into_bytes));
+    Ok(Arc::new(
+        FixedSizeBinaryArray::try_from_sparse_iter_with_size(bytes, 16)?,
+    ))
+}
+
+/// Reads a UUID as Postgres' uuid_in() does.
+fn parse_uuid(text: &str) -> Result<Uuid> {
+    let invalid = || exec_err!("invalid input syntax for type uuid: \"{text}\"");
+    let digits = match text.strip_prefix('{') {
+        Some(rest) => match rest.strip_suffix('}') {
+            Some(digits) => digits,
+            None => return invalid(),
+        },
+        None => text,
+    };
+
+    let hex_digit = |digit: Option<u8>| digit.and_then(|digit| (digit as char).to_digit(16));
+    let mut digits = digits.bytes().peekable();
+    let mut bytes = [0; 16];
+    for (index, byte) in bytes.iter_mut().enumerate() {
+        let (Some(high), Some(low)) = (hex_digit(digits.next()), hex_digit(digits.next())) else {
+            return invalid();
+        };
+        *byte = ((high << 4) | low) as u8;
+        if index % 2 == 1 && index < 15 {
+            digits.next_if_eq(&b'-');
+        }
+    }
+    if digits.next().is_some() {
+        return invalid();
+    }
+    Ok(Uuid::from_bytes(bytes))
+}
+
+/// Is the UUID of the variant described by RFC 4122, the only one with versions?
+fn is_rfc4122(bytes: &[u8; 16]) -> bool {
+    bytes[8] & 0xc0 == 0x80
+}
+
+/// The number of rows a function without arguments is called for. DataFusion passes such
+/// functions a NULL array as long as the batch.
+fn row_count(args: &[ColumnarValue]) -> usize {
+    match args.first() {
+        Some(ColumnarValue::Array(array)) => array.len(),
+        _ => 1,
+    }
+}
+
+/// The nanoseconds since the Unix epoch.
+fn now_nanos() -> i64 {
+    Utc::now().timestamp_nanos_opt().unwrap_or(i64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::new_null_array;
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::postgres::{register_postgres_udfs, register_postgres_udfs_with_options};
+    use crate::registry::RegisterOptions;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_gen_random_uuid() -> Result<()> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs_with_options(
+            &ctx,
+            &RegisterOptions::default().with_random_seed(42),
+        )?;
+        let df = ctx
+            .sql("select pg_uuid_out(gen_random_uuid()) as col_uuid")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------------------------+
+| col_uuid                             |
++--------------------------------------+
+| a2637d13-d171-4278-aadf-a8a3fbe8379b |
++--------------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql("select min(uuid_extract_version(uuid_generate_v4())) as col_version, count(distinct pg_uuid_out(gen_random_uuid())) as col_distinct from (values (1), (2), (3))")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------------+--------------+
+| col_version | col_distinct |
++-------------+--------------+
+| 4           | 3            |
++-------------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_time_based_uuids() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select uuid_extract_version(uuid_generate_v1()) as col_v1, uuid_extract_version(uuid_generate_v1mc()) as col_v1mc, uuid_extract_version(uuid_generate_v7()) as col_v7, uuid_extract_timestamp(uuid_generate_v7()) > now() - interval '1 minute' as col_recent")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+----------+--------+------------+
+| col_v1 | col_v1mc | col_v7 | col_recent |
++--------+----------+--------+------------+
+| 1      | 1        | 7      | true       |
++--------+----------+--------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_uuid_generate_v7_increases() -> Result<()> {
+        let function = UuidGenerateV7::new(Some(42));
+        let mut uuids = vec![];
+        for _ in 0..3 {
+            let args = [ColumnarValue::Array(new_null_array(&Null, 1000))];
+            let ColumnarValue::Array(array) = function.invoke(&args)? else {
+                unreachable!()
+            };
+            uuids.extend(uuid_values(&array)?.into_iter().flatten());
+        }
+
+        assert_eq!(uuids.len(), 3000);
+        assert!(uuids.windows(2).all(|pair| pair[0] < pair[1]));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_name_based_uuids() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select uuid_ns_url() as col_raw, pg_uuid_out(uuid_generate_v3(uuid_ns_url(), 'http://www.postgresql.org')) as col_v3, pg_uuid_out(uuid_generate_v5(uuid_ns_url(), 'http://www.postgresql.org')) as col_v5, pg_uuid_out(uuid_generate_v3(uuid_ns_dns(), 'ünïcode')) as col_unicode, pg_uuid_out(uuid_generate_v5(uuid_ns_dns(), '')) as col_empty, pg_uuid_out(uuid_generate_v5('6BA7B811-9DAD-11D1-80B4-00C04FD430C8', 'http://www.postgresql.org')) as col_text, pg_uuid_out(uuid_generate_v5(uuid_nil(), NULL)) as col_null")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+
+| col_raw                          | col_v3                               | col_v5                               | col_unicode                          | col_empty                            | col_text                             | col_null |
++----------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+
+| 6ba7b8119dad11d180b400c04fd430c8 | cf16fe52-3365-3a1f-8572-288d8d2aaa46 | e1ee1ad4-cd4e-5889-962a-4f605a68d94e | 5159516a-62a2-3282-8ed2-66306c6a12ff | 4ebd0208-8328-5d69-8c44-ec50939c0967 | e1ee1ad4-cd4e-5889-962a-4f605a68d94e |          |
++----------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql("select uuid_generate_v3('6ba7b811', 'x')")
+            .await?
+            .collect()
+            .await;
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .contains("invalid input syntax for type uuid: \"6ba7b811\""));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_uuid_extract() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select id, uuid_extract_version(pg_uuid_in(uuid)) as version, uuid_extract_timestamp(uuid) as timestamp from (values (1, 'c232ab00-9414-11ec-b3c8-9f6bdeced846'), (2, '017f22e2-79b0-7cc3-98c4-dc0c0c07398f'), (3, '41db1265-8bc1-4ab3-992f-885799a4af1d'), (4, '11111111-1111-1111-1111-111111111111'), (5, NULL)) as t(id, uuid)")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+---------+----------------------+
+| id | version | timestamp            |
++----+---------+----------------------+
+| 1  | 1       | 2022-02-22T19:22:22Z |
+| 2  | 7       | 2022-02-22T19:22:22Z |
+| 3  | 4       |                      |
+| 4  |         |                      |
+| 5  |         |                      |
++----+---------+----------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_pg_uuid_in_out() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql("select pg_uuid_out(pg_uuid_in('{A0EEBC99-9C0B4EF8-BB6D6BB9-BD380A11}')) as col_braces, pg_uuid_out(pg_uuid_in('a0eebc999c0b4ef8bb6d6bb9bd380a11')) as col_bare, pg_uuid_out('a0ee-bc99-9c0b-4ef8-bb6d-6bb9-bd38-0a11') as col_grouped, pg_uuid_out(uuid_nil()) as col_nil, pg_uuid_out(pg_uuid_in(NULL)) as col_null")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+
+| col_braces                           | col_bare                             | col_grouped                          | col_nil                              | col_null |
++--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+
+| a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11 | a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11 | a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11 | 00000000-0000-0000-0000-000000000000 |          |
++--------------------------------------+--------------------------------------+--------------------------------------+--------------------------------------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        for text in [
+            "a0eebc99-9c0b4ef8-bb6d6bb9-bd380a1",
+            " a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+            "{a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+            "a0eebc99-9c0b-4ef8-bb6d-6bb9bd380a11-",
+            "a0eebc99--9c0b-4ef8-bb6d-6bb9bd380a11",
+            "a0e-ebc99-9c0b-4ef8-bb6d-6bb9bd380a11",
+        ] {
+            let result = ctx
+                .sql(&format!("select pg_uuid_in('{text}')"))
+                .await?
+                .collect()
+                .await;
+            assert!(result
+                .unwrap_err()
+                .to_string()
+                .contains(&format!("invalid input syntax for type uuid: \"{text}\"")));
+        }
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,759 @@
+#![cfg_attr(
+    not(any(feature = "postgres", feature = "sqlite")),
+    allow(dead_code, unused_variables)
+)]
+
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::{Arc, OnceLock};
+
+use datafusion::arrow::datatypes::{DataType, Field};
+use datafusion::common::{plan_err, ExprSchema};
+use datafusion::datasource::function::TableFunctionImpl;
+use datafusion::error::Result;
+use datafusion::logical_expr::function::AccumulatorArgs;
+use datafusion::logical_expr::simplify::{ExprSimplifyResult, SimplifyInfo};
+use datafusion::logical_expr::{
+    Accumulator, AggregateFunction, AggregateUDF, AggregateUDFImpl, BuiltinScalarFunction,
+    ColumnarValue, Expr, FuncMonotonicity, GroupsAccumulator, ScalarUDF, ScalarUDFImpl, Signature,
+};
+use datafusion::prelude::SessionContext;
+
+use crate::catalog::{Documentation, Documented, FunctionInfo};
+#[cfg(feature = "postgres")]
+use crate::postgres::IntervalStyle;
+
+/// The database whose functions are registered by [`register_udfs`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dialect {
+    #[cfg(feature = "postgres")]
+    Postgres,
+    #[cfg(feature = "sqlite")]
+    Sqlite,
+}
+
+impl Dialect {
+    pub fn name(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "postgres")]
+            Dialect::Postgres => "postgres",
+            #[cfg(feature = "sqlite")]
+            Dialect::Sqlite => "sqlite",
+        }
+    }
+}
+
+/// Registers the functions of `dialect` into `ctx`. Each session has its own function
+/// registry, so sessions serving different tenants can each be given a different dialect.
+pub fn register_udfs(
+    ctx: &SessionContext,
+    dialect: Dialect,
+    options: &RegisterOptions,
+) -> Result<()> {
+    match dialect {
+        #[cfg(feature = "postgres")]
+        Dialect::Postgres => crate::postgres::register_postgres_udfs_with_options(ctx, options),
+        #[cfg(feature = "sqlite")]
+        Dialect::Sqlite => crate::sqlite::register_sqlite_udfs_with_options(ctx, options),
+    }
+}
+
+/// Controls which functions are registered, and under which names.
+///
+/// The default registers every function under its own name, replacing DataFusion's built-in
+/// functions of the same name, which is what `register_postgres_udfs` and
+/// `register_sqlite_udfs` do.
+///
+/// ```ignore
+/// let options = RegisterOptions::default()
+///     .with_prefix("sqlite")
+///     .with_deny_list(["printf"]);
+/// register_sqlite_udfs_with_options(&ctx, &options)?;
+/// // select sqlite.mod(7, 3)
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegisterOptions {
+    prefix: Option<String>,
+    shadow_builtins: bool,
+    allow_list: Option<HashSet<String>>,
+    deny_list: HashSet<String>,
+    #[cfg(feature = "postgres")]
+    interval_style: IntervalStyle,
+    #[cfg(feature = "postgres")]
+    random_seed: Option<u64>,
+}
+
+impl Default for RegisterOptions {
+    fn default() -> Self {
+        Self {
+            prefix: None,
+            shadow_builtins: true,
+            allow_list: None,
+            deny_list: HashSet::new(),
+            #[cfg(feature = "postgres")]
+            interval_style: IntervalStyle::default(),
+            #[cfg(feature = "postgres")]
+            random_seed: None,
+        }
+    }
+}
+
+impl RegisterOptions {
+    /// Registers every function as `<prefix>.<name>`, e.g. `pg.mod` or `sqlite.mod`, so that
+    /// functions of different dialects, and DataFusion's built-ins, can be used side by side.
+    /// DataFusion cannot call a table function by a qualified name, so table functions such as
+    /// `unnest` are not registered at all with a prefix.
+    pub fn with_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.prefix = Some(prefix.into());
+        self
+    }
+
+    /// Whether functions named like a DataFusion built-in, such as `sqrt` or `trunc`, replace
+    /// it. When false they are skipped, and so are aliases named like a built-in. Prefixed
+    /// names never collide with built-ins, so this has no effect together with a prefix.
+    pub fn with_shadow_builtins(mut self, shadow_builtins: bool) -> Self {
+        self.shadow_builtins = shadow_builtins;
+        self
+    }
+
+    /// Registers only the functions whose name, or one of whose aliases, is in `names`.
+    pub fn with_allow_list<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allow_list = Some(names.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Skips the functions whose name, or one of whose aliases, is in `names`. The deny list
+    /// wins over the allow list.
+    pub fn with_deny_list<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.deny_list = names.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// The format Postgres' `interval_out` writes intervals in, and in which `interval_in` reads
+    /// them, like `SET IntervalStyle` does for a Postgres session. Defaults to `postgres`.
+    #[cfg(feature = "postgres")]
+    pub fn with_interval_style(mut self, interval_style: IntervalStyle) -> Self {
+        self.interval_style = interval_style;
+        self
+    }
+
+    /// Seeds the random choices of Postgres' `array_sample` and `array_shuffle`, like `setseed`
+    /// does for a Postgres session, so that a session repeats the same choices.
+    #[cfg(feature = "postgres")]
+    pub fn with_random_seed(mut self, seed: u64) -> Self {
+        self.random_seed = Some(seed);
+        self
+    }
+
+    fn includes(&self, name: &str, aliases: &[String]) -> bool {
+        let mut names = std::iter::once(name).chain(aliases.iter().map(String::as_str));
+        let allowed = match &self.allow_list {
+            Some(allow_list) => names.clone().any(|name| allow_list.contains(name)),
+            None => true,
+        };
+        allowed && !names.any(|name| self.deny_list.contains(name))
+    }
+
+    fn qualify(&self, name: &str) -> String {
+        match &self.prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_string(),
+        }
+    }
+
+    fn skips(&self, name: &str) -> bool {
+        self.prefix.is_none() && !self.shadow_builtins && is_builtin(name)
+    }
+}
+
+/// Registers a dialect's functions into a session following [`RegisterOptions`], recording the
+/// documentation of each function it registers under the name it registers it by. Without a
+/// session it only records them, which is how the [`catalog`](crate::catalog::catalog) is made.
+pub(crate) struct Registrar<'a> {
+    ctx: Option<&'a SessionContext>,
+    functions: &'a RefCell<Vec<FunctionInfo>>,
+    options: &'a RegisterOptions,
+    dialect: Dialect,
+    category: &'static str,
+}
+
+impl<'a> Registrar<'a> {
+    pub(crate) fn new(
+        ctx: &'a SessionContext,
+        functions: &'a RefCell<Vec<FunctionInfo>>,
+        dialect: Dialect,
+        options: &'a RegisterOptions,
+    ) -> Result<Self> {
+        if let Some(prefix) = &options.prefix {
+            if prefix.is_empty() || prefix.contains('.') {
+                return plan_err!("Invalid function prefix '{prefix}'");
+            }
+        }
+        Ok(Self {
+            ctx: Some(ctx),
+            functions,
+            options,
+            dialect,
+            category: "",
+        })
+    }
+
+    pub(crate) fn for_catalog(
+        functions: &'a RefCell<Vec<FunctionInfo>>,
+        dialect: Dialect,
+        options: &'a RegisterOptions,
+    ) -> Self {
+        Self {
+            ctx: None,
+            functions,
+            options,
+            dialect,
+            category: "",
+        }
+    }
+
+    #[cfg(feature = "postgres")]
+    pub(crate) fn interval_style(&self) -> IntervalStyle {
+        self.options.interval_style
+    }
+
+    #[cfg(feature = "postgres")]
+    pub(crate) fn random_seed(&self) -> Option<u64> {
+        self.options.random_seed
+    }
+
+    /// Returns a registrar for the functions of `category`, e.g. "Maths".
+    pub(crate) fn category(&self, category: &'static str) -> Registrar<'a> {
+        Registrar {
+            ctx: self.ctx,
+            functions: self.functions,
+            options: self.options,
+            dialect: self.dialect,
+            category,
+        }
+    }
+
+    pub(crate) fn register_udf<F: ScalarUDFImpl + Documented + 'static>(&self, function: F) {
+        let documentation = function.documentation();
+        let udf = ScalarUDF::new_from_impl(function);
+        let (name, aliases) = match self.get_names(udf.name(), udf.aliases()) {
+            Some(names) => names,
+            None => return,
+        };
+
+        self.record(&name, &aliases, documentation);
+        match self.ctx {
+            Some(ctx) if name == udf.name() && aliases == udf.aliases() => ctx.register_udf(udf),
+            Some(ctx) => ctx.register_udf(ScalarUDF::new_from_impl(RenamedScalarUDF {
+                name,
+                aliases,
+                inner: udf,
+            })),
+            None => {}
+        }
+    }
+
+    pub(crate) fn register_udaf<F: AggregateUDFImpl + Documented + 'static>(&self, function: F) {
+        let documentation = function.documentation();
+        let udaf = AggregateUDF::new_from_impl(function);
+        let (name, aliases) = match self.get_names(udaf.name(), udaf.aliases()) {
+            Some(names) => names,
+            None => return,
+        };
+
+        self.record(&name, &aliases, documentation);
+        match self.ctx {
+            Some(ctx) if name == udaf.name() && aliases == udaf.aliases() => {
+                ctx.register_udaf(udaf)
+            }
+            Some(ctx) => ctx.register_udaf(AggregateUDF::new_from_impl(RenamedAggregateUDF {
+                name,
+                aliases,
+                inner: udaf,
+            })),
+            None => {}
+        }
+    }
+
+    /// Registers a table function, which DataFusion knows by the `name` it is registered under
+    /// rather than by a name of its own. DataFusion only looks up a table function by the first
+    /// part of its name, so one registered with a prefix, like `pg.regexp_matches`, could not be
+    /// called and is skipped.
+    pub(crate) fn register_udtf<F: TableFunctionImpl + Documented + 'static>(
+        &self,
+        name: &str,
+        function: F,
+    ) {
+        if self.options.prefix.is_some() {
+            return;
+        }
+
+        let documentation = function.documentation();
+        let (name, _) = match self.get_names(name, &[]) {
+            Some(names) => names,
+            None => return,
+        };
+
+        self.record(&name, &[], documentation);
+        if let Some(ctx) = self.ctx {
+            ctx.register_udtf(&name, Arc::new(function));
+        }
+    }
+
+    fn record(&self, name: &str, aliases: &[String], documentation: Documentation) {
+        self.functions.borrow_mut().push(FunctionInfo::new(
+            self.dialect,
+            self.category,
+            name.to_string(),
+            aliases.to_vec(),
+            documentation,
+        ));
+    }
+
+    /// Returns the name and aliases to register a function under, or `None` to skip it.
+    fn get_names(&self, name: &str, aliases: &[String]) -> Option<(String, Vec<String>)> {
+        if !self.options.includes(name, aliases) || self.options.skips(name) {
+            return None;
+        }
+
+        let aliases = aliases
+            .iter()
+            .filter(|alias| !self.options.skips(alias))
+            .map(|alias| self.options.qualify(alias))
+            .collect();
+        Some((self.options.qualify(name), aliases))
+    }
+}
+
+/// Returns true if `name` is one of the functions DataFusion provides in a new session.
+fn is_builtin(name: &str) -> bool {
+    static BUILTINS: OnceLock<HashSet<String>> = OnceLock::new();
+    let builtins = BUILTINS.get_or_init(|| {
+        let state = SessionContext::new().state();
+        state
+            .scalar_functions()
+            .keys()
+            .chain(state.aggregate_functions().keys())
+            .cloned()
+            .collect()
+    });
+
+    builtins.contains(name)
+        || BuiltinScalarFunction::from_str(name).is_ok()
+        || AggregateFunction::from_str(name).is_ok()
+}
+
+/// A scalar function registered under another name.
+#[derive(Debug)]
+struct RenamedScalarUDF {
+    name: String,
+    aliases: Vec<String>,
+    inner: ScalarUDF,
+}
+
+impl ScalarUDFImpl for RenamedScalarUDF {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        schema: &dyn ExprSchema,
+        arg_types: &[DataType],
+    ) -> Result<DataType> {
+        self.inner.return_type_from_exprs(args, schema, arg_types)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        self.inner.invoke(args)
+    }
+
+    fn monotonicity(&self) -> Result<Option<FuncMonotonicity>> {
+        self.inner.monotonicity()
+    }
+
+    fn simplify(&self, args: Vec<Expr>, info: &dyn SimplifyInfo) -> Result<ExprSimplifyResult> {
+        self.inner.simplify(args, info)
+    }
+}
+
+/// An aggregate function registered under another name.
+#[derive(Debug)]
+struct RenamedAggregateUDF {
+    name: String,
+    aliases: Vec<String>,
+    inner: AggregateUDF,
+}
+
+impl AggregateUDFImpl for RenamedAggregateUDF {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        self.inner.signature()
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        self.inner.return_type(arg_types)
+    }
+
+    fn accumulator(&self, acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        self.inner.accumulator(acc_args)
+    }
+
+    fn state_fields(
+        &self,
+        name: &str,
+        value_type: DataType,
+        ordering_fields: Vec<Field>,
+    ) -> Result<Vec<Field>> {
+        self.inner.state_fields(name, value_type, ordering_fields)
+    }
+
+    fn groups_accumulator_supported(&self) -> bool {
+        self.inner.groups_accumulator_supported()
+    }
+
+    fn create_groups_accumulator(&self) -> Result<Box<dyn GroupsAccumulator>> {
+        self.inner.create_groups_accumulator()
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_includes() {
+        let options = RegisterOptions::default();
+        assert!(options.includes("pow", &["power".to_string()]));
+
+        let options = RegisterOptions::default().with_allow_list(["power", "sqrt"]);
+        assert!(options.includes("pow", &["power".to_string()]));
+        assert!(options.includes("sqrt", &[]));
+        assert!(!options.includes("exp", &[]));
+
+        let options = RegisterOptions::default()
+            .with_allow_list(["power", "sqrt"])
+            .with_deny_list(["pow"]);
+        assert!(!options.includes("pow", &["power".to_string()]));
+        assert!(options.includes("sqrt", &[]));
+    }
+
+    #[test]
+    fn test_qualify_and_skips() {
+        let options = RegisterOptions::default();
+        assert_eq!(options.qualify("mod"), "mod");
+        assert!(!options.skips("sqrt"));
+
+        let options = RegisterOptions::default().with_shadow_builtins(false);
+        assert!(options.skips("sqrt"));
+        assert!(options.skips("trunc"));
+        assert!(!options.skips("soundex"));
+
+        let options = options.with_prefix("sqlite");
+        assert_eq!(options.qualify("mod"), "sqlite.mod");
+        assert!(!options.skips("sqrt"));
+    }
+}
+
+#[cfg(all(feature = "postgres", feature = "sqlite"))]
+#[cfg(test)]
+mod dialect_tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::common::not_impl_err;
+    use datafusion::logical_expr::Volatility;
+    use datafusion::prelude::{SessionConfig, SessionContext};
+
+    use crate::postgres::register_postgres_udfs_with_options;
+    use crate::sqlite::register_sqlite_udfs_with_options;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prefixed_dialects() -> Result<()> {
+        let ctx = SessionContext::new();
+        register_postgres_udfs_with_options(&ctx, &RegisterOptions::default().with_prefix("pg"))?;
+        register_sqlite_udfs_with_options(&ctx, &RegisterOptions::default().with_prefix("sqlite"))?;
+
+        let df = ctx
+            .sql("select pg.mod(7, 3) as pg_mod, sqlite.mod(7, 3) as sqlite_mod, sqrt(-1) as builtin, sqlite.sqrt(-1) as sqlite_sqrt, sqlite.power(2, 3) as sqlite_power")
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+------------+---------+-------------+--------------+
+| pg_mod | sqlite_mod | builtin | sqlite_sqrt | sqlite_power |
++--------+------------+---------+-------------+--------------+
+| 1      | 1.0        | NaN     |             | 8.0          |
++--------+------------+---------+-------------+--------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert!(ctx.sql("select soundex('Robert')").await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_skips_table_functions() -> Result<()> {
+        let functions = RefCell::new(vec![]);
+        let options = RegisterOptions::default().with_prefix("pg");
+        crate::postgres::register_all(&Registrar::for_catalog(
+            &functions,
+            Dialect::Postgres,
+            &options,
+        ))?;
+
+        let names: Vec<String> = functions
+            .into_inner()
+            .into_iter()
+            .map(|function| function.name)
+            .collect();
+        assert!(names.contains(&"pg.mod".to_string()));
+        assert!(!names.contains(&"pg.unnest".to_string()));
+        assert!(!names.contains(&"pg.regexp_matches".to_string()));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prefixed_aggregate() -> Result<()> {
+        // Two partitions, so the aggregate runs in two phases and its state is exchanged.
+        let ctx = SessionContext::new_with_config(SessionConfig::new().with_target_partitions(2));
+        register_sqlite_udfs_with_options(&ctx, &RegisterOptions::default().with_prefix("sqlite"))?;
+
+        let df = ctx
+            .sql(r#"select k, sqlite.json_infer_schema(doc) as schema from (values (1, '{"a":1}'), (1, '{"a":2.5}'), (2, '{"b":"x"}')) as t(k, doc) group by k"#)
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---+----------+
+| k | schema   |
++---+----------+
+| 1 | a double |
+| 2 | b text   |
++---+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    /// An aggregate with an alias, a state of its own and a groups accumulator.
+    #[derive(Debug)]
+    struct TestAggregate {
+        signature: Signature,
+        aliases: Vec<String>,
+    }
+
+    impl AggregateUDFImpl for TestAggregate {
+        fn as_any(&self) -> &dyn std::any::Any {
+            self
+        }
+
+        fn name(&self) -> &str {
+            "test_agg"
+        }
+
+        fn signature(&self) -> &Signature {
+            &self.signature
+        }
+
+        fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+            Ok(DataType::Int64)
+        }
+
+        fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+            not_impl_err!("test_agg has no accumulator")
+        }
+
+        fn state_fields(
+            &self,
+            _name: &str,
+            _value_type: DataType,
+            _ordering_fields: Vec<Field>,
+        ) -> Result<Vec<Field>> {
+            Ok(vec![
+                Field::new("sum", DataType::Int64, true),
+                Field::new("count", DataType::UInt64, true),
+            ])
+        }
+
+        fn groups_accumulator_supported(&self) -> bool {
+            true
+        }
+
+        fn aliases(&self) -> &[String] {
+            &self.aliases
+        }
+    }
+
+    impl Documented for TestAggregate {
+        fn documentation(&self) -> Documentation {
+            Documentation {
+                syntax: "test_agg(X)",
+                description: "A test aggregate",
+                examples: &["test_agg(X) → 1"],
+                link: None,
+            }
+        }
+    }
+
+    fn test_aggregate() -> TestAggregate {
+        TestAggregate {
+            signature: Signature::any(1, Volatility::Immutable),
+            aliases: vec!["test_alias".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_renamed_aggregate() -> Result<()> {
+        let functions = RefCell::new(vec![]);
+        let options = RegisterOptions::default()
+            .with_prefix("p")
+            .with_allow_list(["test_alias"]);
+        Registrar::for_catalog(&functions, Dialect::Sqlite, &options)
+            .register_udaf(test_aggregate());
+        let functions = functions.into_inner();
+        assert_eq!(functions.len(), 1);
+        assert_eq!(functions[0].name, "p.test_agg");
+        assert_eq!(functions[0].aliases, vec!["p.test_alias".to_string()]);
+
+        let renamed = RenamedAggregateUDF {
+            name: "p.test_agg".to_string(),
+            aliases: vec!["p.test_alias".to_string()],
+            inner: AggregateUDF::new_from_impl(test_aggregate()),
+        };
+        assert_eq!(renamed.aliases(), ["p.test_alias".to_string()]);
+        assert!(renamed.groups_accumulator_supported());
+        assert_eq!(
+            renamed.state_fields("p.test_agg", DataType::Int64, vec![])?,
+            vec![
+                Field::new("sum", DataType::Int64, true),
+                Field::new("count", DataType::UInt64, true),
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_one_dialect_per_session() -> Result<()> {
+        let postgres_ctx = SessionContext::new();
+        register_udfs(
+            &postgres_ctx,
+            Dialect::Postgres,
+            &RegisterOptions::default(),
+        )?;
+        let sqlite_ctx = SessionContext::new();
+        register_udfs(&sqlite_ctx, Dialect::Sqlite, &RegisterOptions::default())?;
+
+        let df = postgres_ctx.sql("select mod(7, 3) as result").await?;
+        let batches = df.clone().collect().await?;
+        let expected = [
+            "+--------+",
+            "| result |",
+            "+--------+",
+            "| 1      |",
+            "+--------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = sqlite_ctx.sql("select mod(7, 3) as result").await?;
+        let batches = df.clone().collect().await?;
+        let expected = [
+            "+--------+",
+            "| result |",
+            "+--------+",
+            "| 1.0    |",
+            "+--------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert!(postgres_ctx.sql("select soundex('Robert')").await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_builtins_and_lists() -> Result<()> {
+        let ctx = SessionContext::new();
+        let options = RegisterOptions::default()
+            .with_shadow_builtins(false)
+            .with_allow_list(["sqrt", "soundex", "unicode"])
+            .with_deny_list(["unicode"]);
+        register_sqlite_udfs_with_options(&ctx, &options)?;
+
+        let df = ctx
+            .sql("select sqrt(-1) as sqrt, soundex('Robert') as soundex")
+            .await?;
+        let batches = df.clone().collect().await?;
+        let expected = [
+            "+------+---------+",
+            "| sqrt | soundex |",
+            "+------+---------+",
+            "| NaN  | R163    |",
+            "+------+---------+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert!(ctx.sql("select unicode('A')").await.is_err());
+        assert!(ctx.sql("select quote('A')").await.is_err());
+        assert!(register_sqlite_udfs_with_options(
+            &ctx,
+            &RegisterOptions::default().with_prefix("")
+        )
+        .is_err());
+        Ok(())
+    }
+}
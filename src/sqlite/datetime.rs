@@ -0,0 +1,1194 @@
+//! SQLite's date and time values, as its date and time functions read and print them.
+//!
+//! A date and time is given as a time value followed by modifiers. The time value is an ISO
+//! 8601 string, 'now', or a number, which is a Julian day number unless a modifier says it
+//! is a unix timestamp. Each modifier then moves the date or changes how it is interpreted,
+//! in order. Internally the date is kept, like SQLite keeps it, as the Julian day number in
+//! milliseconds alongside the calendar fields, either of which is recomputed from the other
+//! on demand. Following SQLite's arithmetic step for step keeps the results, rounding and
+//! out of range cases included, the same as SQLite's.
+
+use chrono::{Datelike, Local, TimeZone, Timelike, Utc};
+
+use crate::sqlite::printf::printf;
+use crate::sqlite::value::{is_space, parse_number, SqliteValue};
+
+/// Milliseconds in a day.
+const DAY_MS: i64 = 86_400_000;
+
+/// The Julian day number of 1970-01-01 00:00:00, in milliseconds.
+const UNIX_EPOCH_MS: i64 = 210_866_760_000_000;
+
+/// The largest Julian day number SQLite handles, 9999-12-31 23:59:59.999, in milliseconds.
+const MAX_JULIAN_DAY_MS: i64 = 464_269_060_799_999;
+
+/// The units of "NNN units" modifiers: the name, the largest magnitude allowed and the
+/// seconds in a unit. SQLite stores the last two as C floats, hence the f32s.
+const UNITS: [(&str, f32, f32); 6] = [
+    ("second", 4.6427e+14, 1.0),
+    ("minute", 7.7379e+12, 60.0),
+    ("hour", 1.2897e+11, 3600.0),
+    ("day", 5373485.0, 86400.0),
+    ("month", 176546.0, 2592000.0),
+    ("year", 14713.0, 31536000.0),
+];
+
+/// The current time as a Julian day number in milliseconds, which is what 'now' reads as.
+pub(crate) fn current_time() -> i64 {
+    UNIX_EPOCH_MS + Utc::now().timestamp_millis()
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct DateTime {
+    /// The Julian day number times 86400000.
+    julian_day_ms: i64,
+    year: i32,
+    month: i32,
+    day: i32,
+    hour: i32,
+    minute: i32,
+    /// The timezone offset in minutes.
+    tz: i32,
+    seconds: f64,
+    valid_jd: bool,
+    valid_ymd: bool,
+    valid_hms: bool,
+    /// The days "floor" goes back to undo a day of the month past the end of the month.
+    floor_days: i32,
+    /// `seconds` holds the number the date was given as, not yet interpreted.
+    raw_seconds: bool,
+    is_error: bool,
+    use_subsec: bool,
+    is_utc: bool,
+    is_local: bool,
+}
+
+impl DateTime {
+    /// Reads a time value followed by modifiers, the arguments of the date and time
+    /// functions. `now` is the Julian day number in milliseconds that 'now' stands for, and
+    /// no arguments at all also mean 'now'. Returns None where SQLite returns NULL.
+    pub(crate) fn parse(args: &[SqliteValue], now: i64) -> Option<DateTime> {
+        let mut date = DateTime::default();
+        match args.first() {
+            None => date.set_to_current(now),
+            Some(value @ (SqliteValue::Integer(_) | SqliteValue::Real(_))) => {
+                date.set_raw_number(value.to_real())
+            }
+            Some(value) => date.parse_date_or_time(&value.to_text()?, now)?,
+        }
+        for (index, modifier) in args.iter().enumerate().skip(1) {
+            date.apply_modifier(&modifier.to_text()?, index)?;
+        }
+        date.compute_jd();
+        if date.is_error || !is_valid_julian_day(date.julian_day_ms) {
+            return None;
+        }
+        // A lone YYYY-MM-DD past the end of its month, like 2023-02-31, is normalized.
+        if args.len() == 1 && date.valid_ymd && date.day > 28 {
+            date.valid_ymd = false;
+        }
+        Some(date)
+    }
+
+    /// The result of date(): YYYY-MM-DD.
+    pub(crate) fn date(mut self) -> String {
+        self.compute_ymd();
+        let sign = if self.year < 0 { "-" } else { "" };
+        format!(
+            "{sign}{:04}-{:02}-{:02}",
+            self.year.abs() % 10000,
+            self.month,
+            self.day
+        )
+    }
+
+    /// The result of time(): HH:MM:SS, or HH:MM:SS.SSS after the 'subsec' modifier.
+    pub(crate) fn time(mut self) -> String {
+        self.compute_hms();
+        format!(
+            "{:02}:{:02}:{}",
+            self.hour,
+            self.minute,
+            self.seconds_text()
+        )
+    }
+
+    /// The result of datetime(): YYYY-MM-DD HH:MM:SS, or YYYY-MM-DD HH:MM:SS.SSS after the
+    /// 'subsec' modifier.
+    pub(crate) fn datetime(mut self) -> String {
+        self.compute_ymd_hms();
+        format!("{} {}", self.date(), self.time())
+    }
+
+    /// The result of julianday().
+    pub(crate) fn julian_day(self) -> f64 {
+        self.julian_day_ms as f64 / DAY_MS as f64
+    }
+
+    /// The result of unixepoch() in whole seconds.
+    pub(crate) fn unix_epoch(self) -> i64 {
+        self.julian_day_ms / 1000 - UNIX_EPOCH_MS / 1000
+    }
+
+    /// The result of unixepoch() after the 'subsec' modifier.
+    pub(crate) fn unix_epoch_subsec(self) -> f64 {
+        (self.julian_day_ms - UNIX_EPOCH_MS) as f64 / 1000.0
+    }
+
+    /// The result of strftime(), or None if `format` has an unknown substitution.
+    pub(crate) fn strftime(mut self, format: &str) -> Option<String> {
+        self.compute_jd();
+        self.compute_ymd_hms();
+        let integer = |format: &str, value: i32| {
+            printf(format, &[SqliteValue::Integer(value.into())]).unwrap_or_default()
+        };
+        let real = |format: &str, value: f64| {
+            printf(format, &[SqliteValue::Real(value)]).unwrap_or_default()
+        };
+
+        let mut out = String::with_capacity(format.len());
+        let mut rest = format;
+        while let Some(position) = rest.find('%') {
+            out.push_str(&rest[..position]);
+            let mut chars = rest[position + 1..].chars();
+            // A '%' that ends the format is an unknown substitution too.
+            let conversion = chars.next()?;
+            out.push_str(&match conversion {
+                'd' => integer("%02d", self.day),
+                'e' => integer("%2d", self.day),
+                'f' => real("%06.3f", self.seconds.min(59.999)),
+                'F' => format!(
+                    "{}-{}-{}",
+                    integer("%04d", self.year),
+                    integer("%02d", self.month),
+                    integer("%02d", self.day)
+                ),
+                'G' => integer("%04d", self.iso_week_thursday().year),
+                'g' => integer("%02d", self.iso_week_thursday().year % 100),
+                'H' => integer("%02d", self.hour),
+                'k' => integer("%2d", self.hour),
+                'I' => integer("%02d", self.hour_of_half_day()),
+                'l' => integer("%2d", self.hour_of_half_day()),
+                'j' => integer("%03d", self.days_after_jan01() + 1),
+                'J' => real("%.16g", self.julian_day()),
+                'm' => integer("%02d", self.month),
+                'M' => integer("%02d", self.minute),
+                'p' => (if self.hour >= 12 { "PM" } else { "AM" }).to_string(),
+                'P' => (if self.hour >= 12 { "pm" } else { "am" }).to_string(),
+                'R' => format!(
+                    "{}:{}",
+                    integer("%02d", self.hour),
+                    integer("%02d", self.minute)
+                ),
+                's' if self.use_subsec => real("%.3f", self.unix_epoch_subsec()),
+                's' => self.unix_epoch().to_string(),
+                'S' => integer("%02d", self.seconds as i32),
+                'T' => format!(
+                    "{}:{}:{}",
+                    integer("%02d", self.hour),
+                    integer("%02d", self.minute),
+                    integer("%02d", self.seconds as i32)
+                ),
+                'u' => match self.days_after_sunday() {
+                    0 => "7".to_string(),
+                    days => days.to_string(),
+                },
+                'w' => self.days_after_sunday().to_string(),
+                'U' => integer(
+                    "%02d",
+                    (self.days_after_jan01() - self.days_after_sunday() + 7) / 7,
+                ),
+                'V' => integer("%02d", self.iso_week_thursday().days_after_jan01() / 7 + 1),
+                'W' => integer(
+                    "%02d",
+                    (self.days_after_jan01() - self.days_after_monday() + 7) / 7,
+                ),
+                'Y' => integer("%04d", self.year),
+                '%' => "%".to_string(),
+                _ => return None,
+            });
+            rest = chars.as_str();
+        }
+        out.push_str(rest);
+        Some(out)
+    }
+
+    /// The result of timediff(): how much later this date is than `other`, as
+    /// (+|-)YYYY-MM-DD HH:MM:SS.SSS.
+    pub(crate) fn time_diff(mut self, mut other: DateTime) -> String {
+        self.compute_ymd_hms();
+        other.compute_ymd_hms();
+        let sign;
+        let mut years;
+        let mut months;
+        if self.julian_day_ms >= other.julian_day_ms {
+            sign = '+';
+            years = self.year - other.year;
+            if years != 0 {
+                other.year = self.year;
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            months = self.month - other.month;
+            if months < 0 {
+                years -= 1;
+                months += 12;
+            }
+            if months != 0 {
+                other.month = self.month;
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            while self.julian_day_ms < other.julian_day_ms {
+                months -= 1;
+                if months < 0 {
+                    months = 11;
+                    years -= 1;
+                }
+                other.month -= 1;
+                if other.month < 1 {
+                    other.month = 12;
+                    other.year -= 1;
+                }
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            self.julian_day_ms -= other.julian_day_ms;
+        } else {
+            sign = '-';
+            years = other.year - self.year;
+            if years != 0 {
+                other.year = self.year;
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            months = other.month - self.month;
+            if months < 0 {
+                years -= 1;
+                months += 12;
+            }
+            if months != 0 {
+                other.month = self.month;
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            while self.julian_day_ms > other.julian_day_ms {
+                months -= 1;
+                if months < 0 {
+                    months = 11;
+                    years -= 1;
+                }
+                other.month += 1;
+                if other.month > 12 {
+                    other.month = 1;
+                    other.year += 1;
+                }
+                other.valid_jd = false;
+                other.compute_jd();
+            }
+            self.julian_day_ms = other.julian_day_ms - self.julian_day_ms;
+        }
+        // Print the difference as a date and time counted from 0000-01-01 00:00:00.
+        self.julian_day_ms += 148_699_540_800_000;
+        self.clear_ymd_hms_tz();
+        self.compute_ymd_hms();
+        printf(
+            "%c%04d-%02d-%02d %02d:%02d:%06.3f",
+            &[
+                SqliteValue::Text(sign.to_string()),
+                SqliteValue::Integer(years.into()),
+                SqliteValue::Integer(months.into()),
+                SqliteValue::Integer((self.day - 1).into()),
+                SqliteValue::Integer(self.hour.into()),
+                SqliteValue::Integer(self.minute.into()),
+                SqliteValue::Real(self.seconds),
+            ],
+        )
+        .unwrap_or_default()
+    }
+
+    /// The seconds of time() and datetime(), with milliseconds after 'subsec'.
+    fn seconds_text(&self) -> String {
+        if self.use_subsec {
+            let milliseconds = (1000.0 * self.seconds + 0.5) as i32;
+            format!(
+                "{:02}.{:03}",
+                milliseconds / 1000 % 100,
+                milliseconds % 1000
+            )
+        } else {
+            format!("{:02}", self.seconds as i32 % 100)
+        }
+    }
+
+    fn set_to_current(&mut self, now: i64) {
+        self.julian_day_ms = now;
+        self.valid_jd = true;
+        self.is_utc = true;
+        self.is_local = false;
+        self.clear_ymd_hms_tz();
+    }
+
+    /// Keeps a numeric time value for the modifiers to interpret, reading it as a Julian day
+    /// number until they do.
+    fn set_raw_number(&mut self, number: f64) {
+        self.seconds = number;
+        self.raw_seconds = true;
+        if (0.0..5373484.5).contains(&number) {
+            self.julian_day_ms = (number * DAY_MS as f64 + 0.5) as i64;
+            self.valid_jd = true;
+        }
+    }
+
+    fn parse_date_or_time(&mut self, text: &str, now: i64) -> Option<()> {
+        if self.parse_yyyy_mm_dd(text.as_bytes()).is_some()
+            || self.parse_hh_mm_ss(text.as_bytes()).is_some()
+        {
+            return Some(());
+        }
+        if text.eq_ignore_ascii_case("now") {
+            self.set_to_current(now);
+        } else if let Some(number) = parse_number(text) {
+            self.set_raw_number(number.to_real());
+        } else if text.eq_ignore_ascii_case("subsec") || text.eq_ignore_ascii_case("subsecond") {
+            self.use_subsec = true;
+            self.set_to_current(now);
+        } else {
+            return None;
+        }
+        Some(())
+    }
+
+    /// Reads [-]YYYY-MM-DD, optionally followed by a time after spaces or a 'T'.
+    fn parse_yyyy_mm_dd(&mut self, text: &[u8]) -> Option<()> {
+        let (negative, text) = match text.split_first() {
+            Some((b'-', rest)) => (true, rest),
+            _ => (false, text),
+        };
+        let year = read_digits(text, 4, 0, 14712)?;
+        expect(text, 4, b'-')?;
+        let month = read_digits(&text[5..], 2, 1, 12)?;
+        expect(text, 7, b'-')?;
+        let day = read_digits(&text[8..], 2, 1, 31)?;
+        let mut rest = &text[10..];
+        while let Some((&c, tail)) = rest.split_first() {
+            if !is_space(c as char) && c != b'T' {
+                break;
+            }
+            rest = tail;
+        }
+        if self.parse_hh_mm_ss(rest).is_none() {
+            if !rest.is_empty() {
+                return None;
+            }
+            self.valid_hms = false;
+        }
+        self.valid_jd = false;
+        self.valid_ymd = true;
+        self.year = if negative { -year } else { year };
+        self.month = month;
+        self.day = day;
+        self.compute_floor();
+        if self.tz != 0 {
+            self.compute_jd();
+        }
+        Some(())
+    }
+
+    /// Reads HH:MM, HH:MM:SS or HH:MM:SS.FFF, optionally followed by a timezone.
+    fn parse_hh_mm_ss(&mut self, text: &[u8]) -> Option<()> {
+        let hour = read_digits(text, 2, 0, 24)?;
+        expect(text, 2, b':')?;
+        let minute = read_digits(&text[3..], 2, 0, 59)?;
+        let mut rest = &text[5..];
+        let mut seconds = 0;
+        let mut fraction = 0.0;
+        if let Some((b':', tail)) = rest.split_first() {
+            seconds = read_digits(tail, 2, 0, 59)?;
+            rest = &tail[2..];
+            if rest.first() == Some(&b'.') && rest.get(1).is_some_and(u8::is_ascii_digit) {
+                rest = &rest[1..];
+                let mut scale = 1.0;
+                while let Some((&digit, tail)) = rest.split_first() {
+                    if !digit.is_ascii_digit() {
+                        break;
+                    }
+                    fraction = fraction * 10.0 + f64::from(digit - b'0');
+                    scale *= 10.0;
+                    rest = tail;
+                }
+                // Truncated so that rounding to milliseconds cannot carry into the minute.
+                fraction = (fraction / scale).min(0.999);
+            }
+        }
+        self.valid_jd = false;
+        self.raw_seconds = false;
+        self.valid_hms = true;
+        self.hour = hour;
+        self.minute = minute;
+        self.seconds = f64::from(seconds) + fraction;
+        self.parse_timezone(rest)
+    }
+
+    /// Reads an optional [+-]HH:MM or Z timezone, which must end the text.
+    fn parse_timezone(&mut self, text: &[u8]) -> Option<()> {
+        let text = skip_spaces(text);
+        self.tz = 0;
+        let rest = match text.first() {
+            Some(&sign @ (b'+' | b'-')) => {
+                let hours = read_digits(&text[1..], 2, 0, 14)?;
+                expect(text, 3, b':')?;
+                let minutes = read_digits(&text[4..], 2, 0, 59)?;
+                let offset = minutes + hours * 60;
+                self.tz = if sign == b'-' { -offset } else { offset };
+                &text[6..]
+            }
+            Some(b'Z' | b'z') => {
+                self.is_local = false;
+                self.is_utc = true;
+                &text[1..]
+            }
+            Some(_) => return None,
+            None => return Some(()),
+        };
+        skip_spaces(rest).is_empty().then_some(())
+    }
+
+    /// Applies the modifier at `index` in the arguments. Returns None if it is not a valid
+    /// modifier at that position.
+    fn apply_modifier(&mut self, modifier: &str, index: usize) -> Option<()> {
+        let modifier = modifier.to_ascii_lowercase();
+        match modifier.as_str() {
+            "auto" => {
+                if index > 1 {
+                    return None;
+                }
+                self.auto_adjust();
+            }
+            "ceiling" => {
+                self.compute_jd();
+                self.clear_ymd_hms_tz();
+                self.floor_days = 0;
+            }
+            "floor" => {
+                self.compute_jd();
+                self.julian_day_ms -= i64::from(self.floor_days) * DAY_MS;
+                self.clear_ymd_hms_tz();
+            }
+            "julianday" => {
+                if index > 1 || !self.valid_jd || !self.raw_seconds {
+                    return None;
+                }
+                self.raw_seconds = false;
+            }
+            "localtime" => {
+                if !self.is_local {
+                    self.convert_to_localtime()?;
+                }
+                self.is_utc = false;
+                self.is_local = true;
+            }
+            "unixepoch" => {
+                if !self.raw_seconds || index > 1 {
+                    return None;
+                }
+                let julian_day_ms = self.seconds * 1000.0 + UNIX_EPOCH_MS as f64;
+                if !(0.0..(MAX_JULIAN_DAY_MS + 1) as f64).contains(&julian_day_ms) {
+                    return None;
+                }
+                self.clear_ymd_hms_tz();
+                self.julian_day_ms = (julian_day_ms + 0.5) as i64;
+                self.valid_jd = true;
+                self.raw_seconds = false;
+            }
+            "utc" => {
+                if !self.is_utc {
+                    self.convert_to_utc()?;
+                }
+            }
+            "subsec" | "subsecond" => self.use_subsec = true,
+            _ => {
+                if let Some(weekday) = modifier.strip_prefix("weekday ") {
+                    self.move_to_weekday(weekday)?;
+                } else if let Some(unit) = modifier.strip_prefix("start of ") {
+                    self.move_to_start_of(unit)?;
+                } else if modifier.starts_with(|c: char| c == '+' || c == '-' || c.is_ascii_digit())
+                {
+                    self.add_offset(modifier.as_bytes())?;
+                } else {
+                    return None;
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Interprets a numeric time value as a Julian day number or, if it is out of the range
+    /// of those, a unix timestamp.
+    fn auto_adjust(&mut self) {
+        if !self.raw_seconds || self.valid_jd {
+            self.raw_seconds = false;
+        } else if (-210_866_760_000.0..=253_402_300_799.0).contains(&self.seconds) {
+            let julian_day_ms = self.seconds * 1000.0 + UNIX_EPOCH_MS as f64;
+            self.clear_ymd_hms_tz();
+            self.julian_day_ms = (julian_day_ms + 0.5) as i64;
+            self.valid_jd = true;
+            self.raw_seconds = false;
+        }
+    }
+
+    /// Moves forward to the next day that is weekday N, 0 being Sunday, unless the date
+    /// already is one.
+    fn move_to_weekday(&mut self, weekday: &str) -> Option<()> {
+        let weekday = parse_number(weekday)?.to_real();
+        if !(0.0..7.0).contains(&weekday) || weekday.fract() != 0.0 {
+            return None;
+        }
+        let weekday = weekday as i64;
+        self.compute_ymd_hms();
+        self.tz = 0;
+        self.valid_jd = false;
+        self.compute_jd();
+        let mut current = ((self.julian_day_ms + 129_600_000) / DAY_MS) % 7;
+        if current > weekday {
+            current -= 7;
+        }
+        self.julian_day_ms += (weekday - current) * DAY_MS;
+        self.clear_ymd_hms_tz();
+        Some(())
+    }
+
+    fn move_to_start_of(&mut self, unit: &str) -> Option<()> {
+        if !self.valid_jd && !self.valid_ymd && !self.valid_hms {
+            return None;
+        }
+        self.compute_ymd();
+        self.valid_hms = true;
+        self.hour = 0;
+        self.minute = 0;
+        self.seconds = 0.0;
+        self.raw_seconds = false;
+        self.tz = 0;
+        self.valid_jd = false;
+        match unit {
+            "month" => self.day = 1,
+            "year" => {
+                self.month = 1;
+                self.day = 1;
+            }
+            "day" => {}
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Applies a "NNN units", "(+|-)HH:MM[:SS[.FFF]]" or
+    /// "(+|-)YYYY-MM-DD[ HH:MM[:SS[.FFF]]]" modifier.
+    fn add_offset(&mut self, modifier: &[u8]) -> Option<()> {
+        let sign = modifier[0];
+        let mut length = 1;
+        while length < modifier.len() {
+            let c = modifier[length];
+            if c == b':' || is_space(c as char) {
+                break;
+            }
+            if c == b'-'
+                && (length == 5 || length == 6)
+                && read_digits(&modifier[1..], length - 1, 0, 14712).is_some()
+            {
+                break;
+            }
+            length += 1;
+        }
+        let amount = parse_number(std::str::from_utf8(&modifier[..length]).ok()?)?.to_real();
+
+        let mut time = modifier;
+        if modifier.get(length) == Some(&b'-') {
+            // (+|-)YYYY-MM-DD adds or subtracts years, months and days, with months limited
+            // to 0-11 and days to 0-30.
+            if sign != b'+' && sign != b'-' {
+                return None;
+            }
+            let years = read_digits(&modifier[1..], length - 1, 0, 14712)?;
+            let months = read_digits(&modifier[length + 1..], 2, 0, 12)?;
+            expect(modifier, length + 3, b'-')?;
+            let mut days = read_digits(&modifier[length + 4..], 2, 0, 31)?;
+            if months >= 12 || days >= 31 {
+                return None;
+            }
+            self.compute_ymd_hms();
+            self.valid_jd = false;
+            if sign == b'-' {
+                self.year -= years;
+                self.month -= months;
+                days = -days;
+            } else {
+                self.year += years;
+                self.month += months;
+            }
+            self.normalize_month();
+            self.compute_floor();
+            self.compute_jd();
+            self.valid_hms = false;
+            self.valid_ymd = false;
+            self.julian_day_ms += i64::from(days) * DAY_MS;
+            match modifier.get(length + 6) {
+                None => return Some(()),
+                Some(&c)
+                    if is_space(c as char)
+                        && read_digits(&modifier[length + 7..], 2, 0, 24).is_some()
+                        && modifier.get(length + 9) == Some(&b':')
+                        && read_digits(&modifier[length + 10..], 2, 0, 59).is_some() =>
+                {
+                    time = &modifier[length + 7..];
+                    length = 2;
+                }
+                Some(_) => return None,
+            }
+        }
+
+        if time.get(length) == Some(&b':') {
+            // (+|-)HH:MM:SS.FFF adds or subtracts a time of day, where ":SS.FFF" and ".FFF"
+            // may be left out.
+            let time = if time[0].is_ascii_digit() {
+                time
+            } else {
+                &time[1..]
+            };
+            let mut offset = DateTime::default();
+            offset.parse_hh_mm_ss(time)?;
+            offset.compute_jd();
+            offset.julian_day_ms -= 43_200_000;
+            offset.julian_day_ms %= DAY_MS;
+            if sign == b'-' {
+                offset.julian_day_ms = -offset.julian_day_ms;
+            }
+            self.compute_jd();
+            self.clear_ymd_hms_tz();
+            self.julian_day_ms += offset.julian_day_ms;
+            return Some(());
+        }
+
+        let unit = std::str::from_utf8(skip_spaces(&modifier[length..])).ok()?;
+        if unit.len() < 3 || unit.len() > 10 {
+            return None;
+        }
+        let unit = unit.strip_suffix('s').unwrap_or(unit);
+        self.compute_jd();
+        let rounder = if amount < 0.0 { -0.5 } else { 0.5 };
+        self.floor_days = 0;
+        let result = UNITS.iter().position(|&(name, limit, _)| {
+            name == unit && amount > -f64::from(limit) && amount < f64::from(limit)
+        });
+        if let Some(position) = result {
+            let mut amount = amount;
+            match UNITS[position].0 {
+                "month" => {
+                    self.compute_ymd_hms();
+                    self.month += amount as i32;
+                    self.normalize_month();
+                    self.compute_floor();
+                    self.valid_jd = false;
+                    amount -= f64::from(amount as i32);
+                }
+                "year" => {
+                    self.compute_ymd_hms();
+                    self.year += amount as i32;
+                    self.compute_floor();
+                    self.valid_jd = false;
+                    amount -= f64::from(amount as i32);
+                }
+                _ => {}
+            }
+            self.compute_jd();
+            let seconds = f64::from(UNITS[position].2);
+            self.julian_day_ms += (amount * 1000.0 * seconds + rounder) as i64;
+        }
+        self.clear_ymd_hms_tz();
+        result.map(|_| ())
+    }
+
+    /// Brings the month back into 1-12, carrying whole years into the year.
+    fn normalize_month(&mut self) {
+        let years = if self.month > 0 {
+            (self.month - 1) / 12
+        } else {
+            (self.month - 12) / 12
+        };
+        self.year += years;
+        self.month -= years * 12;
+    }
+
+    /// Reads the date as UTC and moves it to local time. Like SQLite, dates outside of
+    /// 1970-2037 are converted in an equivalent year within that range.
+    fn convert_to_localtime(&mut self) -> Option<()> {
+        self.compute_jd();
+        let (timestamp, year_diff) = if self.julian_day_ms < 210_866_760_000_000
+            || self.julian_day_ms > 213_014_145_600_000
+        {
+            let mut date = *self;
+            date.compute_ymd_hms();
+            let year_diff = (2000 + date.year % 4) - date.year;
+            date.year += year_diff;
+            date.valid_jd = false;
+            date.compute_jd();
+            (date.julian_day_ms / 1000 - UNIX_EPOCH_MS / 1000, year_diff)
+        } else {
+            (self.julian_day_ms / 1000 - UNIX_EPOCH_MS / 1000, 0)
+        };
+        let local = Local.timestamp_opt(timestamp, 0).single()?.naive_local();
+        self.year = local.year() - year_diff;
+        self.month = local.month() as i32;
+        self.day = local.day() as i32;
+        self.hour = local.hour() as i32;
+        self.minute = local.minute() as i32;
+        self.seconds = f64::from(local.second()) + (self.julian_day_ms % 1000) as f64 * 0.001;
+        self.valid_ymd = true;
+        self.valid_hms = true;
+        self.valid_jd = false;
+        self.raw_seconds = false;
+        self.tz = 0;
+        self.is_error = false;
+        Some(())
+    }
+
+    /// Reads the date as local time and moves it to UTC, by guessing the UTC time and
+    /// correcting the guess by how far its local time is off.
+    fn convert_to_utc(&mut self) -> Option<()> {
+        self.compute_jd();
+        let original = self.julian_day_ms;
+        let mut guess = original;
+        let mut error = 0;
+        for _ in 0..4 {
+            guess -= error;
+            let mut local = DateTime {
+                julian_day_ms: guess,
+                valid_jd: true,
+                ..DateTime::default()
+            };
+            local.convert_to_localtime()?;
+            local.compute_jd();
+            error = local.julian_day_ms - original;
+            if error == 0 {
+                break;
+            }
+        }
+        *self = DateTime {
+            julian_day_ms: guess,
+            valid_jd: true,
+            is_utc: true,
+            ..DateTime::default()
+        };
+        Some(())
+    }
+
+    fn compute_jd(&mut self) {
+        if self.valid_jd {
+            return;
+        }
+        let (mut year, mut month, day) = if self.valid_ymd {
+            (self.year, self.month, self.day)
+        } else {
+            // Without a date, a time is on 2000-01-01.
+            (2000, 1, 1)
+        };
+        if !(-4713..=9999).contains(&year) || self.raw_seconds {
+            self.set_error();
+            return;
+        }
+        if month <= 2 {
+            year -= 1;
+            month += 12;
+        }
+        // Offset so that the century is not rounded towards zero for negative years.
+        let a = (year + 4800) / 100;
+        let b = 38 - a + (a / 4);
+        let x1 = 36525 * (year + 4716) / 100;
+        let x2 = 306001 * (month + 1) / 10000;
+        self.julian_day_ms = ((f64::from(x1 + x2 + day + b) - 1524.5) * DAY_MS as f64) as i64;
+        self.valid_jd = true;
+        if self.valid_hms {
+            self.julian_day_ms += i64::from(self.hour) * 3_600_000
+                + i64::from(self.minute) * 60_000
+                + (self.seconds * 1000.0 + 0.5) as i64;
+            if self.tz != 0 {
+                self.julian_day_ms -= i64::from(self.tz) * 60_000;
+                self.valid_ymd = false;
+                self.valid_hms = false;
+                self.tz = 0;
+                self.is_utc = true;
+                self.is_local = false;
+            }
+        }
+    }
+
+    fn compute_ymd(&mut self) {
+        if self.valid_ymd {
+            return;
+        }
+        if !self.valid_jd {
+            self.year = 2000;
+            self.month = 1;
+            self.day = 1;
+        } else if !is_valid_julian_day(self.julian_day_ms) {
+            self.set_error();
+            return;
+        } else {
+            let z = ((self.julian_day_ms + 43_200_000) / DAY_MS) as i32;
+            let a = ((f64::from(z) - 1867216.25) / 36524.25) as i32;
+            let a = z + 1 + a - (a / 4);
+            let b = a + 1524;
+            let c = ((f64::from(b) - 122.1) / 365.25) as i32;
+            let d = (36525 * (c & 32767)) / 100;
+            let e = (f64::from(b - d) / 30.6001) as i32;
+            let x1 = (30.6001 * f64::from(e)) as i32;
+            self.day = b - d - x1;
+            self.month = if e < 14 { e - 1 } else { e - 13 };
+            self.year = if self.month > 2 { c - 4716 } else { c - 4715 };
+        }
+        self.valid_ymd = true;
+    }
+
+    fn compute_hms(&mut self) {
+        if self.valid_hms {
+            return;
+        }
+        self.compute_jd();
+        let day_ms = ((self.julian_day_ms + 43_200_000) % DAY_MS) as i32;
+        self.seconds = f64::from(day_ms % 60_000) / 1000.0;
+        let day_minutes = day_ms / 60_000;
+        self.minute = day_minutes % 60;
+        self.hour = day_minutes / 60;
+        self.raw_seconds = false;
+        self.valid_hms = true;
+    }
+
+    fn compute_ymd_hms(&mut self) {
+        self.compute_ymd();
+        self.compute_hms();
+    }
+
+    /// Works out how many days "floor" takes off to turn a day past the end of the month,
+    /// which otherwise rolls over into the next month, into the last day of the month.
+    fn compute_floor(&mut self) {
+        self.floor_days = if self.day <= 28 || (1 << self.month) & 0x15aa != 0 {
+            0
+        } else if self.month != 2 {
+            i32::from(self.day == 31)
+        } else if self.year % 4 != 0 || (self.year % 100 == 0 && self.year % 400 != 0) {
+            self.day - 28
+        } else {
+            self.day - 29
+        };
+    }
+
+    fn clear_ymd_hms_tz(&mut self) {
+        self.valid_ymd = false;
+        self.valid_hms = false;
+        self.tz = 0;
+    }
+
+    fn set_error(&mut self) {
+        *self = DateTime {
+            is_error: true,
+            ..DateTime::default()
+        };
+    }
+
+    fn hour_of_half_day(&self) -> i32 {
+        match self.hour {
+            0 => 12,
+            hour if hour > 12 => hour - 12,
+            hour => hour,
+        }
+    }
+
+    fn days_after_jan01(&self) -> i32 {
+        let mut jan01 = *self;
+        jan01.valid_jd = false;
+        jan01.month = 1;
+        jan01.day = 1;
+        jan01.compute_jd();
+        ((self.julian_day_ms - jan01.julian_day_ms + 43_200_000) / DAY_MS) as i32
+    }
+
+    fn days_after_monday(&self) -> i32 {
+        (((self.julian_day_ms + 43_200_000) / DAY_MS) % 7) as i32
+    }
+
+    fn days_after_sunday(&self) -> i32 {
+        (((self.julian_day_ms + 129_600_000) / DAY_MS) % 7) as i32
+    }
+
+    /// The Thursday of the ISO 8601 week the date is in, which decides the week's year.
+    fn iso_week_thursday(&self) -> DateTime {
+        let mut thursday = *self;
+        thursday.julian_day_ms += i64::from(3 - self.days_after_monday()) * DAY_MS;
+        thursday.valid_ymd = false;
+        thursday.compute_ymd();
+        thursday
+    }
+}
+
+fn is_valid_julian_day(julian_day_ms: i64) -> bool {
+    (0..=MAX_JULIAN_DAY_MS).contains(&julian_day_ms)
+}
+
+/// Reads exactly `count` digits at the start of `text` as a number from `min` to `max`.
+fn read_digits(text: &[u8], count: usize, min: i32, max: i32) -> Option<i32> {
+    let digits = text.get(..count)?;
+    if !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let value = digits
+        .iter()
+        .fold(0, |value, digit| value * 10 + i32::from(digit - b'0'));
+    (min..=max).contains(&value).then_some(value)
+}
+
+fn expect(text: &[u8], position: usize, separator: u8) -> Option<()> {
+    (text.get(position) == Some(&separator)).then_some(())
+}
+
+fn skip_spaces(text: &[u8]) -> &[u8] {
+    let spaces = text.iter().take_while(|&&c| is_space(c as char)).count();
+    &text[spaces..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2023-06-15 12:00:00.250 as a Julian day number in milliseconds.
+    const NOW: i64 = 2460111 * DAY_MS + 250;
+
+    fn parse(args: &[SqliteValue]) -> Option<DateTime> {
+        DateTime::parse(args, NOW)
+    }
+
+    fn text(args: &[&str]) -> Vec<SqliteValue> {
+        args.iter()
+            .map(|arg| SqliteValue::Text(arg.to_string()))
+            .collect()
+    }
+
+    fn datetime(args: &[&str]) -> Option<String> {
+        parse(&text(args)).map(DateTime::datetime)
+    }
+
+    fn strftime(format: &str, args: &[&str]) -> Option<String> {
+        parse(&text(args)).and_then(|date| date.strftime(format))
+    }
+
+    fn time_diff(left: &str, right: &str) -> Option<String> {
+        Some(parse(&text(&[left]))?.time_diff(parse(&text(&[right]))?))
+    }
+
+    #[test]
+    fn test_time_values() {
+        assert_eq!(
+            datetime(&["2023-01-01T10:00:00.5+05:30", "subsec"]).as_deref(),
+            Some("2023-01-01 04:30:00.500")
+        );
+        assert_eq!(
+            parse(&text(&["2023-02-31"])).map(DateTime::date).as_deref(),
+            Some("2023-03-03")
+        );
+        assert_eq!(
+            parse(&text(&["12:34:56.789"]))
+                .map(DateTime::time)
+                .as_deref(),
+            Some("12:34:56")
+        );
+        assert_eq!(
+            parse(&text(&["-0044-03-15", "+1 day"]))
+                .map(DateTime::date)
+                .as_deref(),
+            Some("-0044-03-16")
+        );
+        assert_eq!(
+            parse(&[SqliteValue::Real(2460000.5)])
+                .map(DateTime::datetime)
+                .as_deref(),
+            Some("2023-02-25 00:00:00")
+        );
+        assert_eq!(
+            parse(&text(&["2000-01-01 12:00"])).map(DateTime::julian_day),
+            Some(2451545.0)
+        );
+        assert_eq!(
+            parse(&text(&["2023-06-15 10:11:12.345"])).map(DateTime::unix_epoch),
+            Some(1686823872)
+        );
+        assert_eq!(
+            parse(&text(&["2023-06-15 10:11:12.345", "subsec"])).map(DateTime::unix_epoch_subsec),
+            Some(1686823872.345)
+        );
+
+        assert_eq!(datetime(&["now"]).as_deref(), Some("2023-06-15 12:00:00"));
+        assert_eq!(
+            parse(&[]).map(DateTime::datetime).as_deref(),
+            Some("2023-06-15 12:00:00")
+        );
+        assert_eq!(
+            datetime(&["subsec"]).as_deref(),
+            Some("2023-06-15 12:00:00.250")
+        );
+
+        assert_eq!(datetime(&["2023-13-01"]), None);
+        assert_eq!(datetime(&["2023-1-01"]), None);
+        assert_eq!(datetime(&["abc"]), None);
+        assert_eq!(parse(&[SqliteValue::Null]).map(DateTime::datetime), None);
+        // Numbers are Julian day numbers unless a modifier says otherwise.
+        assert_eq!(
+            parse(&[SqliteValue::Integer(1700000000)]).map(DateTime::datetime),
+            None
+        );
+    }
+
+    #[test]
+    fn test_modifiers() {
+        assert_eq!(
+            datetime(&["2023-01-31 10:20:30", "+1 month"]).as_deref(),
+            Some("2023-03-03 10:20:30")
+        );
+        assert_eq!(
+            datetime(&["2023-01-31", "+1 month", "floor"]).as_deref(),
+            Some("2023-02-28 00:00:00")
+        );
+        assert_eq!(
+            datetime(&["2024-02-29", "+1 year"]).as_deref(),
+            Some("2025-03-01 00:00:00")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15", "+1.5 days"]).as_deref(),
+            Some("2023-06-16 12:00:00")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:00", "-3600 seconds"]).as_deref(),
+            Some("2023-06-15 09:00:00")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:11:12", "+01:30"]).as_deref(),
+            Some("2023-06-15 11:41:12")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:11:12", "-0001-02-03 04:05"]).as_deref(),
+            Some("2022-04-12 06:06:12")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:11:12", "start of month"]).as_deref(),
+            Some("2023-06-01 00:00:00")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:11:12", "start of year"]).as_deref(),
+            Some("2023-01-01 00:00:00")
+        );
+        assert_eq!(
+            parse(&text(&["2023-06-15", "weekday 0"]))
+                .map(DateTime::date)
+                .as_deref(),
+            Some("2023-06-18")
+        );
+        assert_eq!(
+            parse(&text(&["2023-06-18", "weekday 0"]))
+                .map(DateTime::date)
+                .as_deref(),
+            Some("2023-06-18")
+        );
+        assert_eq!(
+            datetime(&["2023-06-15 10:11:12", "localtime", "utc"]).as_deref(),
+            Some("2023-06-15 10:11:12")
+        );
+
+        let unix_epoch = |modifier: &str| {
+            parse(&[
+                SqliteValue::Integer(1700000000),
+                SqliteValue::Text(modifier.to_string()),
+            ])
+            .map(DateTime::datetime)
+        };
+        assert_eq!(
+            unix_epoch("unixepoch").as_deref(),
+            Some("2023-11-14 22:13:20")
+        );
+        assert_eq!(unix_epoch("auto").as_deref(), Some("2023-11-14 22:13:20"));
+        assert_eq!(unix_epoch("julianday"), None);
+        assert_eq!(
+            parse(&[
+                SqliteValue::Real(2460000.5),
+                SqliteValue::Text("auto".to_string()),
+            ])
+            .map(DateTime::datetime)
+            .as_deref(),
+            Some("2023-02-25 00:00:00")
+        );
+
+        // 'unixepoch' must come first, and the result must stay within 0000-9999.
+        assert_eq!(
+            parse(&[
+                SqliteValue::Integer(1700000000),
+                SqliteValue::Text("+1 day".to_string()),
+                SqliteValue::Text("unixepoch".to_string()),
+            ])
+            .map(DateTime::datetime),
+            None
+        );
+        assert_eq!(datetime(&["9999-12-31 23:59:59", "+1 second"]), None);
+        assert_eq!(datetime(&["2023-01-01", "weekday 7"]), None);
+        assert_eq!(datetime(&["2023-01-01", "+1 fortnight"]), None);
+        assert_eq!(datetime(&["2023-01-01", ""]), None);
+    }
+
+    #[test]
+    fn test_strftime() {
+        assert_eq!(
+            strftime("%Y-%W", &["2024-12-30"]).as_deref(),
+            Some("2024-53")
+        );
+        assert_eq!(
+            strftime("%G-W%V-%u", &["2024-12-30"]).as_deref(),
+            Some("2025-W01-1")
+        );
+        assert_eq!(
+            strftime("%d %e %j %U %w", &["2023-01-08"]).as_deref(),
+            Some("08  8 008 02 0")
+        );
+        assert_eq!(
+            strftime("%I:%M %p, %l %P, %k, %R, %T", &["2023-06-15 00:05:09"]).as_deref(),
+            Some("12:05 AM, 12 am,  0, 00:05, 00:05:09")
+        );
+        assert_eq!(
+            strftime("%F %H:%M:%f", &["2023-06-15 10:11:12.345"]).as_deref(),
+            Some("2023-06-15 10:11:12.345")
+        );
+        assert_eq!(
+            strftime("%s %J", &["2023-06-15 12:00"]).as_deref(),
+            Some("1686830400 2460111")
+        );
+        assert_eq!(
+            strftime("%s", &["2023-06-15 12:00:00.25", "subsec"]).as_deref(),
+            Some("1686830400.250")
+        );
+        assert_eq!(strftime("100%%", &[]).as_deref(), Some("100%"));
+        assert_eq!(strftime("%x", &["2023-01-01"]), None);
+        assert_eq!(strftime("%Y%", &["2023-01-01"]), None);
+    }
+
+    #[test]
+    fn test_time_diff() {
+        assert_eq!(
+            time_diff("2023-03-31", "2023-02-28").as_deref(),
+            Some("+0000-01-03 00:00:00.000")
+        );
+        assert_eq!(
+            time_diff("2023-02-28", "2023-03-31").as_deref(),
+            Some("-0000-01-03 00:00:00.000")
+        );
+        assert_eq!(
+            time_diff("2024-02-29 12:00", "2023-02-28 13:00:00.5").as_deref(),
+            Some("+0001-00-00 22:59:59.500")
+        );
+        assert_eq!(time_diff("2023-01-01", "x"), None);
+    }
+}
@@ -0,0 +1,660 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, ArrayRef, Float64Array, Int64Array, StringArray};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::DataType::{Float64, Int64, Null, Utf8};
+use datafusion::common::{ExprSchema, ScalarValue};
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::{Exact, VariadicAny};
+use datafusion::logical_expr::{ColumnarValue, Expr, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::sqlite::datetime::{current_time, DateTime};
+use crate::sqlite::scalar_udfs::get_sqlite_values;
+use crate::sqlite::value::SqliteValue;
+
+const DATE_FUNCTIONS_LINK: &str = "https://www.sqlite.org/lang_datefunc.html";
+
+/// The date() function returns the date as text in this format: YYYY-MM-DD.
+///
+/// Like all the date and time functions, it takes a time value followed by zero or more
+/// modifiers. The time value may be an ISO 8601 date and time, 'now', or a number, which is a
+/// Julian day number unless followed by the 'unixepoch' or 'auto' modifier. Without any
+/// arguments, the time value is 'now'. The result is NULL if the time value or any modifier is
+/// invalid.
+#[derive(Debug)]
+pub struct Date {
+    signature: Signature,
+}
+
+impl Date {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Exact(vec![]), VariadicAny], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Date {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "date"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let (args, rows) = get_arguments(args)?;
+        let result = get_dates(&args, rows)?
+            .into_iter()
+            .map(|date| date.map(DateTime::date))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Date {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "date(time-value, modifier, ...)",
+            description: "Returns the date as YYYY-MM-DD",
+            examples: &["date('2024-01-31', '+1 month') → 2024-03-02"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The time() function returns the time as text in this format: HH:MM:SS, or HH:MM:SS.SSS
+/// with the 'subsec' modifier.
+#[derive(Debug)]
+pub struct Time {
+    signature: Signature,
+}
+
+impl Time {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Exact(vec![]), VariadicAny], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Time {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "time"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let (args, rows) = get_arguments(args)?;
+        let result = get_dates(&args, rows)?
+            .into_iter()
+            .map(|date| date.map(DateTime::time))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Time {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "time(time-value, modifier, ...)",
+            description: "Returns the time as HH:MM:SS",
+            examples: &["time('2024-01-31 13:45:10', '+30 minutes') → 14:15:10"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The datetime() function returns the date and time as text in this format:
+/// YYYY-MM-DD HH:MM:SS, or YYYY-MM-DD HH:MM:SS.SSS with the 'subsec' modifier.
+#[derive(Debug)]
+pub struct Datetime {
+    signature: Signature,
+}
+
+impl Datetime {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Exact(vec![]), VariadicAny], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Datetime {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "datetime"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let (args, rows) = get_arguments(args)?;
+        let result = get_dates(&args, rows)?
+            .into_iter()
+            .map(|date| date.map(DateTime::datetime))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Datetime {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "datetime(time-value, modifier, ...)",
+            description: "Returns the date and time as YYYY-MM-DD HH:MM:SS",
+            examples: &["datetime(1700000000, 'unixepoch') → 2023-11-14 22:13:20"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The julianday() function returns the Julian day: the fractional number of days since noon
+/// in Greenwich on November 24, 4714 B.C. (Proleptic Gregorian calendar).
+#[derive(Debug)]
+pub struct JulianDay {
+    signature: Signature,
+}
+
+impl JulianDay {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Exact(vec![]), VariadicAny], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JulianDay {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "julianday"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let (args, rows) = get_arguments(args)?;
+        let result = get_dates(&args, rows)?
+            .into_iter()
+            .map(|date| date.map(DateTime::julian_day))
+            .collect::<Float64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for JulianDay {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "julianday(time-value, modifier, ...)",
+            description: "Returns the Julian day number, the fractional number of days since noon in Greenwich on November 24, 4714 B.C.",
+            examples: &["julianday('2000-01-01') → 2451544.5"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The unixepoch() function returns a unix timestamp: the number of seconds since
+/// 1970-01-01 00:00:00 UTC. The result is an integer, unless the 'subsec' modifier is given,
+/// in which case it is a real with millisecond precision.
+///
+/// Arrow columns have a single type, so 'subsec' only makes the result a real when it is
+/// given as a constant. A 'subsec' read from a column leaves the result in whole seconds.
+#[derive(Debug)]
+pub struct UnixEpoch {
+    signature: Signature,
+}
+
+impl UnixEpoch {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Exact(vec![]), VariadicAny], Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for UnixEpoch {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "unixepoch"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        _arg_types: &[DataType],
+    ) -> Result<DataType> {
+        let subsec = args.iter().any(|arg| match arg {
+            Expr::Literal(ScalarValue::Utf8(Some(modifier))) => is_subsec(modifier),
+            _ => false,
+        });
+        Ok(if subsec { Float64 } else { Int64 })
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let subsec = args.iter().any(|arg| match arg {
+            ColumnarValue::Scalar(ScalarValue::Utf8(Some(modifier))) => is_subsec(modifier),
+            _ => false,
+        });
+        let (args, rows) = get_arguments(args)?;
+        let dates = get_dates(&args, rows)?.into_iter();
+
+        let result = if subsec {
+            Arc::new(
+                dates
+                    .map(|date| date.map(DateTime::unix_epoch_subsec))
+                    .collect::<Float64Array>(),
+            ) as ArrayRef
+        } else {
+            Arc::new(
+                dates
+                    .map(|date| date.map(DateTime::unix_epoch))
+                    .collect::<Int64Array>(),
+            ) as ArrayRef
+        };
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+impl Documented for UnixEpoch {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "unixepoch(time-value, modifier, ...)",
+            description: "Returns the number of seconds since 1970-01-01 00:00:00 UTC",
+            examples: &["unixepoch('2024-01-01') → 1704067200"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The strftime(F, ...) function returns the date formatted according to the format string
+/// F, which may contain these substitutions:
+///
+/// | Substitution | Meaning                                             |
+/// |--------------|-----------------------------------------------------|
+/// | %d           | day of month: 01-31                                 |
+/// | %e           | day of month without leading zero: 1-31             |
+/// | %f           | fractional seconds: SS.SSS                          |
+/// | %F           | ISO 8601 date: YYYY-MM-DD                           |
+/// | %G           | ISO 8601 year corresponding to %V                   |
+/// | %g           | 2-digit ISO 8601 year corresponding to %V           |
+/// | %H           | hour: 00-24                                         |
+/// | %I           | hour for 12-hour clock: 01-12                       |
+/// | %j           | day of year: 001-366                                |
+/// | %J           | Julian day number (fractional)                      |
+/// | %k           | hour without leading zero: 0-24                     |
+/// | %l           | %I without leading zero: 1-12                       |
+/// | %m           | month: 01-12                                        |
+/// | %M           | minute: 00-59                                       |
+/// | %p           | "AM" or "PM" depending on the hour                  |
+/// | %P           | "am" or "pm" depending on the hour                  |
+/// | %R           | ISO 8601 time: HH:MM                                |
+/// | %s           | seconds since 1970-01-01                            |
+/// | %S           | seconds: 00-59                                      |
+/// | %T           | ISO 8601 time: HH:MM:SS                             |
+/// | %U           | week of year (00-53), week 01 starts on a Sunday    |
+/// | %u           | day of week 1-7 with Monday==1                      |
+/// | %V           | ISO 8601 week of year                               |
+/// | %w           | day of week 0-6 with Sunday==0                      |
+/// | %W           | week of year (00-53), week 01 starts on a Monday    |
+/// | %Y           | year: 0000-9999                                     |
+/// | %%           | %                                                   |
+///
+/// The result is NULL if the format has any other substitution.
+#[derive(Debug)]
+pub struct Strftime {
+    signature: Signature,
+}
+
+impl Strftime {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Strftime {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "strftime"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let formats = get_sqlite_values(&args[0])?;
+        let dates = get_dates(&args[1..], formats.len())?;
+
+        let result = formats
+            .iter()
+            .zip(dates)
+            .map(|(format, date)| date?.strftime(&format.to_text()?))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Strftime {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "strftime(format, time-value, modifier, ...)",
+            description:
+                "Returns the date formatted with the substitutions of format, such as %Y and %W",
+            examples: &["strftime('%Y week %W', '2024-03-15') → 2024 week 11"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The timediff(A,B) function returns how much time must be added to B to reach A, as text
+/// in this format: (+|-)YYYY-MM-DD HH:MM:SS.SSS. Both arguments are time values without
+/// modifiers.
+#[derive(Debug)]
+pub struct TimeDiff {
+    signature: Signature,
+}
+
+impl TimeDiff {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Stable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for TimeDiff {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "timediff"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let rows = args[0].len();
+        let left = get_dates(&args[..1], rows)?;
+        let right = get_dates(&args[1..], rows)?;
+
+        let result = left
+            .into_iter()
+            .zip(right)
+            .map(|(left, right)| Some(left?.time_diff(right?)))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for TimeDiff {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "timediff(time-value, time-value)",
+            description: "Returns the time to add to the second time value to reach the first, as +YYYY-MM-DD HH:MM:SS.SSS",
+            examples: &["timediff('2024-03-15', '2023-12-25') → +0000-02-19 00:00:00.000"],
+            link: Some(DATE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// Reads the arguments as arrays, along with the number of rows. DataFusion calls a function
+/// without arguments with a single NULL column that only carries the number of rows, which
+/// is told apart from a NULL argument by being an array rather than a constant.
+fn get_arguments(args: &[ColumnarValue]) -> Result<(Vec<ArrayRef>, usize)> {
+    if let [ColumnarValue::Array(array)] = args {
+        if array.data_type() == &Null {
+            return Ok((vec![], array.len()));
+        }
+    }
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let rows = args.first().map_or(1, |arg| arg.len());
+    Ok((args, rows))
+}
+
+/// Reads the time value and modifiers in each of the `rows` rows of `args`, with None where
+/// they are not valid. All rows read 'now' as the same time.
+fn get_dates(args: &[ArrayRef], rows: usize) -> Result<Vec<Option<DateTime>>> {
+    let now = current_time();
+    let columns = args
+        .iter()
+        .map(get_sqlite_values)
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok((0..rows)
+        .map(|row| {
+            let row_args = columns
+                .iter()
+                .map(|column| column[row].clone())
+                .collect::<Vec<SqliteValue>>();
+            DateTime::parse(&row_args, now)
+        })
+        .collect())
+}
+
+fn is_subsec(modifier: &str) -> bool {
+    modifier.eq_ignore_ascii_case("subsec") || modifier.eq_ignore_ascii_case("subsecond")
+}
+
+#[cfg(feature = "sqlite")]
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_maths_data_test;
+    use crate::sqlite::register_sqlite_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_date_and_time() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, date('2023-01-31', printf('+%d months', index)) as date, time('12:00', printf('+%d minutes', index * 15)) as time, datetime('2023-01-31 10:20:30', printf('+%d months', index), 'floor') as floor, datetime(float) as julian, julianday('2000-01-01 12:00', printf('+%d hours', index * 6)) as julianday FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------+----------+---------------------+----------------------+------------+
+| index | date       | time     | floor               | julian               | julianday  |
++-------+------------+----------+---------------------+----------------------+------------+
+| 1     | 2023-03-03 | 12:15:00 | 2023-02-28 10:20:30 | -4713-11-25 12:00:00 | 2451545.25 |
+| 2     | 2023-03-31 | 12:30:00 | 2023-03-31 10:20:30 | -4713-11-27 19:12:00 | 2451545.5  |
+| 3     | 2023-05-01 | 12:45:00 | 2023-04-30 10:20:30 |                      | 2451545.75 |
++-------+------------+----------+---------------------+----------------------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_unixepoch() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, unixepoch('2023-06-15 10:11:12.345', printf('+%d days', index)) as seconds, unixepoch('2023-06-15 10:11:12.345', printf('+%d days', index), 'subsec') as subsec, datetime(1700000000 + index, 'unixepoch') as unix, datetime(int, 'auto') as auto FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------+----------------+---------------------+----------------------+
+| index | seconds    | subsec         | unix                | auto                 |
++-------+------------+----------------+---------------------+----------------------+
+| 1     | 1686910272 | 1686910272.345 | 2023-11-14 22:13:21 | 1969-12-31 23:59:58  |
+| 2     | 1686996672 | 1686996672.345 | 2023-11-14 22:13:22 | -4713-11-27 12:00:00 |
+| 3     | 1687083072 | 1687083072.345 | 2023-11-14 22:13:23 |                      |
++-------+------------+----------------+---------------------+----------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_strftime() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, strftime('%Y-%W', '2024-12-28', printf('+%d days', index)) as week, strftime('%G-W%V-%u', '2024-12-28', printf('+%d days', index)) as iso_week, strftime('%j %H:%M:%f %p', '2024-12-28 23:59:58.5', printf('+%d seconds', index)) as time, strftime('%x', '2024-12-28') as unknown FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+---------+------------+---------------------+---------+
+| index | week    | iso_week   | time                | unknown |
++-------+---------+------------+---------------------+---------+
+| 1     | 2024-52 | 2024-W52-7 | 363 23:59:59.500 PM |         |
+| 2     | 2024-53 | 2025-W01-1 | 364 00:00:00.500 AM |         |
+| 3     | 2024-53 | 2025-W01-2 | 364 00:00:01.500 AM |         |
++-------+---------+------------+---------------------+---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_timediff_and_now() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select timediff('2024-03-31', '2023-02-28') as diff, timediff('2023-01-01', 'x') as invalid, date() = date('now') as today, datetime(NULL) as null_value",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------------------------+---------+-------+------------+
+| diff                     | invalid | today | null_value |
++--------------------------+---------+-------+------------+
+| +0001-01-03 00:00:00.000 |         | true  |            |
++--------------------------+---------+-------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = set_up_maths_data_test()?;
+        register_sqlite_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,591 @@
+use std::fmt::{Display, Formatter};
+
+use serde_json::Number;
+
+use crate::sqlite::json_node::JsonNode;
+
+/// Nesting limit for arrays and objects, matching SQLite's JSON_MAX_DEPTH.
+const MAX_DEPTH: usize = 1000;
+
+/// A syntax error found while parsing JSON text.
+/// `offset` is the byte offset into the input at which the error was detected,
+/// which is the input length when the text ends prematurely.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Json5Error {
+    pub(crate) offset: usize,
+    pub(crate) message: String,
+}
+
+impl Display for Json5Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for Json5Error {}
+
+/// Parses JSON text including the JSON5 extensions accepted by SQLite:
+/// comments, trailing commas, unquoted object keys, single-quoted strings,
+/// additional string escapes and whitespace, hexadecimal numbers, leading `+`,
+/// leading or trailing decimal points, `Infinity` and `NaN`.
+///
+/// `Infinity` is read as `9e999`, the number too large for an f64 that SQLite writes for it,
+/// and `NaN`, which JSON has no number for, as null.
+pub(crate) fn parse(input: &str) -> Result<JsonNode, Json5Error> {
+    Parser::new(input, true).parse_document()
+}
+
+/// Parses JSON text, accepting only canonical RFC-8259 JSON.
+pub(crate) fn parse_rfc8259(input: &str) -> Result<JsonNode, Json5Error> {
+    Parser::new(input, false).parse_document()
+}
+
+/// Parses `json_string` the way SQLite's JSON functions read text input, accepting both
+/// RFC-8259 JSON and JSON5.
+pub(crate) fn parse_json(json_string: &str) -> Result<JsonNode, Json5Error> {
+    parse(json_string)
+}
+
+/// Returns the 1-based character offset of the first syntax error in `json_string`,
+/// or `None` if it is well-formed JSON or JSON5.
+pub(crate) fn get_json_error_position(json_string: &str) -> Option<usize> {
+    parse_json(json_string)
+        .err()
+        .map(|err| json_string[..err.offset].chars().count() + 1)
+}
+
+/// Describes a syntax error with its line, column and the text surrounding it.
+pub(crate) fn describe_json_error(json_string: &str, err: &Json5Error) -> String {
+    let line_start = json_string[..err.offset]
+        .rfind('\n')
+        .map(|index| index + 1)
+        .unwrap_or(0);
+    let line = json_string[..line_start].matches('\n').count() + 1;
+    let column = json_string[line_start..err.offset].chars().count() + 1;
+
+    let snippet_start = json_string[..err.offset]
+        .char_indices()
+        .rev()
+        .nth(9)
+        .map(|(index, _)| index)
+        .unwrap_or(0);
+    let snippet_end = json_string[err.offset..]
+        .char_indices()
+        .nth(10)
+        .map(|(index, _)| err.offset + index)
+        .unwrap_or(json_string.len());
+
+    format!(
+        "malformed JSON at line {line} column {column} near '{}'",
+        &json_string[snippet_start..snippet_end]
+    )
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    depth: usize,
+    json5: bool,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, json5: bool) -> Self {
+        Self {
+            input,
+            pos: 0,
+            depth: 0,
+            json5,
+        }
+    }
+
+    fn parse_document(&mut self) -> Result<JsonNode, Json5Error> {
+        self.skip_whitespace()?;
+        let value = self.parse_value()?;
+        self.skip_whitespace()?;
+        if self.pos < self.input.len() {
+            return Err(self.error("trailing characters after JSON value"));
+        }
+        Ok(value)
+    }
+
+    fn error(&self, message: &str) -> Json5Error {
+        Json5Error {
+            offset: self.pos,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn bump(&mut self, c: char) {
+        self.pos += c.len_utf8();
+    }
+
+    fn skip_whitespace(&mut self) -> Result<(), Json5Error> {
+        while let Some(c) = self.peek() {
+            if matches!(c, ' ' | '\t' | '\n' | '\r') || (self.json5 && is_json5_whitespace(c)) {
+                self.bump(c);
+            } else if self.json5 && self.rest().starts_with("//") {
+                let line_end = self
+                    .rest()
+                    .find(['\n', '\r', '\u{2028}', '\u{2029}'])
+                    .unwrap_or(self.rest().len());
+                self.pos += line_end;
+            } else if self.json5 && self.rest().starts_with("/*") {
+                match self.rest()[2..].find("*/") {
+                    Some(end) => self.pos += end + 4,
+                    None => return Err(self.error("unterminated comment")),
+                }
+            } else {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<JsonNode, Json5Error> {
+        match self.peek() {
+            Some('{') => self.parse_object(),
+            Some('[') => self.parse_array(),
+            Some('"') => self.parse_string('"').map(JsonNode::String),
+            Some('\'') if self.json5 => self.parse_string('\'').map(JsonNode::String),
+            Some('t') => self.parse_literal("true", JsonNode::Bool(true)),
+            Some('f') => self.parse_literal("false", JsonNode::Bool(false)),
+            Some('n') => self.parse_literal("null", JsonNode::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.parse_number(),
+            Some(c) if self.json5 && matches!(c, '+' | '.' | 'I' | 'N') => self.parse_number(),
+            Some(_) => Err(self.error("unexpected character")),
+            None => Err(self.error("unexpected end of input")),
+        }
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonNode) -> Result<JsonNode, Json5Error> {
+        if self.rest().starts_with(literal) {
+            self.pos += literal.len();
+            Ok(value)
+        } else {
+            Err(self.error("unexpected character"))
+        }
+    }
+
+    fn enter(&mut self) -> Result<(), Json5Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(self.error("JSON nested too deep"));
+        }
+        Ok(())
+    }
+
+    fn parse_object(&mut self) -> Result<JsonNode, Json5Error> {
+        self.enter()?;
+        self.pos += 1;
+        let mut members: Vec<(String, JsonNode)> = vec![];
+        loop {
+            self.skip_whitespace()?;
+            let key = match self.peek() {
+                Some('}') if members.is_empty() || self.json5 => {
+                    self.pos += 1;
+                    break;
+                }
+                Some('"') => self.parse_string('"')?,
+                Some('\'') if self.json5 => self.parse_string('\'')?,
+                Some(c) if self.json5 && is_identifier_start(c) => self.parse_identifier(),
+                Some(_) => return Err(self.error("expected object key")),
+                None => return Err(self.error("unexpected end of input")),
+            };
+            self.skip_whitespace()?;
+            if self.peek() != Some(':') {
+                return Err(self.error("expected ':' after object key"));
+            }
+            self.pos += 1;
+            self.skip_whitespace()?;
+            let value = self.parse_value()?;
+            members.push((key, value));
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some('}') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected ',' or '}'")),
+                None => return Err(self.error("unexpected end of input")),
+            }
+        }
+        self.depth -= 1;
+        Ok(JsonNode::Object(members))
+    }
+
+    fn parse_array(&mut self) -> Result<JsonNode, Json5Error> {
+        self.enter()?;
+        self.pos += 1;
+        let mut values = vec![];
+        loop {
+            self.skip_whitespace()?;
+            if self.peek() == Some(']') && (values.is_empty() || self.json5) {
+                self.pos += 1;
+                break;
+            }
+            values.push(self.parse_value()?);
+            self.skip_whitespace()?;
+            match self.peek() {
+                Some(',') => self.pos += 1,
+                Some(']') => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(_) => return Err(self.error("expected ',' or ']'")),
+                None => return Err(self.error("unexpected end of input")),
+            }
+        }
+        self.depth -= 1;
+        Ok(JsonNode::Array(values))
+    }
+
+    fn parse_identifier(&mut self) -> String {
+        let length = self
+            .rest()
+            .find(|c: char| !is_identifier_part(c))
+            .unwrap_or(self.rest().len());
+        let identifier = self.rest()[..length].to_string();
+        self.pos += length;
+        identifier
+    }
+
+    fn parse_string(&mut self, quote: char) -> Result<String, Json5Error> {
+        self.pos += 1;
+        let mut string = String::new();
+        loop {
+            let c = match self.peek() {
+                Some(c) => c,
+                None => return Err(self.error("unterminated string")),
+            };
+            if c == quote {
+                self.pos += 1;
+                return Ok(string);
+            }
+            match c {
+                '\\' => {
+                    self.pos += 1;
+                    self.parse_escape(&mut string)?;
+                }
+                '\n' | '\r' => return Err(self.error("unescaped line break in string")),
+                c if (c as u32) < 0x20 && !self.json5 => {
+                    return Err(self.error("unescaped control character in string"))
+                }
+                c => {
+                    string.push(c);
+                    self.bump(c);
+                }
+            }
+        }
+    }
+
+    fn parse_escape(&mut self, string: &mut String) -> Result<(), Json5Error> {
+        let c = match self.peek() {
+            Some(c) => c,
+            None => return Err(self.error("unterminated string")),
+        };
+        let unescaped = match c {
+            '"' | '\\' | '/' => Some(c),
+            'b' => Some('\u{08}'),
+            'f' => Some('\u{0C}'),
+            'n' => Some('\n'),
+            'r' => Some('\r'),
+            't' => Some('\t'),
+            'u' => {
+                self.pos += 1;
+                string.push(self.parse_unicode_escape()?);
+                return Ok(());
+            }
+            _ if !self.json5 => return Err(self.error("invalid escape sequence")),
+            '\'' => Some('\''),
+            'v' => Some('\u{0B}'),
+            '0' if !self.rest()[1..].starts_with(|c: char| c.is_ascii_digit()) => Some('\0'),
+            'x' => {
+                self.pos += 1;
+                let code = self.parse_hex_digits(2)?;
+                string.push(char::from(code as u8));
+                return Ok(());
+            }
+            '\n' | '\u{2028}' | '\u{2029}' => None,
+            '\r' => {
+                if self.rest()[1..].starts_with('\n') {
+                    self.pos += 1;
+                }
+                None
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        };
+        if let Some(unescaped) = unescaped {
+            string.push(unescaped);
+        }
+        self.bump(c);
+        Ok(())
+    }
+
+    fn parse_unicode_escape(&mut self) -> Result<char, Json5Error> {
+        let high = self.parse_hex_digits(4)?;
+        if (0xD800..0xDC00).contains(&high) && self.rest().starts_with("\\u") {
+            let start = self.pos;
+            self.pos += 2;
+            let low = self.parse_hex_digits(4)?;
+            if (0xDC00..0xE000).contains(&low) {
+                let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+                return Ok(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+            }
+            self.pos = start;
+        }
+        Ok(char::from_u32(high).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+
+    fn parse_hex_digits(&mut self, count: usize) -> Result<u32, Json5Error> {
+        let digits = self.rest().get(..count).unwrap_or_default();
+        if digits.len() != count || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(self.error("invalid hexadecimal escape"));
+        }
+        self.pos += count;
+        u32::from_str_radix(digits, 16).map_err(|_| self.error("invalid hexadecimal escape"))
+    }
+
+    fn parse_number(&mut self) -> Result<JsonNode, Json5Error> {
+        let negative = match self.peek() {
+            Some('-') => {
+                self.pos += 1;
+                true
+            }
+            Some('+') if self.json5 => {
+                self.pos += 1;
+                false
+            }
+            _ => false,
+        };
+
+        if self.json5 {
+            if self.rest().starts_with("Infinity") {
+                self.pos += "Infinity".len();
+                let text = if negative { "-9e999" } else { "9e999" };
+                return Ok(JsonNode::Number(text.to_string()));
+            }
+            if self.rest().starts_with("NaN") {
+                self.pos += "NaN".len();
+                return Ok(JsonNode::Null);
+            }
+            if self.rest().starts_with("0x") || self.rest().starts_with("0X") {
+                self.pos += 2;
+                return self.parse_hex_number(negative);
+            }
+        }
+
+        let int_digits = self.take_digits();
+        if int_digits.len() > 1 && int_digits.starts_with('0') {
+            self.pos -= int_digits.len() - 1;
+            return Err(self.error("leading zeros are not allowed"));
+        }
+
+        let mut frac_digits = None;
+        if self.peek() == Some('.') {
+            self.pos += 1;
+            frac_digits = Some(self.take_digits());
+        }
+
+        let int_missing = int_digits.is_empty();
+        let frac_missing = frac_digits.is_some_and(str::is_empty);
+        if (int_missing && (frac_digits.is_none() || frac_missing))
+            || (!self.json5 && (int_missing || frac_missing))
+        {
+            return Err(self.error("malformed number"));
+        }
+
+        let mut exponent = String::new();
+        if let Some(e @ ('e' | 'E')) = self.peek() {
+            self.bump(e);
+            exponent.push(e);
+            if let Some(sign @ ('+' | '-')) = self.peek() {
+                self.bump(sign);
+                exponent.push(sign);
+            }
+            let exponent_digits = self.take_digits();
+            if exponent_digits.is_empty() {
+                return Err(self.error("malformed number"));
+            }
+            exponent.push_str(exponent_digits);
+        }
+
+        let mut text = String::new();
+        if negative {
+            text.push('-');
+        }
+        text.push_str(if int_missing { "0" } else { int_digits });
+        if let Some(frac_digits) = frac_digits {
+            text.push('.');
+            text.push_str(if frac_digits.is_empty() {
+                "0"
+            } else {
+                frac_digits
+            });
+        }
+        text.push_str(&exponent);
+
+        // Numbers keep the text they were written with, as SQLite does.
+        Ok(JsonNode::Number(text))
+    }
+
+    fn parse_hex_number(&mut self, negative: bool) -> Result<JsonNode, Json5Error> {
+        let start = self.pos;
+        let length = self
+            .rest()
+            .find(|c: char| !c.is_ascii_hexdigit())
+            .unwrap_or(self.rest().len());
+        if length == 0 {
+            return Err(self.error("malformed hexadecimal number"));
+        }
+        self.pos += length;
+        let digits = &self.input[start..self.pos];
+
+        let number = match u64::from_str_radix(digits, 16) {
+            Ok(value) if !negative => Some(value.to_string()),
+            Ok(value) if value <= i64::MAX as u64 + 1 => {
+                Some(((value as i128).wrapping_neg() as i64).to_string())
+            }
+            _ => {
+                let magnitude = digits.chars().fold(0_f64, |acc, c| {
+                    acc * 16.0 + c.to_digit(16).unwrap_or(0) as f64
+                });
+                Number::from_f64(if negative { -magnitude } else { magnitude })
+                    .map(|number| number.to_string())
+            }
+        };
+        Ok(number.map(JsonNode::Number).unwrap_or(JsonNode::Null))
+    }
+
+    fn take_digits(&mut self) -> &'a str {
+        let length = self
+            .rest()
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest().len());
+        let digits = &self.input[self.pos..self.pos + length];
+        self.pos += length;
+        digits
+    }
+}
+
+/// Whitespace JSON5 accepts beyond the four RFC-8259 whitespace characters:
+/// vertical tab, form feed, no-break space, byte order mark, line and paragraph
+/// separators and the remaining Unicode space separators.
+fn is_json5_whitespace(c: char) -> bool {
+    matches!(
+        c,
+        '\u{0B}' | '\u{0C}' | '\u{A0}' | '\u{FEFF}' | '\u{1680}' | '\u{2000}'
+            ..='\u{200A}' | '\u{2028}' | '\u{2029}' | '\u{202F}' | '\u{205F}' | '\u{3000}'
+    )
+}
+
+fn is_identifier_start(c: char) -> bool {
+    c == '_' || c == '$' || c.is_alphabetic()
+}
+
+fn is_identifier_part(c: char) -> bool {
+    is_identifier_start(c) || c.is_alphanumeric()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_json5() {
+        let value = parse(
+            r#"
+            // config
+            {
+                unquoted: 'single',
+                "trailing": [1, 2, 3,],
+                hex: 0x1F,
+                negative_hex: -0x10,
+                /* numbers */ leading: .5, trailing_point: 5., plus: +1,
+                escapes: 'it\'s \x41\
+ continued',
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            value.to_string(),
+            r#"{"unquoted":"single","trailing":[1,2,3],"hex":31,"negative_hex":-16,"leading":0.5,"trailing_point":5.0,"plus":1,"escapes":"it's A continued"}"#
+        );
+        assert_eq!(
+            parse("[Infinity, -Infinity, -NaN, 1e400, 1E2, 1.0e-3]")
+                .unwrap()
+                .to_string(),
+            "[9e999,-9e999,null,1e400,1E2,1.0e-3]"
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc8259() {
+        assert_eq!(
+            parse_rfc8259(r#"{"a":[2,3.5,true,false,null,"xé"]}"#).unwrap(),
+            JsonNode::Object(vec![(
+                "a".to_string(),
+                JsonNode::Array(vec![
+                    JsonNode::Number("2".to_string()),
+                    JsonNode::Number("3.5".to_string()),
+                    JsonNode::Bool(true),
+                    JsonNode::Bool(false),
+                    JsonNode::Null,
+                    JsonNode::String("xé".to_string()),
+                ])
+            )])
+        );
+        assert_eq!(
+            parse_rfc8259(r#"{"a":1,"b":2,"a":3}"#).unwrap().to_string(),
+            r#"{"a":1,"b":2,"a":3}"#
+        );
+        assert!(parse_rfc8259("{a:1}").is_err());
+        assert!(parse_rfc8259("[1,]").is_err());
+        assert!(parse_rfc8259("'x'").is_err());
+        assert!(parse_rfc8259("0x10").is_err());
+        assert!(parse_rfc8259(".5").is_err());
+        assert!(parse_rfc8259("[1] // comment").is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("[1,2,x]").unwrap_err().offset, 5);
+        assert_eq!(parse(r#"{"x": 35"#).unwrap_err().offset, 8);
+        assert_eq!(parse("{a:1 b:2}").unwrap_err().offset, 5);
+        assert_eq!(parse("[01]").unwrap_err().offset, 2);
+        assert_eq!(parse("[1,,2]").unwrap_err().offset, 3);
+        assert_eq!(parse("/* open").unwrap_err().offset, 0);
+        assert_eq!(parse("").unwrap_err().offset, 0);
+    }
+
+    #[test]
+    fn test_get_json_error_position() {
+        assert_eq!(get_json_error_position(r#"{"x": 35}"#), None);
+        assert_eq!(get_json_error_position("[1,2,x]"), Some(6));
+        assert_eq!(get_json_error_position("[1,\n2,x]"), Some(7));
+        assert_eq!(get_json_error_position(r#"["é",x]"#), Some(6));
+        assert_eq!(get_json_error_position(""), Some(1));
+        assert_eq!(get_json_error_position("{a:1,}"), None);
+        assert_eq!(get_json_error_position("{a:1 b:2}"), Some(6));
+    }
+
+    #[test]
+    fn test_describe_json_error() {
+        let json_string = "[1,2,x]";
+        let err = parse_json(json_string).unwrap_err();
+        assert_eq!(
+            describe_json_error(json_string, &err),
+            "malformed JSON at line 1 column 6 near '[1,2,x]'"
+        );
+    }
+}
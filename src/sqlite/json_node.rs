@@ -0,0 +1,176 @@
+//! JSON documents as SQLite's JSON functions read and write them.
+//!
+//! Numbers keep the text they were written with, so a number too large for an f64 or an
+//! exponent such as `1E2` is written back unchanged, and objects keep their members in
+//! document order, duplicate keys included.
+
+use std::fmt::{Display, Formatter, Write};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum JsonNode {
+    Null,
+    Bool(bool),
+    /// A number, as the text it was written with.
+    Number(String),
+    String(String),
+    Array(Vec<JsonNode>),
+    /// The members of an object in document order, including any duplicate keys, which SQLite
+    /// keeps.
+    Object(Vec<(String, JsonNode)>),
+}
+
+impl JsonNode {
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, JsonNode::Null)
+    }
+
+    pub(crate) fn is_array(&self) -> bool {
+        matches!(self, JsonNode::Array(_))
+    }
+
+    pub(crate) fn as_array(&self) -> Option<&[JsonNode]> {
+        match self {
+            JsonNode::Array(elements) => Some(elements),
+            _ => None,
+        }
+    }
+
+    /// Returns the first member of an object named `key`, which is the one SQLite's paths
+    /// select.
+    pub(crate) fn get(&self, key: &str) -> Option<&JsonNode> {
+        match self {
+            JsonNode::Object(members) => members
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Reads a number as an integer, if it is written as one that fits an i64.
+    pub(crate) fn as_i64(&self) -> Option<i64> {
+        match self {
+            JsonNode::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Reads a number as a float. Numbers too large for an f64, such as the `9e999` SQLite
+    /// writes for an infinity, are infinite.
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            JsonNode::Number(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The name json_type() reports for this node. Integers outside the i64 range are real.
+    pub(crate) fn json_type(&self) -> &'static str {
+        match self {
+            JsonNode::Null => "null",
+            JsonNode::Bool(true) => "true",
+            JsonNode::Bool(false) => "false",
+            JsonNode::Number(_) if self.as_i64().is_some() => "integer",
+            JsonNode::Number(_) => "real",
+            JsonNode::String(_) => "text",
+            JsonNode::Array(_) => "array",
+            JsonNode::Object(_) => "object",
+        }
+    }
+}
+
+/// Writes the node as minified RFC-8259 JSON text.
+impl Display for JsonNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonNode::Null => f.write_str("null"),
+            JsonNode::Bool(bool_value) => write!(f, "{bool_value}"),
+            JsonNode::Number(text) => f.write_str(text),
+            JsonNode::String(string) => write_string(f, string),
+            JsonNode::Array(elements) => {
+                f.write_char('[')?;
+                for (index, element) in elements.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write!(f, "{element}")?;
+                }
+                f.write_char(']')
+            }
+            JsonNode::Object(members) => {
+                f.write_char('{')?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        f.write_char(',')?;
+                    }
+                    write_string(f, name)?;
+                    write!(f, ":{value}")?;
+                }
+                f.write_char('}')
+            }
+        }
+    }
+}
+
+fn write_string(f: &mut Formatter<'_>, string: &str) -> std::fmt::Result {
+    f.write_char('"')?;
+    for c in string.chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\u{08}' => f.write_str("\\b")?,
+            '\u{0C}' => f.write_str("\\f")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sqlite::json5::parse;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let node = JsonNode::Object(vec![
+            ("a".to_string(), JsonNode::Number("1E2".to_string())),
+            (
+                "b\"".to_string(),
+                JsonNode::Array(vec![
+                    JsonNode::Null,
+                    JsonNode::Bool(true),
+                    JsonNode::String("x\n\u{1}é".to_string()),
+                ]),
+            ),
+        ]);
+        assert_eq!(
+            node.to_string(),
+            r#"{"a":1E2,"b\"":[null,true,"x\n\u0001é"]}"#
+        );
+        assert_eq!(JsonNode::Array(vec![]).to_string(), "[]");
+        assert_eq!(JsonNode::Object(vec![]).to_string(), "{}");
+    }
+
+    #[test]
+    fn test_accessors() {
+        let node = parse(r#"{"a":[1,2.5,18446744073709551616,9e999],"b":"x","b":"y"}"#).unwrap();
+        assert_eq!(node.get("b"), Some(&JsonNode::String("x".to_string())));
+        assert_eq!(node.get("c"), None);
+
+        let numbers = node.get("a").unwrap().as_array().unwrap();
+        assert_eq!(
+            numbers.iter().map(JsonNode::json_type).collect::<Vec<_>>(),
+            ["integer", "real", "real", "real"]
+        );
+        assert_eq!(numbers[0].as_i64(), Some(1));
+        assert_eq!(numbers[1].as_i64(), None);
+        assert_eq!(numbers[1].as_f64(), Some(2.5));
+        assert_eq!(numbers[3].as_f64(), Some(f64::INFINITY));
+    }
+}
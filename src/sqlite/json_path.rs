@@ -0,0 +1,233 @@
+//! SQLite's JSON paths, such as `$.a[0]."b c"[#-1]`.
+//!
+//! A path starts with `$` for the whole document and is followed by steps: `.name` or `."name"`
+//! selects an object member, `[N]` the Nth element of an array and `[#-N]` the Nth element from
+//! its end. SQLite reads a path one step at a time while it walks the document, so a malformed
+//! step is only an error once the walk reaches it. A malformed array subscript is only an error
+//! when it is applied to an array.
+
+use std::fmt::{Display, Formatter};
+
+use crate::sqlite::json5;
+use crate::sqlite::json_node::JsonNode;
+
+/// A path that is not a SQLite JSON path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PathError {
+    path: String,
+}
+
+impl Display for PathError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "bad JSON path: '{}'", self.path)
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// One step of a path.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PathStep {
+    /// `.name` or `."name"`, the first member of an object named `name`.
+    Key(String),
+    /// `[N]`, the element of an array at index N.
+    Index(usize),
+    /// `[#-N]`, the element N places before the end of an array. `[#]` is just past its end.
+    FromEnd(usize),
+    /// An array subscript that is neither `[N]` nor `[#-N]`.
+    BadIndex,
+}
+
+impl PathStep {
+    /// Returns the element this step selects from `value`, or `None` if there is none.
+    pub(crate) fn select<'v>(&self, value: &'v JsonNode) -> Option<&'v JsonNode> {
+        match (self, value) {
+            (PathStep::Key(key), JsonNode::Object(_)) => value.get(key),
+            (PathStep::Index(index), JsonNode::Array(values)) => values.get(*index),
+            (PathStep::FromEnd(count), JsonNode::Array(values)) => values
+                .len()
+                .checked_sub(*count)
+                .and_then(|index| values.get(index)),
+            _ => None,
+        }
+    }
+}
+
+/// The steps of a path, read one at a time.
+#[derive(Debug)]
+pub(crate) struct JsonPath<'a> {
+    path: &'a str,
+    rest: &'a str,
+}
+
+impl<'a> JsonPath<'a> {
+    pub(crate) fn parse(path: &'a str) -> Result<Self, PathError> {
+        match path.strip_prefix('$') {
+            Some(rest) => Ok(Self { path, rest }),
+            None => Err(PathError {
+                path: path.to_string(),
+            }),
+        }
+    }
+
+    /// The error for this path, as reported when a step applied to the document is malformed.
+    pub(crate) fn error(&self) -> PathError {
+        PathError {
+            path: self.path.to_string(),
+        }
+    }
+
+    /// Reads the next step, or returns `None` at the end of the path.
+    pub(crate) fn next_step(&mut self) -> Result<Option<PathStep>, PathError> {
+        if self.rest.is_empty() {
+            return Ok(None);
+        }
+        if let Some(after_dot) = self.rest.strip_prefix('.') {
+            return self.read_key(after_dot).map(Some);
+        }
+        if let Some(after_bracket) = self.rest.strip_prefix('[') {
+            return Ok(Some(self.read_subscript(after_bracket)));
+        }
+        Err(self.error())
+    }
+
+    fn read_key(&mut self, after_dot: &'a str) -> Result<PathStep, PathError> {
+        if let Some(quoted) = after_dot.strip_prefix('"') {
+            let mut chars = quoted.char_indices();
+            let end = loop {
+                match chars.next() {
+                    Some((_, '\\')) => {
+                        chars.next();
+                    }
+                    Some((end, '"')) => break end,
+                    Some(_) => {}
+                    None => return Err(self.error()),
+                }
+            };
+            let key = &quoted[..end];
+            self.rest = &quoted[end + 1..];
+            // Escapes in a quoted key stand for the characters they escape, as in object keys.
+            let key = if key.contains('\\') {
+                match json5::parse(&format!("\"{key}\"")) {
+                    Ok(JsonNode::String(key)) => key,
+                    _ => key.to_string(),
+                }
+            } else {
+                key.to_string()
+            };
+            return Ok(PathStep::Key(key));
+        }
+
+        let end = after_dot.find(['.', '[']).unwrap_or(after_dot.len());
+        if end == 0 {
+            return Err(self.error());
+        }
+        self.rest = &after_dot[end..];
+        Ok(PathStep::Key(after_dot[..end].to_string()))
+    }
+
+    fn read_subscript(&mut self, after_bracket: &'a str) -> PathStep {
+        let (step, rest) = match after_bracket.strip_prefix('#') {
+            Some(after_hash) => match after_hash.strip_prefix('-') {
+                Some(after_minus) if after_minus.starts_with(|c: char| c.is_ascii_digit()) => {
+                    let (count, rest) = split_number(after_minus);
+                    (PathStep::FromEnd(count), rest)
+                }
+                _ => (PathStep::FromEnd(0), after_hash),
+            },
+            None if after_bracket.starts_with(|c: char| c.is_ascii_digit()) => {
+                let (index, rest) = split_number(after_bracket);
+                (PathStep::Index(index), rest)
+            }
+            None => (PathStep::BadIndex, ""),
+        };
+        match rest.strip_prefix(']') {
+            Some(rest) => {
+                self.rest = rest;
+                step
+            }
+            None => {
+                self.rest = "";
+                PathStep::BadIndex
+            }
+        }
+    }
+}
+
+/// Splits the leading digits off `text`, reading them as a number. Numbers too large for a
+/// `usize` are past the end of any array.
+fn split_number(text: &str) -> (usize, &str) {
+    let length = text
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(text.len());
+    let number = text[..length].parse().unwrap_or(usize::MAX);
+    (number, &text[length..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(path: &str) -> Result<Vec<PathStep>, PathError> {
+        let mut path = JsonPath::parse(path)?;
+        let mut steps = vec![];
+        while let Some(step) = path.next_step()? {
+            steps.push(step);
+        }
+        Ok(steps)
+    }
+
+    fn key(key: &str) -> PathStep {
+        PathStep::Key(key.to_string())
+    }
+
+    #[test]
+    fn test_steps() {
+        assert_eq!(steps("$"), Ok(vec![]));
+        assert_eq!(
+            steps("$.a[0].\"b.c\"[#-1][#][01]"),
+            Ok(vec![
+                key("a"),
+                PathStep::Index(0),
+                key("b.c"),
+                PathStep::FromEnd(1),
+                PathStep::FromEnd(0),
+                PathStep::Index(1),
+            ])
+        );
+        assert_eq!(steps("$.a b.1.*"), Ok(vec![key("a b"), key("1"), key("*")]));
+        assert_eq!(
+            steps(r#"$.""."a\u0062"."a\"b""#),
+            Ok(vec![key(""), key("ab"), key("a\"b")])
+        );
+        assert_eq!(steps("$[-1]"), Ok(vec![PathStep::BadIndex]));
+        assert_eq!(steps("$[1 ].a"), Ok(vec![PathStep::BadIndex]));
+        assert_eq!(steps("$[#-]"), Ok(vec![PathStep::BadIndex]));
+        assert_eq!(steps("$['a']"), Ok(vec![PathStep::BadIndex]));
+
+        for path in ["a", "", "$.", "$..a", "$.a.", "$.\"a", "$[0]x"] {
+            assert_eq!(
+                steps(path).unwrap_err().to_string(),
+                format!("bad JSON path: '{path}'")
+            );
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let value = json5::parse(r#"{"a": [1, 2, 3], "a b": true}"#).unwrap();
+        let number = |text: &str| JsonNode::Number(text.to_string());
+        assert_eq!(key("a b").select(&value), Some(&JsonNode::Bool(true)));
+        assert_eq!(key("b").select(&value), None);
+
+        let array = value.get("a").unwrap();
+        assert_eq!(PathStep::Index(0).select(array), Some(&number("1")));
+        assert_eq!(PathStep::Index(3).select(array), None);
+        assert_eq!(PathStep::FromEnd(1).select(array), Some(&number("3")));
+        assert_eq!(PathStep::FromEnd(3).select(array), Some(&number("1")));
+        assert_eq!(PathStep::FromEnd(4).select(array), None);
+        assert_eq!(PathStep::FromEnd(0).select(array), None);
+        assert_eq!(PathStep::Index(0).select(&value), None);
+        assert_eq!(key("a").select(array), None);
+    }
+}
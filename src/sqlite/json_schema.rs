@@ -0,0 +1,376 @@
+//! Schema strings read by json_to_struct() and written by json_infer_schema().
+//!
+//! A schema is a comma separated list of `name type` pairs such as `id bigint, tags text[]`.
+//! The types are `boolean`, `bigint`, `double`, `text`, `json`, `struct<...>` holding a nested
+//! schema, and any of these followed by `[]` for a list. Names that are not plain identifiers
+//! are double quoted, with `""` standing for a quote inside the name.
+
+use std::fmt::{Display, Formatter};
+
+use anyhow::{anyhow, Result};
+
+use crate::sqlite::json_node::JsonNode;
+
+/// A named field of a schema.
+pub(crate) type SchemaField = (String, SchemaType);
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SchemaType {
+    /// Only nulls have been seen, so there is nothing to choose a type from.
+    Null,
+    Boolean,
+    BigInt,
+    Double,
+    Text,
+    /// Any JSON value, kept as JSON text.
+    Json,
+    List(Box<SchemaType>),
+    Struct(Vec<SchemaField>),
+}
+
+impl SchemaType {
+    /// The narrowest type that holds `value`.
+    pub(crate) fn infer(value: &JsonNode) -> Self {
+        match value {
+            JsonNode::Null => SchemaType::Null,
+            JsonNode::Bool(_) => SchemaType::Boolean,
+            JsonNode::Number(_) if value.as_i64().is_some() => SchemaType::BigInt,
+            JsonNode::Number(_) => SchemaType::Double,
+            JsonNode::String(_) => SchemaType::Text,
+            JsonNode::Array(elements) => SchemaType::List(Box::new(
+                elements
+                    .iter()
+                    .map(Self::infer)
+                    .fold(SchemaType::Null, Self::merge),
+            )),
+            JsonNode::Object(members) => {
+                // Paths select the first of duplicate keys, so that is the member described.
+                let mut fields: Vec<SchemaField> = vec![];
+                for (name, value) in members {
+                    if !fields.iter().any(|(field_name, _)| field_name == name) {
+                        fields.push((name.clone(), Self::infer(value)));
+                    }
+                }
+                SchemaType::Struct(fields)
+            }
+        }
+    }
+
+    /// The narrowest type that holds values of both `self` and `other`. Integers widen to
+    /// doubles, structs take the union of their fields and anything else that disagrees
+    /// falls back to JSON text.
+    pub(crate) fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (SchemaType::Null, other) | (other, SchemaType::Null) => other,
+            (SchemaType::BigInt, SchemaType::Double) | (SchemaType::Double, SchemaType::BigInt) => {
+                SchemaType::Double
+            }
+            (SchemaType::List(element), SchemaType::List(other_element)) => {
+                SchemaType::List(Box::new(element.merge(*other_element)))
+            }
+            (SchemaType::Struct(fields), SchemaType::Struct(other_fields)) => {
+                SchemaType::Struct(merge_fields(fields, other_fields))
+            }
+            (this, other) if this == other => this,
+            _ => SchemaType::Json,
+        }
+    }
+
+    /// Settles the types inference could not decide: fields that were only ever null become
+    /// text and structs without fields become JSON.
+    pub(crate) fn resolve(self) -> Self {
+        match self {
+            SchemaType::Null => SchemaType::Text,
+            SchemaType::List(element) => SchemaType::List(Box::new(element.resolve())),
+            SchemaType::Struct(fields) if fields.is_empty() => SchemaType::Json,
+            SchemaType::Struct(fields) => SchemaType::Struct(resolve_fields(fields)),
+            other => other,
+        }
+    }
+}
+
+impl Display for SchemaType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaType::Null => write!(f, "null"),
+            SchemaType::Boolean => write!(f, "boolean"),
+            SchemaType::BigInt => write!(f, "bigint"),
+            SchemaType::Double => write!(f, "double"),
+            SchemaType::Text => write!(f, "text"),
+            SchemaType::Json => write!(f, "json"),
+            SchemaType::List(element) => write!(f, "{element}[]"),
+            SchemaType::Struct(fields) => write!(f, "struct<{}>", render_schema(fields)),
+        }
+    }
+}
+
+/// Merges `other` into `fields`, keeping fields in the order they were first seen.
+pub(crate) fn merge_fields(
+    mut fields: Vec<SchemaField>,
+    other: Vec<SchemaField>,
+) -> Vec<SchemaField> {
+    for (name, other_type) in other {
+        match fields
+            .iter_mut()
+            .find(|(field_name, _)| *field_name == name)
+        {
+            Some((_, field_type)) => {
+                *field_type = std::mem::replace(field_type, SchemaType::Null).merge(other_type)
+            }
+            None => fields.push((name, other_type)),
+        }
+    }
+    fields
+}
+
+pub(crate) fn resolve_fields(fields: Vec<SchemaField>) -> Vec<SchemaField> {
+    fields
+        .into_iter()
+        .map(|(name, field_type)| (name, field_type.resolve()))
+        .collect()
+}
+
+pub(crate) fn render_schema(fields: &[SchemaField]) -> String {
+    fields
+        .iter()
+        .map(|(name, field_type)| format!("{} {field_type}", quote_name(name)))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn quote_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("\"{}\"", name.replace('"', "\"\""))
+    }
+}
+
+/// Parses a schema written by hand. Every struct, including the outermost one, must have at
+/// least one field.
+pub(crate) fn parse_schema(schema: &str) -> Result<Vec<SchemaField>> {
+    SchemaParser::new(schema, false).parse()
+}
+
+/// Parses a schema written by json_infer_schema() while it is still gathering types, which
+/// may contain `null` types and structs without fields.
+pub(crate) fn parse_partial_schema(schema: &str) -> Result<Vec<SchemaField>> {
+    SchemaParser::new(schema, true).parse()
+}
+
+struct SchemaParser<'a> {
+    input: &'a str,
+    pos: usize,
+    partial: bool,
+}
+
+impl<'a> SchemaParser<'a> {
+    fn new(input: &'a str, partial: bool) -> Self {
+        Self {
+            input,
+            pos: 0,
+            partial,
+        }
+    }
+
+    fn parse(mut self) -> Result<Vec<SchemaField>> {
+        let fields = self.fields()?;
+        self.skip_whitespace();
+        if self.pos != self.input.len() {
+            return Err(self.error("expected ','"));
+        }
+        Ok(fields)
+    }
+
+    fn fields(&mut self) -> Result<Vec<SchemaField>> {
+        let mut fields: Vec<SchemaField> = Vec::new();
+        self.skip_whitespace();
+        if self.partial && (self.rest().is_empty() || self.rest().starts_with('>')) {
+            return Ok(fields);
+        }
+        loop {
+            let name_pos = self.pos;
+            let name = self.name()?;
+            if fields.iter().any(|(field_name, _)| *field_name == name) {
+                self.pos = name_pos;
+                return Err(self.error(&format!("duplicate field \"{name}\"")));
+            }
+            let field_type = self.field_type()?;
+            fields.push((name, field_type));
+
+            self.skip_whitespace();
+            if !self.eat(",") {
+                return Ok(fields);
+            }
+            self.skip_whitespace();
+        }
+    }
+
+    fn name(&mut self) -> Result<String> {
+        if self.eat("\"") {
+            let mut name = String::new();
+            loop {
+                match self.rest().find('"') {
+                    Some(end) => {
+                        name.push_str(&self.rest()[..end]);
+                        self.pos += end + 1;
+                        if !self.eat("\"") {
+                            return Ok(name);
+                        }
+                        name.push('"');
+                    }
+                    None => return Err(self.error("unterminated quoted name")),
+                }
+            }
+        }
+        let word = self.word();
+        if word.is_empty() {
+            return Err(self.error("expected a field name"));
+        }
+        Ok(word.to_string())
+    }
+
+    fn field_type(&mut self) -> Result<SchemaType> {
+        self.skip_whitespace();
+        let type_pos = self.pos;
+        let mut field_type = match self.word().to_ascii_lowercase().as_str() {
+            "boolean" | "bool" => SchemaType::Boolean,
+            "bigint" | "integer" | "int" | "int8" => SchemaType::BigInt,
+            "double" | "real" | "float" | "float8" => SchemaType::Double,
+            "text" | "varchar" | "string" => SchemaType::Text,
+            "json" | "jsonb" => SchemaType::Json,
+            "null" if self.partial => SchemaType::Null,
+            "struct" => {
+                self.skip_whitespace();
+                if !self.eat("<") {
+                    return Err(self.error("expected '<'"));
+                }
+                let fields = self.fields()?;
+                self.skip_whitespace();
+                if !self.eat(">") {
+                    return Err(self.error("expected '>'"));
+                }
+                SchemaType::Struct(fields)
+            }
+            "" => return Err(self.error("expected a type")),
+            word => {
+                self.pos = type_pos;
+                return Err(self.error(&format!("unknown type \"{word}\"")));
+            }
+        };
+        loop {
+            self.skip_whitespace();
+            if !self.eat("[]") {
+                return Ok(field_type);
+            }
+            field_type = SchemaType::List(Box::new(field_type));
+        }
+    }
+
+    fn word(&mut self) -> &'a str {
+        let rest = &self.input[self.pos..];
+        let end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.pos += end;
+        &rest[..end]
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        let matched = self.rest().starts_with(token);
+        if matched {
+            self.pos += token.len();
+        }
+        matched
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.input.len() - self.rest().trim_start().len();
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> anyhow::Error {
+        anyhow!(
+            "invalid schema at character {}: {message}",
+            self.input[..self.pos].chars().count() + 1
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sqlite::json5::parse;
+
+    use super::*;
+
+    #[test]
+    fn test_infer() {
+        let schema = [
+            r#"{"id": 1, "tags": ["a"], "score": 1}"#,
+            r#"{"id": 2, "tags": [], "score": 2.5, "extra": {"x": true}}"#,
+            r#"{"id": "3", "tags": null, "id": 4}"#,
+        ]
+        .iter()
+        .map(|json| SchemaType::infer(&parse(json).unwrap()))
+        .fold(SchemaType::Null, SchemaType::merge)
+        .resolve();
+
+        assert_eq!(
+            schema.to_string(),
+            "struct<id json, tags text[], score double, extra struct<x boolean>>"
+        );
+    }
+
+    #[test]
+    fn test_parse_schema() {
+        let fields = parse_schema(
+            r#"id BIGINT, "first name" text, tags text[][], pos struct<x double, y double>"#,
+        )
+        .unwrap();
+        assert_eq!(
+            render_schema(&fields),
+            r#"id bigint, "first name" text, tags text[][], pos struct<x double, y double>"#
+        );
+
+        assert_eq!(
+            parse_schema(r#""a""b" int"#).unwrap(),
+            vec![("a\"b".to_string(), SchemaType::BigInt)]
+        );
+        assert_eq!(
+            parse_schema("a date").unwrap_err().to_string(),
+            "invalid schema at character 3: unknown type \"date\""
+        );
+        assert_eq!(
+            parse_schema("a int, a text").unwrap_err().to_string(),
+            "invalid schema at character 8: duplicate field \"a\""
+        );
+        assert_eq!(
+            parse_schema("a int b text").unwrap_err().to_string(),
+            "invalid schema at character 7: expected ','"
+        );
+        assert!(parse_schema("").is_err());
+        assert!(parse_schema("a struct<>").is_err());
+        assert!(parse_schema("a null").is_err());
+    }
+
+    #[test]
+    fn test_parse_partial_schema() {
+        let fields = vec![
+            ("a".to_string(), SchemaType::Null),
+            ("b".to_string(), SchemaType::Struct(vec![])),
+        ];
+        assert_eq!(
+            parse_partial_schema(&render_schema(&fields)).unwrap(),
+            fields
+        );
+        assert_eq!(parse_partial_schema("").unwrap(), vec![]);
+    }
+}
@@ -1,19 +1,57 @@
 use std::sync::Arc;
 
-use datafusion::arrow::array::{Array, ArrayRef, StringBuilder, UInt64Array, UInt8Array};
-use datafusion::arrow::datatypes::DataType;
-use datafusion::arrow::datatypes::DataType::{UInt64, UInt8, Utf8};
-use datafusion::common::DataFusionError;
+use datafusion::arrow::array::{
+    Array, ArrayRef, AsArray, BinaryArray, BooleanArray, Float64Array, Int64Array,
+    LargeBinaryArray, ListArray, NullArray, StringArray, StructArray, UInt64Array, UInt8Array,
+};
+use datafusion::arrow::buffer::{NullBuffer, OffsetBuffer};
+use datafusion::arrow::datatypes::DataType::{
+    Binary, Boolean, Float64, Int64, LargeBinary, LargeUtf8, List, Null, Struct, UInt64, UInt8,
+    Utf8, Utf8View,
+};
+use datafusion::arrow::datatypes::{DataType, Field, FieldRef, Fields};
+use datafusion::common::{DataFusionError, ExprSchema, ScalarValue};
 use datafusion::error::Result;
+use datafusion::logical_expr::function::AccumulatorArgs;
 use datafusion::logical_expr::TypeSignature::Uniform;
-use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
-use serde_json::Value;
-
-use crate::common::{get_json_string_type, get_json_type, get_value_at_string};
-
-/// The json(X) function verifies that its argument X is a valid JSON string and returns a minified
-/// version of that JSON string (with all unnecessary whitespace removed).
-/// If X is not a well-formed JSON string, then this routine throws an error.
+use datafusion::logical_expr::{
+    Accumulator, AggregateUDFImpl, ColumnarValue, Expr, ScalarUDFImpl, Signature, Volatility,
+};
+
+use crate::catalog::{Documentation, Documented};
+use crate::common::strings::{
+    exact_signatures, string_return_type, string_values, StringOutput, STRING_TYPES,
+};
+use crate::sqlite::json5::{
+    describe_json_error, get_json_error_position, parse_json, parse_rfc8259,
+};
+use crate::sqlite::json_node::JsonNode;
+use crate::sqlite::json_path::{JsonPath, PathError, PathStep};
+use crate::sqlite::json_schema::{
+    merge_fields, parse_partial_schema, parse_schema, render_schema, resolve_fields, SchemaField,
+    SchemaType,
+};
+use crate::sqlite::jsonb;
+use crate::sqlite::jsonb::{JsonbElement, JsonbError};
+
+const JSON_FUNCTIONS_LINK: &str = "https://www.sqlite.org/json1.html";
+
+/// The types of the JSON arguments: text of any string type, or a JSONB blob.
+const JSON_TYPES: [DataType; 5] = [Utf8, LargeUtf8, Utf8View, Binary, LargeBinary];
+
+/// json_valid(X,Y) flag: X is RFC-8259 JSON text.
+const JSON_VALID_RFC_8259: i64 = 0x01;
+/// json_valid(X,Y) flag: X is JSON5 text.
+const JSON_VALID_JSON5: i64 = 0x02;
+/// json_valid(X,Y) flag: X is a BLOB that superficially appears to be JSONB.
+const JSON_VALID_JSONB_SUPERFICIAL: i64 = 0x04;
+/// json_valid(X,Y) flag: X is a BLOB that strictly conforms to the JSONB format.
+const JSON_VALID_JSONB_STRICT: i64 = 0x08;
+
+/// The json(X) function verifies that its argument X is a valid JSON string or JSONB blob and
+/// returns a minified version of that JSON string (with all unnecessary whitespace removed).
+/// JSON5 input is accepted and converted into canonical RFC-8259 text.
+/// If X is not a well-formed JSON string or JSONB blob, then this routine throws an error.
 #[derive(Debug)]
 pub struct Json {
     signature: Signature,
@@ -22,7 +60,7 @@ pub struct Json {
 impl Json {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::uniform(1, JSON_TYPES.to_vec(), Volatility::Immutable),
         }
     }
 }
@@ -40,24 +78,19 @@ impl ScalarUDFImpl for Json {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(json_text_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let json_strings = datafusion::common::cast::as_string_array(&args[0])?;
+        let json_inputs = get_json_inputs(&args[0])?;
 
-        let mut string_builder = StringBuilder::with_capacity(json_strings.len(), u8::MAX as usize);
-        json_strings.iter().try_for_each(|json_string| {
-            if let Some(json_string) = json_string {
-                let value: Value = serde_json::from_str(json_string).map_err(|_| {
-                    DataFusionError::Internal("Runtime error: malformed JSON".to_string())
-                })?;
-                let pretty_json = serde_json::to_string(&value).map_err(|_| {
-                    DataFusionError::Internal("Runtime error: malformed JSON".to_string())
-                })?;
-                string_builder.append_value(pretty_json);
+        let mut string_builder =
+            StringOutput::new(&json_text_type(args[0].data_type()), json_inputs.len());
+        json_inputs.iter().try_for_each(|json_input| {
+            if let Some(json_input) = json_input {
+                string_builder.append_value(json_input.parse()?.to_string());
                 Ok::<(), DataFusionError>(())
             } else {
                 string_builder.append_null();
@@ -65,9 +98,96 @@ impl ScalarUDFImpl for Json {
             }
         })?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for Json {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json(X)",
+            description:
+                "Returns the JSON text, JSON5 text or JSONB blob X as minified RFC-8259 JSON text",
+            examples: &[
+                "json(' { \"a\" : [1, 2] } ') → {\"a\":[1,2]}",
+                "json('{a: 0x10}') → {\"a\":16}",
+            ],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The jsonb(X) function returns the binary JSONB representation of the JSON provided as
+/// argument X. An error is raised if X is TEXT that does not have valid JSON syntax.
+/// If X is a BLOB that is already JSONB, it is checked and returned in canonical form.
+///
+/// The result is a pre-parsed form of the document that every JSON function accepts in place
+/// of text, so a column that is read by several JSON functions only needs parsing once. The
+/// function is also available as json_parse(X).
+#[derive(Debug)]
+pub struct Jsonb {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Jsonb {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, JSON_TYPES.to_vec(), Volatility::Immutable),
+            aliases: vec!["json_parse".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Jsonb {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "jsonb"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(jsonb_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let encoded = get_json_inputs(&args[0])?
+            .iter()
+            .map(|json_input| {
+                json_input
+                    .as_ref()
+                    .map(|json_input| json_input.parse().map(|value| jsonb::encode(&value)))
+                    .transpose()
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let array: ArrayRef = match jsonb_type(args[0].data_type()) {
+            LargeBinary => Arc::new(LargeBinaryArray::from_iter(encoded)),
+            _ => Arc::new(BinaryArray::from_iter(encoded)),
+        };
+        Ok(ColumnarValue::Array(array))
+    }
+}
+
+impl Documented for Jsonb {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "jsonb(X)",
+            description: "Returns the JSONB representation of X, which the JSON functions read without parsing it again",
+            examples: &["json(jsonb('{a: 1}')) → {\"a\":1}"],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -85,7 +205,14 @@ pub struct JsonType {
 impl JsonType {
     pub fn new() -> Self {
         Self {
-            signature: Signature::variadic(vec![Utf8], Volatility::Immutable),
+            signature: Signature::one_of(
+                [
+                    vec![Uniform(1, JSON_TYPES.to_vec())],
+                    exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
         }
     }
 }
@@ -103,8 +230,8 @@ impl ScalarUDFImpl for JsonType {
         &self.signature
     }
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(Utf8)
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(json_text_type(&arg_types[0]))
     }
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
@@ -115,16 +242,14 @@ impl ScalarUDFImpl for JsonType {
             ));
         }
 
-        let mut string_builder = StringBuilder::with_capacity(args.len(), u8::MAX as usize);
+        let json_inputs = get_json_inputs(&args[0])?;
+        let mut string_builder =
+            StringOutput::new(&json_text_type(args[0].data_type()), json_inputs.len());
         if args.len() == 1 {
             //1. Just json and no path
-            let json_strings = datafusion::common::cast::as_string_array(&args[0])?;
-            json_strings.iter().try_for_each(|json_string| {
-                if let Some(json_string) = json_string {
-                    string_builder.append_value(
-                        get_json_string_type(json_string)
-                            .map_err(|err| DataFusionError::Internal(err.to_string()))?,
-                    );
+            json_inputs.iter().try_for_each(|json_input| {
+                if let Some(json_input) = json_input {
+                    string_builder.append_value(json_input.root()?.json_type()?);
                     Ok::<(), DataFusionError>(())
                 } else {
                     string_builder.append_null();
@@ -133,23 +258,18 @@ impl ScalarUDFImpl for JsonType {
             })?;
         } else {
             //2. Json and path
-            let json_strings = datafusion::common::cast::as_string_array(&args[0])?;
-            let paths = datafusion::common::cast::as_string_array(&args[1])?;
+            let paths = string_values(&args[1])?;
 
-            json_strings
+            json_inputs
                 .iter()
-                .zip(paths.iter())
-                .try_for_each(|(json_string, path)| {
-                    if let (Some(json_string), Some(path)) = (json_string, path) {
-                        match get_value_at_string(json_string, path) {
-                            Ok(json_at_path) => {
-                                string_builder.append_value(
-                                    get_json_type(&json_at_path).map_err(|err| {
-                                        DataFusionError::Internal(err.to_string())
-                                    })?,
-                                );
+                .zip(paths)
+                .try_for_each(|(json_input, path)| {
+                    if let (Some(json_input), Some(path)) = (json_input, path) {
+                        match json_input.node_at(path)? {
+                            Some(json_at_path) => {
+                                string_builder.append_value(json_at_path.json_type()?);
                             }
-                            Err(_) => {
+                            None => {
                                 string_builder.append_null();
                             }
                         }
@@ -161,9 +281,18 @@ impl ScalarUDFImpl for JsonType {
                 })?;
         }
 
-        Ok(ColumnarValue::Array(
-            Arc::new(string_builder.finish()) as ArrayRef
-        ))
+        Ok(ColumnarValue::Array(string_builder.finish()))
+    }
+}
+
+impl Documented for JsonType {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_type(X [, P])",
+            description: "Returns the type of X, or of the element of X selected by path P: null, true, false, integer, real, text, array or object",
+            examples: &["json_type('{\"a\":[1,2.5]}', '$.a[1]') → real"],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
     }
 }
 
@@ -171,10 +300,20 @@ impl ScalarUDFImpl for JsonType {
 /// without any extensions, or return 0 if the argument X is not well-formed JSON or is
 /// JSON that includes JSON5 extensions.
 ///
+/// The json_valid(X,Y) form takes a bitmask Y that chooses what counts as well-formed:
+///
+/// 0x01 → X is RFC-8259 JSON text
+/// 0x02 → X is JSON5 text
+/// 0x04 → X is a BLOB that superficially appears to be JSONB
+/// 0x08 → X is a BLOB that strictly conforms to the JSONB format
+///
 /// Examples:
 ///
 /// json_valid('{"x": 35}') → 1
 /// json_valid('{"x": 35') → 0
+/// json_valid('{x: 35}') → 0
+/// json_valid('{x: 35}', 6) → 1
+/// json_valid(jsonb('{"x": 35}'), 8) → 1
 /// json_valid(NULL) → NULL
 #[derive(Debug)]
 pub struct JsonValid {
@@ -184,7 +323,14 @@ pub struct JsonValid {
 impl JsonValid {
     pub fn new() -> Self {
         Self {
-            signature: Signature::uniform(1, vec![Utf8], Volatility::Immutable),
+            signature: Signature::one_of(
+                [
+                    vec![Uniform(1, JSON_TYPES.to_vec())],
+                    exact_signatures(&[&JSON_TYPES, &[Int64]]),
+                ]
+                .concat(),
+                Volatility::Immutable,
+            ),
         }
     }
 }
@@ -208,17 +354,122 @@ impl ScalarUDFImpl for JsonValid {
 
     fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
         let args = ColumnarValue::values_to_arrays(args)?;
-        let json_strings = datafusion::common::cast::as_string_array(&args[0])?;
-        let mut uint_builder = UInt8Array::builder(json_strings.len());
-
-        json_strings.iter().for_each(|json_string| {
-            if let Some(json_string) = json_string {
-                let json_value: serde_json::error::Result<Value> =
-                    serde_json::from_str(json_string);
-                uint_builder.append_value(json_value.is_ok() as u8);
-            } else {
-                uint_builder.append_null();
-            }
+        let json_inputs = get_json_inputs(&args[0])?;
+        let flags = args
+            .get(1)
+            .map(|flags| datafusion::common::cast::as_int64_array(flags))
+            .transpose()?;
+        let mut uint_builder = UInt8Array::builder(json_inputs.len());
+
+        json_inputs
+            .iter()
+            .enumerate()
+            .try_for_each(|(index, json_input)| {
+                let flags = match flags {
+                    Some(flags) if flags.is_null(index) => None,
+                    Some(flags) => Some(flags.value(index)),
+                    None => Some(JSON_VALID_RFC_8259),
+                };
+                match (json_input, flags) {
+                    (_, Some(flags)) if !(1..=15).contains(&flags) => {
+                        return Err(DataFusionError::Execution(
+                            "FLAGS parameter to json_valid() must be between 1 and 15".to_string(),
+                        ));
+                    }
+                    (Some(JsonInput::Text(json_string)), Some(flags)) => {
+                        let is_valid = (flags & JSON_VALID_RFC_8259 != 0
+                            && parse_rfc8259(json_string).is_ok())
+                            || (flags & JSON_VALID_JSON5 != 0 && parse_json(json_string).is_ok());
+                        uint_builder.append_value(is_valid as u8);
+                    }
+                    (Some(JsonInput::Jsonb(bytes)), Some(flags)) => {
+                        let is_valid = (flags & JSON_VALID_JSONB_STRICT != 0
+                            && jsonb::decode(bytes).is_ok())
+                            || (flags & JSON_VALID_JSONB_SUPERFICIAL != 0
+                                && jsonb::is_superficially_valid(bytes));
+                        uint_builder.append_value(is_valid as u8);
+                    }
+                    _ => uint_builder.append_null(),
+                }
+                Ok::<(), DataFusionError>(())
+            })?;
+
+        Ok(ColumnarValue::Array(
+            Arc::new(uint_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for JsonValid {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_valid(X [, Y])",
+            description: "Returns 1 if X is well-formed JSON, or of one of the forms selected by the flags Y, and 0 otherwise",
+            examples: &["json_valid('{\"x\": 35}') → 1", "json_valid('{x: 35}', 6) → 1"],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The json_error_position(X) function returns 0 if the input X is a well-formed JSON string
+/// or JSONB blob. If the input X contains one or more syntax errors, then this function returns
+/// the 1-based character position of the first syntax error, or for a JSONB blob the 1-based
+/// byte position of the first element that cannot be decoded.
+///
+/// Examples:
+///
+/// json_error_position('{"x": 35}') → 0
+/// json_error_position('[1,2,x]') → 6
+/// json_error_position(NULL) → NULL
+#[derive(Debug)]
+pub struct JsonErrorPosition {
+    signature: Signature,
+}
+
+impl JsonErrorPosition {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, JSON_TYPES.to_vec(), Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonErrorPosition {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_error_position"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(UInt64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let json_inputs = get_json_inputs(&args[0])?;
+        let mut uint_builder = UInt64Array::builder(json_inputs.len());
+
+        json_inputs.iter().for_each(|json_input| {
+            let position = match json_input {
+                Some(JsonInput::Text(json_string)) => {
+                    Some(get_json_error_position(json_string).unwrap_or(0))
+                }
+                Some(JsonInput::Jsonb(bytes)) => Some(
+                    jsonb::decode(bytes)
+                        .err()
+                        .map(|err| err.offset + 1)
+                        .unwrap_or(0),
+                ),
+                None => None,
+            };
+            uint_builder.append_option(position.map(|position| position as u64));
         });
 
         Ok(ColumnarValue::Array(
@@ -227,6 +478,17 @@ impl ScalarUDFImpl for JsonValid {
     }
 }
 
+impl Documented for JsonErrorPosition {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_error_position(X)",
+            description: "Returns 0 if X is well-formed JSON or JSON5, and otherwise the 1-based character position of its first syntax error",
+            examples: &["json_error_position('[1,2,x]') → 6"],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
+    }
+}
+
 /// The json_array_length(X) function returns the number of elements in the JSON array X, or 0 if X is some kind of JSON value other than an array.
 /// The json_array_length(X,P) locates the array at path P within X and returns the length of that array, or 0 if path P locates an element in X that is not a JSON array,
 /// and NULL if path P does not locate any element of X. Errors are thrown if either X is not well-formed JSON or if P is not a well-formed path.
@@ -235,133 +497,1493 @@ pub struct JsonArrayLength {
     signature: Signature,
 }
 
-impl JsonArrayLength {
-    pub fn new() -> Self {
-        Self {
-            signature: Signature::one_of(
-                vec![Uniform(1, vec![Utf8]), Uniform(2, vec![Utf8])],
-                Volatility::Volatile,
-            ),
+impl JsonArrayLength {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                [
+                    vec![Uniform(1, JSON_TYPES.to_vec())],
+                    exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                ]
+                .concat(),
+                Volatility::Volatile,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonArrayLength {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_array_length"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(UInt64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let mut iter = args.iter();
+        let json_inputs = iter.next().ok_or(DataFusionError::Execution(
+            "First input not set".to_string(),
+        ))?;
+        let json_inputs = get_json_inputs(json_inputs)?;
+        let paths = iter.next();
+        let mut uint_builder = UInt64Array::builder(json_inputs.len());
+
+        match paths {
+            None => {
+                json_inputs.iter().try_for_each(|json_input| {
+                    if let Some(json_input) = json_input {
+                        if let Some(value) = json_input.node_at("$")? {
+                            uint_builder.append_value(value.array_length()?);
+                        } else {
+                            uint_builder.append_null();
+                        }
+                        Ok::<(), DataFusionError>(())
+                    } else {
+                        uint_builder.append_null();
+                        Ok::<(), DataFusionError>(())
+                    }
+                })?;
+            }
+            Some(paths) => {
+                let paths = string_values(paths)?;
+                json_inputs
+                    .iter()
+                    .zip(paths)
+                    .try_for_each(|(json_input, path)| {
+                        if let (Some(json_input), Some(path)) = (json_input, path) {
+                            match json_input.node_at(path)? {
+                                Some(json_at_path) => {
+                                    uint_builder.append_value(json_at_path.array_length()?);
+                                }
+                                None => {
+                                    uint_builder.append_null();
+                                }
+                            }
+                            Ok::<(), DataFusionError>(())
+                        } else {
+                            uint_builder.append_null();
+                            Ok::<(), DataFusionError>(())
+                        }
+                    })?;
+            }
+        }
+
+        Ok(ColumnarValue::Array(
+            Arc::new(uint_builder.finish()) as ArrayRef
+        ))
+    }
+}
+
+impl Documented for JsonArrayLength {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_array_length(X [, P])",
+            description: "Returns the number of elements in the JSON array X, or in the array selected by path P",
+            examples: &["json_array_length('[1,2,3,4]') → 4"],
+            link: Some(JSON_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The json_get_int(X,P) function returns the element of X selected by path P as an integer.
+/// Integers are returned as they are, reals are truncated towards zero and true/false become 1/0.
+/// NULL is returned if P does not select an element of X or the element cannot be converted.
+#[derive(Debug)]
+pub struct JsonGetInt {
+    signature: Signature,
+}
+
+impl JsonGetInt {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetInt {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_get_int"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        extract_as(args, &Int64)
+    }
+}
+
+impl Documented for JsonGetInt {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_get_int(X, P)",
+            description: "Returns the element of X selected by path P as an integer",
+            examples: &["json_get_int('{\"a\": 2.7}', '$.a') → 2"],
+            link: None,
+        }
+    }
+}
+
+/// The json_get_float(X,P) function returns the element of X selected by path P as a real.
+/// Integers and reals are returned as reals and true/false become 1.0/0.0.
+/// NULL is returned if P does not select an element of X or the element cannot be converted.
+#[derive(Debug)]
+pub struct JsonGetFloat {
+    signature: Signature,
+}
+
+impl JsonGetFloat {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetFloat {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_get_float"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        extract_as(args, &Float64)
+    }
+}
+
+impl Documented for JsonGetFloat {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_get_float(X, P)",
+            description: "Returns the element of X selected by path P as a real",
+            examples: &["json_get_float('{\"a\": 2}', '$.a') → 2.0"],
+            link: None,
+        }
+    }
+}
+
+/// The json_get_bool(X,P) function returns the element of X selected by path P as a boolean.
+/// true/false are returned as they are and numbers are true when they are not zero.
+/// NULL is returned if P does not select an element of X or the element cannot be converted.
+#[derive(Debug)]
+pub struct JsonGetBool {
+    signature: Signature,
+}
+
+impl JsonGetBool {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetBool {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_get_bool"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        extract_as(args, &Boolean)
+    }
+}
+
+impl Documented for JsonGetBool {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_get_bool(X, P)",
+            description: "Returns the element of X selected by path P as a boolean",
+            examples: &["json_get_bool('{\"a\": 1}', '$.a') → true"],
+            link: None,
+        }
+    }
+}
+
+/// The json_get_str(X,P) function returns the element of X selected by path P as text.
+/// Strings are returned without their quotes; any other element is returned as minified JSON.
+/// NULL is returned if P does not select an element of X or the element cannot be converted.
+#[derive(Debug)]
+pub struct JsonGetStr {
+    signature: Signature,
+}
+
+impl JsonGetStr {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &STRING_TYPES]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonGetStr {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_get_str"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(json_text_type(&arg_types[0]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        extract_as(args, &json_text_type(&args[0].data_type()))
+    }
+}
+
+impl Documented for JsonGetStr {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_get_str(X, P)",
+            description:
+                "Returns the element of X selected by path P as text, without quotes for strings",
+            examples: &["json_get_str('{\"a\": \"x\"}', '$.a') → x"],
+            link: None,
+        }
+    }
+}
+
+/// The json_extract_as(X,P,T) function returns the element of X selected by path P converted to
+/// the type named by the constant T, which is one of 'integer', 'real', 'boolean' or 'text'
+/// ('int', 'bigint', 'float', 'double', 'bool', 'string' and 'str' are also accepted).
+/// Conversions follow json_get_int, json_get_float, json_get_bool and json_get_str.
+///
+/// Examples:
+///
+/// json_extract_as('{"a":[2,3.5]}', '$.a[0]', 'integer') → 2
+/// json_extract_as('{"a":[2,3.5]}', '$.a[1]', 'real') → 3.5
+#[derive(Debug)]
+pub struct JsonExtractAs {
+    signature: Signature,
+}
+
+impl JsonExtractAs {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &STRING_TYPES, &[Utf8]]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonExtractAs {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_extract_as"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Err(DataFusionError::Internal(
+            "json_extract_as() chooses its return type from its arguments".to_string(),
+        ))
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        arg_types: &[DataType],
+    ) -> Result<DataType> {
+        match args.get(2) {
+            Some(Expr::Literal(ScalarValue::Utf8(Some(type_name)))) => {
+                get_extract_type(type_name, &arg_types[0])
+            }
+            _ => Err(DataFusionError::Plan(
+                "the type argument to json_extract_as() must be a constant string".to_string(),
+            )),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let data_type = match args.get(2) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(type_name)))) => {
+                get_extract_type(type_name, &args[0].data_type())?
+            }
+            _ => {
+                return Err(DataFusionError::Execution(
+                    "the type argument to json_extract_as() must be a constant string".to_string(),
+                ))
+            }
+        };
+        extract_as(&args[..2], &data_type)
+    }
+}
+
+impl Documented for JsonExtractAs {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_extract_as(X, P, TYPE)",
+            description: "Returns the element of X selected by path P as an integer, real, boolean or text column, as TYPE names",
+            examples: &["json_extract_as('{\"a\":[2,3.5]}', '$.a[1]', 'real') → 3.5"],
+            link: None,
+        }
+    }
+}
+
+/// Maps the type name given to json_extract_as() to the Arrow type it returns for JSON of type
+/// `json_type`.
+fn get_extract_type(type_name: &str, json_type: &DataType) -> Result<DataType> {
+    match type_name.to_ascii_lowercase().as_str() {
+        "integer" | "int" | "bigint" => Ok(Int64),
+        "real" | "float" | "double" => Ok(Float64),
+        "boolean" | "bool" => Ok(Boolean),
+        "text" | "string" | "str" => Ok(json_text_type(json_type)),
+        _ => Err(DataFusionError::Plan(format!(
+            "unknown type '{type_name}' for json_extract_as(), expected one of 'integer', 'real', 'boolean' or 'text'"
+        ))),
+    }
+}
+
+/// Extracts the element at path args[1] from the JSON in args[0] and converts it to `data_type`.
+fn extract_as(args: &[ColumnarValue], data_type: &DataType) -> Result<ColumnarValue> {
+    let args = ColumnarValue::values_to_arrays(args)?;
+    let json_inputs = get_json_inputs(&args[0])?;
+    let paths = string_values(&args[1])?;
+
+    let values = json_inputs
+        .iter()
+        .zip(paths)
+        .map(|(json_input, path)| match (json_input, path) {
+            (Some(json_input), Some(path)) => json_input
+                .node_at(path)?
+                .map(JsonElement::into_node)
+                .transpose(),
+            _ => Ok(None),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let values = values.iter().map(Option::as_ref);
+
+    let array: ArrayRef = match data_type {
+        Int64 => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_i64))
+                .collect::<Int64Array>(),
+        ),
+        Float64 => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_f64))
+                .collect::<Float64Array>(),
+        ),
+        Boolean => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_bool))
+                .collect::<BooleanArray>(),
+        ),
+        Utf8 | LargeUtf8 | Utf8View => {
+            let mut string_builder = StringOutput::new(data_type, values.len());
+            values.for_each(|value| match value.and_then(json_to_string) {
+                Some(string) => string_builder.append_value(string),
+                None => string_builder.append_null(),
+            });
+            string_builder.finish()
+        }
+        t => {
+            return Err(DataFusionError::Internal(format!(
+                "Unsupported type {t} for JSON extraction"
+            )))
+        }
+    };
+    Ok(ColumnarValue::Array(array))
+}
+
+fn json_to_i64(value: &JsonNode) -> Option<i64> {
+    match value {
+        JsonNode::Number(_) => value.as_i64().or_else(|| {
+            value
+                .as_f64()
+                .map(f64::trunc)
+                .filter(|float| *float >= i64::MIN as f64 && *float < i64::MAX as f64)
+                .map(|float| float as i64)
+        }),
+        JsonNode::Bool(bool_value) => Some(i64::from(*bool_value)),
+        _ => None,
+    }
+}
+
+fn json_to_f64(value: &JsonNode) -> Option<f64> {
+    match value {
+        JsonNode::Number(_) => value.as_f64(),
+        JsonNode::Bool(bool_value) => Some(f64::from(u8::from(*bool_value))),
+        _ => None,
+    }
+}
+
+fn json_to_bool(value: &JsonNode) -> Option<bool> {
+    match value {
+        JsonNode::Bool(bool_value) => Some(*bool_value),
+        JsonNode::Number(_) => value.as_f64().map(|float| float != 0.0),
+        _ => None,
+    }
+}
+
+fn json_to_string(value: &JsonNode) -> Option<String> {
+    match value {
+        JsonNode::Null => None,
+        JsonNode::String(string) => Some(string.clone()),
+        value => Some(value.to_string()),
+    }
+}
+
+/// The json_to_struct(X,S) function converts each JSON object in X into a struct whose fields
+/// are described by the constant schema S, a comma separated list of `name type` pairs such
+/// as 'id bigint, name text, tags text[]'. It is the equivalent of Postgres's jsonb_to_record.
+///
+/// The types are boolean, bigint, double, text, json (any element, kept as JSON text),
+/// struct<...> holding a nested schema, and any of these followed by [] for a list.
+/// Members that are missing or cannot be converted to their field's type are NULL, as are
+/// rows that are not JSON objects. json_infer_schema(X) writes a schema that fits a column.
+///
+/// Examples:
+///
+/// json_to_struct('{"a":1,"b":"x"}', 'a bigint, b text') → {a: 1, b: x}
+#[derive(Debug)]
+pub struct JsonToStruct {
+    signature: Signature,
+}
+
+impl JsonToStruct {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(
+                exact_signatures(&[&JSON_TYPES, &[Utf8]]),
+                Volatility::Immutable,
+            ),
+        }
+    }
+}
+
+impl ScalarUDFImpl for JsonToStruct {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_to_struct"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Err(DataFusionError::Internal(
+            "json_to_struct() chooses its return type from its arguments".to_string(),
+        ))
+    }
+
+    fn return_type_from_exprs(
+        &self,
+        args: &[Expr],
+        _schema: &dyn ExprSchema,
+        _arg_types: &[DataType],
+    ) -> Result<DataType> {
+        match args.get(1) {
+            Some(Expr::Literal(ScalarValue::Utf8(Some(schema)))) => {
+                Ok(Struct(get_schema_fields(&get_struct_schema(schema)?)))
+            }
+            _ => Err(DataFusionError::Plan(
+                "the schema argument to json_to_struct() must be a constant string".to_string(),
+            )),
+        }
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let fields = match args.get(1) {
+            Some(ColumnarValue::Scalar(ScalarValue::Utf8(Some(schema)))) => {
+                get_struct_schema(schema)?
+            }
+            _ => {
+                return Err(DataFusionError::Execution(
+                    "the schema argument to json_to_struct() must be a constant string".to_string(),
+                ))
+            }
+        };
+
+        let args = ColumnarValue::values_to_arrays(&args[..1])?;
+        let values = get_json_inputs(&args[0])?
+            .iter()
+            .map(|json_input| json_input.as_ref().map(JsonInput::parse).transpose())
+            .collect::<Result<Vec<_>>>()?;
+        let values = values.iter().map(Option::as_ref).collect::<Vec<_>>();
+
+        Ok(ColumnarValue::Array(
+            Arc::new(build_struct_array(&values, &fields)?) as ArrayRef,
+        ))
+    }
+}
+
+impl Documented for JsonToStruct {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_to_struct(X, SCHEMA)",
+            description: "Returns the JSON object X as a struct with the fields of SCHEMA, e.g. 'a bigint, b text[]'",
+            examples: &["json_to_struct('{\"a\":1,\"b\":\"x\"}', 'a bigint, b text') → {a: 1, b: x}"],
+            link: None,
+        }
+    }
+}
+
+/// The json_infer_schema(X) aggregate function scans the JSON objects in X and returns a
+/// schema for json_to_struct() that fits all of them. Values that are not objects are skipped
+/// and NULL is returned if no object has any members.
+///
+/// Integers that appear alongside reals become double, members that are always null become
+/// text and members whose values disagree in any other way become json.
+///
+/// Examples:
+///
+/// json_infer_schema(X) over '{"a":1}' and '{"a":2.5,"b":["x"]}' → 'a double, b text[]'
+#[derive(Debug)]
+pub struct JsonInferSchema {
+    signature: Signature,
+}
+
+impl JsonInferSchema {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::uniform(1, JSON_TYPES.to_vec(), Volatility::Immutable),
+        }
+    }
+}
+
+impl AggregateUDFImpl for JsonInferSchema {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "json_infer_schema"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn accumulator(&self, _acc_args: AccumulatorArgs) -> Result<Box<dyn Accumulator>> {
+        Ok(Box::new(JsonInferSchemaAccumulator::default()))
+    }
+}
+
+impl Documented for JsonInferSchema {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "json_infer_schema(X)",
+            description: "An aggregate that returns a json_to_struct schema fitting every JSON object in X",
+            examples: &["json_infer_schema(X) over '{\"a\":1}' and '{\"a\":2.5,\"b\":[\"x\"]}' → a double, b text[]"],
+            link: None,
+        }
+    }
+}
+
+/// Gathers the fields seen so far. Its state is the schema written with `null` for members
+/// that have only been null, so that partial results merge the same way rows do.
+#[derive(Debug, Default)]
+struct JsonInferSchemaAccumulator {
+    fields: Vec<SchemaField>,
+}
+
+impl Accumulator for JsonInferSchemaAccumulator {
+    fn update_batch(&mut self, values: &[ArrayRef]) -> Result<()> {
+        get_json_inputs(&values[0])?
+            .iter()
+            .flatten()
+            .try_for_each(|json_input| {
+                if let SchemaType::Struct(fields) = SchemaType::infer(&json_input.parse()?) {
+                    self.fields = merge_fields(std::mem::take(&mut self.fields), fields);
+                }
+                Ok::<(), DataFusionError>(())
+            })
+    }
+
+    fn evaluate(&mut self) -> Result<ScalarValue> {
+        let fields = resolve_fields(self.fields.clone());
+        Ok(ScalarValue::Utf8(
+            (!fields.is_empty()).then(|| render_schema(&fields)),
+        ))
+    }
+
+    fn size(&self) -> usize {
+        std::mem::size_of_val(self) + self.fields.capacity() * std::mem::size_of::<SchemaField>()
+    }
+
+    fn state(&mut self) -> Result<Vec<ScalarValue>> {
+        Ok(vec![ScalarValue::Utf8(Some(render_schema(&self.fields)))])
+    }
+
+    fn merge_batch(&mut self, states: &[ArrayRef]) -> Result<()> {
+        datafusion::common::cast::as_string_array(&states[0])?
+            .iter()
+            .flatten()
+            .try_for_each(|schema| {
+                let fields = parse_partial_schema(schema)
+                    .map_err(|err| DataFusionError::Internal(err.to_string()))?;
+                self.fields = merge_fields(std::mem::take(&mut self.fields), fields);
+                Ok::<(), DataFusionError>(())
+            })
+    }
+}
+
+/// Parses the schema given to json_to_struct().
+fn get_struct_schema(schema: &str) -> Result<Vec<SchemaField>> {
+    parse_schema(schema).map_err(|err| DataFusionError::Plan(err.to_string()))
+}
+
+fn get_schema_fields(fields: &[SchemaField]) -> Fields {
+    fields
+        .iter()
+        .map(|(name, field_type)| Field::new(name, get_schema_data_type(field_type), true))
+        .collect()
+}
+
+fn get_schema_data_type(schema_type: &SchemaType) -> DataType {
+    match schema_type {
+        SchemaType::Null => Null,
+        SchemaType::Boolean => Boolean,
+        SchemaType::BigInt => Int64,
+        SchemaType::Double => Float64,
+        SchemaType::Text | SchemaType::Json => Utf8,
+        SchemaType::List(element_type) => List(get_list_field(element_type)),
+        SchemaType::Struct(fields) => Struct(get_schema_fields(fields)),
+    }
+}
+
+fn get_list_field(element_type: &SchemaType) -> FieldRef {
+    Arc::new(Field::new("item", get_schema_data_type(element_type), true))
+}
+
+/// Converts each value to `schema_type`, leaving NULL where a value cannot be converted.
+fn build_array(values: &[Option<&JsonNode>], schema_type: &SchemaType) -> Result<ArrayRef> {
+    let values = values.iter().copied();
+    let array: ArrayRef = match schema_type {
+        SchemaType::Null => Arc::new(NullArray::new(values.len())),
+        SchemaType::Boolean => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_bool))
+                .collect::<BooleanArray>(),
+        ),
+        SchemaType::BigInt => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_i64))
+                .collect::<Int64Array>(),
+        ),
+        SchemaType::Double => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_f64))
+                .collect::<Float64Array>(),
+        ),
+        SchemaType::Text => Arc::new(
+            values
+                .map(|value| value.and_then(json_to_string))
+                .collect::<StringArray>(),
+        ),
+        SchemaType::Json => Arc::new(
+            values
+                .map(|value| {
+                    value
+                        .filter(|value| !value.is_null())
+                        .map(JsonNode::to_string)
+                })
+                .collect::<StringArray>(),
+        ),
+        SchemaType::List(element_type) => {
+            let mut elements = Vec::new();
+            let mut lengths = Vec::with_capacity(values.len());
+            let mut validity = Vec::with_capacity(values.len());
+            values.for_each(|value| match value.and_then(JsonNode::as_array) {
+                Some(array) => {
+                    elements.extend(array.iter().map(Some));
+                    lengths.push(array.len());
+                    validity.push(true);
+                }
+                None => {
+                    lengths.push(0);
+                    validity.push(false);
+                }
+            });
+            Arc::new(ListArray::try_new(
+                get_list_field(element_type),
+                OffsetBuffer::from_lengths(lengths),
+                build_array(&elements, element_type)?,
+                Some(NullBuffer::from(validity)),
+            )?)
+        }
+        SchemaType::Struct(fields) => {
+            let values = values.collect::<Vec<_>>();
+            Arc::new(build_struct_array(&values, fields)?)
+        }
+    };
+    Ok(array)
+}
+
+/// Builds a struct from the members of each JSON object. Values that are not objects are NULL.
+fn build_struct_array(values: &[Option<&JsonNode>], fields: &[SchemaField]) -> Result<StructArray> {
+    let objects = values
+        .iter()
+        .map(|value| value.filter(|value| matches!(value, JsonNode::Object(_))))
+        .collect::<Vec<_>>();
+
+    let columns = fields
+        .iter()
+        .map(|(name, field_type)| {
+            let members = objects
+                .iter()
+                .map(|object| object.and_then(|object| object.get(name)))
+                .collect::<Vec<_>>();
+            build_array(&members, field_type)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let validity = objects.iter().map(Option::is_some).collect::<Vec<_>>();
+
+    Ok(StructArray::try_new(
+        get_schema_fields(fields),
+        columns,
+        Some(NullBuffer::from(validity)),
+    )?)
+}
+
+/// A single row of a JSON argument. SQLite's JSON functions accept either JSON text
+/// or a JSONB blob wherever they take JSON.
+enum JsonInput<'a> {
+    Text(&'a str),
+    Jsonb(&'a [u8]),
+}
+
+impl JsonInput<'_> {
+    fn parse(&self) -> Result<JsonNode> {
+        match self {
+            JsonInput::Text(json_string) => parse_json(json_string).map_err(|err| {
+                DataFusionError::Internal(format!(
+                    "Runtime error: {}",
+                    describe_json_error(json_string, &err)
+                ))
+            }),
+            JsonInput::Jsonb(bytes) => jsonb::decode(bytes).map_err(malformed_jsonb),
+        }
+    }
+
+    /// The outermost element of this row.
+    fn root(&self) -> Result<JsonElement<'_>> {
+        match self {
+            JsonInput::Text(_) => self.parse().map(JsonElement::Node),
+            JsonInput::Jsonb(bytes) => JsonbElement::root(bytes)
+                .map(JsonElement::Jsonb)
+                .map_err(malformed_jsonb),
+        }
+    }
+
+    /// Returns the element at `path`, or `None` if nothing is there. Paths into JSONB are
+    /// followed without decoding the document.
+    fn node_at(&self, path: &str) -> Result<Option<JsonElement<'_>>> {
+        let path = JsonPath::parse(path).map_err(bad_path)?;
+        match self {
+            JsonInput::Text(_) => {
+                let value = self.parse()?;
+                let node = follow(
+                    &value,
+                    path,
+                    |value| value.is_array(),
+                    |value, step| Ok(step.select(value)),
+                )?;
+                Ok(node.cloned().map(JsonElement::Node))
+            }
+            JsonInput::Jsonb(bytes) => {
+                let root = JsonbElement::root(bytes).map_err(malformed_jsonb)?;
+                let node = follow(root, path, JsonbElement::is_array, |element, step| {
+                    element.select(step).map_err(malformed_jsonb)
+                })?;
+                Ok(node.map(JsonElement::Jsonb))
+            }
+        }
+    }
+}
+
+/// Follows `path` from `node` a step at a time, returning `None` as soon as a step selects
+/// nothing, so that the rest of the path is not read.
+fn follow<N>(
+    mut node: N,
+    mut path: JsonPath,
+    is_array: impl Fn(&N) -> bool,
+    select: impl Fn(&N, &PathStep) -> Result<Option<N>>,
+) -> Result<Option<N>> {
+    while let Some(step) = path.next_step().map_err(bad_path)? {
+        if step == PathStep::BadIndex && is_array(&node) {
+            return Err(bad_path(path.error()));
+        }
+        match select(&node, &step)? {
+            Some(child) => node = child,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(node))
+}
+
+fn get_json_inputs(array: &ArrayRef) -> Result<Vec<Option<JsonInput<'_>>>> {
+    match array.data_type() {
+        Utf8 | LargeUtf8 | Utf8View => Ok(string_values(array)?
+            .into_iter()
+            .map(|json_string| json_string.map(JsonInput::Text))
+            .collect()),
+        Binary => Ok(datafusion::common::cast::as_binary_array(array)?
+            .iter()
+            .map(|bytes| bytes.map(JsonInput::Jsonb))
+            .collect()),
+        LargeBinary => Ok(array
+            .as_binary::<i64>()
+            .iter()
+            .map(|bytes| bytes.map(JsonInput::Jsonb))
+            .collect()),
+        t => Err(DataFusionError::Internal(format!(
+            "Unsupported type {t} for JSON argument"
+        ))),
+    }
+}
+
+/// Returns the type of the text returned for JSON of type `json_type`, which is large for large
+/// text and blobs.
+fn json_text_type(json_type: &DataType) -> DataType {
+    match json_type {
+        LargeBinary => LargeUtf8,
+        json_type => string_return_type(json_type),
+    }
+}
+
+/// Returns the type of the JSONB returned for JSON of type `json_type`.
+fn jsonb_type(json_type: &DataType) -> DataType {
+    match json_type {
+        LargeUtf8 | LargeBinary => LargeBinary,
+        _ => Binary,
+    }
+}
+
+/// A JSON element located in a row, either decoded from text or still in its JSONB
+/// form. JSONB elements are read in place, so asking for the type or length of part of a
+/// pre-parsed document does not decode the rest of it.
+enum JsonElement<'a> {
+    Node(JsonNode),
+    Jsonb(JsonbElement<'a>),
+}
+
+impl JsonElement<'_> {
+    fn json_type(&self) -> Result<String> {
+        match self {
+            JsonElement::Node(node) => Ok(node.json_type().to_string()),
+            JsonElement::Jsonb(element) => element
+                .json_type()
+                .map(str::to_string)
+                .map_err(malformed_jsonb),
+        }
+    }
+
+    /// Decodes this element and everything nested inside it.
+    fn into_node(self) -> Result<JsonNode> {
+        match self {
+            JsonElement::Node(node) => Ok(node),
+            JsonElement::Jsonb(element) => element.decode().map_err(malformed_jsonb),
+        }
+    }
+
+    fn array_length(&self) -> Result<u64> {
+        match self {
+            JsonElement::Node(node) => Ok(node.as_array().map_or(0, <[JsonNode]>::len) as u64),
+            JsonElement::Jsonb(element) => element
+                .array_length()
+                .map(|length| length as u64)
+                .map_err(malformed_jsonb),
+        }
+    }
+}
+
+fn malformed_jsonb(err: JsonbError) -> DataFusionError {
+    DataFusionError::Internal(format!("Runtime error: malformed JSONB: {err}"))
+}
+
+fn bad_path(err: PathError) -> DataFusionError {
+    DataFusionError::Internal(format!("Runtime error: {err}"))
+}
+#[cfg(feature = "sqlite")]
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::StringViewArray;
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_json_data_test;
+    use crate::sqlite::register_sqlite_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_json() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, json(json_data) as col_result FROM json_values_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------------------------------+
+| index | col_result                        |
++-------+-----------------------------------+
+| 1     | {"this":"is","a":["test"]}        |
+| 2     | {"a":[2,3.5,true,false,null,"x"]} |
+| 3     | ["one","two"]                     |
+| 4     | 123                               |
+| 5     | 12.3                              |
+| 6     | true                              |
+| 7     | false                             |
+| 8     |                                   |
++-------+-----------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_invalid_json() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, json(' { "this" : "is", "a": [ "test" ]  ') as col_result FROM json_values_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let result = df.clone().collect().await;
+
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .find_root()
+            .to_string()
+            .contains("Internal error: Runtime error: malformed JSON"));
+
+        let df = ctx
+            .sql(r#"select json(' { "this" : "is", "a": [ "test" ]  ') as col_result"#)
+            .await?;
+
+        let result = df.clone().collect().await;
+
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .to_string()
+            .contains(r#"malformed JSON at line 1 column 36 near '"test" ]  '"#));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_error_position() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx.sql(r#"select index, json_error_position(json_data) as col_result FROM json_values_table ORDER BY index ASC"#).await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------+
+| index | col_result |
++-------+------------+
+| 1     | 0          |
+| 2     | 0          |
+| 3     | 0          |
+| 4     | 0          |
+| 5     | 0          |
+| 6     | 0          |
+| 7     | 0          |
+| 8     |            |
++-------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(r#"select json_error_position('[1,2,x]') as a, json_error_position('{"x": 35') as b"#)
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---+---+
+| a | b |
++---+---+
+| 6 | 9 |
++---+---+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_valid() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx.sql(r#"select index, json_valid(json_data) as col_result FROM json_values_table ORDER BY index ASC"#).await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------------+
+| index | col_result |
++-------+------------+
+| 1     | 1          |
+| 2     | 1          |
+| 3     | 1          |
+| 4     | 1          |
+| 5     | 1          |
+| 6     | 1          |
+| 7     | 1          |
+| 8     |            |
++-------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_valid_with_flags() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json_valid('{x: 35,}') as rfc, json_valid('{x: 35,}', 2) as json5, json_valid('{"x": 35}', 2) as json5_rfc, json_valid('{x: 35,}', 4) as jsonb"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----+-------+-----------+-------+
+| rfc | json5 | json5_rfc | jsonb |
++-----+-------+-----------+-------+
+| 0   | 1     | 1         | 0     |
++-----+-------+-----------+-------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(r#"select json_valid('{}', 16) as col_result"#)
+            .await?;
+        let result = df.clone().collect().await;
+        assert!(result
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("FLAGS parameter to json_valid() must be between 1 and 15"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json5() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json('{a: 0x10, /* comment */ b: [.5, ''x'',],}') as json, json_type('{a: [1,]}', '$.a') as type, json_array_length('[1, 2, 3,]') as len, json('[Infinity, -Infinity, NaN, 1.50]') as special, json(jsonb('-Infinity')) as jsonb, json_type('Infinity') as inf_type"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------------------------+-------+-----+--------------------------+--------+----------+
+| json                   | type  | len | special                  | jsonb  | inf_type |
++------------------------+-------+-----+--------------------------+--------+----------+
+| {"a":16,"b":[0.5,"x"]} | array | 3   | [9e999,-9e999,null,1.50] | -9e999 | real     |
++------------------------+-------+-----+--------------------------+--------+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_jsonb() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, json(jsonb(json_data)) as col_result, json_type(jsonb(json_data)) as type FROM json_values_table ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------------------------------+---------+
+| index | col_result                        | type    |
++-------+-----------------------------------+---------+
+| 1     | {"this":"is","a":["test"]}        | object  |
+| 2     | {"a":[2,3.5,true,false,null,"x"]} | object  |
+| 3     | ["one","two"]                     | array   |
+| 4     | 123                               | integer |
+| 5     | 12.3                              | real    |
+| 6     | true                              | true    |
+| 7     | false                             | false   |
+| 8     |                                   |         |
++-------+-----------------------------------+---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                r#"select json_valid(jsonb('{"a":[1,2]}')) as text_only, json_valid(jsonb('{"a":[1,2]}'), 8) as strict, json_array_length(jsonb('{"a":[1,2]}'), '$.a') as len, json_error_position(jsonb('[1]')) as position"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-----------+--------+-----+----------+
+| text_only | strict | len | position |
++-----------+--------+-----+----------+
+| 0         | 1      | 2   | 0        |
++-----------+--------+-----+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_parse() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json_type(parsed, '$.a') as a_type, json_type(parsed, '$.a[1]') as element_type, json_type(parsed, '$.b') as missing, json_array_length(parsed, '$.a') as len, json_array_length(parsed) as root_len FROM (select json_parse(json_data) as parsed from json_path_table)"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+--------------+---------+-----+----------+
+| a_type | element_type | missing | len | root_len |
++--------+--------------+---------+-----+----------+
+| array  | real         |         | 6   | 0        |
++--------+--------------+---------+-----+----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_json_paths() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json_type('[1,2,"x"]', '$[#-1]') as last, json_type(jsonb('{"a b":[1,2.5]}'), '$."a b"[#-1]') as quoted, json_array_length('{"a":[[1,2]]}', '$.a[#-1]') as len, json_type('[1]', '$[#]') as past_end, json_type('{"a":1}', '$[-1]') as not_array"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------+--------+-----+----------+-----------+
+| last | quoted | len | past_end | not_array |
++------+--------+-----+----------+-----------+
+| text | real   | 2   |          |           |
++------+--------+-----+----------+-----------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        for sql in [
+            "select json_type('[1]', 'a')",
+            "select json_type('{\"a\":1}', '$.')",
+            "select json_type('[1]', '$[-1]')",
+            "select json_type('[1,2,x]', '$')",
+        ] {
+            assert!(ctx.sql(sql).await?.collect().await.is_err(), "{sql}");
         }
-    }
-}
 
-impl ScalarUDFImpl for JsonArrayLength {
-    fn as_any(&self) -> &dyn std::any::Any {
-        self
+        Ok(())
     }
 
-    fn name(&self) -> &str {
-        "json_array_length"
-    }
+    #[tokio::test]
+    async fn test_json_duplicate_keys() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json(doc) as text, json(jsonb(doc)) as round_trip, json_get_int(doc, '$.a') as text_a, json_get_int(json_parse(doc), '$.a') as jsonb_a, json_get_str(json(doc), '$.b') as b, json_to_struct(doc, 'a bigint') as record FROM (select '{"a":1,"b":"x","a":2}' as doc)"#,
+            )
+            .await?;
 
-    fn signature(&self) -> &Signature {
-        &self.signature
-    }
+        let batches = df.clone().collect().await?;
 
-    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
-        Ok(UInt64)
+        let expected: Vec<&str> = r#"
++-----------------------+-----------------------+--------+---------+---+--------+
+| text                  | round_trip            | text_a | jsonb_a | b | record |
++-----------------------+-----------------------+--------+---------+---+--------+
+| {"a":1,"b":"x","a":2} | {"a":1,"b":"x","a":2} | 1      | 1       | x | {a: 1} |
++-----------------------+-----------------------+--------+---------+---+--------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
     }
 
-    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
-        let args = ColumnarValue::values_to_arrays(args)?;
-        let mut iter = args.iter();
-        let json_strings = iter.next().ok_or(DataFusionError::Execution(
-            "First input not set".to_string(),
-        ))?;
-        let json_strings = datafusion::common::cast::as_string_array(&json_strings)?;
-        let paths = iter.next();
-        let mut uint_builder = UInt64Array::builder(json_strings.len());
+    #[tokio::test]
+    async fn test_json_get_typed() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index, json_get_int(json_data, '$') as int, json_get_float(json_data, '$') as float, json_get_bool(json_data, '$') as bool, json_get_str(json_data, '$') as str FROM json_values_table ORDER BY index ASC"#,
+            )
+            .await?;
 
-        match paths {
-            None => {
-                json_strings.iter().try_for_each(|json_string| {
-                    if let Some(json_string) = json_string {
-                        if let Ok(value) = get_value_at_string(json_string, "$") {
-                            if let Some(value_array) = value.as_array() {
-                                uint_builder.append_value(value_array.len() as u64);
-                            } else {
-                                uint_builder.append_value(0u64);
-                            }
-                        } else {
-                            uint_builder.append_null();
-                        }
-                        Ok::<(), DataFusionError>(())
-                    } else {
-                        uint_builder.append_null();
-                        Ok::<(), DataFusionError>(())
-                    }
-                })?;
-            }
-            Some(paths) => {
-                let paths = datafusion::common::cast::as_string_array(&paths)?;
-                json_strings
-                    .iter()
-                    .zip(paths.iter())
-                    .try_for_each(|(json_string, path)| {
-                        if let (Some(json_string), Some(path)) = (json_string, path) {
-                            match get_value_at_string(json_string, path) {
-                                Ok(json_at_path) => {
-                                    if let Some(value_array) = json_at_path.as_array() {
-                                        uint_builder.append_value(value_array.len() as u64);
-                                    } else {
-                                        uint_builder.append_value(0u64);
-                                    }
-                                }
-                                Err(_) => {
-                                    uint_builder.append_null();
-                                }
-                            }
-                            Ok::<(), DataFusionError>(())
-                        } else {
-                            uint_builder.append_null();
-                            Ok::<(), DataFusionError>(())
-                        }
-                    })?;
-            }
-        }
+        let batches = df.clone().collect().await?;
 
-        Ok(ColumnarValue::Array(
-            Arc::new(uint_builder.finish()) as ArrayRef
-        ))
+        let expected: Vec<&str> = r#"
++-------+-----+-------+-------+-----------------------------------+
+| index | int | float | bool  | str                               |
++-------+-----+-------+-------+-----------------------------------+
+| 1     |     |       |       | {"this":"is","a":["test"]}        |
+| 2     |     |       |       | {"a":[2,3.5,true,false,null,"x"]} |
+| 3     |     |       |       | ["one","two"]                     |
+| 4     | 123 | 123.0 | true  | 123                               |
+| 5     | 12  | 12.3  | true  | 12.3                              |
+| 6     | 1   | 1.0   | true  | true                              |
+| 7     | 0   | 0.0   | false | false                             |
+| 8     |     |       |       |                                   |
++-------+-----+-------+-------+-----------------------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
     }
-}
 
-#[cfg(feature = "sqlite")]
-#[cfg(test)]
-mod tests {
-    use datafusion::assert_batches_sorted_eq;
-    use datafusion::prelude::SessionContext;
+    #[tokio::test]
+    async fn test_json_extract_as() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select json_extract_as(json_data, '$.a[0]', 'integer') as a0, arrow_typeof(json_extract_as(json_data, '$.a[0]', 'integer')) as a0_type, json_extract_as(json_data, '$.a[1]', 'REAL') as a1, json_extract_as(json_data, '$.a[2]', 'bool') as a2, json_extract_as(json_parse(json_data), '$.a[5]', 'text') as a5, json_extract_as(json_data, '$.a[4]', 'integer') as a4 FROM json_path_table"#,
+            )
+            .await?;
 
-    use crate::common::test_utils::set_up_json_data_test;
-    use crate::sqlite::register_sqlite_udfs;
+        let batches = df.clone().collect().await?;
 
-    use super::*;
+        let expected: Vec<&str> = r#"
++----+---------+-----+------+----+----+
+| a0 | a0_type | a1  | a2   | a5 | a4 |
++----+---------+-----+------+----+----+
+| 2  | Int64   | 3.5 | true | x  |    |
++----+---------+-----+------+----+----+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql(r#"select json_extract_as(json_data, '$.a', 'date') as result FROM json_path_table"#)
+            .await;
+
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .to_string()
+            .contains("unknown type 'date' for json_extract_as()"));
+
+        Ok(())
+    }
 
     #[tokio::test]
-    async fn test_json() -> Result<()> {
+    async fn test_json_get_int_predicate() -> Result<()> {
         let ctx = register_udfs_for_test()?;
         let df = ctx
             .sql(
-                r#"select index, json(json_data) as col_result FROM json_values_table ORDER BY index ASC"#,
+                r#"select index FROM json_values_table WHERE json_get_int(json_data, '$') > 10 ORDER BY index ASC"#,
             )
             .await?;
 
         let batches = df.clone().collect().await?;
 
         let expected: Vec<&str> = r#"
-+-------+-----------------------------------+
-| index | col_result                        |
-+-------+-----------------------------------+
-| 1     | {"this":"is","a":["test"]}        |
-| 2     | {"a":[2,3.5,true,false,null,"x"]} |
-| 3     | ["one","two"]                     |
-| 4     | 123                               |
-| 5     | 12.3                              |
-| 6     | true                              |
-| 7     | false                             |
-| 8     |                                   |
-+-------+-----------------------------------+"#
++-------+
+| index |
++-------+
+| 4     |
+| 5     |
++-------+"#
             .split('\n')
             .filter_map(|input| {
                 if input.is_empty() {
@@ -372,51 +1994,126 @@ mod tests {
             })
             .collect();
         assert_batches_sorted_eq!(expected, &batches);
+
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_invalid_json() -> Result<()> {
+    async fn test_json_to_struct() -> Result<()> {
         let ctx = register_udfs_for_test()?;
         let df = ctx
             .sql(
-                r#"select index, json(' { "this" : "is", "a": [ "test" ]  ') as col_result FROM json_values_table ORDER BY index ASC"#,
+                r#"select index, s['this'] as this, s['a'][1] as first, s['a'][6] as last FROM (select index, json_to_struct(json_data, 'this text, a text[]') as s from json_values_table) ORDER BY index ASC"#,
             )
             .await?;
 
-        let result = df.clone().collect().await;
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+------+-------+------+
+| index | this | first | last |
++-------+------+-------+------+
+| 1     | is   | test  |      |
+| 2     |      | 2     | x    |
+| 3     |      |       |      |
+| 4     |      |       |      |
+| 5     |      |       |      |
+| 6     |      |       |      |
+| 7     |      |       |      |
+| 8     |      |       |      |
++-------+------+-------+------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                r#"select s['n'] as n, s['flag'] as flag, s['nested']['x'][2] as x, s['missing'] as missing FROM (select json_to_struct('{"n": 12.7, "flag": 1, "nested": {"x": [1, 2]}}', 'n bigint, flag boolean, nested struct<x bigint[]>, missing text') as s)"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++----+------+---+---------+
+| n  | flag | x | missing |
++----+------+---+---------+
+| 12 | true | 2 |         |
++----+------+---+---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let result = ctx
+            .sql(r#"select json_to_struct(json_data, 'a date') as s FROM json_values_table"#)
+            .await;
 
         assert!(&result
             .err()
             .unwrap()
             .find_root()
-            .find_root()
             .to_string()
-            .contains("Internal error: Runtime error: malformed JSON"));
+            .contains("invalid schema at character 3: unknown type \"date\""));
 
         Ok(())
     }
 
     #[tokio::test]
-    async fn test_json_valid() -> Result<()> {
+    async fn test_json_infer_schema() -> Result<()> {
         let ctx = register_udfs_for_test()?;
-        let df = ctx.sql(r#"select index, json_valid(json_data) as col_result FROM json_values_table ORDER BY index ASC"#).await?;
+        let df = ctx
+            .sql(
+                r#"select json_infer_schema(json_data) as schema, json_infer_schema(json_parse(json_data)) as parsed_schema FROM json_values_table"#,
+            )
+            .await?;
 
         let batches = df.clone().collect().await?;
 
         let expected: Vec<&str> = r#"
-+-------+------------+
-| index | col_result |
-+-------+------------+
-| 1     | 1          |
-| 2     | 1          |
-| 3     | 1          |
-| 4     | 1          |
-| 5     | 1          |
-| 6     | 1          |
-| 7     | 1          |
-| 8     |            |
-+-------+------------+"#
++---------------------+---------------------+
+| schema              | parsed_schema       |
++---------------------+---------------------+
+| this text, a json[] | this text, a json[] |
++---------------------+---------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                r#"select json_infer_schema(json_data) as schema FROM json_values_table WHERE index > 2"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+
+| schema |
++--------+
+|        |
++--------+"#
             .split('\n')
             .filter_map(|input| {
                 if input.is_empty() {
@@ -427,6 +2124,7 @@ mod tests {
             })
             .collect();
         assert_batches_sorted_eq!(expected, &batches);
+
         Ok(())
     }
 
@@ -924,6 +2622,59 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_large_strings() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                r#"select index,
+                arrow_typeof(json(arrow_cast(json_data, 'LargeUtf8'))) as json_type,
+                arrow_typeof(jsonb(arrow_cast(json_data, 'LargeUtf8'))) as jsonb_type,
+                json_type(arrow_cast(json_data, 'LargeUtf8'), arrow_cast('$.a', 'LargeUtf8')) as col_type,
+                json_get_str(arrow_cast(json_data, 'LargeUtf8'), '$.this') as col_str
+                FROM json_values_table WHERE index <= 3 ORDER BY index ASC"#,
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------+-------------+----------+---------+
+| index | json_type | jsonb_type  | col_type | col_str |
++-------+-----------+-------------+----------+---------+
+| 1     | LargeUtf8 | LargeBinary | array    | is      |
+| 2     | LargeUtf8 | LargeBinary | array    |         |
+| 3     | LargeUtf8 | LargeBinary |          |         |
++-------+-----------+-------------+----------+---------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[test]
+    fn test_string_views() -> Result<()> {
+        let json_data = StringViewArray::from(vec![Some(r#" { "this" : "is" } "#), None]);
+        let result = Json::new().invoke(&[ColumnarValue::Array(Arc::new(json_data))])?;
+        let ColumnarValue::Array(result) = result else {
+            panic!("expected an array")
+        };
+
+        assert_eq!(result.data_type(), &Utf8View);
+        assert_eq!(
+            string_values(&result)?,
+            vec![Some(r#"{"this":"is"}"#), None]
+        );
+        Ok(())
+    }
+
     fn register_udfs_for_test() -> Result<SessionContext> {
         let ctx = set_up_json_data_test()?;
         register_sqlite_udfs(&ctx)?;
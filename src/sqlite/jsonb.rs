@@ -0,0 +1,526 @@
+use std::fmt::{Display, Formatter};
+
+use crate::sqlite::json5;
+use crate::sqlite::json_node::JsonNode;
+use crate::sqlite::json_path::PathStep;
+
+// Element types, stored in the low four bits of each element header.
+const JSONB_NULL: u8 = 0;
+const JSONB_TRUE: u8 = 1;
+const JSONB_FALSE: u8 = 2;
+const JSONB_INT: u8 = 3;
+const JSONB_INT5: u8 = 4;
+const JSONB_FLOAT: u8 = 5;
+const JSONB_FLOAT5: u8 = 6;
+const JSONB_TEXT: u8 = 7;
+const JSONB_TEXTJ: u8 = 8;
+const JSONB_TEXT5: u8 = 9;
+const JSONB_TEXTRAW: u8 = 10;
+const JSONB_ARRAY: u8 = 11;
+const JSONB_OBJECT: u8 = 12;
+
+/// Nesting limit for arrays and objects, matching SQLite's JSON_MAX_DEPTH.
+const MAX_DEPTH: usize = 1000;
+
+/// An error found while decoding a JSONB blob.
+/// `offset` is the byte offset of the element that could not be decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct JsonbError {
+    pub(crate) offset: usize,
+    pub(crate) message: String,
+}
+
+impl Display for JsonbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte offset {}", self.message, self.offset)
+    }
+}
+
+impl std::error::Error for JsonbError {}
+
+fn error(offset: usize, message: &str) -> JsonbError {
+    JsonbError {
+        offset,
+        message: message.to_string(),
+    }
+}
+
+/// Encodes a JSON value in SQLite's JSONB format.
+pub(crate) fn encode(value: &JsonNode) -> Vec<u8> {
+    let mut bytes = vec![];
+    encode_into(value, &mut bytes);
+    bytes
+}
+
+fn encode_into(value: &JsonNode, bytes: &mut Vec<u8>) {
+    match value {
+        JsonNode::Null => write_header(JSONB_NULL, 0, bytes),
+        JsonNode::Bool(true) => write_header(JSONB_TRUE, 0, bytes),
+        JsonNode::Bool(false) => write_header(JSONB_FALSE, 0, bytes),
+        JsonNode::Number(text) => {
+            // Numbers keep their text, so an integer is one written without a fraction or an
+            // exponent, however large.
+            let element_type = if text.contains(['.', 'e', 'E']) {
+                JSONB_FLOAT
+            } else {
+                JSONB_INT
+            };
+            write_element(element_type, text.as_bytes(), bytes);
+        }
+        JsonNode::String(string) => encode_string(string, bytes),
+        JsonNode::Array(values) => {
+            let mut payload = vec![];
+            values
+                .iter()
+                .for_each(|value| encode_into(value, &mut payload));
+            write_element(JSONB_ARRAY, &payload, bytes);
+        }
+        JsonNode::Object(members) => {
+            let mut payload = vec![];
+            members.iter().for_each(|(key, value)| {
+                encode_string(key, &mut payload);
+                encode_into(value, &mut payload);
+            });
+            write_element(JSONB_OBJECT, &payload, bytes);
+        }
+    }
+}
+
+/// Strings that would need escaping in JSON text are stored as TEXTRAW,
+/// so the payload is always the unescaped string.
+fn encode_string(string: &str, bytes: &mut Vec<u8>) {
+    let needs_escaping = string
+        .chars()
+        .any(|c| c == '"' || c == '\\' || (c as u32) < 0x20);
+    let element_type = if needs_escaping {
+        JSONB_TEXTRAW
+    } else {
+        JSONB_TEXT
+    };
+    write_element(element_type, string.as_bytes(), bytes);
+}
+
+fn write_element(element_type: u8, payload: &[u8], bytes: &mut Vec<u8>) {
+    write_header(element_type, payload.len(), bytes);
+    bytes.extend_from_slice(payload);
+}
+
+/// Writes an element header using the smallest size encoding that fits the payload.
+fn write_header(element_type: u8, payload_size: usize, bytes: &mut Vec<u8>) {
+    match payload_size {
+        0..=11 => bytes.push(((payload_size as u8) << 4) | element_type),
+        12..=0xFF => {
+            bytes.push(0xC0 | element_type);
+            bytes.push(payload_size as u8);
+        }
+        0x100..=0xFFFF => {
+            bytes.push(0xD0 | element_type);
+            bytes.extend_from_slice(&(payload_size as u16).to_be_bytes());
+        }
+        _ => match u32::try_from(payload_size) {
+            Ok(payload_size) => {
+                bytes.push(0xE0 | element_type);
+                bytes.extend_from_slice(&payload_size.to_be_bytes());
+            }
+            Err(_) => {
+                bytes.push(0xF0 | element_type);
+                bytes.extend_from_slice(&(payload_size as u64).to_be_bytes());
+            }
+        },
+    }
+}
+
+/// Decodes a JSONB blob into a JSON value.
+pub(crate) fn decode(bytes: &[u8]) -> Result<JsonNode, JsonbError> {
+    let (value, end) = decode_element(bytes, 0, 0)?;
+    if end != bytes.len() {
+        return Err(error(end, "trailing bytes after JSONB element"));
+    }
+    Ok(value)
+}
+
+/// Checks only that the outermost element header is well-formed and spans the whole blob,
+/// the way SQLite's json_valid(X, 4) does.
+pub(crate) fn is_superficially_valid(bytes: &[u8]) -> bool {
+    matches!(
+        read_header(bytes, 0),
+        Ok((element_type, _, payload_end))
+            if element_type <= JSONB_OBJECT && payload_end == bytes.len()
+    )
+}
+
+/// Reads the element header at `offset`,
+/// returning the element type and the byte range of its payload.
+fn read_header(bytes: &[u8], offset: usize) -> Result<(u8, usize, usize), JsonbError> {
+    let first = *bytes
+        .get(offset)
+        .ok_or_else(|| error(offset, "unexpected end of JSONB"))?;
+    let size_length = match first >> 4 {
+        0..=11 => 0,
+        12 => 1,
+        13 => 2,
+        14 => 4,
+        _ => 8,
+    };
+    let payload_size = if size_length == 0 {
+        (first >> 4) as u64
+    } else {
+        bytes
+            .get(offset + 1..offset + 1 + size_length)
+            .ok_or_else(|| error(offset, "unexpected end of JSONB"))?
+            .iter()
+            .fold(0_u64, |size, byte| (size << 8) | *byte as u64)
+    };
+
+    let payload_start = offset + 1 + size_length;
+    let payload_end = usize::try_from(payload_size)
+        .ok()
+        .and_then(|payload_size| payload_start.checked_add(payload_size))
+        .filter(|payload_end| *payload_end <= bytes.len())
+        .ok_or_else(|| error(offset, "JSONB element extends past the end of the blob"))?;
+
+    Ok((first & 0x0F, payload_start, payload_end))
+}
+
+fn decode_element(
+    bytes: &[u8],
+    offset: usize,
+    depth: usize,
+) -> Result<(JsonNode, usize), JsonbError> {
+    if depth > MAX_DEPTH {
+        return Err(error(offset, "JSON nested too deep"));
+    }
+
+    let (element_type, payload_start, payload_end) = read_header(bytes, offset)?;
+    let payload = &bytes[payload_start..payload_end];
+    let payload_text =
+        || std::str::from_utf8(payload).map_err(|_| error(offset, "invalid UTF-8 in JSONB text"));
+
+    let value = match element_type {
+        JSONB_NULL | JSONB_TRUE | JSONB_FALSE if !payload.is_empty() => {
+            return Err(error(offset, "unexpected payload for JSONB literal"))
+        }
+        JSONB_NULL => JsonNode::Null,
+        JSONB_TRUE => JsonNode::Bool(true),
+        JSONB_FALSE => JsonNode::Bool(false),
+        JSONB_INT | JSONB_FLOAT => match json5::parse_rfc8259(payload_text()?) {
+            Ok(value @ JsonNode::Number(_)) => value,
+            _ => return Err(error(offset, "malformed JSONB number")),
+        },
+        JSONB_INT5 | JSONB_FLOAT5 => match json5::parse(payload_text()?) {
+            Ok(value @ (JsonNode::Number(_) | JsonNode::Null)) => value,
+            _ => return Err(error(offset, "malformed JSONB number")),
+        },
+        JSONB_TEXT | JSONB_TEXTRAW => JsonNode::String(payload_text()?.to_string()),
+        JSONB_TEXTJ => serde_json::from_str::<String>(&format!("\"{}\"", payload_text()?))
+            .map(JsonNode::String)
+            .map_err(|_| error(offset, "malformed JSONB text escape"))?,
+        JSONB_TEXT5 => {
+            let text = payload_text()?;
+            json5::parse(&format!("\"{text}\""))
+                .or_else(|_| json5::parse(&format!("'{text}'")))
+                .map_err(|_| error(offset, "malformed JSONB text escape"))?
+        }
+        JSONB_ARRAY => {
+            let mut values = vec![];
+            let mut child_offset = payload_start;
+            while child_offset < payload_end {
+                let (value, next_offset) = decode_element(bytes, child_offset, depth + 1)?;
+                values.push(value);
+                child_offset = next_offset;
+            }
+            check_children_end(child_offset, payload_end)?;
+            JsonNode::Array(values)
+        }
+        JSONB_OBJECT => {
+            let mut members: Vec<(String, JsonNode)> = vec![];
+            let mut child_offset = payload_start;
+            while child_offset < payload_end {
+                let (key, value_offset) = decode_element(bytes, child_offset, depth + 1)?;
+                let key = match key {
+                    JsonNode::String(key) => key,
+                    _ => return Err(error(child_offset, "JSONB object key is not text")),
+                };
+                if value_offset >= payload_end {
+                    return Err(error(value_offset, "JSONB object key has no value"));
+                }
+                let (value, next_offset) = decode_element(bytes, value_offset, depth + 1)?;
+                members.push((key, value));
+                child_offset = next_offset;
+            }
+            check_children_end(child_offset, payload_end)?;
+            JsonNode::Object(members)
+        }
+        _ => return Err(error(offset, "reserved JSONB element type")),
+    };
+
+    Ok((value, payload_end))
+}
+
+fn check_children_end(child_offset: usize, payload_end: usize) -> Result<(), JsonbError> {
+    if child_offset != payload_end {
+        return Err(error(
+            payload_end,
+            "JSONB element extends past the end of its container",
+        ));
+    }
+    Ok(())
+}
+
+/// A JSONB element read lazily from its header. Each header records the size of its payload,
+/// so navigating a document only reads the headers needed to skip over siblings and a query can
+/// look at part of a large document without decoding all of it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct JsonbElement<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    element_type: u8,
+    payload_start: usize,
+    payload_end: usize,
+}
+
+impl<'a> JsonbElement<'a> {
+    /// The outermost element of a JSONB blob, which must span the whole blob.
+    pub(crate) fn root(bytes: &'a [u8]) -> Result<Self, JsonbError> {
+        let element = Self::at(bytes, 0)?;
+        if element.payload_end != bytes.len() {
+            return Err(error(
+                element.payload_end,
+                "trailing bytes after JSONB element",
+            ));
+        }
+        Ok(element)
+    }
+
+    fn at(bytes: &'a [u8], offset: usize) -> Result<Self, JsonbError> {
+        let (element_type, payload_start, payload_end) = read_header(bytes, offset)?;
+        if element_type > JSONB_OBJECT {
+            return Err(error(offset, "reserved JSONB element type"));
+        }
+        Ok(Self {
+            bytes,
+            offset,
+            element_type,
+            payload_start,
+            payload_end,
+        })
+    }
+
+    /// Decodes this element and everything nested inside it.
+    pub(crate) fn decode(&self) -> Result<JsonNode, JsonbError> {
+        decode_element(self.bytes, self.offset, 0).map(|(value, _)| value)
+    }
+
+    /// The name json_type() reports for this element.
+    pub(crate) fn json_type(&self) -> Result<&'static str, JsonbError> {
+        let json_type = match self.element_type {
+            JSONB_NULL => "null",
+            JSONB_TRUE => "true",
+            JSONB_FALSE => "false",
+            // Integers outside the i64 range are reported as real, the same as for text input.
+            JSONB_INT | JSONB_INT5 => self.decode()?.json_type(),
+            JSONB_FLOAT | JSONB_FLOAT5 => "real",
+            JSONB_TEXT | JSONB_TEXTJ | JSONB_TEXT5 | JSONB_TEXTRAW => "text",
+            JSONB_ARRAY => "array",
+            _ => "object",
+        };
+        Ok(json_type)
+    }
+
+    /// The number of elements if this is an array, or 0 for any other kind of element.
+    pub(crate) fn array_length(&self) -> Result<usize, JsonbError> {
+        if self.element_type != JSONB_ARRAY {
+            return Ok(0);
+        }
+        self.children()
+            .try_fold(0, |length, child| child.map(|_| length + 1))
+    }
+
+    pub(crate) fn is_array(&self) -> bool {
+        self.element_type == JSONB_ARRAY
+    }
+
+    /// Returns the element `step` selects from this one, or `None` if there is none.
+    pub(crate) fn select(&self, step: &PathStep) -> Result<Option<Self>, JsonbError> {
+        match (step, self.element_type) {
+            (PathStep::Key(key), JSONB_OBJECT) => {
+                let mut children = self.children();
+                while let Some(child_key) = children.next() {
+                    let child_key = child_key?;
+                    let value = children.next().ok_or_else(|| {
+                        error(child_key.offset, "JSONB object key has no value")
+                    })??;
+                    if child_key.has_key(key)? {
+                        return Ok(Some(value));
+                    }
+                }
+                Ok(None)
+            }
+            (PathStep::Index(index), JSONB_ARRAY) => self.children().nth(*index).transpose(),
+            (PathStep::FromEnd(count), JSONB_ARRAY) => {
+                match self.array_length()?.checked_sub(*count) {
+                    Some(index) => self.children().nth(index).transpose(),
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Compares an object key with `key`, avoiding a decode for keys stored without escapes.
+    fn has_key(&self, key: &str) -> Result<bool, JsonbError> {
+        match self.element_type {
+            JSONB_TEXT | JSONB_TEXTRAW => {
+                Ok(&self.bytes[self.payload_start..self.payload_end] == key.as_bytes())
+            }
+            _ => Ok(matches!(self.decode()?, JsonNode::String(decoded) if decoded == key)),
+        }
+    }
+
+    fn children(&self) -> JsonbChildren<'a> {
+        let end = match self.element_type {
+            JSONB_ARRAY | JSONB_OBJECT => self.payload_end,
+            _ => self.payload_start,
+        };
+        JsonbChildren {
+            bytes: self.bytes,
+            offset: self.payload_start,
+            end,
+        }
+    }
+}
+
+struct JsonbChildren<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for JsonbChildren<'a> {
+    type Item = Result<JsonbElement<'a>, JsonbError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.offset >= self.end {
+            return None;
+        }
+        let child = JsonbElement::at(self.bytes, self.offset).and_then(|child| {
+            if child.payload_end > self.end {
+                return Err(error(
+                    child.offset,
+                    "JSONB element extends past the end of its container",
+                ));
+            }
+            Ok(child)
+        });
+        self.offset = child.as_ref().map_or(self.end, |child| child.payload_end);
+        Some(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn json(text: &str) -> JsonNode {
+        json5::parse(text).unwrap()
+    }
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(&json("null")), vec![0x00]);
+        assert_eq!(encode(&json("true")), vec![0x01]);
+        assert_eq!(encode(&json("123")), vec![0x33, b'1', b'2', b'3']);
+        assert_eq!(encode(&json("12.5")), vec![0x45, b'1', b'2', b'.', b'5']);
+        assert_eq!(encode(&json(r#""a\"b""#)), vec![0x3A, b'a', b'"', b'b']);
+        assert_eq!(
+            encode(&json(r#"{"a": [1, false]}"#)),
+            vec![0x6C, 0x17, b'a', 0x3B, 0x13, b'1', 0x02]
+        );
+
+        let long_text = "x".repeat(300);
+        let encoded = encode(&JsonNode::String(long_text));
+        assert_eq!(&encoded[..3], &[0xD7, 0x01, 0x2C]);
+        assert_eq!(encoded.len(), 303);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let value = json(&format!(
+            r#"{{"this": "is", "a": [2, 3.5, true, false, null, "x", "quo\"te", {{"nested": [[]]}}], "big": 18446744073709551616, "exponent": 1E2, "long": "{}"}}"#,
+            "y".repeat(70000)
+        ));
+        assert_eq!(decode(&encode(&value)).unwrap(), value);
+
+        // Duplicate keys are kept, as SQLite keeps them, and a path selects the first.
+        let bytes = encode(&json(r#"{"a":1,"a":2}"#));
+        assert_eq!(bytes, b"\x8C\x17a\x131\x17a\x132");
+        assert_eq!(decode(&bytes).unwrap().to_string(), r#"{"a":1,"a":2}"#);
+        let first = JsonbElement::root(&bytes)
+            .unwrap()
+            .select(&PathStep::Key("a".to_string()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.decode().unwrap(), json("1"));
+    }
+
+    #[test]
+    fn test_decode_sqlite_element_types() {
+        // INT5 hexadecimal, TEXTJ with escapes and TEXT5 with JSON5 escapes.
+        assert_eq!(decode(&[0x44, b'0', b'x', b'1', b'F']).unwrap(), json("31"));
+        assert_eq!(
+            decode(&[0x48, b'a', b'\\', b'n', b'b']).unwrap(),
+            JsonNode::String("a\nb".to_string())
+        );
+        assert_eq!(
+            decode(&[0x59, b'a', b'\\', b'x', b'4', b'1']).unwrap(),
+            json(r#""aA""#)
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(&[]).is_err());
+        assert_eq!(decode(&[0x33, b'1']).unwrap_err().offset, 0);
+        assert_eq!(decode(&[0x00, 0x00]).unwrap_err().offset, 1);
+        assert_eq!(decode(&[0x0D]).unwrap_err().offset, 0);
+        assert_eq!(decode(&[0x2C, 0x13, b'1']).unwrap_err().offset, 1);
+        assert!(is_superficially_valid(&[0x2B, 0xFF, 0xFF]));
+        assert!(decode(&[0x2B, 0xFF, 0xFF]).is_err());
+        assert!(!is_superficially_valid(b"{}"));
+    }
+
+    #[test]
+    fn test_select() {
+        let bytes = encode(&json(
+            r#"{"a": [2, 3.5, true, false, null, "x"], "b c": {"d": 18446744073709551616}}"#,
+        ));
+        let root = JsonbElement::root(&bytes).unwrap();
+        let select = |element: JsonbElement, step: PathStep| {
+            element
+                .select(&step)
+                .unwrap()
+                .map(|element| element.json_type().unwrap())
+        };
+        let key = |key: &str| PathStep::Key(key.to_string());
+
+        let array = root.select(&key("a")).unwrap().unwrap();
+        assert!(array.is_array());
+        assert_eq!(select(array, PathStep::Index(0)), Some("integer"));
+        assert_eq!(select(array, PathStep::Index(1)), Some("real"));
+        assert_eq!(select(array, PathStep::FromEnd(1)), Some("text"));
+        assert_eq!(select(array, PathStep::Index(6)), None);
+        assert_eq!(select(array, PathStep::FromEnd(7)), None);
+        assert_eq!(select(array, PathStep::FromEnd(0)), None);
+        assert_eq!(select(array, key("a")), None);
+        assert_eq!(select(root, key("b c")), Some("object"));
+        assert_eq!(select(root, key("missing")), None);
+        assert_eq!(select(root, PathStep::Index(0)), None);
+
+        assert_eq!(array.array_length().unwrap(), 6);
+        assert_eq!(root.array_length().unwrap(), 0);
+        assert_eq!(
+            array.decode().unwrap(),
+            json(r#"[2, 3.5, true, false, null, "x"]"#)
+        );
+    }
+}
@@ -0,0 +1,614 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{ArrayRef, Float64Array, Int64Array};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::DataType::{
+    Boolean, Float64, Int16, Int32, Int64, Int8, UInt16, UInt32, UInt8,
+};
+use datafusion::common::ScalarValue;
+use datafusion::error::Result;
+use datafusion::logical_expr::TypeSignature::Any;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::sqlite::scalar_udfs::get_sqlite_values;
+use crate::sqlite::value::SqliteValue;
+
+const MATH_FUNCTIONS_LINK: &str = "https://www.sqlite.org/lang_mathfunc.html";
+
+/// A math function of one argument, such as sqrt(X), sin(X) or exp(X). The argument is
+/// converted with numeric affinity, so text such as '16' is read as a number, and the result
+/// is a real. The result is NULL if the argument is NULL, a blob or text that is not a
+/// number, or outside of the domain of the function, e.g. sqrt(-1) or acos(2), where
+/// DataFusion's built-ins return NaN.
+#[derive(Debug)]
+pub struct MathFunction {
+    name: &'static str,
+    function: fn(f64) -> f64,
+    signature: Signature,
+}
+
+impl MathFunction {
+    pub fn new(name: &'static str, function: fn(f64) -> f64) -> Self {
+        Self {
+            name,
+            function,
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for MathFunction {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| non_nan((self.function)(get_number(value)?.to_real())))
+            .collect::<Float64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for MathFunction {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (&str, &str, &[&str]) = match self.name {
+            "acos" => ("acos(X)", "Returns the arccosine of X", &["acos(1) → 0.0"]),
+            "acosh" => (
+                "acosh(X)",
+                "Returns the hyperbolic arccosine of X",
+                &["acosh(1) → 0.0"],
+            ),
+            "asin" => (
+                "asin(X)",
+                "Returns the arcsine of X",
+                &["asin(1) → 1.5707963267948966"],
+            ),
+            "asinh" => (
+                "asinh(X)",
+                "Returns the hyperbolic arcsine of X",
+                &["asinh(1) → 0.881373587019543"],
+            ),
+            "atan" => (
+                "atan(X)",
+                "Returns the arctangent of X",
+                &["atan(1) → 0.7853981633974483"],
+            ),
+            "atanh" => (
+                "atanh(X)",
+                "Returns the hyperbolic arctangent of X",
+                &["atanh(0.5) → 0.5493061443340548"],
+            ),
+            "cos" => ("cos(X)", "Returns the cosine of X", &["cos(0) → 1.0"]),
+            "cosh" => (
+                "cosh(X)",
+                "Returns the hyperbolic cosine of X",
+                &["cosh(0) → 1.0"],
+            ),
+            "degrees" => (
+                "degrees(X)",
+                "Converts X from radians into degrees",
+                &["degrees(pi()) → 180.0"],
+            ),
+            "exp" => (
+                "exp(X)",
+                "Returns e raised to the power X",
+                &["exp(1) → 2.718281828459045"],
+            ),
+            "ln" => (
+                "ln(X)",
+                "Returns the natural logarithm of X",
+                &["ln(1) → 0.0"],
+            ),
+            "log10" => (
+                "log10(X)",
+                "Returns the base-10 logarithm of X",
+                &["log10(1000) → 3.0"],
+            ),
+            "log2" => (
+                "log2(X)",
+                "Returns the base-2 logarithm of X",
+                &["log2(8) → 3.0"],
+            ),
+            "radians" => (
+                "radians(X)",
+                "Converts X from degrees into radians",
+                &["radians(180) → 3.141592653589793"],
+            ),
+            "sin" => ("sin(X)", "Returns the sine of X", &["sin(0) → 0.0"]),
+            "sinh" => (
+                "sinh(X)",
+                "Returns the hyperbolic sine of X",
+                &["sinh(0) → 0.0"],
+            ),
+            "sqrt" => (
+                "sqrt(X)",
+                "Returns the square root of X",
+                &["sqrt(16) → 4.0"],
+            ),
+            "tan" => ("tan(X)", "Returns the tangent of X", &["tan(0) → 0.0"]),
+            "tanh" => (
+                "tanh(X)",
+                "Returns the hyperbolic tangent of X",
+                &["tanh(0) → 0.0"],
+            ),
+            _ => (self.name, "", &[]),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// A math function of two arguments: atan2(Y,X), mod(X,Y) or pow(X,Y). Both arguments are
+/// converted with numeric affinity and the result is a real, or NULL if either argument is
+/// not a number or the result is undefined, e.g. mod(7, 0).
+#[derive(Debug)]
+pub struct BinaryMathFunction {
+    name: &'static str,
+    aliases: Vec<String>,
+    function: fn(f64, f64) -> f64,
+    signature: Signature,
+}
+
+impl BinaryMathFunction {
+    pub fn new(name: &'static str, aliases: &[&str], function: fn(f64, f64) -> f64) -> Self {
+        Self {
+            name,
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            function,
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for BinaryMathFunction {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let left = get_sqlite_values(&args[0])?;
+        let right = get_sqlite_values(&args[1])?;
+
+        let result = left
+            .iter()
+            .zip(right.iter())
+            .map(|(left, right)| {
+                let left = get_number(left)?.to_real();
+                let right = get_number(right)?.to_real();
+                non_nan((self.function)(left, right))
+            })
+            .collect::<Float64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for BinaryMathFunction {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (&str, &str, &[&str]) = match self.name {
+            "atan2" => (
+                "atan2(Y, X)",
+                "Returns the arctangent of Y/X, using the signs of both to pick the quadrant",
+                &["atan2(1, 1) → 0.7853981633974483"],
+            ),
+            "mod" => (
+                "mod(X, Y)",
+                "Returns the remainder after dividing X by Y",
+                &["mod(7, 3) → 1.0"],
+            ),
+            "pow" => (
+                "pow(X, Y)",
+                "Returns X raised to the power Y",
+                &["pow(2, 10) → 1024.0"],
+            ),
+            _ => (self.name, "", &[]),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// A rounding function: ceil(X), floor(X) or trunc(X). Integers are returned unchanged, so
+/// integer columns give Int64 results, while every other column gives Float64 results. As
+/// with the other math functions, the result is NULL if X is not a number.
+#[derive(Debug)]
+pub struct RoundingFunction {
+    name: &'static str,
+    aliases: Vec<String>,
+    function: fn(f64) -> f64,
+    signature: Signature,
+}
+
+impl RoundingFunction {
+    pub fn new(name: &'static str, aliases: &[&str], function: fn(f64) -> f64) -> Self {
+        Self {
+            name,
+            aliases: aliases.iter().map(|alias| alias.to_string()).collect(),
+            function,
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for RoundingFunction {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(if is_integer_type(&arg_types[0]) {
+            Int64
+        } else {
+            Float64
+        })
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let values = get_sqlite_values(&args[0])?;
+
+        let result = if is_integer_type(args[0].data_type()) {
+            Arc::new(
+                values
+                    .iter()
+                    .map(|value| match value {
+                        SqliteValue::Integer(integer) => Some(*integer),
+                        _ => None,
+                    })
+                    .collect::<Int64Array>(),
+            ) as ArrayRef
+        } else {
+            Arc::new(
+                values
+                    .iter()
+                    .map(|value| match get_number(value)? {
+                        SqliteValue::Integer(integer) => Some(integer as f64),
+                        number => non_nan((self.function)(number.to_real())),
+                    })
+                    .collect::<Float64Array>(),
+            ) as ArrayRef
+        };
+
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+impl Documented for RoundingFunction {
+    fn documentation(&self) -> Documentation {
+        let (syntax, description, examples): (&str, &str, &[&str]) = match self.name {
+            "ceil" => (
+                "ceil(X)",
+                "Returns the smallest integer not less than X",
+                &["ceil(1.2) → 2.0"],
+            ),
+            "floor" => (
+                "floor(X)",
+                "Returns the largest integer not greater than X",
+                &["floor(-1.5) → -2.0"],
+            ),
+            "trunc" => (
+                "trunc(X)",
+                "Returns X rounded towards zero",
+                &["trunc(-2.5) → -2.0"],
+            ),
+            _ => (self.name, "", &[]),
+        };
+        Documentation {
+            syntax,
+            description,
+            examples,
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The log(X) function returns the base-10 logarithm of X, and log(B,X) the base-B logarithm
+/// of X. The result is NULL unless X is positive and B is greater than 1.
+///
+/// Like SQLite, only B is converted with numeric affinity in log(B,X): X is read the way
+/// SQLite reads any value as a real, so log(2, '8 bits') is 3.0.
+#[derive(Debug)]
+pub struct Log {
+    signature: Signature,
+}
+
+impl Log {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(1), Any(2)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Log {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let values = get_sqlite_values(&args[0])?;
+
+        let result = if args.len() == 1 {
+            values
+                .iter()
+                .map(|value| Some(get_positive(value)?.log10()))
+                .collect::<Float64Array>()
+        } else {
+            values
+                .iter()
+                .zip(get_sqlite_values(&args[1])?.iter())
+                .map(|(base, value)| {
+                    let base = get_positive(base)?.ln();
+                    let value = value.to_real();
+                    if base <= 0.0 || value <= 0.0 {
+                        return None;
+                    }
+                    non_nan(value.ln() / base)
+                })
+                .collect::<Float64Array>()
+        };
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Log {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "log([B, ]X)",
+            description: "Returns the base-10 logarithm of X, or the base-B logarithm of X",
+            examples: &["log(100) → 2.0", "log(2, 8) → 3.0"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The pi() function returns an approximation for π.
+#[derive(Debug)]
+pub struct Pi {
+    signature: Signature,
+}
+
+impl Pi {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Pi {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "pi"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Float64)
+    }
+
+    fn invoke(&self, _args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        Ok(ColumnarValue::Scalar(ScalarValue::Float64(Some(PI))))
+    }
+}
+
+impl Documented for Pi {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "pi()",
+            description: "Returns an approximation of π",
+            examples: &["pi() → 3.141592653589793"],
+            link: Some(MATH_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The natural logarithm, with NaN for the non-positive numbers SQLite gives NULL for.
+pub(crate) fn ln(value: f64) -> f64 {
+    if value > 0.0 {
+        value.ln()
+    } else {
+        f64::NAN
+    }
+}
+
+/// The base-10 logarithm, with NaN for the non-positive numbers SQLite gives NULL for.
+pub(crate) fn log10(value: f64) -> f64 {
+    if value > 0.0 {
+        value.log10()
+    } else {
+        f64::NAN
+    }
+}
+
+/// The base-2 logarithm, with NaN for the non-positive numbers SQLite gives NULL for.
+pub(crate) fn log2(value: f64) -> f64 {
+    if value > 0.0 {
+        value.log2()
+    } else {
+        f64::NAN
+    }
+}
+
+/// Reads a value as a number the way the math functions do: with numeric affinity, which
+/// leaves NULL, blobs and text that is not a number as they are, giving a NULL result.
+fn get_number(value: &SqliteValue) -> Option<SqliteValue> {
+    match value {
+        SqliteValue::Blob(_) => None,
+        value => value.to_numeric(),
+    }
+}
+
+fn get_positive(value: &SqliteValue) -> Option<f64> {
+    Some(get_number(value)?.to_real()).filter(|value| *value > 0.0)
+}
+
+/// SQLite stores NaN as NULL, so undefined results become NULL rather than NaN.
+fn non_nan(value: f64) -> Option<f64> {
+    (!value.is_nan()).then_some(value)
+}
+
+/// The types [`get_sqlite_values`] reads as integers.
+fn is_integer_type(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Boolean
+    )
+}
+
+#[cfg(feature = "sqlite")]
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_maths_data_test;
+    use crate::sqlite::register_sqlite_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_math_functions() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, sqrt(int) as sqrt, ln(float) as ln, log(2, uint) as log, mod(index + 5, 3) as mod, pow(float, index) as pow, ceil(int) as ceil_int, ceil(float) as ceil_float, trunc('-2.5') as trunc_text FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+--------------------+--------------------+--------------------+-----+--------------------+----------+------------+------------+
+| index | sqrt               | ln                 | log                | mod | pow                | ceil_int | ceil_float | trunc_text |
++-------+--------------------+--------------------+--------------------+-----+--------------------+----------+------------+------------+
+| 1     |                    | 0.0                | 1.0                | 0.0 | 1.0                | -2       | 1.0        | -2.0       |
+| 2     | 1.7320508075688772 | 1.1939224684724346 | 1.5849625007211563 | 1.0 | 10.889999999999999 | 3        | 4.0        | -2.0       |
+| 3     |                    |                    |                    | 2.0 |                    |          |            | -2.0       |
++-------+--------------------+--------------------+--------------------+-----+--------------------+----------+------------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_domain_errors_and_affinity() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select sqrt(-1) as sqrt, acos(2) as acos, ln(0) as ln, log(1, 8) as log, mod(7, 0) as mod, sqrt('16') as text, sqrt('16abc') as bad_text, sqrt(X'3136') as blob, exp(1000) as exp, degrees(pi()) as degrees, atan2(1, 1) as atan2",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------+------+----+-----+-----+------+----------+------+-----+---------+--------------------+
+| sqrt | acos | ln | log | mod | text | bad_text | blob | exp | degrees | atan2              |
++------+------+----+-----+-----+------+----------+------+-----+---------+--------------------+
+|      |      |    |     |     | 4.0  |          |      | inf | 180.0   | 0.7853981633974483 |
++------+------+----+-----+-----+------+----------+------+-----+---------+--------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = set_up_maths_data_test()?;
+        register_sqlite_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
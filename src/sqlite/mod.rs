@@ -1,18 +1,147 @@
 #![cfg(feature = "sqlite")]
 #![allow(deprecated)]
 
+use std::cell::RefCell;
+
 use datafusion::error::Result;
-use datafusion::logical_expr::ScalarUDF;
 use datafusion::prelude::SessionContext;
 
-use crate::sqlite::json_udfs::{Json, JsonArrayLength, JsonType, JsonValid};
+use crate::catalog::register_functions_table;
+use crate::registry::{Dialect, RegisterOptions, Registrar};
+use crate::sqlite::datetime_udfs::{
+    Date, Datetime, JulianDay, Strftime, Time, TimeDiff, UnixEpoch,
+};
+use crate::sqlite::json_udfs::{
+    Json, JsonArrayLength, JsonErrorPosition, JsonExtractAs, JsonGetBool, JsonGetFloat, JsonGetInt,
+    JsonGetStr, JsonInferSchema, JsonToStruct, JsonType, JsonValid, Jsonb,
+};
+use crate::sqlite::math_udfs::{
+    ln, log10, log2, BinaryMathFunction, Log, MathFunction, Pi, RoundingFunction,
+};
+use crate::sqlite::scalar_udfs::{
+    Changes, Char, Glob, Hex, IfNull, Iif, Instr, Like, Likelihood, Likely, Printf, Quote, Sign,
+    Soundex, TypeOf, Unhex, Unicode, Zeroblob,
+};
 
+mod datetime;
+mod datetime_udfs;
+mod json5;
+mod json_node;
+mod json_path;
+mod json_schema;
 mod json_udfs;
+mod jsonb;
+mod math_udfs;
+mod pattern;
+mod printf;
+mod scalar_udfs;
+mod value;
 
 pub fn register_sqlite_udfs(ctx: &SessionContext) -> Result<()> {
-    ctx.register_udf(ScalarUDF::from(Json::new()));
-    ctx.register_udf(ScalarUDF::from(JsonType::new()));
-    ctx.register_udf(ScalarUDF::from(JsonValid::new()));
-    ctx.register_udf(ScalarUDF::from(JsonArrayLength::new()));
+    register_sqlite_udfs_with_options(ctx, &RegisterOptions::default())
+}
+
+/// Registers the SQLite functions selected by `options`, e.g. under a `sqlite.` prefix.
+pub fn register_sqlite_udfs_with_options(
+    ctx: &SessionContext,
+    options: &RegisterOptions,
+) -> Result<()> {
+    let functions = RefCell::new(vec![]);
+    register_all(&Registrar::new(ctx, &functions, Dialect::Sqlite, options)?)?;
+    register_functions_table(ctx, functions.into_inner());
+    Ok(())
+}
+
+pub(crate) fn register_all(registrar: &Registrar) -> Result<()> {
+    register_json_udfs(&registrar.category("JSON"))?;
+    register_json_extension_udfs(&registrar.category("JSON Extensions"))?;
+    register_scalar_udfs(&registrar.category("Built-In Scalar"))?;
+    register_datetime_udfs(&registrar.category("Date and Time"))?;
+    register_math_udfs(&registrar.category("Maths"))?;
+    Ok(())
+}
+
+fn register_json_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Json::new());
+    registrar.register_udf(JsonType::new());
+    registrar.register_udf(JsonValid::new());
+    registrar.register_udf(JsonArrayLength::new());
+    registrar.register_udf(JsonErrorPosition::new());
+    registrar.register_udf(Jsonb::new());
+    Ok(())
+}
+
+fn register_json_extension_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(JsonGetInt::new());
+    registrar.register_udf(JsonGetFloat::new());
+    registrar.register_udf(JsonGetBool::new());
+    registrar.register_udf(JsonGetStr::new());
+    registrar.register_udf(JsonExtractAs::new());
+    registrar.register_udf(JsonToStruct::new());
+    registrar.register_udaf(JsonInferSchema::new());
+    Ok(())
+}
+
+fn register_scalar_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Instr::new());
+    registrar.register_udf(Hex::new());
+    registrar.register_udf(Unhex::new());
+    registrar.register_udf(Quote::new());
+    registrar.register_udf(Zeroblob::new());
+    registrar.register_udf(Printf::new());
+    registrar.register_udf(Glob::new());
+    registrar.register_udf(Like::new());
+    registrar.register_udf(Likely::new());
+    registrar.register_udf(Likelihood::new());
+    registrar.register_udf(TypeOf::new());
+    registrar.register_udf(Unicode::new());
+    registrar.register_udf(Char::new());
+    registrar.register_udf(Soundex::new());
+    registrar.register_udf(IfNull::new());
+    registrar.register_udf(Iif::new());
+    registrar.register_udf(Sign::new());
+    registrar.register_udf(Changes::new());
+    Ok(())
+}
+
+fn register_datetime_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(Date::new());
+    registrar.register_udf(Time::new());
+    registrar.register_udf(Datetime::new());
+    registrar.register_udf(JulianDay::new());
+    registrar.register_udf(UnixEpoch::new());
+    registrar.register_udf(Strftime::new());
+    registrar.register_udf(TimeDiff::new());
+    Ok(())
+}
+
+fn register_math_udfs(registrar: &Registrar) -> Result<()> {
+    registrar.register_udf(MathFunction::new("acos", f64::acos));
+    registrar.register_udf(MathFunction::new("acosh", libm::acosh));
+    registrar.register_udf(MathFunction::new("asin", f64::asin));
+    registrar.register_udf(MathFunction::new("asinh", libm::asinh));
+    registrar.register_udf(MathFunction::new("atan", f64::atan));
+    registrar.register_udf(MathFunction::new("atanh", libm::atanh));
+    registrar.register_udf(MathFunction::new("cos", f64::cos));
+    registrar.register_udf(MathFunction::new("cosh", f64::cosh));
+    registrar.register_udf(MathFunction::new("degrees", f64::to_degrees));
+    registrar.register_udf(MathFunction::new("exp", f64::exp));
+    registrar.register_udf(MathFunction::new("ln", ln));
+    registrar.register_udf(MathFunction::new("log10", log10));
+    registrar.register_udf(MathFunction::new("log2", log2));
+    registrar.register_udf(MathFunction::new("radians", f64::to_radians));
+    registrar.register_udf(MathFunction::new("sin", f64::sin));
+    registrar.register_udf(MathFunction::new("sinh", f64::sinh));
+    registrar.register_udf(MathFunction::new("sqrt", f64::sqrt));
+    registrar.register_udf(MathFunction::new("tan", f64::tan));
+    registrar.register_udf(MathFunction::new("tanh", f64::tanh));
+    registrar.register_udf(BinaryMathFunction::new("atan2", &[], f64::atan2));
+    registrar.register_udf(BinaryMathFunction::new("mod", &[], |x, y| x % y));
+    registrar.register_udf(BinaryMathFunction::new("pow", &["power"], f64::powf));
+    registrar.register_udf(RoundingFunction::new("ceil", &["ceiling"], f64::ceil));
+    registrar.register_udf(RoundingFunction::new("floor", &[], f64::floor));
+    registrar.register_udf(RoundingFunction::new("trunc", &[], f64::trunc));
+    registrar.register_udf(Log::new());
+    registrar.register_udf(Pi::new());
     Ok(())
 }
@@ -0,0 +1,187 @@
+//! The pattern matching behind SQLite's GLOB and LIKE.
+//!
+//! GLOB is case sensitive and uses `*`, `?` and `[...]` sets, the way Unix file globbing does.
+//! LIKE uses `%` and `_`, folds ASCII letters to the same case and can take an escape character
+//! that makes the character after it match literally.
+
+struct PatternInfo {
+    match_all: char,
+    match_one: char,
+    match_set: Option<char>,
+    no_case: bool,
+    escape: Option<char>,
+}
+
+/// Whether `text` matches the GLOB `pattern`.
+pub(crate) fn glob_matches(pattern: &str, text: &str) -> bool {
+    let info = PatternInfo {
+        match_all: '*',
+        match_one: '?',
+        match_set: Some('['),
+        no_case: false,
+        escape: None,
+    };
+    pattern_compare(&to_chars(pattern), &to_chars(text), &info)
+}
+
+/// Whether `text` matches the LIKE `pattern`, with an optional escape character.
+pub(crate) fn like_matches(pattern: &str, text: &str, escape: Option<char>) -> bool {
+    let info = PatternInfo {
+        match_all: '%',
+        match_one: '_',
+        match_set: None,
+        no_case: true,
+        escape,
+    };
+    pattern_compare(&to_chars(pattern), &to_chars(text), &info)
+}
+
+fn to_chars(text: &str) -> Vec<char> {
+    text.chars().collect()
+}
+
+fn pattern_compare(pattern: &[char], text: &[char], info: &PatternInfo) -> bool {
+    let mut p = 0;
+    let mut t = 0;
+    while p < pattern.len() {
+        let c = pattern[p];
+        p += 1;
+
+        if c == info.match_all {
+            // Runs of wildcards collapse into one, with each single character wildcard in the
+            // run still needing a character of its own.
+            while p < pattern.len()
+                && (pattern[p] == info.match_all || pattern[p] == info.match_one)
+            {
+                if pattern[p] == info.match_one {
+                    if t == text.len() {
+                        return false;
+                    }
+                    t += 1;
+                }
+                p += 1;
+            }
+            if p == pattern.len() {
+                return true;
+            }
+            return (t..=text.len())
+                .any(|start| pattern_compare(&pattern[p..], &text[start..], info));
+        }
+
+        if Some(c) == info.match_set {
+            let Some(&c2) = text.get(t) else {
+                return false;
+            };
+            match match_set(&pattern[p..], c2) {
+                Some((true, length)) => {
+                    p += length;
+                    t += 1;
+                    continue;
+                }
+                _ => return false,
+            }
+        }
+
+        let (c, escaped) = if Some(c) == info.escape {
+            match pattern.get(p) {
+                Some(&escaped) => {
+                    p += 1;
+                    (escaped, true)
+                }
+                None => return false,
+            }
+        } else {
+            (c, false)
+        };
+        let Some(&c2) = text.get(t) else {
+            return false;
+        };
+        t += 1;
+        let matched = c == c2
+            || (info.no_case && c.is_ascii() && c2.is_ascii() && c.eq_ignore_ascii_case(&c2))
+            || (c == info.match_one && !escaped);
+        if !matched {
+            return false;
+        }
+    }
+    t == text.len()
+}
+
+/// Matches `c` against a `[...]` set whose contents, after the opening `[`, start `set`.
+/// Returns whether `c` is in the set and how many characters of `set` were read, or `None`
+/// if the set is never closed.
+fn match_set(set: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 0;
+    let invert = set.first() == Some(&'^');
+    if invert {
+        i += 1;
+    }
+    let mut seen = false;
+    // A `]` straight after the opening bracket is part of the set.
+    if set.get(i) == Some(&']') {
+        seen = c == ']';
+        i += 1;
+    }
+
+    let mut prior: Option<char> = None;
+    loop {
+        let c2 = *set.get(i)?;
+        if c2 == ']' {
+            break;
+        }
+        match (prior, set.get(i + 1)) {
+            (Some(low), Some(&high)) if c2 == '-' && high != ']' => {
+                if low <= c && c <= high {
+                    seen = true;
+                }
+                prior = None;
+                i += 2;
+            }
+            _ => {
+                if c == c2 {
+                    seen = true;
+                }
+                prior = Some(c2);
+                i += 1;
+            }
+        }
+    }
+    Some((seen != invert, i + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("a*c", "abbbc"));
+        assert!(glob_matches("a?c", "abc"));
+        assert!(!glob_matches("a?c", "ac"));
+        assert!(!glob_matches("A*", "abc"));
+        assert!(glob_matches("[a-c]x", "bx"));
+        assert!(!glob_matches("[^a-c]x", "bx"));
+        assert!(glob_matches("[]]", "]"));
+        assert!(glob_matches("[a-]", "-"));
+        assert!(!glob_matches("[abc", "a"));
+        assert!(glob_matches("*.txt", "notes.txt"));
+        assert!(glob_matches("*?", "é"));
+        assert!(!glob_matches("*??", "é"));
+        assert!(glob_matches("", ""));
+        assert!(!glob_matches("", "a"));
+    }
+
+    #[test]
+    fn test_like_matches() {
+        assert!(like_matches("a%c", "ABBBC", None));
+        assert!(like_matches("_b_", "abc", None));
+        assert!(!like_matches("é", "É", None));
+        assert!(like_matches("100\\%", "100%", Some('\\')));
+        assert!(!like_matches("100\\%", "1000", Some('\\')));
+        assert!(like_matches("a\\_c", "a_c", Some('\\')));
+        assert!(!like_matches("a\\_c", "abc", Some('\\')));
+        assert!(!like_matches("abc\\", "abc", Some('\\')));
+        assert!(like_matches("%", "", None));
+        assert!(like_matches("[a]", "[A]", None));
+    }
+}
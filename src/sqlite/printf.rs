@@ -0,0 +1,805 @@
+//! SQLite's printf(), which formats SQL values with C printf style format strings.
+//!
+//! Arguments are converted the way SQLite converts them: `%d` reads its argument as an integer,
+//! `%f` as a real and `%s` as text, so any value can be given to any conversion. Missing
+//! arguments read as NULL.
+//!
+//! Besides the C conversions, SQLite has `%q`, `%Q` and `%w` for quoting SQL literals and
+//! identifiers, `%r` for ordinals, the `,` flag for thousands separators and the `!` flag, which
+//! counts `%s` and `%c` widths and precisions in characters rather than bytes. A `*` width or
+//! precision is read from the next argument. The output is the same, byte for byte, as SQLite's.
+
+use anyhow::{anyhow, Result};
+
+use crate::sqlite::value::SqliteValue;
+
+/// SQLite's default limit on the length of a string.
+const MAX_LENGTH: usize = 1_000_000_000;
+
+/// Formats `args` according to `format`. Fails if the result would be longer than SQLite
+/// allows a string to be.
+pub(crate) fn printf(format: &str, args: &[SqliteValue]) -> Result<String> {
+    let mut out: Vec<u8> = Vec::with_capacity(format.len());
+    let mut args = Arguments {
+        values: args,
+        used: 0,
+    };
+    let format = format.as_bytes();
+    let mut pos = 0;
+
+    while pos < format.len() {
+        let literal_end = format[pos..]
+            .iter()
+            .position(|&c| c == b'%')
+            .map_or(format.len(), |offset| pos + offset);
+        out.extend_from_slice(&format[pos..literal_end]);
+        pos = literal_end + 1;
+        if pos > format.len() {
+            break;
+        }
+        if pos == format.len() {
+            out.push(b'%');
+            break;
+        }
+
+        let Some((spec, conversion)) = Spec::parse(format, &mut pos, &mut args) else {
+            break;
+        };
+        let is_numeric = b"diuxXoprfeEgG".contains(&conversion);
+        if (conversion != b'n' && spec.width > MAX_LENGTH)
+            || (is_numeric
+                && spec
+                    .precision
+                    .is_some_and(|precision| precision > MAX_LENGTH))
+        {
+            return Err(too_big());
+        }
+
+        match conversion {
+            b'd' | b'i' | b'u' | b'x' | b'X' | b'o' | b'p' | b'r' => {
+                let field = format_integer(args.next().to_integer(), conversion, &spec);
+                spec.pad(&mut out, &field, false);
+            }
+            b'f' | b'e' | b'E' | b'g' | b'G' => {
+                let field = format_float_spec(args.next().to_real(), conversion, &spec);
+                spec.pad(&mut out, &field, false);
+            }
+            b's' | b'z' => {
+                let text = args.next_text().unwrap_or_default();
+                let length = spec.text_length(&text);
+                spec.pad(&mut out, &text[..length], spec.alternate_form_2);
+            }
+            b'q' | b'Q' | b'w' => {
+                let field = escape(args.next_text(), conversion, &spec);
+                spec.pad(&mut out, &field, spec.alternate_form_2);
+            }
+            b'c' => format_char(&mut out, args.next_text(), &spec)?,
+            // In C, %n stores the length of the output so far, which has no use in SQL.
+            b'n' => {}
+            b'%' => spec.pad(&mut out, b"%", false),
+            // SQLite stops formatting at a conversion it does not know.
+            _ => break,
+        }
+        if out.len() > MAX_LENGTH {
+            return Err(too_big());
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&out).into_owned())
+}
+
+/// Formats a real with a `%e`, `%f` or `%g` style `conversion`. `alternate_form_2` is the `!`
+/// flag, which keeps a `.0` on whole numbers and allows up to 26 significant digits.
+pub(crate) fn format_float(
+    value: f64,
+    conversion: char,
+    precision: usize,
+    alternate_form_2: bool,
+) -> String {
+    let spec = Spec {
+        alternate_form_2,
+        precision: Some(precision),
+        ..Spec::default()
+    };
+    String::from_utf8_lossy(&format_float_spec(value, conversion as u8, &spec)).into_owned()
+}
+
+fn too_big() -> anyhow::Error {
+    anyhow!("string or blob too big")
+}
+
+/// The values conversions read from, in order. Values past the end read as NULL.
+struct Arguments<'a> {
+    values: &'a [SqliteValue],
+    used: usize,
+}
+
+impl<'a> Arguments<'a> {
+    fn next(&mut self) -> &'a SqliteValue {
+        let value = self.values.get(self.used).unwrap_or(&SqliteValue::Null);
+        self.used += 1;
+        value
+    }
+
+    /// The next value as the bytes of a C string, which end at the first NUL, or `None` for
+    /// NULL.
+    fn next_text(&mut self) -> Option<Vec<u8>> {
+        let mut text = self.next().to_blob()?;
+        if let Some(end) = text.iter().position(|&c| c == 0) {
+            text.truncate(end);
+        }
+        Some(text)
+    }
+}
+
+/// The flags, width and precision of one conversion.
+#[derive(Debug, Default)]
+struct Spec {
+    left_justify: bool,
+    /// The character shown before non-negative numbers, set by the `+` and ` ` flags.
+    prefix: Option<u8>,
+    alternate_form: bool,
+    alternate_form_2: bool,
+    zero_pad: bool,
+    thousands_separator: bool,
+    width: usize,
+    precision: Option<usize>,
+}
+
+impl Spec {
+    /// Parses the conversion that starts after a `%` at `format[*pos]`, returning it with the
+    /// conversion character and leaving `pos` after it. A `*` width or precision is read from
+    /// `args`.
+    fn parse(format: &[u8], pos: &mut usize, args: &mut Arguments) -> Option<(Spec, u8)> {
+        let mut spec = Spec::default();
+        loop {
+            match *format.get(*pos)? {
+                b'-' => spec.left_justify = true,
+                b'+' => spec.prefix = Some(b'+'),
+                b' ' => spec.prefix = Some(b' '),
+                b'#' => spec.alternate_form = true,
+                b'!' => spec.alternate_form_2 = true,
+                b'0' => spec.zero_pad = true,
+                b',' => spec.thousands_separator = true,
+                // Length modifiers have no meaning for SQL values.
+                b'l' => {
+                    *pos += 1;
+                    if format.get(*pos) == Some(&b'l') {
+                        *pos += 1;
+                    }
+                    break;
+                }
+                b'1'..=b'9' => {
+                    spec.width = read_number(format, pos);
+                    if !matches!(format.get(*pos), Some(b'.' | b'l')) {
+                        break;
+                    }
+                    continue;
+                }
+                b'*' => {
+                    // A negative width left justifies the field.
+                    let width = args.next().to_integer() as i32;
+                    spec.left_justify |= width < 0;
+                    spec.width = width.checked_abs().map_or(0, |width| width as usize);
+                    *pos += 1;
+                    if !matches!(format.get(*pos), Some(b'.' | b'l')) {
+                        break;
+                    }
+                    continue;
+                }
+                b'.' => {
+                    *pos += 1;
+                    if format.get(*pos) == Some(&b'*') {
+                        // Unlike C, SQLite uses the magnitude of a negative precision.
+                        let precision = args.next().to_integer() as i32;
+                        spec.precision =
+                            precision.checked_abs().map(|precision| precision as usize);
+                        *pos += 1;
+                    } else {
+                        spec.precision = Some(read_number(format, pos));
+                    }
+                    if format.get(*pos) != Some(&b'l') {
+                        break;
+                    }
+                    continue;
+                }
+                _ => break,
+            }
+            *pos += 1;
+        }
+        let conversion = *format.get(*pos)?;
+        *pos += 1;
+        Some((spec, conversion))
+    }
+
+    /// The number of bytes of `text` that `%s` and `%q` show: all of them, or as many as the
+    /// precision allows, counting characters instead of bytes with the `!` flag.
+    fn text_length(&self, text: &[u8]) -> usize {
+        match self.precision {
+            Some(precision) if self.alternate_form_2 => {
+                let mut length = 0;
+                for _ in 0..precision {
+                    if length == text.len() {
+                        break;
+                    }
+                    length += 1;
+                    if text[length - 1] >= 0xc0 {
+                        length += continuation_bytes(&text[length..]);
+                    }
+                }
+                length
+            }
+            Some(precision) => precision.min(text.len()),
+            None => text.len(),
+        }
+    }
+
+    /// Appends `field` to `out`, padded with spaces to the field width. `count_chars` measures
+    /// `field` in UTF-8 characters rather than bytes.
+    fn pad(&self, out: &mut Vec<u8>, field: &[u8], count_chars: bool) {
+        pad(out, field, self.width, self.left_justify, count_chars);
+    }
+}
+
+fn pad(out: &mut Vec<u8>, field: &[u8], width: usize, left_justify: bool, count_chars: bool) {
+    let length = if count_chars {
+        field.iter().filter(|&&c| c & 0xc0 != 0x80).count()
+    } else {
+        field.len()
+    };
+    let padding = width.saturating_sub(length);
+    if !left_justify {
+        out.resize(out.len() + padding, b' ');
+    }
+    out.extend_from_slice(field);
+    if left_justify {
+        out.resize(out.len() + padding, b' ');
+    }
+}
+
+/// Reads the digits at `format[*pos]`, wrapping and masking them to a positive C int the way
+/// SQLite does.
+fn read_number(format: &[u8], pos: &mut usize) -> usize {
+    let mut number: u32 = 0;
+    while let Some(&digit @ b'0'..=b'9') = format.get(*pos) {
+        number = number
+            .wrapping_mul(10)
+            .wrapping_add(u32::from(digit - b'0'));
+        *pos += 1;
+    }
+    (number & 0x7fff_ffff) as usize
+}
+
+/// The number of UTF-8 continuation bytes at the start of `bytes`.
+fn continuation_bytes(bytes: &[u8]) -> usize {
+    bytes.iter().take_while(|&&c| c & 0xc0 == 0x80).count()
+}
+
+fn format_integer(value: i64, conversion: u8, spec: &Spec) -> Vec<u8> {
+    let (magnitude, prefix) = match conversion {
+        b'd' | b'i' | b'r' if value < 0 => (value.unsigned_abs(), Some(b'-')),
+        b'd' | b'i' | b'r' => (value as u64, spec.prefix),
+        _ => (value as u64, None),
+    };
+    let (base, digit_set, alternate_prefix): (u64, &[u8], &[u8]) = match conversion {
+        b'x' | b'p' => (16, b"0123456789abcdef", b"0x"),
+        b'X' => (16, b"0123456789ABCDEF", b"0X"),
+        b'o' => (8, b"01234567", b"0"),
+        _ => (10, b"0123456789", b""),
+    };
+
+    let mut precision = spec.precision.unwrap_or(0);
+    let width = spec.width.saturating_sub(usize::from(prefix.is_some()));
+    if spec.zero_pad && precision < width {
+        precision = width;
+    }
+
+    // The digits are collected from the last one, so an ordinal suffix goes in first and
+    // counts towards the precision, as it does in SQLite.
+    let mut digits = Vec::new();
+    if conversion == b'r' {
+        let last_digit = magnitude % 10;
+        let suffix = match last_digit {
+            1..=3 if (magnitude / 10) % 10 != 1 => [b"st", b"nd", b"rd"][last_digit as usize - 1],
+            _ => b"th",
+        };
+        digits.extend(suffix.iter().rev());
+    }
+    let mut remaining = magnitude;
+    loop {
+        digits.push(digit_set[(remaining % base) as usize]);
+        remaining /= base;
+        if remaining == 0 {
+            break;
+        }
+    }
+    digits.resize(digits.len().max(precision), b'0');
+    digits.reverse();
+
+    let mut field = Vec::with_capacity(digits.len() + digits.len() / 3 + 3);
+    field.extend(prefix);
+    if spec.alternate_form && magnitude != 0 {
+        field.extend_from_slice(alternate_prefix);
+    }
+    if spec.thousands_separator && matches!(conversion, b'd' | b'i' | b'u') {
+        // Zeros added for the precision are grouped too.
+        for (index, &digit) in digits.iter().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                field.push(b',');
+            }
+            field.push(digit);
+        }
+    } else {
+        field.extend_from_slice(&digits);
+    }
+    field
+}
+
+/// The decimal digits of a real: its value is `0.DIGITS * 10^decimal_point`.
+struct Decimal {
+    negative: bool,
+    digits: Vec<u8>,
+    decimal_point: i64,
+}
+
+impl Decimal {
+    /// Decodes `value` and rounds it like SQLite. A positive `round` keeps that many significant
+    /// digits, while zero or a negative `round` keeps `-round` digits after the decimal point.
+    /// No more than `max_digits` significant digits are kept.
+    fn new(value: f64, round: i64, max_digits: usize) -> Self {
+        let negative = value < 0.0;
+        if value == 0.0 {
+            return Decimal {
+                negative,
+                digits: vec![b'0'],
+                decimal_point: 1,
+            };
+        }
+
+        // 40 correctly rounded significant digits are more than SQLite ever shows.
+        let scientific = format!("{:.39e}", value.abs());
+        let (mantissa, exponent) = scientific.split_once('e').unwrap_or((&scientific, "0"));
+        let mut digits: Vec<u8> = mantissa.bytes().filter(u8::is_ascii_digit).collect();
+        let mut decimal_point = exponent.parse::<i64>().unwrap_or(0) + 1;
+        // SQLite reads the digits from the value scaled to a 64-bit integer no larger than
+        // 2^63, which holds 18 or 19 of them. Any digits after those show as zeros.
+        let integer_digits = if digits[..19] <= b"9223372036854774784"[..] {
+            19
+        } else {
+            18
+        };
+        digits.truncate(integer_digits);
+
+        let mut round = round;
+        if round <= 0 {
+            round = decimal_point - round;
+            if round == 0 && digits[0] >= b'5' {
+                digits.insert(0, b'0');
+                decimal_point += 1;
+                round = 1;
+            }
+        }
+        if round > 0 {
+            let keep = (round as usize).min(max_digits);
+            if keep < digits.len() {
+                let round_up = digits[keep] >= b'5';
+                digits.truncate(keep);
+                if round_up {
+                    let mut carry = true;
+                    for digit in digits.iter_mut().rev() {
+                        if *digit == b'9' {
+                            *digit = b'0';
+                        } else {
+                            *digit += 1;
+                            carry = false;
+                            break;
+                        }
+                    }
+                    if carry {
+                        digits.insert(0, b'1');
+                        decimal_point += 1;
+                    }
+                }
+            }
+        }
+
+        while digits.len() > 1 && digits.last() == Some(&b'0') {
+            digits.pop();
+        }
+        Decimal {
+            negative,
+            digits,
+            decimal_point,
+        }
+    }
+}
+
+fn format_float_spec(value: f64, conversion: u8, spec: &Spec) -> Vec<u8> {
+    #[derive(PartialEq)]
+    enum Style {
+        Fixed,
+        Exponent,
+        Generic,
+    }
+    let mut style = match conversion {
+        b'f' => Style::Fixed,
+        b'e' | b'E' => Style::Exponent,
+        _ => Style::Generic,
+    };
+    let exponent_char = if conversion.is_ascii_uppercase() {
+        b'E'
+    } else {
+        b'e'
+    };
+
+    let mut precision = spec.precision.map_or(6, |precision| precision as i64);
+    let round = match style {
+        Style::Fixed => -precision,
+        Style::Exponent => precision + 1,
+        Style::Generic => {
+            precision = precision.max(1);
+            precision
+        }
+    };
+
+    if value.is_nan() {
+        return if spec.zero_pad {
+            b"null".to_vec()
+        } else {
+            b"NaN".to_vec()
+        };
+    }
+    let decimal = if value.is_infinite() {
+        if !spec.zero_pad {
+            let mut field = Vec::with_capacity(4);
+            if value < 0.0 {
+                field.push(b'-');
+            } else {
+                field.extend(spec.prefix);
+            }
+            field.extend_from_slice(b"Inf");
+            return field;
+        }
+        // With zero padding SQLite writes a number too large to be read back as anything but
+        // infinity.
+        Decimal {
+            negative: value < 0.0,
+            digits: vec![b'9'],
+            decimal_point: 1000,
+        }
+    } else {
+        Decimal::new(value, round, if spec.alternate_form_2 { 26 } else { 16 })
+    };
+    let prefix = if decimal.negative {
+        Some(b'-')
+    } else {
+        spec.prefix
+    };
+    let digit = |index: usize| decimal.digits.get(index).copied().unwrap_or(b'0');
+
+    let exponent = decimal.decimal_point - 1;
+    let remove_trailing_zeros;
+    if style == Style::Generic {
+        precision -= 1;
+        remove_trailing_zeros = !spec.alternate_form;
+        if exponent < -4 || exponent > precision {
+            style = Style::Exponent;
+        } else {
+            precision -= exponent;
+            style = Style::Fixed;
+        }
+    } else {
+        remove_trailing_zeros = spec.alternate_form_2;
+    }
+    let show_point = precision > 0 || spec.alternate_form || spec.alternate_form_2;
+
+    let mut field = Vec::new();
+    field.extend(prefix);
+    let mut index = 0;
+    // Digits before the decimal point.
+    let mut e2 = if style == Style::Exponent {
+        0
+    } else {
+        exponent
+    };
+    if e2 < 0 {
+        field.push(b'0');
+    } else {
+        while e2 >= 0 {
+            field.push(digit(index));
+            index += 1;
+            if spec.thousands_separator && e2 % 3 == 0 && e2 > 1 {
+                field.push(b',');
+            }
+            e2 -= 1;
+        }
+    }
+    if show_point {
+        field.push(b'.');
+    }
+    // Zeros between the decimal point and the first significant digit.
+    e2 += 1;
+    while e2 < 0 && precision > 0 {
+        field.push(b'0');
+        precision -= 1;
+        e2 += 1;
+    }
+    // Significant digits after the decimal point.
+    while precision > 0 {
+        field.push(digit(index));
+        index += 1;
+        precision -= 1;
+    }
+    if remove_trailing_zeros && show_point {
+        while field.last() == Some(&b'0') {
+            field.pop();
+        }
+        if field.last() == Some(&b'.') {
+            if spec.alternate_form_2 {
+                field.push(b'0');
+            } else {
+                field.pop();
+            }
+        }
+    }
+    if style == Style::Exponent {
+        field.push(exponent_char);
+        field.push(if exponent < 0 { b'-' } else { b'+' });
+        field.extend_from_slice(format!("{:02}", exponent.abs()).as_bytes());
+    }
+
+    if spec.zero_pad && !spec.left_justify && field.len() < spec.width {
+        let sign_length = usize::from(prefix.is_some());
+        let zeros = spec.width - field.len();
+        field.splice(sign_length..sign_length, std::iter::repeat_n(b'0', zeros));
+    }
+    field
+}
+
+/// Quotes `text` for `%q`, `%Q` or `%w` by doubling the quote character inside it. `%Q` also
+/// surrounds the text with quotes and writes NULL as `NULL`, where the others write `(NULL)`.
+fn escape(text: Option<Vec<u8>>, conversion: u8, spec: &Spec) -> Vec<u8> {
+    let quote = if conversion == b'w' { b'"' } else { b'\'' };
+    let add_quotes = text.is_some() && conversion == b'Q';
+    let text = text.unwrap_or_else(|| {
+        if conversion == b'Q' {
+            b"NULL".to_vec()
+        } else {
+            b"(NULL)".to_vec()
+        }
+    });
+    // The precision limits how much of the text is used, not the length of the result.
+    let length = spec.text_length(&text);
+
+    let mut field = Vec::with_capacity(length + 2);
+    if add_quotes {
+        field.push(quote);
+    }
+    for &c in &text[..length] {
+        field.push(c);
+        if c == quote {
+            field.push(c);
+        }
+    }
+    if add_quotes {
+        field.push(quote);
+    }
+    field
+}
+
+/// Appends the first character of `text` for `%c`, repeated as many times as the precision
+/// says. NULL and empty text give a NUL character, as in SQLite.
+fn format_char(out: &mut Vec<u8>, text: Option<Vec<u8>>, spec: &Spec) -> Result<()> {
+    let text = text.unwrap_or_default();
+    let character = match text.first() {
+        Some(&lead) if lead & 0xc0 == 0xc0 => &text[..1 + continuation_bytes(&text[1..]).min(3)],
+        Some(_) => &text[..1],
+        None => &[0][..],
+    };
+
+    let mut width = spec.width;
+    let precision = spec.precision.unwrap_or(0);
+    if precision > 1 {
+        if precision.saturating_mul(character.len()) > MAX_LENGTH {
+            return Err(too_big());
+        }
+        // The repeated characters come first, so the width only pads the last one.
+        width = width.saturating_sub(precision - 1);
+        if width > 1 && !spec.left_justify {
+            out.resize(out.len() + width - 1, b' ');
+            width = 0;
+        }
+        for _ in 1..precision {
+            out.extend_from_slice(character);
+        }
+    }
+    pad(out, character, width, spec.left_justify, true);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(format: &str, args: &[SqliteValue]) -> String {
+        printf(format, args).unwrap()
+    }
+
+    #[test]
+    fn test_integers() {
+        let n = |n: i64| SqliteValue::Integer(n);
+        assert_eq!(
+            format("%d|%5d|%-5d|%05d", &[n(42), n(42), n(42), n(-42)]),
+            "42|   42|42   |-0042"
+        );
+        assert_eq!(format("%+d|% d|%.3d", &[n(7), n(7), n(7)]), "+7| 7|007");
+        assert_eq!(
+            format("%x|%X|%#x|%o|%#o", &[n(255), n(255), n(255), n(8), n(8)]),
+            "ff|FF|0xff|10|010"
+        );
+        assert_eq!(format("%u", &[n(-1)]), "18446744073709551615");
+        assert_eq!(format("%lld", &[n(i64::MIN)]), "-9223372036854775808");
+        assert_eq!(format("%p|%#p", &[n(255), n(255)]), "ff|0xff");
+    }
+
+    #[test]
+    fn test_thousands_and_ordinals() {
+        let n = |n: i64| SqliteValue::Integer(n);
+        assert_eq!(
+            format("%,d|%,d|%,d|%,x", &[n(1234567), n(-1000), n(999), n(65535)]),
+            "1,234,567|-1,000|999|ffff"
+        );
+        assert_eq!(format("%,010d", &[n(1234)]), "0,000,001,234");
+        assert_eq!(
+            format(
+                "%,.2f|%,f|%,.3g",
+                &[
+                    SqliteValue::Real(1234567.891),
+                    SqliteValue::Real(-1000.0),
+                    SqliteValue::Real(1234567.0)
+                ]
+            ),
+            "1,234,567.89|-1,000.000000|1.23e+06"
+        );
+        assert_eq!(
+            format(
+                "%r %r %r %r %r %r %r",
+                &[n(1), n(2), n(3), n(11), n(22), n(104), n(113)]
+            ),
+            "1st 2nd 3rd 11th 22nd 104th 113th"
+        );
+        assert_eq!(format("%.5r|%-6r|", &[n(1), n(-2)]), "001st|-2nd  |");
+    }
+
+    #[test]
+    fn test_width_and_precision_arguments() {
+        let n = |n: i64| SqliteValue::Integer(n);
+        let r = |r: f64| SqliteValue::Real(r);
+        assert_eq!(
+            format(
+                "%*d|%-*d|%*d|%.*f",
+                &[n(5), n(42), n(5), n(42), n(-4), n(42), n(2), r(1.23456)]
+            ),
+            "   42|42   |42  |1.23"
+        );
+        assert_eq!(format("%*.*f", &[n(8), n(-3), r(1.5)]), "   1.500");
+        assert_eq!(format("%.*s|", &[SqliteValue::Null, n(12345)]), "|");
+        assert_eq!(format("%5.2ld|%l", &[n(3)]), "   03|");
+        assert_eq!(format("%d%n%d", &[n(1), n(2)]), "12");
+    }
+
+    #[test]
+    fn test_floats() {
+        let r = |r: f64| SqliteValue::Real(r);
+        assert_eq!(
+            format("%f|%.2f|%.0f", &[r(1.23456), r(2.675), r(0.5)]),
+            "1.234560|2.67|1"
+        );
+        assert_eq!(
+            format("%e|%.2E", &[r(12345.678), r(0.000123)]),
+            "1.234568e+04|1.23E-04"
+        );
+        assert_eq!(
+            format(
+                "%g|%g|%g|%G",
+                &[r(100000.0), r(1000000.0), r(0.0001), r(1e-5)]
+            ),
+            "100000|1e+06|0.0001|1E-05"
+        );
+        assert_eq!(format("%#g|%!.3g", &[r(1.5), r(2.0)]), "1.50000|2.0");
+        assert_eq!(
+            format("%08.2f|%-8.2f|%+.1f", &[r(-1.23456), r(1.23456), r(2.0)]),
+            "-0001.23|1.23    |+2.0"
+        );
+        assert_eq!(format("%.20f", &[r(0.1)]), "0.10000000000000000000");
+        assert_eq!(
+            format("%!.20e", &[r(0.1 + 0.2)]),
+            "3.000000000000000444e-01"
+        );
+        assert_eq!(format("%.3f|%.3f", &[r(0.0005), r(0.00049)]), "0.001|0.000");
+        assert_eq!(
+            format("%f|%f", &[r(f64::INFINITY), r(f64::NEG_INFINITY)]),
+            "Inf|-Inf"
+        );
+        assert_eq!(
+            format(
+                "%5f|%05f|%05.1e",
+                &[r(f64::NAN), r(f64::NAN), r(f64::INFINITY)]
+            ),
+            "  NaN| null|9.0e+999"
+        );
+        assert_eq!(format("%.1f", &[r(99.96)]), "100.0");
+    }
+
+    #[test]
+    fn test_strings_and_conversions() {
+        let t = |t: &str| SqliteValue::Text(t.to_string());
+        assert_eq!(
+            format("%s and %s", &[t("this"), t("that")]),
+            "this and that"
+        );
+        assert_eq!(
+            format("[%5s][%-5s][%.2s]", &[t("ab"), t("ab"), t("abc")]),
+            "[   ab][ab   ][ab]"
+        );
+        assert_eq!(
+            format(
+                "[%!.3s][%5s][%!5s][%z]",
+                &[t("héllo"), t("é"), t("é"), t("z")]
+            ),
+            "[hél][   é][    é][z]"
+        );
+        assert_eq!(format("%c%c", &[t("xyz"), t("é")]), "xé");
+        assert_eq!(
+            format(
+                "[%.3c][%5c][%-3c][%5.3c]",
+                &[t("ab"), t("x"), t("é"), t("y")]
+            ),
+            "[aaa][    x][é  ][  yyy]"
+        );
+        assert_eq!(format("%c", &[SqliteValue::Null]), "\0");
+        assert_eq!(format("%d%%|%3%", &[t("12abc")]), "12%|  %");
+        assert_eq!(format("%.1f", &[t("2.25")]), "2.3");
+        assert_eq!(format("%s|%d", &[SqliteValue::Integer(5)]), "5|0");
+        assert_eq!(format("%s", &[SqliteValue::Real(1.0)]), "1.0");
+        assert_eq!(format("%s", &[SqliteValue::Blob(b"a\0b".to_vec())]), "a");
+        assert_eq!(format("100%", &[]), "100%");
+        assert_eq!(format("a%yb", &[]), "a");
+    }
+
+    #[test]
+    fn test_quoting() {
+        let t = |t: &str| SqliteValue::Text(t.to_string());
+        assert_eq!(
+            format(
+                "%q|%Q|%w|%Q|%q|%w",
+                &[
+                    t("it's"),
+                    t("it's"),
+                    t("a\"b"),
+                    SqliteValue::Null,
+                    SqliteValue::Null,
+                    SqliteValue::Null
+                ]
+            ),
+            "it''s|'it''s'|a\"\"b|NULL|(NULL)|(NULL)"
+        );
+        assert_eq!(
+            format("%.3Q|%!.2q|%8Q|", &[t("'ab"), t("é'x"), t("a")]),
+            "'''ab'|é''|     'a'|"
+        );
+        assert_eq!(format("%Q", &[SqliteValue::Integer(12)]), "'12'");
+    }
+
+    #[test]
+    fn test_too_big() {
+        let n = || SqliteValue::Integer(1);
+        assert_eq!(
+            printf("%.2000000000d", &[n()]).unwrap_err().to_string(),
+            "string or blob too big"
+        );
+        assert!(printf("%1000000001s", &[n()]).is_err());
+        assert!(printf("%.2000000000c", &[n()]).is_err());
+        assert_eq!(printf("%.2000000000s", &[n()]).unwrap(), "1");
+    }
+}
@@ -0,0 +1,1606 @@
+use std::sync::Arc;
+
+use datafusion::arrow::array::{
+    Array, ArrayRef, BinaryArray, BooleanArray, Int64Array, StringArray,
+};
+use datafusion::arrow::compute::kernels::zip::zip;
+use datafusion::arrow::compute::{cast, is_not_null};
+use datafusion::arrow::datatypes::DataType;
+use datafusion::arrow::datatypes::DataType::{
+    Binary, Boolean, Decimal128, Decimal256, FixedSizeBinary, Float16, Float32, Float64, Int16,
+    Int32, Int64, Int8, LargeBinary, Null, UInt16, UInt32, UInt64, UInt8, Utf8,
+};
+use datafusion::common::{DataFusionError, ScalarValue};
+use datafusion::error::Result;
+use datafusion::logical_expr::type_coercion::binary::comparison_coercion;
+use datafusion::logical_expr::TypeSignature::Any;
+use datafusion::logical_expr::{ColumnarValue, ScalarUDFImpl, Signature, Volatility};
+
+use crate::catalog::{Documentation, Documented};
+use crate::sqlite::pattern::{glob_matches, like_matches};
+use crate::sqlite::printf::{format_float, printf};
+use crate::sqlite::value::{parse_real_prefix, SqliteValue};
+
+const CORE_FUNCTIONS_LINK: &str = "https://www.sqlite.org/lang_corefunc.html";
+
+/// The instr(X,Y) function finds the first occurrence of string Y within string X and returns
+/// the number of prior characters plus 1, or 0 if Y is nowhere found within X. If both X and Y
+/// are BLOBs, then instr(X,Y) returns one more than the number bytes prior to the first
+/// occurrence of Y, or 0 if Y does not occur anywhere within X. If either X or Y is NULL,
+/// then the result is NULL.
+#[derive(Debug)]
+pub struct Instr {
+    signature: Signature,
+}
+
+impl Instr {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Instr {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "instr"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let haystacks = get_sqlite_values(&args[0])?;
+        let needles = get_sqlite_values(&args[1])?;
+
+        let result = haystacks
+            .iter()
+            .zip(needles.iter())
+            .map(|(haystack, needle)| match (haystack, needle) {
+                (SqliteValue::Null, _) | (_, SqliteValue::Null) => None,
+                (SqliteValue::Blob(haystack), SqliteValue::Blob(needle)) => {
+                    let position = if needle.is_empty() {
+                        Some(0)
+                    } else {
+                        haystack
+                            .windows(needle.len())
+                            .position(|window| window == needle.as_slice())
+                    };
+                    Some(position.map_or(0, |position| position as i64 + 1))
+                }
+                _ => {
+                    let haystack = haystack.to_text()?;
+                    let needle = needle.to_text()?;
+                    let position = haystack.find(&needle);
+                    Some(position.map_or(0, |position| {
+                        haystack[..position].chars().count() as i64 + 1
+                    }))
+                }
+            })
+            .collect::<Int64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Instr {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "instr(X, Y)",
+            description: "Returns the 1-based position of the first occurrence of Y in X, or 0 if Y is not found",
+            examples: &["instr('hello', 'l') → 3"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The hex() function interprets its argument as a BLOB and returns a string which is the
+/// upper-case hexadecimal rendering of the content of that blob. Numbers are rendered as text
+/// first, so hex(12) is the hexadecimal rendering of the string '12'. hex(NULL) is an empty
+/// string.
+#[derive(Debug)]
+pub struct Hex {
+    signature: Signature,
+}
+
+impl Hex {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Hex {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "hex"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| {
+                Some(
+                    value
+                        .to_blob()
+                        .map(|bytes| to_hex(&bytes))
+                        .unwrap_or_default(),
+                )
+            })
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Hex {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "hex(X)",
+            description: "Returns the upper-case hexadecimal rendering of the bytes of X",
+            examples: &["hex('abc') → 616263"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The unhex(X,Y) function returns a BLOB value which is the decoding of the hexadecimal
+/// string X. If X contains any characters that are not hexadecimal digits and which are not
+/// in Y, then unhex(X,Y) returns NULL. If Y is omitted, it is understood to be an empty string
+/// and hence X must be a pure hexadecimal string. All hexadecimal digits in X must occur in
+/// pairs, or else unhex(X,Y) returns NULL. If either parameter X or Y is NULL, then unhex(X,Y)
+/// returns NULL.
+#[derive(Debug)]
+pub struct Unhex {
+    signature: Signature,
+}
+
+impl Unhex {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(1), Any(2)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Unhex {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "unhex"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let hex_strings = get_sqlite_values(&args[0])?;
+        let ignored = match args.get(1) {
+            Some(ignored) => get_sqlite_values(ignored)?
+                .iter()
+                .map(SqliteValue::to_text)
+                .collect(),
+            None => vec![Some(String::new()); hex_strings.len()],
+        };
+
+        let result = hex_strings
+            .iter()
+            .zip(ignored.iter())
+            .map(|(hex_string, ignored)| from_hex(&hex_string.to_text()?, ignored.as_ref()?))
+            .collect::<BinaryArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Unhex {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "unhex(X [, Y])",
+            description: "Decodes the hexadecimal text X into a blob, ignoring the characters of Y between pairs of digits",
+            examples: &["unhex('61-62', '-') → X'6162'"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The quote(X) function returns the text of an SQL literal which is the value of its argument
+/// suitable for inclusion into an SQL statement. Strings are surrounded by single-quotes with
+/// escapes on interior quotes as needed. BLOBs are encoded as hexadecimal literals.
+#[derive(Debug)]
+pub struct Quote {
+    signature: Signature,
+}
+
+impl Quote {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Quote {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "quote"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| Some(quote(value)))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Quote {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "quote(X)",
+            description: "Returns the text of an SQL literal for X",
+            examples: &["quote('it''s') → 'it''s'"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The zeroblob(N) function returns a BLOB consisting of N bytes of 0x00. If N is negative,
+/// an empty BLOB is returned.
+#[derive(Debug)]
+pub struct Zeroblob {
+    signature: Signature,
+}
+
+impl Zeroblob {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Zeroblob {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "zeroblob"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Binary)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| {
+                let length = value.to_integer().max(0);
+                if length > MAX_LENGTH {
+                    return Err(DataFusionError::Execution(
+                        "string or blob too big".to_string(),
+                    ));
+                }
+                Ok(Some(vec![0u8; length as usize]))
+            })
+            .collect::<Result<BinaryArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Zeroblob {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "zeroblob(N)",
+            description: "Returns a blob of N 0x00 bytes",
+            examples: &["hex(zeroblob(2)) → 0000"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The printf(FORMAT,...) SQL function works like the sqlite3_mprintf() C-language function
+/// and the printf() function from the standard C library. The first argument is a format
+/// string that specifies how to construct the output string using values taken from
+/// subsequent arguments. If the FORMAT argument is missing or NULL then the result is NULL.
+/// SQLite's own conversions, such as `%q` for SQL string literals, `%r` for ordinals and the
+/// `,` flag for thousands separators, are supported too. The function is also available as
+/// format(FORMAT,...).
+#[derive(Debug)]
+pub struct Printf {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Printf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+            aliases: vec!["format".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Printf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "printf"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let columns = args
+            .iter()
+            .map(get_sqlite_values)
+            .collect::<Result<Vec<_>>>()?;
+
+        let result = columns[0]
+            .iter()
+            .enumerate()
+            .map(|(row, format)| {
+                let Some(format) = format.to_text() else {
+                    return Ok(None);
+                };
+                let row_args = columns[1..]
+                    .iter()
+                    .map(|column| column[row].clone())
+                    .collect::<Vec<_>>();
+                printf(&format, &row_args)
+                    .map(Some)
+                    .map_err(|err| DataFusionError::Execution(err.to_string()))
+            })
+            .collect::<Result<StringArray>>()?;
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Printf {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "printf(FORMAT, ...)",
+            description: "Returns the text built from FORMAT and the arguments like the C printf() function, with SQLite's extensions such as %q and %r",
+            examples: &["printf('%,d %r', 1234567, 2) → 1,234,567 2nd"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The glob(X,Y) function is equivalent to the expression "Y GLOB X". GLOB is case sensitive
+/// and uses the Unix file globbing syntax for its wildcards: `*`, `?` and `[...]`.
+/// If either argument is NULL, the result is NULL.
+#[derive(Debug)]
+pub struct Glob {
+    signature: Signature,
+}
+
+impl Glob {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Glob {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "glob"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let patterns = get_sqlite_values(&args[0])?;
+        let texts = get_sqlite_values(&args[1])?;
+
+        let result = patterns
+            .iter()
+            .zip(texts.iter())
+            .map(|(pattern, text)| Some(glob_matches(&pattern.to_text()?, &text.to_text()?)))
+            .collect::<BooleanArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Glob {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "glob(X, Y)",
+            description: "Returns 1 if the text Y matches the glob pattern X, and 0 otherwise",
+            examples: &["glob('*.txt', 'notes.txt') → 1"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The like(X,Y) function is equivalent to the expression "Y LIKE X" and like(X,Y,Z) to
+/// "Y LIKE X ESCAPE Z". `%` matches any sequence of characters and `_` any single character,
+/// ignoring the case of ASCII letters. The escape character Z makes the character after it
+/// match literally. If any argument is NULL, the result is NULL.
+#[derive(Debug)]
+pub struct Like {
+    signature: Signature,
+}
+
+impl Like {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::one_of(vec![Any(2), Any(3)], Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Like {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "like"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Boolean)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let patterns = get_sqlite_values(&args[0])?;
+        let texts = get_sqlite_values(&args[1])?;
+        let escapes = match args.get(2) {
+            Some(escapes) => get_sqlite_values(escapes)?
+                .iter()
+                .map(|escape| {
+                    let Some(escape) = escape.to_text() else {
+                        return Ok(None);
+                    };
+                    let mut chars = escape.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(escape), None) => Ok(Some(Some(escape))),
+                        _ => Err(DataFusionError::Execution(
+                            "ESCAPE expression must be a single character".to_string(),
+                        )),
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => vec![Some(None); patterns.len()],
+        };
+
+        let result = patterns
+            .iter()
+            .zip(texts.iter())
+            .zip(escapes.iter())
+            .map(|((pattern, text), escape)| {
+                Some(like_matches(
+                    &pattern.to_text()?,
+                    &text.to_text()?,
+                    (*escape)?,
+                ))
+            })
+            .collect::<BooleanArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Like {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "like(X, Y [, Z])",
+            description: "Returns 1 if the text Y matches the LIKE pattern X, with Z as the escape character, and 0 otherwise",
+            examples: &["like('a%', 'Apple') → 1"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The likely(X) function returns the argument X unchanged. It is a hint to the query planner
+/// in SQLite that X is usually true and has no effect here. The function is also available as
+/// unlikely(X), the hint that X is usually false.
+#[derive(Debug)]
+pub struct Likely {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Likely {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+            aliases: vec!["unlikely".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Likely {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "likely"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        Ok(args[0].clone())
+    }
+}
+
+impl Documented for Likely {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "likely(X)",
+            description: "Returns X unchanged; it is a hint for SQLite's query planner",
+            examples: &["likely(42) → 42"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The likelihood(X,Y) function returns argument X unchanged. The value Y in likelihood(X,Y)
+/// must be a floating point constant between 0.0 and 1.0, inclusive. It is a hint to the
+/// query planner in SQLite about the probability that X is true and has no effect here.
+#[derive(Debug)]
+pub struct Likelihood {
+    signature: Signature,
+}
+
+impl Likelihood {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Likelihood {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "likelihood"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(arg_types[0].clone())
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let probability = match &args[1] {
+            ColumnarValue::Scalar(probability) if !probability.is_null() => {
+                probability.cast_to(&Float64).ok()
+            }
+            _ => None,
+        };
+        match probability {
+            Some(ScalarValue::Float64(Some(probability))) if (0.0..=1.0).contains(&probability) => {
+                Ok(args[0].clone())
+            }
+            _ => Err(DataFusionError::Execution(
+                "second argument to likelihood() must be a constant between 0.0 and 1.0"
+                    .to_string(),
+            )),
+        }
+    }
+}
+
+impl Documented for Likelihood {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "likelihood(X, Y)",
+            description:
+                "Returns X unchanged; Y is the probability, between 0.0 and 1.0, that X is true",
+            examples: &["likelihood(1, 0.5) → 1"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The typeof(X) function returns a string that indicates the datatype of the expression X:
+/// "null", "integer", "real", "text", or "blob". Arrow integer and boolean columns are
+/// "integer", floating point and decimal columns are "real", binary columns are "blob" and
+/// every other type is "text".
+#[derive(Debug)]
+pub struct TypeOf {
+    signature: Signature,
+}
+
+impl TypeOf {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for TypeOf {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "typeof"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| Some(value.type_name()))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for TypeOf {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "typeof(X)",
+            description: "Returns the SQLite storage class of X: null, integer, real, text or blob",
+            examples: &["typeof(3.14) → real"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The unicode(X) function returns the numeric unicode code point corresponding to the first
+/// character of the string X. If X is an empty string or NULL, the result is NULL.
+#[derive(Debug)]
+pub struct Unicode {
+    signature: Signature,
+}
+
+impl Unicode {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Unicode {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "unicode"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| value.to_text()?.chars().next().map(|c| c as i64))
+            .collect::<Int64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Unicode {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "unicode(X)",
+            description: "Returns the code point of the first character of X",
+            examples: &["unicode('A') → 65"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The char(X1,X2,...,XN) function returns a string composed of characters having the unicode
+/// code point values of integers X1 through XN, respectively. Values that are not valid code
+/// points become U+FFFD.
+#[derive(Debug)]
+pub struct Char {
+    signature: Signature,
+}
+
+impl Char {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::variadic_any(Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Char {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "char"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let columns = args
+            .iter()
+            .map(get_sqlite_values)
+            .collect::<Result<Vec<_>>>()?;
+
+        let result = (0..args[0].len())
+            .map(|row| {
+                let text = columns
+                    .iter()
+                    .map(|column| {
+                        u32::try_from(column[row].to_integer())
+                            .ok()
+                            .and_then(char::from_u32)
+                            .unwrap_or(char::REPLACEMENT_CHARACTER)
+                    })
+                    .collect::<String>();
+                Some(text)
+            })
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Char {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "char(X1, X2, ..., XN)",
+            description: "Returns the text made of the characters with the code points X1 to XN",
+            examples: &["char(72, 105) → Hi"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The soundex(X) function returns a string that is the soundex encoding of the string X.
+/// The string "?000" is returned if the argument is NULL or contains no ASCII alphabetic
+/// characters.
+#[derive(Debug)]
+pub struct Soundex {
+    signature: Signature,
+}
+
+impl Soundex {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Soundex {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "soundex"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Utf8)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| Some(soundex(&value.to_text().unwrap_or_default())))
+            .collect::<StringArray>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Soundex {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "soundex(X)",
+            description: "Returns the soundex encoding of X",
+            examples: &["soundex('Robert') → R163"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The ifnull(X,Y) function returns a copy of its first non-NULL argument, or NULL if both
+/// arguments are NULL. Arguments of different types are converted to a common type first.
+#[derive(Debug)]
+pub struct IfNull {
+    signature: Signature,
+}
+
+impl IfNull {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for IfNull {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "ifnull"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(get_common_type(&arg_types[0], &arg_types[1]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let common_type = get_common_type(args[0].data_type(), args[1].data_type());
+        let first = cast(&args[0], &common_type)?;
+        let second = cast(&args[1], &common_type)?;
+
+        let result = zip(&is_not_null(&first)?, &first, &second)?;
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+impl Documented for IfNull {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "ifnull(X, Y)",
+            description: "Returns X if it is not NULL, and Y otherwise",
+            examples: &["ifnull(NULL, 'default') → default"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The iif(X,Y,Z) function returns the value Y if X is true, and Z otherwise. X is true when
+/// it is a non-zero number, or text that starts with a non-zero number. Y and Z are converted
+/// to a common type first.
+#[derive(Debug)]
+pub struct Iif {
+    signature: Signature,
+}
+
+impl Iif {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(3, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Iif {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "iif"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, arg_types: &[DataType]) -> Result<DataType> {
+        Ok(get_common_type(&arg_types[1], &arg_types[2]))
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let conditions = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|condition| Some(condition.is_true()))
+            .collect::<BooleanArray>();
+        let common_type = get_common_type(args[1].data_type(), args[2].data_type());
+        let when_true = cast(&args[1], &common_type)?;
+        let when_false = cast(&args[2], &common_type)?;
+
+        let result = zip(&conditions, &when_true, &when_false)?;
+        Ok(ColumnarValue::Array(result))
+    }
+}
+
+impl Documented for Iif {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "iif(X, Y, Z)",
+            description: "Returns Y if X is true, and Z otherwise",
+            examples: &["iif(1 > 2, 'yes', 'no') → no"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The sign(X) function returns -1, 0, or +1 if the argument X is a numeric value that is
+/// negative, zero, or positive, respectively. If the argument to sign(X) is NULL or is a
+/// string or blob that cannot be losslessly converted into a number, then sign(X) returns NULL.
+#[derive(Debug)]
+pub struct Sign {
+    signature: Signature,
+}
+
+impl Sign {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::any(1, Volatility::Immutable),
+        }
+    }
+}
+
+impl ScalarUDFImpl for Sign {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "sign"
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        let args = ColumnarValue::values_to_arrays(args)?;
+        let result = get_sqlite_values(&args[0])?
+            .iter()
+            .map(|value| {
+                let value = value.to_numeric()?.to_real();
+                Some(if value < 0.0 {
+                    -1
+                } else if value > 0.0 {
+                    1
+                } else {
+                    0
+                })
+            })
+            .collect::<Int64Array>();
+
+        Ok(ColumnarValue::Array(Arc::new(result) as ArrayRef))
+    }
+}
+
+impl Documented for Sign {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "sign(X)",
+            description: "Returns -1, 0 or +1 for a negative, zero or positive X, or NULL if X is not a number",
+            examples: &["sign(-8.4) → -1"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// The changes() function returns the number of rows modified by the most recently completed
+/// INSERT, DELETE or UPDATE statement. DataFusion queries do not modify rows in place, so it
+/// always returns 0. The function is also available as total_changes() and
+/// last_insert_rowid(), which return 0 for the same reason.
+#[derive(Debug)]
+pub struct Changes {
+    signature: Signature,
+    aliases: Vec<String>,
+}
+
+impl Changes {
+    pub fn new() -> Self {
+        Self {
+            signature: Signature::exact(vec![], Volatility::Stable),
+            aliases: vec!["total_changes".to_string(), "last_insert_rowid".to_string()],
+        }
+    }
+}
+
+impl ScalarUDFImpl for Changes {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        "changes"
+    }
+
+    fn aliases(&self) -> &[String] {
+        &self.aliases
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> Result<DataType> {
+        Ok(Int64)
+    }
+
+    fn invoke(&self, _args: &[ColumnarValue]) -> Result<ColumnarValue> {
+        Ok(ColumnarValue::Scalar(ScalarValue::Int64(Some(0))))
+    }
+}
+
+impl Documented for Changes {
+    fn documentation(&self) -> Documentation {
+        Documentation {
+            syntax: "changes()",
+            description: "Returns 0, since DataFusion queries do not modify rows",
+            examples: &["changes() → 0"],
+            link: Some(CORE_FUNCTIONS_LINK),
+        }
+    }
+}
+
+/// SQLite's default limit on the size of a string or BLOB.
+const MAX_LENGTH: i64 = 1_000_000_000;
+
+/// Reads a column as SQLite values. Integer and boolean columns become integers, floating
+/// point and decimal columns become reals, binary columns become blobs and every other type
+/// is read as text.
+pub(crate) fn get_sqlite_values(array: &ArrayRef) -> Result<Vec<SqliteValue>> {
+    let values = match array.data_type() {
+        Null => vec![SqliteValue::Null; array.len()],
+        Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 | Boolean => {
+            let array = cast(array, &Int64)?;
+            datafusion::common::cast::as_int64_array(&array)?
+                .iter()
+                .map(|value| value.map_or(SqliteValue::Null, SqliteValue::Integer))
+                .collect()
+        }
+        UInt64 => datafusion::common::cast::as_uint64_array(array)?
+            .iter()
+            .map(|value| match value {
+                Some(value) => i64::try_from(value)
+                    .map_or(SqliteValue::Real(value as f64), SqliteValue::Integer),
+                None => SqliteValue::Null,
+            })
+            .collect(),
+        Float16 | Float32 | Float64 | Decimal128(_, _) | Decimal256(_, _) => {
+            let array = cast(array, &Float64)?;
+            datafusion::common::cast::as_float64_array(&array)?
+                .iter()
+                .map(|value| value.map_or(SqliteValue::Null, SqliteValue::Real))
+                .collect()
+        }
+        Binary | LargeBinary => {
+            let array = cast(array, &Binary)?;
+            datafusion::common::cast::as_binary_array(&array)?
+                .iter()
+                .map(|value| {
+                    value.map_or(SqliteValue::Null, |bytes| SqliteValue::Blob(bytes.to_vec()))
+                })
+                .collect()
+        }
+        FixedSizeBinary(_) => datafusion::common::cast::as_fixed_size_binary_array(array)?
+            .iter()
+            .map(|value| value.map_or(SqliteValue::Null, |bytes| SqliteValue::Blob(bytes.to_vec())))
+            .collect(),
+        _ => {
+            let array = cast(array, &Utf8)?;
+            datafusion::common::cast::as_string_array(&array)?
+                .iter()
+                .map(|value| {
+                    value.map_or(SqliteValue::Null, |text| {
+                        SqliteValue::Text(text.to_string())
+                    })
+                })
+                .collect()
+        }
+    };
+    Ok(values)
+}
+
+/// The type both `left` and `right` are converted to when either may be returned.
+fn get_common_type(left: &DataType, right: &DataType) -> DataType {
+    match (left, right) {
+        (left, right) if left == right => left.clone(),
+        (Null, other) | (other, Null) => other.clone(),
+        (left, right) => comparison_coercion(left, right).unwrap_or(Utf8),
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02X}")).collect()
+}
+
+/// Decodes pairs of hexadecimal digits, skipping characters in `ignored` between pairs.
+fn from_hex(hex_string: &str, ignored: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(hex_string.len() / 2);
+    let mut chars = hex_string.chars();
+    while let Some(c) = chars.next() {
+        match c.to_digit(16) {
+            Some(high) => {
+                let low = chars.next()?.to_digit(16)?;
+                bytes.push((high * 16 + low) as u8);
+            }
+            None if ignored.contains(c) => {}
+            None => return None,
+        }
+    }
+    Some(bytes)
+}
+
+/// Renders `value` as an SQL literal.
+fn quote(value: &SqliteValue) -> String {
+    match value {
+        SqliteValue::Null => "NULL".to_string(),
+        SqliteValue::Integer(integer) => integer.to_string(),
+        // SQLite has no literal for infinity, so it uses one that overflows to it.
+        SqliteValue::Real(real) if real.is_infinite() && *real < 0.0 => "-9.0e+999".to_string(),
+        SqliteValue::Real(real) if real.is_infinite() => "9.0e+999".to_string(),
+        SqliteValue::Real(real) => {
+            // 15 significant digits are used unless more are needed to read back the same value.
+            let text = format_float(*real, 'g', 15, true);
+            if parse_real_prefix(&text) == *real {
+                text
+            } else {
+                format_float(*real, 'e', 20, true)
+            }
+        }
+        SqliteValue::Text(text) => format!("'{}'", text.replace('\'', "''")),
+        SqliteValue::Blob(bytes) => format!("X'{}'", to_hex(bytes)),
+    }
+}
+
+/// Encodes `text` the way SQLite's soundex() does: the first letter followed by the digits of
+/// up to three more consonant groups, where repeated codes are only counted once.
+fn soundex(text: &str) -> String {
+    // The codes of the letters A to Z. Vowels and H, W and Y have no code.
+    const CODES: &[u8; 26] = b"01230120022455012623010202";
+
+    let letters = text.as_bytes();
+    let Some(start) = letters.iter().position(u8::is_ascii_alphabetic) else {
+        return "?000".to_string();
+    };
+    let code = |letter: u8| {
+        if letter.is_ascii_alphabetic() {
+            CODES[usize::from(letter.to_ascii_uppercase() - b'A')]
+        } else {
+            b'0'
+        }
+    };
+
+    let mut result = vec![letters[start].to_ascii_uppercase()];
+    let mut previous = code(letters[start]);
+    for &letter in &letters[start + 1..] {
+        if result.len() == 4 {
+            break;
+        }
+        let letter_code = code(letter);
+        if letter_code != b'0' && letter_code != previous {
+            result.push(letter_code);
+        }
+        previous = letter_code;
+    }
+    result.resize(4, b'0');
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(feature = "sqlite")]
+#[cfg(test)]
+mod tests {
+    use datafusion::assert_batches_sorted_eq;
+    use datafusion::prelude::SessionContext;
+
+    use crate::common::test_utils::set_up_maths_data_test;
+    use crate::sqlite::register_sqlite_udfs;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_instr() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select instr('hello world', 'o') as text, instr('héllo', 'l') as chars, instr(12345, 34) as number, instr(X'0102', X'02') as blob, instr('abc', 'z') as missing, instr(NULL, 'a') as null",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------+-------+--------+------+---------+------+
+| text | chars | number | blob | missing | null |
++------+-------+--------+------+---------+------+
+| 5    | 3     | 3      | 2    | 0       |      |
++------+-------+--------+------+---------+------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_hex_unhex_quote() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select hex('abc') as hex, hex(12) as number, hex(NULL) as null, hex(unhex('00ff')) as round_trip, unhex('12-34', '-') as ignored, unhex('1g') as invalid, quote('it''s') as text, quote(1.5) as real, quote(X'0A') as blob, quote(NULL) as quoted_null",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++--------+--------+------+------------+---------+---------+---------+------+-------+-------------+
+| hex    | number | null | round_trip | ignored | invalid | text    | real | blob  | quoted_null |
++--------+--------+------+------------+---------+---------+---------+------+-------+-------------+
+| 616263 | 3132   |      | 00FF       | 1234    |         | 'it''s' | 1.5  | X'0A' | NULL        |
++--------+--------+------+------------+---------+---------+---------+------+-------+-------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_printf() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, printf('%d|%5.2f|%-4s|%x', int, float, index, uint) as printf, format('%s', float) as format FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------------+--------+
+| index | printf          | format |
++-------+-----------------+--------+
+| 1     | -2| 1.00|1   |2 | 1.0    |
+| 2     | 3| 3.30|2   |3  | 3.3    |
+| 3     | 0| 0.00|3   |0  |        |
++-------+-----------------+--------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_printf_format_spec() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, printf('%,.2f %r %*d %Q', float * 1000, index, index, int, float) as label FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+-----------------------+
+| index | label                 |
++-------+-----------------------+
+| 1     | 1,000.00 1st -2 '1.0' |
+| 2     | 3,300.00 2nd  3 '3.3' |
+| 3     | 0.00 3rd   0 NULL     |
++-------+-----------------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql("select printf('%.2000000000d', 1) as result")
+            .await?;
+        let result = df.clone().collect().await;
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .to_string()
+            .contains("string or blob too big"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_glob_and_like() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select glob('a*c', 'abc') as glob, glob('A*', 'abc') as glob_case, glob('1?3', 123) as glob_number, like('a%', 'ABC') as like, like('a\\%', 'a%', '\\') as escaped, like('a\\%', 'ab', '\\') as not_escaped, like(NULL, 'a') as null",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------+-----------+-------------+------+---------+-------------+------+
+| glob | glob_case | glob_number | like | escaped | not_escaped | null |
++------+-----------+-------------+------+---------+-------------+------+
+| true | false     | true        | true | true    | false       |      |
++------+-----------+-------------+------+---------+-------------+------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx.sql("select like('a', 'a', 'xy') as result").await?;
+        let result = df.clone().collect().await;
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .to_string()
+            .contains("ESCAPE expression must be a single character"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_typeof_and_conditionals() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select index, typeof(int) as int_type, typeof(float) as float_type, typeof(index > 1) as bool_type, ifnull(int, 'none') as ifnull, iif(index - 2, 'yes', 'no') as iif, sign(int) as sign, likely(index) as likely, likelihood(uint, 0.5) as likelihood FROM maths_table ORDER BY index ASC",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++-------+----------+------------+-----------+--------+-----+------+--------+------------+
+| index | int_type | float_type | bool_type | ifnull | iif | sign | likely | likelihood |
++-------+----------+------------+-----------+--------+-----+------+--------+------------+
+| 1     | integer  | real       | integer   | -2     | yes | -1   | 1      | 2          |
+| 2     | integer  | real       | integer   | 3      | no  | 1    | 2      | 3          |
+| 3     | null     | null       | integer   | none   | yes |      | 3      |            |
++-------+----------+------------+-----------+--------+-----+------+--------+------------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx
+            .sql(
+                "select typeof('a') as text, typeof(X'01') as blob, typeof(NULL) as null, sign('12') as sign_text, sign('abc') as sign_invalid, ifnull(NULL, 5) as ifnull",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++------+------+------+-----------+--------------+--------+
+| text | blob | null | sign_text | sign_invalid | ifnull |
++------+------+------+-----------+--------------+--------+
+| text | blob | null | 1         |              | 5      |
++------+------+------+-----------+--------------+--------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let df = ctx.sql("select likelihood(1, 2) as result").await?;
+        let result = df.clone().collect().await;
+        assert!(&result
+            .err()
+            .unwrap()
+            .find_root()
+            .to_string()
+            .contains("second argument to likelihood() must be a constant between 0.0 and 1.0"));
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_characters() -> Result<()> {
+        let ctx = register_udfs_for_test()?;
+        let df = ctx
+            .sql(
+                "select unicode('é') as unicode, unicode('') as empty, char(72, 105, 233) as char, char(-1) as invalid, soundex('Robert') as robert, soundex('Tymczak') as tymczak, soundex('123') as no_letters, hex(zeroblob(3)) as zeroblob, changes() as changes, last_insert_rowid() as rowid",
+            )
+            .await?;
+
+        let batches = df.clone().collect().await?;
+
+        let expected: Vec<&str> = r#"
++---------+-------+------+---------+--------+---------+------------+----------+---------+-------+
+| unicode | empty | char | invalid | robert | tymczak | no_letters | zeroblob | changes | rowid |
++---------+-------+------+---------+--------+---------+------------+----------+---------+-------+
+| 233     |       | Hié  | �       | R163   | T522    | ?000       | 000000   | 0       | 0     |
++---------+-------+------+---------+--------+---------+------------+----------+---------+-------+"#
+            .split('\n')
+            .filter_map(|input| {
+                if input.is_empty() {
+                    None
+                } else {
+                    Some(input.trim())
+                }
+            })
+            .collect();
+        assert_batches_sorted_eq!(expected, &batches);
+
+        Ok(())
+    }
+
+    fn register_udfs_for_test() -> Result<SessionContext> {
+        let ctx = set_up_maths_data_test()?;
+        register_sqlite_udfs(&ctx)?;
+        Ok(ctx)
+    }
+}
@@ -0,0 +1,277 @@
+//! SQLite's dynamically typed values and the conversions SQLite applies between them.
+//!
+//! SQLite functions accept any value for any argument and convert it to the kind they need:
+//! numbers are rendered as text, text is read as a number by taking its longest numeric
+//! prefix, and NULL reads as 0 or an empty string. The `to_*` methods mirror the
+//! `sqlite3_value_*` accessors so the UDFs get the same results SQLite does for mixed input.
+
+use crate::sqlite::printf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum SqliteValue {
+    Null,
+    Integer(i64),
+    Real(f64),
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+impl SqliteValue {
+    /// The name typeof() reports for this value.
+    pub(crate) fn type_name(&self) -> &'static str {
+        match self {
+            SqliteValue::Null => "null",
+            SqliteValue::Integer(_) => "integer",
+            SqliteValue::Real(_) => "real",
+            SqliteValue::Text(_) => "text",
+            SqliteValue::Blob(_) => "blob",
+        }
+    }
+
+    pub(crate) fn is_null(&self) -> bool {
+        matches!(self, SqliteValue::Null)
+    }
+
+    /// The value as text, or `None` for NULL. Reals are rendered the way SQLite renders them.
+    pub(crate) fn to_text(&self) -> Option<String> {
+        match self {
+            SqliteValue::Null => None,
+            SqliteValue::Integer(integer) => Some(integer.to_string()),
+            SqliteValue::Real(real) => Some(format_real(*real)),
+            SqliteValue::Text(text) => Some(text.clone()),
+            SqliteValue::Blob(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        }
+    }
+
+    /// The value as bytes, or `None` for NULL. Anything that is not a blob is read as text.
+    pub(crate) fn to_blob(&self) -> Option<Vec<u8>> {
+        match self {
+            SqliteValue::Blob(bytes) => Some(bytes.clone()),
+            value => value.to_text().map(String::into_bytes),
+        }
+    }
+
+    /// The value as an integer. Reals are truncated towards zero, text and blobs are read up to
+    /// the first character that cannot continue an integer, and NULL is 0.
+    pub(crate) fn to_integer(&self) -> i64 {
+        match self {
+            SqliteValue::Null => 0,
+            SqliteValue::Integer(integer) => *integer,
+            // `as` saturates at the bounds of i64 and maps NaN to 0, as SQLite does.
+            SqliteValue::Real(real) => *real as i64,
+            SqliteValue::Text(text) => parse_integer_prefix(text),
+            SqliteValue::Blob(bytes) => parse_integer_prefix(&String::from_utf8_lossy(bytes)),
+        }
+    }
+
+    /// The value as a real. Text and blobs are read up to the first character that cannot
+    /// continue a number, and NULL is 0.0.
+    pub(crate) fn to_real(&self) -> f64 {
+        match self {
+            SqliteValue::Null => 0.0,
+            SqliteValue::Integer(integer) => *integer as f64,
+            SqliteValue::Real(real) => *real,
+            SqliteValue::Text(text) => parse_real_prefix(text),
+            SqliteValue::Blob(bytes) => parse_real_prefix(&String::from_utf8_lossy(bytes)),
+        }
+    }
+
+    /// Applies numeric affinity: numbers are returned as they are and text or blobs that hold
+    /// a well-formed number become that number. `None` is returned for NULL and for text that
+    /// cannot be converted without losing information.
+    pub(crate) fn to_numeric(&self) -> Option<SqliteValue> {
+        match self {
+            SqliteValue::Null => None,
+            SqliteValue::Integer(_) | SqliteValue::Real(_) => Some(self.clone()),
+            SqliteValue::Text(text) => parse_number(text),
+            SqliteValue::Blob(bytes) => parse_number(&String::from_utf8_lossy(bytes)),
+        }
+    }
+
+    /// Whether SQLite treats the value as true in a boolean context such as iif().
+    pub(crate) fn is_true(&self) -> bool {
+        match self {
+            SqliteValue::Integer(integer) => *integer != 0,
+            value => value.to_real() != 0.0,
+        }
+    }
+}
+
+/// Renders a real the way SQLite converts it to text, with up to 15 significant digits and
+/// always at least one digit after the decimal point, e.g. `100.0` or `1.0e+15`.
+pub(crate) fn format_real(real: f64) -> String {
+    printf::format_float(real, 'g', 15, true)
+}
+
+/// Reads the longest prefix of `text` that is an integer, ignoring leading whitespace.
+/// Values beyond the range of i64 saturate and text without digits is 0.
+pub(crate) fn parse_integer_prefix(text: &str) -> i64 {
+    let text = text.trim_start_matches(is_space);
+    let (negative, digits) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+
+    let mut value: i64 = 0;
+    for digit in digits.bytes().take_while(u8::is_ascii_digit) {
+        let digit = i64::from(digit - b'0');
+        value = match value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit))
+        {
+            Some(value) => value,
+            // This also catches i64::MIN, which has no positive counterpart.
+            None if negative => return i64::MIN,
+            None => return i64::MAX,
+        };
+    }
+    if negative {
+        -value
+    } else {
+        value
+    }
+}
+
+/// Reads the longest prefix of `text` that is a real number, ignoring leading whitespace.
+/// Text without digits is 0.0.
+pub(crate) fn parse_real_prefix(text: &str) -> f64 {
+    let text = text.trim_start_matches(is_space);
+    let length = scan_number(text).map_or(0, |scan| scan.length);
+    text[..length].parse::<f64>().unwrap_or(0.0)
+}
+
+/// Parses `text` as a number if all of it, apart from surrounding whitespace, is one.
+/// Integers that do not fit into an i64 become reals.
+pub(crate) fn parse_number(text: &str) -> Option<SqliteValue> {
+    let text = text.trim_matches(is_space);
+    let scan = scan_number(text)?;
+    if scan.length != text.len() {
+        return None;
+    }
+    if scan.is_integer {
+        if let Ok(integer) = text.parse::<i64>() {
+            return Some(SqliteValue::Integer(integer));
+        }
+    }
+    text.parse::<f64>().ok().map(SqliteValue::Real)
+}
+
+struct NumberScan {
+    length: usize,
+    is_integer: bool,
+}
+
+/// Finds the longest prefix of `text` of the form `[+-]digits[.digits][e[+-]digits]`, where
+/// either the digits before or after the decimal point may be missing but not both.
+fn scan_number(text: &str) -> Option<NumberScan> {
+    let bytes = text.as_bytes();
+    let count_digits = |from: usize| {
+        bytes[from.min(bytes.len())..]
+            .iter()
+            .take_while(|byte| byte.is_ascii_digit())
+            .count()
+    };
+
+    let mut length = usize::from(matches!(bytes.first(), Some(b'+' | b'-')));
+    let integer_digits = count_digits(length);
+    length += integer_digits;
+
+    let mut is_integer = true;
+    let mut fraction_digits = 0;
+    if bytes.get(length) == Some(&b'.') {
+        fraction_digits = count_digits(length + 1);
+        if integer_digits + fraction_digits > 0 {
+            length += 1 + fraction_digits;
+            is_integer = false;
+        }
+    }
+    if integer_digits + fraction_digits == 0 {
+        return None;
+    }
+
+    if matches!(bytes.get(length), Some(b'e' | b'E')) {
+        let sign = usize::from(matches!(bytes.get(length + 1), Some(b'+' | b'-')));
+        let exponent_digits = count_digits(length + 1 + sign);
+        if exponent_digits > 0 {
+            length += 1 + sign + exponent_digits;
+            is_integer = false;
+        }
+    }
+    Some(NumberScan { length, is_integer })
+}
+
+/// SQLite only treats ASCII whitespace as space around numbers.
+pub(crate) fn is_space(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0b' | '\x0c' | '\r')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_integer_prefix() {
+        assert_eq!(parse_integer_prefix("42"), 42);
+        assert_eq!(parse_integer_prefix("  -17abc"), -17);
+        assert_eq!(parse_integer_prefix("+8"), 8);
+        assert_eq!(parse_integer_prefix("3.9"), 3);
+        assert_eq!(parse_integer_prefix("1e3"), 1);
+        assert_eq!(parse_integer_prefix("abc"), 0);
+        assert_eq!(parse_integer_prefix(""), 0);
+        assert_eq!(parse_integer_prefix("99999999999999999999"), i64::MAX);
+        assert_eq!(parse_integer_prefix("-9223372036854775808"), i64::MIN);
+        assert_eq!(parse_integer_prefix("-99999999999999999999"), i64::MIN);
+    }
+
+    #[test]
+    fn test_parse_real_prefix() {
+        assert_eq!(parse_real_prefix("3.5abc"), 3.5);
+        assert_eq!(parse_real_prefix(" -1e3x"), -1000.0);
+        assert_eq!(parse_real_prefix(".5"), 0.5);
+        assert_eq!(parse_real_prefix("5."), 5.0);
+        assert_eq!(parse_real_prefix("1e"), 1.0);
+        assert_eq!(parse_real_prefix("."), 0.0);
+        assert_eq!(parse_real_prefix("x1"), 0.0);
+    }
+
+    #[test]
+    fn test_to_numeric() {
+        let text = |text: &str| SqliteValue::Text(text.to_string());
+        assert_eq!(text(" 12 ").to_numeric(), Some(SqliteValue::Integer(12)));
+        assert_eq!(text("-1.5").to_numeric(), Some(SqliteValue::Real(-1.5)));
+        assert_eq!(text("1e3").to_numeric(), Some(SqliteValue::Real(1000.0)));
+        assert_eq!(
+            text("99999999999999999999").to_numeric(),
+            Some(SqliteValue::Real(1e20))
+        );
+        assert_eq!(text("12abc").to_numeric(), None);
+        assert_eq!(text("0x10").to_numeric(), None);
+        assert_eq!(text("").to_numeric(), None);
+        assert_eq!(SqliteValue::Null.to_numeric(), None);
+    }
+
+    #[test]
+    fn test_to_text() {
+        assert_eq!(SqliteValue::Real(100.0).to_text().unwrap(), "100.0");
+        assert_eq!(SqliteValue::Real(0.1).to_text().unwrap(), "0.1");
+        assert_eq!(SqliteValue::Real(0.1 + 0.2).to_text().unwrap(), "0.3");
+        assert_eq!(SqliteValue::Real(1e15).to_text().unwrap(), "1.0e+15");
+        assert_eq!(
+            SqliteValue::Real(123456789012345.0).to_text().unwrap(),
+            "123456789012345.0"
+        );
+        assert_eq!(SqliteValue::Real(-0.00001).to_text().unwrap(), "-1.0e-05");
+        assert_eq!(SqliteValue::Real(f64::INFINITY).to_text().unwrap(), "Inf");
+        assert_eq!(SqliteValue::Blob(b"abc".to_vec()).to_text().unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_is_true() {
+        assert!(SqliteValue::Integer(2).is_true());
+        assert!(!SqliteValue::Integer(0).is_true());
+        assert!(SqliteValue::Text("0.5x".to_string()).is_true());
+        assert!(!SqliteValue::Text("abc".to_string()).is_true());
+        assert!(!SqliteValue::Null.is_true());
+    }
+}
@@ -0,0 +1,39 @@
+--
+-- INET
+--
+CREATE TABLE INET_TBL (c cidr, i inet);
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1', '192.168.1.226/24');
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1.0/26', '192.168.1.226');
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1', '192.168.1.0/24');
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1', '192.168.1.0/25');
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1', '192.168.1.255/24');
+INSERT INTO INET_TBL (c, i) VALUES ('192.168.1', '192.168.1.255/25');
+INSERT INTO INET_TBL (c, i) VALUES ('10', '10.1.2.3/8');
+INSERT INTO INET_TBL (c, i) VALUES ('10.0.0.0', '10.1.2.3/8');
+INSERT INTO INET_TBL (c, i) VALUES ('10.1.2.3', '10.1.2.3/32');
+INSERT INTO INET_TBL (c, i) VALUES ('10.1.2', '10.1.2.3/24');
+INSERT INTO INET_TBL (c, i) VALUES ('10.1', '10.1.2.3/16');
+INSERT INTO INET_TBL (c, i) VALUES ('10', '10.1.2.3/8');
+INSERT INTO INET_TBL (c, i) VALUES ('10', '11.1.2.3/8');
+INSERT INTO INET_TBL (c, i) VALUES ('10', '9.1.2.3/8');
+INSERT INTO INET_TBL (c, i) VALUES ('10:23::f1', '10:23::f1/64');
+INSERT INTO INET_TBL (c, i) VALUES ('10:23::8000/113', '10:23::ffff');
+INSERT INTO INET_TBL (c, i) VALUES ('::ffff:1.2.3.4', '::4.3.2.1/24');
+
+SELECT i AS inet, host(i), family(i) FROM INET_TBL;
+SELECT c AS cidr, broadcast(c),
+  i AS inet, broadcast(i) FROM INET_TBL;
+SELECT c AS cidr, network(c) AS "network(cidr)",
+  i AS inet, network(i) AS "network(inet)" FROM INET_TBL;
+SELECT c AS cidr, masklen(c) AS "masklen(cidr)",
+  i AS inet, masklen(i) AS "masklen(inet)" FROM INET_TBL;
+SELECT i AS inet, netmask(i), hostmask(i) FROM INET_TBL;
+SELECT c AS cidr, netmask(c), hostmask(c) FROM INET_TBL;
+SELECT i, set_masklen(i, 24) FROM INET_TBL;
+SELECT c, set_masklen(c, 16) FROM INET_TBL WHERE family(c) = 4;
+SELECT set_masklen(i, 33) FROM INET_TBL;
+SELECT c, i, inet_same_family(c, i) FROM INET_TBL;
+SELECT inet_merge(c, i) FROM INET_TBL;
+SELECT inet_merge(c, i) FROM INET_TBL WHERE inet_same_family(c, i);
+SELECT inet_merge('10.0.0.1/8'::inet, '192.168.0.1/24'::inet);
+SELECT host(NULL::inet), family(NULL::inet), masklen(NULL::inet), broadcast(NULL::inet);
@@ -0,0 +1,30 @@
+--
+-- NUMERIC
+--
+CREATE TABLE ceil_floor_round (a numeric);
+INSERT INTO ceil_floor_round VALUES ('-5.5');
+INSERT INTO ceil_floor_round VALUES ('-5.499999');
+INSERT INTO ceil_floor_round VALUES ('9.5');
+INSERT INTO ceil_floor_round VALUES ('9.4999999');
+INSERT INTO ceil_floor_round VALUES ('0.0');
+INSERT INTO ceil_floor_round VALUES ('0.0000001');
+INSERT INTO ceil_floor_round VALUES ('-0.000001');
+
+SELECT a, ceiling(a) FROM ceil_floor_round;
+SELECT x, y, div(x, y), mod(x, y)
+FROM (VALUES (9, 4), (-9, 4), (9, -4), (-9, -4), (0, 5), (7, 7), (12, 5)) AS t(x, y);
+SELECT div(9.5, 2.5), div(-9.5, 2.5);
+SELECT div(12, 0);
+SELECT mod(12, 0);
+SELECT x, sign(x) FROM (VALUES (-8.4), (-1), (0), (0.5), (42)) AS t(x);
+SELECT min_scale(numeric 'NaN') is NULL;
+SELECT min_scale(0);
+SELECT min_scale(0.00);
+SELECT min_scale(1.0);
+SELECT min_scale(1.1);
+SELECT min_scale(1.12);
+SELECT min_scale(1.123);
+SELECT min_scale(1.1234);
+SELECT min_scale(1.12345);
+SELECT min_scale(1.1000);
+SELECT min_scale(1e100);
@@ -0,0 +1,51 @@
+//! Runs the sqllogictest files of tests/postgres against `register_postgres_udfs`, see
+//! tests/postgres/README.md.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use datafusion::prelude::SessionContext;
+use datafusion_sqllogictest::DataFusion;
+use df_extras::postgres::register_postgres_udfs;
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/postgres");
+    let mut files = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "slt"))
+        .collect::<Vec<_>>();
+    files.sort();
+
+    let mut failures = 0;
+    for file in files {
+        match run(&file).await {
+            Ok(()) => println!("{}: ok", file.display()),
+            Err(error) => {
+                failures += 1;
+                println!("{}: FAILED\n{error}", file.display());
+            }
+        }
+    }
+
+    if failures == 0 {
+        ExitCode::SUCCESS
+    } else {
+        println!("{failures} files differ from Postgres");
+        ExitCode::FAILURE
+    }
+}
+
+async fn run(file: &PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+    // Each file gets its own session, so the tables of one file are not seen by another.
+    let ctx = SessionContext::new();
+    register_postgres_udfs(&ctx)?;
+
+    let relative_path = PathBuf::from(file.file_name().unwrap());
+    let mut runner = sqllogictest::Runner::new(|| async {
+        Ok(DataFusion::new(ctx.clone(), relative_path.clone()))
+    });
+    runner.run_file_async(file).await?;
+    Ok(())
+}
@@ -0,0 +1,683 @@
+//! Runs every function registered by `register_sqlite_udfs` in DataFusion and in SQLite itself,
+//! bundled through rusqlite, and checks that both give the same result for the same call.
+//!
+//! Values are compared the way SQLite shows them: reals are rendered with SQLite's own
+//! real-to-text conversion and blobs as hex, so an integer column in DataFusion matches an
+//! integer in SQLite whatever its Arrow width. A call that fails in one engine must fail in
+//! the other.
+//!
+//! The bundled SQLite is built with SQLITE_ENABLE_MATH_FUNCTIONS, see `.cargo/config.toml`.
+#![cfg(feature = "sqlite")]
+
+use std::collections::HashSet;
+
+use datafusion::arrow::record_batch::RecordBatch;
+use datafusion::common::ScalarValue;
+use datafusion::prelude::SessionContext;
+use df_extras::catalog;
+use df_extras::registry::Dialect;
+use df_extras::sqlite::register_sqlite_udfs;
+use proptest::prelude::*;
+use proptest::test_runner::{Config, TestRunner};
+use rusqlite::types::Value;
+use rusqlite::Connection;
+use tokio::runtime::Runtime;
+
+/// Functions that are not part of SQLite, so there is nothing to compare them with.
+const EXTENSIONS: [&str; 7] = [
+    "json_get_int",
+    "json_get_float",
+    "json_get_bool",
+    "json_get_str",
+    "json_extract_as",
+    "json_to_struct",
+    "json_infer_schema",
+];
+
+const NUMBERS: [&str; 24] = [
+    "NULL",
+    "0",
+    "1",
+    "-1",
+    "2",
+    "3",
+    "7",
+    "-7",
+    "100",
+    "0.5",
+    "-0.5",
+    "2.5",
+    "-2.5",
+    "3.3",
+    "1e100",
+    "-1e-100",
+    "1e308",
+    "9223372036854775807",
+    "'16'",
+    "' 12 '",
+    "'1e3'",
+    "'12abc'",
+    "'abc'",
+    "X'3136'",
+];
+
+const TEXTS: [&str; 16] = [
+    "NULL",
+    "''",
+    "'abc'",
+    "'ABC'",
+    "'hello world'",
+    "'héllo'",
+    "'a%c'",
+    "'a_c'",
+    "'a*c'",
+    "'[ab]c'",
+    "'it''s'",
+    "12345",
+    "1.5",
+    "-3",
+    "X'616263'",
+    "X''",
+];
+
+const JSON_TEXTS: [&str; 31] = [
+    "NULL",
+    "''",
+    "'null'",
+    "'true'",
+    "'false'",
+    "'0'",
+    "'-12'",
+    "'1.5e3'",
+    "'\"text\"'",
+    "'[]'",
+    "'{}'",
+    "'[1,2,3,4]'",
+    "'{\"a\":[1,2.5,\"x\",null,true],\"b\":{\"c\":false}}'",
+    "' { \"a\" : [ 1 , 2 ] } '",
+    "'[[1,[2,[3]]],{\"a\":{\"b\":{}}}]'",
+    "'{\"a\":1,\"a\":2}'",
+    "'{\"a\":[1],\"b\":2,\"a\":{\"c\":3}}'",
+    "'\"\\u00e9\\n\"'",
+    "'{a: 1}'",
+    "'[1,2,]'",
+    "'{\"a\": 0x1F, b: .5, c: +1, d: ''x''}'",
+    "'/* comment */ [1] // more'",
+    "'[Infinity, -Infinity, NaN]'",
+    "'[1,2,x]'",
+    "'{\"a\":}'",
+    "'[1'",
+    "'{\"a\" 1}'",
+    "'01'",
+    "'1.'",
+    "123",
+    "X'0102'",
+];
+
+const JSON_PATHS: [&str; 16] = [
+    "'$'",
+    "'$.a'",
+    "'$.a[0]'",
+    "'$.a[1]'",
+    "'$.a[4]'",
+    "'$.a[9]'",
+    "'$.a[#-1]'",
+    "'$[0]'",
+    "'$[3]'",
+    "'$[#-1]'",
+    "'$.b.c'",
+    "'$.\"a\"'",
+    "'$.missing'",
+    "'a'",
+    "'$.'",
+    "NULL",
+];
+
+const TIME_VALUES: [&str; 18] = [
+    "NULL",
+    "'2024-03-15'",
+    "'2024-03-15 13:45:10'",
+    "'2024-03-15T13:45:10.123'",
+    "'2024-03-15 13:45'",
+    "'2024-03-15 13:45:10+02:00'",
+    "'2024-03-15Z'",
+    "'13:45:10'",
+    "'2024-02-30'",
+    "'0000-01-01'",
+    "'9999-12-31 23:59:59.999'",
+    "'invalid'",
+    "2460384.5",
+    "1700000000",
+    "'1700000000'",
+    "0",
+    "-1.5",
+    "X'00'",
+];
+
+const MODIFIERS: [&str; 18] = [
+    "'+1 day'",
+    "'-3 months'",
+    "'+1.5 hours'",
+    "'-90 seconds'",
+    "'+2 years'",
+    "'+0001-02-03 04:05:06'",
+    "'start of month'",
+    "'start of year'",
+    "'start of day'",
+    "'weekday 0'",
+    "'weekday 3'",
+    "'unixepoch'",
+    "'auto'",
+    "'julianday'",
+    "'subsec'",
+    "'ceiling'",
+    "'floor'",
+    "'invalid'",
+];
+
+/// Evaluates the same SQL expression in both engines.
+struct Harness {
+    sqlite: Connection,
+    ctx: SessionContext,
+    runtime: Runtime,
+}
+
+/// The result of one call: the value as SQLite shows it, NULL, or an error.
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Value(String),
+    Null,
+    Error,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let ctx = SessionContext::new();
+        register_sqlite_udfs(&ctx).unwrap();
+        Self {
+            sqlite: Connection::open_in_memory().unwrap(),
+            ctx,
+            runtime: tokio::runtime::Builder::new_current_thread()
+                .build()
+                .unwrap(),
+        }
+    }
+
+    /// Returns a description of the difference if the engines disagree on `expr`.
+    fn check(&self, expr: &str) -> Option<String> {
+        let expected = self.eval_sqlite(expr);
+        let actual = self.eval_datafusion(expr);
+        (expected != actual)
+            .then(|| format!("{expr}: SQLite gives {expected:?}, df_extras gives {actual:?}"))
+    }
+
+    fn eval_sqlite(&self, expr: &str) -> Outcome {
+        self.sqlite
+            .query_row(&format!("select {expr}"), [], |row| {
+                Ok(self.render(row.get(0)?))
+            })
+            .unwrap_or(Outcome::Error)
+    }
+
+    fn eval_datafusion(&self, expr: &str) -> Outcome {
+        let batches = self.runtime.block_on(async {
+            self.ctx
+                .sql(&format!("select {expr} as result"))
+                .await?
+                .collect()
+                .await
+        });
+        match batches {
+            Ok(batches) => match first_value(&batches) {
+                Some(value) => self.render(value),
+                None => Outcome::Error,
+            },
+            Err(_) => Outcome::Error,
+        }
+    }
+
+    fn render(&self, value: Value) -> Outcome {
+        match value {
+            Value::Null => Outcome::Null,
+            Value::Integer(integer) => Outcome::Value(integer.to_string()),
+            Value::Real(_) => Outcome::Value(
+                self.sqlite
+                    .query_row("select CAST(?1 AS TEXT)", [value], |row| row.get(0))
+                    .unwrap(),
+            ),
+            Value::Text(text) => Outcome::Value(text),
+            Value::Blob(blob) => Outcome::Value(format!(
+                "X'{}'",
+                blob.iter()
+                    .map(|byte| format!("{byte:02X}"))
+                    .collect::<String>()
+            )),
+        }
+    }
+}
+
+/// Returns the value of the only row, read as the SQLite value it stands for.
+fn first_value(batches: &[RecordBatch]) -> Option<Value> {
+    let batch = batches.iter().find(|batch| batch.num_rows() > 0)?;
+    let scalar = ScalarValue::try_from_array(batch.column(0), 0).ok()?;
+    if scalar.is_null() {
+        return Some(Value::Null);
+    }
+    Some(match scalar {
+        ScalarValue::Boolean(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::Int8(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::Int16(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::Int32(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::Int64(Some(value)) => Value::Integer(value),
+        ScalarValue::UInt8(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::UInt16(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::UInt32(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::UInt64(Some(value)) => Value::Integer(value as i64),
+        ScalarValue::Float32(Some(value)) => Value::Real(value as f64),
+        ScalarValue::Float64(Some(value)) => Value::Real(value),
+        ScalarValue::Utf8(Some(value)) | ScalarValue::LargeUtf8(Some(value)) => Value::Text(value),
+        ScalarValue::Binary(Some(value)) | ScalarValue::LargeBinary(Some(value)) => {
+            Value::Blob(value)
+        }
+        other => Value::Text(other.to_string()),
+    })
+}
+
+fn call(function: &str, args: &[&str]) -> String {
+    format!("{function}({})", args.join(", "))
+}
+
+fn unary(function: &str, values: &[&str]) -> Vec<String> {
+    values
+        .iter()
+        .map(|value| call(function, &[*value]))
+        .collect()
+}
+
+fn binary(function: &str, left: &[&str], right: &[&str]) -> Vec<String> {
+    left.iter()
+        .flat_map(|x| right.iter().map(move |y| call(function, &[*x, *y])))
+        .collect()
+}
+
+/// Returns the calls made for each function, keyed by the name they are made with.
+fn corpus() -> Vec<(&'static str, Vec<String>)> {
+    let all_values = [&NUMBERS[..], &TEXTS[..]].concat();
+    let date_functions = ["date", "time", "datetime", "julianday", "unixepoch"];
+    let mut corpus = vec![];
+
+    // Built-in scalar functions.
+    corpus.push(("instr", binary("instr", &TEXTS, &TEXTS)));
+    corpus.push(("hex", unary("hex", &all_values)));
+    let hex_texts = [
+        "'616263'", "'61 62'", "'61-62'", "'6g'", "'ABCDEF'", "'a'", "''", "NULL", "12", "X'01'",
+    ];
+    corpus.push(("unhex", {
+        let mut calls = unary("unhex", &hex_texts);
+        calls.extend(binary("unhex", &hex_texts, &["' '", "'-'", "''", "NULL"]));
+        calls
+    }));
+    corpus.push(("quote", unary("quote", &all_values)));
+    corpus.push((
+        "zeroblob",
+        unary(
+            "zeroblob",
+            &["NULL", "0", "1", "3", "-1", "2.7", "'2'", "'x'"],
+        )
+        .into_iter()
+        .map(|call| format!("hex({call})"))
+        .collect(),
+    ));
+    let formats = [
+        "'%d'",
+        "'%5d|'",
+        "'%-5d|'",
+        "'%05d'",
+        "'%+d'",
+        "'% d'",
+        "'%,d'",
+        "'%i'",
+        "'%u'",
+        "'%x'",
+        "'%X'",
+        "'%#x'",
+        "'%o'",
+        "'%c'",
+        "'%s'",
+        "'%10.3s|'",
+        "'%-8s|'",
+        "'%f'",
+        "'%.2f'",
+        "'%10.1f|'",
+        "'%e'",
+        "'%.3E'",
+        "'%g'",
+        "'%#g'",
+        "'%!.15g'",
+        "'%q'",
+        "'%Q'",
+        "'%w'",
+        "'%r'",
+        "'%%'",
+        "'%.0f'",
+        "'%z'",
+        "'%'",
+        "'%5'",
+        "NULL",
+        "'plain'",
+    ];
+    let printf_args = [
+        "NULL", "0", "-7", "42", "255", "1234567", "2.5", "-0.125", "1e20", "3.3", "'abc'",
+        "'it''s'", "'12'", "''", "X'41'",
+    ];
+    corpus.push(("printf", binary("printf", &formats, &printf_args)));
+    corpus.push((
+        "format",
+        binary("format", &formats, &["42", "2.5", "'abc'"]),
+    ));
+    let patterns = [
+        "'a*c'", "'a?c'", "'[ab]c'", "'[^a]bc'", "'a%'", "'%c'", "'a_c'", "'A%'", "'abc'", "''",
+        "'*'", "NULL", "123",
+    ];
+    corpus.push(("glob", binary("glob", &patterns, &TEXTS)));
+    corpus.push(("like", {
+        let mut calls = binary("like", &patterns, &TEXTS);
+        for pattern in ["'a\\%'", "'a\\_c'"] {
+            for text in ["'a%'", "'ab'", "'a_c'"] {
+                calls.push(call("like", &[pattern, text, "'\\'"]));
+            }
+        }
+        calls.push(call("like", &["'a'", "'a'", "'xy'"]));
+        calls
+    }));
+    corpus.push(("likely", unary("likely", &all_values)));
+    corpus.push(("unlikely", unary("unlikely", &all_values)));
+    corpus.push((
+        "likelihood",
+        binary(
+            "likelihood",
+            &["1", "'a'", "NULL", "2.5"],
+            &["0.5", "0.0", "1.0", "1", "2.0", "-0.5"],
+        ),
+    ));
+    corpus.push(("typeof", unary("typeof", &all_values)));
+    corpus.push(("unicode", unary("unicode", &TEXTS)));
+    corpus.push((
+        "char",
+        [
+            "char()",
+            "char(72, 105)",
+            "char(233, 0x1F600)",
+            "char(-1)",
+            "char(NULL, 65)",
+            "char('66', 67.9)",
+            "char(1114112)",
+        ]
+        .map(String::from)
+        .to_vec(),
+    ));
+    corpus.push((
+        "soundex",
+        unary(
+            "soundex",
+            &[
+                "'Robert'",
+                "'Rupert'",
+                "'Tymczak'",
+                "'Pfister'",
+                "'123'",
+                "''",
+                "NULL",
+                "'é'",
+            ],
+        ),
+    ));
+    let pairs = ["NULL", "1", "'a'", "2.5"];
+    corpus.push(("ifnull", binary("ifnull", &pairs, &pairs)));
+    corpus.push((
+        "iif",
+        ["0", "1", "NULL", "'1'", "'abc'", "0.5", "2", "1 > 2"]
+            .iter()
+            .map(|condition| call("iif", &[*condition, "'yes'", "'no'"]))
+            .collect(),
+    ));
+    corpus.push(("sign", unary("sign", &NUMBERS)));
+    for function in ["changes", "total_changes", "last_insert_rowid"] {
+        corpus.push((function, vec![call(function, &[])]));
+    }
+
+    // JSON functions.
+    corpus.push(("json", unary("json", &JSON_TEXTS)));
+    corpus.push(("jsonb", unary("jsonb", &JSON_TEXTS)));
+    corpus.push((
+        "json_parse",
+        JSON_TEXTS
+            .iter()
+            .map(|json| format!("json(json_parse({json}))"))
+            .collect(),
+    ));
+    corpus.push(("json_valid", {
+        let mut calls = unary("json_valid", &JSON_TEXTS);
+        let flags = (0..=16).map(|flag| flag.to_string()).collect::<Vec<_>>();
+        let flags = flags.iter().map(String::as_str).collect::<Vec<_>>();
+        calls.extend(binary("json_valid", &JSON_TEXTS, &flags));
+        calls.extend(binary(
+            "json_valid",
+            &["jsonb('[1]')", "X'0102'"],
+            &["4", "8"],
+        ));
+        calls
+    }));
+    corpus.push((
+        "json_error_position",
+        unary("json_error_position", &JSON_TEXTS),
+    ));
+    corpus.push(("json_type", {
+        let mut calls = unary("json_type", &JSON_TEXTS);
+        calls.extend(binary("json_type", &JSON_TEXTS, &JSON_PATHS));
+        calls
+    }));
+    corpus.push(("json_array_length", {
+        let mut calls = unary("json_array_length", &JSON_TEXTS);
+        calls.extend(binary("json_array_length", &JSON_TEXTS, &JSON_PATHS));
+        calls
+    }));
+
+    // Date and time functions.
+    for function in date_functions {
+        let mut calls = unary(function, &TIME_VALUES);
+        calls.extend(binary(function, &TIME_VALUES, &MODIFIERS));
+        calls.extend(binary(
+            function,
+            &["1700000000.5", "'1700000000'"],
+            &MODIFIERS,
+        ));
+        corpus.push((function, calls));
+    }
+    let strftime_formats = [
+        "'%Y-%m-%d %H:%M:%S'",
+        "'%d %f %H %j %J %m %M %s %S %w %W %Y %%'",
+        "'%e %F %I %k %l %p %P %R %T %u %G %g %V %U'",
+        "'%'",
+        "'%Q'",
+        "''",
+        "NULL",
+    ];
+    corpus.push((
+        "strftime",
+        binary("strftime", &strftime_formats, &TIME_VALUES),
+    ));
+    corpus.push((
+        "timediff",
+        binary("timediff", &TIME_VALUES[..12], &TIME_VALUES[..12]),
+    ));
+
+    // Math functions.
+    for function in [
+        "acos", "acosh", "asin", "asinh", "atan", "atanh", "ceil", "ceiling", "cos", "cosh",
+        "degrees", "exp", "floor", "ln", "log10", "log2", "radians", "sin", "sinh", "sqrt", "tan",
+        "tanh", "trunc", "log",
+    ] {
+        corpus.push((function, unary(function, &NUMBERS)));
+    }
+    for function in ["atan2", "mod", "pow", "power", "log"] {
+        let numbers = &NUMBERS[..14];
+        corpus.push((function, binary(function, numbers, numbers)));
+    }
+    corpus.push(("pi", vec!["pi()".to_string()]));
+
+    corpus
+}
+
+fn assert_no_differences(differences: Vec<String>) {
+    assert!(
+        differences.is_empty(),
+        "{} calls differ from SQLite:\n{}",
+        differences.len(),
+        differences.join("\n")
+    );
+}
+
+#[test]
+fn test_sqlite_version() {
+    let harness = Harness::new();
+    assert_eq!(
+        harness.eval_sqlite("sqrt(16) || ' ' || sqlite_version()"),
+        Outcome::Value("4.0 3.45.0".to_string())
+    );
+}
+
+#[test]
+fn test_every_function_is_covered() {
+    let covered = corpus()
+        .into_iter()
+        .map(|(function, _)| function)
+        .collect::<HashSet<_>>();
+
+    let missing = catalog()
+        .into_iter()
+        .filter(|function| function.dialect == Dialect::Sqlite)
+        .flat_map(|function| std::iter::once(function.name).chain(function.aliases))
+        .filter(|name| !covered.contains(name.as_str()) && !EXTENSIONS.contains(&name.as_str()))
+        .collect::<Vec<_>>();
+    assert!(missing.is_empty(), "no calls are made for {missing:?}");
+}
+
+#[test]
+fn test_corpus() {
+    let harness = Harness::new();
+    assert_no_differences(
+        corpus()
+            .iter()
+            .flat_map(|(_, calls)| calls)
+            .filter_map(|expr| harness.check(expr))
+            .collect(),
+    );
+}
+
+/// A JSON document of up to three levels, with keys that the generated paths can select and
+/// that an object may repeat. It is written twice: minified, and spread over several lines.
+fn json_document() -> impl Strategy<Value = (String, String)> {
+    let leaf = prop_oneof![
+        Just(serde_json::Value::Null),
+        any::<bool>().prop_map(serde_json::Value::from),
+        any::<i32>().prop_map(serde_json::Value::from),
+        (-100_000i32..100_000).prop_map(|cents| serde_json::Value::from(cents as f64 / 100.0)),
+        "[a-z é\"\\\\]{0,6}".prop_map(serde_json::Value::from),
+    ]
+    .prop_map(|value| (value.to_string(), value.to_string()));
+    leaf.prop_recursive(3, 24, 4, |inner| {
+        prop_oneof![
+            prop::collection::vec(inner.clone(), 0..4).prop_map(|elements| {
+                let (minified, spread): (Vec<_>, Vec<_>) = elements.into_iter().unzip();
+                (
+                    format!("[{}]", minified.join(",")),
+                    format!("[\n  {}\n]", spread.join(",\n  ")),
+                )
+            }),
+            prop::collection::vec(("[a-c]", inner), 0..4).prop_map(|members| {
+                let (minified, spread): (Vec<_>, Vec<_>) = members
+                    .into_iter()
+                    .map(|(key, (minified, spread))| {
+                        (
+                            format!("\"{key}\":{minified}"),
+                            format!("\"{key}\": {spread}"),
+                        )
+                    })
+                    .unzip();
+                (
+                    format!("{{{}}}", minified.join(",")),
+                    format!("{{\n  {}\n}}", spread.join(",\n  ")),
+                )
+            }),
+        ]
+    })
+}
+
+/// A path of up to three steps over the keys and indexes of [`json_document`].
+fn json_path() -> impl Strategy<Value = String> {
+    prop::collection::vec(
+        prop_oneof![
+            "[a-c]".prop_map(|key| format!(".{key}")),
+            (0..4usize).prop_map(|index| format!("[{index}]")),
+            Just("[#-1]".to_string()),
+        ],
+        0..4,
+    )
+    .prop_map(|steps| format!("${}", steps.concat()))
+}
+
+fn sql_text(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+#[test]
+fn test_generated_json() {
+    let harness = Harness::new();
+    let mut runner = TestRunner::new(Config::with_cases(256));
+    runner
+        .run(&(json_document(), json_path()), |(document, path)| {
+            let (minified, spread) = document;
+            let json = sql_text(&minified);
+            let pretty = sql_text(&spread);
+            let path = sql_text(&path);
+            for expr in [
+                format!("json({json})"),
+                format!("json({pretty})"),
+                format!("jsonb({json})"),
+                format!("json_valid({json})"),
+                format!("json_type({json}, {path})"),
+                format!("json_type(jsonb({json}), {path})"),
+                format!("json_array_length({json}, {path})"),
+                format!("json_array_length(jsonb({json}), {path})"),
+            ] {
+                if let Some(difference) = harness.check(&expr) {
+                    return Err(TestCaseError::fail(difference));
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn test_generated_malformed_json() {
+    let harness = Harness::new();
+    let mut runner = TestRunner::new(Config::with_cases(512));
+    runner
+        .run(&"[\\[\\]{}:,\"a-c0-9 .eE+-]{0,12}", |text| {
+            let json = sql_text(&text);
+            for expr in [
+                format!("json_valid({json})"),
+                format!("json_valid({json}, 2)"),
+                format!("json_error_position({json})"),
+                format!("json({json})"),
+            ] {
+                if let Some(difference) = harness.check(&expr) {
+                    return Err(TestCaseError::fail(difference));
+                }
+            }
+            Ok(())
+        })
+        .unwrap();
+}